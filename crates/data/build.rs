@@ -0,0 +1,28 @@
+//! Copies the offline demo spec into `OUT_DIR` for the `demo` feature, from
+//! the file `PORO_DEMO_SPEC` points at or else `assets/demo.json`.
+
+use std::{env, fs, path::PathBuf};
+
+/// Larger specs have to be trimmed first, so demo builds stay small.
+const DEMO_MAX_BYTES: u64 = 1024 * 1024;
+
+fn main() {
+    println!("cargo:rerun-if-env-changed=PORO_DEMO_SPEC");
+    if env::var_os("CARGO_FEATURE_DEMO").is_none() {
+        return;
+    }
+    let source = env::var_os("PORO_DEMO_SPEC")
+        .map_or_else(|| PathBuf::from("assets/demo.json"), PathBuf::from);
+    println!("cargo:rerun-if-changed={}", source.display());
+
+    let size = fs::metadata(&source)
+        .unwrap_or_else(|err| panic!("can't read demo spec {}: {err}", source.display()))
+        .len();
+    assert!(
+        size <= DEMO_MAX_BYTES,
+        "demo spec {} is {size} bytes, over the {DEMO_MAX_BYTES} byte cap; trim it first",
+        source.display()
+    );
+    let out = PathBuf::from(env::var_os("OUT_DIR").expect("OUT_DIR is set by cargo"));
+    fs::copy(&source, out.join("demo.json")).expect("copy demo spec");
+}
@@ -0,0 +1,207 @@
+//! Copying text out of the app, which may run where there's no system
+//! clipboard, like over SSH.
+//!
+//! A [`Clipboard`] tries the system clipboard, then an OSC 52 escape sequence
+//! that asks the terminal to set its clipboard, then a file in the temp
+//! directory.
+
+use std::{
+    fmt,
+    io::{self, Write},
+    path::PathBuf,
+};
+
+use base64::{Engine, engine::general_purpose::STANDARD};
+
+/// Name of the file text is written to when nothing else works.
+pub const CLIPBOARD_FILE: &str = "poroshell-clipboard.txt";
+
+/// Longest OSC 52 sequence sent. Many terminals drop longer ones silently,
+/// so longer text goes to the file instead.
+pub const OSC52_LIMIT: usize = 100_000;
+
+/// The OS clipboard, implemented by the app for whatever clipboard library it
+/// was built with.
+pub trait SystemClipboard {
+    fn set_text(&mut self, text: &str) -> Result<(), String>;
+}
+
+/// The OSC 52 sequence that sets the terminal's clipboard to `text`.
+pub fn osc52(text: &str) -> String {
+    format!("\x1b]52;c;{}\x07", STANDARD.encode(text))
+}
+
+/// How the text was copied, or why it wasn't.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CopyOutcome {
+    System,
+    Osc52,
+    /// Nothing else worked, so the text was written to this file.
+    File(PathBuf),
+    /// Not even the file could be written.
+    Failed(String),
+}
+
+impl fmt::Display for CopyOutcome {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::System => write!(f, "Copied to clipboard"),
+            Self::Osc52 => write!(f, "Copied via OSC52"),
+            Self::File(path) => write!(f, "No clipboard; saved to {}", path.display()),
+            Self::Failed(error) => write!(f, "Couldn't copy: {error}"),
+        }
+    }
+}
+
+pub struct Clipboard {
+    system: Option<Box<dyn SystemClipboard>>,
+    /// Where OSC 52 sequences are written, if the app runs in a terminal.
+    terminal: Option<Box<dyn Write>>,
+    file: PathBuf,
+}
+
+impl Clipboard {
+    pub fn new(system: Option<Box<dyn SystemClipboard>>, terminal: Option<Box<dyn Write>>) -> Self {
+        Self {
+            system,
+            terminal,
+            file: std::env::temp_dir().join(CLIPBOARD_FILE),
+        }
+    }
+
+    #[must_use]
+    pub fn with_file(mut self, file: PathBuf) -> Self {
+        self.file = file;
+        self
+    }
+
+    /// Copy `text` with the first method that works.
+    ///
+    /// A system clipboard that fails once is dropped, so later copies go
+    /// straight to the next method.
+    pub fn copy(&mut self, text: &str) -> CopyOutcome {
+        if let Some(system) = &mut self.system {
+            match system.set_text(text) {
+                Ok(()) => return CopyOutcome::System,
+                Err(_) => self.system = None,
+            }
+        }
+
+        let sequence = osc52(text);
+        if let Some(terminal) = &mut self.terminal
+            && sequence.len() <= OSC52_LIMIT
+            && write_sequence(terminal.as_mut(), &sequence).is_ok()
+        {
+            return CopyOutcome::Osc52;
+        }
+
+        match std::fs::write(&self.file, text) {
+            Ok(()) => CopyOutcome::File(self.file.clone()),
+            Err(err) => CopyOutcome::Failed(err.to_string()),
+        }
+    }
+}
+
+fn write_sequence(terminal: &mut dyn Write, sequence: &str) -> io::Result<()> {
+    terminal.write_all(sequence.as_bytes())?;
+    terminal.flush()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{cell::RefCell, fs, rc::Rc};
+
+    use super::*;
+
+    struct FakeSystem(Result<(), String>);
+
+    impl SystemClipboard for FakeSystem {
+        fn set_text(&mut self, _text: &str) -> Result<(), String> {
+            self.0.clone()
+        }
+    }
+
+    /// A terminal that records what was written, or fails every write.
+    #[derive(Clone, Default)]
+    struct FakeTerminal {
+        written: Rc<RefCell<Vec<u8>>>,
+        broken: bool,
+    }
+
+    impl Write for FakeTerminal {
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+            if self.broken {
+                return Err(io::Error::new(io::ErrorKind::BrokenPipe, "closed"));
+            }
+            self.written.borrow_mut().extend_from_slice(buf);
+            Ok(buf.len())
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    fn temp_file(name: &str) -> PathBuf {
+        std::env::temp_dir().join(format!("poroshell-{name}-{}.txt", std::process::id()))
+    }
+
+    #[test]
+    fn test_osc52() {
+        assert_eq!(osc52("hello"), "\x1b]52;c;aGVsbG8=\x07");
+        assert_eq!(
+            osc52("/lol-summoner/v1/summoners/{id}"),
+            "\x1b]52;c;L2xvbC1zdW1tb25lci92MS9zdW1tb25lcnMve2lkfQ==\x07"
+        );
+    }
+
+    #[test]
+    fn test_system_first() {
+        let terminal = FakeTerminal::default();
+        let mut clipboard = Clipboard::new(
+            Some(Box::new(FakeSystem(Ok(())))),
+            Some(Box::new(terminal.clone())),
+        );
+        assert_eq!(clipboard.copy("hello"), CopyOutcome::System);
+        assert!(terminal.written.borrow().is_empty());
+    }
+
+    #[test]
+    fn test_osc52_fallback() {
+        let terminal = FakeTerminal::default();
+        let mut clipboard = Clipboard::new(
+            Some(Box::new(FakeSystem(Err("no display".to_string())))),
+            Some(Box::new(terminal.clone())),
+        );
+        assert_eq!(clipboard.copy("hello"), CopyOutcome::Osc52);
+        assert_eq!(*terminal.written.borrow(), b"\x1b]52;c;aGVsbG8=\x07");
+        assert_eq!(clipboard.copy("hello").to_string(), "Copied via OSC52");
+
+        // Too long for terminals to accept.
+        let file = temp_file("clipboard-long");
+        let mut clipboard = clipboard.with_file(file.clone());
+        let long = "x".repeat(OSC52_LIMIT);
+        assert_eq!(clipboard.copy(&long), CopyOutcome::File(file.clone()));
+        fs::remove_file(&file).unwrap();
+    }
+
+    #[test]
+    fn test_file_fallback() {
+        let file = temp_file("clipboard");
+        let terminal = FakeTerminal {
+            broken: true,
+            ..Default::default()
+        };
+        let mut clipboard = Clipboard::new(
+            Some(Box::new(FakeSystem(Err("no display".to_string())))),
+            Some(Box::new(terminal)),
+        )
+        .with_file(file.clone());
+        assert_eq!(clipboard.copy("hello"), CopyOutcome::File(file.clone()));
+        assert_eq!(fs::read_to_string(&file).unwrap(), "hello");
+        fs::remove_file(&file).unwrap();
+
+        let mut clipboard = Clipboard::new(None, None).with_file(file.join("missing"));
+        assert!(matches!(clipboard.copy("hello"), CopyOutcome::Failed(_)));
+    }
+}
@@ -0,0 +1,156 @@
+use std::{
+    collections::HashMap,
+    hash::Hash,
+    time::{Duration, Instant},
+};
+
+/// What to do with a command that was just received.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Begin<R> {
+    /// Nothing is running for this key, so execute it.
+    Run,
+    /// An identical command is already running and will deliver the result.
+    Joined,
+    /// An identical command finished recently; reuse its result.
+    Cached(R),
+}
+
+/// Keeps identical commands from running more than once at a time, and answers
+/// repeats within `ttl` of a completion from the previous result.
+///
+/// Time is passed in rather than read, so callers decide what "now" means.
+#[derive(Debug, Clone)]
+pub struct Coalescer<K, R> {
+    ttl: Duration,
+    /// Waiters per running key, including the one executing it.
+    in_flight: HashMap<K, usize>,
+    completed: HashMap<K, (Instant, R)>,
+}
+
+impl<K, R> Coalescer<K, R>
+where
+    K: Eq + Hash + Clone,
+    R: Clone,
+{
+    pub fn new(ttl: Duration) -> Self {
+        Self {
+            ttl,
+            in_flight: HashMap::new(),
+            completed: HashMap::new(),
+        }
+    }
+
+    pub fn begin(&mut self, key: &K, now: Instant) -> Begin<R> {
+        if let Some(waiters) = self.in_flight.get_mut(key) {
+            *waiters += 1;
+            return Begin::Joined;
+        }
+        match self.completed.get(key) {
+            Some((at, result)) if now.duration_since(*at) < self.ttl => {
+                Begin::Cached(result.clone())
+            }
+            _ => {
+                self.completed.remove(key);
+                self.in_flight.insert(key.clone(), 1);
+                Begin::Run
+            }
+        }
+    }
+
+    /// Record that the command for `key` finished, returning how many waiters
+    /// asked for its result. Failures (`None`) aren't cached, so the next
+    /// request retries.
+    pub fn finish(&mut self, key: &K, result: Option<R>, now: Instant) -> usize {
+        if let Some(result) = result {
+            self.completed.insert(key.clone(), (now, result));
+        }
+        self.in_flight.remove(key).unwrap_or_default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TTL: Duration = Duration::from_secs(30);
+
+    /// Runs `key` through the coalescer the way the background loop does,
+    /// counting real executions.
+    fn execute(
+        coalescer: &mut Coalescer<&'static str, String>,
+        key: &'static str,
+        now: Instant,
+        executions: &mut usize,
+    ) -> Begin<String> {
+        let begin = coalescer.begin(&key, now);
+        if begin == Begin::Run {
+            *executions += 1;
+        }
+        begin
+    }
+
+    #[test]
+    fn test_duplicates_in_flight() {
+        let mut coalescer = Coalescer::new(TTL);
+        let mut executions = 0;
+        let now = Instant::now();
+
+        assert_eq!(
+            execute(&mut coalescer, "libraries", now, &mut executions),
+            Begin::Run
+        );
+        assert_eq!(
+            execute(&mut coalescer, "libraries", now, &mut executions),
+            Begin::Joined
+        );
+        assert_eq!(
+            execute(&mut coalescer, "libraries", now, &mut executions),
+            Begin::Joined
+        );
+        // A different key runs on its own.
+        assert_eq!(
+            execute(&mut coalescer, "schema", now, &mut executions),
+            Begin::Run
+        );
+
+        assert_eq!(
+            coalescer.finish(&"libraries", Some("done".to_string()), now),
+            3
+        );
+        assert_eq!(executions, 2);
+    }
+
+    #[test]
+    fn test_cached_until_ttl() {
+        let mut coalescer = Coalescer::new(TTL);
+        let mut executions = 0;
+        let start = Instant::now();
+
+        execute(&mut coalescer, "libraries", start, &mut executions);
+        coalescer.finish(&"libraries", Some("done".to_string()), start);
+
+        let soon = start + Duration::from_secs(5);
+        assert_eq!(
+            execute(&mut coalescer, "libraries", soon, &mut executions),
+            Begin::Cached("done".to_string())
+        );
+        assert_eq!(executions, 1);
+
+        let later = start + TTL;
+        assert_eq!(
+            execute(&mut coalescer, "libraries", later, &mut executions),
+            Begin::Run
+        );
+        assert_eq!(executions, 2);
+    }
+
+    #[test]
+    fn test_failure_not_cached() {
+        let mut coalescer = Coalescer::<&str, String>::new(TTL);
+        let now = Instant::now();
+
+        coalescer.begin(&"libraries", now);
+        assert_eq!(coalescer.finish(&"libraries", None, now), 1);
+        assert_eq!(coalescer.begin(&"libraries", now), Begin::Run);
+    }
+}
@@ -0,0 +1,424 @@
+use std::{
+    cmp::Reverse,
+    collections::{BTreeMap, BTreeSet},
+    fs,
+    path::{Path, PathBuf},
+};
+
+use serde::{Deserialize, de::DeserializeOwned};
+use serde_json::Value;
+
+use crate::i18n::keys;
+
+/// Written at the root of the artifact directory by every run.
+pub const MANIFEST_FILE: &str = "manifest.json";
+
+/// Run records kept across runs, at the root of the artifact directory.
+pub const HISTORY_FILE: &str = "history.json";
+
+/// Per-target files, relative to `<artifact dir>/<target>`.
+pub const SPEC_FILE: &str = "openapi.json";
+pub const REPORT_FILE: &str = "report.json";
+pub const CHANGELOG_FILE: &str = "changelog.md";
+
+/// Target the generator writes when none is given.
+pub const DEFAULT_TARGET: &str = "lcu";
+
+/// Entries shown under each report category.
+pub const TOP_ENTRIES: usize = 10;
+
+/// Report kinds that describe the run rather than problems with the spec.
+const INFORMATIONAL: [&str; 2] = ["timing", "size"];
+
+/// Contents of one dashboard panel, or why there are none.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Panel<T> {
+    Ready(T),
+    /// The artifact the panel is built from wasn't written.
+    Missing(PathBuf),
+    /// The artifact exists but couldn't be parsed.
+    Unreadable {
+        path: PathBuf,
+        error: String,
+    },
+}
+
+impl<T> Panel<T> {
+    pub fn ready(&self) -> Option<&T> {
+        match self {
+            Panel::Ready(value) => Some(value),
+            _ => None,
+        }
+    }
+
+    pub fn map<U>(self, f: impl FnOnce(T) -> U) -> Panel<U> {
+        match self {
+            Panel::Ready(value) => Panel::Ready(f(value)),
+            Panel::Missing(path) => Panel::Missing(path),
+            Panel::Unreadable { path, error } => Panel::Unreadable { path, error },
+        }
+    }
+}
+
+/// A headline number, with its change since the previous run when known.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Count {
+    /// Key of the translated label.
+    pub name: &'static str,
+    pub value: usize,
+    pub delta: Option<i64>,
+}
+
+/// One entry of a generation report.
+#[derive(Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct ReportLine {
+    pub kind: String,
+    pub target: String,
+    pub detail: String,
+}
+
+/// Report entries of one kind, the first [`TOP_ENTRIES`] of them kept.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Category {
+    pub kind: String,
+    pub count: usize,
+    pub entries: Vec<ReportLine>,
+}
+
+/// What the last generation run left in an artifact directory.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Dashboard {
+    pub dir: PathBuf,
+    /// `info.version` of the generated spec.
+    pub version: Option<String>,
+    pub headline: Panel<Vec<Count>>,
+    /// Report categories with the most entries first.
+    pub categories: Panel<Vec<Category>>,
+    pub skipped: Panel<Vec<ReportLine>>,
+    /// Targets that were renamed or merged into another.
+    pub collided: Panel<Vec<ReportLine>>,
+    /// Paths from the manifest, relative to [`Dashboard::dir`].
+    pub artifacts: Panel<Vec<PathBuf>>,
+    pub changelog: Option<PathBuf>,
+    pub spec: Option<PathBuf>,
+}
+
+#[derive(Deserialize)]
+struct ManifestFile {
+    artifacts: Vec<ManifestArtifact>,
+}
+
+#[derive(Deserialize)]
+struct ManifestArtifact {
+    path: PathBuf,
+}
+
+#[derive(Deserialize, Clone)]
+struct ReportFile {
+    entries: Vec<ReportLine>,
+}
+
+#[derive(Deserialize, Default)]
+struct HistoryFile {
+    records: Vec<HistoryRecord>,
+}
+
+#[derive(Deserialize)]
+struct HistoryRecord {
+    version: String,
+    #[serde(default)]
+    operations: BTreeSet<String>,
+    #[serde(default)]
+    components: BTreeSet<String>,
+}
+
+impl Dashboard {
+    /// Read the artifacts of `target` under `dir`. Anything missing or
+    /// unreadable only affects the panels built from it.
+    pub fn load(dir: impl AsRef<Path>, target: &str) -> Self {
+        let dir = dir.as_ref();
+        let target_dir = dir.join(target);
+
+        let spec = read_json::<Value>(&target_dir.join(SPEC_FILE));
+        let history = match read_json::<HistoryFile>(&dir.join(HISTORY_FILE)) {
+            Panel::Ready(history) => history,
+            _ => HistoryFile::default(),
+        };
+        let report = read_json::<ReportFile>(&target_dir.join(REPORT_FILE));
+
+        let version = spec
+            .ready()
+            .and_then(|spec| spec.pointer("/info/version"))
+            .and_then(Value::as_str)
+            .map(str::to_string);
+        let previous = history
+            .records
+            .iter()
+            .rev()
+            .find(|record| Some(&record.version) != version.as_ref());
+
+        Self {
+            dir: dir.to_path_buf(),
+            version,
+            headline: spec.map(|spec| headline(&spec, previous)),
+            categories: report.clone().map(|report| categories(&report.entries)),
+            skipped: report
+                .clone()
+                .map(|report| of_kinds(report.entries, &["skipped"])),
+            collided: report.map(|report| of_kinds(report.entries, &["renamed", "merged"])),
+            artifacts: read_json::<ManifestFile>(&dir.join(MANIFEST_FILE)).map(|manifest| {
+                manifest
+                    .artifacts
+                    .into_iter()
+                    .map(|artifact| artifact.path)
+                    .collect()
+            }),
+            changelog: existing(target_dir.join(CHANGELOG_FILE)),
+            spec: existing(target_dir.join(SPEC_FILE)),
+        }
+    }
+}
+
+fn read_json<T: DeserializeOwned>(path: &Path) -> Panel<T> {
+    let text = match fs::read_to_string(path) {
+        Ok(text) => text,
+        Err(_) if !path.exists() => return Panel::Missing(path.to_path_buf()),
+        Err(error) => {
+            return Panel::Unreadable {
+                path: path.to_path_buf(),
+                error: error.to_string(),
+            };
+        }
+    };
+    match serde_json::from_str(&text) {
+        Ok(value) => Panel::Ready(value),
+        Err(error) => Panel::Unreadable {
+            path: path.to_path_buf(),
+            error: error.to_string(),
+        },
+    }
+}
+
+fn existing(path: PathBuf) -> Option<PathBuf> {
+    path.is_file().then_some(path)
+}
+
+fn headline(spec: &Value, previous: Option<&HistoryRecord>) -> Vec<Count> {
+    // Counted the same way the generator records runs, so deltas line up.
+    let operations = spec
+        .get("paths")
+        .and_then(Value::as_object)
+        .into_iter()
+        .flat_map(|paths| paths.values())
+        .filter_map(Value::as_object)
+        .flat_map(|item| item.values())
+        .filter(|operation| operation.get("operationId").is_some())
+        .count();
+    let components = spec
+        .pointer("/components/schemas")
+        .and_then(Value::as_object)
+        .map_or(0, |schemas| schemas.len());
+    let delta = |value: usize, before: fn(&HistoryRecord) -> usize| {
+        previous.map(|record| value as i64 - before(record) as i64)
+    };
+    vec![
+        Count {
+            name: keys::DASHBOARD_OPERATIONS,
+            value: operations,
+            delta: delta(operations, |record| record.operations.len()),
+        },
+        Count {
+            name: keys::DASHBOARD_COMPONENTS,
+            value: components,
+            delta: delta(components, |record| record.components.len()),
+        },
+    ]
+}
+
+fn categories(entries: &[ReportLine]) -> Vec<Category> {
+    let mut by_kind = BTreeMap::<&str, Vec<&ReportLine>>::new();
+    for entry in entries
+        .iter()
+        .filter(|entry| !INFORMATIONAL.contains(&entry.kind.as_str()))
+    {
+        by_kind.entry(&entry.kind).or_default().push(entry);
+    }
+    let mut categories = by_kind
+        .into_iter()
+        .map(|(kind, entries)| Category {
+            kind: kind.to_string(),
+            count: entries.len(),
+            entries: entries.into_iter().take(TOP_ENTRIES).cloned().collect(),
+        })
+        .collect::<Vec<_>>();
+    // Stable, so ties stay in kind order.
+    categories.sort_by_key(|category| Reverse(category.count));
+    categories
+}
+
+fn of_kinds(entries: Vec<ReportLine>, kinds: &[&str]) -> Vec<ReportLine> {
+    entries
+        .into_iter()
+        .filter(|entry| kinds.contains(&entry.kind.as_str()))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    fn artifact_dir(name: &str) -> PathBuf {
+        let dir =
+            std::env::temp_dir().join(format!("poroshell-dashboard-{name}-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(dir.join(DEFAULT_TARGET)).unwrap();
+        dir
+    }
+
+    fn write(path: PathBuf, value: Value) {
+        fs::write(path, serde_json::to_string_pretty(&value).unwrap()).unwrap();
+    }
+
+    fn spec() -> Value {
+        json!({
+            "info": { "version": "2.0" },
+            "paths": {
+                "/a": { "get": { "operationId": "GetA" }, "post": { "operationId": "PostA" } },
+                "/b": { "get": { "operationId": "GetB" } },
+            },
+            "components": { "schemas": { "A": {}, "B": {} } },
+        })
+    }
+
+    #[test]
+    fn test_load_complete_run() {
+        let dir = artifact_dir("complete");
+        write(dir.join(DEFAULT_TARGET).join(SPEC_FILE), spec());
+        write(
+            dir.join(DEFAULT_TARGET).join(REPORT_FILE),
+            json!({
+                "counts": { "warning": 2, "skipped": 1, "renamed": 1, "merged": 1, "timing": 3 },
+                "entries": [
+                    { "kind": "warning", "target": "A", "detail": "odd" },
+                    { "kind": "warning", "target": "B", "detail": "odder" },
+                    { "kind": "skipped", "target": "/c", "detail": "no schema" },
+                    { "kind": "merged", "target": "C", "detail": "same as A" },
+                    { "kind": "renamed", "target": "D", "detail": "now E" },
+                    { "kind": "timing", "target": "help", "detail": "1s" },
+                    { "kind": "timing", "target": "types", "detail": "2s" },
+                    { "kind": "timing", "target": "events", "detail": "3s" },
+                ],
+            }),
+        );
+        write(
+            dir.join(HISTORY_FILE),
+            json!({ "records": [
+                { "version": "1.0", "operations": ["GetA"], "components": ["A", "B", "C"] },
+                { "version": "2.0", "operations": ["GetA", "PostA", "GetB"], "components": ["A", "B"] },
+            ] }),
+        );
+        write(
+            dir.join(MANIFEST_FILE),
+            json!({ "artifacts": [
+                { "path": "lcu/openapi.json", "template": "{target}/openapi.json", "sha256": "" },
+                { "path": "lcu/report.json", "template": "{target}/report.json", "sha256": "" },
+            ] }),
+        );
+        fs::write(dir.join(DEFAULT_TARGET).join(CHANGELOG_FILE), "# Changes").unwrap();
+
+        let dashboard = Dashboard::load(&dir, DEFAULT_TARGET);
+        assert_eq!(dashboard.version.as_deref(), Some("2.0"));
+        assert_eq!(
+            dashboard.headline,
+            Panel::Ready(vec![
+                Count {
+                    name: keys::DASHBOARD_OPERATIONS,
+                    value: 3,
+                    delta: Some(2)
+                },
+                Count {
+                    name: keys::DASHBOARD_COMPONENTS,
+                    value: 2,
+                    delta: Some(-1)
+                },
+            ])
+        );
+        let categories = dashboard.categories.ready().unwrap();
+        assert_eq!(
+            categories
+                .iter()
+                .map(|category| (category.kind.as_str(), category.count))
+                .collect::<Vec<_>>(),
+            [
+                ("warning", 2),
+                ("merged", 1),
+                ("renamed", 1),
+                ("skipped", 1)
+            ]
+        );
+        assert_eq!(dashboard.skipped.ready().unwrap()[0].target, "/c");
+        assert_eq!(
+            dashboard
+                .collided
+                .ready()
+                .unwrap()
+                .iter()
+                .map(|entry| entry.target.as_str())
+                .collect::<Vec<_>>(),
+            ["C", "D"]
+        );
+        assert_eq!(
+            dashboard.artifacts,
+            Panel::Ready(vec![
+                PathBuf::from("lcu/openapi.json"),
+                PathBuf::from("lcu/report.json")
+            ])
+        );
+        assert_eq!(
+            dashboard.changelog,
+            Some(dir.join(DEFAULT_TARGET).join(CHANGELOG_FILE))
+        );
+        assert_eq!(
+            dashboard.spec,
+            Some(dir.join(DEFAULT_TARGET).join(SPEC_FILE))
+        );
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_load_partial_run() {
+        let dir = artifact_dir("partial");
+        write(dir.join(DEFAULT_TARGET).join(SPEC_FILE), spec());
+        fs::write(
+            dir.join(DEFAULT_TARGET).join(REPORT_FILE),
+            "{ \"entries\": [",
+        )
+        .unwrap();
+
+        let dashboard = Dashboard::load(&dir, DEFAULT_TARGET);
+        // Without history there is nothing to compare against.
+        assert_eq!(
+            dashboard.headline.ready().unwrap()[0],
+            Count {
+                name: keys::DASHBOARD_OPERATIONS,
+                value: 3,
+                delta: None
+            }
+        );
+        assert!(matches!(
+            dashboard.categories,
+            Panel::Unreadable { ref path, .. } if path.ends_with(REPORT_FILE)
+        ));
+        assert!(matches!(dashboard.skipped, Panel::Unreadable { .. }));
+        assert_eq!(dashboard.artifacts, Panel::Missing(dir.join(MANIFEST_FILE)));
+        assert_eq!(dashboard.changelog, None);
+        assert!(dashboard.spec.is_some());
+        fs::remove_dir_all(&dir).unwrap();
+
+        let empty = Dashboard::load(&dir, DEFAULT_TARGET);
+        assert!(matches!(empty.headline, Panel::Missing(_)));
+        assert_eq!(empty.version, None);
+    }
+}
@@ -0,0 +1,61 @@
+//! A small spec built into the binary, so there's something to browse with
+//! no network, at demos and on first runs.
+//!
+//! It's `assets/demo.json`, the miniature spec, unless `PORO_DEMO_SPEC`
+//! pointed at another file when the crate was built.
+
+use crate::Document;
+
+/// What the demo source goes by where other sources have a URL. Nothing is
+/// fetched from it.
+pub const DEMO_URL: &str = "embedded:demo.json";
+
+/// The built-in spec.
+pub const SPEC: &str = include_str!(concat!(env!("OUT_DIR"), "/demo.json"));
+
+/// The demo as a source, labeled with the client version it was taken from.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DemoSource {
+    pub version: String,
+    label: String,
+}
+
+impl DemoSource {
+    pub fn new() -> Self {
+        let version = Document::from_json(SPEC)
+            .map(|document| document.stats().version)
+            .unwrap_or_default();
+        let label = format!("Offline demo ({version})");
+        Self { version, label }
+    }
+
+    pub fn label(&self) -> &str {
+        &self.label
+    }
+
+    /// Parse the spec the same way as a downloaded one.
+    pub fn load(&self) -> serde_json::Result<Document> {
+        Document::from_json(SPEC)
+    }
+}
+
+impl Default for DemoSource {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_embedded_parses() {
+        let demo = DemoSource::new();
+        let document = demo.load().unwrap();
+        let stats = document.stats();
+        assert!(stats.endpoints > 0);
+        assert_eq!(stats.version, demo.version);
+        assert_eq!(demo.label(), format!("Offline demo ({})", demo.version));
+    }
+}
@@ -0,0 +1,555 @@
+//! Marking what changed in a document since a baseline, while browsing it.
+//!
+//! Operations are matched by method and path. A [`DocumentDiff`] is computed
+//! once per pair of documents and keeps both, so lists built from it with
+//! [`DocumentDiff::rows`] carry their markers through any grouping, and
+//! [`RowFilter`] keeps them through search.
+
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    fmt,
+};
+
+use openapi::types::Operation;
+
+use crate::{Document, Grouping, Plugin};
+
+/// How an operation differs from the baseline's.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Change {
+    Added,
+    Changed,
+    /// Only in the baseline. Listed after the rest of its group.
+    Removed,
+}
+
+impl Change {
+    /// What a list shows before the operation.
+    pub fn marker(self) -> char {
+        match self {
+            Self::Added => '+',
+            Self::Changed => '~',
+            Self::Removed => '-',
+        }
+    }
+}
+
+/// What changed in an operation both documents have, for highlighting in
+/// its details.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct OperationChanges {
+    /// Parameters added, removed or changed, by name.
+    pub parameters: BTreeSet<String>,
+    /// Status codes whose response was added, removed or changed.
+    pub responses: BTreeSet<String>,
+    pub request_body: bool,
+}
+
+impl OperationChanges {
+    fn between(old: &Operation, new: &Operation) -> Self {
+        let parameters = |operation: &Operation| {
+            operation
+                .parameters
+                .iter()
+                .map(|parameter| (parameter.name.clone(), parameter.clone()))
+                .collect::<BTreeMap<_, _>>()
+        };
+        let responses = |operation: &Operation| {
+            operation
+                .responses
+                .iter()
+                .flatten()
+                .map(|(status, response)| (status.clone(), response.clone()))
+                .collect::<BTreeMap<_, _>>()
+        };
+        Self {
+            parameters: changed_keys(&parameters(old), &parameters(new)),
+            responses: changed_keys(&responses(old), &responses(new)),
+            request_body: old.request_body != new.request_body,
+        }
+    }
+}
+
+/// Keys only one side has, or whose values differ.
+fn changed_keys<V: PartialEq>(
+    old: &BTreeMap<String, V>,
+    new: &BTreeMap<String, V>,
+) -> BTreeSet<String> {
+    old.keys()
+        .chain(new.keys())
+        .filter(|key| old.get(*key) != new.get(*key))
+        .cloned()
+        .collect()
+}
+
+/// Additions, changes and removals in a list of rows.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ChangeCounts {
+    pub added: usize,
+    pub changed: usize,
+    pub removed: usize,
+}
+
+impl ChangeCounts {
+    pub fn of(rows: &[DiffRow]) -> Self {
+        let mut counts = Self::default();
+        for row in rows {
+            match row.change {
+                Some(Change::Added) => counts.added += 1,
+                Some(Change::Changed) => counts.changed += 1,
+                Some(Change::Removed) => counts.removed += 1,
+                None => (),
+            }
+        }
+        counts
+    }
+
+    pub fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+}
+
+/// Nonzero counts by marker, e.g. `+2 ~1`.
+impl fmt::Display for ChangeCounts {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let counts = [
+            (Change::Added, self.added),
+            (Change::Changed, self.changed),
+            (Change::Removed, self.removed),
+        ];
+        let counts = counts
+            .iter()
+            .filter(|(_, count)| *count > 0)
+            .map(|(change, count)| format!("{}{count}", change.marker()))
+            .collect::<Vec<String>>();
+        write!(f, "{}", counts.join(" "))
+    }
+}
+
+/// An endpoint of a list, with its marker if it changed.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DiffRow {
+    pub plugin: Plugin,
+    pub change: Option<Change>,
+}
+
+impl DiffRow {
+    /// Removed endpoints are shown greyed, as they can't be opened.
+    pub fn is_removed(&self) -> bool {
+        self.change == Some(Change::Removed)
+    }
+}
+
+/// Changes of a document since its baseline.
+#[derive(Debug, Clone)]
+pub struct DocumentDiff {
+    baseline: Document,
+    current: Document,
+    /// By path, then method.
+    changes: BTreeMap<(String, String), Change>,
+    details: BTreeMap<(String, String), OperationChanges>,
+}
+
+impl DocumentDiff {
+    pub fn new(baseline: &Document, current: &Document) -> Self {
+        let operations = |document: &Document| {
+            document
+                .group(crate::ALL_GROUP)
+                .into_iter()
+                .map(|plugin| ((plugin.path.clone(), plugin.method.clone()), plugin))
+                .collect::<BTreeMap<(String, String), Plugin>>()
+        };
+        let (old, new) = (operations(baseline), operations(current));
+
+        let mut changes = BTreeMap::new();
+        let mut details = BTreeMap::new();
+        for (key, plugin) in new.iter() {
+            match old.get(key) {
+                None => {
+                    changes.insert(key.clone(), Change::Added);
+                }
+                Some(old) if old.operation != plugin.operation => {
+                    changes.insert(key.clone(), Change::Changed);
+                    details.insert(
+                        key.clone(),
+                        OperationChanges::between(&old.operation, &plugin.operation),
+                    );
+                }
+                Some(_) => (),
+            }
+        }
+        for key in old.keys().filter(|key| !new.contains_key(*key)) {
+            changes.insert(key.clone(), Change::Removed);
+        }
+
+        Self {
+            baseline: baseline.clone(),
+            current: current.clone(),
+            changes,
+            details,
+        }
+    }
+
+    /// The marker of an endpoint of either document, if it has one.
+    pub fn change(&self, method: &str, path: &str) -> Option<Change> {
+        self.changes
+            .get(&(path.to_string(), method.to_string()))
+            .copied()
+    }
+
+    /// What changed in an endpoint marked [`Change::Changed`].
+    pub fn details(&self, method: &str, path: &str) -> Option<&OperationChanges> {
+        self.details.get(&(path.to_string(), method.to_string()))
+    }
+
+    /// A group's endpoints in their usual order with their markers, then the
+    /// ones the baseline had in the group that are gone.
+    pub fn rows(&self, grouping: Grouping, group: &str) -> Vec<DiffRow> {
+        let row = |plugin: Plugin| DiffRow {
+            change: self.change(&plugin.method, &plugin.path),
+            plugin,
+        };
+        let removed = self
+            .baseline
+            .group_by(grouping, group)
+            .into_iter()
+            .filter(|plugin| self.change(&plugin.method, &plugin.path) == Some(Change::Removed));
+        self.current
+            .group_by(grouping, group)
+            .into_iter()
+            .chain(removed)
+            .map(row)
+            .collect()
+    }
+
+    /// How many of a group's endpoints are marked.
+    pub fn counts(&self, grouping: Grouping, group: &str) -> ChangeCounts {
+        ChangeCounts::of(&self.rows(grouping, group))
+    }
+}
+
+/// A group's endpoints as rows, marked if there is a diff. See
+/// [`DocumentDiff::rows`].
+pub fn rows(
+    document: &Document,
+    diff: Option<&DocumentDiff>,
+    grouping: Grouping,
+    group: &str,
+) -> Vec<DiffRow> {
+    match diff {
+        Some(diff) => diff.rows(grouping, group),
+        None => document
+            .group_by(grouping, group)
+            .into_iter()
+            .map(|plugin| DiffRow {
+                plugin,
+                change: None,
+            })
+            .collect(),
+    }
+}
+
+/// Narrows a list of rows without reordering it, so removed endpoints stay
+/// at the bottom and markers stay on their rows.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RowFilter {
+    /// Matched against the path and operationId, ignoring case. Empty
+    /// matches everything.
+    pub query: String,
+    /// Leave out unmarked rows.
+    pub only_changed: bool,
+}
+
+impl RowFilter {
+    pub fn matches(&self, row: &DiffRow) -> bool {
+        let query = self.query.to_lowercase();
+        let found = [&row.plugin.path, &row.plugin.operation.operation_id]
+            .iter()
+            .any(|text| text.to_lowercase().contains(&query));
+        found && (!self.only_changed || row.change.is_some())
+    }
+
+    pub fn apply(&self, rows: Vec<DiffRow>) -> Vec<DiffRow> {
+        rows.into_iter().filter(|row| self.matches(row)).collect()
+    }
+
+    /// Whether a list of groups shows `group`, which it does if any of its
+    /// rows are kept, and the counts of the rows kept. Without a diff nothing
+    /// is marked, so [`RowFilter::only_changed`] is ignored rather than
+    /// emptying the list.
+    pub fn group(
+        &self,
+        document: &Document,
+        diff: Option<&DocumentDiff>,
+        grouping: Grouping,
+        group: &str,
+    ) -> Option<ChangeCounts> {
+        let filter = Self {
+            query: self.query.clone(),
+            only_changed: self.only_changed && diff.is_some(),
+        };
+        let rows = filter.apply(rows(document, diff, grouping, group));
+        (!rows.is_empty()).then(|| ChangeCounts::of(&rows))
+    }
+}
+
+/// The diff of the latest document pair, kept until either document changes.
+#[derive(Debug, Clone, Default)]
+pub struct DiffCache {
+    /// URLs of the baseline and the current document.
+    key: Option<(String, String)>,
+    diff: Option<DocumentDiff>,
+}
+
+impl DiffCache {
+    /// The diff of these documents, computed only if the last call was for
+    /// another pair.
+    pub fn get(
+        &mut self,
+        (baseline_url, baseline): (&str, &Document),
+        (current_url, current): (&str, &Document),
+    ) -> &DocumentDiff {
+        let key = (baseline_url.to_string(), current_url.to_string());
+        if self.key.as_ref() != Some(&key) || self.diff.is_none() {
+            self.diff = Some(DocumentDiff::new(baseline, current));
+            self.key = Some(key);
+        }
+        self.diff.as_ref().expect("diff computed above")
+    }
+
+    /// The diff last computed, if any.
+    pub fn current(&self) -> Option<&DocumentDiff> {
+        self.diff.as_ref()
+    }
+
+    /// Forget the diff, e.g. when the baseline is unset.
+    pub fn clear(&mut self) {
+        *self = Self::default();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::{Value, json};
+
+    use super::*;
+    use crate::ALL_GROUP;
+
+    fn document(paths: Value) -> Document {
+        Document::new(
+            serde_json::from_value(json!({
+                "openapi": "3.0.0",
+                "info": { "title": "LCU", "description": "", "version": "1.0.0" },
+                "paths": paths,
+                "components": { "schemas": {} },
+            }))
+            .unwrap(),
+        )
+    }
+
+    fn operation(id: &str, tags: &[&str], parameters: Value, response: &str) -> Value {
+        json!({
+            "operationId": id,
+            "tags": tags,
+            "parameters": parameters,
+            "responses": {
+                "200": {
+                    "description": "Success",
+                    "content": { "application/json": { "schema": { "$ref": response } } },
+                },
+            },
+        })
+    }
+
+    /// Lobby's GET changes its parameter and response, its DELETE is gone and
+    /// PUT is new, and the summoner plugin keeps its one endpoint.
+    fn diff() -> DocumentDiff {
+        let id = json!([{ "in": "path", "name": "id", "schema": { "type": "string" } }]);
+        let id_number = json!([{ "in": "path", "name": "id", "schema": { "type": "integer" } }]);
+        let lobby = ["plugins", "lol-lobby"];
+        let summoner = operation(
+            "GetLolSummonerV1CurrentSummoner",
+            &["plugins", "lol-summoner"],
+            json!([]),
+            "#/components/schemas/LolSummonerSummoner",
+        );
+        let baseline = document(json!({
+            "/lol-lobby/v2/lobby/{id}": {
+                "get": operation("GetLolLobbyV2Lobby", &lobby, id.clone(), "#/components/schemas/LolLobbyLobbyDto"),
+                "delete": operation("DeleteLolLobbyV2Lobby", &lobby, id.clone(), "#/components/schemas/Empty"),
+            },
+            "/lol-summoner/v1/current-summoner": { "get": summoner },
+        }));
+        let current = document(json!({
+            "/lol-lobby/v2/lobby/{id}": {
+                "get": operation("GetLolLobbyV2Lobby", &lobby, id_number, "#/components/schemas/LolLobbyLobbyV2Dto"),
+                "put": operation("PutLolLobbyV2Lobby", &lobby, id, "#/components/schemas/Empty"),
+            },
+            "/lol-summoner/v1/current-summoner": { "get": summoner },
+        }));
+        DocumentDiff::new(&baseline, &current)
+    }
+
+    fn markers(rows: &[DiffRow]) -> Vec<(String, Option<char>)> {
+        rows.iter()
+            .map(|row| {
+                (
+                    row.plugin.operation.operation_id.clone(),
+                    row.change.map(Change::marker),
+                )
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_markers() {
+        let diff = diff();
+        let lobby = "/lol-lobby/v2/lobby/{id}";
+        assert_eq!(diff.change("put", lobby), Some(Change::Added));
+        assert_eq!(diff.change("get", lobby), Some(Change::Changed));
+        assert_eq!(diff.change("delete", lobby), Some(Change::Removed));
+        assert_eq!(
+            diff.change("get", "/lol-summoner/v1/current-summoner"),
+            None
+        );
+
+        let changes = diff.details("get", lobby).unwrap();
+        assert_eq!(changes.parameters, BTreeSet::from(["id".to_string()]));
+        assert_eq!(changes.responses, BTreeSet::from(["200".to_string()]));
+        assert!(!changes.request_body);
+        assert!(diff.details("put", lobby).is_none());
+
+        let counts = diff.counts(Grouping::Plugin, "lol-lobby");
+        assert_eq!(counts.to_string(), "+1 ~1 -1");
+        assert!(diff.counts(Grouping::Plugin, "lol-summoner").is_empty());
+    }
+
+    #[test]
+    fn test_removed_listed_last() {
+        let diff = diff();
+        let rows = diff.rows(Grouping::Plugin, "lol-lobby");
+        assert_eq!(
+            markers(&rows),
+            [
+                ("GetLolLobbyV2Lobby".to_string(), Some('~')),
+                ("PutLolLobbyV2Lobby".to_string(), Some('+')),
+                ("DeleteLolLobbyV2Lobby".to_string(), Some('-')),
+            ]
+        );
+        assert!(rows.last().unwrap().is_removed());
+
+        // Even after endpoints that sort after it in the flat list.
+        let all = diff.rows(Grouping::Plugin, ALL_GROUP);
+        assert_eq!(all.len(), 4);
+        assert_eq!(
+            all.last().unwrap().plugin.operation.operation_id,
+            "DeleteLolLobbyV2Lobby"
+        );
+        assert!(diff.rows(Grouping::Workflow, "Lobby").is_empty());
+    }
+
+    #[test]
+    fn test_only_changed_with_search() {
+        let diff = diff();
+        let rows = diff.rows(Grouping::Plugin, ALL_GROUP);
+        let filter = |query: &str, only_changed: bool| {
+            markers(
+                &RowFilter {
+                    query: query.to_string(),
+                    only_changed,
+                }
+                .apply(rows.clone()),
+            )
+        };
+
+        assert_eq!(filter("", false).len(), 4);
+        assert_eq!(
+            filter("", true),
+            [
+                ("GetLolLobbyV2Lobby".to_string(), Some('~')),
+                ("PutLolLobbyV2Lobby".to_string(), Some('+')),
+                ("DeleteLolLobbyV2Lobby".to_string(), Some('-')),
+            ]
+        );
+        // The search narrows the changed rows, and removed ones still match.
+        assert_eq!(
+            filter("delete", true),
+            [("DeleteLolLobbyV2Lobby".to_string(), Some('-'))]
+        );
+        assert_eq!(
+            filter("SUMMONER", false),
+            [("GetLolSummonerV1CurrentSummoner".to_string(), None)]
+        );
+        assert!(filter("summoner", true).is_empty());
+    }
+
+    #[test]
+    fn test_groups_only_changed_with_search() {
+        let diff = diff();
+        let current = diff.current.clone();
+        let group = |query: &str, only_changed: bool, diff: Option<&DocumentDiff>, group: &str| {
+            RowFilter {
+                query: query.to_string(),
+                only_changed,
+            }
+            .group(&current, diff, Grouping::Plugin, group)
+            .map(|counts| counts.to_string())
+        };
+
+        assert_eq!(
+            group("", false, Some(&diff), "lol-lobby").as_deref(),
+            Some("+1 ~1 -1")
+        );
+        assert_eq!(
+            group("", false, Some(&diff), "lol-summoner").as_deref(),
+            Some("")
+        );
+        assert_eq!(group("", true, Some(&diff), "lol-summoner"), None);
+        // Counts follow the search, and a removed endpoint keeps its group listed.
+        assert_eq!(
+            group("delete", true, Some(&diff), "lol-lobby").as_deref(),
+            Some("-1")
+        );
+        assert_eq!(group("summoner", true, Some(&diff), "lol-lobby"), None);
+        assert_eq!(
+            group("summoner", false, Some(&diff), "lol-summoner").as_deref(),
+            Some("")
+        );
+
+        // Without a baseline only the search applies.
+        assert_eq!(group("", true, None, "lol-summoner").as_deref(), Some(""));
+        assert_eq!(group("delete", false, None, "lol-lobby"), None);
+        assert_eq!(
+            markers(&rows(&current, None, Grouping::Plugin, "lol-lobby")),
+            [
+                ("GetLolLobbyV2Lobby".to_string(), None),
+                ("PutLolLobbyV2Lobby".to_string(), None),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_cache() {
+        let baseline = document(json!({}));
+        let current = diff().current;
+        let mut cache = DiffCache::default();
+        assert!(cache.current().is_none());
+
+        let added = cache
+            .get(("old.json", &baseline), ("new.json", &current))
+            .counts(Grouping::Plugin, ALL_GROUP);
+        assert_eq!(added.added, 3);
+        // The same pair reuses the diff even if given other documents.
+        let reused = cache
+            .get(("old.json", &current), ("new.json", &current))
+            .counts(Grouping::Plugin, ALL_GROUP);
+        assert_eq!(reused, added);
+        let recomputed = cache
+            .get(("new.json", &current), ("new.json", &current))
+            .counts(Grouping::Plugin, ALL_GROUP);
+        assert!(recomputed.is_empty());
+
+        cache.clear();
+        assert!(cache.current().is_none());
+    }
+}
@@ -0,0 +1,51 @@
+//! How a [`Document`](crate::Document) sorts endpoints into groups.
+
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+
+/// Rules for grouping endpoints. The default groups by tag the way the
+/// published specs are tagged.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(default)]
+pub struct GroupingConfig {
+    /// Tags that never make a group, like `plugins`, which every plugin endpoint has.
+    pub skip: Vec<String>,
+    /// Tag to the group it belongs to, for plugins that were renamed or split,
+    /// e.g. `lol-champ-select-legacy` to `lol-champ-select`.
+    pub aliases: BTreeMap<String, String>,
+    /// Group for endpoints without tags.
+    pub default_group: String,
+    /// Group by the first path segment instead of by tags.
+    pub by_path: bool,
+}
+
+impl Default for GroupingConfig {
+    fn default() -> Self {
+        Self {
+            skip: vec![String::from("plugins")],
+            aliases: BTreeMap::new(),
+            default_group: String::from("default"),
+            by_path: false,
+        }
+    }
+}
+
+impl GroupingConfig {
+    /// The group a tag belongs to, or `None` if it is skipped.
+    pub fn group_of<'a>(&'a self, tag: &'a str) -> Option<&'a str> {
+        if self.skip.iter().any(|skip| skip == tag) {
+            return None;
+        }
+        Some(self.aliases.get(tag).map_or(tag, String::as_str))
+    }
+
+    /// The tag of a path in [`GroupingConfig::by_path`] mode: its first segment,
+    /// or the default group for the root.
+    pub fn path_tag<'a>(&'a self, path: &'a str) -> &'a str {
+        match path.trim_start_matches('/').split('/').next() {
+            Some(segment) if !segment.is_empty() => segment,
+            _ => &self.default_group,
+        }
+    }
+}
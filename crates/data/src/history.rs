@@ -0,0 +1,210 @@
+use std::{
+    collections::VecDeque,
+    fs,
+    io::{self, BufReader, BufWriter, Write},
+    path::Path,
+};
+
+use serde::{Deserialize, Serialize};
+
+/// Request history file, next to [`crate::settings::SETTINGS_FILE`]. Not
+/// [`crate::dashboard::HISTORY_FILE`], which records generation runs.
+pub const REQUEST_HISTORY_FILE: &str = "request_history.json";
+
+/// Maximum number of characters kept from a response body.
+pub const MAX_RESPONSE_LEN: usize = 4096;
+
+/// Replacement for header values that must never be written to disk.
+pub const REDACTED: &str = "<redacted>";
+
+/// A single executed request.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct HistoryEntry {
+    pub method: String,
+    /// Path with the parameters already substituted.
+    pub path: String,
+    pub headers: Vec<(String, String)>,
+    pub body: Option<serde_json::Value>,
+    pub status: Option<u16>,
+    pub duration_ms: u64,
+    /// Response body, truncated to [`MAX_RESPONSE_LEN`] characters.
+    pub response: Option<String>,
+}
+
+impl HistoryEntry {
+    pub fn new(method: &str, path: &str) -> Self {
+        Self {
+            method: method.into(),
+            path: path.into(),
+            headers: Vec::new(),
+            body: None,
+            status: None,
+            duration_ms: 0,
+            response: None,
+        }
+    }
+
+    pub fn with_response(mut self, status: u16, duration_ms: u64, response: &str) -> Self {
+        self.status = Some(status);
+        self.duration_ms = duration_ms;
+        self.response = Some(truncate(response, MAX_RESPONSE_LEN));
+        self
+    }
+
+    /// Returns a copy with the auth token removed.
+    pub fn redacted(&self) -> Self {
+        let mut entry = self.clone();
+        for (name, value) in entry.headers.iter_mut() {
+            if name.eq_ignore_ascii_case("authorization") {
+                *value = REDACTED.into();
+            }
+        }
+        entry
+    }
+}
+
+/// Ring buffer of executed requests, newest last.
+#[derive(Debug, Clone)]
+pub struct HistoryStore {
+    entries: VecDeque<HistoryEntry>,
+    capacity: usize,
+}
+
+impl HistoryStore {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            entries: VecDeque::with_capacity(capacity),
+            capacity,
+        }
+    }
+
+    /// Record an entry, evicting the oldest one when the store is full.
+    pub fn push(&mut self, entry: HistoryEntry) {
+        if self.capacity == 0 {
+            return;
+        }
+        while self.entries.len() >= self.capacity {
+            self.entries.pop_front();
+        }
+        self.entries.push_back(entry);
+    }
+
+    pub fn get(&self, index: usize) -> Option<&HistoryEntry> {
+        self.entries.get(index)
+    }
+
+    pub fn entries(&self) -> impl Iterator<Item = &HistoryEntry> {
+        self.entries.iter()
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    pub fn clear(&mut self) {
+        self.entries.clear();
+    }
+
+    /// Load history from a file, keeping the newest `capacity` entries.
+    /// A missing file yields an empty store.
+    pub fn load(path: &Path, capacity: usize) -> io::Result<Self> {
+        let mut store = Self::new(capacity);
+        let file = match fs::File::open(path) {
+            Ok(file) => file,
+            Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(store),
+            Err(err) => return Err(err),
+        };
+        let entries: Vec<HistoryEntry> = serde_json::from_reader(BufReader::new(file))?;
+        for entry in entries {
+            store.push(entry);
+        }
+        Ok(store)
+    }
+
+    /// Persist the history with auth tokens redacted.
+    pub fn save(&self, path: &Path) -> io::Result<()> {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let entries = self
+            .entries
+            .iter()
+            .map(HistoryEntry::redacted)
+            .collect::<Vec<HistoryEntry>>();
+        let mut writer = BufWriter::new(fs::File::create(path)?);
+        serde_json::to_writer_pretty(&mut writer, &entries)?;
+        writer.flush()
+    }
+}
+
+fn truncate(s: &str, max_chars: usize) -> String {
+    match s.char_indices().nth(max_chars) {
+        Some((index, _)) => format!("{}…", &s[..index]),
+        None => s.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(path: &str) -> HistoryEntry {
+        HistoryEntry::new("GET", path)
+    }
+
+    #[test]
+    fn test_file_apart_from_run_history() {
+        // The artifact directory may be the working directory too.
+        assert_ne!(REQUEST_HISTORY_FILE, crate::dashboard::HISTORY_FILE);
+        assert_ne!(REQUEST_HISTORY_FILE, crate::settings::SETTINGS_FILE);
+    }
+
+    #[test]
+    fn test_push_caps_oldest() {
+        let mut store = HistoryStore::new(2);
+        store.push(entry("/a"));
+        store.push(entry("/b"));
+        store.push(entry("/c"));
+
+        let paths = store.entries().map(|e| e.path.as_str()).collect::<Vec<_>>();
+        assert_eq!(paths, vec!["/b", "/c"]);
+    }
+
+    #[test]
+    fn test_response_truncated() {
+        let long = "x".repeat(MAX_RESPONSE_LEN + 10);
+        let e = entry("/a").with_response(200, 5, &long);
+        assert_eq!(e.response.unwrap().chars().count(), MAX_RESPONSE_LEN + 1);
+    }
+
+    #[test]
+    fn test_save_load_redacts() {
+        let path = std::env::temp_dir().join(format!("poroshell-history-{}.json", std::process::id()));
+        let mut store = HistoryStore::new(10);
+        let mut e = entry("/lol-summoner/v1/current-summoner").with_response(200, 12, "{}");
+        e.headers.push(("Authorization".into(), "Basic cmlvdDpzZWNyZXQ=".into()));
+        e.headers.push(("Accept".into(), "application/json".into()));
+        store.push(e.clone());
+        store.push(entry("/b"));
+        store.save(&path).unwrap();
+
+        // Loading with a smaller capacity keeps the newest entries.
+        let loaded = HistoryStore::load(&path, 1).unwrap();
+        assert_eq!(loaded.len(), 1);
+        assert_eq!(loaded.get(0).unwrap().path, "/b");
+
+        let loaded = HistoryStore::load(&path, 10).unwrap();
+        let first = loaded.get(0).unwrap();
+        assert_eq!(first.headers[0].1, REDACTED);
+        assert_eq!(first.headers[1].1, "application/json");
+        // The in-memory entry is untouched.
+        assert_eq!(store.get(0).unwrap(), &e);
+
+        fs::remove_file(&path).unwrap();
+        assert!(HistoryStore::load(&path, 10).unwrap().is_empty());
+    }
+}
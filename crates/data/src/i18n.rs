@@ -0,0 +1,474 @@
+//! Translated UI strings.
+//!
+//! Each locale has a catalog under `assets/i18n`, embedded in the binary.
+//! Catalogs are TOML: strings, at the top level or in tables, which is all
+//! [`parse_catalog`] accepts. A key missing from a locale's catalog falls back
+//! to English.
+//!
+//! Text can have positional placeholders, `{0}`, `{1}` and so on, filled in by
+//! [`Strings::format`].
+
+use std::{
+    collections::{BTreeMap, BTreeSet, HashMap},
+    fmt,
+    sync::Mutex,
+};
+
+use serde::Deserialize;
+
+/// Locale used when none is set, and for keys other locales lack.
+pub const DEFAULT_LOCALE: &str = "en";
+
+/// Embedded catalogs by locale.
+const CATALOGS: [(&str, &str); 1] = [("en", include_str!("../assets/i18n/en.toml"))];
+
+/// Every key the app looks up. Use these rather than literals so the English
+/// catalog can be checked against them.
+pub mod keys {
+    pub const HINT_QUIT: &str = "hint.quit";
+    pub const HINT_RESIZE: &str = "hint.resize";
+    pub const HINT_OPEN: &str = "hint.open";
+    pub const HINT_WORKFLOWS: &str = "hint.workflows";
+    pub const HINT_SOURCES: &str = "hint.sources";
+    pub const HINT_LIBRARIES: &str = "hint.libraries";
+    pub const HINT_OPEN_LINK: &str = "hint.open_link";
+    pub const HINT_COPY_LINK: &str = "hint.copy_link";
+    pub const HINT_SCROLL: &str = "hint.scroll";
+    pub const HINT_NAVIGATION: &str = "hint.navigation";
+    pub const HINT_CHOOSE: &str = "hint.choose";
+    pub const HINT_CONFIRM: &str = "hint.confirm";
+    pub const HINT_LOAD: &str = "hint.load";
+    pub const HINT_CLOSE: &str = "hint.close";
+    pub const HINT_NEXT: &str = "hint.next";
+    pub const HINT_BACK: &str = "hint.back";
+    pub const HINT_SKIP: &str = "hint.skip";
+    pub const HINT_DASHBOARD: &str = "hint.dashboard";
+    pub const HINT_CHANGELOG: &str = "hint.changelog";
+    pub const HINT_LOAD_SPEC: &str = "hint.load_spec";
+    pub const HINT_RELOAD: &str = "hint.reload";
+    pub const HINT_BASELINE: &str = "hint.baseline";
+    pub const HINT_ONLY_CHANGED: &str = "hint.only_changed";
+    pub const HINT_ENDPOINTS: &str = "hint.endpoints";
+    pub const HINT_GROUPS: &str = "hint.groups";
+    pub const HINT_HOME: &str = "hint.home";
+    pub const QUIT_CONFIRM: &str = "quit.confirm";
+    pub const QUIT_WORKING: &str = "quit.working";
+    pub const QUIT_OK: &str = "quit.ok";
+    pub const QUIT_CANCEL: &str = "quit.cancel";
+    pub const QUIT_NOW: &str = "quit.now";
+    pub const QUIT_WHEN_DONE: &str = "quit.when_done";
+    pub const SETUP_TITLE: &str = "setup.title";
+    pub const SETUP_SOURCES: &str = "setup.sources";
+    pub const SETUP_LIVE_LCU: &str = "setup.live_lcu";
+    pub const SETUP_ARTIFACT_DIR: &str = "setup.artifact_dir";
+    pub const SETUP_YES: &str = "setup.yes";
+    pub const SETUP_NO: &str = "setup.no";
+    pub const SOURCES_TITLE: &str = "sources.title";
+    pub const HOME_WELCOME: &str = "home.welcome";
+    pub const NAVIGATION_TITLE: &str = "navigation.title";
+    pub const NAVIGATION_EMPTY: &str = "navigation.empty";
+    pub const NAVIGATION_GROUPS: &str = "navigation.groups";
+    pub const NAVIGATION_WORKFLOWS: &str = "navigation.workflows";
+    pub const NAVIGATION_EXPERIMENTAL: &str = "navigation.experimental";
+    pub const ENDPOINTS_GROUPS: &str = "endpoints.groups";
+    pub const ENDPOINTS_TITLE: &str = "endpoints.title";
+    pub const ENDPOINTS_EMPTY: &str = "endpoints.empty";
+    pub const ENDPOINTS_METHOD: &str = "endpoints.method";
+    pub const ENDPOINTS_PATH: &str = "endpoints.path";
+    pub const ENDPOINTS_DESCRIPTION: &str = "endpoints.description";
+    pub const LIBRARIES_TITLE: &str = "libraries.title";
+    pub const LIBRARIES_TITLE_OFFLINE: &str = "libraries.title_offline";
+    pub const LIBRARIES_OWNER: &str = "libraries.owner";
+    pub const LIBRARIES_REPO: &str = "libraries.repo";
+    pub const LIBRARIES_LANGUAGE: &str = "libraries.language";
+    pub const DASHBOARD_TITLE: &str = "dashboard.title";
+    pub const DASHBOARD_EMPTY: &str = "dashboard.empty";
+    pub const DASHBOARD_HEADLINE: &str = "dashboard.headline";
+    pub const DASHBOARD_REPORT: &str = "dashboard.report";
+    pub const DASHBOARD_SKIPPED: &str = "dashboard.skipped";
+    pub const DASHBOARD_COLLIDED: &str = "dashboard.collided";
+    pub const DASHBOARD_ARTIFACTS: &str = "dashboard.artifacts";
+    pub const DASHBOARD_OPERATIONS: &str = "dashboard.operations";
+    pub const DASHBOARD_COMPONENTS: &str = "dashboard.components";
+    pub const DASHBOARD_COUNT: &str = "dashboard.count";
+    pub const DASHBOARD_COUNT_DELTA: &str = "dashboard.count_delta";
+    pub const DASHBOARD_CATEGORY: &str = "dashboard.category";
+    pub const DASHBOARD_MORE: &str = "dashboard.more";
+    pub const DASHBOARD_NONE: &str = "dashboard.none";
+    pub const DASHBOARD_MISSING: &str = "dashboard.missing";
+    pub const DASHBOARD_UNREADABLE: &str = "dashboard.unreadable";
+    pub const ACTIVITY_LIBRARIES: &str = "activity.libraries";
+    pub const ACTIVITY_OPEN_LINK: &str = "activity.open_link";
+    pub const ACTIVITY_DOCUMENT: &str = "activity.document";
+    pub const ACTIVITY_BASELINE: &str = "activity.baseline";
+    pub const ACTIVITY_SOURCES: &str = "activity.sources";
+    pub const ACTIVITY_SETTINGS: &str = "activity.settings";
+    pub const ACTIVITY_DASHBOARD: &str = "activity.dashboard";
+    pub const ACTIVITY_OPEN_FILE: &str = "activity.open_file";
+    pub const STATUS_STATE_WRITTEN: &str = "status.state_written";
+    pub const STATUS_UNVERIFIED: &str = "status.unverified";
+    pub const STATUS_CACHED: &str = "status.cached";
+    pub const STATUS_NO_CHANGELOG: &str = "status.no_changelog";
+    pub const STATUS_NO_SPEC: &str = "status.no_spec";
+    pub const ERROR_SNAPSHOT_MISSING: &str = "error.snapshot_missing";
+    pub const ERROR_MANIFEST: &str = "error.manifest";
+    pub const ERROR_STATE_DUMP: &str = "error.state_dump";
+    pub const ERROR_NETWORK: &str = "error.network";
+    pub const ERROR_PARSE: &str = "error.parse";
+    pub const ERROR_PERMISSION: &str = "error.permission";
+    pub const ERROR_IO: &str = "error.io";
+    pub const ERROR_OPEN_LINK: &str = "error.open_link";
+    pub const ERROR_SCHEMA: &str = "error.schema";
+    pub const ERROR_INTEGRITY: &str = "error.integrity";
+    pub const TERMINAL_TOO_SMALL: &str = "terminal.too_small";
+
+    /// Every key above.
+    pub const ALL: &[&str] = &[
+        HINT_QUIT,
+        HINT_RESIZE,
+        HINT_OPEN,
+        HINT_WORKFLOWS,
+        HINT_SOURCES,
+        HINT_LIBRARIES,
+        HINT_OPEN_LINK,
+        HINT_COPY_LINK,
+        HINT_SCROLL,
+        HINT_NAVIGATION,
+        HINT_CHOOSE,
+        HINT_CONFIRM,
+        HINT_LOAD,
+        HINT_CLOSE,
+        HINT_NEXT,
+        HINT_BACK,
+        HINT_SKIP,
+        HINT_DASHBOARD,
+        HINT_CHANGELOG,
+        HINT_LOAD_SPEC,
+        HINT_RELOAD,
+        HINT_BASELINE,
+        HINT_ONLY_CHANGED,
+        HINT_ENDPOINTS,
+        HINT_GROUPS,
+        HINT_HOME,
+        QUIT_CONFIRM,
+        QUIT_WORKING,
+        QUIT_OK,
+        QUIT_CANCEL,
+        QUIT_NOW,
+        QUIT_WHEN_DONE,
+        SETUP_TITLE,
+        SETUP_SOURCES,
+        SETUP_LIVE_LCU,
+        SETUP_ARTIFACT_DIR,
+        SETUP_YES,
+        SETUP_NO,
+        SOURCES_TITLE,
+        HOME_WELCOME,
+        NAVIGATION_TITLE,
+        NAVIGATION_EMPTY,
+        NAVIGATION_GROUPS,
+        NAVIGATION_WORKFLOWS,
+        NAVIGATION_EXPERIMENTAL,
+        ENDPOINTS_GROUPS,
+        ENDPOINTS_TITLE,
+        ENDPOINTS_EMPTY,
+        ENDPOINTS_METHOD,
+        ENDPOINTS_PATH,
+        ENDPOINTS_DESCRIPTION,
+        LIBRARIES_TITLE,
+        LIBRARIES_TITLE_OFFLINE,
+        LIBRARIES_OWNER,
+        LIBRARIES_REPO,
+        LIBRARIES_LANGUAGE,
+        DASHBOARD_TITLE,
+        DASHBOARD_EMPTY,
+        DASHBOARD_HEADLINE,
+        DASHBOARD_REPORT,
+        DASHBOARD_SKIPPED,
+        DASHBOARD_COLLIDED,
+        DASHBOARD_ARTIFACTS,
+        DASHBOARD_OPERATIONS,
+        DASHBOARD_COMPONENTS,
+        DASHBOARD_COUNT,
+        DASHBOARD_COUNT_DELTA,
+        DASHBOARD_CATEGORY,
+        DASHBOARD_MORE,
+        DASHBOARD_NONE,
+        DASHBOARD_MISSING,
+        DASHBOARD_UNREADABLE,
+        ACTIVITY_LIBRARIES,
+        ACTIVITY_OPEN_LINK,
+        ACTIVITY_DOCUMENT,
+        ACTIVITY_BASELINE,
+        ACTIVITY_SOURCES,
+        ACTIVITY_SETTINGS,
+        ACTIVITY_DASHBOARD,
+        ACTIVITY_OPEN_FILE,
+        STATUS_STATE_WRITTEN,
+        STATUS_UNVERIFIED,
+        STATUS_CACHED,
+        STATUS_NO_CHANGELOG,
+        STATUS_NO_SPEC,
+        ERROR_SNAPSHOT_MISSING,
+        ERROR_MANIFEST,
+        ERROR_STATE_DUMP,
+        ERROR_NETWORK,
+        ERROR_PARSE,
+        ERROR_PERMISSION,
+        ERROR_IO,
+        ERROR_OPEN_LINK,
+        ERROR_SCHEMA,
+        ERROR_INTEGRITY,
+        TERMINAL_TOO_SMALL,
+    ];
+}
+
+/// The locales with a catalog.
+pub fn locales() -> impl Iterator<Item = &'static str> {
+    CATALOGS.iter().map(|(locale, _)| *locale)
+}
+
+/// Why a catalog couldn't be read.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CatalogError {
+    /// 1-based.
+    pub line: usize,
+    pub message: String,
+}
+
+impl fmt::Display for CatalogError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "line {}: {}", self.line, self.message)
+    }
+}
+
+impl std::error::Error for CatalogError {}
+
+/// A top-level entry of a catalog.
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum Entry {
+    Text(String),
+    Table(HashMap<String, String>),
+}
+
+/// Read a catalog into text by key. Keys in a `[table]` are prefixed with its
+/// name, e.g. `quit = "quit"` under `[hint]` is `hint.quit`.
+pub fn parse_catalog(text: &str) -> Result<BTreeMap<String, String>, CatalogError> {
+    let catalog: HashMap<String, Entry> = toml::from_str(text).map_err(|err| CatalogError {
+        line: err
+            .span()
+            .map_or(1, |span| text[..span.start].matches('\n').count() + 1),
+        message: err.message().to_string(),
+    })?;
+    let mut entries = BTreeMap::new();
+    for (name, entry) in catalog {
+        match entry {
+            Entry::Text(text) => {
+                entries.insert(name, text);
+            }
+            Entry::Table(table) => {
+                entries.extend(
+                    table
+                        .into_iter()
+                        .map(|(key, text)| (format!("{name}.{key}"), text)),
+                );
+            }
+        }
+    }
+    Ok(entries)
+}
+
+/// The UI strings of one locale.
+#[derive(Debug)]
+pub struct Strings {
+    locale: String,
+    entries: BTreeMap<String, String>,
+    /// The English catalog.
+    fallback: BTreeMap<String, String>,
+    /// Keys already reported to `on_missing`.
+    missing: Mutex<BTreeSet<String>>,
+    on_missing: Option<fn(&str, &str)>,
+}
+
+impl Default for Strings {
+    fn default() -> Self {
+        Self::new(DEFAULT_LOCALE)
+    }
+}
+
+impl Strings {
+    /// The embedded catalog of `locale`. A locale without one gets English.
+    pub fn new(locale: &str) -> Self {
+        let catalog = |locale: &str| {
+            CATALOGS
+                .iter()
+                .find(|(name, _)| *name == locale)
+                .map(|(_, text)| parse_catalog(text).expect("embedded catalogs are valid"))
+        };
+        let fallback = catalog(DEFAULT_LOCALE).unwrap_or_default();
+        Self {
+            locale: locale.to_string(),
+            entries: catalog(locale).unwrap_or_else(|| fallback.clone()),
+            fallback,
+            missing: Mutex::default(),
+            on_missing: None,
+        }
+    }
+
+    /// Strings from catalog text, for catalogs that aren't embedded.
+    pub fn from_catalogs(
+        locale: &str,
+        catalog: &str,
+        fallback: &str,
+    ) -> Result<Self, CatalogError> {
+        Ok(Self {
+            locale: locale.to_string(),
+            entries: parse_catalog(catalog)?,
+            fallback: parse_catalog(fallback)?,
+            missing: Mutex::default(),
+            on_missing: None,
+        })
+    }
+
+    /// Call `hook` with the locale and key the first time each key is missing.
+    #[must_use]
+    pub fn with_missing_hook(mut self, hook: fn(&str, &str)) -> Self {
+        self.on_missing = Some(hook);
+        self
+    }
+
+    pub fn locale(&self) -> &str {
+        &self.locale
+    }
+
+    /// The text of `key`, else its English text, else the key itself.
+    pub fn get<'a>(&'a self, key: &'a str) -> &'a str {
+        if let Some(text) = self.entries.get(key) {
+            return text;
+        }
+        let first = self
+            .missing
+            .lock()
+            .is_ok_and(|mut missing| missing.insert(key.to_string()));
+        if first && let Some(hook) = self.on_missing {
+            hook(&self.locale, key);
+        }
+        self.fallback.get(key).map_or(key, String::as_str)
+    }
+
+    /// The text of `key` with `{0}`, `{1}`, ... replaced by `args`.
+    /// Placeholders without an argument are left as they are.
+    pub fn format(&self, key: &str, args: &[&dyn fmt::Display]) -> String {
+        let mut text = self.get(key);
+        let mut formatted = String::with_capacity(text.len());
+        while let Some(start) = text.find('{') {
+            formatted.push_str(&text[..start]);
+            let rest = &text[start..];
+            let arg = rest
+                .find('}')
+                .and_then(|end| Some((end, args.get(rest[1..end].parse::<usize>().ok()?)?)));
+            match arg {
+                Some((end, arg)) => {
+                    formatted.push_str(&arg.to_string());
+                    text = &rest[end + 1..];
+                }
+                None => {
+                    formatted.push('{');
+                    text = &rest[1..];
+                }
+            }
+        }
+        formatted.push_str(text);
+        formatted
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    use super::*;
+
+    #[test]
+    fn test_parse_catalog() {
+        let catalog = parse_catalog(
+            "# Comment\ntop = \"Top\"\n\n[quit]\nconfirm = \"Quit \\\"now\\\"?\" # trailing\nok=\"Ok\"\n",
+        )
+        .unwrap();
+        assert_eq!(catalog["top"], "Top");
+        assert_eq!(catalog["quit.confirm"], "Quit \"now\"?");
+        assert_eq!(catalog["quit.ok"], "Ok");
+
+        let error = parse_catalog("[quit]\nok = Ok\n").unwrap_err();
+        assert_eq!(error.line, 2);
+        let error = parse_catalog("a = \"1\"\na = \"2\"").unwrap_err();
+        assert_eq!(error.line, 2);
+        assert!(parse_catalog("[quit\n").is_err());
+        // Only strings.
+        assert!(parse_catalog("[quit]\nok = 1\n").is_err());
+        assert!(parse_catalog("[quit.dialog]\nok = \"Ok\"\n").is_err());
+    }
+
+    #[test]
+    fn test_format() {
+        let strings = Strings::default();
+        assert_eq!(
+            strings.format(keys::SETUP_TITLE, &[&1, &3, &"Sources?"]),
+            "Setup 1/3: Sources?"
+        );
+        // Missing arguments leave the placeholder.
+        assert_eq!(
+            strings.format(keys::ERROR_PARSE, &[]),
+            "{0} isn't in the expected format."
+        );
+    }
+
+    static MISSES: AtomicUsize = AtomicUsize::new(0);
+
+    #[test]
+    fn test_fallback() {
+        let strings = Strings::from_catalogs(
+            "fr",
+            "[quit]\nok = \"D'accord\"\n",
+            "[quit]\nok = \"Ok\"\ncancel = \"Cancel\"\n",
+        )
+        .unwrap()
+        .with_missing_hook(|locale, key| {
+            assert_eq!((locale, key), ("fr", "quit.cancel"));
+            MISSES.fetch_add(1, Ordering::SeqCst);
+        });
+        assert_eq!(strings.get(keys::QUIT_OK), "D'accord");
+        assert_eq!(strings.get(keys::QUIT_CANCEL), "Cancel");
+        assert_eq!(strings.get(keys::QUIT_CANCEL), "Cancel");
+        assert_eq!(MISSES.load(Ordering::SeqCst), 1);
+
+        // Unknown everywhere, and a locale without a catalog.
+        assert_eq!(Strings::default().get("quit.maybe"), "quit.maybe");
+        assert_eq!(Strings::new("xx").get(keys::QUIT_OK), "Ok");
+    }
+
+    #[test]
+    fn test_catalogs_complete() {
+        for (locale, text) in CATALOGS {
+            parse_catalog(text).unwrap_or_else(|err| panic!("{locale}: {err}"));
+        }
+        let english = parse_catalog(CATALOGS[0].1).unwrap();
+        let listed = keys::ALL.iter().copied().collect::<BTreeSet<&str>>();
+        assert_eq!(listed.len(), keys::ALL.len(), "keys are listed twice");
+
+        let unlisted = english
+            .keys()
+            .filter(|key| !listed.contains(key.as_str()))
+            .collect::<Vec<_>>();
+        assert!(unlisted.is_empty(), "not in keys::ALL: {unlisted:?}");
+        let untranslated = listed
+            .iter()
+            .filter(|key| !english.contains_key(**key))
+            .collect::<Vec<_>>();
+        assert!(
+            untranslated.is_empty(),
+            "not in the English catalog: {untranslated:?}"
+        );
+    }
+}
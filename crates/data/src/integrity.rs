@@ -0,0 +1,402 @@
+//! Checking downloaded documents against the hashes published next to them,
+//! and keeping the last good copy of each.
+//!
+//! poro_schema's artifact writer publishes a `manifest.json` listing every
+//! file it wrote with the SHA-256 of its contents. [`CachedLoader`] looks for
+//! that manifest in the document's directory and the one above, and only
+//! caches a download once it matches.
+
+use std::{
+    fmt, fs,
+    future::Future,
+    path::{Path, PathBuf},
+};
+
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+/// Name of the manifest the artifact writer publishes.
+pub const HASHES_FILE: &str = "manifest.json";
+
+/// Where downloaded documents are cached, next to the settings file.
+pub const CACHE_DIR: &str = "cache";
+
+/// Lowercase hex SHA-256 of `text`, as written in the manifest.
+pub fn sha256_hex(text: &str) -> String {
+    Sha256::digest(text.as_bytes())
+        .iter()
+        .map(|byte| format!("{byte:02x}"))
+        .collect()
+}
+
+#[derive(Deserialize, Debug)]
+struct HashManifest {
+    artifacts: Vec<HashedArtifact>,
+}
+
+#[derive(Deserialize, Debug)]
+struct HashedArtifact {
+    /// Relative to the manifest.
+    path: String,
+    /// Missing from manifests written before hashes were added.
+    #[serde(default)]
+    sha256: Option<String>,
+}
+
+/// Manifests that could list `url`, nearest first, each with the path the
+/// document would be listed under.
+fn manifest_candidates(url: &str) -> Vec<(String, String)> {
+    let Some((dir, name)) = url.rsplit_once('/') else {
+        return Vec::new();
+    };
+    let mut candidates = vec![(format!("{dir}/{HASHES_FILE}"), name.to_string())];
+    // Stop at the host, which has no parent directory.
+    if let Some((parent, last)) = dir.rsplit_once('/')
+        && !parent.ends_with('/')
+        && !parent.ends_with(':')
+    {
+        candidates.push((format!("{parent}/{HASHES_FILE}"), format!("{last}/{name}")));
+    }
+    candidates
+}
+
+/// How far a loaded document can be trusted.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Integrity {
+    /// The download matched its published hash.
+    Verified,
+    /// No manifest lists the document, so there was nothing to check against.
+    Unverified,
+    /// The download didn't match, so the last verified copy was used instead.
+    Cached(IntegrityError),
+}
+
+/// A download that doesn't match the hash published for it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct IntegrityError {
+    pub url: String,
+    pub expected: String,
+    pub actual: String,
+}
+
+impl fmt::Display for IntegrityError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} doesn't match its published hash: expected sha256 {}, got {}",
+            self.url, self.expected, self.actual
+        )
+    }
+}
+
+impl std::error::Error for IntegrityError {}
+
+/// Why [`CachedLoader::load`] has no document to return.
+#[derive(Debug)]
+pub enum DocumentError<E> {
+    /// The download itself failed.
+    Fetch(E),
+    /// The download didn't match and nothing was cached to fall back to.
+    Integrity(IntegrityError),
+}
+
+impl<E: fmt::Display> fmt::Display for DocumentError<E> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Fetch(err) => write!(f, "{err}"),
+            Self::Integrity(err) => write!(f, "{err}"),
+        }
+    }
+}
+
+/// A document and how far it can be trusted.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Loaded {
+    pub text: String,
+    pub integrity: Integrity,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+struct CacheEntry {
+    url: String,
+    /// Whether the text matched a published hash when it was cached.
+    verified: bool,
+    text: String,
+}
+
+/// Downloads documents, checks them against their manifest, and caches them
+/// under `dir`, one file per URL.
+#[derive(Debug, Clone)]
+pub struct CachedLoader {
+    pub dir: PathBuf,
+}
+
+impl CachedLoader {
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        Self { dir: dir.into() }
+    }
+
+    /// Download `url` and check it against the nearest manifest listing it.
+    ///
+    /// A verified download replaces the cached copy. One that fails the check
+    /// is never cached; the cached copy is returned instead, if there is one.
+    /// Without a manifest the download is returned as [`Integrity::Unverified`],
+    /// and only cached when it wouldn't replace a verified copy.
+    ///
+    /// `fetch` performs the downloads, so any HTTP client can be used.
+    pub async fn load<F, Fut, E>(&self, url: &str, fetch: F) -> Result<Loaded, DocumentError<E>>
+    where
+        F: Fn(String) -> Fut,
+        Fut: Future<Output = Result<String, E>>,
+    {
+        let text = fetch(url.to_string()).await.map_err(DocumentError::Fetch)?;
+        let Some(expected) = published_hash(url, &fetch).await else {
+            if !self.cached(url).is_some_and(|entry| entry.verified) {
+                self.store(url, &text, false);
+            }
+            return Ok(Loaded {
+                text,
+                integrity: Integrity::Unverified,
+            });
+        };
+
+        let actual = sha256_hex(&text);
+        if actual != expected {
+            let error = IntegrityError {
+                url: url.to_string(),
+                expected,
+                actual,
+            };
+            return match self.cached(url).filter(|entry| entry.verified) {
+                Some(entry) => Ok(Loaded {
+                    text: entry.text,
+                    integrity: Integrity::Cached(error),
+                }),
+                None => Err(DocumentError::Integrity(error)),
+            };
+        }
+        self.store(url, &text, true);
+        Ok(Loaded {
+            text,
+            integrity: Integrity::Verified,
+        })
+    }
+
+    fn entry_path(&self, url: &str) -> PathBuf {
+        self.dir.join(format!("{}.json", &sha256_hex(url)[..16]))
+    }
+
+    fn cached(&self, url: &str) -> Option<CacheEntry> {
+        let json = fs::read_to_string(self.entry_path(url)).ok()?;
+        serde_json::from_str::<CacheEntry>(&json)
+            .ok()
+            .filter(|entry| entry.url == url)
+    }
+
+    /// Best effort: a document that can't be cached is still usable.
+    fn store(&self, url: &str, text: &str, verified: bool) {
+        let entry = CacheEntry {
+            url: url.to_string(),
+            verified,
+            text: text.to_string(),
+        };
+        let Ok(json) = serde_json::to_string(&entry) else {
+            return;
+        };
+        let path = self.entry_path(url);
+        let _ = fs::create_dir_all(&self.dir).and_then(|()| write_atomic(&path, &json));
+    }
+}
+
+/// Write to a temporary sibling and rename it into place, so a crash never
+/// leaves a half-written entry.
+fn write_atomic(path: &Path, contents: &str) -> std::io::Result<()> {
+    let temporary = path.with_extension("json.tmp");
+    fs::write(&temporary, contents)?;
+    fs::rename(&temporary, path)
+}
+
+/// The hash the nearest manifest lists for `url`, if any manifest does.
+async fn published_hash<F, Fut, E>(url: &str, fetch: &F) -> Option<String>
+where
+    F: Fn(String) -> Fut,
+    Fut: Future<Output = Result<String, E>>,
+{
+    for (manifest_url, path) in manifest_candidates(url) {
+        let Ok(json) = fetch(manifest_url).await else {
+            continue;
+        };
+        let Ok(manifest) = serde_json::from_str::<HashManifest>(&json) else {
+            continue;
+        };
+        let listed = manifest
+            .artifacts
+            .into_iter()
+            .find(|artifact| artifact.path.replace('\\', "/") == path);
+        if let Some(hash) = listed.and_then(|artifact| artifact.sha256) {
+            return Some(hash);
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use super::*;
+
+    const URL: &str = "https://example.invalid/poroschema/lcu/openapi.json";
+    const MANIFEST_URL: &str = "https://example.invalid/poroschema/manifest.json";
+
+    fn manifest(path: &str, text: &str) -> String {
+        format!(
+            r#"{{ "artifacts": [{{ "path": "{path}", "template": "{{target}}/openapi.json", "sha256": "{}" }}], "format_version": 1 }}"#,
+            sha256_hex(text)
+        )
+    }
+
+    fn loader(name: &str) -> CachedLoader {
+        let dir =
+            std::env::temp_dir().join(format!("poroshell-cache-{name}-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        CachedLoader::new(dir)
+    }
+
+    async fn load(
+        loader: &CachedLoader,
+        responses: &[(&str, &str)],
+    ) -> Result<Loaded, DocumentError<String>> {
+        let responses = responses
+            .iter()
+            .map(|(url, body)| (url.to_string(), body.to_string()))
+            .collect::<HashMap<String, String>>();
+        let responses = &responses;
+        loader
+            .load(URL, |url| async move {
+                responses.get(&url).cloned().ok_or(format!("404 {url}"))
+            })
+            .await
+    }
+
+    #[test]
+    fn test_hash_format() {
+        // Matches what poro_schema's artifact writer puts in the manifest.
+        assert_eq!(
+            sha256_hex("{}"),
+            "44136fa355b3678a1146ad16f7e8649e94fb4fc21fe77e8310c060f61caaff8a"
+        );
+        assert_eq!(
+            manifest_candidates(URL),
+            [
+                (
+                    "https://example.invalid/poroschema/lcu/manifest.json".to_string(),
+                    "openapi.json".to_string()
+                ),
+                (MANIFEST_URL.to_string(), "lcu/openapi.json".to_string()),
+            ]
+        );
+        assert_eq!(
+            manifest_candidates("https://example.invalid/lcu.json").len(),
+            1
+        );
+    }
+
+    #[tokio::test]
+    async fn test_verified() {
+        let loader = loader("verified");
+        let loaded = load(
+            &loader,
+            &[
+                (URL, "{}"),
+                (MANIFEST_URL, &manifest("lcu/openapi.json", "{}")),
+            ],
+        )
+        .await
+        .unwrap();
+        assert_eq!(
+            loaded,
+            Loaded {
+                text: "{}".to_string(),
+                integrity: Integrity::Verified
+            }
+        );
+        assert!(loader.cached(URL).unwrap().verified);
+        fs::remove_dir_all(&loader.dir).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_mismatch_falls_back() {
+        let loader = loader("mismatch");
+        let good = manifest("lcu/openapi.json", "{}");
+        load(&loader, &[(URL, "{}"), (MANIFEST_URL, &good)])
+            .await
+            .unwrap();
+
+        // A truncated download is refused for the cached copy, which stays.
+        let loaded = load(&loader, &[(URL, "{"), (MANIFEST_URL, &good)])
+            .await
+            .unwrap();
+        assert_eq!(loaded.text, "{}");
+        let Integrity::Cached(error) = loaded.integrity else {
+            panic!("expected the cached copy");
+        };
+        assert_eq!(error.actual, sha256_hex("{"));
+        assert_eq!(loader.cached(URL).unwrap().text, "{}");
+
+        // Without a cached copy the mismatch is an error of its own.
+        fs::remove_dir_all(&loader.dir).unwrap();
+        let error = load(&loader, &[(URL, "{"), (MANIFEST_URL, &good)])
+            .await
+            .unwrap_err();
+        assert!(matches!(error, DocumentError::Integrity(_)));
+        assert!(
+            error
+                .to_string()
+                .contains("doesn't match its published hash")
+        );
+        assert!(loader.cached(URL).is_none());
+    }
+
+    #[tokio::test]
+    async fn test_unverified() {
+        let loader = loader("unverified");
+        let loaded = load(&loader, &[(URL, "{}")]).await.unwrap();
+        assert_eq!(loaded.integrity, Integrity::Unverified);
+        assert!(!loader.cached(URL).unwrap().verified);
+
+        // A manifest that doesn't list the document verifies nothing.
+        let other = manifest("lolclient/openapi.json", "{}");
+        let loaded = load(&loader, &[(URL, "{}"), (MANIFEST_URL, &other)])
+            .await
+            .unwrap();
+        assert_eq!(loaded.integrity, Integrity::Unverified);
+        fs::remove_dir_all(&loader.dir).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_no_cache_poisoning() {
+        let loader = loader("poisoning");
+        let good = manifest("lcu/openapi.json", "{}");
+        load(&loader, &[(URL, "{}"), (MANIFEST_URL, &good)])
+            .await
+            .unwrap();
+
+        load(
+            &loader,
+            &[(URL, "{\"poisoned\": true}"), (MANIFEST_URL, &good)],
+        )
+        .await
+        .unwrap();
+        // Unchecked downloads don't replace a verified copy either.
+        let loaded = load(&loader, &[(URL, "{\"unchecked\": true}")])
+            .await
+            .unwrap();
+        assert_eq!(loaded.integrity, Integrity::Unverified);
+
+        let entry = loader.cached(URL).unwrap();
+        assert!(entry.verified);
+        assert_eq!(entry.text, "{}");
+        fs::remove_dir_all(&loader.dir).unwrap();
+    }
+}
@@ -0,0 +1,236 @@
+//! Pane sizes of the pages, adjustable at runtime and saved in the settings.
+
+use serde::{Deserialize, Serialize};
+
+/// Percentage points the navigation pane grows or shrinks by per keypress.
+pub const RESIZE_STEP: i16 = 5;
+
+/// Narrowest either pane of a split gets, in columns.
+pub const MIN_PANE_WIDTH: u16 = 24;
+
+/// Terminals narrower than this stack the panes instead of splitting them.
+pub const STACK_BELOW_WIDTH: u16 = 2 * MIN_PANE_WIDTH + 24;
+
+/// The navigation share is kept within these bounds whatever the terminal width.
+const MIN_PERCENT: u16 = 10;
+const MAX_PERCENT: u16 = 90;
+
+/// Smallest terminal the pages are drawn in. Below it the whole screen is a
+/// placeholder until the terminal grows back.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MinSize {
+    pub width: u16,
+    pub height: u16,
+}
+
+impl Default for MinSize {
+    fn default() -> Self {
+        Self {
+            width: 60,
+            height: 15,
+        }
+    }
+}
+
+/// Whether a terminal is big enough for the pages.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SizeGuard {
+    Fits,
+    /// Smaller than `need` in either direction; `have` is the current size.
+    TooSmall {
+        need: MinSize,
+        have: MinSize,
+    },
+}
+
+impl MinSize {
+    pub fn check(self, width: u16, height: u16) -> SizeGuard {
+        if width < self.width || height < self.height {
+            SizeGuard::TooSmall {
+                need: self,
+                have: MinSize { width, height },
+            }
+        } else {
+            SizeGuard::Fits
+        }
+    }
+}
+
+/// How a page's panes are arranged for the terminal's width.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Arrangement {
+    /// Navigation on the left, details on the right.
+    Split,
+    /// Navigation above the details, for narrow terminals.
+    Stacked,
+}
+
+impl Arrangement {
+    pub fn for_width(width: u16) -> Self {
+        if width < STACK_BELOW_WIDTH {
+            Self::Stacked
+        } else {
+            Self::Split
+        }
+    }
+}
+
+/// A page split into a navigation pane and a detail pane.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SplitLayout {
+    /// Share of the width given to the navigation pane, in percent.
+    pub navigation_percent: u16,
+}
+
+impl Default for SplitLayout {
+    fn default() -> Self {
+        Self {
+            navigation_percent: 33,
+        }
+    }
+}
+
+impl SplitLayout {
+    /// Grow the navigation pane by `delta` percentage points, or shrink it
+    /// when negative.
+    pub fn resized(self, delta: i16) -> Self {
+        let percent = self.navigation_percent.saturating_add_signed(delta);
+        Self {
+            navigation_percent: percent.clamp(MIN_PERCENT, MAX_PERCENT),
+        }
+    }
+
+    /// The share the navigation pane actually gets in a split `width` wide,
+    /// so resizing from there takes effect on the next keypress instead of
+    /// after several that only move a share the minimums override.
+    pub fn clamped(self, width: u16) -> Self {
+        if Arrangement::for_width(width) == Arrangement::Stacked {
+            return self;
+        }
+        let width = u32::from(width);
+        let min = u32::from(MIN_PANE_WIDTH) * 100;
+        // Rounded up and down so the widths stay at least the minimums.
+        let lowest = u16::try_from(min.div_ceil(width)).unwrap_or(MAX_PERCENT);
+        let highest = u16::try_from((width * 100 - min) / width).unwrap_or(MAX_PERCENT);
+        Self {
+            navigation_percent: self
+                .navigation_percent
+                .clamp(lowest.max(MIN_PERCENT), highest.min(MAX_PERCENT)),
+        }
+    }
+
+    /// Columns for the navigation and detail panes of a split `width` wide.
+    /// Both keep [`MIN_PANE_WIDTH`] while the width allows it.
+    pub fn widths(self, width: u16) -> (u16, u16) {
+        let wanted = u32::from(width) * u32::from(self.navigation_percent) / 100;
+        let navigation = u16::try_from(wanted).unwrap_or(width);
+        let navigation = if width >= 2 * MIN_PANE_WIDTH {
+            navigation.clamp(MIN_PANE_WIDTH, width - MIN_PANE_WIDTH)
+        } else {
+            width / 2
+        };
+        (navigation, width - navigation)
+    }
+}
+
+/// Pane sizes of every page.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[serde(default)]
+pub struct Layouts {
+    pub home: SplitLayout,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_resize_clamped() {
+        let layout = SplitLayout::default();
+        assert_eq!(layout.resized(RESIZE_STEP).navigation_percent, 38);
+        assert_eq!(layout.resized(-RESIZE_STEP).navigation_percent, 28);
+
+        let mut shrunk = layout;
+        for _ in 0..20 {
+            shrunk = shrunk.resized(-RESIZE_STEP);
+        }
+        assert_eq!(shrunk.navigation_percent, MIN_PERCENT);
+        assert_eq!(layout.resized(i16::MAX).navigation_percent, MAX_PERCENT);
+
+        // The minimum width wins over a small share.
+        assert_eq!(shrunk.widths(120), (MIN_PANE_WIDTH, 120 - MIN_PANE_WIDTH));
+        let grown = layout.resized(i16::MAX);
+        assert_eq!(grown.widths(120), (120 - MIN_PANE_WIDTH, MIN_PANE_WIDTH));
+        assert_eq!(layout.widths(150), (49, 101));
+    }
+
+    #[test]
+    fn test_size_guard() {
+        let min = MinSize::default();
+        assert_eq!(min.check(60, 15), SizeGuard::Fits);
+        assert_eq!(min.check(200, 60), SizeGuard::Fits);
+        assert_eq!(
+            min.check(45, 10),
+            SizeGuard::TooSmall {
+                need: min,
+                have: MinSize {
+                    width: 45,
+                    height: 10
+                }
+            }
+        );
+        assert!(matches!(min.check(59, 40), SizeGuard::TooSmall { .. }));
+        assert!(matches!(min.check(100, 14), SizeGuard::TooSmall { .. }));
+        let custom = MinSize {
+            width: 40,
+            height: 8,
+        };
+        assert_eq!(custom.check(45, 10), SizeGuard::Fits);
+    }
+
+    #[test]
+    fn test_clamped_across_resizes() {
+        let mut layout = SplitLayout::default().resized(i16::MAX);
+        let mut seen = Vec::new();
+        for width in [200, 120, 80, 72, 60, 120, 200] {
+            layout = layout.clamped(width);
+            seen.push(layout.navigation_percent);
+            // The share and the drawn widths agree.
+            if Arrangement::for_width(width) == Arrangement::Split {
+                let (navigation, details) = layout.widths(width);
+                assert!(navigation >= MIN_PANE_WIDTH && details >= MIN_PANE_WIDTH);
+                assert_eq!(
+                    u32::from(navigation),
+                    u32::from(width) * u32::from(layout.navigation_percent) / 100
+                );
+            }
+        }
+        // Narrowing pulls the share in; stacked widths leave it alone, and
+        // widening again doesn't push it back out.
+        assert_eq!(seen, [88, 80, 70, 66, 66, 66, 66]);
+
+        // A shrink right after narrowing moves the pane.
+        let narrowed = SplitLayout::default().resized(i16::MAX).clamped(80);
+        let (before, _) = narrowed.widths(80);
+        let (after, _) = narrowed.resized(-RESIZE_STEP).widths(80);
+        assert!(after < before);
+
+        let small = SplitLayout::default().resized(i16::MIN).clamped(100);
+        assert_eq!(small.navigation_percent, 24);
+    }
+
+    #[test]
+    fn test_arrangement() {
+        assert_eq!(Arrangement::for_width(40), Arrangement::Stacked);
+        assert_eq!(
+            Arrangement::for_width(STACK_BELOW_WIDTH - 1),
+            Arrangement::Stacked
+        );
+        assert_eq!(
+            Arrangement::for_width(STACK_BELOW_WIDTH),
+            Arrangement::Split
+        );
+        // Too narrow for both minimums, the split is even.
+        assert_eq!(SplitLayout::default().widths(30), (15, 15));
+    }
+}
@@ -1,67 +1,158 @@
-use std::collections::{BTreeMap, HashMap};
+use std::collections::{BTreeMap, BTreeSet, HashMap};
 
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
+
+use grouping::GroupingConfig;
+
+pub mod clipboard;
+pub mod coalesce;
+pub mod dashboard;
+#[cfg(feature = "demo")]
+pub mod demo;
+pub mod diff;
+pub mod grouping;
+pub mod history;
+pub mod i18n;
+pub mod integrity;
+pub mod layout;
+pub mod selection;
+pub mod libraries;
+pub mod manifest;
+pub mod settings;
+pub mod setup;
+pub mod snapshot;
+pub mod status;
+pub mod window;
+#[cfg(feature = "schema")]
+mod spec;
 
 pub type Plugins = BTreeMap<String, Vec<Plugin>>;
 
+/// Pseudo-group listing every endpoint of a document once.
+pub const ALL_GROUP: &str = "(all)";
+
+/// Prefix of the operation tags poroschema adds for workflow views.
+pub const WORKFLOW_TAG_PREFIX: &str = "Workflow: ";
+
+/// `x-stability` of operations poroschema flags as unfinished.
+pub const EXPERIMENTAL: &str = "experimental";
+
+/// How the navigation pane groups endpoints.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+#[serde(rename_all = "snake_case")]
+pub enum Grouping {
+    /// By plugin and other tags.
+    #[default]
+    Plugin,
+    /// By the workflows in the spec's `Workflow:` tags.
+    Workflow,
+}
+
+impl Grouping {
+    pub fn toggled(self) -> Self {
+        match self {
+            Self::Plugin => Self::Workflow,
+            Self::Workflow => Self::Plugin,
+        }
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct Document {
     plugins: Plugins,
+    workflows: Plugins,
+    /// Aliased groups with the tags aliased into them.
+    members: BTreeMap<String, Vec<String>>,
+    display_names: BTreeMap<String, String>,
     info: openapi::types::Info,
     paths: Vec<String>,
 }
 
 impl Document {
     pub fn new(data: openapi::types::Document) -> Self {
+        Self::new_with_config(data, &GroupingConfig::default())
+    }
+
+    /// [`Document::new`] with custom grouping rules.
+    pub fn new_with_config(data: openapi::types::Document, config: &GroupingConfig) -> Self {
+        let display_names = display_names(
+            data.tags
+                .iter()
+                .flatten()
+                .map(|tag| (&tag.name, &tag.display_name)),
+        );
+        Self::from_paths(data.info, &data.paths, display_names, config)
+    }
+
+    fn from_paths(
+        info: openapi::types::Info,
+        paths: &openapi::types::Paths,
+        display_names: BTreeMap<String, String>,
+        config: &GroupingConfig,
+    ) -> Self {
         let mut plugins = Plugins::new();
+        let mut workflows = Plugins::new();
+        let mut members = BTreeMap::<String, BTreeSet<String>>::new();
 
-        for (path, path_item) in data.paths.iter() {
+        for (path, path_item) in paths.iter() {
             for (method, operation) in path_item {
-                let mut subplugins = Vec::<Plugin>::new();
-                let mut key = String::from("_unknown");
-
                 // Process and group endpoints into the following formats:
-                // "_unknown" - group that should not be possible
                 // "default" - no tags
                 // "builtin" - 'builtin' not associated with an endpoint
                 // "lol-summoner" etc. - 'plugin' associated with an endpoint
                 // "performance", "tracing", etc.
-                if operation.tags.is_empty() {
-                    key = String::from("default");
-                    if plugins.contains_key(&key) {
-                        let _ = plugins
-                            .get_mut(&key)
-                            .map(|p| p.push(Plugin::new(&method, &path, &key, &operation)));
+                let mut tags = Vec::<&str>::new();
+                for tag in operation.tags.iter() {
+                    if let Some(workflow) = tag.strip_prefix(WORKFLOW_TAG_PREFIX) {
+                        workflows
+                            .entry(workflow.to_string())
+                            .or_default()
+                            .push(Plugin::new(method, path, workflow, operation));
                     } else {
-                        subplugins.push(Plugin::new(&method, &path, &key, &operation));
-                        plugins.insert(String::from(key), subplugins);
+                        tags.push(tag);
                     }
-                } else {
-                    for tag in operation.tags.iter() {
-                        if tag == "plugins" {
-                            continue;
-                        } else {
-                            key = tag.clone();
-                        }
-
-                        if plugins.contains_key(&key) {
-                            let _ = plugins
-                                .get_mut(&key)
-                                .map(|p| p.push(Plugin::new(&method, &path, &tag, &operation)));
-                        } else {
-                            subplugins.push(Plugin::new(&method, &path, &tag, &operation));
-                            plugins.insert(key, subplugins.clone());
-                        }
+                }
+                if config.by_path {
+                    tags = vec![config.path_tag(path)];
+                } else if operation.tags.is_empty() {
+                    tags.push(&config.default_group);
+                }
+
+                let mut seen = Vec::<&str>::new();
+                for tag in tags {
+                    let Some(group) = config.group_of(tag) else {
+                        continue;
+                    };
+                    if group != tag {
+                        members
+                            .entry(group.to_string())
+                            .or_default()
+                            .insert(tag.to_string());
+                    }
+                    // Aliased tags can land in the same group twice.
+                    if seen.contains(&group) {
+                        continue;
                     }
+                    seen.push(group);
+                    plugins
+                        .entry(group.to_string())
+                        .or_default()
+                        .push(Plugin::new(method, path, tag, operation));
                 }
             }
         }
 
-        let paths = data.paths.keys().cloned().collect::<Vec<String>>();
+        let paths = paths.keys().cloned().collect::<Vec<String>>();
 
         Self {
             plugins,
-            info: data.info,
+            workflows,
+            members: members
+                .into_iter()
+                .map(|(group, tags)| (group, tags.into_iter().collect()))
+                .collect(),
+            display_names,
+            info,
             paths,
         }
     }
@@ -70,6 +161,20 @@ impl Document {
         self.plugins.clone()
     }
 
+    /// Groups for a [`Grouping`]. Endpoints in no workflow aren't in any workflow group.
+    pub fn groups(&self, grouping: Grouping) -> &Plugins {
+        match grouping {
+            Grouping::Plugin => &self.plugins,
+            Grouping::Workflow => &self.workflows,
+        }
+    }
+
+    /// The tags aliased into a group, e.g. a plugin's legacy name. Empty for
+    /// groups that weren't aliased.
+    pub fn members(&self, group: &str) -> &[String] {
+        self.members.get(group).map_or(&[], Vec::as_slice)
+    }
+
     pub fn info(&self) -> openapi::types::Info {
         self.info.clone()
     }
@@ -77,9 +182,152 @@ impl Document {
     pub fn paths(&self) -> Vec<String> {
         self.paths.clone()
     }
+
+    /// Parse a published spec.
+    pub fn from_json(json: &str) -> serde_json::Result<Self> {
+        Ok(Self::new(serde_json::from_str(json)?))
+    }
+
+    /// Endpoint counts per group and overall. Endpoints with several tags count
+    /// once in each group but once in the total.
+    pub fn stats(&self) -> DocumentStats {
+        DocumentStats {
+            groups: self
+                .plugins
+                .iter()
+                .map(|(name, plugins)| (name.clone(), plugins.len()))
+                .collect(),
+            workflows: self
+                .workflows
+                .iter()
+                .map(|(name, plugins)| (name.clone(), plugins.len()))
+                .collect(),
+            members: self.members.clone(),
+            display_names: self.display_names.clone(),
+            experimental: self.experimental_counts(Grouping::Plugin),
+            experimental_workflows: self.experimental_counts(Grouping::Workflow),
+            endpoints: self.group(ALL_GROUP).len(),
+            version: self.info.version.clone(),
+        }
+    }
+
+    /// Experimental endpoints per group, leaving out groups without any.
+    /// [`ALL_GROUP`] is counted under plugin grouping.
+    fn experimental_counts(&self, grouping: Grouping) -> BTreeMap<String, usize> {
+        let all =
+            (grouping == Grouping::Plugin).then(|| (ALL_GROUP.to_string(), self.group(ALL_GROUP)));
+        self.groups(grouping)
+            .iter()
+            .map(|(name, plugins)| (name.clone(), plugins.clone()))
+            .chain(all)
+            .map(|(name, plugins)| {
+                let count = plugins
+                    .iter()
+                    .filter(|plugin| plugin.is_experimental())
+                    .count();
+                (name, count)
+            })
+            .filter(|(_, count)| *count > 0)
+            .collect()
+    }
+
+    /// The endpoints of a group. [`ALL_GROUP`] is every endpoint once, ordered
+    /// by path then method.
+    pub fn group(&self, name: &str) -> Vec<Plugin> {
+        self.group_by(Grouping::Plugin, name)
+    }
+
+    /// [`Document::group`] for any [`Grouping`].
+    pub fn group_by(&self, grouping: Grouping, name: &str) -> Vec<Plugin> {
+        if name != ALL_GROUP {
+            return self.groups(grouping).get(name).cloned().unwrap_or_default();
+        }
+        let mut all = self
+            .plugins
+            .values()
+            .flatten()
+            .map(|plugin| ((plugin.path.clone(), plugin.method.clone()), plugin.clone()))
+            .collect::<BTreeMap<(String, String), Plugin>>()
+            .into_values()
+            .collect::<Vec<Plugin>>();
+        all.sort_by(|a, b| (&a.path, &a.method).cmp(&(&b.path, &b.method)));
+        all
+    }
 }
 
-#[derive(Debug, Clone)]
+/// Sizes of a loaded document, computed once when it loads.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct DocumentStats {
+    /// Group names with their endpoint counts, sorted by name.
+    pub groups: Vec<(String, usize)>,
+    /// Workflow names with their endpoint counts, sorted by name.
+    #[serde(default)]
+    pub workflows: Vec<(String, usize)>,
+    /// See [`Document::members`].
+    #[serde(default)]
+    pub members: BTreeMap<String, Vec<String>>,
+    /// Tag names to the `x-display-name` the spec gives them.
+    #[serde(default)]
+    pub display_names: BTreeMap<String, String>,
+    /// Experimental endpoint counts of the groups with any, see [`DocumentStats::experimental_in`].
+    #[serde(default)]
+    pub experimental: BTreeMap<String, usize>,
+    #[serde(default)]
+    pub experimental_workflows: BTreeMap<String, usize>,
+    pub endpoints: usize,
+    pub version: String,
+}
+
+impl DocumentStats {
+    /// Group names and counts for a [`Grouping`].
+    pub fn grouped(&self, grouping: Grouping) -> &[(String, usize)] {
+        match grouping {
+            Grouping::Plugin => &self.groups,
+            Grouping::Workflow => &self.workflows,
+        }
+    }
+
+    /// How many of a group's endpoints are experimental. [`ALL_GROUP`] counts
+    /// every endpoint once.
+    pub fn experimental_in(&self, grouping: Grouping, group: &str) -> usize {
+        let counts = match grouping {
+            Grouping::Workflow if group != ALL_GROUP => &self.experimental_workflows,
+            _ => &self.experimental,
+        };
+        counts.get(group).copied().unwrap_or_default()
+    }
+
+    /// What to show for a group: its tag's display name if the spec has one.
+    /// Workflow groups are named without their tag's prefix.
+    pub fn label<'a>(&'a self, grouping: Grouping, group: &'a str) -> &'a str {
+        let tag = match grouping {
+            Grouping::Plugin => group.to_string(),
+            Grouping::Workflow => format!("{WORKFLOW_TAG_PREFIX}{group}"),
+        };
+        self.display_names.get(&tag).map_or(group, String::as_str)
+    }
+
+    /// The group on navigation row `index`, where row 0 is [`ALL_GROUP`].
+    pub fn group_at(&self, grouping: Grouping, index: usize) -> Option<&str> {
+        match index {
+            0 => Some(ALL_GROUP),
+            _ => self
+                .grouped(grouping)
+                .get(index - 1)
+                .map(|(name, _)| name.as_str()),
+        }
+    }
+}
+
+/// Tag names to their display names, for tags that have one.
+fn display_names<'a>(
+    tags: impl Iterator<Item = (&'a String, &'a Option<String>)>,
+) -> BTreeMap<String, String> {
+    tags.filter_map(|(name, display_name)| Some((name.clone(), display_name.clone()?)))
+        .collect()
+}
+
+#[derive(Debug, Clone, PartialEq)]
 pub struct Plugin {
     method: String,
     path: String,
@@ -112,9 +360,14 @@ impl Plugin {
     pub fn operation(&self) -> openapi::types::Operation {
         self.operation.clone()
     }
+
+    /// Whether the spec flags it as unfinished.
+    pub fn is_experimental(&self) -> bool {
+        self.operation.stability.as_deref() == Some(EXPERIMENTAL)
+    }
 }
 
-#[derive(Deserialize, Debug, Clone)]
+#[derive(Deserialize, Debug, Clone, PartialEq)]
 pub struct RiotAPILibrary {
     pub owner: String,
     pub repo: String,
@@ -130,4 +383,217 @@ mod tests {
 
     #[test]
     fn it_works() {}
+
+    fn document() -> Document {
+        Document::from_json(&document_json()).unwrap()
+    }
+
+    fn document_json() -> String {
+        let operation =
+            |id: &str, tags: &str| format!(r#"{{ "operationId": "{id}", "tags": {tags} }}"#);
+        format!(
+            r#"{{
+                "openapi": "3.0.0",
+                "info": {{ "title": "LCU", "description": "", "version": "14.3.556.1234" }},
+                "paths": {{
+                    "/lol-lobby/v2/lobby": {{ "post": {post}, "get": {get} }},
+                    "/Help": {{ "post": {help} }},
+                    "/lol-summoner/v1/current-summoner": {{ "get": {summoner} }}
+                }},
+                "components": {{ "schemas": {{}} }},
+                "tags": [
+                    {{ "name": "builtin" }},
+                    {{ "name": "lol-lobby", "x-display-name": "Lobby (lol-lobby)" }},
+                    {{ "name": "Workflow: Lobby", "x-display-name": "Lobby & Matchmaking" }}
+                ]
+            }}"#,
+            post = operation(
+                "PostLolLobbyV2Lobby",
+                r#"["plugins", "lol-lobby", "Workflow: Lobby"]"#
+            ),
+            get = operation("GetLolLobbyV2Lobby", r#"["plugins", "lol-lobby", "lobby"]"#),
+            help = operation("Help", r#"["builtin"]"#),
+            summoner = operation(
+                "GetLolSummonerV1CurrentSummoner",
+                r#"["plugins", "lol-summoner"]"#
+            ),
+        )
+    }
+
+    fn document_with(config: &GroupingConfig) -> Document {
+        Document::new_with_config(serde_json::from_str(&document_json()).unwrap(), config)
+    }
+
+    #[test]
+    fn test_stats() {
+        let stats = document().stats();
+
+        assert_eq!(
+            stats.groups,
+            [
+                ("builtin".to_string(), 1),
+                ("lobby".to_string(), 1),
+                ("lol-lobby".to_string(), 2),
+                ("lol-summoner".to_string(), 1),
+            ]
+        );
+        // GetLolLobbyV2Lobby is in two groups but is one endpoint.
+        assert_eq!(stats.endpoints, 4);
+        assert_eq!(stats.version, "14.3.556.1234");
+        assert_eq!(stats.group_at(Grouping::Plugin, 0), Some(ALL_GROUP));
+        assert_eq!(stats.group_at(Grouping::Plugin, 3), Some("lol-lobby"));
+        assert_eq!(stats.group_at(Grouping::Plugin, 5), None);
+
+        assert_eq!(
+            stats.label(Grouping::Plugin, "lol-lobby"),
+            "Lobby (lol-lobby)"
+        );
+        assert_eq!(stats.label(Grouping::Plugin, "builtin"), "builtin");
+        assert_eq!(
+            stats.label(Grouping::Workflow, "Lobby"),
+            "Lobby & Matchmaking"
+        );
+        assert_eq!(stats.label(Grouping::Plugin, "Lobby"), "Lobby");
+    }
+
+    #[test]
+    fn test_experimental_counts() {
+        let json = document_json().replace(
+            r#""operationId": "PostLolLobbyV2Lobby""#,
+            r#""operationId": "PostLolLobbyV2Lobby", "x-stability": "experimental""#,
+        );
+        let document = Document::from_json(&json).unwrap();
+        let lobby = document.group("lol-lobby");
+        assert_eq!(
+            lobby
+                .iter()
+                .map(Plugin::is_experimental)
+                .collect::<Vec<bool>>(),
+            [true, false]
+        );
+
+        let stats = document.stats();
+        assert_eq!(stats.experimental_in(Grouping::Plugin, "lol-lobby"), 1);
+        assert_eq!(stats.experimental_in(Grouping::Plugin, "lobby"), 0);
+        assert_eq!(stats.experimental_in(Grouping::Plugin, ALL_GROUP), 1);
+        assert_eq!(stats.experimental_in(Grouping::Workflow, "Lobby"), 1);
+        assert_eq!(stats.experimental_in(Grouping::Workflow, ALL_GROUP), 1);
+        let stable = Document::from_json(&document_json()).unwrap().stats();
+        assert_eq!(stable.experimental_in(Grouping::Plugin, ALL_GROUP), 0);
+    }
+
+    #[test]
+    fn test_workflow_grouping() {
+        let document = document();
+        let stats = document.stats();
+
+        // Workflow tags aren't plugin groups.
+        assert!(
+            stats
+                .groups
+                .iter()
+                .all(|(name, _)| !name.starts_with("Workflow"))
+        );
+        assert_eq!(stats.workflows, [("Lobby".to_string(), 1)]);
+        let grouping = Grouping::default().toggled();
+        assert_eq!(grouping, Grouping::Workflow);
+        assert_eq!(stats.group_at(grouping, 1), Some("Lobby"));
+        assert_eq!(stats.group_at(grouping, 2), None);
+
+        let lobby = document.group_by(grouping, "Lobby");
+        assert_eq!(lobby.len(), 1);
+        assert_eq!(lobby[0].tag(), "Lobby");
+        assert_eq!(document.group_by(grouping, ALL_GROUP).len(), 4);
+        assert_eq!(grouping.toggled(), Grouping::Plugin);
+    }
+
+    #[test]
+    fn test_all_group() {
+        let document = document();
+        let all = document
+            .group(ALL_GROUP)
+            .into_iter()
+            .map(|plugin| format!("{} {}", plugin.method(), plugin.path()))
+            .collect::<Vec<String>>();
+
+        assert_eq!(
+            all,
+            [
+                "post /Help",
+                "get /lol-lobby/v2/lobby",
+                "post /lol-lobby/v2/lobby",
+                "get /lol-summoner/v1/current-summoner",
+            ]
+        );
+        assert_eq!(document.group("lol-lobby").len(), 2);
+        assert!(document.group("missing").is_empty());
+    }
+
+    #[test]
+    fn test_grouping_default_unchanged() {
+        let configured = document_with(&GroupingConfig::default());
+        assert_eq!(configured.stats(), document().stats());
+        assert!(configured.members("lol-lobby").is_empty());
+    }
+
+    #[test]
+    fn test_grouping_aliases() {
+        let config = GroupingConfig {
+            aliases: BTreeMap::from([("lobby".to_string(), "lol-lobby".to_string())]),
+            ..Default::default()
+        };
+        let document = document_with(&config);
+        let stats = document.stats();
+
+        // GetLolLobbyV2Lobby has both tags but is in the group once.
+        assert_eq!(
+            stats.groups,
+            [
+                ("builtin".to_string(), 1),
+                ("lol-lobby".to_string(), 2),
+                ("lol-summoner".to_string(), 1),
+            ]
+        );
+        assert_eq!(document.members("lol-lobby"), ["lobby"]);
+        assert_eq!(stats.members["lol-lobby"], ["lobby"]);
+    }
+
+    #[test]
+    fn test_grouping_skip() {
+        let config = GroupingConfig {
+            skip: vec!["plugins".to_string(), "builtin".to_string()],
+            ..Default::default()
+        };
+        let stats = document_with(&config).stats();
+        assert!(stats.groups.iter().all(|(name, _)| name != "builtin"));
+        // Skipping "plugins" is the default; without it every plugin endpoint
+        // lands in one big group.
+        let config = GroupingConfig {
+            skip: Vec::new(),
+            ..Default::default()
+        };
+        let stats = document_with(&config).stats();
+        assert!(stats.groups.contains(&("plugins".to_string(), 3)));
+    }
+
+    #[test]
+    fn test_grouping_by_path() {
+        let config = GroupingConfig {
+            by_path: true,
+            ..Default::default()
+        };
+        let document = document_with(&config);
+        let stats = document.stats();
+        assert_eq!(
+            stats.groups,
+            [
+                ("Help".to_string(), 1),
+                ("lol-lobby".to_string(), 2),
+                ("lol-summoner".to_string(), 1),
+            ]
+        );
+        // Workflows still come from tags.
+        assert_eq!(stats.workflows, [("Lobby".to_string(), 1)]);
+        assert_eq!(config.path_tag("/"), "default");
+    }
 }
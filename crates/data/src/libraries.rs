@@ -0,0 +1,177 @@
+use std::{fmt, future::Future};
+
+use crate::RiotAPILibrary;
+
+/// Mirrors of poroschema's `libraries.json`, tried in order.
+pub const DEFAULT_MIRRORS: [&str; 2] = [
+    "https://raw.githubusercontent.com/BlossomiShymae/poroschema/refs/heads/main/other/libraries.json",
+    "https://cdn.jsdelivr.net/gh/BlossomiShymae/poroschema@main/other/libraries.json",
+];
+
+/// Snapshot of the library list taken at build time, used when no mirror works.
+pub const EMBEDDED: &str = include_str!("../assets/libraries.json");
+
+/// Why a mirror was passed over.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LoadError {
+    /// The request itself failed.
+    Network { url: String, error: String },
+    /// The response arrived but isn't a list of libraries.
+    Format { url: String, error: String },
+}
+
+impl fmt::Display for LoadError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Network { url, error } => write!(f, "network failed for {url}: {error}"),
+            Self::Format { url, error } => write!(f, "format changed at {url}: {error}"),
+        }
+    }
+}
+
+/// Where the loaded list came from, with the failures of any mirror tried before it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SourceUsed {
+    Mirror {
+        url: String,
+        failures: Vec<LoadError>,
+    },
+    /// Every mirror failed, so the data may be stale.
+    Embedded { failures: Vec<LoadError> },
+}
+
+impl SourceUsed {
+    pub fn is_stale(&self) -> bool {
+        matches!(self, Self::Embedded { .. })
+    }
+
+    pub fn failures(&self) -> &[LoadError] {
+        match self {
+            Self::Mirror { failures, .. } | Self::Embedded { failures } => failures,
+        }
+    }
+}
+
+/// Load the library list from the first mirror that returns a valid one,
+/// falling back to `fallback` (normally [`EMBEDDED`]).
+///
+/// `fetch` performs the download, so any HTTP client can be used.
+pub async fn load<F, Fut, E>(
+    sources: &[String],
+    fallback: &str,
+    mut fetch: F,
+) -> (Vec<RiotAPILibrary>, SourceUsed)
+where
+    F: FnMut(String) -> Fut,
+    Fut: Future<Output = Result<String, E>>,
+    E: fmt::Display,
+{
+    let mut failures = Vec::new();
+    for url in sources {
+        let body = match fetch(url.clone()).await {
+            Ok(body) => body,
+            Err(err) => {
+                failures.push(LoadError::Network {
+                    url: url.clone(),
+                    error: err.to_string(),
+                });
+                continue;
+            }
+        };
+        match serde_json::from_str::<Vec<RiotAPILibrary>>(&body) {
+            Ok(libraries) => {
+                let source = SourceUsed::Mirror {
+                    url: url.clone(),
+                    failures,
+                };
+                return (libraries, source);
+            }
+            Err(err) => failures.push(LoadError::Format {
+                url: url.clone(),
+                error: err.to_string(),
+            }),
+        }
+    }
+
+    // The embedded snapshot is checked by the tests, so a broken one is a build bug.
+    let libraries = serde_json::from_str(fallback).unwrap_or_default();
+    (libraries, SourceUsed::Embedded { failures })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use super::*;
+
+    const VALID: &str = r#"[{ "owner": "o", "repo": "r", "language": "Rust", "tags": ["lcu"] }]"#;
+
+    fn mirrors() -> Vec<String> {
+        vec!["https://a".to_string(), "https://b".to_string()]
+    }
+
+    async fn run(
+        responses: HashMap<&'static str, Result<&'static str, &'static str>>,
+    ) -> (Vec<RiotAPILibrary>, SourceUsed) {
+        load(&mirrors(), EMBEDDED, |url: String| {
+            let response = responses[url.as_str()];
+            async move { response.map(String::from) }
+        })
+        .await
+    }
+
+    #[tokio::test]
+    async fn test_mirror_failover() {
+        let (libraries, source) = run(HashMap::from([
+            ("https://a", Err("connection refused")),
+            ("https://b", Ok(VALID)),
+        ]))
+        .await;
+
+        assert_eq!(libraries.len(), 1);
+        assert_eq!(
+            source,
+            SourceUsed::Mirror {
+                url: "https://b".to_string(),
+                failures: vec![LoadError::Network {
+                    url: "https://a".to_string(),
+                    error: "connection refused".to_string(),
+                }],
+            }
+        );
+        assert!(!source.is_stale());
+    }
+
+    #[tokio::test]
+    async fn test_format_error() {
+        let (libraries, source) = run(HashMap::from([
+            ("https://a", Ok(r#"{ "libraries": [] }"#)),
+            ("https://b", Ok(VALID)),
+        ]))
+        .await;
+
+        assert_eq!(libraries[0].owner, "o");
+        assert!(matches!(
+            &source.failures()[0],
+            LoadError::Format { url, .. } if url == "https://a"
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_embedded_fallback() {
+        let (libraries, source) = run(HashMap::from([
+            ("https://a", Err("timed out")),
+            ("https://b", Ok("<html>blocked</html>")),
+        ]))
+        .await;
+
+        assert!(source.is_stale());
+        assert_eq!(source.failures().len(), 2);
+        assert!(matches!(source.failures()[1], LoadError::Format { .. }));
+        assert!(!libraries.is_empty());
+        assert_eq!(
+            libraries,
+            serde_json::from_str::<Vec<RiotAPILibrary>>(EMBEDDED).unwrap()
+        );
+    }
+}
@@ -0,0 +1,215 @@
+//! Community-hosted specs listed in a sources manifest, offered next to the
+//! built-in [`SchemaSource`]s.
+
+use std::{fmt, future::Future, path::Path};
+
+use serde::{Deserialize, Serialize};
+
+use crate::settings::SchemaSource;
+
+/// The manifest published with poroschema, used unless the settings point elsewhere.
+pub const DEFAULT_MANIFEST_URL: &str = "https://raw.githubusercontent.com/BlossomiShymae/poroschema/refs/heads/main/other/sources.json";
+
+/// One spec listed in a manifest.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct ManifestSource {
+    pub name: String,
+    pub url: String,
+    /// What the document is, e.g. `openapi-3.0`. Only shown; every source is
+    /// read as an OpenAPI document.
+    #[serde(default)]
+    pub format: Option<String>,
+    #[serde(default)]
+    pub description: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq, Eq)]
+pub struct SourcesManifest {
+    pub sources: Vec<ManifestSource>,
+}
+
+/// A source the user can pick: built in, or from the manifest.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SourceChoice {
+    Builtin(SchemaSource),
+    Manifest(ManifestSource),
+    /// The spec built into the binary.
+    #[cfg(feature = "demo")]
+    Demo(crate::demo::DemoSource),
+}
+
+impl SourceChoice {
+    pub fn label(&self) -> &str {
+        match self {
+            Self::Builtin(source) => source.label(),
+            Self::Manifest(source) => &source.name,
+            #[cfg(feature = "demo")]
+            Self::Demo(source) => source.label(),
+        }
+    }
+
+    pub fn url(&self) -> &str {
+        match self {
+            Self::Builtin(source) => source.url(),
+            Self::Manifest(source) => &source.url,
+            #[cfg(feature = "demo")]
+            Self::Demo(_) => crate::demo::DEMO_URL,
+        }
+    }
+}
+
+/// The built-in sources, then the offline demo with the `demo` feature, then
+/// the manifest's. Built-ins win over
+/// manifest entries with the same name (ignoring case) or URL, and the
+/// first of several manifest entries with one name wins.
+pub fn merge(manifest: Option<&SourcesManifest>) -> Vec<SourceChoice> {
+    let mut choices = SchemaSource::ALL.map(SourceChoice::Builtin).to_vec();
+    #[cfg(feature = "demo")]
+    choices.push(SourceChoice::Demo(crate::demo::DemoSource::new()));
+    for source in manifest.into_iter().flat_map(|manifest| &manifest.sources) {
+        let taken = choices.iter().any(|choice| {
+            choice.label().eq_ignore_ascii_case(&source.name) || choice.url() == source.url
+        });
+        if !taken {
+            choices.push(SourceChoice::Manifest(source.clone()));
+        }
+    }
+    choices
+}
+
+/// Why the manifest's sources aren't offered.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ManifestError {
+    /// The manifest couldn't be fetched or read.
+    Unreachable { location: String, error: String },
+    /// It arrived but isn't a sources manifest.
+    Format { location: String, error: String },
+}
+
+impl fmt::Display for ManifestError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Unreachable { location, error } => {
+                write!(f, "couldn't load sources manifest {location}: {error}")
+            }
+            Self::Format { location, error } => {
+                write!(f, "bad sources manifest {location}: {error}")
+            }
+        }
+    }
+}
+
+/// Load the manifest at `location`, a URL or a local file, and merge it with
+/// the built-ins. On failure only the built-ins are returned, with the error.
+///
+/// `fetch` downloads URLs, so any HTTP client can be used.
+pub async fn load<F, Fut, E>(location: &str, fetch: F) -> (Vec<SourceChoice>, Option<ManifestError>)
+where
+    F: FnOnce(String) -> Fut,
+    Fut: Future<Output = Result<String, E>>,
+    E: fmt::Display,
+{
+    let unreachable = |error: String| ManifestError::Unreachable {
+        location: location.to_string(),
+        error,
+    };
+    let body = if location.starts_with("http://") || location.starts_with("https://") {
+        fetch(location.to_string())
+            .await
+            .map_err(|err| unreachable(err.to_string()))
+    } else {
+        std::fs::read_to_string(Path::new(location)).map_err(|err| unreachable(err.to_string()))
+    };
+    let manifest = body.and_then(|body| {
+        serde_json::from_str::<SourcesManifest>(&body).map_err(|err| ManifestError::Format {
+            location: location.to_string(),
+            error: err.to_string(),
+        })
+    });
+    match manifest {
+        Ok(manifest) => (merge(Some(&manifest)), None),
+        Err(err) => (merge(None), Some(err)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use super::*;
+
+    const MANIFEST: &str = r#"{
+        "sources": [
+            { "name": "Annotated LCU", "url": "https://example.invalid/annotated.json", "format": "openapi-3.0" },
+            { "name": "lcu", "url": "https://example.invalid/shadow.json" },
+            { "name": "Mirror", "url": "https://raw.githubusercontent.com/BlossomiShymae/poroschema/refs/heads/main/schemas/lcu.json" },
+            { "name": "Annotated LCU", "url": "https://example.invalid/second.json", "description": "Later copy" }
+        ]
+    }"#;
+
+    async fn run(
+        location: &str,
+        response: Result<&'static str, &'static str>,
+    ) -> (Vec<SourceChoice>, Option<ManifestError>) {
+        load(location, |_| async move { response.map(String::from) }).await
+    }
+
+    #[tokio::test]
+    async fn test_merge_precedence() {
+        let (choices, error) = run(DEFAULT_MANIFEST_URL, Ok(MANIFEST)).await;
+        assert_eq!(error, None);
+        let labels = choices
+            .iter()
+            .map(SourceChoice::label)
+            .collect::<Vec<&str>>();
+        // Entries named or located like a built-in, and the repeated name, are dropped.
+        let builtins = merge(None).len();
+        assert_eq!(labels[..3], ["LCU", "LoL Client", "Riot API"]);
+        assert_eq!(labels[builtins..], ["Annotated LCU"]);
+        assert_eq!(
+            choices[builtins].url(),
+            "https://example.invalid/annotated.json"
+        );
+        assert_eq!(choices[0], SourceChoice::Builtin(SchemaSource::Lcu));
+    }
+
+    #[tokio::test]
+    async fn test_parse_failure() {
+        let (choices, error) = run(DEFAULT_MANIFEST_URL, Ok(r#"{ "sources": {} }"#)).await;
+        assert_eq!(choices, merge(None));
+        assert!(matches!(error, Some(ManifestError::Format { .. })));
+
+        let (choices, error) = run(DEFAULT_MANIFEST_URL, Err("connection refused")).await;
+        assert_eq!(choices, merge(None));
+        assert_eq!(
+            error.unwrap().to_string(),
+            format!("couldn't load sources manifest {DEFAULT_MANIFEST_URL}: connection refused")
+        );
+    }
+
+    #[tokio::test]
+    async fn test_local_file() {
+        let path =
+            std::env::temp_dir().join(format!("poroshell-sources-{}.json", std::process::id()));
+        fs::write(&path, MANIFEST).unwrap();
+        // Local files are read directly, never fetched.
+        let (choices, error) = run(path.to_str().unwrap(), Err("fetched")).await;
+        assert_eq!(error, None);
+        assert_eq!(choices.len(), merge(None).len() + 1);
+        fs::remove_file(&path).unwrap();
+
+        let (choices, error) = run(path.to_str().unwrap(), Err("fetched")).await;
+        assert_eq!(choices, merge(None));
+        assert!(matches!(error, Some(ManifestError::Unreachable { .. })));
+    }
+
+    #[test]
+    fn test_demo_listed() {
+        let demo = merge(None)
+            .into_iter()
+            .find(|choice| choice.label().starts_with("Offline demo"));
+        assert_eq!(demo.is_some(), cfg!(feature = "demo"));
+        #[cfg(feature = "demo")]
+        assert_eq!(demo.unwrap().url(), crate::demo::DEMO_URL);
+    }
+}
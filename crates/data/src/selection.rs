@@ -0,0 +1,171 @@
+//! Keeping the selected row when a list is rebuilt.
+//!
+//! Lists are rebuilt on reloads, filters and grouping toggles. A [`Selection`]
+//! remembers the selected row by key rather than by index, so it can find the
+//! row again afterwards, or the nearest one that is still there.
+
+use std::{collections::HashMap, hash::Hash};
+
+/// Identifies an endpoint across reloads, where its position may change.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct EndpointKey {
+    pub operation_id: String,
+    pub path: String,
+    /// Lower-case HTTP method.
+    pub method: String,
+}
+
+/// The selected row of a list, by key.
+#[derive(Debug, Clone)]
+pub struct Selection<K> {
+    /// Keys of the list as last resolved, in order.
+    keys: Vec<K>,
+    index: usize,
+}
+
+impl<K> Default for Selection<K> {
+    fn default() -> Self {
+        Self {
+            keys: Vec::new(),
+            index: 0,
+        }
+    }
+}
+
+impl<K: PartialEq> Selection<K> {
+    pub fn index(&self) -> usize {
+        self.index
+    }
+
+    pub fn key(&self) -> Option<&K> {
+        self.keys.get(self.index)
+    }
+
+    /// The user moved to `index` of the current list.
+    pub fn select(&mut self, index: usize) {
+        self.index = index.min(self.keys.len().saturating_sub(1));
+    }
+
+    /// Take the rebuilt list and return where the selection is in it: the
+    /// same key if it survived, else the closest surviving neighbor in the
+    /// old order, preferring the one after. With no neighbor left, the old
+    /// position is kept as far as the new list allows.
+    pub fn resolve(&mut self, keys: Vec<K>) -> usize {
+        let position = |key: &K| keys.iter().position(|k| k == key);
+        let old = self.index;
+        let survivor = (0..self.keys.len())
+            .flat_map(|distance| [old.checked_add(distance), old.checked_sub(distance)])
+            .flatten()
+            .filter_map(|i| self.keys.get(i))
+            .find_map(position);
+        self.index = survivor.unwrap_or_else(|| old.min(keys.len().saturating_sub(1)));
+        self.keys = keys;
+        self.index
+    }
+}
+
+/// A [`Selection`] per view of the same pane, like each grouping of the
+/// navigation pane, so switching views and back returns to the same row.
+#[derive(Debug, Clone)]
+pub struct Selections<C, K> {
+    views: HashMap<C, Selection<K>>,
+}
+
+impl<C, K> Default for Selections<C, K> {
+    fn default() -> Self {
+        Self {
+            views: HashMap::new(),
+        }
+    }
+}
+
+impl<C: Eq + Hash, K> Selections<C, K> {
+    pub fn view(&mut self, view: C) -> &mut Selection<K> {
+        self.views.entry(view).or_default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn keys(names: &[&str]) -> Vec<String> {
+        names.iter().map(ToString::to_string).collect()
+    }
+
+    #[test]
+    fn test_reordered() {
+        let mut selection = Selection::default();
+        selection.resolve(keys(&["a", "b", "c", "d"]));
+        selection.select(2);
+        assert_eq!(selection.key().map(String::as_str), Some("c"));
+
+        assert_eq!(selection.resolve(keys(&["d", "c", "b", "a"])), 1);
+        assert_eq!(selection.resolve(keys(&["x", "y", "c"])), 2);
+    }
+
+    #[test]
+    fn test_removed() {
+        let mut selection = Selection::default();
+        selection.resolve(keys(&["a", "b", "c", "d", "e"]));
+        selection.select(2);
+
+        // The next row survived, so it takes over.
+        assert_eq!(selection.resolve(keys(&["a", "b", "d", "e"])), 2);
+        assert_eq!(selection.key().map(String::as_str), Some("d"));
+
+        // Then the one before, when nothing after is left.
+        selection.resolve(keys(&["a", "b", "c", "d"]));
+        selection.select(3);
+        assert_eq!(selection.resolve(keys(&["a", "b"])), 1);
+
+        // Nothing in common: the old position, clamped.
+        selection.select(1);
+        assert_eq!(selection.resolve(keys(&["x"])), 0);
+        assert_eq!(selection.resolve(Vec::new()), 0);
+        assert_eq!(selection.key(), None);
+    }
+
+    #[test]
+    fn test_views_independent() {
+        let mut selections = Selections::<bool, String>::default();
+        selections
+            .view(false)
+            .resolve(keys(&["lol-lobby", "lol-summoner"]));
+        selections.view(false).select(1);
+        selections
+            .view(true)
+            .resolve(keys(&["Lobby", "Champ select"]));
+        assert_eq!(selections.view(true).index(), 0);
+
+        // Back to the first view after a reload that added a group ahead.
+        let index =
+            selections
+                .view(false)
+                .resolve(keys(&["lol-chat", "lol-lobby", "lol-summoner"]));
+        assert_eq!(index, 2);
+    }
+
+    #[test]
+    fn test_endpoint_keys() {
+        let key = |id: &str, path: &str, method: &str| EndpointKey {
+            operation_id: id.to_string(),
+            path: path.to_string(),
+            method: method.to_string(),
+        };
+        let get = key("GetLolLobbyV2Lobby", "/lol-lobby/v2/lobby", "get");
+        let post = key("PostLolLobbyV2Lobby", "/lol-lobby/v2/lobby", "post");
+        let delete = key("DeleteLolLobbyV2Lobby", "/lol-lobby/v2/lobby", "delete");
+
+        let mut selection = Selection::default();
+        selection.resolve(vec![get.clone(), post.clone(), delete.clone()]);
+        selection.select(1);
+        // A filter hides the selection, so its neighbor takes over.
+        assert_eq!(selection.resolve(vec![get.clone(), delete.clone()]), 1);
+        assert_eq!(selection.key(), Some(&delete));
+        // Clearing the filter keeps the row chosen since.
+        selection.select(0);
+        assert_eq!(selection.resolve(vec![get.clone(), post, delete]), 0);
+        assert_eq!(selection.key(), Some(&get));
+    }
+}
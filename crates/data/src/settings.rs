@@ -0,0 +1,572 @@
+//! Settings, and writing them safely while other instances may be too.
+//!
+//! A TUI and a CLI run can share one settings file. Each save takes a lock
+//! file, re-reads the settings, merges in what another instance changed
+//! since this one last read them, and replaces the file by renaming a
+//! finished copy over it, so a reader never sees half a file.
+
+use std::{
+    ffi::OsString,
+    fs,
+    io::{self, Write},
+    path::{Path, PathBuf},
+    thread,
+    time::{Duration, Instant},
+};
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::{
+    i18n::DEFAULT_LOCALE,
+    integrity::sha256_hex,
+    layout::{Layouts, MinSize},
+    libraries::DEFAULT_MIRRORS,
+    manifest::DEFAULT_MANIFEST_URL,
+};
+
+/// Settings file, relative to the working directory like `logs/`.
+pub const SETTINGS_FILE: &str = "settings.json";
+
+/// A lock file this old was left by an instance that stopped mid-save.
+pub const STALE_LOCK: Duration = Duration::from_secs(10);
+
+/// How long a save waits for another instance to finish its own. Longer than
+/// [`STALE_LOCK`], so a lock left by a crash goes stale before the wait ends.
+const LOCK_TIMEOUT: Duration = Duration::from_secs(15);
+
+const _: () = assert!(LOCK_TIMEOUT.as_millis() > STALE_LOCK.as_millis());
+
+const LOCK_RETRY: Duration = Duration::from_millis(20);
+
+/// A published poroschema spec that can be browsed.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[serde(rename_all = "snake_case")]
+pub enum SchemaSource {
+    Lcu,
+    LolClient,
+    RiotApi,
+}
+
+impl SchemaSource {
+    pub const ALL: [SchemaSource; 3] = [Self::Lcu, Self::LolClient, Self::RiotApi];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::Lcu => "LCU",
+            Self::LolClient => "LoL Client",
+            Self::RiotApi => "Riot API",
+        }
+    }
+
+    pub fn url(&self) -> &'static str {
+        match self {
+            Self::Lcu => {
+                "https://raw.githubusercontent.com/BlossomiShymae/poroschema/refs/heads/main/schemas/lcu.json"
+            }
+            Self::LolClient => {
+                "https://raw.githubusercontent.com/BlossomiShymae/poroschema/refs/heads/main/schemas/lolclient.json"
+            }
+            Self::RiotApi => {
+                "https://raw.githubusercontent.com/BlossomiShymae/poroschema/refs/heads/main/schemas/riotapi.json"
+            }
+        }
+    }
+
+    /// The source published at `url`, if it is one of ours.
+    pub fn from_url(url: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|source| source.url() == url)
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct Settings {
+    /// Specs to load on startup.
+    pub sources: Vec<SchemaSource>,
+    /// Whether features that talk to a running League client are enabled.
+    pub live_lcu: bool,
+    /// Where generated specs and reports are written.
+    pub artifact_dir: PathBuf,
+    /// URLs of `libraries.json`, tried in order.
+    #[serde(default = "default_library_mirrors")]
+    pub library_mirrors: Vec<String>,
+    /// How long a finished network load is reused for repeats of the same request.
+    #[serde(default = "default_result_ttl_secs")]
+    pub result_ttl_secs: u64,
+    /// Capture the mouse for clicking and scrolling. Off leaves the terminal's
+    /// own text selection working.
+    #[serde(default = "default_mouse")]
+    pub mouse: bool,
+    /// URL or file path of a sources manifest listing more specs to browse.
+    #[serde(default = "default_sources_manifest")]
+    pub sources_manifest: String,
+    /// Pane sizes as last adjusted.
+    #[serde(default)]
+    pub layouts: Layouts,
+    /// Below this size the terminal shows a placeholder instead of the pages.
+    #[serde(default)]
+    pub min_terminal_size: MinSize,
+    /// Language of the interface, one of [`crate::i18n::locales`].
+    #[serde(default = "default_locale")]
+    pub locale: String,
+    /// URL of a spec to mark changes against while browsing, see [`crate::diff`].
+    #[serde(default)]
+    pub baseline: Option<String>,
+}
+
+fn default_mouse() -> bool {
+    true
+}
+
+fn default_result_ttl_secs() -> u64 {
+    30
+}
+
+fn default_sources_manifest() -> String {
+    DEFAULT_MANIFEST_URL.to_string()
+}
+
+fn default_locale() -> String {
+    DEFAULT_LOCALE.to_string()
+}
+
+fn default_library_mirrors() -> Vec<String> {
+    DEFAULT_MIRRORS.iter().map(|url| url.to_string()).collect()
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Self {
+            sources: vec![SchemaSource::Lcu],
+            live_lcu: false,
+            artifact_dir: PathBuf::from("artifacts"),
+            library_mirrors: default_library_mirrors(),
+            result_ttl_secs: default_result_ttl_secs(),
+            mouse: default_mouse(),
+            sources_manifest: default_sources_manifest(),
+            layouts: Layouts::default(),
+            min_terminal_size: MinSize::default(),
+            locale: default_locale(),
+            baseline: None,
+        }
+    }
+}
+
+impl Settings {
+    /// Load settings from a file. A missing file yields `None`, which means
+    /// this is the first run.
+    pub fn load(path: &Path) -> io::Result<Option<Self>> {
+        SettingsFile::new(path).load()
+    }
+}
+
+/// The settings file as one instance sees it. Remembers what it last read or
+/// wrote, so a save only overwrites the settings this instance changed.
+#[derive(Debug, Clone)]
+pub struct SettingsFile {
+    path: PathBuf,
+    /// Hash of the file as last read or written.
+    seen: Option<String>,
+    /// The settings as last read or written, to merge against.
+    base: Value,
+    recovered: bool,
+}
+
+impl SettingsFile {
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self {
+            path: path.into(),
+            seen: None,
+            base: Value::Null,
+            recovered: false,
+        }
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Where the copy replaced by the last save is kept.
+    pub fn backup_path(&self) -> PathBuf {
+        sibling(&self.path, ".bak")
+    }
+
+    /// Whether the last load fell back to the backup because the file
+    /// couldn't be read.
+    pub fn recovered(&self) -> bool {
+        self.recovered
+    }
+
+    /// Read the settings. A missing file yields `None`, which means this is
+    /// the first run. An unreadable one yields the backup, if there is one.
+    ///
+    /// No lock is needed, as saves only ever rename a whole file into place.
+    pub fn load(&mut self) -> io::Result<Option<Settings>> {
+        let Some(text) = read_to_string(&self.path)? else {
+            return Ok(None);
+        };
+        self.recovered = false;
+        let (text, settings) = match serde_json::from_str::<Settings>(&text) {
+            Ok(settings) => (text, settings),
+            Err(err) => {
+                // Cut short by a crash, or broken by hand.
+                let backup = read_to_string(&self.backup_path())?.ok_or(err)?;
+                let settings = serde_json::from_str(&backup)?;
+                self.recovered = true;
+                (backup, settings)
+            }
+        };
+        self.remember(&text, &settings)?;
+        Ok(Some(settings))
+    }
+
+    /// Write `settings`, keeping what other instances changed since the last
+    /// load or save. Returns the settings as written.
+    ///
+    /// Only settings that differ from the last read are taken from
+    /// `settings`. Lists are merged as sets, so entries another instance
+    /// added survive, and anything else is last writer wins. Without a last
+    /// load or save there is nothing to merge against, so the file is
+    /// overwritten.
+    pub fn save(&mut self, settings: &Settings) -> io::Result<Settings> {
+        if let Some(parent) = self.path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let _lock = SettingsLock::acquire(&self.path, STALE_LOCK, LOCK_TIMEOUT)?;
+
+        let current = read_to_string(&self.path)?;
+        let mut merged = settings.clone();
+        if let Some(text) = &current
+            && let Some(seen) = &self.seen
+            && *seen != sha256_hex(text)
+            // Whatever is there that isn't JSON is replaced whole.
+            && let Ok(theirs) = serde_json::from_str::<Value>(text)
+        {
+            let mine = serde_json::to_value(settings)?;
+            merged = serde_json::from_value(merge(&self.base, &mine, &theirs))?;
+        }
+
+        if let Some(text) = current.filter(|text| serde_json::from_str::<Settings>(text).is_ok()) {
+            fs::write(self.backup_path(), text)?;
+        }
+        let text = serde_json::to_string_pretty(&merged)?;
+        write_atomic(&self.path, &text)?;
+        self.remember(&text, &merged)?;
+        Ok(merged)
+    }
+
+    fn remember(&mut self, text: &str, settings: &Settings) -> io::Result<()> {
+        self.seen = Some(sha256_hex(text));
+        self.base = serde_json::to_value(settings)?;
+        Ok(())
+    }
+}
+
+/// Three-way merge of JSON settings: `mine` and `theirs` both started from
+/// `base`.
+fn merge(base: &Value, mine: &Value, theirs: &Value) -> Value {
+    if mine == base {
+        return theirs.clone();
+    }
+    if theirs == base {
+        return mine.clone();
+    }
+    match (mine, theirs) {
+        (Value::Object(mine), Value::Object(theirs)) => {
+            let mut merged = theirs.clone();
+            for (key, value) in mine {
+                let merged_value = match theirs.get(key) {
+                    Some(theirs) => merge(base.get(key).unwrap_or(&Value::Null), value, theirs),
+                    None => value.clone(),
+                };
+                merged.insert(key.clone(), merged_value);
+            }
+            Value::Object(merged)
+        }
+        (Value::Array(mine), Value::Array(theirs)) => {
+            let base = base.as_array().map(Vec::as_slice).unwrap_or_default();
+            // Their order, without what this instance removed, then what it added.
+            let mut merged = theirs
+                .iter()
+                .filter(|item| !base.contains(item) || mine.contains(item))
+                .cloned()
+                .collect::<Vec<Value>>();
+            for item in mine {
+                if !base.contains(item) && !merged.contains(item) {
+                    merged.push(item.clone());
+                }
+            }
+            Value::Array(merged)
+        }
+        // Both changed it, and this instance is writing last.
+        _ => mine.clone(),
+    }
+}
+
+/// `path` with `suffix` after its file name.
+fn sibling(path: &Path, suffix: &str) -> PathBuf {
+    let mut name = OsString::from(path.as_os_str());
+    name.push(suffix);
+    PathBuf::from(name)
+}
+
+/// The contents of `path`, or `None` if it doesn't exist.
+fn read_to_string(path: &Path) -> io::Result<Option<String>> {
+    match fs::read_to_string(path) {
+        Ok(text) => Ok(Some(text)),
+        Err(err) if err.kind() == io::ErrorKind::NotFound => Ok(None),
+        Err(err) => Err(err),
+    }
+}
+
+/// Write `text` to a temporary file next to `path`, then rename it over
+/// `path`. A crash leaves either the old file or the new one, and at worst a
+/// stray temporary file that the next save replaces.
+fn write_atomic(path: &Path, text: &str) -> io::Result<()> {
+    let temp = sibling(path, ".tmp");
+    let mut file = fs::File::create(&temp)?;
+    file.write_all(text.as_bytes())?;
+    file.sync_all()?;
+    fs::rename(&temp, path)
+}
+
+/// A lock file next to the settings, held from re-reading them until the
+/// merged copy is in place. Removed when dropped.
+#[derive(Debug)]
+struct SettingsLock {
+    path: PathBuf,
+}
+
+impl SettingsLock {
+    /// Wait up to `timeout` for the lock. One older than `stale_after` was
+    /// left by an instance that stopped mid-save, and is taken over.
+    fn acquire(settings: &Path, stale_after: Duration, timeout: Duration) -> io::Result<Self> {
+        let path = sibling(settings, ".lock");
+        let start = Instant::now();
+        loop {
+            match fs::OpenOptions::new()
+                .write(true)
+                .create_new(true)
+                .open(&path)
+            {
+                Ok(mut file) => {
+                    writeln!(file, "{}", std::process::id())?;
+                    return Ok(Self { path });
+                }
+                Err(err) if err.kind() == io::ErrorKind::AlreadyExists => {
+                    let age = fs::metadata(&path)
+                        .and_then(|metadata| metadata.modified())
+                        .ok()
+                        .and_then(|modified| modified.elapsed().ok());
+                    if age.is_some_and(|age| age >= stale_after) {
+                        fs::remove_file(&path).ok();
+                        continue;
+                    }
+                    if start.elapsed() >= timeout {
+                        return Err(io::Error::new(
+                            io::ErrorKind::WouldBlock,
+                            format!("{} is held by another instance", path.display()),
+                        ));
+                    }
+                    thread::sleep(LOCK_RETRY);
+                }
+                Err(err) => return Err(err),
+            }
+        }
+    }
+}
+
+impl Drop for SettingsLock {
+    fn drop(&mut self) {
+        fs::remove_file(&self.path).ok();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::layout::{RESIZE_STEP, SplitLayout};
+
+    #[test]
+    fn test_save_load() {
+        let path =
+            std::env::temp_dir().join(format!("poroshell-settings-{}.json", std::process::id()));
+        assert_eq!(Settings::load(&path).unwrap(), None);
+
+        let settings = Settings {
+            sources: vec![SchemaSource::Lcu, SchemaSource::RiotApi],
+            live_lcu: true,
+            artifact_dir: PathBuf::from("out"),
+            library_mirrors: vec!["https://example.invalid/libraries.json".to_string()],
+            result_ttl_secs: 5,
+            mouse: false,
+            sources_manifest: "sources.json".to_string(),
+            layouts: Layouts {
+                home: SplitLayout::default().resized(RESIZE_STEP),
+            },
+            min_terminal_size: MinSize {
+                width: 40,
+                height: 10,
+            },
+            locale: "fr".to_string(),
+            baseline: Some(SchemaSource::Lcu.url().to_string()),
+        };
+        SettingsFile::new(&path).save(&settings).unwrap();
+        assert_eq!(Settings::load(&path).unwrap(), Some(settings));
+
+        fs::remove_file(&path).unwrap();
+    }
+
+    /// An empty directory for one test's settings file.
+    fn settings_dir(name: &str) -> PathBuf {
+        let dir =
+            std::env::temp_dir().join(format!("poroshell-settings-{name}-{}", std::process::id()));
+        fs::remove_dir_all(&dir).ok();
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    #[test]
+    fn test_interleaved_saves() {
+        let dir = settings_dir("interleaved");
+        let path = dir.join(SETTINGS_FILE);
+        SettingsFile::new(&path).save(&Settings::default()).unwrap();
+
+        let mut tui = SettingsFile::new(&path);
+        let mut cli = SettingsFile::new(&path);
+        let mut tui_settings = tui.load().unwrap().unwrap();
+        let mut cli_settings = cli.load().unwrap().unwrap();
+
+        tui_settings.sources.push(SchemaSource::RiotApi);
+        tui_settings.mouse = false;
+        tui.save(&tui_settings).unwrap();
+
+        // The CLI read before the TUI saved, so it merges instead of clobbering.
+        cli_settings.sources.push(SchemaSource::LolClient);
+        cli_settings
+            .sources
+            .retain(|source| *source != SchemaSource::Lcu);
+        cli_settings.locale = "fr".to_string();
+        let merged = cli.save(&cli_settings).unwrap();
+        assert_eq!(
+            merged.sources,
+            [SchemaSource::RiotApi, SchemaSource::LolClient]
+        );
+        assert!(!merged.mouse);
+        assert_eq!(merged.locale, "fr");
+        assert_eq!(Settings::load(&path).unwrap(), Some(merged.clone()));
+
+        // Both changed the same setting, so the last save wins.
+        tui_settings.locale = "de".to_string();
+        let merged = tui.save(&tui_settings).unwrap();
+        assert_eq!(merged.locale, "de");
+        assert_eq!(merged.sources.len(), 2);
+
+        // A save with nothing new from elsewhere writes exactly what it's given.
+        tui_settings.live_lcu = true;
+        assert_eq!(
+            tui.save(&tui_settings).unwrap().sources,
+            tui_settings.sources
+        );
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_save_removes_entries() {
+        let dir = settings_dir("remove");
+        let path = dir.join(SETTINGS_FILE);
+        let mut settings = Settings {
+            sources: vec![SchemaSource::Lcu, SchemaSource::RiotApi],
+            ..Settings::default()
+        };
+        let mut file = SettingsFile::new(&path);
+        file.save(&settings).unwrap();
+
+        settings.sources.pop();
+        file.save(&settings).unwrap();
+        assert_eq!(Settings::load(&path).unwrap(), Some(settings.clone()));
+
+        // With no load or save to merge against, the file is overwritten.
+        settings.library_mirrors.clear();
+        SettingsFile::new(&path).save(&settings).unwrap();
+        assert_eq!(Settings::load(&path).unwrap(), Some(settings));
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_crash_mid_save() {
+        let dir = settings_dir("crash");
+        let path = dir.join(SETTINGS_FILE);
+        let mut file = SettingsFile::new(&path);
+        let first = Settings {
+            locale: "fr".to_string(),
+            ..Settings::default()
+        };
+        file.save(&first).unwrap();
+
+        // A save that died before its rename leaves the old file in place.
+        let temp = sibling(&path, ".tmp");
+        fs::write(&temp, r#"{ "sources": ["#).unwrap();
+        assert_eq!(Settings::load(&path).unwrap(), Some(first.clone()));
+        file.save(&Settings::default()).unwrap();
+        assert!(!temp.exists());
+        assert_eq!(Settings::load(&path).unwrap(), Some(Settings::default()));
+
+        // The copy the save replaced is kept, and read if the file breaks.
+        assert!(!file.recovered());
+        fs::write(&path, r#"{ "sources": ["#).unwrap();
+        assert_eq!(file.load().unwrap(), Some(first.clone()));
+        assert!(file.recovered());
+        // The broken file isn't kept as the backup.
+        file.save(&first).unwrap();
+        assert_eq!(file.load().unwrap(), Some(first));
+        assert!(!file.recovered());
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_stale_lock() {
+        let dir = settings_dir("lock");
+        let path = dir.join(SETTINGS_FILE);
+        let held = SettingsLock::acquire(&path, STALE_LOCK, LOCK_TIMEOUT).unwrap();
+        let err = SettingsLock::acquire(&path, STALE_LOCK, Duration::ZERO).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::WouldBlock);
+        drop(held);
+        assert!(!sibling(&path, ".lock").exists());
+
+        // An instance stopped mid-save and left its lock behind.
+        let lock = sibling(&path, ".lock");
+        let left = fs::File::create(&lock).unwrap();
+        left.set_modified(std::time::SystemTime::now() - STALE_LOCK * 2)
+            .unwrap();
+        SettingsFile::new(&path).save(&Settings::default()).unwrap();
+        assert!(!lock.exists());
+
+        // One left just now goes stale while a save waits for it.
+        let left = SettingsLock::acquire(&path, STALE_LOCK, LOCK_TIMEOUT).unwrap();
+        std::mem::forget(left);
+        let stale_after = Duration::from_millis(50);
+        SettingsLock::acquire(&path, stale_after, stale_after * 4).unwrap();
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_load_without_mirrors() {
+        let settings: Settings =
+            serde_json::from_str(r#"{ "sources": [], "live_lcu": false, "artifact_dir": "a" }"#)
+                .unwrap();
+        assert_eq!(
+            settings.library_mirrors,
+            Settings::default().library_mirrors
+        );
+        assert!(settings.mouse);
+        assert_eq!(settings.sources_manifest, DEFAULT_MANIFEST_URL);
+        assert_eq!(settings.layouts, Layouts::default());
+        assert_eq!(settings.min_terminal_size, MinSize::default());
+        assert_eq!(settings.locale, DEFAULT_LOCALE);
+    }
+}
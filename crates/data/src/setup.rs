@@ -0,0 +1,185 @@
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use crate::settings::{SchemaSource, Settings};
+
+/// A page of the first-run setup wizard, in order.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SetupStep {
+    Sources,
+    LiveLcu,
+    ArtifactDir,
+    Done,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SetupError {
+    NoSources,
+    EmptyArtifactDir,
+    ArtifactDirNotWritable(String),
+}
+
+impl std::fmt::Display for SetupError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::NoSources => write!(f, "Select at least one source"),
+            Self::EmptyArtifactDir => write!(f, "Enter a directory"),
+            Self::ArtifactDirNotWritable(err) => write!(f, "Directory is not writable: {err}"),
+        }
+    }
+}
+
+/// First-run setup, independent of how it is drawn.
+#[derive(Debug, Clone)]
+pub struct SetupWizard {
+    step: SetupStep,
+    settings: Settings,
+}
+
+impl Default for SetupWizard {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl SetupWizard {
+    pub fn new() -> Self {
+        Self {
+            step: SetupStep::Sources,
+            settings: Settings::default(),
+        }
+    }
+
+    pub fn step(&self) -> SetupStep {
+        self.step
+    }
+
+    /// The answers so far.
+    pub fn settings(&self) -> &Settings {
+        &self.settings
+    }
+
+    pub fn set_sources(&mut self, sources: Vec<SchemaSource>) {
+        self.settings.sources = sources;
+    }
+
+    pub fn set_live_lcu(&mut self, live_lcu: bool) {
+        self.settings.live_lcu = live_lcu;
+    }
+
+    pub fn set_artifact_dir(&mut self, artifact_dir: impl Into<PathBuf>) {
+        self.settings.artifact_dir = artifact_dir.into();
+    }
+
+    /// Validate the current step and move to the next one. The step is kept
+    /// when validation fails.
+    pub fn advance(&mut self) -> Result<SetupStep, SetupError> {
+        self.step = match self.step {
+            SetupStep::Sources => {
+                if self.settings.sources.is_empty() {
+                    return Err(SetupError::NoSources);
+                }
+                SetupStep::LiveLcu
+            }
+            SetupStep::LiveLcu => SetupStep::ArtifactDir,
+            SetupStep::ArtifactDir => {
+                check_writable(&self.settings.artifact_dir)?;
+                SetupStep::Done
+            }
+            SetupStep::Done => SetupStep::Done,
+        };
+        Ok(self.step)
+    }
+
+    pub fn back(&mut self) -> SetupStep {
+        self.step = match self.step {
+            SetupStep::Sources | SetupStep::LiveLcu => SetupStep::Sources,
+            SetupStep::ArtifactDir => SetupStep::LiveLcu,
+            SetupStep::Done => SetupStep::ArtifactDir,
+        };
+        self.step
+    }
+
+    /// The final settings, once every step has been completed.
+    pub fn finish(self) -> Option<Settings> {
+        (self.step == SetupStep::Done).then_some(self.settings)
+    }
+}
+
+fn check_writable(dir: &Path) -> Result<(), SetupError> {
+    if dir.as_os_str().is_empty() {
+        return Err(SetupError::EmptyArtifactDir);
+    }
+    let probe = dir.join(".poroshell-write-check");
+    fs::create_dir_all(dir)
+        .and_then(|_| fs::write(&probe, b""))
+        .and_then(|_| fs::remove_file(&probe))
+        .map_err(|err| SetupError::ArtifactDirNotWritable(err.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_navigation() {
+        let mut wizard = SetupWizard::new();
+        assert_eq!(wizard.back(), SetupStep::Sources);
+        assert_eq!(wizard.advance(), Ok(SetupStep::LiveLcu));
+        assert_eq!(wizard.back(), SetupStep::Sources);
+        assert_eq!(wizard.advance(), Ok(SetupStep::LiveLcu));
+        assert_eq!(wizard.advance(), Ok(SetupStep::ArtifactDir));
+        assert!(wizard.clone().finish().is_none());
+    }
+
+    #[test]
+    fn test_validation_failure() {
+        let mut wizard = SetupWizard::new();
+        wizard.set_sources(Vec::new());
+        assert_eq!(wizard.advance(), Err(SetupError::NoSources));
+        assert_eq!(wizard.step(), SetupStep::Sources);
+
+        wizard.set_sources(vec![SchemaSource::Lcu]);
+        wizard.advance().unwrap();
+        wizard.advance().unwrap();
+        wizard.set_artifact_dir("");
+        assert_eq!(wizard.advance(), Err(SetupError::EmptyArtifactDir));
+
+        // A file where the directory should be.
+        let file = std::env::temp_dir().join(format!("poroshell-setup-{}", std::process::id()));
+        fs::write(&file, b"").unwrap();
+        wizard.set_artifact_dir(&file);
+        assert!(matches!(
+            wizard.advance(),
+            Err(SetupError::ArtifactDirNotWritable(_))
+        ));
+        assert_eq!(wizard.step(), SetupStep::ArtifactDir);
+        fs::remove_file(&file).unwrap();
+    }
+
+    #[test]
+    fn test_produced_settings() {
+        let dir = std::env::temp_dir().join(format!("poroshell-artifacts-{}", std::process::id()));
+        let mut wizard = SetupWizard::new();
+        wizard.set_sources(vec![SchemaSource::Lcu, SchemaSource::LolClient]);
+        wizard.advance().unwrap();
+        wizard.set_live_lcu(true);
+        wizard.advance().unwrap();
+        wizard.set_artifact_dir(&dir);
+        assert_eq!(wizard.advance(), Ok(SetupStep::Done));
+
+        let settings = wizard.finish().unwrap();
+        assert_eq!(
+            settings,
+            Settings {
+                sources: vec![SchemaSource::Lcu, SchemaSource::LolClient],
+                live_lcu: true,
+                artifact_dir: dir.clone(),
+                ..Settings::default()
+            }
+        );
+        fs::remove_dir(&dir).unwrap();
+    }
+}
@@ -0,0 +1,221 @@
+//! Direct conversion from a generated spec, so a freshly built spec can be
+//! browsed without writing it out and reading it back.
+
+use openapi::types::{
+    AdditionalProperties, ApplicationJson, Content, Format, In, Info, Operation, Parameter,
+    PathItem, Paths, RequestBody, Responses, Schema, TextPlain, Type,
+};
+use poro_schema::openapi::OpenApiSpec;
+use serde_json::Value;
+
+use crate::{Document, grouping::GroupingConfig};
+
+impl Document {
+    /// Group a generated spec the same way [`Document::new`] groups a loaded one.
+    pub fn from_spec(spec: &OpenApiSpec) -> Self {
+        let info = Info {
+            title: spec.info.title.clone(),
+            description: spec.info.description.clone(),
+            version: spec.info.version.clone(),
+        };
+
+        let mut paths = Paths::new();
+        for (path, path_item) in spec.paths.iter() {
+            let mut item = PathItem::new();
+            for (method, operation) in path_item.as_object().into_iter().flatten() {
+                item.insert(method.clone(), operation_from(operation));
+            }
+            paths.insert(path.clone(), item);
+        }
+
+        let display_names =
+            crate::display_names(spec.tags.iter().map(|tag| (&tag.name, &tag.display_name)));
+        Self::from_paths(info, &paths, display_names, &GroupingConfig::default())
+    }
+}
+
+fn operation_from(value: &Value) -> Operation {
+    Operation {
+        description: string(value, "description"),
+        operation_id: string(value, "operationId").unwrap_or_default(),
+        parameters: array(value, "parameters")
+            .filter_map(parameter_from)
+            .collect(),
+        responses: value
+            .get("responses")
+            .and_then(Value::as_object)
+            .map(|responses| {
+                responses
+                    .iter()
+                    .map(|(status, response)| {
+                        let responses = Responses {
+                            content: content_from(response),
+                            description: string(response, "description").unwrap_or_default(),
+                        };
+                        (status.clone(), responses)
+                    })
+                    .collect()
+            }),
+        summary: string(value, "summary"),
+        tags: array(value, "tags")
+            .filter_map(|tag| tag.as_str().map(String::from))
+            .collect(),
+        request_body: value.get("requestBody").and_then(|body| {
+            Some(RequestBody {
+                content: content_from(body)?,
+                required: body.get("required").and_then(Value::as_bool),
+            })
+        }),
+        console_help: string(value, "x-console-help"),
+        stability: string(value, "x-stability"),
+    }
+}
+
+fn parameter_from(value: &Value) -> Option<Parameter> {
+    let parameter_in = match value.get("in")?.as_str()? {
+        "path" => In::Path,
+        "query" => In::Query,
+        "header" => In::Header,
+        _ => return None,
+    };
+    Some(Parameter {
+        parameter_in,
+        parameter_enum: None,
+        description: string(value, "description"),
+        format: None,
+        name: string(value, "name")?,
+        required: value.get("required").and_then(Value::as_bool),
+        schema: value.get("schema").map(schema_from),
+        parameter_type: None,
+    })
+}
+
+fn content_from(value: &Value) -> Option<Content> {
+    let content = value.get("content")?;
+    Some(Content {
+        application_json: content.get("application/json").map(|json| ApplicationJson {
+            schema: json.get("schema").map(schema_from),
+            example: json.get("example").cloned(),
+        }),
+        text_plain: content.get("text/plain").map(|text| TextPlain {
+            schema: text.get("schema").map(schema_from),
+            example: text.get("example").cloned(),
+        }),
+    })
+}
+
+fn schema_from(value: &Value) -> Schema {
+    Schema {
+        schema_type: value
+            .get("type")
+            .and_then(Value::as_str)
+            .and_then(type_from),
+        format: value
+            .get("format")
+            .and_then(Value::as_str)
+            .and_then(format_from),
+        minimum: value.get("minimum").and_then(Value::as_i64),
+        description: string(value, "description"),
+        schema_ref: string(value, "$ref"),
+        schema_enum: value
+            .get("enum")
+            .map(|values| array_of(values).cloned().collect()),
+        enum_varnames: strings(value, "x-enum-varnames"),
+        enum_description: strings(value, "x-enum-description"),
+        additional_properties: value.get("additionalProperties").map(
+            |additional| match additional {
+                Value::Bool(allowed) => Box::new(AdditionalProperties::Bool(*allowed)),
+                // An empty schema allows anything.
+                Value::Object(schema) if schema.is_empty() => {
+                    Box::new(AdditionalProperties::Bool(true))
+                }
+                schema => Box::new(AdditionalProperties::Schema(Box::new(schema_from(schema)))),
+            },
+        ),
+        properties: value
+            .get("properties")
+            .and_then(Value::as_object)
+            .map(|properties| {
+                properties
+                    .iter()
+                    .map(|(name, schema)| (name.clone(), schema_from(schema)))
+                    .collect()
+            }),
+        items: value.get("items").map(|items| Box::new(schema_from(items))),
+        required: strings(value, "required"),
+    }
+}
+
+fn type_from(ty: &str) -> Option<Type> {
+    Some(match ty {
+        "array" => Type::Array,
+        "boolean" => Type::Boolean,
+        "integer" => Type::Integer,
+        "number" => Type::Number,
+        "object" => Type::Object,
+        "string" => Type::String,
+        _ => return None,
+    })
+}
+
+fn format_from(format: &str) -> Option<Format> {
+    Some(match format {
+        "double" => Format::Double,
+        "float" => Format::Float,
+        "int8" => Format::Int8,
+        "int16" => Format::Int16,
+        "int32" => Format::Int32,
+        "int64" => Format::Int64,
+        "uint8" => Format::Uint8,
+        "uint16" => Format::Uint16,
+        "uint32" => Format::Uint32,
+        "uint64" => Format::Uint64,
+        _ => return None,
+    })
+}
+
+fn string(value: &Value, key: &str) -> Option<String> {
+    value.get(key).and_then(Value::as_str).map(String::from)
+}
+
+fn strings(value: &Value, key: &str) -> Option<Vec<String>> {
+    value.get(key).map(|values| {
+        array_of(values)
+            .filter_map(|v| v.as_str().map(String::from))
+            .collect()
+    })
+}
+
+fn array<'a>(value: &'a Value, key: &str) -> impl Iterator<Item = &'a Value> {
+    value.get(key).into_iter().flat_map(array_of)
+}
+
+fn array_of(value: &Value) -> impl Iterator<Item = &Value> {
+    value.as_array().into_iter().flatten()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Grouping;
+
+    #[test]
+    fn test_from_spec_matches_round_trip() {
+        let spec = poroshell_test_utils::fixtures::miniature_spec("1.0.0");
+
+        let json = serde_json::to_string(&spec).unwrap();
+        let loaded: openapi::types::Document = serde_json::from_str(&json).unwrap();
+        let round_trip = Document::new(loaded);
+        let direct = Document::from_spec(&spec);
+
+        assert!(!direct.plugins().is_empty());
+        assert_eq!(direct.plugins(), round_trip.plugins());
+        assert_eq!(direct.paths(), round_trip.paths());
+        assert_eq!(direct.info(), round_trip.info());
+        assert_eq!(direct.stats(), round_trip.stats());
+        assert_eq!(
+            direct.stats().label(Grouping::Plugin, "lol-summoner"),
+            "Summoner (lol-summoner)"
+        );
+    }
+}
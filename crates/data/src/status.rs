@@ -0,0 +1,391 @@
+//! What the status bar along the bottom of the screen shows: key hints for the
+//! focused pane, background work in progress, and short-lived messages.
+
+use std::{
+    collections::HashMap,
+    time::{Duration, Instant},
+};
+
+use crate::{
+    i18n::{Strings, keys},
+    integrity::Integrity,
+};
+
+/// How long a message stays in the status bar.
+pub const MESSAGE_TTL: Duration = Duration::from_secs(3);
+
+/// Frames of the spinner shown while background work runs, one per tick.
+const SPINNER: [char; 4] = ['|', '/', '-', '\\'];
+
+/// Separator between hints on the bar.
+const HINT_SEPARATOR: &str = " · ";
+
+/// A key and what it does, e.g. `Enter: open`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Hint {
+    pub key: &'static str,
+    /// Catalog key of the action, from [`keys`].
+    pub action: &'static str,
+}
+
+impl Hint {
+    pub const fn new(key: &'static str, action: &'static str) -> Self {
+        Self { key, action }
+    }
+}
+
+/// Key hints per page and focused pane, both by their debug names, like
+/// `("Home", "Navigation")`.
+#[derive(Debug, Clone)]
+pub struct HintRegistry {
+    hints: HashMap<(String, String), Vec<Hint>>,
+    /// Keys that work everywhere, shown after the pane's own.
+    global: Vec<Hint>,
+}
+
+impl Default for HintRegistry {
+    /// The hints for every pane of the app.
+    fn default() -> Self {
+        Self::new(vec![
+            Hint::new("Esc", keys::HINT_QUIT),
+            Hint::new("Ctrl+←/→", keys::HINT_RESIZE),
+        ])
+        .with(
+            "Home",
+            "Navigation",
+            vec![
+                Hint::new("Enter", keys::HINT_OPEN),
+                Hint::new("w", keys::HINT_WORKFLOWS),
+                Hint::new("o", keys::HINT_SOURCES),
+                Hint::new("c", keys::HINT_ONLY_CHANGED),
+                Hint::new("Ctrl+r", keys::HINT_DASHBOARD),
+                Hint::new("Tab", keys::HINT_LIBRARIES),
+            ],
+        )
+        .with(
+            "Home",
+            "Libraries",
+            vec![
+                Hint::new("Enter", keys::HINT_OPEN_LINK),
+                Hint::new("y", keys::HINT_COPY_LINK),
+                Hint::new("PgUp/PgDn", keys::HINT_SCROLL),
+                Hint::new("Tab", keys::HINT_NAVIGATION),
+            ],
+        )
+        .with(
+            "Dashboard",
+            "Dashboard",
+            vec![
+                Hint::new("c", keys::HINT_CHANGELOG),
+                Hint::new("l", keys::HINT_LOAD_SPEC),
+                Hint::new("r", keys::HINT_RELOAD),
+                Hint::new("Ctrl+r", keys::HINT_CLOSE),
+            ],
+        )
+        .with(
+            "Endpoints",
+            "EndpointsGroups",
+            vec![
+                Hint::new("Tab", keys::HINT_ENDPOINTS),
+                Hint::new("Esc", keys::HINT_HOME),
+            ],
+        )
+        .with(
+            "Endpoints",
+            "EndpointsTable",
+            vec![
+                Hint::new("PgUp/PgDn", keys::HINT_SCROLL),
+                Hint::new("Tab", keys::HINT_GROUPS),
+                Hint::new("Esc", keys::HINT_HOME),
+            ],
+        )
+        .with(
+            "Home",
+            "QuitDialog",
+            vec![
+                Hint::new("←/→", keys::HINT_CHOOSE),
+                Hint::new("Enter", keys::HINT_CONFIRM),
+            ],
+        )
+        .with(
+            "Home",
+            "SourcesDialog",
+            vec![
+                Hint::new("Enter", keys::HINT_LOAD),
+                Hint::new("b", keys::HINT_BASELINE),
+                Hint::new("Esc", keys::HINT_CLOSE),
+            ],
+        )
+        .with(
+            "Home",
+            "SetupDialog",
+            vec![
+                Hint::new("Enter", keys::HINT_NEXT),
+                Hint::new("Shift+Tab", keys::HINT_BACK),
+                Hint::new("s", keys::HINT_SKIP),
+            ],
+        )
+    }
+}
+
+impl HintRegistry {
+    pub fn new(global: Vec<Hint>) -> Self {
+        Self {
+            hints: HashMap::new(),
+            global,
+        }
+    }
+
+    #[must_use]
+    pub fn with(mut self, page: &str, focus: &str, hints: Vec<Hint>) -> Self {
+        self.hints
+            .insert((page.to_string(), focus.to_string()), hints);
+        self
+    }
+
+    /// The pane's hints followed by the global ones, except for keys the pane
+    /// uses otherwise. Dialogs have only their own.
+    pub fn hints(&self, page: &str, focus: &str) -> Vec<Hint> {
+        let own = self
+            .hints
+            .get(&(page.to_string(), focus.to_string()))
+            .cloned()
+            .unwrap_or_default();
+        if focus.ends_with("Dialog") {
+            return own;
+        }
+        let global = self
+            .global
+            .iter()
+            .filter(|hint| own.iter().all(|own| own.key != hint.key))
+            .copied()
+            .collect::<Vec<Hint>>();
+        own.into_iter().chain(global).collect()
+    }
+
+    /// The hints as one line, e.g. `Enter: open · Tab: libraries`.
+    pub fn line(&self, page: &str, focus: &str, strings: &Strings) -> String {
+        self.hints(page, focus)
+            .iter()
+            .map(|hint| format!("{}: {}", hint.key, strings.get(hint.action)))
+            .collect::<Vec<String>>()
+            .join(HINT_SEPARATOR)
+    }
+}
+
+/// Background work in flight, by label, like `Loading lcu.json…`.
+#[derive(Debug, Clone, Default)]
+pub struct Activity {
+    /// Labels in the order the work started. Repeats are separate entries.
+    running: Vec<String>,
+    frame: usize,
+}
+
+impl Activity {
+    pub fn start(&mut self, label: &str) {
+        self.running.push(label.to_string());
+    }
+
+    /// One piece of work with this label finished.
+    pub fn finish(&mut self, label: &str) {
+        if let Some(index) = self.running.iter().position(|running| running == label) {
+            self.running.remove(index);
+        }
+    }
+
+    /// Every piece of work with this label finished, for work whose repeats
+    /// share one result.
+    pub fn finish_all(&mut self, label: &str) {
+        self.running.retain(|running| running != label);
+    }
+
+    pub fn in_flight(&self) -> usize {
+        self.running.len()
+    }
+
+    /// Advance the spinner.
+    pub fn tick(&mut self) {
+        self.frame = (self.frame + 1) % SPINNER.len();
+    }
+
+    /// Spinner and the oldest running label, with a count of the rest.
+    pub fn line(&self) -> Option<String> {
+        let first = self.running.first()?;
+        let spinner = SPINNER[self.frame];
+        Some(match self.running.len() - 1 {
+            0 => format!("{spinner} {first}"),
+            more => format!("{spinner} {first} (+{more})"),
+        })
+    }
+}
+
+/// Confirmations like `Copied to clipboard` that expire after [`MESSAGE_TTL`].
+///
+/// Time is passed in rather than read, like [`crate::coalesce::Coalescer`].
+#[derive(Debug, Clone, Default)]
+pub struct Messages {
+    queue: Vec<(Instant, String)>,
+}
+
+impl Messages {
+    pub fn push(&mut self, message: &str, now: Instant) {
+        self.queue.push((now + MESSAGE_TTL, message.to_string()));
+    }
+
+    /// Drop expired messages. Returns whether any were dropped.
+    pub fn expire(&mut self, now: Instant) -> bool {
+        let before = self.queue.len();
+        self.queue.retain(|(until, _)| *until > now);
+        self.queue.len() != before
+    }
+
+    /// The newest message still showing.
+    pub fn current(&self) -> Option<&str> {
+        self.queue.last().map(|(_, message)| message.as_str())
+    }
+}
+
+/// Everything on the status bar.
+#[derive(Debug, Clone, Default)]
+pub struct Status {
+    pub hints: HintRegistry,
+    pub activity: Activity,
+    pub messages: Messages,
+    /// How far the open document can be trusted, once one is loaded.
+    pub integrity: Option<Integrity>,
+}
+
+impl Status {
+    /// The bar's text: a marker if the open document wasn't verified, a
+    /// message if one is showing, else the running work, then the hints for
+    /// the focused pane.
+    pub fn line(&self, page: &str, focus: &str, strings: &Strings) -> String {
+        let hints = self.hints.line(page, focus, strings);
+        let line = self.unmarked(hints);
+        let marker = match self.integrity {
+            Some(Integrity::Unverified) => strings.get(keys::STATUS_UNVERIFIED),
+            Some(Integrity::Cached(_)) => strings.get(keys::STATUS_CACHED),
+            Some(Integrity::Verified) | None => return line,
+        };
+        if line.is_empty() {
+            marker.to_string()
+        } else {
+            format!("{marker} {line}")
+        }
+    }
+
+    fn unmarked(&self, hints: String) -> String {
+        let prefix = self
+            .messages
+            .current()
+            .map(str::to_string)
+            .or_else(|| self.activity.line());
+        match prefix {
+            Some(prefix) if hints.is_empty() => prefix,
+            Some(prefix) => format!("{prefix}  {hints}"),
+            None => hints,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_hints() {
+        let hints = HintRegistry::default();
+        let strings = Strings::default();
+        assert_eq!(
+            hints.line("Home", "Navigation", &strings),
+            "Enter: open · w: workflows · o: sources · c: only changed · Ctrl+r: dashboard · Tab: libraries · Esc: quit · Ctrl+←/→: resize"
+        );
+        assert_eq!(
+            hints.line("Home", "QuitDialog", &strings),
+            "←/→: choose · Enter: confirm"
+        );
+        // Esc goes back from the endpoints rather than quitting.
+        assert_eq!(
+            hints.line("Endpoints", "EndpointsGroups", &strings),
+            "Tab: endpoints · Esc: home · Ctrl+←/→: resize"
+        );
+        // Panes without hints of their own still get the global ones.
+        assert_eq!(
+            hints.line("Home", "Welcome", &strings),
+            "Esc: quit · Ctrl+←/→: resize"
+        );
+    }
+
+    #[test]
+    fn test_activity() {
+        let mut activity = Activity::default();
+        assert_eq!(activity.line(), None);
+
+        activity.start("Loading libraries…");
+        activity.start("Loading lcu.json…");
+        activity.start("Loading libraries…");
+        assert_eq!(activity.line().unwrap(), "| Loading libraries… (+2)");
+
+        activity.tick();
+        activity.finish("Loading lcu.json…");
+        assert_eq!(activity.line().unwrap(), "/ Loading libraries… (+1)");
+        activity.finish_all("Loading libraries…");
+        assert_eq!(activity.in_flight(), 0);
+        assert_eq!(activity.line(), None);
+    }
+
+    #[test]
+    fn test_messages_expire() {
+        let now = Instant::now();
+        let mut messages = Messages::default();
+        messages.push("Layout saved", now);
+        messages.push("Copied to clipboard", now + Duration::from_secs(1));
+        assert_eq!(messages.current(), Some("Copied to clipboard"));
+
+        assert!(!messages.expire(now + Duration::from_secs(2)));
+        assert!(messages.expire(now + MESSAGE_TTL));
+        assert_eq!(messages.current(), Some("Copied to clipboard"));
+        assert!(messages.expire(now + MESSAGE_TTL + Duration::from_secs(1)));
+        assert_eq!(messages.current(), None);
+    }
+
+    #[test]
+    fn test_status_line() {
+        let now = Instant::now();
+        let mut status = Status::default();
+        let strings = Strings::default();
+        status.activity.start("Loading lcu.json…");
+        assert_eq!(
+            status.line("Home", "Libraries", &strings),
+            "| Loading lcu.json…  Enter: open link · y: copy link · PgUp/PgDn: scroll · Tab: navigation · Esc: quit · Ctrl+←/→: resize"
+        );
+
+        // A message takes the place of the spinner while it shows.
+        status.messages.push("State written", now);
+        assert!(
+            status
+                .line("Home", "SetupDialog", &strings)
+                .starts_with("State written  Enter: next")
+        );
+        status.messages.expire(now + MESSAGE_TTL);
+        assert!(
+            status
+                .line("Home", "SetupDialog", &strings)
+                .starts_with("| Loading lcu.json…  Enter: next")
+        );
+
+        // Documents that weren't checked against a hash stay marked.
+        status.integrity = Some(Integrity::Unverified);
+        assert!(
+            status
+                .line("Home", "SetupDialog", &strings)
+                .starts_with("[unverified] | Loading lcu.json…")
+        );
+        status.integrity = Some(Integrity::Verified);
+        assert!(
+            status
+                .line("Home", "SetupDialog", &strings)
+                .starts_with("| Loading lcu.json…")
+        );
+    }
+}
@@ -0,0 +1,208 @@
+//! Showing a slice of a long list, so a table with thousands of rows only
+//! builds the rows on screen.
+//!
+//! [`WindowedRows`] keeps every row and the current filter; the component is
+//! handed only [`WindowedRows::window`]. Moving the selection or refiltering
+//! is then cheap, and the rows outside the window are never formatted.
+
+use std::ops::Range;
+
+/// Rows built beyond each edge of the visible ones, so short scrolls don't
+/// need a new window.
+pub const WINDOW_MARGIN: usize = 20;
+
+/// The rows to build for a list `len` long whose first visible row is
+/// `offset`, with `height` rows visible and `margin` more on either side.
+pub fn window(len: usize, offset: usize, height: usize, margin: usize) -> Range<usize> {
+    let start = offset.min(len).saturating_sub(margin);
+    let end = offset
+        .saturating_add(height)
+        .saturating_add(margin)
+        .min(len);
+    start..end
+}
+
+/// Rows of a table, the filter over them, and the selection.
+#[derive(Debug, Clone)]
+pub struct WindowedRows<T> {
+    rows: Vec<T>,
+    /// Indices into `rows` that pass the filter, in order.
+    filtered: Vec<usize>,
+    /// Position in `filtered`.
+    selected: usize,
+    /// First visible position in `filtered`.
+    offset: usize,
+    height: usize,
+    /// The window last handed out, to tell whether a move needs a new one.
+    built: Range<usize>,
+}
+
+impl<T> WindowedRows<T> {
+    pub fn new(rows: Vec<T>, height: usize) -> Self {
+        let filtered = (0..rows.len()).collect();
+        let mut windowed = Self {
+            rows,
+            filtered,
+            selected: 0,
+            offset: 0,
+            height: height.max(1),
+            built: 0..0,
+        };
+        windowed.built = windowed.range();
+        windowed
+    }
+
+    /// Rows passing the filter.
+    pub fn len(&self) -> usize {
+        self.filtered.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.filtered.is_empty()
+    }
+
+    /// Position of the selection among the filtered rows.
+    pub fn selected(&self) -> usize {
+        self.selected
+    }
+
+    /// The selected row, if any row passes the filter.
+    pub fn selected_row(&self) -> Option<&T> {
+        self.filtered
+            .get(self.selected)
+            .map(|&index| &self.rows[index])
+    }
+
+    /// Keep only the rows `keep` accepts. The selection and scroll position
+    /// are clamped to what is left.
+    pub fn filter(&mut self, keep: impl Fn(&T) -> bool) {
+        self.filtered = (0..self.rows.len())
+            .filter(|&index| keep(&self.rows[index]))
+            .collect();
+        self.selected = self.selected.min(self.len().saturating_sub(1));
+        self.offset = self.offset.min(self.len().saturating_sub(self.height));
+        self.scroll_to_selected();
+        self.built = self.range();
+    }
+
+    /// Change how many rows fit, e.g. when the terminal is resized.
+    pub fn resize(&mut self, height: usize) {
+        self.height = height.max(1);
+        self.scroll_to_selected();
+    }
+
+    /// Move the selection by `delta` rows. Returns whether the rows handed
+    /// out last no longer cover the view, so a new window is needed.
+    pub fn move_by(&mut self, delta: isize) -> bool {
+        let last = self.len().saturating_sub(1);
+        self.selected = self.selected.saturating_add_signed(delta).min(last);
+        self.scroll_to_selected();
+        let visible = self.offset..(self.offset + self.height).min(self.len());
+        visible.start < self.built.start || visible.end > self.built.end
+    }
+
+    /// The filtered rows to build, starting at position `window.start`, and
+    /// the selection relative to them.
+    pub fn window(&mut self) -> (Range<usize>, Vec<&T>, usize) {
+        self.built = self.range();
+        let rows = self.filtered[self.built.clone()]
+            .iter()
+            .map(|&index| &self.rows[index])
+            .collect();
+        (self.built.clone(), rows, self.selected - self.built.start)
+    }
+
+    fn range(&self) -> Range<usize> {
+        window(self.len(), self.offset, self.height, WINDOW_MARGIN)
+    }
+
+    /// Scroll as little as needed to keep the selection visible.
+    fn scroll_to_selected(&mut self) {
+        if self.selected < self.offset {
+            self.offset = self.selected;
+        } else if self.selected >= self.offset + self.height {
+            self.offset = self.selected + 1 - self.height;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_window_edges() {
+        // At the start, only the margin below.
+        assert_eq!(window(1000, 0, 10, 5), 0..15);
+        assert_eq!(window(1000, 100, 10, 5), 95..115);
+        // At the end, only the margin above.
+        assert_eq!(window(1000, 990, 10, 5), 985..1000);
+        // Shorter than the window.
+        assert_eq!(window(3, 0, 10, 5), 0..3);
+        assert_eq!(window(0, 0, 10, 5), 0..0);
+        // An offset past the end, as after a filter, is clamped.
+        assert_eq!(window(8, 50, 10, 5), 3..8);
+    }
+
+    #[test]
+    fn test_move_and_rewindow() {
+        let mut rows = WindowedRows::new((0..1000).collect::<Vec<usize>>(), 10);
+        let (range, built, selected) = rows.window();
+        assert_eq!((range, built.len(), selected), (0..30, 30, 0));
+
+        // Within the margin, the built rows still cover the view.
+        assert!(!rows.move_by(15));
+        assert!(!rows.move_by(14));
+        assert!(rows.move_by(1));
+        let (range, _, selected) = rows.window();
+        assert_eq!(range, 1..51);
+        assert_eq!(selected, 29);
+
+        assert!(rows.move_by(10_000));
+        assert_eq!(rows.selected_row(), Some(&999));
+        assert_eq!(rows.window().0, 970..1000);
+        assert!(!rows.move_by(-5));
+    }
+
+    #[test]
+    fn test_filter_below_offset() {
+        let mut rows = WindowedRows::new((0..1000).collect::<Vec<usize>>(), 10);
+        rows.move_by(500);
+        rows.filter(|row| row % 100 == 0);
+        // The selection and scroll position fall back into the ten rows left.
+        assert_eq!(rows.len(), 10);
+        assert_eq!(rows.selected_row(), Some(&900));
+        let (range, built, selected) = rows.window();
+        assert_eq!(range, 0..10);
+        assert_eq!(built[selected], &900);
+
+        rows.filter(|_| false);
+        assert!(rows.is_empty());
+        assert_eq!(rows.selected_row(), None);
+        assert_eq!(rows.window().0, 0..0);
+
+        rows.filter(|_| true);
+        assert_eq!(rows.len(), 1000);
+    }
+
+    /// Each keypress of a search refilters 5000 rows, but only the window
+    /// of them is built.
+    #[test]
+    fn test_window_builds_only_visible_rows() {
+        let endpoints = (0..5000)
+            .map(|i| format!("/lol-plugin-{}/v1/resource/{i}", i % 150))
+            .collect::<Vec<String>>();
+        let mut rows = WindowedRows::new(endpoints, 40);
+        rows.move_by(2500);
+        assert_eq!(rows.window().1.len(), 40 + 2 * WINDOW_MARGIN);
+
+        for search in ["/lol-plugin-1", "/lol-plugin-12"] {
+            rows.filter(|row| row.starts_with(search));
+            let len = rows.len();
+            let (range, built, _) = rows.window();
+            assert!(len > built.len(), "{search}");
+            assert!(built.len() <= 40 + 2 * WINDOW_MARGIN, "{search}");
+            assert_eq!(range.len(), built.len());
+        }
+    }
+}
@@ -0,0 +1,418 @@
+//! A stand-in LCU that serves a generated spec, so clients can be tested
+//! without running League.
+//!
+//! Every operation answers with an example built from its response schema.
+//! Requests must use basic auth as `riot` with the configured password, like
+//! the real client, and the certificate is self-signed.
+
+use std::{
+    io,
+    net::{Ipv4Addr, SocketAddr},
+    path::PathBuf,
+    sync::Arc,
+};
+
+use base64::{Engine, prelude::BASE64_STANDARD};
+use http_body_util::{BodyExt, Full};
+use hyper::{
+    Method, Request, Response, StatusCode,
+    body::{Bytes, Incoming},
+    header,
+    server::conn::http1,
+    service::service_fn,
+};
+use hyper_util::rt::TokioIo;
+use poro_schema::{example::example, openapi::OpenApiSpec};
+use rustls::{
+    ServerConfig,
+    pki_types::{CertificateDer, PrivateKeyDer, pem::PemObject},
+};
+use serde_json::{Map, Value, json};
+use tokio::net::TcpListener;
+use tokio_rustls::TlsAcceptor;
+
+const CERTIFICATE: &[u8] = include_bytes!("../assets/cert.pem");
+const PRIVATE_KEY: &[u8] = include_bytes!("../assets/key.pem");
+
+/// User name the LCU expects in basic auth.
+pub const USERNAME: &str = "riot";
+
+#[derive(Debug, Clone)]
+pub struct MockOptions {
+    /// Port to listen on; 0 picks a free one.
+    pub port: u16,
+    pub password: String,
+    /// Where to write a lockfile pointing at the server, for clients that
+    /// discover the LCU that way.
+    pub lockfile: Option<PathBuf>,
+}
+
+impl Default for MockOptions {
+    fn default() -> Self {
+        Self {
+            port: 0,
+            password: "poroshell".to_string(),
+            lockfile: None,
+        }
+    }
+}
+
+pub struct MockServer {
+    listener: TcpListener,
+    acceptor: TlsAcceptor,
+    routes: Arc<Routes>,
+}
+
+impl MockServer {
+    /// Listen on 127.0.0.1 and write the lockfile, if one was asked for.
+    pub async fn bind(spec: &OpenApiSpec, options: &MockOptions) -> io::Result<Self> {
+        let listener = TcpListener::bind((Ipv4Addr::LOCALHOST, options.port)).await?;
+        let acceptor = TlsAcceptor::from(Arc::new(tls_config()?));
+        let auth = format!(
+            "Basic {}",
+            BASE64_STANDARD.encode(format!("{USERNAME}:{}", options.password))
+        );
+        let server = Self {
+            listener,
+            acceptor,
+            routes: Arc::new(Routes::new(spec, auth)),
+        };
+
+        if let Some(path) = options.lockfile.as_ref() {
+            let port = server.local_addr()?.port();
+            tokio::fs::write(path, lockfile(port, &options.password)).await?;
+        }
+        Ok(server)
+    }
+
+    pub fn local_addr(&self) -> io::Result<SocketAddr> {
+        self.listener.local_addr()
+    }
+
+    /// Serve until the task is dropped. Connections that fail, such as a client
+    /// rejecting the certificate, are dropped without stopping the server.
+    pub async fn run(self) -> io::Result<()> {
+        loop {
+            let (stream, _) = self.listener.accept().await?;
+            let acceptor = self.acceptor.clone();
+            let routes = self.routes.clone();
+            tokio::spawn(async move {
+                let Ok(stream) = acceptor.accept(stream).await else {
+                    return;
+                };
+                let service = service_fn(move |request| {
+                    let routes = routes.clone();
+                    async move { Ok::<_, hyper::Error>(routes.respond(request).await) }
+                });
+                let _ = http1::Builder::new()
+                    .serve_connection(TokioIo::new(stream), service)
+                    .await;
+            });
+        }
+    }
+}
+
+/// Lockfile contents in the League client's `name:pid:port:password:protocol` format.
+pub fn lockfile(port: u16, password: &str) -> String {
+    format!(
+        "LeagueClient:{}:{port}:{password}:https",
+        std::process::id()
+    )
+}
+
+fn tls_config() -> io::Result<ServerConfig> {
+    let invalid = |err| io::Error::new(io::ErrorKind::InvalidData, err);
+    let certificates = CertificateDer::pem_slice_iter(CERTIFICATE)
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|err| invalid(err.to_string()))?;
+    let key = PrivateKeyDer::from_pem_slice(PRIVATE_KEY).map_err(|err| invalid(err.to_string()))?;
+
+    ServerConfig::builder_with_provider(Arc::new(rustls::crypto::ring::default_provider()))
+        .with_safe_default_protocol_versions()
+        .and_then(|builder| {
+            builder
+                .with_no_client_auth()
+                .with_single_cert(certificates, key)
+        })
+        .map_err(|err| invalid(err.to_string()))
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Segment {
+    Literal(String),
+    Variable(String),
+}
+
+#[derive(Debug)]
+struct Route {
+    method: Method,
+    segments: Vec<Segment>,
+    /// `None` for operations without a response body.
+    example: Option<Value>,
+}
+
+impl Route {
+    /// Path variables by name, if `path` matches this route.
+    fn matches<'a>(&self, path: &[&'a str]) -> Option<Vec<(&str, &'a str)>> {
+        if path.len() != self.segments.len() {
+            return None;
+        }
+        let mut variables = Vec::new();
+        for (segment, part) in self.segments.iter().zip(path) {
+            match segment {
+                Segment::Literal(literal) if literal == part => {}
+                Segment::Literal(_) => return None,
+                Segment::Variable(name) => variables.push((name.as_str(), *part)),
+            }
+        }
+        Some(variables)
+    }
+}
+
+struct Routes {
+    routes: Vec<Route>,
+    auth: String,
+}
+
+impl Routes {
+    fn new(spec: &OpenApiSpec, auth: String) -> Self {
+        let schemas = spec.components.get("schemas").and_then(Value::as_object);
+        let mut routes = Vec::new();
+        for (path, path_item) in spec.paths.iter() {
+            let segments = split(path)
+                .into_iter()
+                .map(
+                    |segment| match segment.strip_prefix('{').and_then(|s| s.strip_suffix('}')) {
+                        Some(name) => Segment::Variable(name.to_string()),
+                        None => Segment::Literal(segment.to_string()),
+                    },
+                )
+                .collect::<Vec<Segment>>();
+            for (method, operation) in path_item.as_object().into_iter().flatten() {
+                let Ok(method) = method.to_uppercase().parse::<Method>() else {
+                    continue;
+                };
+                let example = operation
+                    .pointer("/responses/200/content/application~1json/schema")
+                    .map(|schema| example(schema, schemas));
+                routes.push(Route {
+                    method,
+                    segments: segments.clone(),
+                    example,
+                });
+            }
+        }
+        // Literal segments win over variables, so `/summoners/current` isn't
+        // answered by `/summoners/{id}`.
+        routes.sort_by_key(|route| {
+            let literals = route
+                .segments
+                .iter()
+                .filter(|s| matches!(s, Segment::Literal(_)))
+                .count();
+            std::cmp::Reverse(literals)
+        });
+        Self { routes, auth }
+    }
+
+    async fn respond(&self, request: Request<Incoming>) -> Response<Full<Bytes>> {
+        let authorized = request
+            .headers()
+            .get(header::AUTHORIZATION)
+            .is_some_and(|value| value.as_bytes() == self.auth.as_bytes());
+        if !authorized {
+            return error(
+                StatusCode::UNAUTHORIZED,
+                "UNAUTHORIZED",
+                "Invalid credentials",
+            );
+        }
+
+        let method = request.method().clone();
+        let path = request.uri().path().to_string();
+        let query = request.uri().query().unwrap_or_default().to_string();
+        // Drain the body; the mock doesn't look at it.
+        let _ = request.into_body().collect().await;
+
+        let parts = split(&path);
+        let Some((route, variables)) = self
+            .routes
+            .iter()
+            .filter(|route| route.method == method)
+            .find_map(|route| route.matches(&parts).map(|variables| (route, variables)))
+        else {
+            return error(StatusCode::NOT_FOUND, "RPC_ERROR", "Invalid URI format");
+        };
+
+        let Some(example) = route.example.clone() else {
+            return response(StatusCode::NO_CONTENT, Bytes::new());
+        };
+        let arguments = variables.into_iter().chain(query_pairs(&query));
+        let body = serde_json::to_vec(&echo(example, arguments)).unwrap_or_default();
+        response(StatusCode::OK, Bytes::from(body))
+    }
+}
+
+fn split(path: &str) -> Vec<&str> {
+    path.split('/').filter(|part| !part.is_empty()).collect()
+}
+
+fn query_pairs(query: &str) -> impl Iterator<Item = (&str, &str)> {
+    query
+        .split('&')
+        .filter(|pair| !pair.is_empty())
+        .map(|pair| pair.split_once('=').unwrap_or((pair, "")))
+}
+
+/// Copy request arguments into top-level fields of the same name, keeping the
+/// example's type where the argument parses as it.
+fn echo<'a>(mut example: Value, arguments: impl Iterator<Item = (&'a str, &'a str)>) -> Value {
+    let Value::Object(fields) = &mut example else {
+        return example;
+    };
+    for (name, argument) in arguments {
+        let Some(field) = fields.get_mut(name) else {
+            continue;
+        };
+        let parsed = match field {
+            Value::Number(_) | Value::Bool(_) => serde_json::from_str(argument).ok(),
+            _ => Some(Value::String(argument.to_string())),
+        };
+        if let Some(parsed) = parsed {
+            *field = parsed;
+        }
+    }
+    example
+}
+
+fn response(status: StatusCode, body: Bytes) -> Response<Full<Bytes>> {
+    let mut response = Response::new(Full::new(body));
+    *response.status_mut() = status;
+    if status != StatusCode::NO_CONTENT {
+        response.headers_mut().insert(
+            header::CONTENT_TYPE,
+            header::HeaderValue::from_static("application/json"),
+        );
+    }
+    response
+}
+
+/// An error in the LCU's shape.
+fn error(status: StatusCode, code: &str, message: &str) -> Response<Full<Bytes>> {
+    let body = json!({
+        "errorCode": code,
+        "httpStatus": status.as_u16(),
+        "implementationDetails": Map::new(),
+        "message": message,
+    });
+    response(status, Bytes::from(body.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Read;
+
+    use poroshell_test_utils::fixtures::miniature_spec;
+
+    use super::*;
+
+    fn spec() -> OpenApiSpec {
+        miniature_spec("1.0.0")
+    }
+
+    async fn serve(options: &MockOptions) -> u16 {
+        let server = MockServer::bind(&spec(), options).await.unwrap();
+        let port = server.local_addr().unwrap().port();
+        tokio::spawn(server.run());
+        port
+    }
+
+    /// GET `path` with a client that trusts the self-signed certificate.
+    async fn get(port: u16, path: &str, password: &str) -> (u16, Value) {
+        let url = format!("https://127.0.0.1:{port}{path}");
+        let auth = format!(
+            "Basic {}",
+            BASE64_STANDARD.encode(format!("{USERNAME}:{password}"))
+        );
+        tokio::task::spawn_blocking(move || {
+            let agent: ureq::Agent = ureq::Agent::config_builder()
+                .tls_config(
+                    ureq::tls::TlsConfig::builder()
+                        .disable_verification(true)
+                        .build(),
+                )
+                .http_status_as_error(false)
+                .build()
+                .into();
+            let mut response = agent
+                .get(&url)
+                .header("Authorization", &auth)
+                .call()
+                .unwrap();
+            let mut body = String::new();
+            response
+                .body_mut()
+                .as_reader()
+                .read_to_string(&mut body)
+                .unwrap();
+            let json = serde_json::from_str(&body).unwrap_or(Value::Null);
+            (response.status().as_u16(), json)
+        })
+        .await
+        .unwrap()
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_example_response() {
+        let options = MockOptions::default();
+        let port = serve(&options).await;
+
+        let (status, body) = get(port, "/lol-summoner/v1/summoners/42", &options.password).await;
+        assert_eq!(status, 200);
+        assert_eq!(body["displayName"], "");
+        assert_eq!(body["accountId"], 0);
+        assert_eq!(body["privacy"], "PRIVATE");
+
+        // Arguments are echoed into fields with the same name.
+        let path = "/lol-summoner/v1/current-summoner?displayName=Poro&accountId=7";
+        let (status, body) = get(port, path, &options.password).await;
+        assert_eq!(status, 200);
+        assert_eq!(body["displayName"], "Poro");
+        assert_eq!(body["accountId"], 7);
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_wrong_token() {
+        let port = serve(&MockOptions::default()).await;
+        let (status, body) = get(port, "/lol-summoner/v1/current-summoner", "wrong").await;
+        assert_eq!(status, 401);
+        assert_eq!(body["httpStatus"], 401);
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_unknown_path() {
+        let options = MockOptions::default();
+        let port = serve(&options).await;
+        let (status, body) = get(port, "/lol-nothing/v1/here", &options.password).await;
+        assert_eq!(status, 404);
+        assert_eq!(body["errorCode"], "RPC_ERROR");
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_lockfile() {
+        let path = std::env::temp_dir().join(format!("poroshell-mock-{}.lock", std::process::id()));
+        let options = MockOptions {
+            lockfile: Some(path.clone()),
+            ..Default::default()
+        };
+        let port = serve(&options).await;
+
+        let contents = std::fs::read_to_string(&path).unwrap();
+        let fields = contents.split(':').collect::<Vec<&str>>();
+        assert_eq!(fields[0], "LeagueClient");
+        assert_eq!(fields[2], port.to_string());
+        assert_eq!(fields[3], options.password);
+        assert_eq!(fields[4], "https");
+        std::fs::remove_file(&path).unwrap();
+    }
+}
@@ -0,0 +1,38 @@
+//! `poroshell-mock <spec.json> [--port N] [--password P] [--lockfile PATH]`
+
+use std::{error::Error, fs::File, io::BufReader, path::PathBuf};
+
+use poro_mock::{MockOptions, MockServer, USERNAME};
+use poro_schema::openapi::OpenApiSpec;
+
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn Error>> {
+    let mut args = std::env::args().skip(1);
+    let mut spec_path = None;
+    let mut options = MockOptions::default();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--port" => options.port = args.next().ok_or("--port needs a value")?.parse()?,
+            "--password" => options.password = args.next().ok_or("--password needs a value")?,
+            "--lockfile" => {
+                options.lockfile = Some(PathBuf::from(
+                    args.next().ok_or("--lockfile needs a value")?,
+                ));
+            }
+            _ => spec_path = Some(arg),
+        }
+    }
+    let spec_path = spec_path
+        .ok_or("usage: poroshell-mock <spec.json> [--port N] [--password P] [--lockfile PATH]")?;
+
+    let spec: OpenApiSpec = serde_json::from_reader(BufReader::new(File::open(spec_path)?))?;
+    let server = MockServer::bind(&spec, &options).await?;
+    println!(
+        "Serving {} on https://127.0.0.1:{} as {USERNAME}:{}",
+        spec.info.title,
+        server.local_addr()?.port(),
+        options.password
+    );
+    server.run().await?;
+    Ok(())
+}
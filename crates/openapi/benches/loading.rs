@@ -0,0 +1,54 @@
+//! Summary vs full parse of a spec.
+//!
+//! Run with `cargo bench -p openapi`. The large input is the miniature fixture
+//! with its components and paths copied [`SCALE`] times, which is roughly the
+//! size of lcu.json.
+//!
+//! Baseline medians from one run; only compare numbers taken on the same machine.
+//!
+//! | bench     | miniature | large (x500) |
+//! |-----------|-----------|--------------|
+//! | summarize | 7.9 µs    | 2.2 ms       |
+//! | hydrate   | 15 µs     | 9.6 ms       |
+
+use criterion::{BenchmarkId, Criterion, criterion_group, criterion_main};
+use openapi::reader::{hydrate, summarize};
+use serde_json::{Map, Value};
+
+const MINIATURE: &[u8] = poroshell_test_utils::fixtures::MINIATURE_SPEC.as_bytes();
+
+const SCALE: usize = 500;
+
+fn inflate(bytes: &[u8], n: usize) -> Vec<u8> {
+    let mut document: Value = serde_json::from_slice(bytes).unwrap();
+    let copy = |section: &Value| {
+        let section = section.as_object().unwrap();
+        (0..n)
+            .flat_map(|i| {
+                section
+                    .iter()
+                    .map(move |(key, value)| (format!("{key}{i}"), value.clone()))
+            })
+            .collect::<Map<String, Value>>()
+    };
+    document["components"]["schemas"] = Value::Object(copy(&document["components"]["schemas"]));
+    document["paths"] = Value::Object(copy(&document["paths"]));
+    serde_json::to_vec(&document).unwrap()
+}
+
+fn loading(c: &mut Criterion) {
+    let large = inflate(MINIATURE, SCALE);
+    let mut group = c.benchmark_group("loading");
+    for (size, bytes) in [("miniature", MINIATURE), ("large", large.as_slice())] {
+        group.bench_with_input(BenchmarkId::new("summarize", size), bytes, |b, bytes| {
+            b.iter(|| summarize(bytes).unwrap())
+        });
+        group.bench_with_input(BenchmarkId::new("hydrate", size), bytes, |b, bytes| {
+            b.iter(|| hydrate(bytes).unwrap())
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, loading);
+criterion_main!(benches);
@@ -1,5 +1,6 @@
 pub mod error;
 pub mod reader;
+pub mod summary;
 pub mod types;
 
 #[cfg(test)]
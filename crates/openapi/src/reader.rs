@@ -1,11 +1,31 @@
 use std::io::Read;
 
-use crate::{error::Error, types::Document};
+use crate::{error::Error, summary::DocumentSummary, types::Document};
 
 pub fn load(uri: &str) -> Result<Document, Error> {
+    hydrate(&fetch(uri)?)
+}
+
+/// Download a document without parsing it, so it can be summarized first and
+/// hydrated later from the same bytes.
+pub fn fetch(uri: &str) -> Result<Vec<u8>, Error> {
     let mut bytes = Vec::new();
     let res = ureq::get(uri).call().map_err(Error::Ureq)?;
     let (_, body) = res.into_parts();
     let _ = body.into_reader().read_to_end(&mut bytes);
-    serde_json::from_slice::<Document>(&bytes).map_err(Error::SerdeJson)
+    Ok(bytes)
+}
+
+/// Load only what the endpoint list needs. See [`DocumentSummary`].
+pub fn load_paths_only(uri: &str) -> Result<DocumentSummary, Error> {
+    summarize(&fetch(uri)?)
+}
+
+pub fn summarize(bytes: &[u8]) -> Result<DocumentSummary, Error> {
+    serde_json::from_slice::<DocumentSummary>(bytes).map_err(Error::SerdeJson)
+}
+
+/// Fully parse a document, components included.
+pub fn hydrate(bytes: &[u8]) -> Result<Document, Error> {
+    serde_json::from_slice::<Document>(bytes).map_err(Error::SerdeJson)
 }
@@ -0,0 +1,90 @@
+use hashlink::LinkedHashMap;
+use serde_derive::Deserialize;
+
+use crate::types::{Document, Info, Tag};
+
+/// The parts of a [`Document`] needed to list endpoints.
+///
+/// Deserializing this skips components and operation bodies entirely, which
+/// makes it much cheaper than a full parse of a large spec.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct DocumentSummary {
+    pub info: Info,
+    pub paths: LinkedHashMap<String, LinkedHashMap<String, OperationSummary>>,
+    pub tags: Option<Vec<Tag>>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OperationSummary {
+    pub operation_id: String,
+    pub summary: Option<String>,
+    pub tags: Vec<String>,
+}
+
+impl From<&Document> for DocumentSummary {
+    fn from(document: &Document) -> Self {
+        let paths = document
+            .paths
+            .iter()
+            .map(|(path, item)| {
+                let operations = item
+                    .iter()
+                    .map(|(method, operation)| {
+                        let summary = OperationSummary {
+                            operation_id: operation.operation_id.clone(),
+                            summary: operation.summary.clone(),
+                            tags: operation.tags.clone(),
+                        };
+                        (method.clone(), summary)
+                    })
+                    .collect();
+                (path.clone(), operations)
+            })
+            .collect();
+
+        Self {
+            info: document.info.clone(),
+            paths,
+            tags: document.tags.clone(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::reader::{hydrate, summarize};
+
+    const MINIATURE: &[u8] = poroshell_test_utils::fixtures::MINIATURE_SPEC.as_bytes();
+
+    #[test]
+    fn test_summary_matches_document() {
+        let summary = summarize(MINIATURE).unwrap();
+        let document = hydrate(MINIATURE).unwrap();
+        assert_eq!(summary, DocumentSummary::from(&document));
+
+        let methods = summary.paths["/lol-lobby/v2/lobby"]
+            .keys()
+            .map(String::as_str)
+            .collect::<Vec<&str>>();
+        assert_eq!(methods, ["get", "post"]);
+        assert_eq!(
+            summary.paths["/lol-lobby/v2/lobby"]["get"]
+                .summary
+                .as_deref(),
+            Some("Get the current lobby.")
+        );
+    }
+
+    #[test]
+    fn test_summary_ignores_components() {
+        // Components that wouldn't pass the strict full parse don't matter here.
+        let mut json: serde_json::Value = serde_json::from_slice(MINIATURE).unwrap();
+        json["components"]["schemas"]["Broken"] = serde_json::json!({ "nullable": true });
+        let bytes = serde_json::to_vec(&json).unwrap();
+
+        assert!(hydrate(&bytes).is_err());
+        assert_eq!(summarize(&bytes).unwrap(), summarize(MINIATURE).unwrap());
+    }
+}
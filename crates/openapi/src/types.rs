@@ -19,6 +19,12 @@ pub struct Document {
     pub paths: Paths,
     pub components: Components,
     pub tags: Option<Vec<Tag>>,
+    /// Workflow names to their operationIds.
+    #[serde(rename = "x-workflows")]
+    pub workflows: Option<LinkedHashMap<String, Vec<String>>>,
+    /// Websocket event names to their payloads.
+    #[serde(rename = "x-events")]
+    pub events: Option<LinkedHashMap<String, Event>>,
 }
 
 pub type Paths = LinkedHashMap<String, PathItem>;
@@ -41,20 +47,51 @@ pub struct Schema {
     pub description: Option<String>,
     #[serde(rename = "$ref")]
     pub schema_ref: Option<String>,
+    /// Names, or numbers for numeric enums.
     #[serde(rename = "enum")]
-    pub schema_enum: Option<Vec<String>>,
+    pub schema_enum: Option<Vec<serde_json::Value>>,
+    /// Names of a numeric enum's values.
+    #[serde(rename = "x-enum-varnames")]
+    pub enum_varnames: Option<Vec<String>>,
+    #[serde(rename = "x-enum-description")]
+    pub enum_description: Option<Vec<String>>,
     pub additional_properties: Option<Box<AdditionalProperties>>,
     pub properties: Option<LinkedHashMap<String, Schema>>,
     pub items: Option<Box<Schema>>,
     pub required: Option<Vec<String>>,
 }
 
+/// An empty schema, `{}`, allows anything and is read as `true`.
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(untagged)]
 #[serde(deny_unknown_fields)]
+#[serde(from = "WrittenAdditionalProperties")]
 pub enum AdditionalProperties {
     Bool(bool),
-    Schema(Schema),
+    Schema(Box<Schema>),
+}
+
+/// `additionalProperties` as written, before `{}` is read as `true`.
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum WrittenAdditionalProperties {
+    Bool(bool),
+    Empty(EmptySchema),
+    Schema(Box<Schema>),
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct EmptySchema {}
+
+impl From<WrittenAdditionalProperties> for AdditionalProperties {
+    fn from(written: WrittenAdditionalProperties) -> Self {
+        match written {
+            WrittenAdditionalProperties::Bool(allowed) => Self::Bool(allowed),
+            WrittenAdditionalProperties::Empty(EmptySchema {}) => Self::Bool(true),
+            WrittenAdditionalProperties::Schema(schema) => Self::Schema(schema),
+        }
+    }
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
@@ -99,11 +136,18 @@ pub struct Info {
 pub struct Operation {
     pub description: Option<String>,
     pub operation_id: String,
+    #[serde(default)]
     pub parameters: Vec<Parameter>,
     pub responses: Option<LinkedHashMap<String, Responses>>,
     pub summary: Option<String>,
     pub tags: Vec<String>,
     pub request_body: Option<RequestBody>,
+    /// Usage notes from the LCU console help.
+    #[serde(rename = "x-console-help")]
+    pub console_help: Option<String>,
+    /// `experimental` or `stable`, where the generator classified it.
+    #[serde(rename = "x-stability")]
+    pub stability: Option<String>,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
@@ -128,25 +172,40 @@ pub struct Parameter {
 pub enum In {
     Path,
     Query,
+    Header,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(deny_unknown_fields)]
 pub struct RequestBody {
     pub content: Content,
+    pub required: Option<bool>,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(deny_unknown_fields)]
 pub struct Content {
     #[serde(rename = "application/json")]
-    pub application_json: ApplicationJson,
+    pub application_json: Option<ApplicationJson>,
+    /// Bare string bodies, such as the builtin remoting endpoints take.
+    #[serde(rename = "text/plain")]
+    pub text_plain: Option<TextPlain>,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(deny_unknown_fields)]
 pub struct ApplicationJson {
     pub schema: Option<Schema>,
+    /// A synthetic example shaped like the schema.
+    pub example: Option<serde_json::Value>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct TextPlain {
+    pub schema: Option<Schema>,
+    /// A synthetic example shaped like the schema.
+    pub example: Option<serde_json::Value>,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
@@ -160,4 +219,33 @@ pub struct Responses {
 #[serde(deny_unknown_fields)]
 pub struct Tag {
     pub name: String,
+    /// A name for people, where `name` is what operations refer to.
+    #[serde(rename = "x-display-name")]
+    pub display_name: Option<String>,
+}
+
+/// A websocket event, such as `OnJsonApiEvent_lol-lobby_v2_lobby`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Event {
+    pub description: Option<String>,
+    pub tags: Option<Vec<String>>,
+    pub schema: Schema,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_additional_properties_forms() {
+        let read = |json: &str| serde_json::from_str::<AdditionalProperties>(json).unwrap();
+        assert_eq!(read("{}"), AdditionalProperties::Bool(true));
+        assert_eq!(read("true"), AdditionalProperties::Bool(true));
+        assert_eq!(read("false"), AdditionalProperties::Bool(false));
+        assert!(matches!(
+            read(r#"{ "type": "string" }"#),
+            AdditionalProperties::Schema(schema) if schema.schema_type == Some(Type::String)
+        ));
+    }
 }
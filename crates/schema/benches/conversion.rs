@@ -0,0 +1,174 @@
+//! Benchmarks for the conversion and serialization hot paths.
+//!
+//! Run with `cargo bench -p poro_schema`. The large inputs are the miniature
+//! fixture scaled up by [`inflate`], so no multi-MB help dump is needed.
+//!
+//! To measure a change, save a baseline before it and compare against that
+//! after, on the same machine:
+//!
+//! ```text
+//! cargo bench -p poro_schema -- --save-baseline before
+//! cargo bench -p poro_schema -- --baseline before
+//! ```
+
+use criterion::{ BenchmarkId, Criterion, criterion_group, criterion_main };
+use poro_schema::{
+    convert::{ components_from, normalize_path, paths_from },
+    help::ExtendedHelp,
+    options::SchemaOptions,
+    prelude::OpenApiSpec,
+    report::SchemaReport,
+    style::SerializationStyle,
+};
+use poroshell_test_utils::assert_valid_openapi;
+use serde_json::Value;
+
+const MINIATURE: &str = poroshell_test_utils::fixtures::MINIATURE_HELP;
+
+/// Times the miniature fixture is copied into the large input.
+const SCALE: usize = 250;
+
+/// Copy every type, endpoint, and event in `help` `n` times under distinct
+/// names, paths, and plugin tags, keeping references between copies intact.
+fn inflate(help: &str, n: usize) -> String {
+    let help: Value = serde_json::from_str(help).unwrap();
+    let names = help["types"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|ty| ty["name"].as_str().unwrap().to_string())
+        .collect::<Vec<String>>();
+
+    let rename = |value: &mut Value, i: usize| {
+        if let Some(name) = value.as_str() && names.iter().any(|n| n == name) {
+            *value = Value::String(format!("{name}{i}"));
+        }
+    };
+    let retype = |data_type: &mut Value, i: usize| {
+        rename(&mut data_type["type"], i);
+        rename(&mut data_type["elementType"], i);
+    };
+    let copy = |section: &str, i: usize| {
+        help[section]
+            .as_array()
+            .unwrap()
+            .iter()
+            .cloned()
+            .map(move |mut item| {
+                let name = item["name"].as_str().unwrap();
+                item["name"] = Value::String(format!("{name}{i}"));
+                for field in item["fields"].as_array_mut().into_iter().flatten() {
+                    retype(&mut field["type"], i);
+                }
+                for arg in item["arguments"].as_array_mut().into_iter().flatten() {
+                    retype(&mut arg["type"], i);
+                }
+                for key in ["returns", "type"] {
+                    if item[key].is_object() {
+                        retype(&mut item[key], i);
+                    }
+                }
+                if let Some(path) = item["path"].as_str() {
+                    item["path"] = Value::String(path.replacen('/', &format!("/copy{i}-"), 1));
+                }
+                for tag in item["tags"].as_array_mut().into_iter().flatten() {
+                    if let Some(plugin) = tag.as_str().and_then(|t| t.strip_prefix("Plugin ")) {
+                        *tag = Value::String(format!("Plugin copy{i}-{plugin}"));
+                    }
+                }
+                item
+            })
+            .collect::<Vec<Value>>()
+    };
+
+    let mut inflated = serde_json::Map::new();
+    for section in ["types", "endpoints", "events"] {
+        let items = (0..n).flat_map(|i| copy(section, i)).collect::<Vec<Value>>();
+        inflated.insert(section.to_string(), Value::Array(items));
+    }
+    serde_json::to_string(&inflated).unwrap()
+}
+
+fn inputs() -> [(&'static str, String); 2] {
+    [
+        ("miniature", MINIATURE.to_string()),
+        ("large", inflate(MINIATURE, SCALE)),
+    ]
+}
+
+fn deserialize(c: &mut Criterion) {
+    let mut group = c.benchmark_group("deserialize");
+    for (name, json) in inputs() {
+        group.bench_with_input(BenchmarkId::from_parameter(name), &json, |b, json| {
+            b.iter(|| json.parse::<ExtendedHelp>().unwrap())
+        });
+    }
+    group.finish();
+}
+
+fn convert(c: &mut Criterion) {
+    let options = SchemaOptions::default();
+    let mut group = c.benchmark_group("convert");
+    for (name, json) in inputs() {
+        let help: ExtendedHelp = json.parse().unwrap();
+        group.bench_with_input(BenchmarkId::new("components_from", name), &help, |b, help| {
+            b.iter(|| components_from(help, &options, &mut SchemaReport::default()).unwrap())
+        });
+        group.bench_with_input(BenchmarkId::new("paths_from", name), &help, |b, help| {
+            b.iter(|| paths_from(help, &options, &mut SchemaReport::default()).unwrap())
+        });
+    }
+    group.finish();
+}
+
+fn normalize(c: &mut Criterion) {
+    let mut group = c.benchmark_group("normalize_path");
+    for (name, json) in inputs() {
+        let help: ExtendedHelp = json.parse().unwrap();
+        let paths = help.endpoints
+            .iter()
+            .filter_map(|endpoint| endpoint.path.clone())
+            .collect::<Vec<String>>();
+        group.bench_with_input(BenchmarkId::from_parameter(name), &paths, |b, paths| {
+            b.iter(|| {
+                for path in paths.iter() {
+                    let _ = normalize_path(path);
+                }
+            })
+        });
+    }
+    group.finish();
+}
+
+fn serialize(c: &mut Criterion) {
+    let mut group = c.benchmark_group("serialize");
+    for (name, json) in inputs() {
+        let help: ExtendedHelp = json.parse().unwrap();
+        let spec = OpenApiSpec::from_help(&help, "1.0.0").unwrap();
+        for (style_name, style) in [
+            ("standard", SerializationStyle::Standard),
+            ("hasagi_compat", SerializationStyle::HasagiCompat),
+        ] {
+            group.bench_with_input(BenchmarkId::new(style_name, name), &spec, |b, spec| {
+                b.iter(|| spec.to_string_styled(style).unwrap())
+            });
+        }
+    }
+    group.finish();
+}
+
+/// Resolving every `$ref` in the converted spec.
+fn validate_refs(c: &mut Criterion) {
+    let mut group = c.benchmark_group("validate_refs");
+    for (name, json) in inputs() {
+        let help: ExtendedHelp = json.parse().unwrap();
+        let spec = serde_json::to_value(OpenApiSpec::from_help(&help, "1.0.0").unwrap()).unwrap();
+        group.bench_with_input(BenchmarkId::from_parameter(name), &spec, |b, spec| {
+            b.iter(|| assert_valid_openapi(spec))
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, deserialize, convert, normalize, serialize, validate_refs);
+criterion_main!(benches);
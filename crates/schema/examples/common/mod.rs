@@ -0,0 +1,52 @@
+//! Argument parsing shared by the examples, so they don't need a CLI crate.
+
+// Each example uses a different part of this.
+#![allow(dead_code)]
+
+use std::process;
+
+/// Positional arguments and `--switches`, in order.
+pub struct Args {
+    pub positional: Vec<String>,
+    pub switches: Vec<String>,
+}
+
+impl Args {
+    pub fn parse() -> Self {
+        Self::from_iter(std::env::args().skip(1))
+    }
+
+    pub fn from_iter(args: impl IntoIterator<Item = String>) -> Self {
+        let (switches, positional) = args.into_iter().partition(|arg| arg.starts_with("--"));
+        Self { positional, switches }
+    }
+
+    pub fn switch(&self, name: &str) -> bool {
+        self.switches.iter().any(|switch| switch.trim_start_matches("--") == name)
+    }
+
+    /// The value of a `--name=value` switch.
+    pub fn value(&self, name: &str) -> Option<&str> {
+        self.switches
+            .iter()
+            .find_map(|switch| switch.trim_start_matches("--").strip_prefix(name)?.strip_prefix('='))
+    }
+
+    /// The values of every `--name=value` switch, for ones that can repeat.
+    pub fn values<'a>(&'a self, name: &'a str) -> impl Iterator<Item = &'a str> {
+        self.switches
+            .iter()
+            .filter_map(move |switch| switch.trim_start_matches("--").strip_prefix(name)?.strip_prefix('='))
+    }
+
+    /// The positional argument at `index`, or exit with `usage`.
+    pub fn required(&self, index: usize, usage: &str) -> &str {
+        match self.positional.get(index) {
+            Some(arg) => arg,
+            None => {
+                eprintln!("usage: {usage}");
+                process::exit(2);
+            }
+        }
+    }
+}
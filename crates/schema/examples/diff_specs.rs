@@ -0,0 +1,70 @@
+//! Print which operations and components were added or removed between two specs.
+//!
+//! ```sh
+//! cargo run -p poro_schema --example diff_specs -- old/openapi.json new/openapi.json
+//! ```
+//!
+//! `--lenient` compares what loads of specs that don't fit the spec types,
+//! after listing every part that didn't.
+//!
+//! Parameters that look renamed are listed too; `--rename-threshold=0.5`
+//! reports less certain renames, see `RenameDetection`.
+//!
+//! `--json` prints every change down to single schema keywords as JSON
+//! instead, see `SpecDiff`.
+//!
+//! `--level=help` compares two `extended-help.json` files instead, to tell
+//! changes to the LCU apart from changes to the generator:
+//!
+//! ```sh
+//! cargo run -p poro_schema --example diff_specs -- old/extended-help.json new/extended-help.json --level=help
+//! ```
+
+mod common;
+
+use std::fs;
+
+use poro_schema::{ error::Error, prelude::*, renames::{ self, RenameDetection }, since::RunRecord };
+
+fn load(path: &str, lenient: bool) -> Result<OpenApiSpec, Error> {
+    let json = fs::read_to_string(path)?;
+    if !lenient {
+        return Ok(serde_json::from_str(&json)?);
+    }
+    let (partial, errors) = OpenApiSpec::from_value_lenient(serde_json::from_str(&json)?);
+    for error in errors.iter() {
+        eprintln!("{path}: {error}");
+    }
+    Ok(partial.into_spec())
+}
+
+fn main() -> Result<(), Error> {
+    let args = common::Args::parse();
+    let usage = "diff_specs <old openapi.json> <new openapi.json> [--lenient] [--rename-threshold=<0-1>] [--json] [--level=help]";
+    if args.value("level") == Some("help") {
+        let old: ExtendedHelp = fs::read_to_string(args.required(0, usage))?.parse()?;
+        let new: ExtendedHelp = fs::read_to_string(args.required(1, usage))?.parse()?;
+        print!("{}", old.diff(&new).to_markdown());
+        return Ok(());
+    }
+    let lenient = args.switch("lenient");
+    let old = load(args.required(0, usage), lenient)?;
+    let new = load(args.required(1, usage), lenient)?;
+    if args.switch("json") {
+        println!("{}", serde_json::to_string_pretty(&old.diff(&new))?);
+        return Ok(());
+    }
+    let mut detection = RenameDetection::default();
+    if let Some(threshold) = args.value("rename-threshold") {
+        match threshold.parse() {
+            Ok(threshold) => detection.threshold = threshold,
+            Err(_) => {
+                eprintln!("--rename-threshold takes a number between 0 and 1, got {threshold}");
+                std::process::exit(2);
+            }
+        }
+    }
+    print!("{}", RunRecord::from_spec(&old).changelog(&RunRecord::from_spec(&new)));
+    print!("{}", renames::changelog(&detection.detect(&old, &new)));
+    Ok(())
+}
@@ -0,0 +1,45 @@
+//! Show every step that made an operation look the way it does.
+//!
+//! ```sh
+//! cargo run -p poro_schema --example explain -- extended-help.json --operation=PostLolLobbyV2Lobby
+//! cargo run -p poro_schema --example explain -- extended-help.json --operation=PostLolLobbyV2Lobby --caution --workflows=workflows.json --patches=patches.json
+//! ```
+//!
+//! Patch sets are applied in the order given, as the trace's last steps.
+
+mod common;
+
+use std::{ fs, path::Path };
+
+use poro_schema::{
+    caution::CautionRules,
+    error::Error,
+    explain::explain,
+    options::SchemaOptions,
+    patchset::PatchSet,
+    prelude::*,
+    workflow::Workflows,
+};
+
+fn main() -> Result<(), Error> {
+    let args = common::Args::parse();
+    let usage = "explain <extended-help.json> --operation=<operationId> [--caution] [--workflows=<file>] [--patches=<file>]...";
+    let help: ExtendedHelp = fs::read_to_string(args.required(0, usage))?.parse()?;
+    let Some(operation_id) = args.value("operation") else {
+        eprintln!("usage: {usage}");
+        std::process::exit(2);
+    };
+
+    let options = SchemaOptions {
+        caution: args.switch("caution").then(CautionRules::default),
+        workflows: args.value("workflows").map(|path| Workflows::load(Path::new(path))).transpose()?,
+        ..Default::default()
+    };
+    let mut trace = explain(&help, "0.0.0", &options, operation_id)?;
+    for path in args.values("patches") {
+        let patches: PatchSet = serde_json::from_str(&fs::read_to_string(path)?)?;
+        trace.apply_patches(path, &patches)?;
+    }
+    println!("{}", serde_json::to_string_pretty(&trace)?);
+    Ok(())
+}
@@ -0,0 +1,112 @@
+//! Generate a spec from a running League client and write it with a report.
+//!
+//! ```sh
+//! cargo run -p poro_schema --example generate -- artifacts [--polite] [--overwrite] [--probe-latency] [--size-budget=10] [--concurrency=4]
+//! cargo run -p poro_schema --example generate -- --list-clients
+//! ```
+
+mod common;
+
+use std::time::Duration;
+
+use poro_schema::{
+    artifact::{ ArtifactWriter, OutputTemplate, TemplateVars },
+    discovery::{ discover, select, KnownInstallDirs },
+    caution::CautionRules,
+    error::Error,
+    latency::{ probe, probe_targets, LatencyAnnotations },
+    lcu,
+    options::SchemaOptions,
+    prelude::*,
+    report::{ ReportKind, SchemaReport },
+    servers::ServerRules,
+    since::{ RunHistory, RunRecord },
+    size::SizeBudget,
+    style::SerializationStyle,
+    throttle::Politeness,
+};
+
+/// Calls per operation when timing them with `--probe-latency`.
+const PROBE_ROUNDS: usize = 5;
+
+#[tokio::main(flavor = "current_thread")]
+async fn main() -> Result<(), Error> {
+    let args = common::Args::parse();
+    let instances = discover(&KnownInstallDirs::default());
+    if args.switch("list-clients") {
+        for instance in instances.iter() {
+            println!("{}", instance.label());
+        }
+        return Ok(());
+    }
+    let root = args.required(0, "generate <output dir> [--polite] [--overwrite] [--probe-latency] [--size-budget=<percent>] [--concurrency=<requests>]");
+
+    let mut options = SchemaOptions { servers: Some(ServerRules::default()), ..Default::default() };
+    if args.switch("polite") {
+        options.politeness = Politeness { min_delay: Duration::from_millis(20), ..Default::default() };
+    }
+    if let Some(concurrency) = args.value("concurrency") {
+        let Ok(concurrency) = concurrency.parse::<usize>() else {
+            eprintln!("--concurrency takes a number of requests, got {concurrency}");
+            std::process::exit(2);
+        };
+        options.politeness.concurrency = concurrency;
+    }
+
+    // irelia connects to whichever lockfile it finds first, so refuse to guess.
+    if !instances.is_empty() {
+        eprintln!("Using {}", select(&instances, None)?.label());
+    }
+    eprintln!("Fetching help from the client...");
+    let client = lcu()?;
+    let help = client.extended_help_with(&options).await?;
+    for entry in help.report.of_kind(ReportKind::Timing) {
+        eprintln!("{}", entry.detail);
+    }
+
+    eprintln!("Converting...");
+    let mut report = SchemaReport::default();
+    let mut spec = OpenApiSpec::from_help_with(&help, "0.0.0", &options, &mut report)?;
+    eprintln!("{}", report.summary_line());
+
+    if args.switch("probe-latency") {
+        // Merged into what the last run in this directory published.
+        let previous = std::fs::read_to_string(std::path::Path::new(root).join("lcu/openapi.json"))
+            .ok()
+            .and_then(|json| serde_json::from_str::<OpenApiSpec>(&json).ok());
+        let targets = probe_targets(&spec, &CautionRules::default());
+        eprintln!("Timing {} operations...", targets.len());
+        let client = &client;
+        let samples = probe(&targets, PROBE_ROUNDS, |path| async move { Ok(client.get::<serde_json::Value>(path).await?) }).await;
+        let annotated = LatencyAnnotations { samples: &samples, previous: previous.as_ref() }.apply(&mut spec);
+        eprintln!("Annotated {annotated} operations with expected latency");
+    }
+
+    // Sizes are measured last, on exactly what gets written, and kept with
+    // the run's record so the next run can compare against them.
+    if let Some(percent) = args.value("size-budget") {
+        let Ok(max_growth_percent) = percent.parse::<f64>() else {
+            eprintln!("--size-budget takes a percentage, got {percent}");
+            std::process::exit(2);
+        };
+        let history_path = std::path::Path::new(root).join("history.json");
+        let mut history = RunHistory::load(&history_path)?;
+        let sizes = SizeBudget { max_growth_percent }.apply(&spec, Some(&history), &mut report)?;
+        eprintln!("{} bytes; {}", sizes.total, report.summary_line());
+        history.push(RunRecord { sizes: Some(sizes), ..RunRecord::from_spec(&spec) });
+        history.save(&history_path)?;
+    }
+
+    let vars = TemplateVars { version: spec.info.version.clone(), target: "lcu".to_string(), ..Default::default() };
+    let mut writer = ArtifactWriter::new(root, vars).with_overwrite(args.switch("overwrite"));
+    let spec_path = writer.write_streaming(&OutputTemplate::parse("{target}/openapi.json")?, |out| {
+        spec.write_streaming(out, SerializationStyle::Standard)
+    })?;
+    writer.write(&OutputTemplate::parse("{target}/report.md")?, &report.to_markdown())?;
+    writer.write(&OutputTemplate::parse("{target}/report.json")?, &serde_json::to_string_pretty(&report)?)?;
+    // Published with the spec, so downloaders can check their copies.
+    writer.write_manifest(&OutputTemplate::parse("manifest.json")?)?;
+    eprintln!("Wrote {}", spec_path.display());
+    println!("{}", writer.manifest().to_json());
+    Ok(())
+}
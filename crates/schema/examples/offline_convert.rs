@@ -0,0 +1,118 @@
+//! Convert a saved `extended-help.json` to OpenAPI without a running client.
+//!
+//! ```sh
+//! cargo run -p poro_schema --example offline_convert -- extended-help.json > openapi.json
+//! cargo run -p poro_schema --example offline_convert -- extended-help.json --hasagi-compat > hasagi-swagger.json
+//! cargo run -p poro_schema --example offline_convert -- extended-help.json --fragment=blitz.json > openapi.json
+//! cargo run -p poro_schema --example offline_convert -- extended-help.json --renames=renames.json > openapi.json
+//! cargo run -p poro_schema --example offline_convert -- extended-help.json --format=json-schema --root=LolSummonerSummoner > summoner.schema.json
+//! ```
+
+mod common;
+
+use std::{ fs, io::Write, path::Path };
+
+use poro_schema::{
+    compat::HasagiCompat,
+    component_renames::ComponentRenames,
+    error::Error,
+    fragment::SpecFragment,
+    json_schema::{ self, JsonSchemaOptions },
+    options::SchemaOptions,
+    prelude::*,
+    report::SchemaReport,
+    since::RunRecord,
+    style::SerializationStyle,
+};
+
+/// Parse help JSON and convert it, keeping what the conversion reported.
+fn convert(json: &str, version: &str, options: &SchemaOptions) -> Result<(OpenApiSpec, SchemaReport), Error> {
+    let help: ExtendedHelp = json.parse()?;
+    let mut report = SchemaReport::default();
+    let spec = OpenApiSpec::from_help_with(&help, version, options, &mut report)?;
+    Ok((spec, report))
+}
+
+fn main() -> Result<(), Error> {
+    let args = common::Args::parse();
+    let path = args.required(
+        0,
+        "offline_convert <extended-help.json> [version] [--hasagi | --hasagi-compat] [--fragment=<path>]... [--renames=<path>] [--format=openapi | --format=json-schema [--root=<component>]]"
+    );
+    let version = args.positional.get(1).map_or("0.0.0", String::as_str);
+    let style = if args.switch("hasagi") { SerializationStyle::HasagiCompat } else { SerializationStyle::Standard };
+
+    let json = fs::read_to_string(path)?;
+    if args.switch("hasagi-compat") {
+        let mut report = SchemaReport::default();
+        let output = HasagiCompat::convert(&json.parse()?, version, &mut report)?;
+        eprintln!("{}", report.summary_line());
+        println!("{output}");
+        return Ok(());
+    }
+
+    let options = SchemaOptions {
+        component_renames: args.value("renames").map(|path| ComponentRenames::load(Path::new(path))).transpose()?,
+        ..Default::default()
+    };
+    let (mut spec, report) = convert(&json, version, &options)?;
+    for path in args.values("fragment") {
+        let fragment = SpecFragment::from_path(path)?;
+        spec.merge_fragment(&fragment)?;
+        eprintln!("merged fragment {} from {path}", fragment.namespace);
+    }
+    let record = RunRecord::from_spec(&spec);
+    eprintln!(
+        "{} paths, {} operations, {} components",
+        spec.paths.len(),
+        record.operations.len(),
+        record.components.len()
+    );
+    eprintln!("{}", report.summary_line());
+    match args.value("format").unwrap_or("openapi") {
+        "openapi" => {
+            let mut stdout = std::io::stdout().lock();
+            spec.write_streaming(&mut stdout, style)?;
+            writeln!(stdout)?;
+        }
+        "json-schema" => {
+            let opts = JsonSchemaOptions { root: args.value("root").map(str::to_string), ..Default::default() };
+            if let Some(root) = json_schema::missing_root(&spec, &opts) {
+                eprintln!("no component named {root}");
+                std::process::exit(2);
+            }
+            println!("{}", serde_json::to_string_pretty(&json_schema::components_to_json_schema_bundle(&spec, &opts))?);
+        }
+        format => {
+            eprintln!("--format is openapi or json-schema, got {format}");
+            std::process::exit(2);
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_convert_miniature() {
+        let (spec, _) = convert(poroshell_test_utils::fixtures::MINIATURE_HELP, "1.0.0", &SchemaOptions::default()).unwrap();
+        poroshell_test_utils::assert_valid_openapi(&serde_json::to_value(&spec).unwrap());
+        assert_eq!(spec.info.version, "1.0.0");
+        let mut paths = spec.paths.keys().map(String::as_str).collect::<Vec<&str>>();
+        paths.sort();
+        assert_eq!(
+            paths,
+            [
+                "/Help",
+                "/Subscribe",
+                "/lol-lobby/v2/lobby",
+                "/lol-summoner/v1/current-summoner",
+                "/lol-summoner/v1/summoners",
+                "/lol-summoner/v1/summoners/{id}",
+            ]
+        );
+        assert_eq!(RunRecord::from_spec(&spec).components.len(), 6);
+    }
+}
@@ -0,0 +1,53 @@
+//! Run the whole patch-day sequence against a running League client and
+//! publish the set under `<output dir>/<version>`.
+//!
+//! ```sh
+//! cargo run -p poro_schema --example patch_day -- artifacts --baseline=artifacts/14.3.1/lcu/openapi.json [--polite] [--force]
+//! ```
+//!
+//! When a stage fails, what the earlier stages wrote stays in
+//! `<output dir>/.staging-<version>`.
+
+mod common;
+
+use std::{ fs, time::Duration };
+
+use poro_schema::{
+    drift::DriftGate,
+    error::Error,
+    lcu,
+    options::SchemaOptions,
+    patch_day::PatchDay,
+    prelude::*,
+    servers::ServerRules,
+    throttle::Politeness,
+};
+
+#[derive(serde::Deserialize)]
+struct Build {
+    version: String,
+}
+
+#[tokio::main(flavor = "current_thread")]
+async fn main() -> Result<(), Error> {
+    let args = common::Args::parse();
+    let root = args.required(0, "patch_day <output dir> [--baseline=<openapi.json>] [--polite] [--force]");
+
+    let mut patch_day = PatchDay::new(root);
+    patch_day.options = SchemaOptions { servers: Some(ServerRules::default()), ..Default::default() };
+    if args.switch("polite") {
+        patch_day.options.politeness = Politeness { min_delay: Duration::from_millis(20), ..Default::default() };
+    }
+    patch_day.gate = DriftGate { force: args.switch("force"), ..Default::default() };
+    if let Some(path) = args.value("baseline") {
+        patch_day.baseline = Some(serde_json::from_str(&fs::read_to_string(path)?)?);
+    }
+
+    let client = lcu()?;
+    let Build { version } = client.get("/system/v1/builds").await?;
+    eprintln!("Running patch day for {version}...");
+    let summary = patch_day.run(client.extended_help_with(&patch_day.options), &version).await.map_err(Box::new)?;
+    eprintln!("Published {}", summary.published.display());
+    print!("{}", summary.to_markdown());
+    Ok(())
+}
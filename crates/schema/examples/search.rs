@@ -0,0 +1,33 @@
+//! Find operations in a spec with a structured query.
+//!
+//! ```sh
+//! cargo run -p poro_schema --example search -- openapi.json "method:post tag:lol-lobby returns:LolLobbyLobbyDto"
+//! cargo run -p poro_schema --example search -- openapi.json "param:query.puuid! ~tag:lol-summoner" --json
+//! ```
+//!
+//! See `Query` for the syntax. Hits are listed best first, one per line,
+//! with the clauses they matched; `--json` prints them as JSON instead.
+
+mod common;
+
+use std::fs;
+
+use poro_schema::{ error::Error, prelude::*, search::{ self, Query } };
+
+fn main() -> Result<(), Error> {
+    let args = common::Args::parse();
+    let usage = "search <openapi.json> <query> [--json]";
+    let spec: OpenApiSpec = serde_json::from_str(&fs::read_to_string(args.required(0, usage))?)?;
+    let query: Query = args.required(1, usage).parse()?;
+
+    let hits = search::query(&spec, &query);
+    if args.switch("json") {
+        println!("{}", serde_json::to_string_pretty(&hits)?);
+        return Ok(());
+    }
+    for hit in hits.iter() {
+        println!("{:<7} {} {} [{}]", hit.method, hit.path, hit.operation_id, hit.matched.join(" "));
+    }
+    eprintln!("{} operations", hits.len());
+    Ok(())
+}
@@ -0,0 +1,32 @@
+//! Turn hand edits of a generated spec into patches for the next run.
+//!
+//! ```sh
+//! cargo run -p poro_schema --example spec_patches -- openapi.json edited.json > patches.json
+//! ```
+//!
+//! Edits that can't be written as patches are listed on stderr.
+
+mod common;
+
+use std::fs;
+
+use poro_schema::{ error::Error, patchset::{ DEFAULT_COMPACT_THRESHOLD, diff_to_patches } };
+use serde_json::Value;
+
+fn load(path: &str) -> Result<Value, Error> {
+    Ok(serde_json::from_str(&fs::read_to_string(path)?)?)
+}
+
+fn main() -> Result<(), Error> {
+    let args = common::Args::parse();
+    let usage = "spec_patches <generated openapi.json> <edited openapi.json>";
+    let generated = load(args.required(0, usage))?;
+    let edited = load(args.required(1, usage))?;
+
+    let diff = diff_to_patches(&generated, &edited, DEFAULT_COMPACT_THRESHOLD);
+    for unsupported in diff.unsupported.iter() {
+        eprintln!("unsupported: {unsupported}");
+    }
+    println!("{}", serde_json::to_string_pretty(&diff.patches)?);
+    Ok(())
+}
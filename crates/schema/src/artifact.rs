@@ -0,0 +1,340 @@
+//! Writing generated files under templated names.
+
+use std::{ fmt, fs, io::{ self, Write }, path::{ Path, PathBuf } };
+
+use serde::{ Deserialize, Serialize };
+use sha2::{ Digest, Sha256 };
+
+#[cfg(feature = "core")]
+use crate::capabilities::{ CapabilityReport, capabilities };
+use crate::{ error::Error, migrate::{ self, Artifact } };
+
+/// Placeholders an [`OutputTemplate`] may use.
+pub const PLACEHOLDERS: [&str; 4] = ["version", "date", "profile", "target"];
+
+/// An output path pattern such as `{version}/lcu-{version}-{profile}.json`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OutputTemplate {
+    pattern: String,
+}
+
+/// Values for the [`PLACEHOLDERS`] of an [`OutputTemplate`].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct TemplateVars {
+    pub version: String,
+    /// Usually `YYYY-MM-DD`; the caller picks the format.
+    pub date: String,
+    pub profile: String,
+    /// `lcu` or `riotclient`.
+    pub target: String,
+}
+
+/// A template that names placeholders outside [`PLACEHOLDERS`], or doesn't close one.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TemplateError {
+    pub template: String,
+    pub unknown: Vec<String>,
+}
+
+impl fmt::Display for TemplateError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "unknown placeholders in `{}`: {}; expected one of {}",
+            self.template,
+            self.unknown.join(", "),
+            PLACEHOLDERS.map(|p| format!("{{{p}}}")).join(", ")
+        )
+    }
+}
+
+impl std::error::Error for TemplateError {}
+
+impl OutputTemplate {
+    /// Check every placeholder up front, so a typo fails before anything is generated.
+    pub fn parse(pattern: &str) -> Result<Self, TemplateError> {
+        let mut unknown = Vec::new();
+        let mut rest = pattern;
+        while let Some(start) = rest.find('{') {
+            let Some(end) = rest[start..].find('}') else {
+                unknown.push(rest[start..].to_string());
+                break;
+            };
+            let name = &rest[start + 1..start + end];
+            if !PLACEHOLDERS.contains(&name) {
+                unknown.push(format!("{{{name}}}"));
+            }
+            rest = &rest[start + end + 1..];
+        }
+        if unknown.is_empty() {
+            Ok(Self { pattern: pattern.to_string() })
+        } else {
+            Err(TemplateError { template: pattern.to_string(), unknown })
+        }
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.pattern
+    }
+
+    pub fn resolve(&self, vars: &TemplateVars) -> PathBuf {
+        let resolved = self.pattern
+            .replace("{version}", &vars.version)
+            .replace("{date}", &vars.date)
+            .replace("{profile}", &vars.profile)
+            .replace("{target}", &vars.target);
+        PathBuf::from(resolved)
+    }
+}
+
+/// One written artifact, as listed in the [`Manifest`].
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Eq)]
+pub struct ManifestEntry {
+    /// Path relative to the writer's root.
+    pub path: PathBuf,
+    pub template: String,
+    /// Lowercase hex SHA-256 of the contents, see [`sha256_hex`]. Empty in
+    /// manifests written before hashes were added.
+    #[serde(default)]
+    pub sha256: String,
+}
+
+/// Everything an [`ArtifactWriter`] wrote.
+#[derive(Deserialize, Serialize, Debug, Clone, Default, PartialEq, Eq)]
+pub struct Manifest {
+    pub artifacts: Vec<ManifestEntry>,
+    /// What the generator could do, see [`crate::capabilities`]. Missing from
+    /// manifests written before it was recorded.
+    #[cfg(feature = "core")]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub capabilities: Option<CapabilityReport>,
+}
+
+impl Manifest {
+    pub fn to_json(&self) -> String {
+        migrate::to_string(Artifact::Manifest, self).expect("manifest serializes")
+    }
+
+    /// Parse a manifest written by this or an older version.
+    pub fn from_json(json: &str) -> Result<Self, Error> {
+        migrate::load(Artifact::Manifest, json)
+    }
+}
+
+/// Writes artifacts under `root` at templated paths.
+///
+/// Each file is written to a temporary sibling and renamed into place, so a
+/// failed run never leaves a half-written artifact. An existing artifact is
+/// only replaced when `overwrite` is set.
+#[derive(Debug, Clone)]
+pub struct ArtifactWriter {
+    pub root: PathBuf,
+    pub vars: TemplateVars,
+    pub overwrite: bool,
+    manifest: Manifest,
+}
+
+impl ArtifactWriter {
+    pub fn new(root: impl Into<PathBuf>, vars: TemplateVars) -> Self {
+        #[cfg(feature = "core")]
+        let manifest = Manifest { capabilities: Some(capabilities()), ..Default::default() };
+        #[cfg(not(feature = "core"))]
+        let manifest = Manifest::default();
+        Self { root: root.into(), vars, overwrite: false, manifest }
+    }
+
+    pub fn with_overwrite(mut self, overwrite: bool) -> Self {
+        self.overwrite = overwrite;
+        self
+    }
+
+    /// Write `contents` at the resolved `template`, creating directories as
+    /// needed, and return the full path.
+    pub fn write(&mut self, template: &OutputTemplate, contents: &str) -> Result<PathBuf, Error> {
+        self.write_streaming(template, |out| Ok(out.write_all(contents.as_bytes())?))
+    }
+
+    /// Like [`Self::write`], with `write` writing the contents straight into
+    /// the file. For artifacts too big to build as a string first, like the
+    /// spec through [`crate::openapi::OpenApiSpec::write_streaming`].
+    pub fn write_streaming<F>(&mut self, template: &OutputTemplate, write: F) -> Result<PathBuf, Error>
+        where F: FnOnce(&mut dyn Write) -> Result<(), Error>
+    {
+        let relative = template.resolve(&self.vars);
+        let path = self.root.join(&relative);
+        if path.exists() && !self.overwrite {
+            let message = format!("{} already exists; pass --overwrite to replace it", path.display());
+            return Err(io::Error::new(io::ErrorKind::AlreadyExists, message).into());
+        }
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let temporary = temporary_path(&path);
+        let mut out = HashingWriter { inner: io::BufWriter::new(fs::File::create(&temporary)?), hasher: Sha256::new() };
+        if let Err(err) = write(&mut out).and_then(|()| Ok(out.inner.flush()?)) {
+            let _ = fs::remove_file(&temporary);
+            return Err(err);
+        }
+        // Closes the file before it's moved.
+        let HashingWriter { inner, hasher } = out;
+        drop(inner);
+        let sha256 = hex(&hasher.finalize());
+        fs::rename(&temporary, &path)?;
+
+        self.manifest.artifacts.push(ManifestEntry {
+            path: relative,
+            template: template.as_str().to_string(),
+            sha256,
+        });
+        Ok(path)
+    }
+
+    /// Write the [`Manifest`] of everything written so far at `template`, for
+    /// downloaders to check their copies against. The manifest doesn't list
+    /// itself.
+    pub fn write_manifest(&mut self, template: &OutputTemplate) -> Result<PathBuf, Error> {
+        let json = self.manifest.to_json();
+        let path = self.write(template, &json)?;
+        self.manifest.artifacts.pop();
+        Ok(path)
+    }
+
+    pub fn manifest(&self) -> &Manifest {
+        &self.manifest
+    }
+}
+
+/// Lowercase hex SHA-256 of `contents`, as listed in the [`Manifest`].
+pub fn sha256_hex(contents: &str) -> String {
+    hex(&Sha256::digest(contents.as_bytes()))
+}
+
+fn hex(digest: &[u8]) -> String {
+    digest.iter().map(|byte| format!("{byte:02x}")).collect()
+}
+
+/// Hashes what passes through, for the manifest.
+struct HashingWriter<W> {
+    inner: W,
+    hasher: Sha256,
+}
+
+impl<W: Write> Write for HashingWriter<W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let written = self.inner.write(buf)?;
+        self.hasher.update(&buf[..written]);
+        Ok(written)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.inner.flush()
+    }
+}
+
+fn temporary_path(path: &Path) -> PathBuf {
+    let mut name = path.file_name().unwrap_or_default().to_os_string();
+    name.push(".tmp");
+    path.with_file_name(name)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn vars() -> TemplateVars {
+        TemplateVars {
+            version: "14.3.556.1234".to_string(),
+            date: "2024-02-07".to_string(),
+            profile: "full".to_string(),
+            target: "lcu".to_string(),
+        }
+    }
+
+    fn temp_root(name: &str) -> PathBuf {
+        std::env::temp_dir().join(format!("poroshell-artifact-{name}-{}", std::process::id()))
+    }
+
+    #[test]
+    fn test_resolve() {
+        let template = OutputTemplate::parse("{date}/{target}-{version}-{profile}.json").unwrap();
+        assert_eq!(template.resolve(&vars()), PathBuf::from("2024-02-07/lcu-14.3.556.1234-full.json"));
+        assert_eq!(OutputTemplate::parse("openapi.json").unwrap().resolve(&vars()), PathBuf::from("openapi.json"));
+    }
+
+    #[test]
+    fn test_unknown_placeholders() {
+        let err = OutputTemplate::parse("{version}/{patch}-{Profile}.json").unwrap_err();
+        assert_eq!(err.unknown, ["{patch}", "{Profile}"]);
+        assert!(err.to_string().contains("{patch}, {Profile}"));
+        assert_eq!(OutputTemplate::parse("{version").unwrap_err().unknown, ["{version"]);
+    }
+
+    #[test]
+    fn test_nested_directories() {
+        let root = temp_root("nested");
+        let mut writer = ArtifactWriter::new(&root, vars());
+        let template = OutputTemplate::parse("{target}/{version}/{profile}/openapi.json").unwrap();
+        let path = writer.write(&template, "{}").unwrap();
+
+        assert_eq!(path, root.join("lcu/14.3.556.1234/full/openapi.json"));
+        assert_eq!(fs::read_to_string(&path).unwrap(), "{}");
+        assert!(!temporary_path(&path).exists());
+        let entry = &writer.manifest().artifacts[0];
+        assert_eq!(entry.path, PathBuf::from("lcu/14.3.556.1234/full/openapi.json"));
+        assert_eq!(entry.template, "{target}/{version}/{profile}/openapi.json");
+        assert_eq!(entry.sha256, "44136fa355b3678a1146ad16f7e8649e94fb4fc21fe77e8310c060f61caaff8a");
+        let json = writer.manifest().to_json();
+        assert!(json.contains("\"format_version\": 1"));
+        assert_eq!(writer.manifest().capabilities, Some(capabilities()));
+        assert_eq!(&Manifest::from_json(&json).unwrap(), writer.manifest());
+
+        let published = writer.write_manifest(&OutputTemplate::parse("manifest.json").unwrap()).unwrap();
+        assert_eq!(fs::read_to_string(published).unwrap(), json);
+        assert_eq!(writer.manifest().artifacts.len(), 1);
+
+        fs::remove_dir_all(&root).unwrap();
+    }
+
+    #[test]
+    fn test_write_streaming() {
+        let root = temp_root("streaming");
+        let mut writer = ArtifactWriter::new(&root, vars());
+        let streamed = writer
+            .write_streaming(&OutputTemplate::parse("streamed.json").unwrap(), |out| Ok(write!(out, "{{}}")?))
+            .unwrap();
+        writer.write(&OutputTemplate::parse("written.json").unwrap(), "{}").unwrap();
+        assert_eq!(fs::read_to_string(&streamed).unwrap(), "{}");
+        let artifacts = &writer.manifest().artifacts;
+        assert_eq!(artifacts[0].sha256, artifacts[1].sha256);
+
+        // A failed write leaves nothing behind.
+        let failed = writer.write_streaming(&OutputTemplate::parse("failed.json").unwrap(), |out| {
+            out.write_all(b"{ \"partial\": ")?;
+            Err(io::Error::other("interrupted").into())
+        });
+        assert!(failed.is_err());
+        assert!(!root.join("failed.json").exists());
+        assert!(!temporary_path(&root.join("failed.json")).exists());
+        assert_eq!(writer.manifest().artifacts.len(), 2);
+
+        fs::remove_dir_all(&root).unwrap();
+    }
+
+    #[test]
+    fn test_overwrite() {
+        let root = temp_root("overwrite");
+        let template = OutputTemplate::parse("{target}-{version}.json").unwrap();
+        ArtifactWriter::new(&root, vars()).write(&template, "first").unwrap();
+
+        let err = ArtifactWriter::new(&root, vars()).write(&template, "second").unwrap_err();
+        assert!(matches!(&err, Error::Io(err) if err.kind() == io::ErrorKind::AlreadyExists), "{err}");
+        assert_eq!(fs::read_to_string(root.join("lcu-14.3.556.1234.json")).unwrap(), "first");
+
+        let mut writer = ArtifactWriter::new(&root, vars()).with_overwrite(true);
+        writer.write(&template, "second").unwrap();
+        assert_eq!(fs::read_to_string(root.join("lcu-14.3.556.1234.json")).unwrap(), "second");
+        assert_eq!(writer.manifest().artifacts.len(), 1);
+
+        fs::remove_dir_all(&root).unwrap();
+    }
+}
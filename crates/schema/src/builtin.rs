@@ -0,0 +1,235 @@
+//! Calling the builtin remoting endpoints, like `/Subscribe` and
+//! `/AsyncStatus`, without hand-rolling each request.
+//!
+//! Builtins take their arguments in the query, like the help requests
+//! [`crate::collect`] sends, so [`BuiltinClient`] wraps the same kind of
+//! `post` and can drive any client.
+
+use std::future::Future;
+
+use serde::{ de::DeserializeOwned, Deserialize, Serialize };
+use serde_json::Value;
+
+use crate::error::Error;
+
+/// Token of an async call, see [`crate::envelope::ASYNC_HANDLE`].
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[serde(transparent)]
+pub struct AsyncHandle(pub u32);
+
+/// Where an async call is at.
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AsyncState {
+    Running,
+    Succeeded,
+    Failed,
+    Cancelled,
+    /// A state this version doesn't know.
+    #[serde(other)]
+    Unknown,
+}
+
+impl AsyncState {
+    /// Whether the call is over and `/AsyncResult` can be asked for.
+    pub fn is_done(self) -> bool {
+        self != Self::Running
+    }
+}
+
+/// The answer of `/AsyncStatus`.
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct AsyncStatus {
+    pub async_token: AsyncHandle,
+    pub status: AsyncState,
+}
+
+/// One line of the client's log, from `/LoggingGetEntries`.
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Eq)]
+pub struct LogEntry {
+    pub severity: String,
+    pub message: String,
+    /// Milliseconds since logging started.
+    pub timestamp: u64,
+}
+
+/// Typed calls to the builtin endpoints over `post`, which sends one request
+/// like `/Subscribe?eventName=X` and returns the response.
+pub struct BuiltinClient<F> {
+    post: F,
+}
+
+impl<F, Fut> BuiltinClient<F>
+    where F: Fn(String) -> Fut, Fut: Future<Output = Result<Value, Error>>
+{
+    pub fn new(post: F) -> Self {
+        Self { post }
+    }
+
+    /// Send `event`, e.g. `OnJsonApiEvent`, over the websocket from now on.
+    pub async fn subscribe(&self, event: &str) -> Result<(), Error> {
+        (self.post)(format!("/Subscribe?eventName={event}")).await.map(drop)
+    }
+
+    pub async fn unsubscribe(&self, event: &str) -> Result<(), Error> {
+        (self.post)(format!("/Unsubscribe?eventName={event}")).await.map(drop)
+    }
+
+    /// Where the call `handle` is at.
+    pub async fn async_status(&self, handle: AsyncHandle) -> Result<AsyncStatus, Error> {
+        self.parse(format!("/AsyncStatus?asyncToken={}", handle.0)).await
+    }
+
+    /// What the finished call `handle` returned.
+    pub async fn async_result<T: DeserializeOwned>(&self, handle: AsyncHandle) -> Result<T, Error> {
+        self.parse(format!("/AsyncResult?asyncToken={}", handle.0)).await
+    }
+
+    /// Forget the finished call `handle`.
+    pub async fn async_delete(&self, handle: AsyncHandle) -> Result<(), Error> {
+        (self.post)(format!("/AsyncDelete?asyncToken={}", handle.0)).await.map(drop)
+    }
+
+    /// Stop the running call `handle`.
+    pub async fn cancel(&self, handle: AsyncHandle) -> Result<(), Error> {
+        (self.post)(format!("/Cancel?asyncToken={}", handle.0)).await.map(drop)
+    }
+
+    /// Close the client.
+    pub async fn exit(&self) -> Result<(), Error> {
+        (self.post)("/Exit".to_string()).await.map(drop)
+    }
+
+    /// Format websocket messages as `format`, e.g. `JSON`.
+    pub async fn websocket_format(&self, format: &str) -> Result<(), Error> {
+        (self.post)(format!("/WebSocketFormat?format={format}")).await.map(drop)
+    }
+
+    pub async fn logging_start(&self) -> Result<(), Error> {
+        (self.post)("/LoggingStart".to_string()).await.map(drop)
+    }
+
+    pub async fn logging_stop(&self) -> Result<(), Error> {
+        (self.post)("/LoggingStop".to_string()).await.map(drop)
+    }
+
+    /// Log entries after `since`, in milliseconds since logging started.
+    /// The client returns all of them, so they're filtered here.
+    pub async fn logging_entries(&self, since: u64) -> Result<Vec<LogEntry>, Error> {
+        let entries: Vec<LogEntry> = self.parse("/LoggingGetEntries".to_string()).await?;
+        Ok(entries.into_iter().filter(|entry| entry.timestamp > since).collect())
+    }
+
+    async fn parse<T: DeserializeOwned>(&self, endpoint: String) -> Result<T, Error> {
+        Ok(serde_json::from_value((self.post)(endpoint).await?)?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{ cell::RefCell, collections::{ HashMap, VecDeque } };
+
+    use serde_json::json;
+
+    use super::*;
+
+    /// Answers each builtin with the responses queued for it, in turn, and
+    /// records the requests.
+    struct ScriptedClient {
+        requests: RefCell<Vec<String>>,
+        responses: RefCell<HashMap<&'static str, VecDeque<Value>>>,
+    }
+
+    impl ScriptedClient {
+        fn new(responses: &[(&'static str, Value)]) -> Self {
+            let mut queued = HashMap::<&str, VecDeque<Value>>::new();
+            for (path, response) in responses {
+                queued.entry(path).or_default().push_back(response.clone());
+            }
+            Self { requests: RefCell::new(Vec::new()), responses: RefCell::new(queued) }
+        }
+
+        async fn post(&self, endpoint: String) -> Result<Value, Error> {
+            let path = endpoint.split('?').next().unwrap().to_string();
+            self.requests.borrow_mut().push(endpoint);
+            let response = self.responses.borrow_mut().get_mut(path.as_str()).and_then(VecDeque::pop_front);
+            Ok(response.unwrap_or(Value::Null))
+        }
+
+        fn requests(&self) -> Vec<String> {
+            self.requests.borrow().clone()
+        }
+    }
+
+    #[tokio::test]
+    async fn test_request_shapes() {
+        let client = ScriptedClient::new(&[]);
+        let builtin = BuiltinClient::new(|endpoint| client.post(endpoint));
+        builtin.subscribe("OnJsonApiEvent").await.unwrap();
+        builtin.unsubscribe("OnJsonApiEvent").await.unwrap();
+        builtin.websocket_format("JSON").await.unwrap();
+        builtin.cancel(AsyncHandle(7)).await.unwrap();
+        builtin.async_delete(AsyncHandle(7)).await.unwrap();
+        builtin.logging_start().await.unwrap();
+        builtin.logging_stop().await.unwrap();
+        builtin.exit().await.unwrap();
+
+        assert_eq!(
+            client.requests(),
+            [
+                "/Subscribe?eventName=OnJsonApiEvent",
+                "/Unsubscribe?eventName=OnJsonApiEvent",
+                "/WebSocketFormat?format=JSON",
+                "/Cancel?asyncToken=7",
+                "/AsyncDelete?asyncToken=7",
+                "/LoggingStart",
+                "/LoggingStop",
+                "/Exit",
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_async_flow() {
+        let client = ScriptedClient::new(&[
+            ("/AsyncStatus", json!({ "asyncToken": 3, "status": "Running" })),
+            ("/AsyncStatus", json!({ "asyncToken": 3, "status": "Succeeded" })),
+            ("/AsyncResult", json!({ "summonerLevel": 30 })),
+        ]);
+        let builtin = BuiltinClient::new(|endpoint| client.post(endpoint));
+        let handle = AsyncHandle(3);
+
+        let mut status = builtin.async_status(handle).await.unwrap();
+        assert_eq!(status, AsyncStatus { async_token: handle, status: AsyncState::Running });
+        while !status.status.is_done() {
+            status = builtin.async_status(handle).await.unwrap();
+        }
+        assert_eq!(status.status, AsyncState::Succeeded);
+        let result: Value = builtin.async_result(handle).await.unwrap();
+        assert_eq!(result["summonerLevel"], 30);
+        assert_eq!(client.requests(), ["/AsyncStatus?asyncToken=3", "/AsyncStatus?asyncToken=3", "/AsyncResult?asyncToken=3"]);
+
+        let unknown: AsyncStatus = serde_json::from_value(json!({ "asyncToken": 3, "status": "Paused" })).unwrap();
+        assert_eq!(unknown.status, AsyncState::Unknown);
+        assert!(unknown.status.is_done());
+    }
+
+    #[tokio::test]
+    async fn test_logging_entries() {
+        let entries = json!([
+            { "severity": "Okay", "message": "plugin loaded", "timestamp": 100 },
+            { "severity": "Warning", "message": "slow response", "timestamp": 250 },
+        ]);
+        let client = ScriptedClient::new(&[("/LoggingGetEntries", entries.clone()), ("/LoggingGetEntries", entries)]);
+        let builtin = BuiltinClient::new(|endpoint| client.post(endpoint));
+
+        assert_eq!(builtin.logging_entries(0).await.unwrap().len(), 2);
+        let recent = builtin.logging_entries(100).await.unwrap();
+        assert_eq!(recent, [LogEntry { severity: "Warning".to_string(), message: "slow response".to_string(), timestamp: 250 }]);
+
+        // A response of another shape is an error rather than no entries.
+        let broken = ScriptedClient::new(&[("/LoggingGetEntries", json!({ "entries": [] }))]);
+        let builtin = BuiltinClient::new(|endpoint| broken.post(endpoint));
+        assert!(matches!(builtin.logging_entries(0).await, Err(Error::Json(_))));
+    }
+}
@@ -0,0 +1,208 @@
+//! What this build of the converter can do, for consumers asking whether a
+//! published spec covers something.
+//!
+//! The report is assembled from the constants and enums the converter itself
+//! uses, so it can't drift from the code. [`crate::artifact::ArtifactWriter`]
+//! includes it in every manifest.
+
+use std::collections::BTreeMap;
+
+use serde::{ Deserialize, Serialize };
+
+use crate::{
+    caution::X_CAUTION,
+    convert::{ CONSOLE_HELP_EXTENSION, X_REDACTED },
+    enums::{ X_ENUM_DESCRIPTION, X_ENUM_VARNAMES },
+    envelope::{ X_ENVELOPE, X_ENVELOPE_ITEMS },
+    export::POSTMAN_VERSION,
+    help::{ MAP_TYPE, PRIMITIVE_TYPES, VECTOR_TYPE },
+    json_schema::JSON_SCHEMA_VERSION,
+    latency::X_EXPECTED_LATENCY,
+    migrate::Artifact,
+    openapi::{ OPENAPI_VERSION, X_EVENTS },
+    renames::X_RENAMED_FROM,
+    routing::X_GREEDY_PARAMS,
+    samples::X_CODE_SAMPLES,
+    servers::X_EXTERNAL_HOST,
+    since::X_SINCE,
+    slug::{ X_PREVIOUS_SLUGS, X_SLUG },
+    stability::X_STABILITY,
+    style::SerializationStyle,
+    tags::X_DISPLAY_NAME,
+    transform::{ X_CONSUMED_BY, X_ORIGINAL_FORMAT, X_PRODUCED_BY, X_USED_BY },
+    workflow::X_WORKFLOWS,
+};
+
+/// Version of the [`CapabilityReport`] layout. Bump it when a field is renamed
+/// or changes meaning; new fields don't need it.
+pub const CAPABILITIES_VERSION: u32 = 1;
+
+/// Every `x-*` key the converter and its annotators can write.
+pub const EXTENSIONS: [&str; 23] = [
+    CONSOLE_HELP_EXTENSION,
+    X_CAUTION,
+    X_CODE_SAMPLES,
+    X_CONSUMED_BY,
+    X_DISPLAY_NAME,
+    X_ENUM_DESCRIPTION,
+    X_ENUM_VARNAMES,
+    X_ENVELOPE,
+    X_ENVELOPE_ITEMS,
+    X_EVENTS,
+    X_EXPECTED_LATENCY,
+    X_EXTERNAL_HOST,
+    X_GREEDY_PARAMS,
+    X_ORIGINAL_FORMAT,
+    X_PREVIOUS_SLUGS,
+    X_PRODUCED_BY,
+    X_REDACTED,
+    X_RENAMED_FROM,
+    X_SINCE,
+    X_SLUG,
+    X_STABILITY,
+    X_USED_BY,
+    X_WORKFLOWS,
+];
+
+/// Help [`crate::help::DataType`] forms the converter understands.
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Eq)]
+pub struct DataTypeForms {
+    /// Names that map straight to a schema.
+    pub primitives: Vec<String>,
+    /// Names whose `elementType` holds the item or value type.
+    pub containers: Vec<String>,
+}
+
+/// A format the generated spec can be written in.
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Eq)]
+pub struct OutputFormat {
+    pub name: String,
+    pub version: String,
+}
+
+/// What the generator that wrote a set of artifacts could do.
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Eq)]
+pub struct CapabilityReport {
+    /// See [`CAPABILITIES_VERSION`].
+    pub version: u32,
+    /// `poro_schema` and its crate version.
+    pub generator: String,
+    pub data_types: DataTypeForms,
+    /// See [`EXTENSIONS`].
+    pub extensions: Vec<String>,
+    /// Serialization styles, see [`SerializationStyle`].
+    pub profiles: Vec<String>,
+    pub output_formats: Vec<OutputFormat>,
+    /// Format version written for each saved artifact, see [`Artifact`].
+    pub artifacts: BTreeMap<String, u32>,
+}
+
+/// The capabilities of this build.
+pub fn capabilities() -> CapabilityReport {
+    let strings = |names: &[&str]| names.iter().map(|name| name.to_string()).collect::<Vec<String>>();
+    let format = |name: &str, version: &str| OutputFormat { name: name.to_string(), version: version.to_string() };
+    CapabilityReport {
+        version: CAPABILITIES_VERSION,
+        generator: format!("{} {}", env!("CARGO_PKG_NAME"), env!("CARGO_PKG_VERSION")),
+        data_types: DataTypeForms {
+            primitives: strings(&PRIMITIVE_TYPES),
+            containers: strings(&[VECTOR_TYPE, MAP_TYPE]),
+        },
+        extensions: strings(&EXTENSIONS),
+        profiles: SerializationStyle::ALL.iter().map(|style| style.name().to_string()).collect(),
+        output_formats: vec![
+            format("openapi", OPENAPI_VERSION),
+            format("postman-collection", POSTMAN_VERSION),
+            format("json-schema", JSON_SCHEMA_VERSION),
+        ],
+        artifacts: Artifact::ALL.iter().map(|artifact| (artifact.name().to_string(), artifact.current())).collect(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{ collections::BTreeSet, fs, path::Path };
+
+    use super::*;
+    use crate::{ convert::primitive, help::DataType, json_schema::JSON_SCHEMA_DIALECT, openapi::OpenApiSpec, options::{ PrivacyPolicy, SchemaOptions }, report::SchemaReport, tests::miniature };
+
+    /// `"x-..."` literals outside the test modules of every source file.
+    fn extension_literals() -> BTreeSet<String> {
+        let src = Path::new(env!("CARGO_MANIFEST_DIR")).join("src");
+        let mut found = BTreeSet::new();
+        for entry in fs::read_dir(src).unwrap() {
+            let source = fs::read_to_string(entry.unwrap().path()).unwrap();
+            let code = source.split("#[cfg(test)]").next().unwrap_or_default();
+            for literal in code.split("\"x-").skip(1) {
+                let Some((name, _)) = literal.split_once('"') else {
+                    continue;
+                };
+                if !name.is_empty() && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '-') {
+                    found.insert(format!("x-{name}"));
+                }
+            }
+        }
+        found
+    }
+
+    #[test]
+    fn test_every_extension_registered() {
+        let report = capabilities();
+        let listed = report.extensions.iter().cloned().collect::<BTreeSet<String>>();
+        assert_eq!(listed.len(), EXTENSIONS.len(), "extensions are listed twice");
+        assert!(extension_literals().contains(X_REDACTED));
+        let unregistered = extension_literals().difference(&listed).cloned().collect::<Vec<String>>();
+        assert!(unregistered.is_empty(), "add {unregistered:?} to EXTENSIONS");
+
+        // What the converter writes is all listed, with everything switched on.
+        let options = SchemaOptions { include_extensions: true, slugs: true, privacy: PrivacyPolicy::Redact, ..Default::default() };
+        let spec = OpenApiSpec::from_help_with(&miniature(), "1.0.0", &options, &mut SchemaReport::default()).unwrap();
+        let json = serde_json::to_string(&spec).unwrap();
+        for literal in json.split("\"x-").skip(1) {
+            let name = format!("x-{}", literal.split_once('"').unwrap().0);
+            assert!(listed.contains(&name), "{name} isn't in EXTENSIONS");
+        }
+    }
+
+    #[test]
+    fn test_every_data_type_form() {
+        let report = capabilities();
+        for name in PRIMITIVE_TYPES {
+            assert!(primitive(name).is_some(), "{name:?} is listed but not converted");
+        }
+        assert!(primitive("LolSummonerSummoner").is_none());
+        assert_eq!(report.data_types.primitives, PRIMITIVE_TYPES);
+        assert_eq!(report.data_types.containers, [VECTOR_TYPE, MAP_TYPE]);
+
+        // Every type the miniature's help uses is one of the forms, or a type it defines.
+        let help = miniature();
+        let defined = help.types.iter().map(|ty| ty.info.name.as_str()).collect::<BTreeSet<&str>>();
+        let fields = help.types.iter().flat_map(|ty| ty.fields.iter().map(|field| &field.ty));
+        let arguments = help.endpoints.iter().flat_map(|endpoint| endpoint.arguments.iter().map(|argument| &argument.ty));
+        let is_form = |name: &str| {
+            report.data_types.primitives.iter().chain(&report.data_types.containers).any(|form| form == name) || defined.contains(name)
+        };
+        for DataType { element_type, ty } in fields.chain(arguments) {
+            assert!(is_form(ty), "{ty}");
+            assert!(element_type.is_empty() || is_form(element_type), "{element_type}");
+        }
+    }
+
+    #[test]
+    fn test_report() {
+        let report = capabilities();
+        assert_eq!(report.version, CAPABILITIES_VERSION);
+        assert!(report.generator.starts_with("poro_schema "));
+        assert_eq!(report.profiles, ["standard", "hasagi-compat"]);
+        assert_eq!(report.output_formats[0], OutputFormat { name: "openapi".to_string(), version: "3.0.0".to_string() });
+        assert!(crate::export::postman(&OpenApiSpec::from_help(&miniature(), "1.0.0").unwrap(), &Default::default())["info"]["schema"]
+            .as_str()
+            .unwrap()
+            .contains(&format!("v{POSTMAN_VERSION}")));
+        assert!(JSON_SCHEMA_DIALECT.contains(JSON_SCHEMA_VERSION));
+        assert_eq!(report.artifacts["extended-help.json"], Artifact::ExtendedHelp.current());
+
+        let json = serde_json::to_string(&report).unwrap();
+        assert_eq!(serde_json::from_str::<CapabilityReport>(&json).unwrap(), report);
+    }
+}
@@ -0,0 +1,234 @@
+//! Operations that are risky to send, like store purchases and queue actions.
+
+use serde::{ Deserialize, Serialize };
+use serde_json::Value;
+
+use crate::{ openapi::OpenApiSpec, rules::{ OperationMatch, tags_of } };
+
+/// Extension key holding a [`Caution`] on an operation.
+pub const X_CAUTION: &str = "x-caution";
+
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[serde(rename_all = "snake_case")]
+pub enum CautionLevel {
+    /// Visible side effects, but nothing lasting.
+    Notice,
+    /// Changes client state in a way that is annoying to undo.
+    Warning,
+    /// Spends currency, ends sessions, or can get the account penalized.
+    Danger,
+}
+
+/// Why an operation should be sent with care, as written under [`X_CAUTION`].
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Eq)]
+pub struct Caution {
+    pub level: CautionLevel,
+    pub reason: String,
+}
+
+impl Caution {
+    /// The caution on a generated operation, if it has one.
+    pub fn of(operation: &Value) -> Option<Self> {
+        serde_json::from_value(operation.get(X_CAUTION)?.clone()).ok()
+    }
+}
+
+/// A caution for the operations an [`OperationMatch`] matches.
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Eq)]
+pub struct CautionRule {
+    #[serde(flatten)]
+    pub operations: OperationMatch,
+    /// `None` marks matching operations as safe, for overriding a default rule.
+    pub caution: Option<Caution>,
+}
+
+impl CautionRule {
+    pub fn new(method: Option<&str>, path_prefix: &str, level: CautionLevel, reason: &str) -> Self {
+        Self {
+            operations: OperationMatch {
+                method: method.map(str::to_string),
+                path_prefix: Some(path_prefix.to_string()),
+                tag: None,
+            },
+            caution: Some(Caution { level, reason: reason.to_string() }),
+        }
+    }
+
+    pub fn matches(&self, method: &str, path: &str, tags: &[&str]) -> bool {
+        self.operations.matches(method, path, tags)
+    }
+}
+
+/// The built-in rules.
+pub fn default_rules() -> Vec<CautionRule> {
+    use CautionLevel::*;
+    vec![
+        CautionRule::new(None, "/lol-store", Danger, "Can spend RP or Blue Essence"),
+        CautionRule::new(None, "/lol-purchase-widget", Danger, "Can spend RP or Blue Essence"),
+        CautionRule::new(None, "/process-control", Danger, "Can quit the client"),
+        CautionRule::new(None, "/lol-login", Danger, "Can log the client out"),
+        CautionRule::new(
+            Some("POST"),
+            "/lol-lobby/v2/lobby/matchmaking/search",
+            Warning,
+            "Enters matchmaking, where dodging is penalized"
+        ),
+        CautionRule::new(Some("POST"), "/lol-matchmaking/v1/ready-check", Warning, "Answers the ready check"),
+        CautionRule::new(Some("DELETE"), "/lol-lobby", Warning, "Leaves or breaks up the lobby"),
+        CautionRule::new(Some("POST"), "/riotclient/kill-and-restart-ux", Notice, "Restarts the client UI")
+    ]
+}
+
+/// Classifies operations with user overrides checked before the [`default_rules`].
+#[derive(Debug, Clone, Default)]
+pub struct CautionRules {
+    pub overrides: Vec<CautionRule>,
+}
+
+impl CautionRules {
+    /// The caution of the first matching rule, if any.
+    pub fn classify(&self, method: &str, path: &str, tags: &[&str]) -> Option<Caution> {
+        classify(&self.overrides, method, path, tags).or_else(|| {
+            let overridden = self.overrides.iter().any(|rule| rule.matches(method, path, tags));
+            if overridden { None } else { classify(&default_rules(), method, path, tags) }
+        })
+    }
+}
+
+/// The caution of the first rule in `rules` matching the operation.
+pub fn classify(rules: &[CautionRule], method: &str, path: &str, tags: &[&str]) -> Option<Caution> {
+    rules
+        .iter()
+        .find(|rule| rule.matches(method, path, tags))
+        .and_then(|rule| rule.caution.clone())
+}
+
+/// Writes [`X_CAUTION`] on every operation [`CautionRules`] classifies.
+#[derive(Debug, Clone)]
+pub struct CautionAnnotations<'a> {
+    pub rules: &'a CautionRules,
+}
+
+impl CautionAnnotations<'_> {
+    /// Returns the number of operations that were annotated.
+    pub fn apply(&self, spec: &mut OpenApiSpec) -> usize {
+        let mut count = 0;
+        for (path, item) in spec.paths.iter_mut() {
+            let Some(item) = item.as_object_mut() else {
+                continue;
+            };
+            for (method, operation) in item.iter_mut() {
+                let Some(caution) = self.rules.classify(method, path, &tags_of(operation)) else {
+                    continue;
+                };
+                if let Value::Object(operation) = operation {
+                    operation.insert(X_CAUTION.to_string(), serde_json::to_value(caution).unwrap_or_default());
+                    count += 1;
+                }
+            }
+        }
+        count
+    }
+}
+
+/// What a request runner does before sending an operation.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Gate {
+    Send,
+    /// Ask first, with the danger-styled dialog.
+    Confirm,
+    Block,
+}
+
+/// When a request runner asks before sending, or refuses to.
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CautionPolicy {
+    /// Operations at or above this level need confirmation.
+    pub confirm_at: CautionLevel,
+    /// Refuse operations at or above `confirm_at` instead of asking.
+    pub block: bool,
+}
+
+impl Default for CautionPolicy {
+    fn default() -> Self {
+        Self { confirm_at: CautionLevel::Warning, block: false }
+    }
+}
+
+impl CautionPolicy {
+    pub fn gate(&self, caution: Option<&Caution>) -> Gate {
+        match caution {
+            Some(caution) if caution.level >= self.confirm_at && self.block => Gate::Block,
+            Some(caution) if caution.level >= self.confirm_at => Gate::Confirm,
+            _ => Gate::Send,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{ options::SchemaOptions, report::SchemaReport, tests::miniature };
+
+    #[test]
+    fn test_classify() {
+        use CautionLevel::*;
+        let rules = CautionRules::default();
+        let cases: [(&str, &str, &[&str], Option<CautionLevel>); 9] = [
+            ("POST", "/lol-store/v1/purchase", &[], Some(Danger)),
+            ("GET", "/lol-store/v1/catalog", &[], Some(Danger)),
+            ("GET", "/lol-store-front/v1/items", &[], None),
+            ("delete", "/lol-lobby/v2/lobby", &[], Some(Warning)),
+            ("GET", "/lol-lobby/v2/lobby", &[], None),
+            ("POST", "/lol-lobby/v2/lobby/matchmaking/search", &[], Some(Warning)),
+            ("POST", "/lol-login/v1/session", &[], Some(Danger)),
+            ("POST", "/riotclient/kill-and-restart-ux", &[], Some(Notice)),
+            ("GET", "/lol-summoner/v1/current-summoner", &["Plugin lol-summoner"], None),
+        ];
+        for (method, path, tags, level) in cases {
+            let caution = rules.classify(method, path, tags);
+            assert_eq!(caution.map(|c| c.level), level, "{method} {path}");
+        }
+    }
+
+    #[test]
+    fn test_overrides() {
+        let rules = CautionRules {
+            overrides: vec![
+                CautionRule { caution: None, ..CautionRule::new(Some("GET"), "/lol-store", CautionLevel::Notice, "") },
+                CautionRule {
+                    operations: OperationMatch { tag: Some("Plugin lol-summoner".to_string()), ..Default::default() },
+                    caution: Some(Caution { level: CautionLevel::Notice, reason: "Testing".to_string() }),
+                }
+            ],
+        };
+        assert_eq!(rules.classify("GET", "/lol-store/v1/catalog", &[]), None);
+        assert_eq!(rules.classify("POST", "/lol-store/v1/purchase", &[]).unwrap().level, CautionLevel::Danger);
+        assert_eq!(rules.classify("GET", "/lol-summoner/v1/current-summoner", &["Plugin lol-summoner"]).unwrap().reason, "Testing");
+    }
+
+    #[test]
+    fn test_annotations_and_gate() {
+        let options = SchemaOptions { caution: Some(CautionRules::default()), ..Default::default() };
+        let spec = OpenApiSpec::from_help_with(&miniature(), "1.0.0", &options, &mut SchemaReport::default()).unwrap();
+
+        let post = &spec.paths["/lol-lobby/v2/lobby"]["post"];
+        let delete = &spec.paths["/lol-lobby/v2/lobby"]["delete"];
+        let get = &spec.paths["/lol-summoner/v1/current-summoner"]["get"];
+        assert_eq!(Caution::of(post), None);
+        let caution = Caution::of(delete).unwrap();
+        assert_eq!(caution.level, CautionLevel::Warning);
+        assert_eq!(Caution::of(get), None);
+
+        let policy = CautionPolicy::default();
+        assert_eq!(policy.gate(Caution::of(delete).as_ref()), Gate::Confirm);
+        assert_eq!(policy.gate(Caution::of(get).as_ref()), Gate::Send);
+        let strict = CautionPolicy { confirm_at: CautionLevel::Notice, block: true };
+        assert_eq!(strict.gate(Some(&caution)), Gate::Block);
+        let lax = CautionPolicy { confirm_at: CautionLevel::Danger, block: false };
+        assert_eq!(lax.gate(Some(&caution)), Gate::Send);
+
+        let plain = OpenApiSpec::from_help(&miniature(), "1.0.0").unwrap();
+        assert!(plain.paths["/lol-lobby/v2/lobby"]["delete"].get(X_CAUTION).is_none());
+    }
+}
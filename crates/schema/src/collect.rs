@@ -0,0 +1,363 @@
+//! Fetching the full help of every target in the `/help` listing.
+//!
+//! Plugins keep loading while the client starts up, so the listing can change
+//! during a long run. The listing is fetched again at the end, and targets
+//! that came or went in between are handled per [`MidRunTargets`] and
+//! recorded in the report.
+//!
+//! A stock client has thousands of targets, so help requests can be sent a
+//! few at a time with [`collect_concurrent`]. Each kind of target is put back
+//! in name order, so the result is the same however the answers arrive.
+
+use std::{ collections::BTreeSet, future::Future, path::{ Path, PathBuf } };
+
+use futures_util::{ stream, StreamExt, TryStreamExt };
+use serde_json::Value;
+
+use crate::{
+    bundle::parse_endpoint,
+    error::Error,
+    help::{ dedup_events, ConsoleEndpointInner, Endpoint, Event, ExtendedHelp, Help, SeqFirst, Type },
+    options::MidRunTargets,
+    report::{ ReportKind, SchemaReport },
+};
+
+/// The targets of a `/help` listing, by kind.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Targets {
+    pub types: BTreeSet<String>,
+    pub events: BTreeSet<String>,
+    pub functions: BTreeSet<String>,
+}
+
+impl Targets {
+    pub fn of(help: &Help) -> Self {
+        Self {
+            types: help.types.keys().cloned().collect(),
+            events: help.events.keys().cloned().collect(),
+            functions: help.functions.keys().cloned().collect(),
+        }
+    }
+
+    /// Targets in `self` but not in `other`.
+    pub fn difference(&self, other: &Self) -> Self {
+        Self {
+            types: self.types.difference(&other.types).cloned().collect(),
+            events: self.events.difference(&other.events).cloned().collect(),
+            functions: self.functions.difference(&other.functions).cloned().collect(),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.types.is_empty() && self.events.is_empty() && self.functions.is_empty()
+    }
+
+    fn names(&self) -> impl Iterator<Item = &String> {
+        self.types.iter().chain(self.events.iter()).chain(self.functions.iter())
+    }
+}
+
+/// Full help of each target, with endpoints still as JSON for
+/// [`crate::patch_builtin_endpoints`].
+#[derive(Debug, Default)]
+pub struct Collected {
+    pub types: Vec<Type>,
+    pub events: Vec<Event>,
+    pub endpoints: Vec<Value>,
+}
+
+/// Fetch the listing and the full help of everything in it, then check the
+/// listing again for targets that came or went meanwhile.
+///
+/// `post` sends one help request, like `/help?target=X&format=Full`, and
+/// returns the response, so any client can be used.
+pub async fn collect<F, Fut>(post: F, mid_run: MidRunTargets, report: &mut SchemaReport) -> Result<Collected, Error>
+    where F: Fn(String) -> Fut, Fut: Future<Output = Result<Value, Error>>
+{
+    collect_concurrent(post, mid_run, 1, report).await
+}
+
+/// Like [`collect`], with up to `concurrency` help requests in flight. The
+/// first request to fail stops the rest.
+pub async fn collect_concurrent<F, Fut>(
+    post: F,
+    mid_run: MidRunTargets,
+    concurrency: usize,
+    report: &mut SchemaReport
+) -> Result<Collected, Error>
+    where F: Fn(String) -> Fut, Fut: Future<Output = Result<Value, Error>>
+{
+    let listing = |post: &F| {
+        let response = post("/help".to_string());
+        async move { Ok::<_, Error>(Targets::of(&serde_json::from_value::<Help>(response.await?)?)) }
+    };
+
+    let first = listing(&post).await?;
+    let mut collected = Collected::default();
+    fetch(&first, &post, concurrency, &mut collected).await?;
+
+    let second = listing(&post).await?;
+    let appeared = second.difference(&first);
+    match mid_run {
+        MidRunTargets::Fetch => {
+            fetch(&appeared, &post, concurrency, &mut collected).await?;
+            for name in appeared.names() {
+                report.warn(name, "appeared in the help listing mid-run; fetched afterwards");
+            }
+        }
+        MidRunTargets::Report => {
+            for name in appeared.names() {
+                report.push(ReportKind::Skipped, name, "appeared in the help listing mid-run, not included");
+            }
+        }
+    }
+    for name in first.difference(&second).names() {
+        report.warn(name, "removed from the help listing mid-run");
+    }
+    Ok(collected)
+}
+
+/// Where [`extended_help`] leaves bug-report bundles by default.
+pub fn default_bundle_dir() -> PathBuf {
+    std::env::temp_dir().join("poroshell-bundles")
+}
+
+/// [`collect`] everything and finish it into [`ExtendedHelp`], with the
+/// builtin endpoints patched and duplicate events merged.
+///
+/// An endpoint that doesn't parse means something major changed in the API,
+/// so it leaves a bug-report bundle in `bundle_dir`.
+pub async fn extended_help<F, Fut>(post: F, mid_run: MidRunTargets, bundle_dir: &Path) -> Result<ExtendedHelp, Error>
+    where F: Fn(String) -> Fut, Fut: Future<Output = Result<Value, Error>>
+{
+    extended_help_concurrent(post, mid_run, 1, bundle_dir).await
+}
+
+/// Like [`extended_help`], collected with [`collect_concurrent`].
+pub async fn extended_help_concurrent<F, Fut>(
+    post: F,
+    mid_run: MidRunTargets,
+    concurrency: usize,
+    bundle_dir: &Path
+) -> Result<ExtendedHelp, Error>
+    where F: Fn(String) -> Fut, Fut: Future<Output = Result<Value, Error>>
+{
+    let mut report = SchemaReport::default();
+    let Collected { types, events, mut endpoints } = collect_concurrent(post, mid_run, concurrency, &mut report).await?;
+
+    crate::patch_builtin_endpoints(&mut endpoints)?;
+    let endpoints = endpoints
+        .into_iter()
+        .map(|json| parse_endpoint(json, &report, bundle_dir))
+        .collect::<Result<Vec<Endpoint>, Error>>()?;
+    let events = dedup_events(events, &mut report);
+    Ok(ExtendedHelp { types, endpoints, events, report })
+}
+
+async fn fetch<F, Fut>(targets: &Targets, post: &F, concurrency: usize, collected: &mut Collected) -> Result<(), Error>
+    where F: Fn(String) -> Fut, Fut: Future<Output = Result<Value, Error>>
+{
+    let types = fetch_each(&targets.types, concurrency, |name| async move {
+        let SeqFirst::<Type>(full) = serde_json::from_value(post(format!("/help?target={name}&format=Full")).await?)?;
+        Ok(full)
+    });
+    collected.types.extend(types.await?);
+
+    let events = fetch_each(&targets.events, concurrency, |name| async move {
+        let SeqFirst::<Event>(full) = serde_json::from_value(post(format!("/help?target={name}&format=Full")).await?)?;
+        Ok(full)
+    });
+    collected.events.extend(events.await?);
+
+    let endpoints = fetch_each(&targets.functions, concurrency, |name| async move {
+        let SeqFirst::<Endpoint>(mut full) = serde_json::from_value(post(format!("/help?target={name}&format=Full")).await?)?;
+
+        // Finish construction using data from console help.
+        let mut console = post(format!("/help?target={name}&format=Console")).await?;
+        let console = console
+            .as_object_mut()
+            .expect("Console endpoint response should be an object");
+        if let Some(console) = console.remove(&name) {
+            let console: ConsoleEndpointInner = serde_json::from_value(console)?;
+            full.path_params = console.path_params();
+            full.path = console.url;
+            full.method = console.http_method;
+        }
+        Ok(serde_json::to_value(full)?)
+    });
+    collected.endpoints.extend(endpoints.await?);
+    Ok(())
+}
+
+/// `fetch_one` of every name, up to `concurrency` at a time, in name order.
+async fn fetch_each<T, F, Fut>(names: &BTreeSet<String>, concurrency: usize, fetch_one: F) -> Result<Vec<T>, Error>
+    where F: Fn(String) -> Fut, Fut: Future<Output = Result<T, Error>>
+{
+    let mut fetched = stream::iter(names.iter().cloned())
+        .map(|name| {
+            let fetched = fetch_one(name.clone());
+            async move { Ok::<_, Error>((name, fetched.await?)) }
+        })
+        .buffer_unordered(concurrency.max(1))
+        .try_collect::<Vec<(String, T)>>()
+        .await?;
+    fetched.sort_by(|(a, _), (b, _)| a.cmp(b));
+    Ok(fetched.into_iter().map(|(_, item)| item).collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{ cell::Cell, collections::HashMap };
+
+    use serde_json::json;
+
+    use poroshell_test_utils::{ HelpBuilder, MockPoroClient };
+
+    use super::*;
+    use crate::tests::miniature;
+
+    /// A client whose listing gains `LolLobbyLobbyDto` and `PostLolLobbyV2Lobby`
+    /// and loses `LolSummonerSummonerRerollPoints` after the first request,
+    /// answering help requests from the miniature fixture.
+    struct GrowingClient {
+        listings: Cell<usize>,
+        requests: Cell<usize>,
+        full: HashMap<String, Value>,
+    }
+
+    impl GrowingClient {
+        fn new() -> Self {
+            let help = miniature();
+            let mut full = HashMap::new();
+            for ty in help.types.iter() {
+                full.insert(ty.info.name.clone(), json!([ty]));
+            }
+            for endpoint in help.endpoints.iter() {
+                full.insert(endpoint.info.name.clone(), json!([endpoint]));
+            }
+            Self { listings: Cell::new(0), requests: Cell::new(0), full }
+        }
+
+        fn listing(&self) -> Value {
+            let mut types = vec!["LolSummonerSummoner", "LolSummonerSummonerRerollPoints"];
+            let mut functions = vec!["GetLolSummonerV1CurrentSummoner"];
+            if self.listings.replace(self.listings.get() + 1) > 0 {
+                types = vec!["LolSummonerSummoner", "LolLobbyLobbyDto"];
+                functions.push("PostLolLobbyV2Lobby");
+            }
+            let names = |names: Vec<&'static str>| names.into_iter().map(|name| (name, "")).collect::<HashMap<&str, &str>>();
+            json!({ "types": names(types), "functions": names(functions), "events": {} })
+        }
+
+        async fn post(&self, endpoint: String) -> Result<Value, Error> {
+            self.requests.set(self.requests.get() + 1);
+            if endpoint == "/help" {
+                return Ok(self.listing());
+            }
+            let target = endpoint.trim_start_matches("/help?target=").split('&').next().unwrap();
+            if endpoint.ends_with("format=Console") {
+                let endpoint = self.full[target][0].clone();
+                return Ok(json!({ target: { "url": endpoint["path"], "httpMethod": endpoint["method"] } }));
+            }
+            Ok(self.full[target].clone())
+        }
+    }
+
+    /// Answers like [`MockPoroClient`], taking longer the shorter the target's
+    /// name so answers arrive out of order, and refusing `refused`.
+    struct SlowClient {
+        mock: MockPoroClient,
+        refused: Option<&'static str>,
+        in_flight: Cell<usize>,
+        peak: Cell<usize>,
+        requests: Cell<usize>,
+    }
+
+    impl SlowClient {
+        fn new(refused: Option<&'static str>) -> Self {
+            let mock = MockPoroClient::new(HelpBuilder::miniature().json());
+            Self { mock, refused, in_flight: Cell::new(0), peak: Cell::new(0), requests: Cell::new(0) }
+        }
+
+        async fn post(&self, endpoint: String) -> Result<Value, Error> {
+            self.requests.set(self.requests.get() + 1);
+            self.in_flight.set(self.in_flight.get() + 1);
+            self.peak.set(self.peak.get().max(self.in_flight.get()));
+            for _ in endpoint.len()..80 {
+                tokio::task::yield_now().await;
+            }
+            self.in_flight.set(self.in_flight.get() - 1);
+            if self.refused.is_some_and(|refused| endpoint.contains(refused)) {
+                return Err(std::io::Error::other("refused").into());
+            }
+            Ok(self.mock.respond(&endpoint))
+        }
+    }
+
+    fn names<T>(items: &[T], name: impl Fn(&T) -> &str) -> Vec<&str> {
+        items.iter().map(name).collect()
+    }
+
+    #[tokio::test]
+    async fn test_appeared_fetched() {
+        let client = GrowingClient::new();
+        let mut report = SchemaReport::default();
+        let collected = collect(|endpoint| client.post(endpoint), MidRunTargets::Fetch, &mut report).await.unwrap();
+
+        assert_eq!(
+            names(&collected.types, |ty| &ty.info.name),
+            ["LolSummonerSummoner", "LolSummonerSummonerRerollPoints", "LolLobbyLobbyDto"]
+        );
+        assert_eq!(collected.endpoints.len(), 2);
+        assert_eq!(collected.endpoints[1]["path"], "/lol-lobby/v2/lobby");
+        let warnings = report.of_kind(ReportKind::Warning).map(|e| (e.target.as_str(), e.detail.as_str())).collect::<Vec<_>>();
+        assert_eq!(
+            warnings,
+            [
+                ("LolLobbyLobbyDto", "appeared in the help listing mid-run; fetched afterwards"),
+                ("PostLolLobbyV2Lobby", "appeared in the help listing mid-run; fetched afterwards"),
+                ("LolSummonerSummonerRerollPoints", "removed from the help listing mid-run"),
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_appeared_reported() {
+        let client = GrowingClient::new();
+        let mut report = SchemaReport::default();
+        let collected = collect(|endpoint| client.post(endpoint), MidRunTargets::Report, &mut report).await.unwrap();
+
+        assert_eq!(names(&collected.types, |ty| &ty.info.name), ["LolSummonerSummoner", "LolSummonerSummonerRerollPoints"]);
+        assert_eq!(collected.endpoints.len(), 1);
+        // Two listings, two types, and a function's full and console help.
+        assert_eq!(client.requests.get(), 6);
+        let skipped = report.of_kind(ReportKind::Skipped).map(|e| e.target.as_str()).collect::<Vec<_>>();
+        assert_eq!(skipped, ["LolLobbyLobbyDto", "PostLolLobbyV2Lobby"]);
+        assert_eq!(report.of_kind(ReportKind::Warning).count(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_concurrent_matches_sequential() {
+        let bundle_dir = std::env::temp_dir().join("poroshell-collect-bundles");
+        let sequential = SlowClient::new(None);
+        let expected = extended_help(|endpoint| sequential.post(endpoint), MidRunTargets::Fetch, &bundle_dir).await.unwrap();
+        assert_eq!(sequential.peak.get(), 1);
+
+        let client = SlowClient::new(None);
+        let help = extended_help_concurrent(|endpoint| client.post(endpoint), MidRunTargets::Fetch, 4, &bundle_dir).await.unwrap();
+        assert!((2..=4).contains(&client.peak.get()), "{} in flight", client.peak.get());
+        assert_eq!(client.requests.get(), sequential.requests.get());
+        assert_eq!(serde_json::to_value(&help).unwrap(), serde_json::to_value(&expected).unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_concurrent_fails_fast() {
+        let all = SlowClient::new(None);
+        collect_concurrent(|endpoint| all.post(endpoint), MidRunTargets::Fetch, 2, &mut SchemaReport::default()).await.unwrap();
+
+        let client = SlowClient::new(Some("LolLobbyLobbyDto"));
+        let err = collect_concurrent(|endpoint| client.post(endpoint), MidRunTargets::Fetch, 2, &mut SchemaReport::default()).await;
+        assert!(matches!(err, Err(Error::Io(_))));
+        assert!(client.requests.get() < all.requests.get());
+    }
+}
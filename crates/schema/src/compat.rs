@@ -0,0 +1,97 @@
+//! Output for tools written against hasagi's `openapi.json`, so they can read
+//! our spec without changes.
+//!
+//! [`HasagiCompat`] converts with the options hasagi's output corresponds to,
+//! removes everything hasagi doesn't emit, and serializes in
+//! [`SerializationStyle::HasagiCompat`].
+
+use serde_json::Value;
+
+use crate::{
+    error::Error,
+    help::ExtendedHelp,
+    openapi::OpenApiSpec,
+    options::{ SchemaOptions, UnknownFields },
+    report::SchemaReport,
+    style::SerializationStyle,
+};
+
+#[derive(Debug, Clone, Copy, Default)]
+pub struct HasagiCompat;
+
+impl HasagiCompat {
+    /// Conversion options matching hasagi: no enrichments, and object
+    /// components that don't claim to reject unknown fields.
+    pub fn options() -> SchemaOptions {
+        SchemaOptions { unknown_fields: UnknownFields::Unspecified, ..Default::default() }
+    }
+
+    /// Convert `help` and serialize it the way hasagi does.
+    pub fn convert(help: &ExtendedHelp, version: &str, report: &mut SchemaReport) -> Result<String, Error> {
+        let mut spec = OpenApiSpec::from_help_with(help, version, &Self::options(), report)?;
+        Self.apply(&mut spec);
+        spec.to_string_styled(SerializationStyle::HasagiCompat)
+    }
+
+    /// Remove every `x-*` extension, which hasagi's output has none of.
+    pub fn apply(&self, spec: &mut OpenApiSpec) {
+        spec.workflows.clear();
+        spec.events.clear();
+        for tag in spec.tags.iter_mut() {
+            tag.display_name = None;
+        }
+        for value in spec.components.values_mut().chain(spec.paths.values_mut()) {
+            strip_extensions(value);
+        }
+    }
+}
+
+fn strip_extensions(value: &mut Value) {
+    match value {
+        Value::Object(object) => {
+            object.retain(|key, _| !key.starts_with("x-"));
+            object.values_mut().for_each(strip_extensions);
+        }
+        Value::Array(values) => values.iter_mut().for_each(strip_extensions),
+        _ => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{ caution::CautionRules, tests::miniature };
+
+    fn extensions(value: &Value) -> usize {
+        match value {
+            Value::Object(object) => {
+                object.keys().filter(|key| key.starts_with("x-")).count() + object.values().map(extensions).sum::<usize>()
+            }
+            Value::Array(values) => values.iter().map(extensions).sum(),
+            _ => 0,
+        }
+    }
+
+    #[test]
+    fn test_no_extensions() {
+        let options = SchemaOptions {
+            include_extensions: true,
+            caution: Some(CautionRules::default()),
+            slugs: true,
+            ..Default::default()
+        };
+        let mut spec = OpenApiSpec::from_help_with(&miniature(), "1.0.0", &options, &mut SchemaReport::default()).unwrap();
+        assert!(extensions(&serde_json::to_value(&spec).unwrap()) > 0);
+        HasagiCompat.apply(&mut spec);
+        assert_eq!(extensions(&serde_json::to_value(&spec).unwrap()), 0);
+    }
+
+    #[test]
+    fn test_convert() {
+        let json = HasagiCompat::convert(&miniature(), "1.0.0", &mut SchemaReport::default()).unwrap();
+        let spec: Value = serde_json::from_str(&json).unwrap();
+        assert_eq!(extensions(&spec), 0);
+        assert!(spec["components"]["schemas"]["LolSummonerSummoner"].get("additionalProperties").is_none());
+        assert!(json.find("\"tags\"").unwrap() < json.find("\"paths\"").unwrap());
+    }
+}
@@ -0,0 +1,294 @@
+//! Renaming components after conversion, for the names the LCU makes awkward
+//! in generated code, like `LolLobbyTeamBuilderChampSelectV1Session`.
+//!
+//! A [`ComponentRenames`] file maps names one to one and has rules for the
+//! rest. Every ref follows its component, and a renamed component keeps its
+//! help name in [`X_RENAMED_FROM`] so it can be traced back.
+
+use std::{ collections::BTreeMap, fmt, fs, path::Path };
+
+use regex::Regex;
+use serde::{ Deserialize, Serialize };
+use serde_json::Value;
+
+use crate::{
+    error::Error,
+    fragment::rewrite_refs,
+    openapi::OpenApiSpec,
+    renames::X_RENAMED_FROM,
+    report::{ ReportKind, SchemaReport },
+};
+
+/// Prefix of refs to component schemas.
+const SCHEMA_REF: &str = "#/components/schemas/";
+
+/// A regex and what to replace its first match with, e.g. `^LolLobbyTeamBuilder`
+/// and `TeamBuilder`.
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Eq)]
+pub struct RenameRule {
+    pub pattern: String,
+    /// `$1` or `${name}` put in a capture group, as in [`Regex::replace`].
+    pub replacement: String,
+}
+
+/// A renames file.
+#[derive(Deserialize, Serialize, Debug, Clone, Default, PartialEq, Eq)]
+#[serde(default)]
+pub struct ComponentRenames {
+    /// Old names to new ones. A component listed here skips the rules.
+    pub renames: BTreeMap<String, String>,
+    /// Applied in the order they're declared, each to what the previous one left.
+    pub rules: Vec<RenameRule>,
+}
+
+/// Why components can't be renamed. Nothing is renamed when one is returned.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ComponentRenameError {
+    /// A rule whose pattern doesn't compile.
+    Pattern { pattern: String, message: String },
+    /// Components that would end up with the same name, sorted. One of them
+    /// may be a component keeping its own name.
+    Collision { to: String, from: Vec<String> },
+    /// A component that would end up with no name.
+    Empty { from: String },
+}
+
+impl fmt::Display for ComponentRenameError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Pattern { pattern, message } => write!(f, "rename rule {pattern} doesn't compile: {message}"),
+            Self::Collision { to, from } => write!(f, "{} would all be named {to}", from.join(", ")),
+            Self::Empty { from } => write!(f, "{from} would be renamed to an empty name"),
+        }
+    }
+}
+
+impl std::error::Error for ComponentRenameError {}
+
+impl ComponentRenames {
+    pub fn load(path: &Path) -> Result<Self, Error> {
+        Ok(serde_json::from_str(&fs::read_to_string(path)?)?)
+    }
+
+    /// The new name of each of `names` that gets one, by old name.
+    pub fn plan<'a>(&self, names: impl IntoIterator<Item = &'a str>) -> Result<BTreeMap<String, String>, ComponentRenameError> {
+        let rules = self.rules
+            .iter()
+            .map(|rule| {
+                Regex::new(&rule.pattern)
+                    .map(|regex| (regex, rule.replacement.as_str()))
+                    .map_err(|err| ComponentRenameError::Pattern { pattern: rule.pattern.clone(), message: err.to_string() })
+            })
+            .collect::<Result<Vec<(Regex, &str)>, ComponentRenameError>>()?;
+
+        let mut plan = BTreeMap::new();
+        for name in names {
+            let renamed = match self.renames.get(name) {
+                Some(renamed) => renamed.clone(),
+                None => rules.iter().fold(name.to_string(), |name, (regex, replacement)| regex.replace(&name, *replacement).into_owned()),
+            };
+            if renamed != name {
+                plan.insert(name.to_string(), renamed);
+            }
+        }
+        Ok(plan)
+    }
+
+    /// Rename the spec's components, recording each rename in `report`, and
+    /// warning about explicit renames of components the spec doesn't have.
+    /// Returns the number of components renamed.
+    pub fn apply(&self, spec: &mut OpenApiSpec, report: &mut SchemaReport) -> Result<usize, ComponentRenameError> {
+        let schemas = spec.components.get("schemas").and_then(Value::as_object);
+        let names = schemas.into_iter().flat_map(|schemas| schemas.keys().cloned()).collect::<Vec<String>>();
+        let plan = self.plan(names.iter().map(String::as_str))?;
+        spec.rename_components(&plan)?;
+
+        for from in self.renames.keys().filter(|from| !names.contains(from)) {
+            report.warn(from, "no component by this name to rename");
+        }
+        for (from, to) in plan.iter() {
+            report.push(ReportKind::Renamed, from, format!("component renamed to {to}"));
+        }
+        Ok(plan.len())
+    }
+}
+
+impl OpenApiSpec {
+    /// Rename one component, see [`OpenApiSpec::rename_components`].
+    pub fn rename_component(&mut self, from: &str, to: &str) -> Result<(), ComponentRenameError> {
+        self.rename_components(&BTreeMap::from([(from.to_string(), to.to_string())]))
+    }
+
+    /// Rename components, old name to new, and rewrite every ref to them in
+    /// the components, paths, and events. Each renamed component gets
+    /// [`X_RENAMED_FROM`]. Names the spec has no component by are ignored.
+    ///
+    /// Renames happen all at once, so `A` to `B` and `B` to `C` is fine, but
+    /// two components can't end up with one name.
+    pub fn rename_components(&mut self, renames: &BTreeMap<String, String>) -> Result<(), ComponentRenameError> {
+        let Some(Value::Object(schemas)) = self.components.get_mut("schemas") else {
+            return Ok(());
+        };
+        let renamed = |name: &String| renames.get(name).unwrap_or(name).clone();
+
+        let mut targets = BTreeMap::<String, Vec<String>>::new();
+        for name in schemas.keys() {
+            let to = renamed(name);
+            if to.is_empty() {
+                return Err(ComponentRenameError::Empty { from: name.clone() });
+            }
+            targets.entry(to).or_default().push(name.clone());
+        }
+        if let Some((to, mut from)) = targets.into_iter().find(|(_, from)| from.len() > 1) {
+            from.sort();
+            return Err(ComponentRenameError::Collision { to, from });
+        }
+
+        *schemas = std::mem::take(schemas)
+            .into_iter()
+            .map(|(name, mut schema)| {
+                let to = renamed(&name);
+                if to != name && let Value::Object(schema) = &mut schema {
+                    schema.insert(X_RENAMED_FROM.to_string(), Value::from(name));
+                }
+                (to, schema)
+            })
+            .collect();
+
+        let mut rewrite = |reference: &mut String| {
+            if let Some(to) = reference.strip_prefix(SCHEMA_REF).and_then(|name| renames.get(name)) {
+                *reference = format!("{SCHEMA_REF}{to}");
+            }
+        };
+        for value in self.components.values_mut().chain(self.paths.values_mut()).chain(self.events.values_mut()) {
+            rewrite_refs(value, &mut rewrite);
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+    use crate::{ index::direct_refs, options::SchemaOptions, tests::{ miniature, miniature_spec } };
+
+    fn rule(pattern: &str, replacement: &str) -> RenameRule {
+        RenameRule { pattern: pattern.to_string(), replacement: replacement.to_string() }
+    }
+
+    fn names(spec: &OpenApiSpec) -> Vec<&str> {
+        spec.components["schemas"].as_object().unwrap().keys().map(String::as_str).collect()
+    }
+
+    #[test]
+    fn test_explicit_renames() {
+        let renames = ComponentRenames {
+            renames: BTreeMap::from([
+                ("LolSummonerSummoner".to_string(), "Summoner".to_string()),
+                ("LolMissing".to_string(), "Missing".to_string()),
+            ]),
+            rules: vec![rule("^LolSummoner", "Rule")],
+        };
+        let mut spec = miniature_spec("1.0.0");
+        let mut report = SchemaReport::default();
+        assert_eq!(renames.apply(&mut spec, &mut report).unwrap(), 3);
+
+        let names = names(&spec);
+        assert!(names.contains(&"Summoner"));
+        assert!(names.contains(&"RuleSummonerRerollPoints"));
+        assert!(!names.contains(&"LolSummonerSummoner"));
+        let renamed = report.of_kind(ReportKind::Renamed).map(|entry| (entry.target.as_str(), entry.detail.as_str())).collect::<Vec<_>>();
+        assert!(renamed.contains(&("LolSummonerSummoner", "component renamed to Summoner")));
+        assert_eq!(report.of_kind(ReportKind::Warning).next().unwrap().target, "LolMissing");
+    }
+
+    #[test]
+    fn test_rules_in_order() {
+        let renames = ComponentRenames {
+            renames: BTreeMap::new(),
+            rules: vec![rule("^Lol(?<plugin>Lobby|Summoner)", "${plugin}"), rule("Dto$", ""), rule("^Lobby(.+)", "Lobby.$1")],
+        };
+        let plan = renames.plan(["LolLobbyLobbyDto", "LolLobbyLobbyParticipantDto", "LolSummonerSummoner", "Other"]).unwrap();
+        assert_eq!(
+            plan,
+            BTreeMap::from([
+                ("LolLobbyLobbyDto".to_string(), "Lobby.Lobby".to_string()),
+                ("LolLobbyLobbyParticipantDto".to_string(), "Lobby.LobbyParticipant".to_string()),
+                ("LolSummonerSummoner".to_string(), "SummonerSummoner".to_string()),
+            ])
+        );
+
+        let broken = ComponentRenames { renames: BTreeMap::new(), rules: vec![rule("(", "")] };
+        assert!(matches!(broken.plan(["Other"]), Err(ComponentRenameError::Pattern { .. })));
+    }
+
+    #[test]
+    fn test_collision() {
+        let mut spec = miniature_spec("1.0.0");
+        let before = spec.clone();
+        let renames = ComponentRenames { renames: BTreeMap::new(), rules: vec![rule("^LolSummonerSummoner.*", "Summoner")] };
+        let err = renames.apply(&mut spec, &mut SchemaReport::default()).unwrap_err();
+        assert_eq!(
+            err,
+            ComponentRenameError::Collision {
+                to: "Summoner".to_string(),
+                from: vec!["LolSummonerSummoner".to_string(), "LolSummonerSummonerRerollPoints".to_string()],
+            }
+        );
+        assert_eq!(err.to_string(), "LolSummonerSummoner, LolSummonerSummonerRerollPoints would all be named Summoner");
+
+        // Onto a component that keeps its name.
+        let err = spec.rename_component("LolLobbyLobbyDto", "LolSummonerSummoner").unwrap_err();
+        assert!(matches!(err, ComponentRenameError::Collision { to, .. } if to == "LolSummonerSummoner"));
+        assert_eq!(serde_json::to_value(&spec).unwrap(), serde_json::to_value(&before).unwrap());
+    }
+
+    #[test]
+    fn test_refs_rewritten() {
+        let mut spec = miniature_spec("1.0.0");
+        spec.rename_components(&BTreeMap::from([
+            ("LolSummonerSummoner".to_string(), "Summoner".to_string()),
+            ("LolSummonerSummonerRerollPoints".to_string(), "RerollPoints".to_string()),
+            ("LolLobbyLobbyParticipantDto".to_string(), "LobbyMember".to_string()),
+        ])).unwrap();
+
+        let schemas = &spec.components["schemas"];
+        assert_eq!(schemas["Summoner"]["properties"]["rerollPoints"], json!({ "$ref": "#/components/schemas/RerollPoints" }));
+        assert_eq!(
+            schemas["LolLobbyLobbyDto"]["properties"]["members"]["items"],
+            json!({ "$ref": "#/components/schemas/LobbyMember" })
+        );
+        assert_eq!(
+            spec.paths["/lol-summoner/v1/summoners/{id}"]["get"]["responses"]["200"]["content"]["application/json"]["schema"],
+            json!({ "$ref": "#/components/schemas/Summoner" })
+        );
+        assert_eq!(spec.events["OnJsonApiEvent_lol-summoner_v1_current-summoner"]["schema"]["$ref"], "#/components/schemas/Summoner");
+
+        // No ref is left pointing at a component that isn't there.
+        let whole = serde_json::to_value(&spec).unwrap();
+        let names = names(&spec);
+        assert!(direct_refs(&whole).iter().all(|name| names.contains(&name.as_str())));
+    }
+
+    #[test]
+    fn test_traceability() {
+        let renames = ComponentRenames {
+            renames: BTreeMap::from([("LolSummonerSummoner".to_string(), "Summoner".to_string())]),
+            rules: Vec::new(),
+        };
+        let options = SchemaOptions { component_renames: Some(renames), ..Default::default() };
+        let mut report = SchemaReport::default();
+        let spec = OpenApiSpec::from_help_with(&miniature(), "1.0.0", &options, &mut report).unwrap();
+
+        let schemas = &spec.components["schemas"];
+        assert_eq!(schemas["Summoner"][X_RENAMED_FROM], "LolSummonerSummoner");
+        assert!(schemas["LolLobbyLobbyDto"].get(X_RENAMED_FROM).is_none());
+        assert_eq!(report.of_kind(ReportKind::Renamed).count(), 1);
+
+        let file: ComponentRenames = serde_json::from_value(json!({ "rules": [{ "pattern": "^Lol", "replacement": "" }] })).unwrap();
+        assert!(file.renames.is_empty());
+        assert_eq!(file.plan(["LolLobbyLobbyDto"]).unwrap()["LolLobbyLobbyDto"], "LobbyLobbyDto");
+    }
+}
@@ -0,0 +1,153 @@
+//! Contract tests for the published `extended-help.json`.
+//!
+//! External tools parse the file, so a renamed or dropped key breaks them even
+//! when everything here still compiles. If a change to the shape is intended,
+//! update `crates/test-utils/fixtures/contract-extended-help.json` and
+//! [`EXPECTED_KEYS`] together and call the change out in the release notes.
+
+use std::collections::BTreeMap;
+
+use serde_json::{ Value, json };
+
+use crate::{
+    help::{ ArgLocation, Argument, DataType, Endpoint, Event, ExtendedHelp, Field, Info, Type },
+    report::SchemaReport,
+};
+
+const FIXTURE: &str = poroshell_test_utils::fixtures::CONTRACT_HELP;
+
+const HOW_TO_UPDATE: &str =
+    "if this change to extended-help.json is intended, update crates/test-utils/fixtures/contract-extended-help.json \
+     and EXPECTED_KEYS in crates/schema/src/contract.rs, and mention it in the release notes";
+
+/// Every key of each kind of object in the published JSON.
+const EXPECTED_KEYS: [(&str, &[&str]); 8] = [
+    ("ExtendedHelp", &["endpoints", "events", "types"]),
+    ("Type", &["description", "fields", "name", "nameSpace", "size", "tags", "values"]),
+    ("Value", &["description", "name", "value"]),
+    ("Field", &["description", "name", "offset", "optional", "type"]),
+    (
+        "Endpoint",
+        &[
+            "argLocations",
+            "arguments",
+            "async",
+            "description",
+            "help",
+            "method",
+            "name",
+            "nameSpace",
+            "overridden",
+            "path",
+            "pathParams",
+            "requestContentType",
+            "returns",
+            "silentOverride",
+            "tags",
+            "threadSafe",
+        ],
+    ),
+    ("Argument", &["description", "name", "optional", "type"]),
+    ("Event", &["description", "name", "nameSpace", "tags", "type"]),
+    ("DataType", &["elementType", "type"]),
+];
+
+fn info(name: &str, description: &str) -> Info {
+    Info { name: name.to_string(), description: description.to_string() }
+}
+
+fn data_type(ty: &str) -> DataType {
+    DataType { element_type: String::new(), ty: ty.to_string() }
+}
+
+/// An [`ExtendedHelp`] with every optional field set, so every key is written.
+fn built() -> ExtendedHelp {
+    let value = serde_json::from_value(json!({ "name": "PRIVATE", "description": "", "value": 0 })).unwrap();
+    ExtendedHelp {
+        types: vec![Type {
+            values: vec![value],
+            fields: vec![Field {
+                info: info("summonerId", ""),
+                offset: 0,
+                is_optional: false,
+                ty: data_type("uint64"),
+            }],
+            info: info("LolSummonerSummoner", ""),
+            namespace: "Plugins".to_string(),
+            size: 8,
+            tags: vec!["Plugin lol-summoner".to_string()],
+        }],
+        endpoints: vec![Endpoint {
+            info: info("GetLolSummonerV1SummonersById", ""),
+            namespace: "Plugins".to_string(),
+            help: String::new(),
+            arguments: vec![Argument { info: info("id", ""), is_optional: false, ty: data_type("uint64") }],
+            tags: vec!["Plugin lol-summoner".to_string()],
+            method: Some("GET".to_string()),
+            path: Some("/lol-summoner/v1/summoners/{id}".to_string()),
+            path_params: vec!["id".to_string()],
+            request_content_type: Some("application/json".to_string()),
+            arg_locations: BTreeMap::from([("id".to_string(), ArgLocation::Path)]),
+            return_ty: data_type("LolSummonerSummoner"),
+            is_async: false,
+            is_thread_safe: false,
+            is_override: false,
+            is_silent_override: false,
+        }],
+        events: vec![Event {
+            info: info("OnJsonApiEvent", ""),
+            namespace: "Builtin".to_string(),
+            tags: Vec::new(),
+            ty: data_type("object"),
+        }],
+        report: SchemaReport::default(),
+    }
+}
+
+/// Record the keys of `value`, an object of `kind`, and of the objects inside it.
+fn collect_keys(kind: &'static str, value: &Value, found: &mut BTreeMap<&'static str, Vec<String>>) {
+    let Some(object) = value.as_object() else {
+        return;
+    };
+    found.entry(kind).or_default().extend(object.keys().cloned());
+    let children: &[(&str, &'static str)] = match kind {
+        "ExtendedHelp" => &[("types", "Type"), ("endpoints", "Endpoint"), ("events", "Event")],
+        "Type" => &[("values", "Value"), ("fields", "Field")],
+        "Field" | "Argument" | "Event" => &[("type", "DataType")],
+        "Endpoint" => &[("arguments", "Argument"), ("returns", "DataType")],
+        _ => &[],
+    };
+    for (key, child) in children {
+        match &object[*key] {
+            Value::Array(items) => items.iter().for_each(|item| collect_keys(child, item, found)),
+            item => collect_keys(child, item, found),
+        }
+    }
+}
+
+#[test]
+fn test_fixture_round_trip() {
+    let help: ExtendedHelp = FIXTURE.parse().unwrap();
+    // Without `preserve_order`, serde_json writes object keys sorted, like the fixture.
+    let written = serde_json::to_string_pretty(&serde_json::to_value(&help).unwrap()).unwrap() + "\n";
+    assert!(written == FIXTURE, "extended-help.json no longer round-trips; {HOW_TO_UPDATE}\n{written}");
+}
+
+#[test]
+fn test_keys() {
+    let mut found = BTreeMap::new();
+    collect_keys("ExtendedHelp", &serde_json::to_value(built()).unwrap(), &mut found);
+
+    for (kind, expected) in EXPECTED_KEYS {
+        let mut keys = found.remove(kind).unwrap_or_default();
+        keys.sort();
+        keys.dedup();
+        for key in expected {
+            assert!(keys.iter().any(|k| k == key), "{kind} no longer has the key `{key}`; {HOW_TO_UPDATE}");
+        }
+        for key in keys.iter() {
+            assert!(expected.contains(&key.as_str()), "{kind} has the new key `{key}`; {HOW_TO_UPDATE}");
+        }
+    }
+    assert!(found.is_empty(), "unexpected kinds {:?}; {HOW_TO_UPDATE}", found.keys());
+}
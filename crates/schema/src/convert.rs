@@ -0,0 +1,1478 @@
+use std::{ cell::RefCell, collections::{ BTreeMap, BTreeSet } };
+
+use fxhash::{ FxHashMap, FxHashSet };
+use serde_json::{ Map, Value };
+
+use crate::{
+    caution::CautionAnnotations,
+    cycles::report_cycles,
+    drift::SpecCounts,
+    enums::EnumSchema,
+    example::ExampleMode,
+    explain::{ Stage, Tracer },
+    error::Error,
+    fallback::{ Fallback, FallbackSite, record_fallbacks },
+    lint::{ lint, lint_experimental_workflows },
+    help::{ split_console_url, ArgLocation, Argument, DataType, Endpoint, Event, ExtendedHelp, Type, MAP_TYPE, PRIMITIVE_TYPES, VECTOR_TYPE },
+    options::{ PrivacyPolicy, SchemaOptions, UnknownFields },
+    openapi::{
+        non_empty,
+        EventSchema,
+        OpenApiInfo,
+        OpenApiSpec,
+        Operation,
+        Parameter,
+        ParameterLocation,
+        RequestBody,
+        Response,
+        SchemaObject,
+        Tag,
+    },
+    report::{ ReportKind, SchemaReport },
+    routing::X_GREEDY_PARAMS,
+    sanitize::sanitize_spec,
+    servers::{ host_server, is_local_host, ServerAnnotations, X_EXTERNAL_HOST },
+    since::SinceAnnotations,
+    slug::SlugAnnotations,
+    stability::StabilityAnnotations,
+    tags::TagNames,
+    transform::UsageAnnotations,
+    workflow::WorkflowTags,
+};
+
+/// Prefix of the help tags that name the plugin owning a type or endpoint.
+const PLUGIN_TAG_PREFIX: &str = "Plugin ";
+
+/// Operation tag shared by every endpoint that belongs to a plugin.
+pub const PLUGINS_TAG: &str = "plugins";
+
+/// HTTP methods that may carry a request body.
+const BODY_METHODS: [&str; 3] = ["post", "put", "patch"];
+
+const JSON: &str = "application/json";
+const TEXT_PLAIN: &str = "text/plain";
+
+/// Operation extension holding the endpoint's console help text. It isn't
+/// `externalDocs` because OpenAPI requires that to have a `url`, and the help
+/// text has none.
+pub const CONSOLE_HELP_EXTENSION: &str = "x-console-help";
+
+/// Marks components redacted under [`PrivacyPolicy::Redact`].
+pub const X_REDACTED: &str = "x-redacted";
+
+/// HTTP methods an operation may be emitted under.
+const METHODS: [&str; 7] = ["get", "post", "put", "patch", "delete", "head", "options"];
+
+/// Returns the plugin name from a help tag like `Plugin lol-summoner`.
+pub fn plugin_from_tag(tag: &str) -> Option<&str> {
+    tag.strip_prefix(PLUGIN_TAG_PREFIX)
+}
+
+impl OpenApiSpec {
+    /// Convert [`ExtendedHelp`] into an OpenAPI v3 spec without touching the network.
+    ///
+    /// ```no_run
+    /// # fn run(json: &str) -> Result<(), poro_schema::error::Error> {
+    /// use poro_schema::{ help::ExtendedHelp, openapi::OpenApiSpec };
+    ///
+    /// let help: ExtendedHelp = json.parse()?;
+    /// let spec = OpenApiSpec::from_help(&help, "14.3.556.1234")?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn from_help(help: &ExtendedHelp, version: impl Into<String>) -> Result<Self, Error> {
+        Self::from_help_with(help, version, &SchemaOptions::default(), &mut SchemaReport::default())
+    }
+
+    /// Like [`OpenApiSpec::from_help`], recording what the conversion did in `report`.
+    pub fn from_help_with(
+        help: &ExtendedHelp,
+        version: impl Into<String>,
+        options: &SchemaOptions,
+        report: &mut SchemaReport
+    ) -> Result<Self, Error> {
+        Self::from_help_traced(help, version, options, report, &mut Tracer::off())
+    }
+
+    /// The conversion, with `tracer` shown the spec after every stage.
+    pub(crate) fn from_help_traced(
+        help: &ExtendedHelp,
+        version: impl Into<String>,
+        options: &SchemaOptions,
+        report: &mut SchemaReport,
+        tracer: &mut Tracer
+    ) -> Result<Self, Error> {
+        let mut spec = OpenApiSpec::new(OpenApiInfo::lcu(version))
+            .with_components(components_from(help, options, report)?)
+            .with_paths(paths_from(help, options, report)?)
+            .with_tags(tags_from(help))
+            .with_events(events_from(help, options, report)?);
+        tracer.step(Stage::Convert, &spec);
+        // Before anything copies help text around.
+        sanitize_spec(&mut spec, report);
+        tracer.step(Stage::Sanitize, &spec);
+        // Before inlining, so inlined copies are rewritten with their components.
+        if let Some(int64) = options.int64_as_string.as_ref() {
+            int64.apply(&mut spec);
+            tracer.step(Stage::Int64AsString, &spec);
+        }
+        if let Some(inline) = options.inline_components.as_ref() {
+            inline.apply(&mut spec, report);
+            tracer.step(Stage::Inline, &spec);
+        }
+        // After inlining, which would copy the shared handle back out.
+        if let Some(envelopes) = options.envelopes.as_ref() {
+            envelopes.apply(&mut spec, report);
+            tracer.step(Stage::Envelopes, &spec);
+        }
+        if let Some(history) = options.history.as_ref() {
+            SinceAnnotations { history }.apply(&mut spec);
+            tracer.step(Stage::Since, &spec);
+        }
+        if options.slugs {
+            SlugAnnotations { history: options.history.as_ref() }.apply(&mut spec);
+            tracer.step(Stage::Slugs, &spec);
+        }
+        if let Some(rules) = options.caution.as_ref() {
+            CautionAnnotations { rules }.apply(&mut spec);
+            tracer.step(Stage::Caution, &spec);
+        }
+        if let Some(rules) = options.stability.as_ref() {
+            StabilityAnnotations { rules, history: options.history.as_ref() }.apply(&mut spec);
+            tracer.step(Stage::Stability, &spec);
+        }
+        if let Some(rules) = options.servers.as_ref() {
+            ServerAnnotations { rules }.apply(&mut spec);
+            tracer.step(Stage::Servers, &spec);
+        }
+        if let Some(workflows) = options.workflows.as_ref() {
+            WorkflowTags { workflows }.apply(&mut spec, report);
+            tracer.step(Stage::Workflows, &spec);
+        }
+        if let Some(examples) = options.request_examples.as_ref() &&
+            examples.mode == ExampleMode::Inline
+        {
+            examples.apply(&mut spec);
+            tracer.step(Stage::Examples, &spec);
+        }
+        if let Some(samples) = options.code_samples.as_ref() {
+            samples.apply(&mut spec);
+            tracer.step(Stage::Samples, &spec);
+        }
+        if let Some(renames) = options.component_renames.as_ref() {
+            renames.apply(&mut spec, report)?;
+            tracer.step(Stage::Renames, &spec);
+        }
+        // Last, so the annotations see the final refs.
+        if options.include_extensions {
+            UsageAnnotations::default().apply(&mut spec);
+            tracer.step(Stage::Usage, &spec);
+        }
+        if let Some(drift) = options.drift.as_ref() {
+            drift.gate.check(&drift.baseline, &SpecCounts::from_spec(&spec), report)?;
+        }
+        report_cycles(&spec.components, report);
+        lint(&spec, report);
+        if options.stability.as_ref().is_some_and(|rules| rules.warn_in_workflows) {
+            lint_experimental_workflows(&spec, report);
+        }
+        Ok(spec)
+    }
+}
+
+/// Build the `components` section from the help types.
+pub fn components_from(
+    help: &ExtendedHelp,
+    options: &SchemaOptions,
+    report: &mut SchemaReport
+) -> Result<Map<String, Value>, Error> {
+    report_private_types(help, options, report);
+
+    let resolver = TypeResolver::new(help, options);
+    let mut schemas = Map::new();
+    for ty in help.types.iter() {
+        if ty.is_private() && options.privacy == PrivacyPolicy::Obscure {
+            continue;
+        }
+        let schema = resolver.component(ty);
+        record_fallbacks(&ty.info.name, resolver.take_fallbacks(), options.strict, report)?;
+        schemas.insert(ty.info.name.clone(), serde_json::to_value(schema)?);
+    }
+
+    let mut components = Map::new();
+    components.insert("schemas".to_string(), Value::Object(schemas));
+    Ok(components)
+}
+
+/// Build the `paths` section from the help endpoints.
+/// Endpoints without an HTTP path (e.g. websocket-only functions) are skipped.
+/// Endpoints whose URL names a host are keyed by the URL's path and keep the
+/// host in [`X_EXTERNAL_HOST`]; hosts other than this machine are left out
+/// with a warning.
+pub fn paths_from(
+    help: &ExtendedHelp,
+    options: &SchemaOptions,
+    report: &mut SchemaReport
+) -> Result<Map<String, Value>, Error> {
+    let resolver = TypeResolver::new(help, options);
+    let mut paths = Map::new();
+    for endpoint in help.endpoints.iter() {
+        let Some(url) = endpoint.path.as_ref() else {
+            continue;
+        };
+        let (host, path) = split_console_url(url);
+        if let Some(host) = host && !is_local_host(host) {
+            report.push(ReportKind::Warning, &endpoint.info.name, format!("{url} is on external host {host}, left out"));
+            continue;
+        }
+        let path = match normalize_path(path) {
+            Ok(path) => path_template(&path),
+            Err(detail) => {
+                report.push(ReportKind::Skipped, &endpoint.info.name, detail);
+                continue;
+            }
+        };
+        let method = method_of(endpoint);
+        let mut operation = resolver.operation(endpoint, &method, report);
+        if let Some(host) = host {
+            operation.additional_fields.insert(X_EXTERNAL_HOST.to_string(), Value::from(host));
+            operation.servers.extend(host_server(host));
+        }
+        let fallbacks = method_fallback(endpoint)
+            .map(|detail| Fallback { site: FallbackSite::Method, detail })
+            .into_iter()
+            .chain(resolver.take_fallbacks())
+            .chain(operation.tags.is_empty().then(|| Fallback {
+                site: FallbackSite::Tags,
+                detail: "no tags; viewers group it by its path".to_string(),
+            }));
+        record_fallbacks(&endpoint.info.name, fallbacks, options.strict, report)?;
+        let operation = serde_json::to_value(operation)?;
+
+        let path_item = paths.entry(path.clone()).or_insert_with(|| Value::Object(Map::new()));
+        if let Value::Object(path_item) = path_item {
+            if path_item.contains_key(&method) {
+                report.push(
+                    ReportKind::Merged,
+                    &endpoint.info.name,
+                    format!("{} {path} already defined, kept the first", method.to_uppercase())
+                );
+                continue;
+            }
+            path_item.insert(method, operation);
+        }
+    }
+    Ok(paths)
+}
+
+/// Build the [`crate::openapi::X_EVENTS`] section from the help events.
+///
+/// Payloads reference the same components as the operations, so a type the
+/// help doesn't define falls back to a generic object here too.
+pub fn events_from(
+    help: &ExtendedHelp,
+    options: &SchemaOptions,
+    report: &mut SchemaReport
+) -> Result<Map<String, Value>, Error> {
+    let resolver = TypeResolver::new(help, options);
+    let mut events = Map::new();
+    for event in help.events.iter() {
+        let schema = resolver.event(event);
+        record_fallbacks(&event.info.name, resolver.take_fallbacks(), options.strict, report)?;
+        if events.contains_key(&event.info.name) {
+            report.push(ReportKind::Merged, &event.info.name, "event already defined, kept the first");
+            continue;
+        }
+        events.insert(event.info.name.clone(), serde_json::to_value(schema)?);
+    }
+    Ok(events)
+}
+
+/// Normalize a console URL so equivalent routes share one path key.
+///
+/// Collapses duplicate slashes, strips trailing slashes (except the root), and
+/// percent-encodes spaces. Paths with nested, unclosed, unopened, or empty
+/// braces are rejected with a description of the problem.
+pub fn normalize_path(path: &str) -> Result<String, String> {
+    let mut depth = 0;
+    let mut empty = false;
+    let mut previous = ' ';
+    for c in path.chars() {
+        match c {
+            '{' if depth > 0 => {
+                return Err(format!("nested brace in path {path}"));
+            }
+            '{' => {
+                depth += 1;
+            }
+            '}' if depth == 0 => {
+                return Err(format!("unopened brace in path {path}"));
+            }
+            '}' => {
+                depth -= 1;
+                empty |= previous == '{';
+            }
+            _ => {}
+        }
+        previous = c;
+    }
+    if depth > 0 {
+        return Err(format!("unclosed brace in path {path}"));
+    }
+    if empty {
+        return Err(format!("empty parameter in path {path}"));
+    }
+
+    let segments = path
+        .split('/')
+        .filter(|segment| !segment.is_empty())
+        .collect::<Vec<&str>>();
+    Ok(format!("/{}", segments.join("/")).replace(' ', "%20"))
+}
+
+/// Record every private type, how it was handled, and what references it.
+fn report_private_types(help: &ExtendedHelp, options: &SchemaOptions, report: &mut SchemaReport) {
+    let private = help.types
+        .iter()
+        .filter(|ty| ty.is_private())
+        .map(|ty| ty.info.name.as_str())
+        .collect::<FxHashSet<&str>>();
+    if private.is_empty() {
+        return;
+    }
+
+    // Every (data type, referrer) pair in the help data.
+    let uses = help.types
+        .iter()
+        .flat_map(|ty| ty.fields.iter().map(|f| (&f.ty, ty.info.name.as_str())))
+        .chain(
+            help.endpoints.iter().flat_map(|endpoint| {
+                endpoint.arguments
+                    .iter()
+                    .map(|arg| &arg.ty)
+                    .chain(std::iter::once(&endpoint.return_ty))
+                    .map(|ty| (ty, endpoint.info.name.as_str()))
+            })
+        )
+        .chain(help.events.iter().map(|event| (&event.ty, event.info.name.as_str())));
+
+    let mut references = FxHashMap::<&str, Vec<&str>>::default();
+    for (ty, referrer) in uses {
+        for name in [ty.ty.as_str(), ty.element_type.as_str()] {
+            if let Some(name) = private.get(name) {
+                references.entry(name).or_default().push(referrer);
+            }
+        }
+    }
+
+    let handling = match options.privacy {
+        PrivacyPolicy::Obscure => "obscured",
+        PrivacyPolicy::Include => "included",
+        PrivacyPolicy::Redact => "redacted",
+    };
+    let mut names = private.into_iter().collect::<Vec<&str>>();
+    names.sort();
+    for name in names {
+        let mut referrers = references.remove(name).unwrap_or_default();
+        referrers.sort();
+        referrers.dedup();
+        let detail = if referrers.is_empty() {
+            format!("{handling}; not referenced")
+        } else {
+            format!("{handling}; referenced by {}", referrers.join(", "))
+        };
+        report.push(ReportKind::Private, name, detail);
+    }
+}
+
+/// Build the top-level tag list from every tag used by an operation.
+pub fn tags_from(help: &ExtendedHelp) -> Vec<Tag> {
+    let endpoints = help.endpoints.iter().filter(|e| e.path.is_some());
+    let plugins = endpoints
+        .clone()
+        .flat_map(|e| e.tags.iter())
+        .filter_map(|tag| plugin_from_tag(tag))
+        .collect::<BTreeSet<&str>>();
+    let mut tags = endpoints
+        .flat_map(operation_tags)
+        .collect::<Vec<String>>();
+    tags.sort();
+    tags.dedup();
+    let names = TagNames::starter();
+    tags.into_iter()
+        .map(|name| {
+            let is_plugin = plugins.contains(name.as_str());
+            Tag { display_name: Some(names.display_name(&name, is_plugin)), name }
+        })
+        .collect()
+}
+
+/// Operation tags for an endpoint. Plugin endpoints are tagged with both
+/// [`PLUGINS_TAG`] and the plugin name.
+pub fn operation_tags(endpoint: &Endpoint) -> Vec<String> {
+    spec_tags(&endpoint.tags)
+}
+
+/// Help tags as the spec writes them, with `Plugin lol-lobby` split into
+/// [`PLUGINS_TAG`] and `lol-lobby`.
+fn spec_tags(help_tags: &[String]) -> Vec<String> {
+    let mut tags = Vec::new();
+    for tag in help_tags.iter() {
+        match plugin_from_tag(tag) {
+            Some(plugin) => {
+                tags.push(PLUGINS_TAG.to_string());
+                tags.push(plugin.to_string());
+            }
+            None => tags.push(tag.clone()),
+        }
+    }
+    tags.dedup();
+    tags
+}
+
+/// The request body content type: the patched override, `text/plain` for a
+/// bare string argument, and JSON for everything else.
+pub fn request_content_type<'a>(endpoint: &'a Endpoint, body: &Argument) -> &'a str {
+    match endpoint.request_content_type.as_deref() {
+        Some(content_type) => content_type,
+        None if body.ty.ty == "string" => TEXT_PLAIN,
+        None => JSON,
+    }
+}
+
+/// The lowercase HTTP method of an endpoint, defaulting to `get` when unknown.
+pub fn method_of(endpoint: &Endpoint) -> String {
+    let method = endpoint.method.as_deref().unwrap_or("get").to_lowercase();
+    if METHODS.contains(&method.as_str()) { method } else { "get".to_string() }
+}
+
+/// Why [`method_of`] defaulted to `get`, if it did.
+pub(crate) fn method_fallback(endpoint: &Endpoint) -> Option<String> {
+    match endpoint.method.as_deref() {
+        None => Some("no method; emitted as GET".to_string()),
+        Some(method) if !METHODS.contains(&method.to_lowercase().as_str()) => {
+            Some(format!("method {method} isn't an HTTP method; emitted as GET"))
+        }
+        Some(_) => None,
+    }
+}
+
+/// Greedy path variables (`{+path}`) are emitted as plain variables.
+fn path_template(path: &str) -> String {
+    path.replace("{+", "{")
+}
+
+fn param_name(path_param: &str) -> String {
+    path_param.trim_start_matches('+').to_string()
+}
+
+/// Key for matching a path variable to an argument: case, `_`, and `-` are ignored.
+fn match_key(name: &str) -> String {
+    name.trim_start_matches('+')
+        .chars()
+        .filter(|c| *c != '_' && *c != '-')
+        .flat_map(char::to_lowercase)
+        .collect()
+}
+
+/// The endpoint's console help, unless it is empty or just repeats the
+/// description. Text longer than `max` characters is cut off with an ellipsis.
+fn console_help(endpoint: &Endpoint, max: usize) -> Option<String> {
+    let help = endpoint.help.trim();
+    if help.is_empty() || help == endpoint.info.description.trim() {
+        return None;
+    }
+    if help.chars().count() <= max {
+        return Some(help.to_string());
+    }
+    let cut = help.chars().take(max.saturating_sub(1)).collect::<String>();
+    Some(format!("{}…", cut.trim_end()))
+}
+
+/// Resolves help [`DataType`]s into schemas, falling back to generic objects
+/// for types that do not exist in the help data.
+///
+/// Fallbacks are collected as they happen and taken after each target with
+/// [`TypeResolver::take_fallbacks`].
+struct TypeResolver<'a> {
+    types: FxHashMap<&'a str, &'a Type>,
+    /// Obscured private types. Referencing one is deliberate, not a fallback.
+    obscured: FxHashSet<&'a str>,
+    fallbacks: RefCell<Vec<Fallback>>,
+    privacy: PrivacyPolicy,
+    unknown_fields: UnknownFields,
+    max_console_help: usize,
+}
+
+impl<'a> TypeResolver<'a> {
+    fn new(help: &'a ExtendedHelp, options: &SchemaOptions) -> Self {
+        Self {
+            types: help.types
+                .iter()
+                // Obscured types don't exist as far as references are concerned.
+                .filter(|ty| !(ty.is_private() && options.privacy == PrivacyPolicy::Obscure))
+                .map(|ty| (ty.info.name.as_str(), ty))
+                .collect(),
+            obscured: help.types
+                .iter()
+                .filter(|ty| ty.is_private() && options.privacy == PrivacyPolicy::Obscure)
+                .map(|ty| ty.info.name.as_str())
+                .collect(),
+            fallbacks: RefCell::default(),
+            privacy: options.privacy,
+            unknown_fields: options.unknown_fields,
+            max_console_help: options.max_console_help,
+        }
+    }
+
+    fn fall_back(&self, site: FallbackSite, detail: String) {
+        self.fallbacks.borrow_mut().push(Fallback { site, detail });
+    }
+
+    /// Fallbacks since the last call, oldest first.
+    fn take_fallbacks(&self) -> Vec<Fallback> {
+        self.fallbacks.take()
+    }
+
+    /// An object component before its properties are filled in.
+    fn component_object(&self) -> SchemaObject {
+        match self.unknown_fields {
+            UnknownFields::Closed => SchemaObject::object_of(false),
+            UnknownFields::Unspecified => SchemaObject::of_type("object"),
+        }
+    }
+
+    fn component(&self, ty: &Type) -> SchemaObject {
+        let mut schema = if ty.is_private() && self.privacy == PrivacyPolicy::Redact {
+            let mut schema = self.component_object();
+            schema.properties = Some(
+                ty.fields
+                    .iter()
+                    .map(|field| (field.info.name.clone(), SchemaObject::object_of(true)))
+                    .collect()
+            );
+            schema.required = required_of(ty);
+            schema.additional_fields.insert(X_REDACTED.to_string(), Value::Bool(true));
+            schema
+        } else if let Some(enumeration) = EnumSchema::from_type(ty) {
+            enumeration.to_schema()
+        } else {
+            let mut schema = self.component_object();
+            let mut properties = BTreeMap::new();
+            for field in ty.fields.iter() {
+                let mut property = self.schema_of(&field.ty);
+                if property.reference.is_none() {
+                    property.description = non_empty(&field.info.description);
+                }
+                properties.insert(field.info.name.clone(), property);
+            }
+            schema.properties = Some(properties);
+            schema.required = required_of(ty);
+            schema
+        };
+        schema.description = non_empty(&ty.info.description);
+        schema
+    }
+
+    /// Schema for a field, argument, or return [`DataType`].
+    fn schema_of(&self, ty: &DataType) -> SchemaObject {
+        match ty.ty.as_str() {
+            VECTOR_TYPE => SchemaObject::array_of(self.schema_of_name(&ty.element_type)),
+            MAP_TYPE => SchemaObject::map_of(self.schema_of_name(&ty.element_type)),
+            name => self.schema_of_name(name),
+        }
+    }
+
+    fn schema_of_name(&self, name: &str) -> SchemaObject {
+        if let Some(schema) = primitive(name) {
+            return schema;
+        }
+        if self.types.contains_key(name) {
+            SchemaObject::reference(name)
+        } else {
+            if !self.obscured.contains(name) {
+                self.fall_back(FallbackSite::Schema, format!("type {name} isn't defined; emitted as a generic object"));
+            }
+            SchemaObject::object_of(true)
+        }
+    }
+
+    fn event(&self, event: &Event) -> EventSchema {
+        EventSchema {
+            description: non_empty(&event.info.description),
+            tags: spec_tags(&event.tags),
+            schema: self.schema_of(&event.ty),
+        }
+    }
+
+    fn operation(&self, endpoint: &Endpoint, method: &str, report: &mut SchemaReport) -> Operation {
+        let mut parameters = Vec::new();
+        let mut request_body = None;
+
+        let location = |arg: &Argument| endpoint.arg_locations.get(&arg.info.name).copied();
+
+        // Path arguments come first, in the same order as the template variables,
+        // and are matched to arguments by name. A variable no argument is named
+        // after takes the next argument patched into the path, if there is one.
+        // A repeated variable is one parameter.
+        let mut matched = vec![false; endpoint.arguments.len()];
+        let mut seen = FxHashSet::default();
+        for path_param in endpoint.path_params.iter() {
+            let name = param_name(path_param);
+            let key = match_key(&name);
+            if !seen.insert(key.clone()) {
+                continue;
+            }
+            let unmatched = |(i, arg): &(usize, &Argument)| {
+                !matched[*i] && location(arg).is_none_or(|l| l == ArgLocation::Path)
+            };
+            let arg = endpoint.arguments
+                .iter()
+                .enumerate()
+                .find(|pair| unmatched(pair) && match_key(&pair.1.info.name) == key)
+                .or_else(|| {
+                    endpoint.arguments
+                        .iter()
+                        .enumerate()
+                        .find(|pair| unmatched(pair) && location(pair.1) == Some(ArgLocation::Path))
+                })
+                .map(|(i, arg)| {
+                    matched[i] = true;
+                    arg
+                });
+            if arg.is_none() {
+                self.fall_back(FallbackSite::PathParam, format!("path parameter {name} has no matching argument; typed as string"));
+            }
+            parameters.push(Parameter {
+                name,
+                location: ParameterLocation::Path,
+                required: true,
+                description: arg.and_then(|a| non_empty(&a.info.description)),
+                schema: arg.map_or_else(
+                    || SchemaObject::of_type("string"),
+                    |a| self.path_schema(&a.ty)
+                ),
+            });
+        }
+
+        let rest = endpoint.arguments
+            .iter()
+            .zip(matched)
+            .filter(|(_, matched)| !matched)
+            .map(|(arg, _)| arg)
+            .collect::<Vec<&Argument>>();
+        // A patched location always wins. Otherwise a lone argument is the body, and
+        // so is the first one when a patch sets the content type. With several
+        // arguments, the first object or array is the body and primitives stay in
+        // the query.
+        let body = rest
+            .iter()
+            .position(|arg| location(arg) == Some(ArgLocation::Body))
+            .or_else(|| {
+                if !BODY_METHODS.contains(&method) {
+                    return None;
+                }
+                let mut unplaced = (0..rest.len()).filter(|i| location(rest[*i]).is_none());
+                if rest.len() == 1 || endpoint.request_content_type.is_some() {
+                    unplaced.next()
+                } else {
+                    unplaced.find(|i| self.is_structured(&rest[*i].ty))
+                }
+            });
+        for (i, arg) in rest.iter().enumerate() {
+            if body == Some(i) {
+                let content_type = request_content_type(endpoint, arg);
+                let schema = if content_type == TEXT_PLAIN {
+                    SchemaObject::of_type("string")
+                } else {
+                    self.schema_of(&arg.ty)
+                };
+                request_body = Some(RequestBody::with_content(content_type, schema, !arg.is_optional));
+                continue;
+            }
+            match location(arg) {
+                Some(ArgLocation::Header) => {
+                    parameters.push(Parameter {
+                        name: arg.info.name.clone(),
+                        location: ParameterLocation::Header,
+                        required: !arg.is_optional,
+                        description: non_empty(&arg.info.description),
+                        schema: self.schema_of(&arg.ty),
+                    });
+                    continue;
+                }
+                Some(ArgLocation::Path) => {
+                    report.warn(&endpoint.info.name, format!("argument {} has no path variable left; sent in the query", arg.info.name));
+                }
+                Some(ArgLocation::Body) => {
+                    report.warn(&endpoint.info.name, format!("argument {} is a second body; sent in the query", arg.info.name));
+                }
+                Some(ArgLocation::Query) | None => {}
+            }
+            parameters.extend(self.query_params(arg));
+        }
+
+        let responses = if endpoint.return_ty.ty.is_empty() {
+            BTreeMap::from([("204".to_string(), Response::empty("No content"))])
+        } else {
+            BTreeMap::from([
+                (
+                    "200".to_string(),
+                    Response::json("Successful response", self.schema_of(&endpoint.return_ty)),
+                ),
+            ])
+        };
+
+        let mut additional_fields = BTreeMap::new();
+        let greedy = endpoint.path_params
+            .iter()
+            .filter(|param| param.starts_with('+'))
+            .map(|param| Value::from(param_name(param)))
+            .collect::<Vec<Value>>();
+        if !greedy.is_empty() {
+            additional_fields.insert(X_GREEDY_PARAMS.to_string(), Value::Array(greedy));
+        }
+        if let Some(help) = console_help(endpoint, self.max_console_help) {
+            additional_fields.insert(CONSOLE_HELP_EXTENSION.to_string(), Value::String(help));
+        }
+
+        Operation {
+            operation_id: endpoint.info.name.clone(),
+            summary: None,
+            description: non_empty(&endpoint.info.description),
+            tags: operation_tags(endpoint),
+            parameters,
+            request_body,
+            responses,
+            servers: Vec::new(),
+            additional_fields,
+        }
+    }
+
+    /// Schema for a path variable. Integers keep their format and enums are
+    /// inlined with their variants, since a path segment is a plain value.
+    /// Objects and arrays can't be one, so they are sent as strings.
+    fn path_schema(&self, ty: &DataType) -> SchemaObject {
+        if let Some(enumeration) = self.types.get(ty.ty.as_str()).and_then(|ty| EnumSchema::from_type(ty)) {
+            return enumeration.to_schema();
+        }
+        if self.is_structured(ty) {
+            return SchemaObject::of_type("string");
+        }
+        self.schema_of(ty)
+    }
+
+    /// Whether an argument is an object or array, which belongs in a body rather
+    /// than the query. Enums are sent as plain values.
+    fn is_structured(&self, ty: &DataType) -> bool {
+        match ty.ty.as_str() {
+            VECTOR_TYPE | MAP_TYPE | "" | "object" => true,
+            name if primitive(name).is_some() => false,
+            name => self.types.get(name).is_none_or(|ty| ty.values.is_empty()),
+        }
+    }
+
+    /// Query parameters for an argument. Arguments typed as an object component
+    /// are exploded into one parameter per property.
+    fn query_params(&self, arg: &Argument) -> Vec<Parameter> {
+        if let Some(ty) = self.types.get(arg.ty.ty.as_str()) &&
+            ty.values.is_empty() &&
+            !ty.fields.is_empty()
+        {
+            // Same required set as the component the argument refers to.
+            let required = required_of(ty).unwrap_or_default();
+            return ty.fields
+                .iter()
+                .map(|field| Parameter {
+                    name: field.info.name.clone(),
+                    location: ParameterLocation::Query,
+                    required: !arg.is_optional && required.contains(&field.info.name),
+                    description: non_empty(&field.info.description),
+                    schema: self.schema_of(&field.ty),
+                })
+                .collect();
+        }
+        vec![Parameter {
+            name: arg.info.name.clone(),
+            location: ParameterLocation::Query,
+            required: !arg.is_optional,
+            description: non_empty(&arg.info.description),
+            schema: self.schema_of(&arg.ty),
+        }]
+    }
+}
+
+/// Sorted names of an object type's non-optional fields, or `None` if there are none.
+fn required_of(ty: &Type) -> Option<Vec<String>> {
+    let mut required = ty.fields
+        .iter()
+        .filter(|field| !field.is_optional)
+        .map(|field| field.info.name.clone())
+        .collect::<Vec<String>>();
+    required.sort();
+    if required.is_empty() { None } else { Some(required) }
+}
+
+/// Schema for a primitive help type name, or `None` if the name is not one of
+/// [`PRIMITIVE_TYPES`].
+pub(crate) fn primitive(name: &str) -> Option<SchemaObject> {
+    if !PRIMITIVE_TYPES.contains(&name) {
+        return None;
+    }
+    let schema = match name {
+        "" | "object" => SchemaObject::object_of(true),
+        "bool" => SchemaObject::of_type("boolean"),
+        "string" => SchemaObject::of_type("string"),
+        "int8" | "int16" | "int32" | "int64" => {
+            SchemaObject { format: Some(name.to_string()), ..SchemaObject::of_type("integer") }
+        }
+        "uint8" | "uint16" | "uint32" | "uint64" => {
+            SchemaObject {
+                format: Some(name.to_string()),
+                minimum: Some(0.into()),
+                ..SchemaObject::of_type("integer")
+            }
+        }
+        "float" | "double" => {
+            SchemaObject { format: Some(name.to_string()), ..SchemaObject::of_type("number") }
+        }
+        _ => {
+            return None;
+        }
+    };
+    Some(schema)
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+    use crate::tests::miniature;
+
+    fn spec() -> OpenApiSpec {
+        OpenApiSpec::from_help(&miniature(), "1.0.0").unwrap()
+    }
+
+    #[test]
+    fn test_components() {
+        let spec = spec();
+        let schemas = &spec.components["schemas"];
+
+        let summoner = &schemas["LolSummonerSummoner"];
+        assert_eq!(summoner["type"], "object");
+        assert_eq!(
+            summoner["properties"]["accountId"],
+            json!({ "type": "integer", "format": "uint64", "minimum": 0 })
+        );
+        assert_eq!(
+            summoner["properties"]["rerollPoints"],
+            json!({ "$ref": "#/components/schemas/LolSummonerSummonerRerollPoints" })
+        );
+        assert_eq!(summoner["required"], json!(["accountId", "displayName", "privacy", "puuid", "rerollPoints", "summonerLevel"]));
+
+        let lobby = &schemas["LolLobbyLobbyDto"];
+        assert_eq!(
+            lobby["properties"]["members"],
+            json!({ "type": "array", "items": { "$ref": "#/components/schemas/LolLobbyLobbyParticipantDto" } })
+        );
+        assert_eq!(
+            lobby["properties"]["customData"],
+            json!({ "type": "object", "additionalProperties": { "type": "string" } })
+        );
+
+        let privacy = &schemas["LolSummonerProfilePrivacySetting"];
+        assert_eq!(privacy["type"], "string");
+        assert_eq!(privacy["enum"], json!(["PRIVATE", "PUBLIC"]));
+    }
+
+    #[test]
+    fn test_unknown_fields() {
+        let closed = spec();
+        let schemas = &closed.components["schemas"];
+        assert_eq!(schemas["LolSummonerSummoner"]["additionalProperties"], false);
+
+        let options = SchemaOptions { unknown_fields: UnknownFields::Unspecified, ..Default::default() };
+        let open = OpenApiSpec::from_help_with(&miniature(), "1.0.0", &options, &mut SchemaReport::default()).unwrap();
+        let schemas = &open.components["schemas"];
+        let summoner = &schemas["LolSummonerSummoner"];
+        assert_eq!(summoner["type"], "object");
+        assert!(summoner.get("additionalProperties").is_none());
+        // Maps still say what their values are.
+        assert_eq!(
+            schemas["LolLobbyLobbyDto"]["properties"]["customData"],
+            json!({ "type": "object", "additionalProperties": { "type": "string" } })
+        );
+        // Everything else is the same.
+        let mut reclosed = schemas.clone();
+        for schema in reclosed.as_object_mut().unwrap().values_mut() {
+            if schema["type"] == "object" {
+                schema["additionalProperties"] = Value::Bool(false);
+            }
+        }
+        assert_eq!(&reclosed, &closed.components["schemas"]);
+    }
+
+    #[test]
+    fn test_additional_properties_states() {
+        let unspecified = SchemaObject::of_type("object");
+        let closed = SchemaObject::object_of(false);
+        let values = SchemaObject::map_of(SchemaObject::of_type("string"));
+        let states = [
+            (&unspecified, json!({ "type": "object" })),
+            (&closed, json!({ "type": "object", "additionalProperties": false })),
+            (&values, json!({ "type": "object", "additionalProperties": { "type": "string" } })),
+        ];
+        for (schema, expected) in states {
+            let json = serde_json::to_value(schema).unwrap();
+            assert_eq!(json, expected);
+            assert_eq!(&serde_json::from_value::<SchemaObject>(json).unwrap(), schema);
+        }
+        let open: SchemaObject = serde_json::from_value(json!({ "additionalProperties": true })).unwrap();
+        assert_eq!(open.additional_properties, Some(crate::openapi::AdditionalProperties::Bool(true)));
+    }
+
+    #[test]
+    fn test_paths() {
+        let spec = spec();
+
+        let get = &spec.paths["/lol-summoner/v1/summoners/{id}"]["get"];
+        assert_eq!(get["operationId"], "GetLolSummonerV1SummonersById");
+        assert_eq!(get["tags"], json!(["plugins", "lol-summoner"]));
+        assert_eq!(
+            get["parameters"],
+            json!([{
+                "name": "id",
+                "in": "path",
+                "required": true,
+                "schema": { "type": "integer", "format": "uint64", "minimum": 0 }
+            }])
+        );
+        assert_eq!(
+            get["responses"]["200"]["content"]["application/json"]["schema"],
+            json!({ "$ref": "#/components/schemas/LolSummonerSummoner" })
+        );
+
+        let post = &spec.paths["/lol-lobby/v2/lobby"]["post"];
+        assert!(post.get("parameters").is_none());
+        assert_eq!(
+            post["requestBody"]["content"]["application/json"]["schema"],
+            json!({ "$ref": "#/components/schemas/LolLobbyLobbyChangeGameDto" })
+        );
+
+        let delete = &spec.paths["/lol-lobby/v2/lobby"]["delete"];
+        assert_eq!(delete["responses"], json!({ "204": { "description": "No content" } }));
+
+        // Endpoints without a path are not emitted.
+        assert!(!spec.paths.values().any(|p| p.get("get").is_some_and(|o| o["operationId"] == "LolSummonerRefresh")));
+    }
+
+    #[test]
+    fn test_events() {
+        use crate::{ help::{ Event, Info }, index::direct_refs, openapi::{ EventSchema, X_EVENTS } };
+
+        let mut help = miniature();
+        help.events.push(Event {
+            info: Info { name: "OnJsonApiEvent_lol-lobby_v2_lobby".to_string(), description: "The lobby changed.".to_string() },
+            namespace: "Plugins".to_string(),
+            tags: vec!["Plugin lol-lobby".to_string()],
+            ty: DataType { element_type: "LolLobbyLobbyParticipantDto".to_string(), ty: VECTOR_TYPE.to_string() },
+        });
+        let spec = OpenApiSpec::from_help(&help, "1.0.0").unwrap();
+
+        // No payload type, so any object.
+        assert_eq!(spec.events["OnJsonApiEvent"], json!({ "tags": ["builtin"], "schema": { "type": "object", "additionalProperties": true } }));
+        assert_eq!(
+            spec.events["OnJsonApiEvent_lol-summoner_v1_current-summoner"],
+            json!({ "tags": ["plugins", "lol-summoner"], "schema": { "$ref": "#/components/schemas/LolSummonerSummoner" } })
+        );
+        let lobby: EventSchema = serde_json::from_value(spec.events["OnJsonApiEvent_lol-lobby_v2_lobby"].clone()).unwrap();
+        assert_eq!(lobby.description.as_deref(), Some("The lobby changed."));
+        assert_eq!(lobby.schema, SchemaObject::array_of(SchemaObject::reference("LolLobbyLobbyParticipantDto")));
+        let schemas = spec.components["schemas"].as_object().unwrap();
+        assert!(direct_refs(&Value::Object(spec.events.clone())).iter().all(|name| schemas.contains_key(name)));
+
+        let json = serde_json::to_value(&spec).unwrap();
+        assert_eq!(json[X_EVENTS], Value::Object(spec.events.clone()));
+        let read: OpenApiSpec = serde_json::from_value(json.clone()).unwrap();
+        assert_eq!(read.events, spec.events);
+        assert_eq!(serde_json::to_value(&read).unwrap(), json);
+
+        help.events.clear();
+        let without = serde_json::to_value(OpenApiSpec::from_help(&help, "1.0.0").unwrap()).unwrap();
+        assert!(without.get(X_EVENTS).is_none());
+    }
+
+    #[test]
+    fn test_text_plain_body() {
+        let mut help = miniature();
+        let spec = OpenApiSpec::from_help(&help, "1.0.0").unwrap();
+
+        // Patched: the first argument is the body, the rest stay in the query.
+        let post = &spec.paths["/Help"]["post"];
+        assert_eq!(
+            post["requestBody"],
+            json!({ "content": { "text/plain": { "schema": { "type": "string" } } }, "required": false })
+        );
+        assert_eq!(post["parameters"][0]["name"], "format");
+        let post = &spec.paths["/lol-lobby/v2/lobby"]["post"];
+        assert!(post["requestBody"]["content"].get("application/json").is_some());
+
+        // Unpatched, a lone string argument is still sent as text.
+        let help_endpoint = help.endpoints.iter_mut().find(|e| e.info.name == "Help").unwrap();
+        help_endpoint.request_content_type = None;
+        help_endpoint.arguments.truncate(1);
+        let spec = OpenApiSpec::from_help(&help, "1.0.0").unwrap();
+        assert!(spec.paths["/Help"]["post"]["requestBody"]["content"].get("text/plain").is_some());
+    }
+
+    fn private_help() -> ExtendedHelp {
+        let no_ty = json!({ "elementType": "", "type": "" });
+        let help =
+            json!({
+            "types": [
+                {
+                    "values": [],
+                    "fields": [
+                        { "name": "payload", "description": "", "offset": 0, "optional": false, "type": { "elementType": "", "type": "PrivateApiTypeNotSupported" } },
+                        { "name": "sku", "description": "", "offset": 1, "optional": false, "type": { "elementType": "", "type": "string" } }
+                    ],
+                    "name": "LolStorePurchaseRequest",
+                    "description": "",
+                    "nameSpace": "Plugins",
+                    "size": 0,
+                    "tags": ["Plugin lol-store"]
+                }
+            ],
+            "endpoints": [
+                {
+                    "name": "PostLolStoreV1Purchase",
+                    "description": "",
+                    "nameSpace": "Plugins",
+                    "help": "",
+                    "arguments": [
+                        { "name": "request", "description": "", "optional": false, "type": { "elementType": "", "type": "LolStorePurchaseRequest" } }
+                    ],
+                    "tags": ["Plugin lol-store"],
+                    "method": "POST",
+                    "path": "/lol-store/v1/purchase",
+                    "pathParams": [],
+                    "returns": no_ty
+                }
+            ],
+            "events": []
+        });
+        serde_json::from_value(help).unwrap()
+    }
+
+    fn body_schema(spec: &OpenApiSpec) -> &Value {
+        &spec.paths["/lol-store/v1/purchase"]["post"]["requestBody"]["content"]["application/json"]["schema"]
+    }
+
+    fn convert_with(privacy: PrivacyPolicy) -> (OpenApiSpec, SchemaReport) {
+        let mut report = SchemaReport::default();
+        let options = SchemaOptions { privacy, ..Default::default() };
+        let spec = OpenApiSpec::from_help_with(&private_help(), "1.0.0", &options, &mut report).unwrap();
+        (spec, report)
+    }
+
+    #[test]
+    fn test_privacy_obscure() {
+        let (spec, report) = convert_with(PrivacyPolicy::Obscure);
+        assert!(spec.components["schemas"].get("LolStorePurchaseRequest").is_none());
+        assert_eq!(body_schema(&spec), &json!({ "type": "object", "additionalProperties": true }));
+
+        let entries = report.of_kind(ReportKind::Private).collect::<Vec<_>>();
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].target, "LolStorePurchaseRequest");
+        assert_eq!(entries[0].detail, "obscured; referenced by PostLolStoreV1Purchase");
+    }
+
+    #[test]
+    fn test_privacy_include() {
+        let (spec, report) = convert_with(PrivacyPolicy::Include);
+        let schema = &spec.components["schemas"]["LolStorePurchaseRequest"];
+        assert_eq!(schema["properties"]["sku"], json!({ "type": "string" }));
+        assert!(schema.get("x-redacted").is_none());
+        assert_eq!(body_schema(&spec), &json!({ "$ref": "#/components/schemas/LolStorePurchaseRequest" }));
+        assert!(report.entries[0].detail.starts_with("included;"));
+    }
+
+    #[test]
+    fn test_privacy_redact() {
+        let (spec, report) = convert_with(PrivacyPolicy::Redact);
+        let schema = &spec.components["schemas"]["LolStorePurchaseRequest"];
+        assert_eq!(schema["x-redacted"], true);
+        assert_eq!(schema["required"], json!(["payload", "sku"]));
+        assert_eq!(
+            schema["properties"]["sku"],
+            json!({ "type": "object", "additionalProperties": true })
+        );
+        assert_eq!(body_schema(&spec), &json!({ "$ref": "#/components/schemas/LolStorePurchaseRequest" }));
+        assert!(report.entries[0].detail.starts_with("redacted;"));
+    }
+
+    #[test]
+    fn test_normalize_path() {
+        assert_eq!(normalize_path("/lol-foo/v1//bar/").unwrap(), "/lol-foo/v1/bar");
+        assert_eq!(normalize_path("//lol-foo///bar").unwrap(), "/lol-foo/bar");
+        assert_eq!(normalize_path("/").unwrap(), "/");
+        assert_eq!(normalize_path("/lol-foo/v1/my bar").unwrap(), "/lol-foo/v1/my%20bar");
+        assert_eq!(normalize_path("/lol-foo/v1/{id}/").unwrap(), "/lol-foo/v1/{id}");
+        assert_eq!(normalize_path("/{+path}").unwrap(), "/{+path}");
+    }
+
+    #[test]
+    fn test_normalize_path_malformed_braces() {
+        assert!(normalize_path("/lol-foo/{a{b}}").unwrap_err().starts_with("nested"));
+        assert!(normalize_path("/lol-foo/{id").unwrap_err().starts_with("unclosed"));
+        assert!(normalize_path("/lol-foo/id}").unwrap_err().starts_with("unopened"));
+        assert!(normalize_path("/lol-foo/{}").unwrap_err().starts_with("empty"));
+    }
+
+    #[test]
+    fn test_paths_normalized_and_merged() {
+        let mut help = miniature();
+        let paths = [
+            ("GetLolSummonerV1CurrentSummoner", "/lol-summoner/v1//current-summoner/"),
+            ("DeleteLolLobbyV2Lobby", "/lol-summoner/v1/current-summoner"),
+            ("GetLolSummonerV1Summoners", "/lol-summoner/v1/current-summoner//"),
+            ("PostLolLobbyV2Lobby", "/lol-lobby/v2/{lobby"),
+        ];
+        for (name, path) in paths {
+            let endpoint = help.endpoints
+                .iter_mut()
+                .find(|e| e.info.name == name)
+                .unwrap();
+            endpoint.path = Some(path.to_string());
+        }
+
+        let mut report = SchemaReport::default();
+        let paths = paths_from(&help, &SchemaOptions::default(), &mut report).unwrap();
+        let item = paths["/lol-summoner/v1/current-summoner"].as_object().unwrap();
+        assert_eq!(item.keys().collect::<Vec<_>>(), ["delete", "get"]);
+        assert_eq!(item["get"]["operationId"], "GetLolSummonerV1CurrentSummoner");
+        assert!(!paths.keys().any(|path| path.contains("//") || path.contains("{lobby")));
+
+        let merged = report.of_kind(ReportKind::Merged).collect::<Vec<_>>();
+        assert_eq!(merged.len(), 1);
+        assert_eq!(merged[0].target, "GetLolSummonerV1Summoners");
+        let skipped = report.of_kind(ReportKind::Skipped).collect::<Vec<_>>();
+        assert_eq!(skipped.len(), 1);
+        assert_eq!(skipped[0].target, "PostLolLobbyV2Lobby");
+    }
+
+    #[test]
+    fn test_absolute_console_urls() {
+        let mut help = miniature();
+        let urls = [
+            ("GetLolSummonerV1CurrentSummoner", "https://localhost/lol-summoner/v1/current-summoner"),
+            ("DeleteLolLobbyV2Lobby", "127.0.0.1:2999/liveclientdata/allgamedata"),
+            ("PostLolLobbyV2Lobby", "https://example.com/lol-lobby/v2/lobby"),
+        ];
+        for (name, url) in urls {
+            let endpoint = help.endpoints
+                .iter_mut()
+                .find(|e| e.info.name == name)
+                .unwrap();
+            endpoint.path = Some(url.to_string());
+        }
+
+        let mut report = SchemaReport::default();
+        let paths = paths_from(&help, &SchemaOptions::default(), &mut report).unwrap();
+        assert!(!paths.keys().any(|path| path.contains("://") || path.contains("example.com")));
+
+        let get = &paths["/lol-summoner/v1/current-summoner"]["get"];
+        assert_eq!(get[X_EXTERNAL_HOST], "localhost");
+        assert!(get.get("servers").is_none());
+
+        let game = &paths["/liveclientdata/allgamedata"]["delete"];
+        assert_eq!(game[X_EXTERNAL_HOST], "127.0.0.1:2999");
+        assert_eq!(game["servers"], json!([{ "url": "https://127.0.0.1:2999", "description": "Live Client Data" }]));
+
+        assert!(paths.get("/lol-lobby/v2/lobby").is_none_or(|item| item.get("post").is_none()));
+        let warnings = report.of_kind(ReportKind::Warning).collect::<Vec<_>>();
+        assert_eq!(warnings.len(), 1);
+        assert_eq!(warnings[0].target, "PostLolLobbyV2Lobby");
+        assert!(warnings[0].detail.contains("example.com"));
+    }
+
+    #[test]
+    fn test_query_params_required_from_component() {
+        let mut help = miniature();
+        let endpoint = help.endpoints
+            .iter_mut()
+            .find(|e| e.info.name == "PostLolLobbyV2Lobby")
+            .unwrap();
+        endpoint.method = Some("GET".to_string());
+
+        let spec = OpenApiSpec::from_help(&help, "1.0.0").unwrap();
+        let component = &spec.components["schemas"]["LolLobbyLobbyChangeGameDto"];
+        let parameters = spec.paths["/lol-lobby/v2/lobby"]["get"]["parameters"]
+            .as_array()
+            .unwrap();
+        assert_eq!(parameters.len(), 2);
+        for parameter in parameters {
+            let required = component["required"]
+                .as_array()
+                .unwrap()
+                .contains(&parameter["name"]);
+            assert_eq!(parameter["required"], required, "{}", parameter["name"]);
+        }
+        assert_eq!(component["required"], json!(["queueId"]));
+    }
+
+    #[test]
+    fn test_nested_objects_keep_required() {
+        let spec = spec();
+        let schemas = &spec.components["schemas"];
+
+        // Referenced directly, and as the items of an array.
+        let reroll_points = &schemas["LolSummonerSummonerRerollPoints"];
+        assert_eq!(reroll_points["required"], json!(["currentPoints", "numberOfRolls"]));
+        let participant = &schemas["LolLobbyLobbyParticipantDto"];
+        assert_eq!(participant["required"], json!(["isLeader", "summonerId"]));
+    }
+
+    fn argument(name: &str, ty: &str, element_type: &str) -> Argument {
+        serde_json::from_value(
+            json!({ "name": name, "description": "", "optional": false, "type": { "elementType": element_type, "type": ty } })
+        ).unwrap()
+    }
+
+    /// PostLolLobbyV2Lobby with an id, a flag, and a string list around its DTO.
+    fn mixed_args_help() -> ExtendedHelp {
+        let mut help = miniature();
+        let endpoint = help.endpoints
+            .iter_mut()
+            .find(|e| e.info.name == "PostLolLobbyV2Lobby")
+            .unwrap();
+        let dto = endpoint.arguments.remove(0);
+        endpoint.arguments = vec![
+            argument("gameId", "uint64", ""),
+            dto,
+            argument("force", "bool", ""),
+            argument("flags", "vector", "string")
+        ];
+        help
+    }
+
+    fn locations_of(operation: &Value) -> Vec<(String, String)> {
+        operation["parameters"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|p| (p["name"].as_str().unwrap().to_string(), p["in"].as_str().unwrap().to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn test_query_and_body_heuristic() {
+        let spec = OpenApiSpec::from_help(&mixed_args_help(), "1.0.0").unwrap();
+        let post = &spec.paths["/lol-lobby/v2/lobby"]["post"];
+
+        // The DTO comes first of the structured arguments, so it is the body.
+        assert_eq!(
+            post["requestBody"],
+            json!({
+                "content": { "application/json": { "schema": { "$ref": "#/components/schemas/LolLobbyLobbyChangeGameDto" } } },
+                "required": true
+            })
+        );
+        assert_eq!(
+            locations_of(post),
+            [
+                ("gameId".to_string(), "query".to_string()),
+                ("force".to_string(), "query".to_string()),
+                ("flags".to_string(), "query".to_string()),
+            ]
+        );
+        assert_eq!(post["parameters"][2]["schema"], json!({ "type": "array", "items": { "type": "string" } }));
+    }
+
+    #[test]
+    fn test_query_and_body_patched() {
+        use crate::patch::Patch;
+
+        let mut help = mixed_args_help();
+        let endpoint = help.endpoints
+            .iter_mut()
+            .find(|e| e.info.name == "PostLolLobbyV2Lobby")
+            .unwrap();
+        let mut json = serde_json::to_value(&*endpoint).unwrap();
+        json.patch_mut(
+            "argLocations",
+            json!({ "flags": "body", "force": "header", "lobbyChange": "query" })
+        ).unwrap();
+        *endpoint = serde_json::from_value(json).unwrap();
+
+        let mut report = SchemaReport::default();
+        let spec = OpenApiSpec::from_help_with(&help, "1.0.0", &SchemaOptions::default(), &mut report).unwrap();
+        let post = &spec.paths["/lol-lobby/v2/lobby"]["post"];
+
+        assert_eq!(
+            post["requestBody"]["content"]["application/json"]["schema"],
+            json!({ "type": "array", "items": { "type": "string" } })
+        );
+        // The DTO in the query is exploded into its fields.
+        assert_eq!(
+            locations_of(post),
+            [
+                ("gameId".to_string(), "query".to_string()),
+                ("queueId".to_string(), "query".to_string()),
+                ("isCustom".to_string(), "query".to_string()),
+                ("force".to_string(), "header".to_string()),
+            ]
+        );
+        assert!(report.of_kind(ReportKind::Warning).next().is_none());
+    }
+
+    /// Endpoint `name` with its path and arguments replaced.
+    fn with_path_args(help: &mut ExtendedHelp, name: &str, path: &str, args: &[&str]) {
+        let endpoint = help.endpoints
+            .iter_mut()
+            .find(|e| e.info.name == name)
+            .unwrap();
+        endpoint.path = Some(path.to_string());
+        endpoint.path_params = crate::help::ConsoleEndpointInner {
+            http_method: None,
+            url: Some(path.to_string()),
+        }.path_params();
+        endpoint.arguments = args
+            .iter()
+            .map(|arg| {
+                serde_json::from_value(
+                    json!({ "name": arg, "description": "", "optional": false, "type": { "elementType": "", "type": "uint64" } })
+                ).unwrap()
+            })
+            .collect();
+    }
+
+    fn parameters_of(paths: &Map<String, Value>, path: &str) -> Vec<(String, String, String)> {
+        paths[path]["get"]["parameters"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|p| {
+                (
+                    p["name"].as_str().unwrap().to_string(),
+                    p["in"].as_str().unwrap().to_string(),
+                    p["schema"]["type"].as_str().unwrap().to_string(),
+                )
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_path_args_mismatched_name() {
+        let mut help = miniature();
+        with_path_args(&mut help, "GetLolSummonerV1SummonersById", "/lol-summoner/v1/summoners/{id}", &["summonerId"]);
+
+        let mut report = SchemaReport::default();
+        let paths = paths_from(&help, &SchemaOptions::default(), &mut report).unwrap();
+        assert_eq!(
+            parameters_of(&paths, "/lol-summoner/v1/summoners/{id}"),
+            [
+                ("id".to_string(), "path".to_string(), "string".to_string()),
+                ("summonerId".to_string(), "query".to_string(), "integer".to_string()),
+            ]
+        );
+        let fallbacks = report.of_kind(ReportKind::Fallback).collect::<Vec<_>>();
+        assert_eq!(fallbacks.len(), 1);
+        assert_eq!(fallbacks[0].detail, "path parameter id has no matching argument; typed as string");
+    }
+
+    #[test]
+    fn test_path_args_by_name() {
+        let mut help = miniature();
+        with_path_args(
+            &mut help,
+            "GetLolSummonerV1SummonersById",
+            "/lol-summoner/v1/{+summoner-id}/games/{gameId}/{summoner-id}",
+            &["game_id", "region", "summonerId"]
+        );
+
+        let mut report = SchemaReport::default();
+        let paths = paths_from(&help, &SchemaOptions::default(), &mut report).unwrap();
+        assert_eq!(
+            parameters_of(&paths, "/lol-summoner/v1/{summoner-id}/games/{gameId}/{summoner-id}"),
+            [
+                ("summoner-id".to_string(), "path".to_string(), "integer".to_string()),
+                ("gameId".to_string(), "path".to_string(), "integer".to_string()),
+                ("region".to_string(), "query".to_string(), "integer".to_string()),
+            ]
+        );
+        assert!(report.is_empty());
+    }
+
+    #[test]
+    fn test_path_param_types() {
+        let mut help = miniature();
+        with_path_args(&mut help, "GetLolSummonerV1SummonersById", "/lol-summoner/v1/summoners/{id}", &["id"]);
+        let endpoint = help.endpoints
+            .iter_mut()
+            .find(|e| e.info.name == "GetLolSummonerV1SummonersById")
+            .unwrap();
+        endpoint.arguments[0].ty.ty = "int32".to_string();
+        endpoint.path = Some("/lol-summoner/v1/summoners/{id}/{privacy}".to_string());
+        endpoint.path_params.push("privacy".to_string());
+        endpoint.arguments.push(argument("privacy", "LolSummonerProfilePrivacySetting", ""));
+
+        let mut report = SchemaReport::default();
+        let paths = paths_from(&help, &SchemaOptions::default(), &mut report).unwrap();
+        let parameters = &paths["/lol-summoner/v1/summoners/{id}/{privacy}"]["get"]["parameters"];
+        assert_eq!(parameters[0]["schema"], json!({ "type": "integer", "format": "int32" }));
+        // The enum is inlined rather than referenced.
+        assert_eq!(parameters[1]["in"], "path");
+        assert_eq!(parameters[1]["schema"]["type"], "string");
+        assert_eq!(parameters[1]["schema"]["enum"], json!(["PRIVATE", "PUBLIC"]));
+        assert!(parameters[1]["schema"].get("$ref").is_none());
+        assert!(report.is_empty());
+    }
+
+    fn help_of<'a>(paths: &'a Map<String, Value>, path: &str) -> Option<&'a str> {
+        paths[path]["get"].get(CONSOLE_HELP_EXTENSION).and_then(Value::as_str)
+    }
+
+    #[test]
+    fn test_console_help() {
+        let spec = spec();
+        assert_eq!(
+            help_of(&spec.paths, "/lol-summoner/v1/summoners/{id}"),
+            Some("Usage: GET /lol-summoner/v1/summoners/{id}")
+        );
+        assert_eq!(help_of(&spec.paths, "/lol-summoner/v1/current-summoner"), None);
+    }
+
+    #[test]
+    fn test_console_help_same_as_description() {
+        let mut help = miniature();
+        let endpoint = help.endpoints
+            .iter_mut()
+            .find(|e| e.info.name == "GetLolSummonerV1SummonersById")
+            .unwrap();
+        endpoint.help = format!("  {}\n", endpoint.info.description);
+
+        let paths = paths_from(&help, &SchemaOptions::default(), &mut SchemaReport::default()).unwrap();
+        assert_eq!(help_of(&paths, "/lol-summoner/v1/summoners/{id}"), None);
+    }
+
+    #[test]
+    fn test_console_help_truncated() {
+        let options = SchemaOptions { max_console_help: 12, ..Default::default() };
+        let paths = paths_from(&miniature(), &options, &mut SchemaReport::default()).unwrap();
+        let help = help_of(&paths, "/lol-summoner/v1/summoners/{id}").unwrap();
+        assert_eq!(help, "Usage: GET…");
+        assert!(help.chars().count() <= 12);
+    }
+
+    #[test]
+    fn test_tags() {
+        let spec = spec();
+        let names = spec.tags
+            .iter()
+            .map(|t| t.name.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(names, vec!["builtin", "lol-lobby", "lol-summoner", "plugins"]);
+    }
+}
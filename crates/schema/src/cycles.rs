@@ -0,0 +1,152 @@
+//! Components that refer back to themselves.
+//!
+//! Cycles such as a lobby member pointing at its lobby are legal OpenAPI, but
+//! anything that expands refs naively never finishes on them. The passes that
+//! expand refs ask [`FindCycles`] which components to treat with care.
+
+use std::collections::{ BTreeMap, BTreeSet };
+
+use crate::{ index::ref_graph, openapi::Components, report::{ ReportKind, SchemaReport } };
+
+pub trait FindCycles {
+    /// Each group of component schemas that reach each other through refs,
+    /// and each schema that refers to itself. Names are sorted within a
+    /// group, and groups by their first name.
+    fn find_cycles(&self) -> Vec<Vec<String>>;
+
+    /// Every component in one of the [`FindCycles::find_cycles`] groups.
+    fn cyclic(&self) -> BTreeSet<String> {
+        self.find_cycles().into_iter().flatten().collect()
+    }
+}
+
+impl FindCycles for Components {
+    fn find_cycles(&self) -> Vec<Vec<String>> {
+        let graph = ref_graph(self);
+        let mut tarjan = Tarjan {
+            graph: &graph,
+            index: BTreeMap::new(),
+            low: BTreeMap::new(),
+            stack: Vec::new(),
+            on_stack: BTreeSet::new(),
+            components: Vec::new(),
+        };
+        for name in graph.keys() {
+            if !tarjan.index.contains_key(name) {
+                tarjan.connect(name);
+            }
+        }
+        let mut cycles = tarjan.components
+            .into_iter()
+            .filter(|group| group.len() > 1 || graph[group[0].as_str()].contains(&group[0]))
+            .map(|mut group| {
+                group.sort();
+                group
+            })
+            .collect::<Vec<Vec<String>>>();
+        cycles.sort();
+        cycles
+    }
+}
+
+/// Record each cycle in `components`, under its first component.
+pub fn report_cycles(components: &Components, report: &mut SchemaReport) {
+    for group in components.find_cycles() {
+        let detail = if group.len() == 1 {
+            "refers to itself".to_string()
+        } else {
+            format!("refers to itself through {}", group[1..].join(", "))
+        };
+        report.push(ReportKind::Cycle, &group[0], detail);
+    }
+}
+
+/// Tarjan's strongly connected components, iterating refs in sorted order so
+/// the result doesn't depend on map order.
+struct Tarjan<'a> {
+    graph: &'a BTreeMap<&'a str, BTreeSet<String>>,
+    index: BTreeMap<&'a str, usize>,
+    low: BTreeMap<&'a str, usize>,
+    stack: Vec<&'a str>,
+    on_stack: BTreeSet<&'a str>,
+    components: Vec<Vec<String>>,
+}
+
+impl<'a> Tarjan<'a> {
+    fn connect(&mut self, name: &'a str) {
+        let index = self.index.len();
+        self.index.insert(name, index);
+        self.low.insert(name, index);
+        self.stack.push(name);
+        self.on_stack.insert(name);
+
+        // Refs to components that don't exist aren't edges.
+        let graph = self.graph;
+        let refs = graph[name].iter().filter_map(|next| graph.get_key_value(next.as_str()).map(|(next, _)| *next));
+        for next in refs {
+            if !self.index.contains_key(next) {
+                self.connect(next);
+                self.low.insert(name, self.low[name].min(self.low[next]));
+            } else if self.on_stack.contains(next) {
+                self.low.insert(name, self.low[name].min(self.index[next]));
+            }
+        }
+
+        if self.low[name] == self.index[name] {
+            let mut group = Vec::new();
+            while let Some(member) = self.stack.pop() {
+                self.on_stack.remove(member);
+                group.push(member.to_string());
+                if member == name {
+                    break;
+                }
+            }
+            self.components.push(group);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+    use crate::{ example::example_from_schema, openapi::SchemaObject };
+
+    fn components() -> Components {
+        let schemas = json!({
+            "Lobby": { "type": "object", "properties": { "members": { "type": "array", "items": { "$ref": "#/components/schemas/Member" } } } },
+            "Member": { "type": "object", "properties": { "lobby": { "$ref": "#/components/schemas/Lobby" }, "name": { "type": "string" } } },
+            "Node": { "type": "object", "properties": { "next": { "$ref": "#/components/schemas/Node" } } },
+            "Game": { "type": "object", "properties": { "queue": { "$ref": "#/components/schemas/Queue" } } },
+            "Queue": { "type": "object", "properties": { "map": { "$ref": "#/components/schemas/Map" } } },
+            "Map": { "type": "object", "properties": { "id": { "type": "integer" }, "missing": { "$ref": "#/components/schemas/Missing" } } },
+        });
+        json!({ "schemas": schemas }).as_object().unwrap().clone()
+    }
+
+    #[test]
+    fn test_find_cycles() {
+        let components = components();
+        assert_eq!(components.find_cycles(), [vec!["Lobby", "Member"], vec!["Node"]]);
+        assert_eq!(components.cyclic().into_iter().collect::<Vec<String>>(), ["Lobby", "Member", "Node"]);
+
+        let mut report = SchemaReport::default();
+        report_cycles(&components, &mut report);
+        let entries = report.of_kind(ReportKind::Cycle).map(|entry| (entry.target.as_str(), entry.detail.as_str())).collect::<Vec<_>>();
+        assert_eq!(entries, [("Lobby", "refers to itself through Member"), ("Node", "refers to itself")]);
+
+        assert!(Components::new().find_cycles().is_empty());
+    }
+
+    #[test]
+    fn test_examples_terminate_on_cycles() {
+        let components = components();
+        for name in ["Lobby", "Member", "Node", "Game"] {
+            let value = example_from_schema(&SchemaObject::reference(name), &components, 64);
+            assert!(value.is_object(), "{name}");
+        }
+        let lobby = example_from_schema(&SchemaObject::reference("Lobby"), &components, 64);
+        assert_eq!(lobby, json!({ "members": [{ "lobby": {}, "name": "name" }] }));
+    }
+}
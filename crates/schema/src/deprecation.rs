@@ -0,0 +1,164 @@
+//! Surfacing deprecated components on the operations that still use them.
+
+use std::collections::{ BTreeMap, BTreeSet };
+
+use serde_json::Value;
+
+use crate::{
+    openapi::{ OpenApiSpec, SchemaObject },
+    report::{ ReportKind, SchemaReport },
+    visit::{ visit_operation_mut, visit_schema_mut },
+};
+
+/// Start of the note appended to the description of an operation that uses
+/// deprecated components.
+pub const DEPRECATED_NOTE: &str = "Uses deprecated components:";
+
+/// How far [`deprecation_sweep`] goes.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum DeprecationMode {
+    /// Note the deprecated components in the description.
+    #[default]
+    Annotate,
+    /// Also mark the operation itself `deprecated: true`.
+    Aggressive,
+}
+
+/// Find operations whose parameters, request body, or responses reach a
+/// deprecated component, directly or through other components. Each gets
+/// a [`DEPRECATED_NOTE`] in its description and one report entry per
+/// component. Returns the number of operations annotated.
+pub fn deprecation_sweep(spec: &mut OpenApiSpec, mode: DeprecationMode, report: &mut SchemaReport) -> usize {
+    let mut deprecated = BTreeSet::new();
+    let mut refs = BTreeMap::new();
+    if let Some(Value::Object(schemas)) = spec.components.get_mut("schemas") {
+        for (name, schema) in schemas.iter_mut() {
+            let parsed = serde_json::from_value::<SchemaObject>(schema.clone());
+            if parsed.is_ok_and(|schema| schema.is_deprecated()) {
+                deprecated.insert(name.clone());
+            }
+            let mut reached = BTreeSet::new();
+            visit_schema_mut(schema, format!("components.schemas.{name}"), None, &mut collect_refs(&mut reached));
+            refs.insert(name.clone(), reached);
+        }
+    }
+    if deprecated.is_empty() {
+        return 0;
+    }
+
+    let mut count = 0;
+    for (path, item) in spec.paths.iter_mut() {
+        let Some(item) = item.as_object_mut() else {
+            continue;
+        };
+        for (method, operation) in item.iter_mut() {
+            let mut direct = BTreeSet::new();
+            visit_operation_mut(operation, &format!("paths.{path}.{method}"), &mut collect_refs(&mut direct));
+            let used = reachable(direct, &refs)
+                .into_iter()
+                .filter(|name| deprecated.contains(name))
+                .collect::<Vec<String>>();
+            if used.is_empty() {
+                continue;
+            }
+            let Value::Object(operation) = operation else {
+                continue;
+            };
+
+            let id = operation.get("operationId").and_then(Value::as_str).unwrap_or(path).to_string();
+            for name in used.iter() {
+                report.push(ReportKind::Deprecated, &id, format!("uses {name}"));
+            }
+            let note = format!("{DEPRECATED_NOTE} {}.", used.join(", "));
+            let description = match operation.get("description").and_then(Value::as_str) {
+                Some(description) if !description.is_empty() => format!("{description}\n\n{note}"),
+                _ => note,
+            };
+            operation.insert("description".to_string(), Value::String(description));
+            if mode == DeprecationMode::Aggressive {
+                operation.insert("deprecated".to_string(), Value::Bool(true));
+            }
+            count += 1;
+        }
+    }
+    count
+}
+
+/// A visitor that collects the component names of every `$ref` it sees.
+fn collect_refs(names: &mut BTreeSet<String>) -> impl FnMut(&crate::visit::SchemaContext, &mut Value) + '_ {
+    |_, schema| {
+        let reference = schema.get("$ref").and_then(Value::as_str);
+        if let Some(name) = reference.and_then(|r| r.strip_prefix("#/components/schemas/")) {
+            names.insert(name.to_string());
+        }
+    }
+}
+
+/// `names` and every component reachable from them.
+fn reachable(names: BTreeSet<String>, refs: &BTreeMap<String, BTreeSet<String>>) -> BTreeSet<String> {
+    let mut seen = BTreeSet::new();
+    let mut pending = names.into_iter().collect::<Vec<String>>();
+    while let Some(name) = pending.pop() {
+        if let Some(next) = refs.get(&name) {
+            pending.extend(next.iter().filter(|next| !seen.contains(*next)).cloned());
+        }
+        seen.insert(name);
+    }
+    seen
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+    use crate::tests::miniature;
+
+    /// The miniature with the reroll points component deprecated, which only
+    /// the summoner component refers to.
+    fn spec() -> OpenApiSpec {
+        let mut spec = OpenApiSpec::from_help(&miniature(), "1.0.0").unwrap();
+        spec.components["schemas"]["LolSummonerSummonerRerollPoints"]["deprecated"] = json!(true);
+        spec
+    }
+
+    #[test]
+    fn test_annotate() {
+        let mut spec = spec();
+        let untouched = spec.paths["/lol-lobby/v2/lobby"].clone();
+        let mut report = SchemaReport::default();
+        let count = deprecation_sweep(&mut spec, DeprecationMode::Annotate, &mut report);
+
+        // Every summoner endpoint returns a summoner, which has reroll points.
+        assert_eq!(count, 3);
+        let get = &spec.paths["/lol-summoner/v1/summoners/{id}"]["get"];
+        assert!(get["description"].as_str().unwrap().ends_with("\n\nUses deprecated components: LolSummonerSummonerRerollPoints."));
+        assert!(get.get("deprecated").is_none());
+        assert_eq!(spec.paths["/lol-lobby/v2/lobby"], untouched);
+
+        let entries = report.of_kind(ReportKind::Deprecated).collect::<Vec<_>>();
+        assert_eq!(entries.len(), 3);
+        assert_eq!(entries[0].detail, "uses LolSummonerSummonerRerollPoints");
+    }
+
+    #[test]
+    fn test_aggressive() {
+        let mut spec = spec();
+        deprecation_sweep(&mut spec, DeprecationMode::Aggressive, &mut SchemaReport::default());
+        assert_eq!(spec.paths["/lol-summoner/v1/summoners/{id}"]["get"]["deprecated"], true);
+        assert!(spec.paths["/lol-lobby/v2/lobby"]["get"].get("deprecated").is_none());
+    }
+
+    #[test]
+    fn test_deprecated_flag() {
+        let flagged: SchemaObject = serde_json::from_value(json!({ "type": "object", "deprecated": true })).unwrap();
+        assert!(flagged.deprecated && flagged.is_deprecated());
+        assert_eq!(serde_json::to_value(&flagged).unwrap(), json!({ "type": "object", "deprecated": true }));
+        assert_eq!(serde_json::to_value(SchemaObject::of_type("object")).unwrap(), json!({ "type": "object" }));
+
+        // Set by hand the way patches did before the flag existed.
+        let mut legacy = SchemaObject::of_type("object");
+        legacy.additional_fields.insert("deprecated".to_string(), json!(true));
+        assert!(legacy.is_deprecated());
+    }
+}
@@ -0,0 +1,242 @@
+//! Finding every running client, for machines with the live and PBE clients
+//! open at once.
+//!
+//! Each client writes a lockfile into its install directory. [`discover`]
+//! reads them through an [`InstanceSource`], so tests can fake the filesystem.
+
+use std::{ fmt, io, path::{ Path, PathBuf } };
+
+/// Which client a lockfile belongs to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Product {
+    Live,
+    Pbe,
+    RiotClient,
+}
+
+impl Product {
+    /// Name used to select the product, e.g. with `--client pbe`.
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Live => "live",
+            Self::Pbe => "pbe",
+            Self::RiotClient => "riot-client",
+        }
+    }
+}
+
+impl fmt::Display for Product {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+/// A running client, as its lockfile describes it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConnectionInfo {
+    pub product: Product,
+    pub install_path: PathBuf,
+    pub pid: u32,
+    pub port: u16,
+    /// Basic auth password, for the user `riot`.
+    pub password: String,
+    pub protocol: String,
+}
+
+impl ConnectionInfo {
+    /// Read a lockfile in the `name:pid:port:password:protocol` format.
+    pub fn from_lockfile(install_path: &Path, contents: &str) -> Option<Self> {
+        let mut fields = contents.trim().split(':');
+        let name = fields.next()?;
+        let pid = fields.next()?.parse().ok()?;
+        let port = fields.next()?.parse().ok()?;
+        let password = fields.next()?.to_string();
+        let protocol = fields.next()?.to_string();
+        let pbe = install_path.to_string_lossy().to_ascii_lowercase().contains("pbe");
+        let product = match name {
+            "Riot Client" | "RiotClient" => Product::RiotClient,
+            _ if pbe => Product::Pbe,
+            _ => Product::Live,
+        };
+        Some(Self { product, install_path: install_path.to_path_buf(), pid, port, password, protocol })
+    }
+
+    pub fn url(&self) -> String {
+        format!("{}://127.0.0.1:{}", self.protocol, self.port)
+    }
+
+    /// One line for listings, e.g. `pbe on port 51234 (C:\Riot Games\League of Legends (PBE))`.
+    pub fn label(&self) -> String {
+        format!("{} on port {} ({})", self.product, self.port, self.install_path.display())
+    }
+}
+
+/// Where lockfiles might be, and how to read them.
+pub trait InstanceSource {
+    fn install_dirs(&self) -> Vec<PathBuf>;
+
+    fn read_lockfile(&self, dir: &Path) -> io::Result<String>;
+}
+
+/// The default install directories on Windows and macOS, and the Riot Client's
+/// config directory.
+#[derive(Debug, Clone)]
+pub struct KnownInstallDirs {
+    pub dirs: Vec<PathBuf>,
+}
+
+impl Default for KnownInstallDirs {
+    fn default() -> Self {
+        let mut dirs = vec![
+            PathBuf::from(r"C:\Riot Games\League of Legends"),
+            PathBuf::from(r"C:\Riot Games\League of Legends (PBE)"),
+            PathBuf::from("/Applications/League of Legends.app/Contents/LoL"),
+            PathBuf::from("/Applications/League of Legends (PBE).app/Contents/LoL"),
+        ];
+        if let Some(local) = std::env::var_os("LOCALAPPDATA") {
+            dirs.push(Path::new(&local).join(r"Riot Games\Riot Client\Config"));
+        }
+        Self { dirs }
+    }
+}
+
+impl InstanceSource for KnownInstallDirs {
+    fn install_dirs(&self) -> Vec<PathBuf> {
+        self.dirs.clone()
+    }
+
+    fn read_lockfile(&self, dir: &Path) -> io::Result<String> {
+        std::fs::read_to_string(dir.join("lockfile"))
+    }
+}
+
+/// Every client with a readable lockfile, sorted by product then port.
+pub fn discover(source: &impl InstanceSource) -> Vec<ConnectionInfo> {
+    let mut instances = source
+        .install_dirs()
+        .iter()
+        .filter_map(|dir| ConnectionInfo::from_lockfile(dir, &source.read_lockfile(dir).ok()?))
+        .collect::<Vec<ConnectionInfo>>();
+    instances.sort_by_key(|instance| (instance.product, instance.port));
+    instances
+}
+
+/// Why no single client could be chosen. Lists what is running, so the user
+/// can pick one.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ClientChoiceError {
+    NoneRunning,
+    /// Nothing running matches the selection.
+    NotFound { wanted: String, running: Vec<String> },
+    /// Several clients run and none was selected.
+    Ambiguous { running: Vec<String> },
+}
+
+impl fmt::Display for ClientChoiceError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::NoneRunning => write!(f, "no running client found"),
+            Self::NotFound { wanted, running } => {
+                write!(f, "no running client matches {wanted}; running: {}", running.join("; "))
+            }
+            Self::Ambiguous { running } => {
+                write!(f, "several clients are running, choose one with --client: {}", running.join("; "))
+            }
+        }
+    }
+}
+
+impl std::error::Error for ClientChoiceError {}
+
+/// The client `wanted` names, by product name or port, or the only one
+/// running when nothing is wanted. Never guesses between several.
+pub fn select<'a>(instances: &'a [ConnectionInfo], wanted: Option<&str>) -> Result<&'a ConnectionInfo, ClientChoiceError> {
+    let running = || instances.iter().map(ConnectionInfo::label).collect::<Vec<String>>();
+    let matching = instances
+        .iter()
+        .filter(|instance| {
+            wanted.is_none_or(|wanted| {
+                instance.product.name().eq_ignore_ascii_case(wanted) || instance.port.to_string() == wanted
+            })
+        })
+        .collect::<Vec<&ConnectionInfo>>();
+    match (matching.as_slice(), wanted) {
+        ([instance], _) => Ok(instance),
+        ([], _) if instances.is_empty() => Err(ClientChoiceError::NoneRunning),
+        ([], Some(wanted)) => Err(ClientChoiceError::NotFound { wanted: wanted.to_string(), running: running() }),
+        _ => Err(ClientChoiceError::Ambiguous { running: running() }),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use super::*;
+
+    /// Lockfile contents by install directory.
+    struct FakeInstances(BTreeMap<PathBuf, String>);
+
+    impl FakeInstances {
+        fn new(lockfiles: &[(&str, &str)]) -> Self {
+            Self(lockfiles.iter().map(|(dir, contents)| (PathBuf::from(dir), contents.to_string())).collect())
+        }
+    }
+
+    impl InstanceSource for FakeInstances {
+        fn install_dirs(&self) -> Vec<PathBuf> {
+            let mut dirs = self.0.keys().cloned().collect::<Vec<PathBuf>>();
+            dirs.push(PathBuf::from("/not/installed"));
+            dirs
+        }
+
+        fn read_lockfile(&self, dir: &Path) -> io::Result<String> {
+            self.0.get(dir).cloned().ok_or_else(|| io::Error::from(io::ErrorKind::NotFound))
+        }
+    }
+
+    fn both() -> FakeInstances {
+        FakeInstances::new(&[
+            ("/Riot Games/League of Legends (PBE)", "LeagueClient:200:51234:pbe-pass:https"),
+            ("/Riot Games/League of Legends", "LeagueClient:100:50000:live-pass:https"),
+            ("/Riot Games/Riot Client/Config", "Riot Client:300:60000:rc-pass:https"),
+            ("/Riot Games/Broken", "LeagueClient:not-a-pid"),
+        ])
+    }
+
+    #[test]
+    fn test_discover_labels() {
+        let instances = discover(&both());
+        let products = instances.iter().map(|instance| instance.product).collect::<Vec<Product>>();
+        assert_eq!(products, [Product::Live, Product::Pbe, Product::RiotClient]);
+        assert_eq!(instances[1].label(), "pbe on port 51234 (/Riot Games/League of Legends (PBE))");
+        assert_eq!(instances[1].url(), "https://127.0.0.1:51234");
+        assert_eq!(instances[1].password, "pbe-pass");
+    }
+
+    #[test]
+    fn test_select() {
+        let instances = discover(&both());
+        assert_eq!(select(&instances, Some("pbe")).unwrap().pid, 200);
+        assert_eq!(select(&instances, Some("PBE")).unwrap().pid, 200);
+        assert_eq!(select(&instances, Some("50000")).unwrap().product, Product::Live);
+
+        let Err(ClientChoiceError::NotFound { running, .. }) = select(&instances, Some("tournament")) else {
+            panic!("expected no match");
+        };
+        assert_eq!(running.len(), 3);
+
+        let only = discover(&FakeInstances::new(&[("/League of Legends", "LeagueClient:1:2:p:https")]));
+        assert_eq!(select(&only, None).unwrap().port, 2);
+        assert_eq!(select(&[], None), Err(ClientChoiceError::NoneRunning));
+    }
+
+    #[test]
+    fn test_ambiguous() {
+        let instances = discover(&both());
+        let err = select(&instances, None).unwrap_err();
+        assert!(matches!(&err, ClientChoiceError::Ambiguous { running } if running.len() == 3));
+        let message = err.to_string();
+        assert!(message.contains("live on port 50000") && message.contains("pbe on port 51234"), "{message}");
+    }
+}
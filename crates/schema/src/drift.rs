@@ -0,0 +1,218 @@
+//! Catching a generated spec that lost a large part of the previous one.
+//!
+//! When the client is missing permissions or only half started, `/help` can
+//! leave out whole plugins. Generation still succeeds, so without this check
+//! the gutted spec would be published.
+
+use std::{ collections::BTreeMap, fmt };
+
+use serde_json::Value;
+
+use crate::{ openapi::OpenApiSpec, report::SchemaReport, since::RunRecord };
+
+/// How many plugins a [`DriftError`] names.
+const MAX_PLUGINS_LISTED: usize = 5;
+
+/// Sizes of a spec that are compared between runs.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SpecCounts {
+    pub operations: usize,
+    pub components: usize,
+    /// Operations per plugin, by first path segment. Empty when the counts
+    /// come from a [`RunRecord`], which doesn't keep paths.
+    pub plugins: BTreeMap<String, usize>,
+}
+
+impl SpecCounts {
+    pub fn from_spec(spec: &OpenApiSpec) -> Self {
+        let mut counts = Self {
+            components: spec.components
+                .get("schemas")
+                .and_then(Value::as_object)
+                .map_or(0, |schemas| schemas.len()),
+            ..Self::default()
+        };
+        for (path, item) in spec.paths.iter() {
+            let operations = item.as_object().map_or(0, |item| item.len());
+            let plugin = path.trim_start_matches('/').split('/').next().unwrap_or_default();
+            counts.operations += operations;
+            *counts.plugins.entry(plugin.to_string()).or_default() += operations;
+        }
+        counts
+    }
+
+    pub fn from_record(record: &RunRecord) -> Self {
+        Self {
+            operations: record.operations.len(),
+            components: record.components.len(),
+            plugins: BTreeMap::new(),
+        }
+    }
+}
+
+/// Fails a run whose operation or component count fell by more than
+/// `max_drop_percent` since the baseline.
+#[derive(Debug, Clone)]
+pub struct DriftGate {
+    pub max_drop_percent: f64,
+    /// Publish anyway, with a warning in the report instead of an error.
+    pub force: bool,
+    /// The run was limited on purpose, so shrinking is expected and not checked.
+    pub partial: bool,
+}
+
+impl Default for DriftGate {
+    fn default() -> Self {
+        Self { max_drop_percent: 20.0, force: false, partial: false }
+    }
+}
+
+/// A count that fell past the threshold, or a plugin that lost operations.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CountDrop {
+    pub name: String,
+    pub before: usize,
+    pub after: usize,
+}
+
+impl fmt::Display for CountDrop {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} {} -> {}", self.name, self.before, self.after)
+    }
+}
+
+/// A candidate spec that shrank too much, with the plugins that lost the most.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DriftError {
+    pub max_drop_percent: f64,
+    /// Operations and components, whichever fell past the threshold.
+    pub totals: Vec<CountDrop>,
+    /// Largest losses first.
+    pub plugins: Vec<CountDrop>,
+}
+
+impl fmt::Display for DriftError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let totals = self.totals.iter().map(CountDrop::to_string).collect::<Vec<String>>();
+        write!(f, "spec shrank by more than {}% since the last run: {}", self.max_drop_percent, totals.join(", "))?;
+        if !self.plugins.is_empty() {
+            let plugins = self.plugins.iter().map(CountDrop::to_string).collect::<Vec<String>>();
+            write!(f, "; largest losses: {}", plugins.join(", "))?;
+        }
+        write!(f, "; rerun with --force if this is intended")
+    }
+}
+
+impl std::error::Error for DriftError {}
+
+impl DriftGate {
+    /// Compare `candidate` against `baseline`. With `force`, a failure is
+    /// recorded in `report` as a warning instead.
+    pub fn check(&self, baseline: &SpecCounts, candidate: &SpecCounts, report: &mut SchemaReport) -> Result<(), DriftError> {
+        if self.partial {
+            return Ok(());
+        }
+        let fell = |before: usize, after: usize| {
+            before > 0 && after < before && (((before - after) as f64) / (before as f64)) * 100.0 > self.max_drop_percent
+        };
+        let totals = [
+            ("operations", baseline.operations, candidate.operations),
+            ("components", baseline.components, candidate.components),
+        ]
+            .into_iter()
+            .filter(|(_, before, after)| fell(*before, *after))
+            .map(|(name, before, after)| CountDrop { name: name.to_string(), before, after })
+            .collect::<Vec<CountDrop>>();
+        if totals.is_empty() {
+            return Ok(());
+        }
+
+        let mut plugins = baseline.plugins
+            .iter()
+            .map(|(name, before)| CountDrop {
+                name: name.clone(),
+                before: *before,
+                after: candidate.plugins.get(name).copied().unwrap_or_default(),
+            })
+            .filter(|drop| drop.after < drop.before)
+            .collect::<Vec<CountDrop>>();
+        // Biggest loss first; ties by name so the message is stable.
+        plugins.sort_by(|a, b| (b.before - b.after).cmp(&(a.before - a.after)).then_with(|| a.name.cmp(&b.name)));
+        plugins.truncate(MAX_PLUGINS_LISTED);
+
+        let err = DriftError { max_drop_percent: self.max_drop_percent, totals, plugins };
+        if self.force {
+            report.warn("spec", format!("{err} (forced)"));
+            return Ok(());
+        }
+        Err(err)
+    }
+}
+
+/// A baseline to gate a run against, see [`crate::options::SchemaOptions::drift`].
+#[derive(Debug, Clone)]
+pub struct DriftCheck {
+    pub baseline: SpecCounts,
+    pub gate: DriftGate,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{ error::Error, help::ExtendedHelp, options::SchemaOptions, report::ReportKind, tests::miniature };
+
+    /// The miniature without its lobby endpoints, as if the plugin hadn't loaded.
+    fn gutted() -> ExtendedHelp {
+        let mut help = miniature();
+        help.endpoints.retain(|endpoint| !endpoint.info.name.contains("LolLobby"));
+        help.types.retain(|ty| !ty.info.name.starts_with("LolLobby"));
+        help
+    }
+
+    #[test]
+    fn test_gutted_spec_fails() {
+        let baseline = SpecCounts::from_spec(&OpenApiSpec::from_help(&miniature(), "1.0.0").unwrap());
+        let candidate = SpecCounts::from_spec(&OpenApiSpec::from_help(&gutted(), "1.0.0").unwrap());
+        assert_eq!(baseline.plugins["lol-lobby"], 2);
+
+        let err = DriftGate::default().check(&baseline, &candidate, &mut SchemaReport::default()).unwrap_err();
+        assert_eq!(err.totals.iter().map(|drop| drop.name.as_str()).collect::<Vec<&str>>(), ["operations", "components"]);
+        assert_eq!(err.plugins, [CountDrop { name: "lol-lobby".to_string(), before: 2, after: 0 }]);
+        assert!(err.to_string().contains("largest losses: lol-lobby 2 -> 0"), "{err}");
+
+        // A looser threshold lets it through.
+        let loose = DriftGate { max_drop_percent: 60.0, ..Default::default() };
+        assert!(loose.check(&baseline, &candidate, &mut SchemaReport::default()).is_ok());
+    }
+
+    #[test]
+    fn test_force_and_partial() {
+        let baseline = SpecCounts::from_spec(&OpenApiSpec::from_help(&miniature(), "1.0.0").unwrap());
+        let candidate = SpecCounts::from_spec(&OpenApiSpec::from_help(&gutted(), "1.0.0").unwrap());
+
+        let mut report = SchemaReport::default();
+        let forced = DriftGate { force: true, ..Default::default() };
+        assert!(forced.check(&baseline, &candidate, &mut report).is_ok());
+        assert_eq!(report.of_kind(ReportKind::Warning).count(), 1);
+
+        let mut report = SchemaReport::default();
+        let partial = DriftGate { partial: true, ..Default::default() };
+        assert!(partial.check(&baseline, &candidate, &mut report).is_ok());
+        assert!(report.is_empty());
+    }
+
+    #[test]
+    fn test_generation_gated() {
+        let full = OpenApiSpec::from_help(&miniature(), "1.0.0").unwrap();
+        let check = DriftCheck { baseline: SpecCounts::from_record(&RunRecord::from_spec(&full)), gate: DriftGate::default() };
+        let options = SchemaOptions { drift: Some(check), ..Default::default() };
+
+        let result = OpenApiSpec::from_help_with(&gutted(), "1.0.0", &options, &mut SchemaReport::default());
+        let Err(Error::Drift(err)) = result else {
+            panic!("expected a drift error");
+        };
+        // History records don't know paths, so no plugin is named.
+        assert!(err.plugins.is_empty());
+        assert!(OpenApiSpec::from_help_with(&miniature(), "1.0.0", &options, &mut SchemaReport::default()).is_ok());
+    }
+}
@@ -0,0 +1,195 @@
+//! Enum components, named or numeric.
+
+use serde_json::{ Number, Value };
+
+use crate::{ help::Type, openapi::SchemaObject };
+
+/// Extension key with the names of a numeric enum's values, index-aligned with `enum`.
+pub const X_ENUM_VARNAMES: &str = "x-enum-varnames";
+
+/// Extension key with the descriptions of an enum's values, index-aligned with `enum`.
+pub const X_ENUM_DESCRIPTION: &str = "x-enum-description";
+
+/// One value of an LCU enum.
+#[derive(Debug, Clone, PartialEq)]
+pub struct EnumVariant {
+    /// `None` when the LCU gives no name, or only the number again.
+    pub name: Option<String>,
+    pub value: Number,
+    pub description: String,
+}
+
+impl EnumVariant {
+    fn from_help(value: &crate::help::Value) -> Self {
+        let name = Some(value.name.clone()).filter(|name| !name.is_empty() && name.parse::<f64>().is_err());
+        Self { name, value: value.value.clone(), description: value.description.clone() }
+    }
+}
+
+/// An enum component and how it is written.
+///
+/// LCU enums are sent by name, so they become string enums. An enum with any
+/// variant whose name is missing or numeric is sent by number instead: `enum`
+/// then holds the numbers, sign and fraction kept, and the names move to
+/// [`X_ENUM_VARNAMES`]. Names, numbers and [`X_ENUM_DESCRIPTION`] stay index-aligned.
+#[derive(Debug, Clone, PartialEq)]
+pub struct EnumSchema {
+    pub variants: Vec<EnumVariant>,
+}
+
+impl EnumSchema {
+    /// The enum of a help type, if it has values.
+    pub fn from_type(ty: &Type) -> Option<Self> {
+        if ty.values.is_empty() {
+            return None;
+        }
+        Some(Self { variants: ty.values.iter().map(EnumVariant::from_help).collect() })
+    }
+
+    pub fn is_numeric(&self) -> bool {
+        self.variants.iter().any(|variant| variant.name.is_none())
+    }
+
+    pub fn to_schema(&self) -> SchemaObject {
+        let mut schema;
+        if self.is_numeric() {
+            let integers = self.variants.iter().all(|v| v.value.is_i64() || v.value.is_u64());
+            schema = SchemaObject::of_type(if integers { "integer" } else { "number" });
+            schema.enum_values = Some(
+                self.variants
+                    .iter()
+                    .map(|v| Value::Number(v.value.clone()))
+                    .collect()
+            );
+            if self.variants.iter().any(|v| v.name.is_some()) {
+                let names = self.variants
+                    .iter()
+                    .map(|v| Value::from(v.name.clone().unwrap_or_default()))
+                    .collect();
+                schema.additional_fields.insert(X_ENUM_VARNAMES.to_string(), Value::Array(names));
+            }
+        } else {
+            schema = SchemaObject::of_type("string");
+            schema.enum_values = Some(
+                self.variants
+                    .iter()
+                    .map(|v| Value::from(v.name.clone().unwrap_or_default()))
+                    .collect()
+            );
+        }
+        if self.variants.iter().any(|v| !v.description.is_empty()) {
+            let descriptions = self.variants
+                .iter()
+                .map(|v| Value::from(v.description.clone()))
+                .collect();
+            schema.additional_fields.insert(X_ENUM_DESCRIPTION.to_string(), Value::Array(descriptions));
+        }
+        schema
+    }
+
+    /// Read an enum back from a schema written by [`EnumSchema::to_schema`].
+    ///
+    /// String enums have no numbers, so their values are numbered from 0.
+    pub fn from_schema(schema: &SchemaObject) -> Option<Self> {
+        let values = schema.enum_values.as_ref()?;
+        let strings = |key: &str| {
+            schema.additional_fields
+                .get(key)
+                .and_then(Value::as_array)
+                .map(|values| values.iter().map(|v| v.as_str().unwrap_or_default().to_string()).collect())
+                .unwrap_or_default()
+        };
+        let names: Vec<String> = strings(X_ENUM_VARNAMES);
+        let descriptions: Vec<String> = strings(X_ENUM_DESCRIPTION);
+
+        let variants = values
+            .iter()
+            .enumerate()
+            .map(|(i, value)| {
+                let (name, value) = match value {
+                    Value::Number(number) => {
+                        let name = names.get(i).filter(|name| !name.is_empty()).cloned();
+                        (name, number.clone())
+                    }
+                    other => (other.as_str().map(str::to_string), Number::from(i)),
+                };
+                EnumVariant { name, value, description: descriptions.get(i).cloned().unwrap_or_default() }
+            })
+            .collect();
+        Some(Self { variants })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    fn enum_type(values: Value) -> Type {
+        serde_json::from_value(
+            json!({
+                "values": values,
+                "fields": [],
+                "name": "LolTestEnum",
+                "description": "",
+                "nameSpace": "Plugins",
+                "size": 4,
+                "tags": []
+            })
+        ).unwrap()
+    }
+
+    #[test]
+    fn test_named_enum() {
+        let ty = enum_type(json!([
+            { "name": "PRIVATE", "description": "", "value": 0 },
+            { "name": "PUBLIC", "description": "", "value": 1 }
+        ]));
+        let schema = EnumSchema::from_type(&ty).unwrap().to_schema();
+        assert_eq!(schema.ty.as_deref(), Some("string"));
+        assert_eq!(schema.enum_values, Some(vec![json!("PRIVATE"), json!("PUBLIC")]));
+        assert!(schema.additional_fields.is_empty());
+    }
+
+    #[test]
+    fn test_negative_and_float() {
+        let ty = enum_type(json!([
+            { "name": "", "description": "Unset", "value": -1 },
+            { "name": "", "description": "", "value": 420 }
+        ]));
+        let schema = EnumSchema::from_type(&ty).unwrap().to_schema();
+        assert_eq!(schema.ty.as_deref(), Some("integer"));
+        assert_eq!(schema.enum_values, Some(vec![json!(-1), json!(420)]));
+        assert!(!schema.additional_fields.contains_key(X_ENUM_VARNAMES));
+        assert_eq!(schema.additional_fields[X_ENUM_DESCRIPTION], json!(["Unset", ""]));
+
+        let ty = enum_type(json!([
+            { "name": "0.5", "description": "", "value": 0.5 },
+            { "name": "1", "description": "", "value": 1 }
+        ]));
+        let schema = EnumSchema::from_type(&ty).unwrap().to_schema();
+        assert_eq!(schema.ty.as_deref(), Some("number"));
+        assert_eq!(schema.enum_values, Some(vec![json!(0.5), json!(1)]));
+    }
+
+    #[test]
+    fn test_mixed_round_trip() {
+        let ty = enum_type(json!([
+            { "name": "RANKED_SOLO", "description": "Ranked solo/duo", "value": 420 },
+            { "name": "", "description": "", "value": -2 },
+            { "name": "ARAM", "description": "", "value": 450 }
+        ]));
+        let enumeration = EnumSchema::from_type(&ty).unwrap();
+        assert!(enumeration.is_numeric());
+
+        let schema = enumeration.to_schema();
+        assert_eq!(schema.enum_values, Some(vec![json!(420), json!(-2), json!(450)]));
+        assert_eq!(schema.additional_fields[X_ENUM_VARNAMES], json!(["RANKED_SOLO", "", "ARAM"]));
+        assert_eq!(schema.additional_fields[X_ENUM_DESCRIPTION], json!(["Ranked solo/duo", "", ""]));
+
+        let json = serde_json::to_string(&schema).unwrap();
+        let read: SchemaObject = serde_json::from_str(&json).unwrap();
+        assert_eq!(EnumSchema::from_schema(&read), Some(enumeration));
+    }
+}
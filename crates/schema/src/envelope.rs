@@ -0,0 +1,252 @@
+//! Wrapper shapes many LCU responses share, so clients can unwrap them once
+//! instead of per endpoint: paged results and async call handles.
+
+use std::collections::BTreeSet;
+
+use serde_json::{ Value, json };
+
+use crate::{ openapi::{ OpenApiSpec, SchemaObject }, report::{ ReportKind, SchemaReport } };
+
+/// Extension key naming the wrapper a component is, e.g. `paged`.
+pub const X_ENVELOPE: &str = "x-envelope";
+/// Extension key holding the schema of a paged component's items.
+pub const X_ENVELOPE_ITEMS: &str = "x-envelope-items";
+
+/// Component shared by the builtin async endpoints for the token they take.
+pub const ASYNC_HANDLE: &str = "LcuAsyncHandle";
+/// Builtin endpoints that take the token of an async call.
+pub const ASYNC_ENDPOINTS: [&str; 3] = ["AsyncDelete", "AsyncResult", "AsyncStatus"];
+/// Name of the argument the [`ASYNC_ENDPOINTS`] take the token in.
+const ASYNC_TOKEN: &str = "asyncToken";
+
+/// The fields of a paging wrapper: an array of items and an integer total.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PagedShape {
+    pub items: String,
+    pub total: String,
+}
+
+impl PagedShape {
+    pub fn new(items: &str, total: &str) -> Self {
+        Self { items: items.to_string(), total: total.to_string() }
+    }
+
+    /// The item schema of `schema`, if it has exactly this shape's fields and
+    /// nothing else.
+    fn items_of<'a>(&self, schema: &'a SchemaObject) -> Option<&'a SchemaObject> {
+        let properties = schema.properties.as_ref()?;
+        let names = properties.keys().map(String::as_str).collect::<BTreeSet<&str>>();
+        if names != BTreeSet::from([self.items.as_str(), self.total.as_str()]) {
+            return None;
+        }
+        let items = &properties[&self.items];
+        let total = &properties[&self.total];
+        if items.ty.as_deref() != Some("array") || total.ty.as_deref() != Some("integer") {
+            return None;
+        }
+        items.items.as_deref()
+    }
+}
+
+/// Which components are recognized as wrappers.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EnvelopeRules {
+    pub paged: Vec<PagedShape>,
+}
+
+impl Default for EnvelopeRules {
+    fn default() -> Self {
+        Self { paged: vec![PagedShape::new("items", "total")] }
+    }
+}
+
+impl EnvelopeRules {
+    /// Mark paging wrappers with [`X_ENVELOPE`] and point the async endpoints
+    /// at [`ASYNC_HANDLE`]. Every match is listed in the report.
+    pub fn apply(&self, spec: &mut OpenApiSpec, report: &mut SchemaReport) {
+        self.mark_paged(spec, report);
+        wire_async_handle(spec, report);
+    }
+
+    fn mark_paged(&self, spec: &mut OpenApiSpec, report: &mut SchemaReport) {
+        let Some(Value::Object(schemas)) = spec.components.get_mut("schemas") else {
+            return;
+        };
+        for (name, schema) in schemas.iter_mut() {
+            let Ok(parsed) = serde_json::from_value::<SchemaObject>(schema.clone()) else {
+                continue;
+            };
+            let Some((shape, items)) = self.paged.iter().find_map(|shape| Some((shape, shape.items_of(&parsed)?))) else {
+                continue;
+            };
+            let items = serde_json::to_value(items).unwrap_or_default();
+            let item_name = items.get("$ref").and_then(Value::as_str).map(|r| r.trim_start_matches("#/components/schemas/"));
+            report.push(
+                ReportKind::Envelope,
+                name,
+                match item_name {
+                    Some(item) => format!("paged {item} in {} with {}", shape.items, shape.total),
+                    None => format!("paged in {} with {}", shape.items, shape.total),
+                }
+            );
+            if let Value::Object(schema) = schema {
+                schema.insert(X_ENVELOPE.to_string(), Value::from("paged"));
+                schema.insert(X_ENVELOPE_ITEMS.to_string(), items);
+            }
+        }
+    }
+}
+
+/// Replace the token of the builtin async endpoints with a reference to one
+/// shared [`ASYNC_HANDLE`] component, added if any endpoint uses it.
+fn wire_async_handle(spec: &mut OpenApiSpec, report: &mut SchemaReport) {
+    let handle = Value::from(format!("#/components/schemas/{ASYNC_HANDLE}"));
+    let mut wired = Vec::new();
+    let operations = spec.paths
+        .values_mut()
+        .filter_map(Value::as_object_mut)
+        .flat_map(|item| item.values_mut())
+        .filter_map(Value::as_object_mut);
+    for operation in operations {
+        let Some(id) = operation.get("operationId").and_then(Value::as_str).map(str::to_string) else {
+            continue;
+        };
+        let builtin = operation
+            .get("tags")
+            .and_then(Value::as_array)
+            .is_some_and(|tags| tags.iter().any(|tag| tag == "builtin"));
+        if !builtin || !ASYNC_ENDPOINTS.contains(&id.as_str()) {
+            continue;
+        }
+
+        // The token is the lone body, or a parameter named after it.
+        let mut uses = false;
+        if let Some(Value::Object(content)) = operation.get_mut("requestBody").and_then(|body| body.get_mut("content")) {
+            for media in content.values_mut() {
+                uses |= media.get_mut("schema").is_some_and(|schema| link_handle(schema, &handle));
+            }
+        }
+        if let Some(Value::Array(parameters)) = operation.get_mut("parameters") {
+            for parameter in parameters.iter_mut().filter(|parameter| parameter["name"] == ASYNC_TOKEN) {
+                uses |= parameter.get_mut("schema").is_some_and(|schema| link_handle(schema, &handle));
+            }
+        }
+        if uses {
+            wired.push(id);
+        }
+    }
+    if wired.is_empty() {
+        return;
+    }
+
+    wired.sort();
+    if let Some(Value::Object(schemas)) = spec.components.get_mut("schemas") {
+        schemas.insert(
+            ASYNC_HANDLE.to_string(),
+            json!({
+                "type": "integer",
+                "format": "uint32",
+                "description": "Token of an async call, for AsyncStatus, AsyncResult and AsyncDelete.",
+            })
+        );
+    }
+    report.push(ReportKind::Envelope, ASYNC_HANDLE, format!("async handle of {}", wired.join(", ")));
+}
+
+/// Replace an integer token schema with `handle`. Returns whether it did.
+fn link_handle(schema: &mut Value, handle: &Value) -> bool {
+    if schema["type"] != "integer" {
+        return false;
+    }
+    *schema = json!({ "$ref": handle });
+    true
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{ help::{ Endpoint, ExtendedHelp, Type }, options::SchemaOptions, tests::miniature };
+
+    fn paged_type(name: &str, extra: Option<&str>) -> Type {
+        let mut fields = vec![
+            json!({ "name": "items", "description": "", "offset": 0, "optional": false, "type": { "elementType": "LolSummonerSummoner", "type": "vector" } }),
+            json!({ "name": "total", "description": "", "offset": 1, "optional": false, "type": { "elementType": "", "type": "uint32" } }),
+        ];
+        if let Some(extra) = extra {
+            fields.push(json!({ "name": extra, "description": "", "offset": 2, "optional": false, "type": { "elementType": "", "type": "string" } }));
+        }
+        serde_json::from_value(
+            json!({ "values": [], "fields": fields, "name": name, "description": "", "nameSpace": "Plugins", "size": 0, "tags": ["Plugin lol-summoner"] })
+        ).unwrap()
+    }
+
+    fn async_endpoint(name: &str) -> Endpoint {
+        serde_json::from_value(
+            json!({
+                "name": name, "description": "", "nameSpace": "", "help": "",
+                "arguments": [{ "name": "asyncToken", "description": "", "optional": false, "type": { "elementType": "", "type": "uint32" } }],
+                "tags": ["builtin"], "method": "post", "path": format!("/{name}"), "pathParams": [],
+                "returns": { "elementType": "", "type": "object" },
+                "async": false, "threadSafe": false, "overridden": false, "silentOverride": false
+            })
+        ).unwrap()
+    }
+
+    fn convert(help: &ExtendedHelp) -> (OpenApiSpec, SchemaReport) {
+        let options = SchemaOptions { envelopes: Some(EnvelopeRules::default()), ..Default::default() };
+        let mut report = SchemaReport::default();
+        let spec = OpenApiSpec::from_help_with(help, "1.0.0", &options, &mut report).unwrap();
+        (spec, report)
+    }
+
+    #[test]
+    fn test_paged() {
+        let mut help = miniature();
+        help.types.push(paged_type("LolSummonerSummonerPage", None));
+        let (spec, report) = convert(&help);
+
+        let page = &spec.components["schemas"]["LolSummonerSummonerPage"];
+        assert_eq!(page[X_ENVELOPE], "paged");
+        assert_eq!(page[X_ENVELOPE_ITEMS], json!({ "$ref": "#/components/schemas/LolSummonerSummoner" }));
+        let entries = report.of_kind(ReportKind::Envelope).collect::<Vec<_>>();
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].detail, "paged LolSummonerSummoner in items with total");
+    }
+
+    #[test]
+    fn test_near_miss() {
+        let mut help = miniature();
+        // An extra field, and the right fields with a string total.
+        help.types.push(paged_type("LolSummonerSummonerCursor", Some("nextCursor")));
+        let mut stringly = paged_type("LolSummonerSummonerStringPage", None);
+        stringly.fields[1].ty.ty = "string".to_string();
+        help.types.push(stringly);
+        let (spec, report) = convert(&help);
+
+        for name in ["LolSummonerSummonerCursor", "LolSummonerSummonerStringPage"] {
+            assert!(spec.components["schemas"][name].get(X_ENVELOPE).is_none(), "{name}");
+        }
+        assert!(report.of_kind(ReportKind::Envelope).next().is_none());
+    }
+
+    #[test]
+    fn test_async_handle() {
+        let mut help = miniature();
+        help.endpoints.extend(ASYNC_ENDPOINTS.map(async_endpoint));
+        let (spec, report) = convert(&help);
+
+        let handle = json!({ "$ref": "#/components/schemas/LcuAsyncHandle" });
+        for name in ASYNC_ENDPOINTS {
+            let operation = &spec.paths[&format!("/{name}")]["post"];
+            assert_eq!(operation["requestBody"]["content"]["application/json"]["schema"], handle, "{name}");
+        }
+        assert_eq!(spec.components["schemas"][ASYNC_HANDLE]["type"], "integer");
+        let entries = report.of_kind(ReportKind::Envelope).collect::<Vec<_>>();
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].detail, "async handle of AsyncDelete, AsyncResult, AsyncStatus");
+
+        // Without the endpoints, there's no handle.
+        let (spec, _) = convert(&miniature());
+        assert!(spec.components["schemas"].get(ASYNC_HANDLE).is_none());
+    }
+}
@@ -1,4 +1,5 @@
 use derive_more::{ Display, Error, From };
+#[cfg(feature = "irelia")]
 use irelia::requests::{ HyperError };
 
 #[derive(Error, Debug, Display, From)]
@@ -6,5 +7,34 @@ pub enum Error {
     Io(std::io::Error),
     Fmt(std::fmt::Error),
     Json(serde_json::Error),
+    /// An output template with placeholders that don't exist.
+    Template(crate::artifact::TemplateError),
+    /// A conversion failed and a bug report bundle was written for it.
+    Bundled(Box<crate::bundle::BundledError>),
+    /// The spec shrank too much since the last run.
+    Drift(crate::drift::DriftError),
+    /// A spec fragment that can't be merged.
+    Fragment(crate::fragment::FragmentError),
+    /// Configured component renames that can't be applied.
+    Rename(crate::component_renames::ComponentRenameError),
+    /// A saved artifact in a format this version can't read.
+    Migration(crate::migrate::MigrationError),
+    /// No single running client to connect to.
+    #[cfg(feature = "irelia")]
+    Client(crate::discovery::ClientChoiceError),
+    /// A search query line that doesn't parse.
+    Query(crate::search::QueryError),
+    /// Strict mode refused a fallback.
+    Parse(crate::fallback::ParseError),
+    /// A request a replayed session has no recording of.
+    #[cfg(feature = "irelia")]
+    Replay(crate::recording::ReplayMiss),
+    /// An operation to explain that the conversion doesn't emit.
+    #[cfg(feature = "core")]
+    Explain(crate::explain::UnknownOperation),
+    /// A patch-day run stopped at one of its stages.
+    #[cfg(feature = "irelia")]
+    PatchDay(Box<crate::patch_day::PatchDayError>),
+    #[cfg(feature = "irelia")]
     IreliaHyper(irelia::error::Error<HyperError>),
 }
@@ -0,0 +1,458 @@
+//! Placeholder values shaped like a schema, for request bodies and mock responses.
+
+use std::collections::{ BTreeMap, BTreeSet };
+
+use serde_json::{ Map, Value, json };
+use sha2::{ Digest, Sha256 };
+
+use crate::{ cycles::FindCycles, openapi::{ Components, OpenApiSpec, SchemaObject } };
+
+/// How deep examples follow nested schemas before giving up with `{}`.
+const MAX_DEPTH: usize = 8;
+
+/// Example value shaped like `schema`, with refs resolved against `schemas`.
+///
+/// Strings are empty, numbers zero, and booleans false. Enums take their first
+/// value and arrays hold a single example item.
+pub fn example(schema: &Value, schemas: Option<&Map<String, Value>>) -> Value {
+    example_at(schema, schemas, 0)
+}
+
+fn example_at(schema: &Value, schemas: Option<&Map<String, Value>>, depth: usize) -> Value {
+    if depth > MAX_DEPTH {
+        return json!({});
+    }
+    if let Some(reference) = schema["$ref"].as_str() {
+        let name = reference.trim_start_matches("#/components/schemas/");
+        return match schemas.and_then(|schemas| schemas.get(name)) {
+            Some(component) => example_at(component, schemas, depth + 1),
+            None => json!({}),
+        };
+    }
+    if let Some(first) = schema["enum"].as_array().and_then(|values| values.first()) {
+        return first.clone();
+    }
+    match schema["type"].as_str() {
+        Some("string") => json!(""),
+        Some("integer" | "number") => json!(0),
+        Some("boolean") => json!(false),
+        Some("array") => {
+            Value::Array(vec![example_at(&schema["items"], schemas, depth + 1)])
+        }
+        _ => {
+            let object = schema["properties"]
+                .as_object()
+                .into_iter()
+                .flatten()
+                .map(|(name, property)| (name.clone(), example_at(property, schemas, depth + 1)))
+                .collect::<Map<String, Value>>();
+            Value::Object(object)
+        }
+    }
+}
+
+/// Name of the sidecar file [`ExampleMode::Sidecar`] examples are written to.
+pub const EXAMPLES_FILE: &str = "examples.json";
+
+/// Deterministic example request value for `schema`, with refs resolved
+/// against the `schemas` in `components`.
+///
+/// Strings hold the name of their property, or `string` outside an object.
+/// Numbers are zero, booleans false, enums take their first value, and arrays
+/// hold a single item. Maps are empty. A ref back to a component of a
+/// [`FindCycles::find_cycles`] group that is already being expanded, or one
+/// more than `depth_limit` refs deep, is `{}`.
+pub fn example_from_schema(schema: &SchemaObject, components: &Components, depth_limit: usize) -> Value {
+    example_with(schema, components, depth_limit, ValueProvider::Simple, "")
+}
+
+/// [`example_from_schema`] with leaf values from `values`, seeded by
+/// `operation_id` where the provider uses a seed.
+pub fn example_with(
+    schema: &SchemaObject,
+    components: &Components,
+    depth_limit: usize,
+    values: ValueProvider,
+    operation_id: &str
+) -> Value {
+    let cyclic = components.cyclic();
+    let synthesizer = Synthesizer::new(components, &cyclic, depth_limit, values, operation_id);
+    synthesizer.synthesize(schema, "string", "", &mut Vec::new())
+}
+
+/// Where the leaf values of synthetic examples come from.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ValueProvider {
+    /// Strings hold their property name, numbers are zero, booleans false,
+    /// and enums take their first value.
+    Simple,
+    /// Plausible values that differ from field to field but not from run to
+    /// run, see [`seeded_value`].
+    #[default]
+    Seeded,
+}
+
+/// Timestamps from [`ValueProvider::Seeded`] fall on this day, at a time of
+/// day picked by the seed.
+pub const SEEDED_EPOCH_DATE: &str = "2024-01-01";
+
+/// Words that summoner names and other strings are made from.
+const WORDS: [&str; 16] = [
+    "Poro", "Teemo", "Baron", "Dragon", "Herald", "Rift", "Nexus", "Yordle",
+    "Ward", "Minion", "Blue", "Red", "Scuttle", "Krug", "Gromp", "Raptor",
+];
+
+/// Seed for the value at `path` in the examples of `operation_id`: the first
+/// eight bytes of their SHA-256, so it's the same on every platform and run.
+pub fn seed(operation_id: &str, path: &str) -> u64 {
+    let digest = Sha256::digest(format!("{operation_id}\0{path}").as_bytes());
+    u64::from_be_bytes(digest[..8].try_into().expect("8 bytes"))
+}
+
+/// A value for a leaf `schema` named `name`, picked by `seed`.
+///
+/// Names are two words from a small list, ids are in the ranges the client
+/// uses, timestamps fall on [`SEEDED_EPOCH_DATE`], and booleans follow the
+/// seed's parity.
+pub fn seeded_value(schema: &SchemaObject, name: &str, seed: u64) -> Value {
+    if let Some(values) = schema.enum_values.as_ref().filter(|values| !values.is_empty()) {
+        return values[(seed % values.len() as u64) as usize].clone();
+    }
+    let lower = name.to_lowercase();
+    let is_id = lower == "id" || name.ends_with("Id") || name.ends_with("Ids");
+    let word = |shift: u32| WORDS[((seed >> shift) % WORDS.len() as u64) as usize];
+    match schema.ty.as_deref() {
+        Some("string") if schema.format.as_deref() == Some("date-time") || lower.ends_with("date") || lower.ends_with("time") => {
+            let seconds = seed % 86_400;
+            json!(format!("{SEEDED_EPOCH_DATE}T{:02}:{:02}:{:02}Z", seconds / 3600, seconds / 60 % 60, seconds % 60))
+        }
+        Some("string") if lower == "puuid" || lower.ends_with("uuid") => {
+            let hex = format!("{seed:016x}{:016x}", seed.rotate_left(32) ^ 0x9e37_79b9_7f4a_7c15);
+            json!(format!("{}-{}-{}-{}-{}", &hex[..8], &hex[8..12], &hex[12..16], &hex[16..20], &hex[20..32]))
+        }
+        Some("string") if lower.contains("name") => json!(format!("{}{}", word(0), word(8))),
+        Some("string") => json!(word(0)),
+        Some("integer") if is_id => json!(100_000 + seed % 900_000_000),
+        Some("integer") => json!(seed % 100),
+        Some("number") => json!((seed % 10_000) as f64 / 100.0),
+        Some("boolean") => json!(seed % 2 == 1),
+        _ => Value::Null,
+    }
+}
+
+struct Synthesizer<'a> {
+    schemas: Option<&'a Map<String, Value>>,
+    /// Components in a ref cycle, the only ones that can be reached again
+    /// while they are being expanded.
+    cyclic: &'a BTreeSet<String>,
+    depth_limit: usize,
+    values: ValueProvider,
+    operation_id: &'a str,
+}
+
+impl<'a> Synthesizer<'a> {
+    fn new(
+        components: &'a Components,
+        cyclic: &'a BTreeSet<String>,
+        depth_limit: usize,
+        values: ValueProvider,
+        operation_id: &'a str
+    ) -> Self {
+        let schemas = components.get("schemas").and_then(Value::as_object);
+        Self { schemas, cyclic, depth_limit, values, operation_id }
+    }
+
+    /// `name` is the property the value is for and `path` where it is, as
+    /// dot-separated property names.
+    fn synthesize(&self, schema: &SchemaObject, name: &str, path: &str, expanding: &mut Vec<String>) -> Value {
+        if let Some(reference) = schema.reference_name() {
+            let is_repeat = self.cyclic.contains(reference) && expanding.iter().any(|expanded| expanded == reference);
+            if expanding.len() >= self.depth_limit || is_repeat {
+                return json!({});
+            }
+            let Some(component) = self.schemas
+                .and_then(|schemas| schemas.get(reference))
+                .and_then(|component| serde_json::from_value::<SchemaObject>(component.clone()).ok()) else {
+                return json!({});
+            };
+            expanding.push(reference.to_string());
+            let value = self.synthesize(&component, name, path, expanding);
+            expanding.pop();
+            return value;
+        }
+        let is_leaf = schema.enum_values.is_some() || matches!(schema.ty.as_deref(), Some("string" | "integer" | "number" | "boolean"));
+        if is_leaf && self.values == ValueProvider::Seeded {
+            return seeded_value(schema, name, seed(self.operation_id, path));
+        }
+        if let Some(first) = schema.enum_values.as_ref().and_then(|values| values.first()) {
+            return first.clone();
+        }
+        match schema.ty.as_deref() {
+            Some("string") => Value::from(name),
+            Some("integer" | "number") => json!(0),
+            Some("boolean") => json!(false),
+            Some("array") => {
+                let items = schema.items.as_deref().cloned().unwrap_or_default();
+                Value::Array(vec![self.synthesize(&items, name, &format!("{path}.0"), expanding)])
+            }
+            _ => {
+                let object = schema.properties
+                    .iter()
+                    .flatten()
+                    .map(|(property, schema)| {
+                        let path = if path.is_empty() { property.clone() } else { format!("{path}.{property}") };
+                        (property.clone(), self.synthesize(schema, property, &path, expanding))
+                    })
+                    .collect::<Map<String, Value>>();
+                Value::Object(object)
+            }
+        }
+    }
+}
+
+/// Where synthetic request examples go.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ExampleMode {
+    /// As the `example` of each request body content type.
+    #[default]
+    Inline,
+    /// In an [`EXAMPLES_FILE`] next to the spec, keyed by operationId.
+    Sidecar,
+}
+
+/// Synthetic examples for every operation with a request body, made by
+/// [`example_with`] and seeded by the operationId.
+#[derive(Debug, Clone)]
+pub struct RequestExamples {
+    pub mode: ExampleMode,
+    pub depth_limit: usize,
+    pub values: ValueProvider,
+}
+
+impl Default for RequestExamples {
+    fn default() -> Self {
+        Self { mode: ExampleMode::default(), depth_limit: 4, values: ValueProvider::default() }
+    }
+}
+
+impl RequestExamples {
+    /// Examples keyed by operationId, for the sidecar file. Bodies with several
+    /// content types use the JSON one.
+    pub fn collect(&self, spec: &OpenApiSpec) -> BTreeMap<String, Value> {
+        let cyclic = spec.components.cyclic();
+        let mut examples = BTreeMap::new();
+        for operation in spec.paths.values().filter_map(Value::as_object).flat_map(|item| item.values()) {
+            let (Some(id), Some(content)) = (
+                operation["operationId"].as_str(),
+                operation["requestBody"]["content"].as_object(),
+            ) else {
+                continue;
+            };
+            let media = content.get("application/json").or_else(|| content.values().next());
+            if let Some(schema) = media.and_then(schema_of) {
+                let synthesizer = Synthesizer::new(&spec.components, &cyclic, self.depth_limit, self.values, id);
+                examples.insert(id.to_string(), synthesizer.synthesize(&schema, "string", "", &mut Vec::new()));
+            }
+        }
+        examples
+    }
+
+    /// Write an `example` on every request body content type. Returns how many
+    /// were written.
+    pub fn apply(&self, spec: &mut OpenApiSpec) -> usize {
+        let cyclic = spec.components.cyclic();
+        let mut count = 0;
+        for item in spec.paths.values_mut().filter_map(Value::as_object_mut) {
+            for operation in item.values_mut() {
+                let id = operation["operationId"].as_str().unwrap_or_default().to_string();
+                let content = operation
+                    .get_mut("requestBody")
+                    .and_then(|body| body.get_mut("content"))
+                    .and_then(Value::as_object_mut);
+                let Some(content) = content else {
+                    continue;
+                };
+                for media in content.values_mut() {
+                    let Some(schema) = schema_of(media) else {
+                        continue;
+                    };
+                    let synthesizer = Synthesizer::new(&spec.components, &cyclic, self.depth_limit, self.values, &id);
+                    let example = synthesizer.synthesize(&schema, "string", "", &mut Vec::new());
+                    if let Some(media) = media.as_object_mut() {
+                        media.insert("example".to_string(), example);
+                        count += 1;
+                    }
+                }
+            }
+        }
+        count
+    }
+}
+
+fn schema_of(media: &Value) -> Option<SchemaObject> {
+    serde_json::from_value(media.get("schema")?.clone()).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_example() {
+        let schemas = json!({
+            "Node": { "type": "object", "properties": { "next": { "$ref": "#/components/schemas/Node" } } },
+            "Queue": { "type": "string", "enum": ["NORMAL", "RANKED"] },
+        });
+        let schema = json!({
+            "type": "object",
+            "properties": {
+                "ids": { "type": "array", "items": { "type": "integer" } },
+                "queue": { "$ref": "#/components/schemas/Queue" },
+                "node": { "$ref": "#/components/schemas/Node" },
+            }
+        });
+        let value = example(&schema, schemas.as_object());
+        assert_eq!(value["ids"], json!([0]));
+        assert_eq!(value["queue"], "NORMAL");
+        // Recursion stops instead of overflowing.
+        assert!(value["node"]["next"]["next"].is_object());
+    }
+
+    fn components(schemas: Value) -> Components {
+        json!({ "schemas": schemas }).as_object().unwrap().clone()
+    }
+
+    fn reference(name: &str) -> SchemaObject {
+        SchemaObject::reference(name)
+    }
+
+    #[test]
+    fn test_nested_refs() {
+        let components = components(json!({
+            "Lobby": { "type": "object", "properties": { "config": { "$ref": "#/components/schemas/Config" } } },
+            "Config": {
+                "type": "object",
+                "properties": {
+                    "name": { "type": "string" },
+                    "queueId": { "type": "integer" },
+                    "tags": { "type": "array", "items": { "type": "string" } },
+                }
+            },
+        }));
+        let value = example_from_schema(&reference("Lobby"), &components, 4);
+        assert_eq!(value, json!({ "config": { "name": "name", "queueId": 0, "tags": ["tags"] } }));
+        assert_eq!(example_from_schema(&SchemaObject::of_type("string"), &components, 4), "string");
+    }
+
+    #[test]
+    fn test_cyclic_components() {
+        let components = components(json!({
+            "Left": { "type": "object", "properties": { "right": { "$ref": "#/components/schemas/Right" } } },
+            "Right": { "type": "object", "properties": { "left": { "$ref": "#/components/schemas/Left" } } },
+        }));
+        let value = example_from_schema(&reference("Left"), &components, 8);
+        assert_eq!(value, json!({ "right": { "left": {} } }));
+        // The depth limit cuts it off sooner.
+        assert_eq!(example_from_schema(&reference("Left"), &components, 1), json!({ "right": {} }));
+        // Same output every time.
+        assert_eq!(example_from_schema(&reference("Left"), &components, 8), value);
+    }
+
+    #[test]
+    fn test_enum_selection() {
+        let components = components(json!({
+            "Queue": { "type": "string", "enum": ["RANKED", "NORMAL"] },
+            "Priority": { "type": "integer", "enum": [-1, 1], "x-enum-varnames": ["LOW", "HIGH"] },
+        }));
+        assert_eq!(example_from_schema(&reference("Queue"), &components, 4), "RANKED");
+        assert_eq!(example_from_schema(&reference("Priority"), &components, 4), -1);
+    }
+
+    #[test]
+    fn test_request_examples() {
+        use crate::{ options::SchemaOptions, report::SchemaReport, tests::miniature };
+
+        let examples = RequestExamples { values: ValueProvider::Simple, ..Default::default() };
+        let options = SchemaOptions { request_examples: Some(examples.clone()), ..Default::default() };
+        let spec = OpenApiSpec::from_help_with(&miniature(), "1.0.0", &options, &mut SchemaReport::default()).unwrap();
+        let example = json!({ "isCustom": false, "queueId": 0 });
+        let body = &spec.paths["/lol-lobby/v2/lobby"]["post"]["requestBody"];
+        assert_eq!(body["content"]["application/json"]["example"], example);
+        assert_eq!(spec.paths["/Help"]["post"]["requestBody"]["content"]["text/plain"]["example"], "string");
+
+        // Inline examples survive writing the spec out and reading it back.
+        let read: OpenApiSpec = serde_json::from_str(&serde_json::to_string(&spec).unwrap()).unwrap();
+        let body: crate::openapi::RequestBody = serde_json::from_value(
+            read.paths["/lol-lobby/v2/lobby"]["post"]["requestBody"].clone()
+        ).unwrap();
+        assert_eq!(body.content["application/json"].example, Some(example.clone()));
+
+        let sidecar = RequestExamples { mode: ExampleMode::Sidecar, ..examples };
+        let plain = OpenApiSpec::from_help(&miniature(), "1.0.0").unwrap();
+        assert!(plain.paths["/lol-lobby/v2/lobby"]["post"]["requestBody"]["content"]["application/json"].get("example").is_none());
+        assert_eq!(sidecar.collect(&plain)["PostLolLobbyV2Lobby"], example);
+    }
+
+    #[test]
+    fn test_seeded_determinism() {
+        use crate::{ options::SchemaOptions, report::SchemaReport, tests::miniature };
+
+        let options = SchemaOptions { request_examples: Some(RequestExamples::default()), ..Default::default() };
+        let generate = || {
+            let spec = OpenApiSpec::from_help_with(&miniature(), "1.0.0", &options, &mut SchemaReport::default()).unwrap();
+            serde_json::to_string_pretty(&spec).unwrap()
+        };
+        assert_eq!(generate(), generate());
+
+        let spec = OpenApiSpec::from_help(&miniature(), "1.0.0").unwrap();
+        let examples = RequestExamples { mode: ExampleMode::Sidecar, ..Default::default() };
+        assert_eq!(examples.collect(&spec), examples.collect(&spec));
+        assert_ne!(examples.collect(&spec)["PostLolLobbyV2Lobby"]["queueId"], 0);
+    }
+
+    #[test]
+    fn test_seeded_variation() {
+        let components = components(json!({
+            "Summoner": {
+                "type": "object",
+                "properties": {
+                    "displayName": { "type": "string" },
+                    "gameName": { "type": "string" },
+                    "summonerId": { "type": "integer" },
+                    "accountId": { "type": "integer" },
+                    "puuid": { "type": "string" },
+                }
+            },
+        }));
+        let value = example_with(&reference("Summoner"), &components, 4, ValueProvider::Seeded, "GetSummoner");
+        assert_ne!(value["displayName"], value["gameName"]);
+        assert_ne!(value["summonerId"], value["accountId"]);
+        assert!(value["summonerId"].as_u64().unwrap() >= 100_000);
+        assert_eq!(value["puuid"].as_str().unwrap().len(), 36);
+        assert!(WORDS.iter().any(|word| value["displayName"].as_str().unwrap().starts_with(word)));
+
+        // Another operation gets other values for the same schema.
+        let other = example_with(&reference("Summoner"), &components, 4, ValueProvider::Seeded, "PutSummoner");
+        assert_ne!(value, other);
+        assert_ne!(seed("GetSummoner", "puuid"), seed("GetSummoner", "accountId"));
+    }
+
+    #[test]
+    fn test_seeded_timestamps() {
+        let components = components(json!({
+            "Game": {
+                "type": "object",
+                "properties": {
+                    "createdAt": { "type": "string", "format": "date-time" },
+                    "startDate": { "type": "string" },
+                }
+            },
+        }));
+        let value = example_with(&reference("Game"), &components, 4, ValueProvider::Seeded, "GetGame");
+        for field in ["createdAt", "startDate"] {
+            let timestamp = value[field].as_str().unwrap();
+            assert!(timestamp.starts_with(&format!("{SEEDED_EPOCH_DATE}T")), "{timestamp}");
+            assert!(timestamp.ends_with('Z') && timestamp.len() == 20, "{timestamp}");
+        }
+    }
+}
@@ -0,0 +1,347 @@
+//! Why an operation looks the way it does.
+//!
+//! [`explain`] runs the conversion following one operation
+//! and lists every stage that changed it, with the fields before and after.
+//! Patch sets applied afterwards are added with [`ExplainTrace::apply_patches`].
+//! Normal runs trace nothing: the tracer is off and each stage costs a branch.
+
+use std::{ collections::BTreeSet, fmt };
+
+use serde::Serialize;
+use serde_json::{ Map, Value };
+
+use crate::{
+    caution::Caution,
+    convert::{ method_fallback, method_of },
+    error::Error,
+    help::ExtendedHelp,
+    openapi::OpenApiSpec,
+    options::SchemaOptions,
+    patchset::{ PatchOp, PatchSet },
+    report::{ ReportEntry, SchemaReport },
+    stability::X_STABILITY,
+    workflow::WORKFLOW_TAG_PREFIX,
+};
+
+/// A stage that can change an operation, in pipeline order.
+#[derive(Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum Stage {
+    Convert,
+    Sanitize,
+    Int64AsString,
+    Inline,
+    Envelopes,
+    Since,
+    Slugs,
+    Caution,
+    Stability,
+    Servers,
+    Workflows,
+    Examples,
+    Samples,
+    Renames,
+    Usage,
+    Patch,
+}
+
+/// A top-level field of the operation that a step changed. `None` is absent.
+#[derive(Serialize, Debug, Clone, PartialEq)]
+pub struct FieldChange {
+    pub field: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub before: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub after: Option<Value>,
+}
+
+#[derive(Serialize, Debug, Clone, PartialEq)]
+pub struct ExplainStep {
+    pub stage: Stage,
+    /// What the stage decided, e.g. `classified as danger: Spends RP`.
+    pub detail: String,
+    pub changes: Vec<FieldChange>,
+}
+
+/// Every step that touched one operation, from its help endpoint to what was emitted.
+#[derive(Serialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ExplainTrace {
+    pub operation_id: String,
+    /// The help endpoint it's converted from.
+    pub source: Value,
+    /// Where it was emitted, unless it was skipped.
+    pub path: Option<String>,
+    pub method: Option<String>,
+    pub steps: Vec<ExplainStep>,
+    /// What the report says about the endpoint, e.g. why it was skipped.
+    pub notes: Vec<ReportEntry>,
+    pub emitted: Option<Value>,
+}
+
+/// An operationId no help endpoint has.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UnknownOperation {
+    pub operation_id: String,
+}
+
+impl fmt::Display for UnknownOperation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "no endpoint named {} in the help", self.operation_id)
+    }
+}
+
+impl std::error::Error for UnknownOperation {}
+
+/// Convert `help` like [`OpenApiSpec::from_help_with`] and trace `operation_id` through it.
+pub fn explain(
+    help: &ExtendedHelp,
+    version: impl Into<String>,
+    options: &SchemaOptions,
+    operation_id: &str
+) -> Result<ExplainTrace, Error> {
+    let Some(endpoint) = help.endpoints.iter().find(|endpoint| endpoint.info.name == operation_id) else {
+        return Err(UnknownOperation { operation_id: operation_id.to_string() }.into());
+    };
+    let reason = method_fallback(endpoint).unwrap_or_else(|| "method from the help".to_string());
+    let mut tracer = Tracer {
+        trace: Some(ExplainTrace {
+            operation_id: operation_id.to_string(),
+            source: serde_json::to_value(endpoint)?,
+            path: None,
+            method: None,
+            steps: Vec::new(),
+            notes: Vec::new(),
+            emitted: None,
+        }),
+        method: (method_of(endpoint).to_uppercase(), reason),
+    };
+    let mut report = SchemaReport::default();
+    OpenApiSpec::from_help_traced(help, version, options, &mut report, &mut tracer)?;
+
+    let mut trace = tracer.trace.take().expect("tracer is on");
+    trace.notes = report.entries
+        .into_iter()
+        .filter(|entry| entry.target == operation_id)
+        .collect();
+    Ok(trace)
+}
+
+impl ExplainTrace {
+    /// Apply the ops of a patch set that reach this operation, a step each.
+    /// `name` says where the patches came from, e.g. their file.
+    pub fn apply_patches(&mut self, name: &str, patches: &PatchSet) -> Result<(), Error> {
+        let (Some(path), Some(method)) = (self.path.clone(), self.method.clone()) else {
+            return Ok(());
+        };
+        let target = format!("paths.{path}.{method}");
+        for op in patches.ops.iter().filter(|op| reaches(op.path(), &target)) {
+            let mut operations = Map::new();
+            if let Some(emitted) = self.emitted.clone() {
+                operations.insert(method.clone(), emitted);
+            }
+            let mut spec = serde_json::json!({ "paths": { path.as_str(): operations } });
+            PatchSet { ops: vec![op.clone()] }.apply(&mut spec)?;
+
+            let after = spec["paths"].get(&path).and_then(|item| item.get(&method)).cloned();
+            let verb = match op {
+                PatchOp::Set { .. } => "set",
+                PatchOp::Remove { .. } => "remove",
+            };
+            self.record(Stage::Patch, format!("{name}: {verb} {}", op.path()), after);
+        }
+        Ok(())
+    }
+
+    /// Add a step if it changed something. Conversion and patches are always listed.
+    fn record(&mut self, stage: Stage, detail: String, after: Option<Value>) {
+        let changes = changes(self.emitted.as_ref(), after.as_ref());
+        if changes.is_empty() && stage != Stage::Convert && stage != Stage::Patch {
+            return;
+        }
+        self.steps.push(ExplainStep { stage, detail, changes });
+        self.emitted = after;
+    }
+}
+
+/// Whether a patch at `path` can change the operation at `target`.
+fn reaches(path: &str, target: &str) -> bool {
+    let within = |inner: &str, outer: &str| inner == outer || inner.starts_with(&format!("{outer}."));
+    within(path, target) || within(target, path)
+}
+
+/// The top-level fields that differ, in name order.
+fn changes(before: Option<&Value>, after: Option<&Value>) -> Vec<FieldChange> {
+    let empty = Map::new();
+    let before = before.and_then(Value::as_object).unwrap_or(&empty);
+    let after = after.and_then(Value::as_object).unwrap_or(&empty);
+    before.keys()
+        .chain(after.keys())
+        .collect::<BTreeSet<&String>>()
+        .into_iter()
+        .filter(|field| before.get(*field) != after.get(*field))
+        .map(|field| FieldChange { field: field.clone(), before: before.get(field).cloned(), after: after.get(field).cloned() })
+        .collect()
+}
+
+/// Shown the spec after every conversion stage, keeping the steps that
+/// changed one operation.
+pub(crate) struct Tracer {
+    trace: Option<ExplainTrace>,
+    /// The converted method and why, for the first step.
+    method: (String, String),
+}
+
+impl Tracer {
+    /// Traces nothing.
+    pub(crate) fn off() -> Self {
+        Self { trace: None, method: Default::default() }
+    }
+
+    pub(crate) fn step(&mut self, stage: Stage, spec: &OpenApiSpec) {
+        let Some(trace) = self.trace.as_mut() else {
+            return;
+        };
+        let found = find(spec, &trace.operation_id);
+        if stage == Stage::Convert && let Some((path, method, _)) = found.as_ref() {
+            trace.path = Some(path.to_string());
+            trace.method = Some(method.to_string());
+        }
+        let after = found.map(|(_, _, operation)| operation.clone());
+        let detail = match (stage, after.as_ref()) {
+            (Stage::Convert, Some(_)) => {
+                let (method, reason) = &self.method;
+                format!("{method} {}; {reason}", trace.path.as_deref().unwrap_or_default())
+            }
+            (Stage::Convert, None) => "not emitted; see the notes".to_string(),
+            (Stage::Caution, Some(operation)) => match Caution::of(operation) {
+                Some(caution) => {
+                    let level = serde_json::to_value(caution.level).unwrap_or_default();
+                    format!("classified as {}: {}", level.as_str().unwrap_or_default(), caution.reason)
+                }
+                None => "classified as safe".to_string(),
+            },
+            (Stage::Stability, Some(operation)) => {
+                let stability = operation[X_STABILITY].as_str().unwrap_or_default();
+                format!("marked {stability}")
+            }
+            (Stage::Workflows, Some(operation)) => {
+                let workflows = operation["tags"]
+                    .as_array()
+                    .into_iter()
+                    .flatten()
+                    .filter_map(Value::as_str)
+                    .filter(|tag| tag.starts_with(WORKFLOW_TAG_PREFIX))
+                    .collect::<Vec<&str>>();
+                format!("tagged {}", workflows.join(", "))
+            }
+            _ => description(stage).to_string(),
+        };
+        trace.record(stage, detail, after);
+    }
+}
+
+fn description(stage: Stage) -> &'static str {
+    match stage {
+        Stage::Convert => "converted from the help",
+        Stage::Sanitize => "help text sanitized",
+        Stage::Int64AsString => "64-bit integers written as strings",
+        Stage::Inline => "small components inlined",
+        Stage::Envelopes => "response envelopes shared",
+        Stage::Since => "annotated with the version it appeared in",
+        Stage::Slugs => "given a stable slug",
+        Stage::Caution => "classified",
+        Stage::Stability => "given a stability",
+        Stage::Servers => "given its servers",
+        Stage::Workflows => "tagged with workflows",
+        Stage::Examples => "given request examples",
+        Stage::Samples => "given code samples",
+        Stage::Renames => "components renamed",
+        Stage::Usage => "annotated with usage",
+        Stage::Patch => "patched",
+    }
+}
+
+/// The path, method, and operation with this operationId.
+fn find<'a>(spec: &'a OpenApiSpec, operation_id: &str) -> Option<(&'a String, &'a String, &'a Value)> {
+    spec.paths
+        .iter()
+        .filter_map(|(path, item)| Some((path, item.as_object()?)))
+        .flat_map(|(path, item)| item.iter().map(move |(method, operation)| (path, method, operation)))
+        .find(|(_, _, operation)| operation["operationId"] == operation_id)
+}
+
+#[cfg(test)]
+mod test {
+    use serde_json::json;
+
+    use super::*;
+    use crate::{ caution::{ CautionLevel, CautionRule, CautionRules }, tests::miniature };
+
+    fn options() -> SchemaOptions {
+        SchemaOptions {
+            caution: Some(CautionRules {
+                overrides: vec![CautionRule::new(Some("POST"), "/lol-lobby", CautionLevel::Warning, "Leaves the current lobby")],
+            }),
+            workflows: Some(serde_json::from_value(json!({ "Lobby": { "paths": ["/lol-lobby"] } })).unwrap()),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_explain() {
+        let mut trace = explain(&miniature(), "1.0.0", &options(), "PostLolLobbyV2Lobby").unwrap();
+        assert_eq!(trace.path.as_deref(), Some("/lol-lobby/v2/lobby"));
+        assert_eq!(trace.method.as_deref(), Some("post"));
+        assert_eq!(trace.source["method"], "POST");
+
+        let rename = PatchSet {
+            ops: vec![
+                PatchOp::Set { path: "paths./lol-lobby/v2/lobby.post.summary".to_string(), value: json!("Create a lobby") },
+                // Another operation's, so not part of the trace.
+                PatchOp::Remove { path: "paths./lol-lobby/v2/lobby.delete".to_string() },
+            ],
+        };
+        let rewrite = PatchSet {
+            ops: vec![PatchOp::Set { path: "paths./lol-lobby/v2/lobby.post.summary".to_string(), value: json!("Make a lobby") }],
+        };
+        trace.apply_patches("rename.json", &rename).unwrap();
+        trace.apply_patches("rewrite.json", &rewrite).unwrap();
+
+        let steps = trace.steps.iter().map(|step| (step.stage, step.detail.as_str())).collect::<Vec<(Stage, &str)>>();
+        assert_eq!(steps, [
+            (Stage::Convert, "POST /lol-lobby/v2/lobby; method from the help"),
+            (Stage::Caution, "classified as warning: Leaves the current lobby"),
+            (Stage::Workflows, "tagged Workflow: Lobby"),
+            (Stage::Patch, "rename.json: set paths./lol-lobby/v2/lobby.post.summary"),
+            (Stage::Patch, "rewrite.json: set paths./lol-lobby/v2/lobby.post.summary"),
+        ]);
+
+        let workflows = &trace.steps[2].changes;
+        assert_eq!(workflows.len(), 1);
+        assert_eq!(workflows[0].field, "tags");
+        assert_eq!(workflows[0].before, Some(json!(["plugins", "lol-lobby"])));
+        assert_eq!(workflows[0].after, Some(json!(["plugins", "lol-lobby", "Workflow: Lobby"])));
+
+        let summaries = trace.steps[3..]
+            .iter()
+            .map(|step| (step.changes[0].before.clone(), step.changes[0].after.clone()))
+            .collect::<Vec<_>>();
+        assert_eq!(summaries, [
+            (None, Some(json!("Create a lobby"))),
+            (Some(json!("Create a lobby")), Some(json!("Make a lobby"))),
+        ]);
+        assert_eq!(trace.emitted.as_ref().unwrap()["summary"], "Make a lobby");
+        assert_eq!(trace.emitted.as_ref().unwrap()["x-caution"]["level"], "warning");
+
+        // The trace follows the conversion exactly.
+        let spec = OpenApiSpec::from_help_with(&miniature(), "1.0.0", &options(), &mut SchemaReport::default()).unwrap();
+        assert_eq!(trace.steps[2].changes[0].after.as_ref(), spec.paths["/lol-lobby/v2/lobby"]["post"].get("tags"));
+    }
+
+    #[test]
+    fn test_explain_unknown() {
+        let error = explain(&miniature(), "1.0.0", &SchemaOptions::default(), "GetNothing").unwrap_err();
+        assert!(matches!(error, Error::Explain(UnknownOperation { ref operation_id }) if operation_id == "GetNothing"));
+    }
+}
@@ -0,0 +1,301 @@
+//! Exports of a generated spec into formats other tools import directly.
+
+use std::collections::BTreeMap;
+
+use serde_json::{ Map, Value, json };
+
+use crate::{ convert::PLUGINS_TAG, example::example, openapi::OpenApiSpec };
+
+/// Version of the Postman Collection format [`postman`] writes.
+pub const POSTMAN_VERSION: &str = "2.1.0";
+
+const POSTMAN_SCHEMA: &str = "https://schema.getpostman.com/json/collection/v2.1.0/collection.json";
+
+/// Folder for operations without a tag.
+const UNTAGGED_FOLDER: &str = "default";
+
+#[derive(Debug, Clone)]
+pub struct PostmanOptions {
+    /// Collection and environment name.
+    pub name: String,
+    /// User name for basic auth; the password is the `{{token}}` variable.
+    pub username: String,
+}
+
+impl Default for PostmanOptions {
+    fn default() -> Self {
+        Self { name: "LCU".to_string(), username: "riot".to_string() }
+    }
+}
+
+/// A Postman Collection v2.1 with a folder per tag and a request per operation.
+///
+/// Requests go to `{{baseUrl}}` and authenticate with collection-level basic
+/// auth, so only the `port` and `token` variables need filling in, either on the
+/// collection or through [`postman_environment`].
+pub fn postman(spec: &OpenApiSpec, opts: &PostmanOptions) -> Value {
+    let schemas = spec.components.get("schemas").and_then(Value::as_object);
+
+    let mut paths = spec.paths.iter().collect::<Vec<(&String, &Value)>>();
+    paths.sort_by_key(|(path, _)| *path);
+
+    let mut folders = BTreeMap::<String, Vec<Value>>::new();
+    for (path, path_item) in paths {
+        let Some(path_item) = path_item.as_object() else {
+            continue;
+        };
+        let mut operations = path_item.iter().collect::<Vec<(&String, &Value)>>();
+        operations.sort_by_key(|(method, _)| *method);
+
+        for (method, operation) in operations {
+            let folder = operation["tags"]
+                .as_array()
+                .into_iter()
+                .flatten()
+                .filter_map(Value::as_str)
+                .find(|tag| *tag != PLUGINS_TAG)
+                .unwrap_or(UNTAGGED_FOLDER);
+            folders
+                .entry(folder.to_string())
+                .or_default()
+                .push(postman_request(path, method, operation, schemas));
+        }
+    }
+
+    let items = folders
+        .into_iter()
+        .map(|(name, mut requests)| {
+            dedup_names(&mut requests);
+            json!({ "name": name, "item": requests })
+        })
+        .collect::<Vec<Value>>();
+
+    json!({
+        "info": {
+            "name": opts.name,
+            "description": spec.info.description,
+            "version": spec.info.version,
+            "schema": POSTMAN_SCHEMA,
+        },
+        "auth": {
+            "type": "basic",
+            "basic": [
+                { "key": "username", "value": opts.username, "type": "string" },
+                { "key": "password", "value": "{{token}}", "type": "string" },
+            ],
+        },
+        "variable": [
+            { "key": "baseUrl", "value": "https://127.0.0.1:{{port}}" },
+            { "key": "port", "value": "" },
+            { "key": "token", "value": "" },
+        ],
+        "item": items,
+    })
+}
+
+/// Environment template holding the variables the collection expects.
+pub fn postman_environment(opts: &PostmanOptions) -> Value {
+    let values = ["port", "token"]
+        .iter()
+        .map(|key| json!({ "key": key, "value": "", "enabled": true }))
+        .collect::<Vec<Value>>();
+    json!({ "name": opts.name, "values": values })
+}
+
+fn postman_request(
+    path: &str,
+    method: &str,
+    operation: &Value,
+    schemas: Option<&Map<String, Value>>
+) -> Value {
+    let parameters = operation["parameters"].as_array().map(Vec::as_slice).unwrap_or_default();
+    let in_location = |location: &'static str| {
+        parameters.iter().filter(move |p| p["in"] == location)
+    };
+
+    let segments = path
+        .split('/')
+        .filter(|segment| !segment.is_empty())
+        .map(|segment| {
+            match segment.strip_prefix('{').and_then(|s| s.strip_suffix('}')) {
+                Some(name) => format!(":{name}"),
+                None => segment.to_string(),
+            }
+        })
+        .collect::<Vec<String>>();
+    let variables = in_location("path")
+        .map(|p| json!({ "key": p["name"], "value": "", "description": description(p) }))
+        .collect::<Vec<Value>>();
+    let query = in_location("query")
+        .map(|p| {
+            json!({
+                "key": p["name"],
+                "value": "",
+                "description": description(p),
+                "disabled": p["required"] != true,
+            })
+        })
+        .collect::<Vec<Value>>();
+
+    let mut raw = format!("{{{{baseUrl}}}}/{}", segments.join("/"));
+    let enabled_query = query
+        .iter()
+        .filter(|q| q["disabled"] == false)
+        .filter_map(|q| q["key"].as_str())
+        .map(|key| format!("{key}="))
+        .collect::<Vec<String>>();
+    if !enabled_query.is_empty() {
+        raw = format!("{raw}?{}", enabled_query.join("&"));
+    }
+
+    let mut url = json!({ "raw": raw, "host": ["{{baseUrl}}"], "path": segments });
+    if !variables.is_empty() {
+        url["variable"] = Value::Array(variables);
+    }
+    if !query.is_empty() {
+        url["query"] = Value::Array(query);
+    }
+
+    let mut request =
+        json!({
+        "method": method.to_uppercase(),
+        "header": [],
+        "url": url,
+    });
+    if let Some(description) = operation.get("description") {
+        request["description"] = description.clone();
+    }
+    if let Some(schema) = operation.pointer("/requestBody/content/application~1json/schema") {
+        let skeleton = example(schema, schemas);
+        request["header"] = json!([{ "key": "Content-Type", "value": "application/json" }]);
+        request["body"] = json!({
+            "mode": "raw",
+            "raw": serde_json::to_string_pretty(&skeleton).unwrap_or_default(),
+            "options": { "raw": { "language": "json" } },
+        });
+    } else if operation.pointer("/requestBody/content/text~1plain").is_some() {
+        request["header"] = json!([{ "key": "Content-Type", "value": "text/plain" }]);
+        request["body"] = json!({ "mode": "raw", "raw": "" });
+    }
+
+    json!({ "name": operation["operationId"], "request": request })
+}
+
+fn description(parameter: &Value) -> Value {
+    parameter.get("description").cloned().unwrap_or(Value::String(String::new()))
+}
+
+/// Suffix clashing request names within a folder with their method and path.
+fn dedup_names(requests: &mut [Value]) {
+    let mut counts = BTreeMap::<String, usize>::new();
+    for request in requests.iter() {
+        *counts.entry(request["name"].as_str().unwrap_or_default().to_string()).or_default() += 1;
+    }
+    for request in requests.iter_mut() {
+        let name = request["name"].as_str().unwrap_or_default().to_string();
+        if counts[&name] > 1 {
+            let method = request["request"]["method"].as_str().unwrap_or_default().to_string();
+            let path = request["request"]["url"]["path"]
+                .as_array()
+                .into_iter()
+                .flatten()
+                .filter_map(Value::as_str)
+                .collect::<Vec<&str>>()
+                .join("/");
+            request["name"] = Value::String(format!("{name} ({method} /{path})"));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::miniature;
+
+    fn collection() -> Value {
+        let spec = OpenApiSpec::from_help(&miniature(), "1.0.0").unwrap();
+        postman(&spec, &PostmanOptions::default())
+    }
+
+    fn request<'a>(collection: &'a Value, folder: &str, name: &str) -> &'a Value {
+        let folder = collection["item"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .find(|f| f["name"] == folder)
+            .unwrap();
+        folder["item"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .find(|r| r["name"] == name)
+            .unwrap()
+    }
+
+    #[test]
+    fn test_postman_collection() {
+        let collection = collection();
+        assert_eq!(collection["info"]["schema"], POSTMAN_SCHEMA);
+        assert_eq!(collection["auth"]["basic"][1]["value"], "{{token}}");
+
+        let folders = collection["item"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|f| f["name"].as_str().unwrap())
+            .collect::<Vec<&str>>();
+        assert_eq!(folders, ["builtin", "lol-lobby", "lol-summoner"]);
+
+        // Deterministic across runs.
+        assert_eq!(collection, self::collection());
+    }
+
+    #[test]
+    fn test_postman_get_with_params() {
+        let collection = collection();
+        let by_id = request(&collection, "lol-summoner", "GetLolSummonerV1SummonersById");
+        assert_eq!(
+            by_id["request"],
+            json!({
+                "method": "GET",
+                "header": [],
+                "description": "Get a summoner by id.",
+                "url": {
+                    "raw": "{{baseUrl}}/lol-summoner/v1/summoners/:id",
+                    "host": ["{{baseUrl}}"],
+                    "path": ["lol-summoner", "v1", "summoners", ":id"],
+                    "variable": [{ "key": "id", "value": "", "description": "" }],
+                },
+            })
+        );
+
+        let by_name = request(&collection, "lol-summoner", "GetLolSummonerV1Summoners");
+        assert_eq!(by_name["request"]["url"]["raw"], "{{baseUrl}}/lol-summoner/v1/summoners?name=");
+        assert_eq!(by_name["request"]["url"]["query"][0]["disabled"], false);
+    }
+
+    #[test]
+    fn test_postman_post_with_body() {
+        let collection = collection();
+        let post = request(&collection, "lol-lobby", "PostLolLobbyV2Lobby");
+        assert_eq!(post["request"]["method"], "POST");
+        assert_eq!(post["request"]["header"][0]["value"], "application/json");
+        let body: Value = serde_json::from_str(post["request"]["body"]["raw"].as_str().unwrap()).unwrap();
+        assert_eq!(body, json!({ "isCustom": false, "queueId": 0 }));
+    }
+
+    #[test]
+    fn test_dedup_names() {
+        let mut requests = vec![
+            json!({ "name": "Same", "request": { "method": "GET", "url": { "path": ["a"] } } }),
+            json!({ "name": "Same", "request": { "method": "PUT", "url": { "path": ["a"] } } }),
+            json!({ "name": "Other", "request": { "method": "GET", "url": { "path": ["b"] } } }),
+        ];
+        dedup_names(&mut requests);
+        let names = requests
+            .iter()
+            .map(|r| r["name"].as_str().unwrap())
+            .collect::<Vec<&str>>();
+        assert_eq!(names, ["Same (GET /a)", "Same (PUT /a)", "Other"]);
+    }
+}
@@ -0,0 +1,186 @@
+//! Places where conversion guesses because the help data is missing
+//! something, and [`SchemaOptions::strict`](crate::options::SchemaOptions::strict)
+//! mode, which refuses to guess.
+
+use std::fmt;
+
+#[cfg(feature = "core")]
+use crate::{ error::Error, report::{ ReportKind, SchemaReport } };
+
+/// Where conversion fell back to a default.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum FallbackSite {
+    /// A missing or unknown HTTP method was emitted as GET.
+    Method,
+    /// A type the help data doesn't define became a generic object.
+    Schema,
+    /// An endpoint without tags, which viewers group by path segment.
+    Tags,
+    /// A path variable without a matching argument was typed as a string.
+    PathParam,
+}
+
+impl FallbackSite {
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Method => "method",
+            Self::Schema => "schema",
+            Self::Tags => "tags",
+            Self::PathParam => "path parameter",
+        }
+    }
+}
+
+impl fmt::Display for FallbackSite {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+/// Help data that can't be converted as-is.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ParseError {
+    /// Strict mode hit a fallback. `detail` says what would have been done.
+    StrictFallback { site: FallbackSite, target: String, detail: String },
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::StrictFallback { site, target, detail } => {
+                write!(f, "strict mode: {site} fallback for {target}: {detail}")
+            }
+        }
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+/// A fallback taken while converting one target.
+#[cfg(feature = "core")]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct Fallback {
+    pub site: FallbackSite,
+    pub detail: String,
+}
+
+/// Record `fallbacks` of `target` in the report, or fail on the first one in
+/// strict mode.
+#[cfg(feature = "core")]
+pub(crate) fn record_fallbacks(
+    target: &str,
+    fallbacks: impl IntoIterator<Item = Fallback>,
+    strict: bool,
+    report: &mut SchemaReport
+) -> Result<(), Error> {
+    for Fallback { site, detail } in fallbacks {
+        if strict {
+            return Err(ParseError::StrictFallback { site, target: target.to_string(), detail }.into());
+        }
+        report.push(ReportKind::Fallback, target, detail);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{ error::Error, help::{ Endpoint, ExtendedHelp }, openapi::OpenApiSpec, options::SchemaOptions, report::{ ReportKind, SchemaReport }, tests::miniature };
+
+    use super::*;
+
+    fn endpoint<'a>(help: &'a mut ExtendedHelp, name: &str) -> &'a mut Endpoint {
+        help.endpoints.iter_mut().find(|e| e.info.name == name).unwrap()
+    }
+
+    /// Convert `help` leniently and strictly. The lenient run must record
+    /// exactly one fallback, which the strict run must fail on.
+    fn check(help: ExtendedHelp, site: FallbackSite, target: &str, detail: &str) {
+        let mut report = SchemaReport::default();
+        OpenApiSpec::from_help_with(&help, "1.0.0", &SchemaOptions::default(), &mut report).unwrap();
+        let fallbacks = report.of_kind(ReportKind::Fallback).collect::<Vec<_>>();
+        assert_eq!(fallbacks.len(), 1, "{fallbacks:?}");
+        assert_eq!(fallbacks[0].target, target);
+        assert_eq!(fallbacks[0].detail, detail);
+
+        let options = SchemaOptions { strict: true, ..Default::default() };
+        let err = OpenApiSpec::from_help_with(&help, "1.0.0", &options, &mut SchemaReport::default()).unwrap_err();
+        let Error::Parse(err) = err else { panic!("expected a parse error, got {err}") };
+        assert_eq!(
+            err,
+            ParseError::StrictFallback { site, target: target.to_string(), detail: detail.to_string() }
+        );
+    }
+
+    #[test]
+    fn test_none_by_default() {
+        let mut report = SchemaReport::default();
+        let options = SchemaOptions { strict: true, ..Default::default() };
+        OpenApiSpec::from_help_with(&miniature(), "1.0.0", &options, &mut report).unwrap();
+        assert!(report.of_kind(ReportKind::Fallback).next().is_none());
+    }
+
+    #[test]
+    fn test_method() {
+        let mut help = miniature();
+        endpoint(&mut help, "DeleteLolLobbyV2Lobby").method = Some("FETCH".to_string());
+        check(help, FallbackSite::Method, "DeleteLolLobbyV2Lobby", "method FETCH isn't an HTTP method; emitted as GET");
+
+        let mut help = miniature();
+        endpoint(&mut help, "DeleteLolLobbyV2Lobby").method = None;
+        check(help, FallbackSite::Method, "DeleteLolLobbyV2Lobby", "no method; emitted as GET");
+    }
+
+    #[test]
+    fn test_schema() {
+        let mut help = miniature();
+        let ty = help.types.iter_mut().find(|ty| ty.info.name == "LolSummonerSummonerRerollPoints").unwrap();
+        ty.fields[0].ty.ty = "LolMissingType".to_string();
+        check(
+            help,
+            FallbackSite::Schema,
+            "LolSummonerSummonerRerollPoints",
+            "type LolMissingType isn't defined; emitted as a generic object"
+        );
+
+        let mut help = miniature();
+        help.events[1].ty.ty = "LolMissingType".to_string();
+        check(
+            help,
+            FallbackSite::Schema,
+            "OnJsonApiEvent_lol-summoner_v1_current-summoner",
+            "type LolMissingType isn't defined; emitted as a generic object"
+        );
+    }
+
+    #[test]
+    fn test_tags() {
+        let mut help = miniature();
+        endpoint(&mut help, "DeleteLolLobbyV2Lobby").tags.clear();
+        check(help, FallbackSite::Tags, "DeleteLolLobbyV2Lobby", "no tags; viewers group it by its path");
+    }
+
+    #[test]
+    fn test_path_param() {
+        let mut help = miniature();
+        endpoint(&mut help, "GetLolSummonerV1SummonersById").arguments.clear();
+        check(
+            help,
+            FallbackSite::PathParam,
+            "GetLolSummonerV1SummonersById",
+            "path parameter id has no matching argument; typed as string"
+        );
+    }
+
+    #[test]
+    fn test_display() {
+        let err = ParseError::StrictFallback {
+            site: FallbackSite::PathParam,
+            target: "GetLolSummonerV1SummonersById".to_string(),
+            detail: "path parameter id has no matching argument; typed as string".to_string(),
+        };
+        assert_eq!(
+            err.to_string(),
+            "strict mode: path parameter fallback for GetLolSummonerV1SummonersById: path parameter id has no matching argument; typed as string"
+        );
+    }
+}
@@ -0,0 +1,279 @@
+//! Spec fragments for endpoints that out-of-tree plugins add to the client.
+//!
+//! A fragment is the paths, components, and tags of a spec, without `info`,
+//! plus a namespace. When merged, the namespace is put in front of the names
+//! of the fragment's components and of the refs to them, so they can't
+//! collide with Riot's. Refs to components the fragment doesn't have point at
+//! the base spec's.
+
+use std::{ fmt, path::Path };
+
+use serde::{ Deserialize, Serialize };
+use serde_json::{ Map, Value };
+
+use crate::{ error::Error, openapi::{ Components, OpenApiSpec, Tag }, transform::Normalize };
+
+/// Prefix of refs to component schemas.
+const SCHEMA_REF: &str = "#/components/schemas/";
+
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+pub struct SpecFragment {
+    /// Put in front of the fragment's component names, e.g. `Blitz` makes
+    /// `Overlay` into `BlitzOverlay`.
+    pub namespace: String,
+    #[serde(default)]
+    pub paths: Map<String, Value>,
+    #[serde(default)]
+    pub components: Components,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<Tag>,
+}
+
+/// Why a fragment can't be merged into a spec.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FragmentError {
+    /// A fragment needs a namespace to keep its components apart.
+    NoNamespace,
+    /// A ref to a component neither the fragment nor the base spec has.
+    DanglingRef { namespace: String, location: String, reference: String },
+    /// An operation or namespaced component the base spec already has.
+    Collision { namespace: String, location: String },
+}
+
+impl fmt::Display for FragmentError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::NoNamespace => write!(f, "fragment has an empty namespace"),
+            Self::DanglingRef { namespace, location, reference } => {
+                write!(f, "fragment {namespace}: {location} refers to {reference}, which neither the fragment nor the spec has")
+            }
+            Self::Collision { namespace, location } => {
+                write!(f, "fragment {namespace}: {location} is already in the spec")
+            }
+        }
+    }
+}
+
+impl std::error::Error for FragmentError {}
+
+impl SpecFragment {
+    /// Load a fragment from a JSON file.
+    pub fn from_path(path: impl AsRef<Path>) -> Result<Self, Error> {
+        Ok(serde_json::from_str(&std::fs::read_to_string(path)?)?)
+    }
+
+    fn schemas(&self) -> Option<&Map<String, Value>> {
+        self.components.get("schemas").and_then(Value::as_object)
+    }
+
+    fn namespaced(&self, name: &str) -> String {
+        format!("{}{name}", self.namespace)
+    }
+
+    /// Check that every ref resolves within the fragment or `base`, and that
+    /// nothing it adds is in `base` already.
+    pub fn validate(&self, base: &OpenApiSpec) -> Result<(), FragmentError> {
+        if self.namespace.is_empty() {
+            return Err(FragmentError::NoNamespace);
+        }
+        let base_schemas = base.components.get("schemas").and_then(Value::as_object);
+        let collision = |location: String| FragmentError::Collision { namespace: self.namespace.clone(), location };
+
+        for name in self.schemas().into_iter().flat_map(|schemas| schemas.keys()) {
+            let namespaced = self.namespaced(name);
+            if base_schemas.is_some_and(|schemas| schemas.contains_key(&namespaced)) {
+                return Err(collision(format!("components.schemas.{namespaced}")));
+            }
+        }
+        for (path, item) in self.paths.iter() {
+            let Some(base_item) = base.paths.get(path).and_then(Value::as_object) else {
+                continue;
+            };
+            for method in item.as_object().into_iter().flat_map(|item| item.keys()) {
+                if base_item.contains_key(method) {
+                    return Err(collision(format!("paths.{path}.{method}")));
+                }
+            }
+        }
+
+        let mut refs = Vec::new();
+        for (name, schema) in self.schemas().into_iter().flatten() {
+            collect_refs(schema, format!("components.schemas.{name}"), &mut refs);
+        }
+        for (path, item) in self.paths.iter() {
+            collect_refs(item, format!("paths.{path}"), &mut refs);
+        }
+        for (location, reference) in refs {
+            let name = reference.strip_prefix(SCHEMA_REF);
+            let own = name.is_some_and(|name| self.schemas().is_some_and(|schemas| schemas.contains_key(name)));
+            let riot = name.is_some_and(|name| base_schemas.is_some_and(|schemas| schemas.contains_key(name)));
+            if !own && !riot {
+                return Err(FragmentError::DanglingRef { namespace: self.namespace.clone(), location, reference });
+            }
+        }
+        Ok(())
+    }
+
+    /// The fragment with its components and the refs to them namespaced.
+    fn namespace_components(&self) -> Self {
+        let mut fragment = self.clone();
+        let own = self.schemas().map(|schemas| schemas.keys().cloned().collect::<Vec<String>>()).unwrap_or_default();
+        let rename = |value: &mut Value| {
+            rewrite_refs(value, &mut |reference| {
+                if let Some(name) = reference.strip_prefix(SCHEMA_REF) && own.iter().any(|own| own == name) {
+                    *reference = format!("{SCHEMA_REF}{}", self.namespaced(name));
+                }
+            });
+        };
+        if let Some(Value::Object(schemas)) = fragment.components.get_mut("schemas") {
+            *schemas = std::mem::take(schemas)
+                .into_iter()
+                .map(|(name, mut schema)| {
+                    rename(&mut schema);
+                    (self.namespaced(&name), schema)
+                })
+                .collect();
+        }
+        fragment.paths.values_mut().for_each(rename);
+        fragment
+    }
+}
+
+impl OpenApiSpec {
+    /// Add a fragment's paths, components, and tags after checking it with
+    /// [`SpecFragment::validate`]. Nothing is added if the check fails.
+    /// Fragments are written by hand or by other tools, so their schemas are
+    /// put in our forms with [`Normalize`].
+    pub fn merge_fragment(&mut self, fragment: &SpecFragment) -> Result<(), FragmentError> {
+        fragment.validate(self)?;
+        let fragment = fragment.namespace_components();
+
+        for (section, values) in fragment.components {
+            let Value::Object(values) = values else {
+                continue;
+            };
+            let section = self.components.entry(section).or_insert_with(|| Value::Object(Map::new()));
+            if let Value::Object(section) = section {
+                section.extend(values);
+            }
+        }
+        for (path, item) in fragment.paths {
+            match (self.paths.get_mut(&path), item) {
+                (Some(Value::Object(existing)), Value::Object(item)) => existing.extend(item),
+                (_, item) => {
+                    self.paths.insert(path, item);
+                }
+            }
+        }
+        for tag in fragment.tags {
+            if !self.tags.iter().any(|existing| existing.name == tag.name) {
+                self.tags.push(tag);
+            }
+        }
+        Normalize.apply(self);
+        Ok(())
+    }
+}
+
+/// Every `$ref` under `value`, with the dot path of the object holding it.
+fn collect_refs(value: &Value, location: String, refs: &mut Vec<(String, String)>) {
+    match value {
+        Value::Object(object) => {
+            if let Some(Value::String(reference)) = object.get("$ref") {
+                refs.push((location.clone(), reference.clone()));
+            }
+            for (key, value) in object.iter() {
+                collect_refs(value, format!("{location}.{key}"), refs);
+            }
+        }
+        Value::Array(values) => {
+            for (i, value) in values.iter().enumerate() {
+                collect_refs(value, format!("{location}.{i}"), refs);
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Call `f` with every `$ref` under `value`, to change it in place.
+pub(crate) fn rewrite_refs(value: &mut Value, f: &mut impl FnMut(&mut String)) {
+    match value {
+        Value::Object(object) => {
+            if let Some(Value::String(reference)) = object.get_mut("$ref") {
+                f(reference);
+            }
+            object.values_mut().for_each(|value| rewrite_refs(value, f));
+        }
+        Value::Array(values) => values.iter_mut().for_each(|value| rewrite_refs(value, f)),
+        _ => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+    use crate::tests::miniature;
+
+    fn base() -> OpenApiSpec {
+        OpenApiSpec::from_help(&miniature(), "1.0.0").unwrap()
+    }
+
+    fn fixture() -> SpecFragment {
+        SpecFragment::from_path(poroshell_test_utils::fixtures::path("fragment.json")).unwrap()
+    }
+
+    #[test]
+    fn test_merge() {
+        let fragment = fixture();
+        assert_eq!(fragment.namespace, "Blitz");
+        let mut spec = base();
+        let riot = spec.components["schemas"]["LolSummonerSummoner"].clone();
+        spec.merge_fragment(&fragment).unwrap();
+
+        let schemas = spec.components["schemas"].as_object().unwrap();
+        assert!(schemas.contains_key("BlitzOverlay"));
+        assert!(!schemas.contains_key("Overlay"));
+        let overlay = &schemas["BlitzOverlay"]["properties"];
+        assert_eq!(overlay["position"]["$ref"], "#/components/schemas/BlitzOverlayPosition");
+        // Refs to Riot's components are left alone.
+        assert_eq!(overlay["summoner"]["$ref"], "#/components/schemas/LolSummonerSummonerRerollPoints");
+
+        // The fragment's own LolSummonerSummoner doesn't replace Riot's.
+        assert_eq!(schemas["LolSummonerSummoner"], riot);
+        assert!(schemas.contains_key("BlitzLolSummonerSummoner"));
+        let get = &spec.paths["/blitz/v1/summoner"]["get"];
+        assert_eq!(
+            get["responses"]["200"]["content"]["application/json"]["schema"]["$ref"],
+            "#/components/schemas/BlitzLolSummonerSummoner"
+        );
+        assert!(spec.tags.contains(&Tag::new("blitz")));
+    }
+
+    #[test]
+    fn test_rejected() {
+        let mut fragment = fixture();
+        fragment.components["schemas"]["Overlay"]["properties"]["theme"] = json!({ "$ref": "#/components/schemas/Theme" });
+        let mut spec = base();
+        let err = spec.merge_fragment(&fragment).unwrap_err();
+        assert_eq!(
+            err,
+            FragmentError::DanglingRef {
+                namespace: "Blitz".to_string(),
+                location: "components.schemas.Overlay.properties.theme".to_string(),
+                reference: "#/components/schemas/Theme".to_string(),
+            }
+        );
+        assert!(err.to_string().contains("refers to #/components/schemas/Theme"));
+        assert!(!spec.paths.contains_key("/blitz/v1/summoner"));
+
+        // Merging twice would add the same operations again.
+        let mut spec = base();
+        spec.merge_fragment(&fixture()).unwrap();
+        assert!(matches!(spec.merge_fragment(&fixture()), Err(FragmentError::Collision { .. })));
+
+        let fragment = SpecFragment { namespace: String::new(), ..fixture() };
+        assert_eq!(base().merge_fragment(&fragment), Err(FragmentError::NoNamespace));
+    }
+}
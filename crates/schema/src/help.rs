@@ -1,12 +1,29 @@
+use std::collections::BTreeMap;
+
 use fxhash::{ FxHashMap, FxHashSet };
 use serde::{ de::Visitor, ser::{ SerializeMap, SerializeSeq }, Deserialize, Serialize };
 
+use crate::{ error::Error, options::PRIVATE_API_MARKER, report::{ ReportKind, SchemaReport } };
+
 /// Constructed using multiple API calls to get all the types, endpoints, and events.
 #[derive(Serialize, Deserialize, Debug)]
 pub struct ExtendedHelp {
     pub types: Vec<Type>,
     pub endpoints: Vec<Endpoint>,
     pub events: Vec<Event>,
+    /// What happened while the help was collected. Not part of the saved JSON.
+    #[serde(skip)]
+    pub report: SchemaReport,
+}
+
+impl std::str::FromStr for ExtendedHelp {
+    type Err = Error;
+
+    /// Parse a previously saved `extended-help.json`, migrating it from an
+    /// older format if needed.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        crate::migrate::load(crate::migrate::Artifact::ExtendedHelp, s)
+    }
 }
 
 /// The base help returned from the LCU API.
@@ -17,13 +34,13 @@ pub struct Help {
     pub types: StringMap,
 }
 
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
 pub struct Info {
     pub name: String,
     pub description: String,
 }
 
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
 pub struct Event {
     #[serde(flatten)]
     pub info: Info,
@@ -50,6 +67,13 @@ pub struct Endpoint {
     pub path: Option<String>,
     #[serde(default)]
     pub path_params: Vec<String>,
+    /// Request body content type set by a patch, for endpoints that don't take JSON.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub request_content_type: Option<String>,
+    /// Where arguments go in the request, set by a patch, by argument name.
+    /// Arguments not listed are placed by the converter's heuristic.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub arg_locations: BTreeMap<String, ArgLocation>,
     #[serde(rename = "returns")]
     pub return_ty: DataType,
     #[serde(rename = "async", default, deserialize_with = "deserialize_bool_any")]
@@ -62,6 +86,16 @@ pub struct Endpoint {
     pub is_silent_override: bool,
 }
 
+/// Where an endpoint argument goes in the request.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum ArgLocation {
+    Path,
+    Query,
+    Header,
+    Body,
+}
+
 #[derive(Serialize, Deserialize, Debug)]
 #[serde(rename_all = "camelCase")]
 pub struct ConsoleEndpointInner {
@@ -71,6 +105,19 @@ pub struct ConsoleEndpointInner {
     pub url: Option<String>,
 }
 
+impl ConsoleEndpointInner {
+    /// Names of the `{...}` template variables in the URL, in order.
+    pub fn path_params(&self) -> Vec<String> {
+        let Some(url) = self.url.as_ref() else {
+            return Vec::new();
+        };
+        let reg = regex::Regex::new(r"\{(.*?)\}").expect("Path param regex should compile");
+        reg.captures_iter(url.as_str())
+            .map(|cap| cap[1].to_string())
+            .collect()
+    }
+}
+
 #[derive(Serialize, Deserialize, Debug)]
 #[serde(rename_all = "camelCase")]
 pub struct Argument {
@@ -94,6 +141,16 @@ pub struct Type {
     pub tags: Vec<String>,
 }
 
+impl Type {
+    /// Whether the LCU hides this type from its public API.
+    pub fn is_private(&self) -> bool {
+        self.info.description == PRIVATE_API_MARKER ||
+            self.fields
+                .iter()
+                .any(|f| f.ty.ty == PRIVATE_API_MARKER || f.ty.element_type == PRIVATE_API_MARKER)
+    }
+}
+
 #[derive(Serialize, Deserialize, Debug)]
 pub struct Value {
     pub name: String,
@@ -116,7 +173,30 @@ pub struct Field {
     pub ty: DataType,
 }
 
-#[derive(Serialize, Deserialize, Debug)]
+/// [`DataType::ty`] of a list of `element_type`.
+pub const VECTOR_TYPE: &str = "vector";
+/// [`DataType::ty`] of a string-keyed map of `element_type`.
+pub const MAP_TYPE: &str = "map";
+/// [`DataType::ty`] names the converter maps to a schema directly. Any other
+/// name, apart from [`VECTOR_TYPE`] and [`MAP_TYPE`], refers to a [`Type`].
+pub const PRIMITIVE_TYPES: [&str; 14] = [
+    "",
+    "object",
+    "bool",
+    "string",
+    "int8",
+    "int16",
+    "int32",
+    "int64",
+    "uint8",
+    "uint16",
+    "uint32",
+    "uint64",
+    "float",
+    "double",
+];
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
 #[serde(rename_all = "camelCase")]
 pub struct DataType {
     pub element_type: String,
@@ -124,6 +204,63 @@ pub struct DataType {
     pub ty: String,
 }
 
+/// Collapse events that share a name.
+///
+/// Identical duplicates are collapsed into one. Duplicates that differ are all kept, with
+/// the namespace appended to their name (`name:namespace`) so none of them silently wins.
+/// Both cases are recorded in the report. The order of first appearance is preserved.
+pub fn dedup_events(events: Vec<Event>, report: &mut SchemaReport) -> Vec<Event> {
+    let mut groups = Vec::<Vec<Event>>::new();
+    let mut index = FxHashMap::<String, usize>::default();
+    for event in events {
+        match index.get(&event.info.name) {
+            Some(&i) => groups[i].push(event),
+            None => {
+                index.insert(event.info.name.clone(), groups.len());
+                groups.push(vec![event]);
+            }
+        }
+    }
+
+    let mut deduped = Vec::new();
+    for group in groups {
+        // Each distinct event with how many copies of it the help listed.
+        let mut counted = Vec::<(Event, usize)>::new();
+        for event in group {
+            match counted.iter_mut().find(|(seen, _)| *seen == event) {
+                Some((_, count)) => *count += 1,
+                None => counted.push((event, 1)),
+            }
+        }
+        let name = counted[0].0.info.name.clone();
+        let mut distinct = Vec::with_capacity(counted.len());
+        for (event, count) in counted {
+            if count > 1 {
+                report.push(ReportKind::Merged, &name, format!("collapsed {count} identical events"));
+            }
+            distinct.push(event);
+        }
+        if distinct.len() == 1 {
+            deduped.extend(distinct);
+            continue;
+        }
+
+        let mut used = FxHashSet::<String>::default();
+        for mut event in distinct {
+            let mut renamed = format!("{name}:{}", event.namespace);
+            let mut n = 2;
+            while !used.insert(renamed.clone()) {
+                renamed = format!("{name}:{}:{n}", event.namespace);
+                n += 1;
+            }
+            report.push(ReportKind::Renamed, &name, format!("renamed to {renamed}"));
+            event.info.name = renamed;
+            deduped.push(event);
+        }
+    }
+    deduped
+}
+
 /// A helper type that will only deserialize the first item of a sequence.
 #[derive(Debug, Clone)]
 pub struct SeqFirst<T>(pub T);
@@ -199,13 +336,13 @@ impl StringMap {
 }
 
 impl Serialize for StringMap {
+    /// Entries are written sorted by key, since the maps behind them have no order.
     fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error> where S: serde::Serializer {
-        let mut map = serializer.serialize_map(Some(self.values.len() + self.empty.len()))?;
-        for (key, value) in &self.values {
-            map.serialize_entry(key, value)?;
-        }
-        for key in &self.empty {
-            map.serialize_entry(key, "")?;
+        let mut entries = self.iter().collect::<Vec<(&str, Option<&str>)>>();
+        entries.sort_unstable_by_key(|(key, _)| *key);
+        let mut map = serializer.serialize_map(Some(entries.len()))?;
+        for (key, value) in entries {
+            map.serialize_entry(key, value.unwrap_or_default())?;
         }
         map.end()
     }
@@ -300,13 +437,148 @@ fn deserialize_string_option<'de, D>(deserializer: D) -> Result<Option<String>,
     }
 }
 
+/// Splits a console URL into the host it names, if any, and its path.
+///
+/// Most console URLs are client-relative paths, but a few are absolute URIs
+/// like `https://127.0.0.1:2999/liveclientdata/allgamedata` or start with a
+/// host, like `localhost:2999/liveclientdata/allgamedata`. Without a scheme,
+/// a first segment with a `.` or `:` in it, or `localhost`, is taken as the
+/// host; LCU path segments have neither.
+pub fn split_console_url(url: &str) -> (Option<&str>, &str) {
+    let rest = match url.split_once("://") {
+        Some((scheme, rest)) if !scheme.is_empty() && !scheme.contains('/') => rest,
+        _ =>
+            match url.strip_prefix("//") {
+                Some(rest) => rest,
+                None if url.starts_with('/') => {
+                    return (None, url);
+                }
+                None => {
+                    let first = url.split('/').next().unwrap_or_default();
+                    if !(first.contains(['.', ':']) || first == "localhost") {
+                        return (None, url);
+                    }
+                    url
+                }
+            }
+    };
+    match rest.find('/') {
+        Some(index) => (Some(&rest[..index]), &rest[index..]),
+        None => (Some(rest), "/"),
+    }
+}
+
 fn deserialize_console_url<'de, D>(deserializer: D) -> Result<Option<String>, D::Error>
     where D: serde::Deserializer<'de>
 {
     let s: String = String::deserialize(deserializer)?;
     if s.is_empty() {
         Ok(None)
+    } else if s.starts_with('/') || split_console_url(&s).0.is_some() {
+        Ok(Some(s))
     } else {
-        Ok(Some(if s.starts_with('/') { s } else { format!("/{s}") }))
+        Ok(Some(format!("/{s}")))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn event(name: &str, namespace: &str, ty: &str) -> Event {
+        Event {
+            info: Info { name: name.to_string(), description: String::new() },
+            namespace: namespace.to_string(),
+            tags: Vec::new(),
+            ty: DataType { element_type: String::new(), ty: ty.to_string() },
+        }
+    }
+
+    #[test]
+    fn test_console_url_hosts() {
+        assert_eq!(split_console_url("/lol-summoner/v1/current-summoner"), (None, "/lol-summoner/v1/current-summoner"));
+        assert_eq!(split_console_url("https://127.0.0.1:2999/liveclientdata/allgamedata"), (Some("127.0.0.1:2999"), "/liveclientdata/allgamedata"));
+        assert_eq!(split_console_url("localhost:2999/liveclientdata/allgamedata"), (Some("localhost:2999"), "/liveclientdata/allgamedata"));
+        assert_eq!(split_console_url("https://example.com"), (Some("example.com"), "/"));
+
+        let console: ConsoleEndpointInner = serde_json::from_str(r#"{ "url": "lol-lobby/v2/lobby" }"#).unwrap();
+        assert_eq!(console.url.as_deref(), Some("/lol-lobby/v2/lobby"));
+        let console: ConsoleEndpointInner = serde_json::from_str(r#"{ "url": "https://127.0.0.1:2999/liveclientdata/{name}" }"#).unwrap();
+        assert_eq!(console.url.as_deref(), Some("https://127.0.0.1:2999/liveclientdata/{name}"));
+        assert_eq!(console.path_params(), ["name"]);
+    }
+
+    #[test]
+    fn test_string_map_sorted() {
+        let listing = r#"{ "c": "", "a": "x", "d": "y", "b": "" }"#;
+        let map: StringMap = serde_json::from_str(listing).unwrap();
+        assert_eq!(serde_json::to_string(&map).unwrap(), r#"{"a":"x","b":"","c":"","d":"y"}"#);
+    }
+
+    #[test]
+    fn test_dedup_events_identical() {
+        let mut report = SchemaReport::default();
+        let events = vec![
+            event("OnJsonApiEvent", "", ""),
+            event("OnLcdsEvent", "", ""),
+            event("OnJsonApiEvent", "", "")
+        ];
+        let events = dedup_events(events, &mut report);
+
+        let names = events
+            .iter()
+            .map(|e| e.info.name.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(names, vec!["OnJsonApiEvent", "OnLcdsEvent"]);
+        assert_eq!(report.entries.len(), 1);
+        assert_eq!(report.entries[0].kind, ReportKind::Merged);
+        assert_eq!(report.entries[0].target, "OnJsonApiEvent");
+        assert_eq!(report.entries[0].detail, "collapsed 2 identical events");
+    }
+
+    #[test]
+    fn test_dedup_events_several_identical_sets() {
+        let mut report = SchemaReport::default();
+        let events = vec![
+            event("OnServiceProxyEvent", "lol-lobby", "LolLobbyLobbyDto"),
+            event("OnServiceProxyEvent", "lol-lobby", "LolLobbyLobbyDto"),
+            event("OnServiceProxyEvent", "lol-chat", "LolChatConversation"),
+            event("OnServiceProxyEvent", "lol-chat", "LolChatConversation"),
+            event("OnServiceProxyEvent", "lol-chat", "LolChatConversation")
+        ];
+        let events = dedup_events(events, &mut report);
+        assert_eq!(events.len(), 2);
+
+        let merged = report.entries
+            .iter()
+            .filter(|e| e.kind == ReportKind::Merged)
+            .map(|e| e.detail.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(merged, vec!["collapsed 2 identical events", "collapsed 3 identical events"]);
+    }
+
+    #[test]
+    fn test_dedup_events_differing() {
+        let mut report = SchemaReport::default();
+        let events = vec![
+            event("OnServiceProxyEvent", "lol-lobby", "LolLobbyLobbyDto"),
+            event("OnServiceProxyEvent", "lol-chat", "LolChatConversation"),
+            event("OnServiceProxyEvent", "lol-chat", "LolChatConversation")
+        ];
+        let events = dedup_events(events, &mut report);
+
+        let names = events
+            .iter()
+            .map(|e| e.info.name.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(names, vec!["OnServiceProxyEvent:lol-lobby", "OnServiceProxyEvent:lol-chat"]);
+        assert_eq!(events[1].ty.ty, "LolChatConversation");
+
+        let kinds = report.entries
+            .iter()
+            .map(|e| e.kind)
+            .collect::<Vec<_>>();
+        assert_eq!(kinds, vec![ReportKind::Merged, ReportKind::Renamed, ReportKind::Renamed]);
+        assert_eq!(report.entries[2].detail, "renamed to OnServiceProxyEvent:lol-chat");
     }
 }
@@ -0,0 +1,299 @@
+//! What changed between two [`ExtendedHelp`]s, before any conversion.
+//!
+//! Comparing specs mixes changes to the LCU with changes to the generator.
+//! Comparing the help they were made from only shows the former.
+
+use std::{ collections::BTreeMap, fmt::{ self, Write } };
+
+use crate::help::{ DataType, Endpoint, Event, ExtendedHelp, Type };
+
+/// One difference within a changed item. `old` is `None` for something added
+/// and `new` is `None` for something removed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Change {
+    /// What changed, like `field summonerId`, `method`, or `returns`.
+    pub item: String,
+    pub old: Option<String>,
+    pub new: Option<String>,
+}
+
+impl Change {
+    fn new(item: impl Into<String>, old: Option<String>, new: Option<String>) -> Self {
+        Self { item: item.into(), old, new }
+    }
+}
+
+impl fmt::Display for Change {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match (&self.old, &self.new) {
+            (None, Some(new)) => write!(f, "{} added: {new}", self.item),
+            (Some(old), None) => write!(f, "{} removed: {old}", self.item),
+            (Some(old), Some(new)) => write!(f, "{}: {old} -> {new}", self.item),
+            (None, None) => write!(f, "{}", self.item),
+        }
+    }
+}
+
+/// Added, removed, and changed items of one kind, each sorted by name.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct HelpChanges {
+    pub added: Vec<String>,
+    pub removed: Vec<String>,
+    pub changed: Vec<(String, Vec<Change>)>,
+}
+
+impl HelpChanges {
+    fn of<'a, T: 'a>(
+        old: impl IntoIterator<Item = (&'a str, &'a T)>,
+        new: impl IntoIterator<Item = (&'a str, &'a T)>,
+        compare: impl Fn(&T, &T) -> Vec<Change>
+    ) -> Self {
+        let old = old.into_iter().collect::<BTreeMap<&str, &T>>();
+        let new = new.into_iter().collect::<BTreeMap<&str, &T>>();
+        let mut changes = Self::default();
+        for (name, new_item) in new.iter() {
+            match old.get(name) {
+                None => changes.added.push(name.to_string()),
+                Some(old_item) => {
+                    let item_changes = compare(old_item, new_item);
+                    if !item_changes.is_empty() {
+                        changes.changed.push((name.to_string(), item_changes));
+                    }
+                }
+            }
+        }
+        changes.removed = old
+            .keys()
+            .filter(|name| !new.contains_key(*name))
+            .map(|name| name.to_string())
+            .collect();
+        changes
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty() && self.changed.is_empty()
+    }
+
+    fn write_markdown(&self, title: &str, markdown: &mut String) {
+        let _ = writeln!(
+            markdown,
+            "\n## {title}: {} added, {} removed, {} changed\n",
+            self.added.len(),
+            self.removed.len(),
+            self.changed.len()
+        );
+        for name in self.added.iter() {
+            let _ = writeln!(markdown, "- Added `{name}`");
+        }
+        for name in self.removed.iter() {
+            let _ = writeln!(markdown, "- Removed `{name}`");
+        }
+        for (name, changes) in self.changed.iter() {
+            let _ = writeln!(markdown, "- Changed `{name}`");
+            for change in changes {
+                let _ = writeln!(markdown, "  - {change}");
+            }
+        }
+    }
+}
+
+/// Differences between two [`ExtendedHelp`]s, from [`ExtendedHelp::diff`].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct HelpDiff {
+    pub types: HelpChanges,
+    pub endpoints: HelpChanges,
+    pub events: HelpChanges,
+}
+
+impl HelpDiff {
+    pub fn is_empty(&self) -> bool {
+        self.types.is_empty() && self.endpoints.is_empty() && self.events.is_empty()
+    }
+
+    /// A `# Source changes` section, with a list per kind that changed.
+    pub fn to_markdown(&self) -> String {
+        let mut markdown = String::from("# Source changes\n");
+        if self.is_empty() {
+            markdown.push_str("\nThe help data is unchanged.\n");
+            return markdown;
+        }
+        for (title, changes) in [("Types", &self.types), ("Endpoints", &self.endpoints), ("Events", &self.events)] {
+            if !changes.is_empty() {
+                changes.write_markdown(title, &mut markdown);
+            }
+        }
+        markdown
+    }
+}
+
+impl ExtendedHelp {
+    /// What changed from `self` to `other`: types by field and enum value,
+    /// endpoints by method, path, arguments, and return type, and events by
+    /// type. Descriptions are ignored.
+    pub fn diff(&self, other: &ExtendedHelp) -> HelpDiff {
+        HelpDiff {
+            types: HelpChanges::of(
+                self.types.iter().map(|ty| (ty.info.name.as_str(), ty)),
+                other.types.iter().map(|ty| (ty.info.name.as_str(), ty)),
+                compare_types
+            ),
+            endpoints: HelpChanges::of(
+                self.endpoints.iter().map(|endpoint| (endpoint.info.name.as_str(), endpoint)),
+                other.endpoints.iter().map(|endpoint| (endpoint.info.name.as_str(), endpoint)),
+                compare_endpoints
+            ),
+            events: HelpChanges::of(
+                self.events.iter().map(|event| (event.info.name.as_str(), event)),
+                other.events.iter().map(|event| (event.info.name.as_str(), event)),
+                compare_events
+            ),
+        }
+    }
+}
+
+/// A type as written in help, like `uint64` or `vector<LolSummonerSummoner>`.
+fn type_name(ty: &DataType) -> String {
+    if ty.element_type.is_empty() { ty.ty.clone() } else { format!("{}<{}>", ty.ty, ty.element_type) }
+}
+
+/// A type, marked when it's optional.
+fn member(ty: &DataType, optional: bool) -> String {
+    if optional { format!("{} (optional)", type_name(ty)) } else { type_name(ty) }
+}
+
+/// Changes between two lists of named members, each described by a string.
+fn compare_members(kind: &str, old: &[(String, String)], new: &[(String, String)]) -> Vec<Change> {
+    let old = old.iter().map(|(name, value)| (name, value)).collect::<BTreeMap<_, _>>();
+    let new = new.iter().map(|(name, value)| (name, value)).collect::<BTreeMap<_, _>>();
+    let mut changes = Vec::new();
+    for (name, new_value) in new.iter() {
+        match old.get(name) {
+            None => changes.push(Change::new(format!("{kind} {name}"), None, Some(new_value.to_string()))),
+            Some(old_value) if old_value != new_value => {
+                changes.push(Change::new(format!("{kind} {name}"), Some(old_value.to_string()), Some(new_value.to_string())));
+            }
+            Some(_) => {}
+        }
+    }
+    for (name, old_value) in old.iter().filter(|(name, _)| !new.contains_key(*name)) {
+        changes.push(Change::new(format!("{kind} {name}"), Some(old_value.to_string()), None));
+    }
+    changes
+}
+
+fn compare_field(kind: &str, old: Option<String>, new: Option<String>) -> Option<Change> {
+    (old != new).then(|| Change::new(kind, old, new))
+}
+
+fn compare_types(old: &Type, new: &Type) -> Vec<Change> {
+    let fields = |ty: &Type| {
+        ty.fields
+            .iter()
+            .map(|field| (field.info.name.clone(), member(&field.ty, field.is_optional)))
+            .collect::<Vec<_>>()
+    };
+    let values = |ty: &Type| ty.values.iter().map(|value| (value.name.clone(), value.value.to_string())).collect::<Vec<_>>();
+    let mut changes = compare_members("field", &fields(old), &fields(new));
+    changes.extend(compare_members("value", &values(old), &values(new)));
+    changes
+}
+
+fn compare_endpoints(old: &Endpoint, new: &Endpoint) -> Vec<Change> {
+    let arguments = |endpoint: &Endpoint| {
+        endpoint.arguments
+            .iter()
+            .map(|argument| (argument.info.name.clone(), member(&argument.ty, argument.is_optional)))
+            .collect::<Vec<_>>()
+    };
+    let mut changes = Vec::new();
+    changes.extend(compare_field("method", old.method.clone(), new.method.clone()));
+    changes.extend(compare_field("path", old.path.clone(), new.path.clone()));
+    changes.extend(compare_members("argument", &arguments(old), &arguments(new)));
+    changes.extend(compare_field("returns", Some(type_name(&old.return_ty)), Some(type_name(&new.return_ty))));
+    changes
+}
+
+fn compare_events(old: &Event, new: &Event) -> Vec<Change> {
+    compare_field("type", Some(type_name(&old.ty)), Some(type_name(&new.ty))).into_iter().collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::miniature;
+
+    fn change(item: &str, old: Option<&str>, new: Option<&str>) -> Change {
+        Change::new(item, old.map(str::to_string), new.map(str::to_string))
+    }
+
+    /// The miniature with something of every kind added, removed, and changed.
+    fn patched() -> ExtendedHelp {
+        let mut help = miniature();
+        help.types.retain(|ty| ty.info.name != "LolLobbyLobbyChangeGameDto");
+        let mut added = miniature().types.remove(0);
+        added.info.name = "LolSummonerSummonerIcon".to_string();
+        help.types.push(added);
+        let summoner = help.types.iter_mut().find(|ty| ty.info.name == "LolSummonerSummoner").unwrap();
+        summoner.fields[0].ty.ty = "string".to_string();
+        summoner.fields[1].is_optional = true;
+        summoner.fields.retain(|field| field.info.name != "puuid");
+
+        help.endpoints.retain(|endpoint| endpoint.info.name != "Subscribe");
+        let by_id = help.endpoints.iter_mut().find(|endpoint| endpoint.info.name == "GetLolSummonerV1SummonersById").unwrap();
+        by_id.path = Some("/lol-summoner/v2/summoners/{id}".to_string());
+        by_id.return_ty.element_type = "LolSummonerSummoner".to_string();
+        by_id.return_ty.ty = "vector".to_string();
+        by_id.info.description = "Descriptions don't count".to_string();
+
+        help.events[0].ty.ty = "string".to_string();
+        help
+    }
+
+    #[test]
+    fn test_diff() {
+        let diff = miniature().diff(&patched());
+
+        assert_eq!(diff.types.added, ["LolSummonerSummonerIcon"]);
+        assert_eq!(diff.types.removed, ["LolLobbyLobbyChangeGameDto"]);
+        assert_eq!(
+            diff.types.changed,
+            [
+                (
+                    "LolSummonerSummoner".to_string(),
+                    vec![
+                        change("field accountId", Some("uint64"), Some("string")),
+                        change("field displayName", Some("string"), Some("string (optional)")),
+                        change("field puuid", Some("string"), None),
+                    ],
+                ),
+            ]
+        );
+
+        assert!(diff.endpoints.added.is_empty());
+        assert_eq!(diff.endpoints.removed, ["Subscribe"]);
+        let (name, changes) = &diff.endpoints.changed[0];
+        assert_eq!(name, "GetLolSummonerV1SummonersById");
+        assert_eq!(
+            changes,
+            &[
+                change("path", Some("/lol-summoner/v1/summoners/{id}"), Some("/lol-summoner/v2/summoners/{id}")),
+                change("returns", Some("LolSummonerSummoner"), Some("vector<LolSummonerSummoner>")),
+            ]
+        );
+
+        assert_eq!(diff.events.changed.len(), 1);
+        assert!(miniature().diff(&miniature()).is_empty());
+    }
+
+    #[test]
+    fn test_markdown() {
+        let markdown = miniature().diff(&patched()).to_markdown();
+        assert!(markdown.starts_with("# Source changes\n\n## Types: 1 added, 1 removed, 1 changed\n\n"));
+        assert!(markdown.contains("- Added `LolSummonerSummonerIcon`\n- Removed `LolLobbyLobbyChangeGameDto`\n"));
+        assert!(markdown.contains("- Changed `LolSummonerSummoner`\n  - field accountId: uint64 -> string\n"));
+        assert!(markdown.contains("  - field puuid removed: string\n"));
+        assert!(markdown.contains("## Endpoints: 0 added, 1 removed, 1 changed"));
+
+        assert_eq!(miniature().diff(&miniature()).to_markdown(), "# Source changes\n\nThe help data is unchanged.\n");
+    }
+}
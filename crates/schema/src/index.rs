@@ -0,0 +1,141 @@
+use std::collections::{ BTreeMap, BTreeSet };
+
+use serde_json::Value;
+
+use crate::openapi::{ Components, OpenApiSpec };
+
+const REF_PREFIX: &str = "#/components/schemas/";
+
+/// Reverse map from component schemas to the operations that use them.
+///
+/// Usage is transitive: an operation returning `LolLobbyLobbyDto` also produces
+/// every component that `LolLobbyLobbyDto` refers to.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SpecIndex {
+    /// Component name to the operationIds whose responses contain it.
+    pub produced_by: BTreeMap<String, BTreeSet<String>>,
+    /// Component name to the operationIds whose request bodies or parameters contain it.
+    pub consumed_by: BTreeMap<String, BTreeSet<String>>,
+}
+
+impl SpecIndex {
+    pub fn build(spec: &OpenApiSpec) -> Self {
+        let schemas = ref_graph(&spec.components);
+
+        let mut index = Self::default();
+        for operation in spec.paths.values().filter_map(Value::as_object).flat_map(|item| item.values()) {
+            let Some(id) = operation.get("operationId").and_then(Value::as_str) else {
+                continue;
+            };
+
+            let produced = operation.get("responses").map(direct_refs).unwrap_or_default();
+            for name in closure(produced, &schemas) {
+                index.produced_by.entry(name).or_default().insert(id.to_string());
+            }
+
+            let mut consumed = operation.get("requestBody").map(direct_refs).unwrap_or_default();
+            consumed.extend(operation.get("parameters").map(direct_refs).unwrap_or_default());
+            for name in closure(consumed, &schemas) {
+                index.consumed_by.entry(name).or_default().insert(id.to_string());
+            }
+        }
+        index
+    }
+
+    /// Every operation that produces or consumes the component, sorted.
+    pub fn used_by(&self, component: &str) -> BTreeSet<&str> {
+        let producers = self.produced_by.get(component).into_iter().flatten();
+        let consumers = self.consumed_by.get(component).into_iter().flatten();
+        producers.chain(consumers).map(String::as_str).collect()
+    }
+}
+
+/// Component names referenced anywhere inside `value`.
+pub(crate) fn direct_refs(value: &Value) -> BTreeSet<String> {
+    let mut refs = BTreeSet::new();
+    walk_refs(value, &mut |name| {
+        refs.insert(name.to_string());
+    });
+    refs
+}
+
+/// Call `f` with the component name of every `$ref` inside `value`, once per occurrence.
+pub(crate) fn walk_refs<F>(value: &Value, f: &mut F) where F: FnMut(&str) {
+    match value {
+        Value::Object(object) => {
+            if let Some(name) = ref_name(value) {
+                f(name);
+            }
+            object.values().for_each(|v| walk_refs(v, f));
+        }
+        Value::Array(array) => array.iter().for_each(|v| walk_refs(v, f)),
+        _ => {}
+    }
+}
+
+/// The component a `{ "$ref": ... }` schema points at.
+pub(crate) fn ref_name(schema: &Value) -> Option<&str> {
+    schema
+        .get("$ref")
+        .and_then(Value::as_str)
+        .and_then(|r| r.strip_prefix(REF_PREFIX))
+}
+
+/// The ref graph of `components`: each component schema, and the components
+/// it refers to directly. Refs may name components that don't exist.
+pub fn ref_graph(components: &Components) -> BTreeMap<&str, BTreeSet<String>> {
+    components
+        .get("schemas")
+        .and_then(Value::as_object)
+        .map(|schemas| {
+            schemas
+                .iter()
+                .map(|(name, schema)| (name.as_str(), direct_refs(schema)))
+                .collect::<BTreeMap<&str, BTreeSet<String>>>()
+        })
+        .unwrap_or_default()
+}
+
+/// Every component referenced inside `value`, directly or through other
+/// components of `spec`.
+#[cfg(feature = "irelia")]
+pub(crate) fn reachable(spec: &OpenApiSpec, value: &Value) -> BTreeSet<String> {
+    closure(direct_refs(value), &ref_graph(&spec.components))
+}
+
+/// `roots` plus every component reachable from them.
+fn closure(roots: BTreeSet<String>, schemas: &BTreeMap<&str, BTreeSet<String>>) -> BTreeSet<String> {
+    let mut seen = BTreeSet::new();
+    let mut pending = roots.into_iter().collect::<Vec<String>>();
+    while let Some(name) = pending.pop() {
+        if let Some(refs) = schemas.get(name.as_str()) {
+            pending.extend(refs.iter().filter(|r| !seen.contains(*r)).cloned());
+        }
+        seen.insert(name);
+    }
+    seen
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::miniature;
+
+    #[test]
+    fn test_index() {
+        let spec = OpenApiSpec::from_help(&miniature(), "1.0.0").unwrap();
+        let index = SpecIndex::build(&spec);
+
+        assert_eq!(
+            index.used_by("LolSummonerSummoner").into_iter().collect::<Vec<_>>(),
+            ["GetLolSummonerV1CurrentSummoner", "GetLolSummonerV1Summoners", "GetLolSummonerV1SummonersById"]
+        );
+        // Reached through LolSummonerSummoner.
+        assert!(index.produced_by["LolSummonerProfilePrivacySetting"].contains("GetLolSummonerV1CurrentSummoner"));
+        assert_eq!(
+            index.consumed_by["LolLobbyLobbyChangeGameDto"].iter().collect::<Vec<_>>(),
+            ["PostLolLobbyV2Lobby"]
+        );
+        assert!(!index.produced_by.contains_key("LolLobbyLobbyChangeGameDto"));
+    }
+}
@@ -0,0 +1,213 @@
+//! The component schemas of a spec as one JSON Schema document, for
+//! validating websocket payloads and stored data without the paths.
+//!
+//! OpenAPI 3.0 schemas are a dialect of an older draft, so a few keywords are
+//! rewritten on the way: `nullable` becomes a `"null"` type, `example` becomes
+//! `examples`, and boolean `exclusiveMinimum`/`exclusiveMaximum` become the
+//! bounds themselves. Formats like `uint64` and `x-` extensions like
+//! [`crate::enums::X_ENUM_VARNAMES`] are kept, as annotations.
+
+use serde_json::{ Map, Value, json };
+
+use crate::{ openapi::OpenApiSpec, visit::visit_schema_mut };
+
+/// The JSON Schema draft the bundle is written in.
+pub const JSON_SCHEMA_VERSION: &str = "2020-12";
+
+/// `$schema` of the bundle, naming [`JSON_SCHEMA_VERSION`].
+pub const JSON_SCHEMA_DIALECT: &str = "https://json-schema.org/draft/2020-12/schema";
+
+const COMPONENT_REF: &str = "#/components/schemas/";
+
+const DEFS_REF: &str = "#/$defs/";
+
+/// OpenAPI keywords with no JSON Schema counterpart.
+const OPENAPI_ONLY: [&str; 3] = ["discriminator", "xml", "externalDocs"];
+
+#[derive(Debug, Clone, Default)]
+pub struct JsonSchemaOptions {
+    /// The component the document validates, by name. Without one the
+    /// document only holds the `$defs`, and validates anything.
+    pub root: Option<String>,
+    /// `$id` of the document.
+    pub id: Option<String>,
+}
+
+/// A draft 2020-12 document with every component schema under `$defs`, and
+/// references between them pointing there.
+///
+/// A [`JsonSchemaOptions::root`] that isn't a component is left out, see
+/// [`missing_root`].
+pub fn components_to_json_schema_bundle(spec: &OpenApiSpec, opts: &JsonSchemaOptions) -> Value {
+    let mut defs = spec.components.get("schemas").and_then(Value::as_object).cloned().unwrap_or_default();
+    for (name, schema) in defs.iter_mut() {
+        visit_schema_mut(schema, name.clone(), None, &mut |_, schema| translate(schema));
+    }
+
+    let mut bundle = Map::new();
+    bundle.insert("$schema".to_string(), json!(JSON_SCHEMA_DIALECT));
+    if let Some(id) = &opts.id {
+        bundle.insert("$id".to_string(), json!(id));
+    }
+    bundle.insert("title".to_string(), json!(spec.info.title));
+    bundle.insert("description".to_string(), json!(format!("Component schemas of {} v{}", spec.info.title, spec.info.version)));
+    if let Some(root) = opts.root.as_ref().filter(|root| defs.contains_key(*root)) {
+        bundle.insert("$ref".to_string(), json!(format!("{DEFS_REF}{root}")));
+    }
+    bundle.insert("$defs".to_string(), Value::Object(defs));
+    Value::Object(bundle)
+}
+
+/// The root `opts` asks for, if `spec` has no component by that name.
+pub fn missing_root<'a>(spec: &OpenApiSpec, opts: &'a JsonSchemaOptions) -> Option<&'a str> {
+    let schemas = spec.components.get("schemas").and_then(Value::as_object);
+    opts.root.as_deref().filter(|root| schemas.is_none_or(|schemas| !schemas.contains_key(*root)))
+}
+
+/// Rewrite one schema object's OpenAPI keywords. Nested schemas are visited
+/// separately.
+fn translate(schema: &mut Value) {
+    let Value::Object(object) = schema else {
+        return;
+    };
+    if let Some(Value::String(reference)) = object.get_mut("$ref")
+        && let Some(name) = reference.strip_prefix(COMPONENT_REF)
+    {
+        *reference = format!("{DEFS_REF}{name}");
+    }
+    for key in OPENAPI_ONLY {
+        object.remove(key);
+    }
+    if let Some(example) = object.remove("example") {
+        object.entry("examples").or_insert_with(|| json!([example]));
+    }
+    for (exclusive, bound) in [("exclusiveMinimum", "minimum"), ("exclusiveMaximum", "maximum")] {
+        match object.get(exclusive) {
+            Some(Value::Bool(true)) => {
+                match object.remove(bound) {
+                    Some(bound) => object.insert(exclusive.to_string(), bound),
+                    None => object.remove(exclusive),
+                };
+            }
+            Some(Value::Bool(false)) => {
+                object.remove(exclusive);
+            }
+            _ => (),
+        }
+    }
+
+    if object.remove("nullable") != Some(Value::Bool(true)) {
+        return;
+    }
+    if let Some(Value::Array(values)) = object.get_mut("enum")
+        && !values.contains(&Value::Null)
+    {
+        values.push(Value::Null);
+    }
+    match object.get_mut("type") {
+        Some(Value::String(ty)) => {
+            let ty = std::mem::take(ty);
+            object.insert("type".to_string(), json!([ty, "null"]));
+        }
+        Some(Value::Array(types)) => {
+            if !types.contains(&json!("null")) {
+                types.push(json!("null"));
+            }
+        }
+        // A bare `$ref` or combinator, which can't take a type of its own.
+        _ => {
+            let rest = std::mem::take(object);
+            object.insert("anyOf".to_string(), json!([rest, { "type": "null" }]));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::miniature;
+
+    fn bundle(root: Option<&str>) -> Value {
+        let spec = OpenApiSpec::from_help(&miniature(), "1.0.0").unwrap();
+        let opts = JsonSchemaOptions { root: root.map(str::to_string), ..Default::default() };
+        components_to_json_schema_bundle(&spec, &opts)
+    }
+
+    #[test]
+    fn test_bundle_is_valid_json_schema() {
+        let bundle = bundle(Some("LolSummonerSummoner"));
+        jsonschema::draft202012::meta::validate(&bundle).unwrap();
+        assert_eq!(bundle["$schema"], JSON_SCHEMA_DIALECT);
+        assert_eq!(bundle["$ref"], "#/$defs/LolSummonerSummoner");
+        assert_eq!(
+            bundle["$defs"]["LolSummonerSummoner"]["properties"]["rerollPoints"],
+            json!({ "$ref": "#/$defs/LolSummonerSummonerRerollPoints" })
+        );
+        // Formats are kept.
+        assert_eq!(bundle["$defs"]["LolSummonerSummoner"]["properties"]["accountId"]["format"], "uint64");
+        assert!(!bundle.to_string().contains(COMPONENT_REF));
+
+        let defs_only = self::bundle(Some("Missing"));
+        assert!(defs_only.get("$ref").is_none());
+        assert_eq!(defs_only["$defs"], bundle["$defs"]);
+    }
+
+    #[test]
+    fn test_validate_payload() {
+        let bundle = bundle(Some("LolSummonerSummoner"));
+        let validator = jsonschema::draft202012::new(&bundle).unwrap();
+        let mut summoner = json!({
+            "accountId": 123456,
+            "displayName": "Poro",
+            "privacy": "PUBLIC",
+            "puuid": "0f1c6b9a-8a53-4bf4-9c7c-7a1d2f6e1f00",
+            "rerollPoints": { "currentPoints": 250, "numberOfRolls": 2 },
+            "summonerLevel": 30,
+        });
+        let errors = validator.iter_errors(&summoner).map(|err| err.to_string()).collect::<Vec<String>>();
+        assert!(errors.is_empty(), "{errors:?}");
+
+        summoner["privacy"] = json!("FRIENDS");
+        assert!(!validator.is_valid(&summoner));
+        summoner["privacy"] = json!("PUBLIC");
+        summoner["accountId"] = json!(-1);
+        assert!(!validator.is_valid(&summoner));
+        summoner["accountId"] = json!(1);
+        summoner["rerollPoints"] = json!({ "currentPoints": 250 });
+        assert!(!validator.is_valid(&summoner));
+    }
+
+    #[test]
+    fn test_translate() {
+        let mut spec = OpenApiSpec::from_help(&miniature(), "1.0.0").unwrap();
+        spec.components["schemas"]["Nullable"] = json!({
+            "type": "object",
+            "properties": {
+                "name": { "type": "string", "nullable": true, "example": "Poro" },
+                "queue": { "type": "string", "enum": ["ARAM", "URF"], "nullable": true },
+                "summoner": { "$ref": "#/components/schemas/LolSummonerSummoner", "nullable": true },
+                "level": { "type": "integer", "minimum": 0, "exclusiveMinimum": true, "nullable": false },
+                "priority": { "type": "integer", "enum": [-1, 1], "x-enum-varnames": ["LOW", "HIGH"] },
+            },
+            "discriminator": { "propertyName": "name" },
+        });
+        let bundle = components_to_json_schema_bundle(&spec, &JsonSchemaOptions::default());
+        jsonschema::draft202012::meta::validate(&bundle).unwrap();
+
+        let nullable = &bundle["$defs"]["Nullable"];
+        assert!(nullable.get("discriminator").is_none());
+        let properties = &nullable["properties"];
+        assert_eq!(properties["name"], json!({ "type": ["string", "null"], "examples": ["Poro"] }));
+        assert_eq!(properties["queue"], json!({ "type": ["string", "null"], "enum": ["ARAM", "URF", null] }));
+        assert_eq!(
+            properties["summoner"],
+            json!({ "anyOf": [{ "$ref": "#/$defs/LolSummonerSummoner" }, { "type": "null" }] })
+        );
+        assert_eq!(properties["level"], json!({ "type": "integer", "exclusiveMinimum": 0 }));
+        assert_eq!(properties["priority"]["x-enum-varnames"], json!(["LOW", "HIGH"]));
+
+        let validator = jsonschema::draft202012::new(&json!({ "$ref": "#/$defs/Nullable", "$defs": bundle["$defs"] })).unwrap();
+        assert!(validator.is_valid(&json!({ "name": null, "queue": null, "summoner": null, "level": 1 })));
+        assert!(!validator.is_valid(&json!({ "level": 0 })));
+    }
+}
@@ -0,0 +1,220 @@
+//! Measured response times, so client authors can pick timeouts.
+//!
+//! Only operations that were actually probed get an expectation; nothing is
+//! guessed from the help data.
+
+use std::{ collections::BTreeMap, time::Duration };
+
+use serde::{ Deserialize, Serialize };
+use serde_json::Value;
+
+use crate::{ caution::CautionRules, openapi::OpenApiSpec, rules::tags_of };
+
+/// Extension key holding an [`ExpectedLatency`] on an operation.
+pub const X_EXPECTED_LATENCY: &str = "x-expected-latency";
+
+/// Latencies are rounded to this many milliseconds, so the spec doesn't churn
+/// with every run's jitter.
+pub const LATENCY_STEP_MS: u64 = 50;
+
+/// Measured latencies of each operation, by operationId.
+pub type LatencySamples = BTreeMap<String, Vec<Duration>>;
+
+/// Coarse latency of an operation, as written under [`X_EXPECTED_LATENCY`].
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ExpectedLatency {
+    pub p50_ms: u64,
+    pub p95_ms: u64,
+    /// How many measurements the percentiles come from, across runs.
+    pub samples: u64,
+}
+
+impl ExpectedLatency {
+    /// Nearest-rank percentiles of `samples`, or `None` without any.
+    pub fn from_samples(samples: &[Duration]) -> Option<Self> {
+        if samples.is_empty() {
+            return None;
+        }
+        let mut millis = samples.iter().map(Duration::as_millis).collect::<Vec<u128>>();
+        millis.sort_unstable();
+        let percentile = |p: usize| {
+            let rank = (p * millis.len()).div_ceil(100).max(1);
+            round(u64::try_from(millis[rank - 1]).unwrap_or(u64::MAX))
+        };
+        Some(Self { p50_ms: percentile(50), p95_ms: percentile(95), samples: samples.len() as u64 })
+    }
+
+    /// Both expectations combined, each percentile weighted by its sample count.
+    pub fn merge(&self, other: &Self) -> Self {
+        let samples = self.samples + other.samples;
+        if samples == 0 {
+            return *self;
+        }
+        let weighted = |a: u64, b: u64| round((a * self.samples + b * other.samples + samples / 2) / samples);
+        Self { p50_ms: weighted(self.p50_ms, other.p50_ms), p95_ms: weighted(self.p95_ms, other.p95_ms), samples }
+    }
+
+    /// The expectation on a generated operation, if it has one.
+    pub fn of(operation: &Value) -> Option<Self> {
+        serde_json::from_value(operation.get(X_EXPECTED_LATENCY)?.clone()).ok()
+    }
+}
+
+/// `millis` to the nearest [`LATENCY_STEP_MS`].
+fn round(millis: u64) -> u64 {
+    millis.saturating_add(LATENCY_STEP_MS / 2) / LATENCY_STEP_MS * LATENCY_STEP_MS
+}
+
+/// Operations that are fine to call just to time them: `GET`s without path
+/// parameters that no caution rule matches. Returned as operationId and path.
+pub fn probe_targets(spec: &OpenApiSpec, rules: &CautionRules) -> Vec<(String, String)> {
+    let mut targets = Vec::new();
+    for (path, item) in spec.paths.iter() {
+        let Some(operation) = item.get("get") else {
+            continue;
+        };
+        let Some(id) = operation["operationId"].as_str() else {
+            continue;
+        };
+        if path.contains('{') || rules.classify("GET", path, &tags_of(operation)).is_some() {
+            continue;
+        }
+        targets.push((id.to_string(), path.clone()));
+    }
+    targets
+}
+
+/// Time `rounds` calls of every target, one after another. `get` sends a
+/// `GET` to a path. Failed calls aren't measured.
+#[cfg(feature = "irelia")]
+pub async fn probe<F, Fut>(targets: &[(String, String)], rounds: usize, get: F) -> LatencySamples
+    where F: Fn(String) -> Fut, Fut: std::future::Future<Output = Result<Value, crate::error::Error>>
+{
+    let mut samples = LatencySamples::new();
+    for _ in 0..rounds {
+        for (id, path) in targets {
+            let start = std::time::Instant::now();
+            if get(path.clone()).await.is_ok() {
+                samples.entry(id.clone()).or_default().push(start.elapsed());
+            }
+        }
+    }
+    samples
+}
+
+/// Writes [`X_EXPECTED_LATENCY`] on every operation with samples, merged with
+/// the expectation the same operation has in `previous`.
+#[derive(Debug, Clone)]
+pub struct LatencyAnnotations<'a> {
+    pub samples: &'a LatencySamples,
+    pub previous: Option<&'a OpenApiSpec>,
+}
+
+impl LatencyAnnotations<'_> {
+    /// Returns the number of operations that were annotated.
+    pub fn apply(&self, spec: &mut OpenApiSpec) -> usize {
+        let previous = self.previous.map(expectations).unwrap_or_default();
+        let mut annotated = 0;
+        let operations = spec.paths
+            .values_mut()
+            .filter_map(Value::as_object_mut)
+            .flat_map(|item| item.values_mut())
+            .filter_map(Value::as_object_mut);
+        for operation in operations {
+            let Some(id) = operation.get("operationId").and_then(Value::as_str) else {
+                continue;
+            };
+            let Some(measured) = self.samples.get(id).and_then(|samples| ExpectedLatency::from_samples(samples)) else {
+                continue;
+            };
+            let expected = previous.get(id).map_or(measured, |previous| previous.merge(&measured));
+            if let Ok(value) = serde_json::to_value(expected) {
+                operation.insert(X_EXPECTED_LATENCY.to_string(), value);
+                annotated += 1;
+            }
+        }
+        annotated
+    }
+}
+
+/// Every [`ExpectedLatency`] in `spec`, by operationId.
+fn expectations(spec: &OpenApiSpec) -> BTreeMap<String, ExpectedLatency> {
+    spec.paths
+        .values()
+        .filter_map(Value::as_object)
+        .flat_map(|item| item.values())
+        .filter_map(|operation| Some((operation["operationId"].as_str()?.to_string(), ExpectedLatency::of(operation)?)))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+    use crate::{ caution::{ CautionLevel, CautionRule }, tests::miniature_spec };
+
+    fn ms(millis: &[u64]) -> Vec<Duration> {
+        millis.iter().copied().map(Duration::from_millis).collect()
+    }
+
+    #[test]
+    fn test_percentiles() {
+        let samples = ms(&[110, 90, 130, 2040, 120, 100, 95, 105, 115, 125]);
+        let expected = ExpectedLatency::from_samples(&samples).unwrap();
+        // The 5th of 10 is 110ms, the 10th is the outlier.
+        assert_eq!(expected, ExpectedLatency { p50_ms: 100, p95_ms: 2050, samples: 10 });
+        assert_eq!(ExpectedLatency::from_samples(&ms(&[24])).unwrap().p95_ms, 0);
+        assert_eq!(ExpectedLatency::from_samples(&ms(&[25])).unwrap().p95_ms, 50);
+        assert!(ExpectedLatency::from_samples(&[]).is_none());
+    }
+
+    #[test]
+    fn test_merge() {
+        let old = ExpectedLatency { p50_ms: 100, p95_ms: 400, samples: 30 };
+        let new = ExpectedLatency { p50_ms: 300, p95_ms: 400, samples: 10 };
+        assert_eq!(old.merge(&new), ExpectedLatency { p50_ms: 150, p95_ms: 400, samples: 40 });
+
+        // Jitter within a step leaves the published numbers alone.
+        let jittery = ExpectedLatency::from_samples(&ms(&[104, 96, 118, 88, 111])).unwrap();
+        assert_eq!(old.merge(&jittery).p50_ms, 100);
+        assert_eq!(old.merge(&old), ExpectedLatency { samples: 60, ..old });
+    }
+
+    #[test]
+    fn test_only_probed() {
+        let mut samples = LatencySamples::new();
+        samples.insert("GetLolSummonerV1CurrentSummoner".to_string(), ms(&[180, 220, 210]));
+        samples.insert("NotInTheSpec".to_string(), ms(&[100]));
+        let mut spec = miniature_spec("1.0.0");
+        assert_eq!(LatencyAnnotations { samples: &samples, previous: None }.apply(&mut spec), 1);
+
+        let current = &spec.paths["/lol-summoner/v1/current-summoner"]["get"];
+        assert_eq!(current[X_EXPECTED_LATENCY], json!({ "p50_ms": 200, "p95_ms": 200, "samples": 3 }));
+        let annotated = spec.paths
+            .values()
+            .flat_map(|item| item.as_object().unwrap().values())
+            .filter(|operation| operation.get(X_EXPECTED_LATENCY).is_some())
+            .count();
+        assert_eq!(annotated, 1);
+
+        // The next run merges into what this one published.
+        let previous = spec.clone();
+        samples.insert("GetLolSummonerV1CurrentSummoner".to_string(), ms(&[500]));
+        LatencyAnnotations { samples: &samples, previous: Some(&previous) }.apply(&mut spec);
+        let current = &spec.paths["/lol-summoner/v1/current-summoner"]["get"];
+        assert_eq!(ExpectedLatency::of(current), Some(ExpectedLatency { p50_ms: 300, p95_ms: 300, samples: 4 }));
+    }
+
+    #[test]
+    fn test_probe_targets() {
+        let spec = miniature_spec("1.0.0");
+        let rules = CautionRules::default();
+        let targets = probe_targets(&spec, &rules);
+        assert!(targets.iter().any(|(id, _)| id == "GetLolSummonerV1CurrentSummoner"));
+        assert!(targets.iter().all(|(_, path)| !path.contains('{')));
+
+        let rules = CautionRules { overrides: vec![CautionRule::new(None, "/lol-summoner", CautionLevel::Notice, "Slow")] };
+        assert!(probe_targets(&spec, &rules).iter().all(|(_, path)| !path.starts_with("/lol-summoner")));
+    }
+}
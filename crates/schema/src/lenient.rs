@@ -0,0 +1,238 @@
+//! Loading specs that don't fit [`OpenApiSpec`]'s types, such as hand-edited
+//! fixtures, reporting every broken part instead of only the first.
+
+use std::{ collections::BTreeMap, fmt };
+
+use serde::de::DeserializeOwned;
+use serde_json::{ Map, Value };
+
+use crate::openapi::{ Components, EventSchema, OpenApiInfo, OpenApiSpec, Operation, SchemaObject, Tag, X_EVENTS };
+
+/// Path item keys that hold operations.
+const OPERATION_KEYS: [&str; 8] = ["get", "put", "post", "delete", "options", "head", "patch", "trace"];
+
+/// A part of a spec that failed to load.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SpecLoadError {
+    /// Where it is, e.g. `components.schemas.LolSummonerSummoner` or
+    /// `paths./lol-summoner/v1/current-summoner.get`.
+    pub location: String,
+    pub message: String,
+}
+
+impl fmt::Display for SpecLoadError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}", self.location, self.message)
+    }
+}
+
+/// The parts of a spec that loaded. Broken components, operations and events are left
+/// out; everything else is kept as it was.
+#[derive(Debug, Clone, Default)]
+pub struct PartialSpec {
+    pub openapi: Option<String>,
+    pub info: Option<OpenApiInfo>,
+    pub components: Components,
+    pub paths: Map<String, Value>,
+    pub tags: Vec<Tag>,
+    pub workflows: BTreeMap<String, Vec<String>>,
+    pub events: Map<String, Value>,
+}
+
+impl PartialSpec {
+    /// The loaded parts as a spec. A missing `openapi` is taken to be 3.0.0
+    /// and a missing `info` is left empty.
+    pub fn into_spec(self) -> OpenApiSpec {
+        let info = self.info.unwrap_or_else(|| OpenApiInfo {
+            title: String::new(),
+            description: String::new(),
+            version: String::new(),
+        });
+        let mut spec = OpenApiSpec::new(info).with_components(self.components).with_paths(self.paths).with_tags(self.tags).with_events(self.events);
+        if let Some(openapi) = self.openapi {
+            spec.openapi = openapi;
+        }
+        spec.workflows = self.workflows;
+        spec
+    }
+}
+
+impl OpenApiSpec {
+    /// Load a spec section by section, down to each component schema and
+    /// operation, so one pass finds every problem. Unlike deserializing an
+    /// [`OpenApiSpec`], this never gives up: broken parts are reported and
+    /// skipped.
+    pub fn from_value_lenient(value: Value) -> (PartialSpec, Vec<SpecLoadError>) {
+        let mut loader = Loader::default();
+        let Value::Object(mut root) = value else {
+            loader.fail("(root)", "expected an object");
+            return (PartialSpec::default(), loader.errors);
+        };
+
+        let mut partial = PartialSpec {
+            openapi: loader.required(&mut root, "openapi"),
+            info: loader.required(&mut root, "info"),
+            ..Default::default()
+        };
+
+        match root.remove("tags") {
+            Some(Value::Array(tags)) => {
+                partial.tags = tags
+                    .into_iter()
+                    .enumerate()
+                    .filter_map(|(i, tag)| loader.parse(&format!("tags.{i}"), tag))
+                    .collect();
+            }
+            Some(_) => loader.fail("tags", "expected an array"),
+            None => {}
+        }
+        if let Some(workflows) = root.remove("x-workflows") {
+            partial.workflows = loader.parse("x-workflows", workflows).unwrap_or_default();
+        }
+
+        if let Some(mut components) = loader.object(&mut root, "components") {
+            if let Some(Value::Object(schemas)) = components.get_mut("schemas") {
+                schemas.retain(|name, schema| {
+                    loader.check::<SchemaObject>(&format!("components.schemas.{name}"), schema)
+                });
+            }
+            partial.components = components;
+        }
+
+        if let Some(mut paths) = loader.object(&mut root, "paths") {
+            paths.retain(|path, item| {
+                let Value::Object(item) = item else {
+                    loader.fail(&format!("paths.{path}"), "expected an object");
+                    return false;
+                };
+                item.retain(|key, operation| {
+                    !OPERATION_KEYS.contains(&key.as_str()) ||
+                        loader.check::<Operation>(&format!("paths.{path}.{key}"), operation)
+                });
+                true
+            });
+            partial.paths = paths;
+        }
+
+        match root.remove(X_EVENTS) {
+            Some(Value::Object(mut events)) => {
+                events.retain(|name, event| loader.check::<EventSchema>(&format!("{X_EVENTS}.{name}"), event));
+                partial.events = events;
+            }
+            Some(_) => loader.fail(X_EVENTS, "expected an object"),
+            None => {}
+        }
+
+        (partial, loader.errors)
+    }
+}
+
+#[derive(Default)]
+struct Loader {
+    errors: Vec<SpecLoadError>,
+}
+
+impl Loader {
+    fn fail(&mut self, location: &str, message: impl Into<String>) {
+        self.errors.push(SpecLoadError { location: location.to_string(), message: message.into() });
+    }
+
+    fn parse<T: DeserializeOwned>(&mut self, location: &str, value: Value) -> Option<T> {
+        serde_json::from_value(value)
+            .map_err(|err| self.fail(location, err.to_string()))
+            .ok()
+    }
+
+    /// Whether `value` fits `T`, keeping it as it is.
+    fn check<T: DeserializeOwned>(&mut self, location: &str, value: &Value) -> bool {
+        self.parse::<T>(location, value.clone()).is_some()
+    }
+
+    fn required<T: DeserializeOwned>(&mut self, root: &mut Map<String, Value>, key: &str) -> Option<T> {
+        match root.remove(key) {
+            Some(value) => self.parse(key, value),
+            None => {
+                self.fail(key, "missing");
+                None
+            }
+        }
+    }
+
+    /// A required section that is an object.
+    fn object(&mut self, root: &mut Map<String, Value>, key: &str) -> Option<Map<String, Value>> {
+        match root.remove(key) {
+            Some(Value::Object(object)) => Some(object),
+            Some(_) => {
+                self.fail(key, "expected an object");
+                None
+            }
+            None => {
+                self.fail(key, "missing");
+                None
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+    use crate::tests::miniature;
+
+    fn miniature_value() -> Value {
+        serde_json::to_value(OpenApiSpec::from_help(&miniature(), "1.0.0").unwrap()).unwrap()
+    }
+
+    #[test]
+    fn test_every_error_reported() {
+        let mut value = miniature_value();
+        value["components"]["schemas"]["LolLobbyLobbyDto"]["properties"] = json!(["partyId"]);
+        value["components"]["schemas"]["LolSummonerSummonerRerollPoints"]["required"] = json!("currentPoints");
+        value["paths"]["/lol-lobby/v2/lobby"]["post"].as_object_mut().unwrap().remove("responses");
+        value[X_EVENTS]["OnJsonApiEvent"]["tags"] = json!("builtin");
+
+        let (partial, errors) = OpenApiSpec::from_value_lenient(value);
+        let locations = errors.iter().map(|err| err.location.as_str()).collect::<Vec<&str>>();
+        assert_eq!(
+            locations,
+            [
+                "components.schemas.LolLobbyLobbyDto",
+                "components.schemas.LolSummonerSummonerRerollPoints",
+                "paths./lol-lobby/v2/lobby.post",
+                "x-events.OnJsonApiEvent",
+            ]
+        );
+        assert_eq!(errors[2].to_string(), "paths./lol-lobby/v2/lobby.post: missing field `responses`");
+
+        // Everything else loaded.
+        let schemas = partial.components["schemas"].as_object().unwrap();
+        assert_eq!(schemas.len(), 4);
+        assert!(schemas.contains_key("LolSummonerSummoner"));
+        assert!(partial.paths["/lol-lobby/v2/lobby"].get("post").is_none());
+        assert!(partial.paths["/lol-lobby/v2/lobby"].get("delete").is_some());
+        assert_eq!(partial.paths.len(), miniature_value()["paths"].as_object().unwrap().len());
+        assert!(partial.events.contains_key("OnJsonApiEvent_lol-summoner_v1_current-summoner"));
+        assert!(!partial.events.contains_key("OnJsonApiEvent"));
+        assert_eq!(partial.into_spec().info.version, "1.0.0");
+    }
+
+    #[test]
+    fn test_broken_sections() {
+        let mut value = miniature_value();
+        value["info"] = json!({ "title": "LCU" });
+        value["tags"] = json!([{ "name": "lol-lobby" }, "lol-summoner"]);
+        value.as_object_mut().unwrap().remove("paths");
+
+        let (partial, errors) = OpenApiSpec::from_value_lenient(value);
+        let locations = errors.iter().map(|err| err.location.as_str()).collect::<Vec<&str>>();
+        assert_eq!(locations, ["info", "tags.1", "paths"]);
+        assert_eq!(partial.tags, [Tag::new("lol-lobby")]);
+        assert_eq!(partial.openapi.as_deref(), Some("3.0.0"));
+        assert!(!partial.components.is_empty());
+
+        let (_, errors) = OpenApiSpec::from_value_lenient(json!([]));
+        assert_eq!(errors[0].to_string(), "(root): expected an object");
+    }
+}
@@ -1,19 +1,95 @@
+#[cfg(feature = "irelia")]
 use irelia::{ requests::RequestClientTrait, rest::LcuClient };
 
 use error::Error;
-use openapi::{ OpenApiInfo, OpenApiSpec };
+use openapi::OpenApiSpec;
 use patch::Patch;
-use help::{ ConsoleEndpointInner, Endpoint, Event, ExtendedHelp, Help, SeqFirst, Type };
+#[cfg(feature = "irelia")]
+use report::ReportKind;
+#[cfg(feature = "irelia")]
+use throttle::{ paced, Throttle };
+#[cfg(feature = "irelia")]
+use std::{ sync::Mutex, time::Instant };
+use help::ExtendedHelp;
+use options::SchemaOptions;
 
 /// `use poro_schema::prelude::*;` to import common traits and types.
 pub mod prelude {
-    pub use super::{ lcu, PoroSchema, help::ExtendedHelp, openapi::OpenApiSpec };
+    #[cfg(feature = "irelia")]
+    pub use super::lcu;
+    pub use super::{ PoroSchema, help::ExtendedHelp, openapi::OpenApiSpec };
 }
 
+pub mod artifact;
+#[cfg(feature = "irelia")]
+pub mod builtin;
+pub mod bundle;
+#[cfg(feature = "core")]
+pub mod capabilities;
+pub mod caution;
+#[cfg(feature = "irelia")]
+pub mod collect;
+pub mod cycles;
+#[cfg(feature = "core")]
+pub mod compat;
+pub mod component_renames;
+#[cfg(test)]
+mod contract;
+pub mod deprecation;
+#[cfg(feature = "irelia")]
+pub mod discovery;
+pub mod drift;
+pub mod enums;
+pub mod envelope;
 pub mod help;
+pub mod help_diff;
+pub mod spec_diff;
+pub mod index;
+pub mod json_schema;
+pub mod latency;
+pub mod lenient;
+pub mod lint;
+pub mod migrate;
 pub mod error;
+pub mod example;
+#[cfg(feature = "core")]
+pub mod explain;
+#[cfg(feature = "core")]
+pub mod export;
+pub mod fallback;
+pub mod fragment;
 pub mod openapi;
+pub mod options;
 pub mod patch;
+#[cfg(feature = "irelia")]
+pub mod patch_day;
+pub mod patchset;
+#[cfg(feature = "core")]
+pub mod provenance;
+#[cfg(feature = "irelia")]
+pub mod recording;
+pub mod renames;
+pub mod report;
+pub mod routing;
+pub mod rules;
+pub mod samples;
+pub mod search;
+pub mod sanitize;
+pub mod servers;
+pub mod since;
+pub mod size;
+pub mod slug;
+pub mod stability;
+#[cfg(feature = "core")]
+pub mod style;
+pub mod tags;
+pub mod throttle;
+pub mod transform;
+pub mod version;
+pub mod visit;
+pub mod workflow;
+#[cfg(feature = "core")]
+pub mod convert;
 
 /// Pattern: `apply_patches!(to: $jsons, name_lens: $name_lens, patches: [ ($name, $($path, $value),*), ... ])`
 macro_rules! apply_patches {
@@ -34,6 +110,7 @@ macro_rules! apply_patches {
 }
 
 /// Create a new irelia client.
+#[cfg(feature = "irelia")]
 #[inline]
 pub fn lcu() -> Result<LcuClient<irelia::requests::RequestClientType>, Error> {
     let req = irelia::requests::new();
@@ -41,93 +118,26 @@ pub fn lcu() -> Result<LcuClient<irelia::requests::RequestClientType>, Error> {
     Ok(lcu)
 }
 
-pub trait PoroSchema {
-    /// Construct [`ExtendedHelp`] using the LCU API.
-    fn extended_help(
-        &self
-    ) -> impl std::future::Future<Output = Result<ExtendedHelp, Error>> + Send;
-
-    /// Construct [`OpenApiSpec`] using the LCU API.
-    fn openapi(&self) -> impl std::future::Future<Output = Result<OpenApiSpec, Error>> + Send;
-
-    // /// Construct [`Swagger`] using the LCU API.
-    // async fn swagger(&self) -> Result<Swagger, Error>;
-}
-
-impl<T: RequestClientTrait + Clone> PoroSchema
-    for LcuClient<T>
-    where error::Error: From<irelia::error::Error<<T as RequestClientTrait>::Error>>
-{
-    async fn extended_help(&self) -> Result<ExtendedHelp, Error> {
-        let help: Help = self.post("/help", "").await?;
-
-        // construct the extended help object
-        let mut full_types = Vec::<Type>::new();
-        let mut full_events = Vec::<Event>::new();
-        let mut full_endpoints = Vec::<serde_json::Value>::new();
-
-        // Get help for all types
-        for ty_name in help.types.keys() {
-            let endpoint = format!("/help?target={ty_name}&format=Full");
-            let SeqFirst::<Type>(full) = self.post(endpoint, "").await?;
-            full_types.push(full);
-        }
-
-        // Get help for all events
-        for ev_name in help.events.keys() {
-            let endpoint = format!("/help?target={ev_name}&format=Full");
-            let SeqFirst::<Event>(full) = self.post(endpoint, "").await?;
-            full_events.push(full);
-        }
-
-        // Get help for all endpoints
-        let reg = regex::Regex::new(r"\{(.*?)\}");
-        for fn_name in help.functions.keys() {
-            let endpoint = format!("/help?target={fn_name}&format=Full");
-            let SeqFirst::<Endpoint>(mut full) = self.post(endpoint, "").await?;
-
-            // Finish construction using data from console help.
-            {
-                let endpoint = format!("/help?target={fn_name}&format=Console");
-                let mut console: serde_json::Value = self.post(endpoint, "").await?;
-                let console = console
-                    .as_object_mut()
-                    .expect("Console endpoint response should be an object");
-
-                if let Some(console) = console.remove(fn_name) {
-                    let console: ConsoleEndpointInner = serde_json::from_value(console)?;
-                    full.path_params = if let Some(url) = console.url.as_ref() {
-                        reg.clone()
-                            .unwrap()
-                            .captures_iter(url.as_str())
-                            .map(|cap| cap[1].to_string())
-                            .collect::<Vec<_>>()
-                    } else {
-                        Vec::new()
-                    };
-                    full.path = console.url;
-                    full.method = console.http_method;
-                }
-            }
-            let full = serde_json::to_value(full)?;
-            full_endpoints.push(full);
-        }
-
-        // Apply endpoint patches
-        apply_patches!(
-        to: &mut full_endpoints,
+/// Give the builtin remoting endpoints, which have no console URL, a method and path,
+/// and apply the other endpoint fixes that the help data gets wrong.
+pub fn patch_builtin_endpoints(endpoints: &mut [serde_json::Value]) -> Result<(), Error> {
+    apply_patches!(
+        to: endpoints,
         patches: [
             ("Help", 
                 ("method", "post"), 
-                ("path", "/Help")
+                ("path", "/Help"),
+                ("requestContentType", "text/plain")
             ),
             ("Subscribe", 
                 ("method", "post"), 
-                ("path", "/Subscribe")
+                ("path", "/Subscribe"),
+                ("requestContentType", "text/plain")
             ),
             ("Unsubscribe", 
                 ("method", "post"), 
-                ("path", "/Unsubscribe")
+                ("path", "/Unsubscribe"),
+                ("requestContentType", "text/plain")
             ),
             ("AsyncDelete", 
                 ("method", "post"), 
@@ -179,53 +189,63 @@ impl<T: RequestClientTrait + Clone> PoroSchema
         ]
     );
 
-        let full_endpoints = full_endpoints
-            .into_iter()
-            .map(|json| {
-                match serde_json::from_value::<Endpoint>(json) {
-                    Ok(endpoint) => endpoint,
-                    Err(e) => {
-                        panic!("Something major changed in the API. Please report this error: {e}");
-                    }
-                }
-            })
-            .collect::<Vec<Endpoint>>();
-
-        println!("Total Types: {}", full_types.len());
-        println!("Total Endpoints: {}", full_endpoints.len());
-        println!("Total Events: {}", full_events.len());
-
-        Ok(ExtendedHelp {
-            types: full_types,
-            endpoints: full_endpoints,
-            events: full_events,
-        })
+    Ok(())
+}
+
+pub trait PoroSchema {
+    /// Construct [`ExtendedHelp`] using the LCU API.
+    fn extended_help(
+        &self
+    ) -> impl std::future::Future<Output = Result<ExtendedHelp, Error>> + Send;
+
+    /// Construct [`ExtendedHelp`] using the LCU API, paced by [`SchemaOptions::politeness`].
+    fn extended_help_with(
+        &self,
+        options: &SchemaOptions
+    ) -> impl std::future::Future<Output = Result<ExtendedHelp, Error>> + Send;
+
+    /// Construct [`OpenApiSpec`] using the LCU API.
+    fn openapi(&self) -> impl std::future::Future<Output = Result<OpenApiSpec, Error>> + Send;
+
+    // /// Construct [`Swagger`] using the LCU API.
+    // async fn swagger(&self) -> Result<Swagger, Error>;
+}
+
+#[cfg(feature = "irelia")]
+impl<T: RequestClientTrait + Clone> PoroSchema
+    for LcuClient<T>
+    where error::Error: From<irelia::error::Error<<T as RequestClientTrait>::Error>>
+{
+    async fn extended_help(&self) -> Result<ExtendedHelp, Error> {
+        self.extended_help_with(&SchemaOptions::default()).await
     }
 
-    async fn openapi(&self) -> Result<OpenApiSpec, Error> {
-        use serde_json::Value;
-        use serde::{ Deserialize, Serialize };
+    async fn extended_help_with(&self, options: &SchemaOptions) -> Result<ExtendedHelp, Error> {
+        let throttle = Mutex::new(Throttle::new(options.politeness.clone()));
+        let epoch = Instant::now();
+        let post = |endpoint: String| {
+            paced(&throttle, epoch, async move { Ok(self.post::<_, serde_json::Value>(endpoint, "").await?) })
+        };
+        let mut help = collect::extended_help_concurrent(post, options.mid_run, options.politeness.concurrency, &collect::default_bundle_dir()).await?;
+        let stats = throttle.into_inner().expect("throttle lock").stats();
+        help.report.push(ReportKind::Timing, "requests", stats.to_string());
 
-        #[derive(Deserialize)]
+        println!("Total Types: {}", help.types.len());
+        println!("Total Endpoints: {}", help.endpoints.len());
+        println!("Total Events: {}", help.events.len());
+
+        Ok(help)
+    }
+
+    async fn openapi(&self) -> Result<OpenApiSpec, Error> {
+        #[derive(serde::Deserialize)]
         struct Version {
             version: String,
         }
         let Version { version } = self.get("/system/v1/builds").await?;
+        let help = self.extended_help().await?;
 
-        let mut endpoints_with_missing_data = Vec::<String>::new();
-
-        let mut spec = OpenApiSpec {
-            openapi: "3.0.0".to_string(),
-            info: OpenApiInfo {
-                title: "LCU PORO-SCHEMA".to_string(),
-                description: "OpenAPI v3 specification for LCU".to_string(),
-                version,
-            },
-            components: serde_json::Map::new(),
-            paths: serde_json::Map::new(),
-        };
-
-        Ok(spec)
+        OpenApiSpec::from_help(&help, version)
     }
 }
 
@@ -235,27 +255,30 @@ pub fn swagger() -> Result<(), Error> {
 
 #[cfg(test)]
 mod tests {
-    use std::io::Write;
-
     use super::*;
 
-    fn w<T>(name: &str, value: T) -> Result<(), Error> where T: serde::Serialize {
-        let file = std::fs::File::create(name)?;
-        let mut writer = std::io::BufWriter::new(file);
-        serde_json::to_writer_pretty(&mut writer, &value)?;
-        writer.flush()?;
-        drop(writer);
-        Ok(())
+    /// The miniature extended help fixture shared by conversion tests.
+    pub(crate) fn miniature() -> ExtendedHelp {
+        poroshell_test_utils::fixtures::MINIATURE_HELP.parse().unwrap()
+    }
+
+    /// The spec converted from [`miniature`]. Mirrors
+    /// `poroshell_test_utils::fixtures::miniature_spec`, whose types are a
+    /// separate build of this crate and don't fit its own unit tests.
+    pub(crate) fn miniature_spec(version: &str) -> OpenApiSpec {
+        OpenApiSpec::from_help(&miniature(), version).unwrap()
     }
 
+    #[cfg(feature = "irelia")]
     #[tokio::test]
     #[ignore]
     async fn download_extended_help() {
         let lcu = lcu().unwrap();
         let xhelp = lcu.extended_help().await.unwrap();
-        w("extended-help.json", xhelp).unwrap();
+        std::fs::write("extended-help.json", migrate::to_string(migrate::Artifact::ExtendedHelp, &xhelp).unwrap()).unwrap();
     }
 
+    #[cfg(feature = "irelia")]
     #[tokio::test]
     #[ignore]
     async fn generate_openapi_v3() {
@@ -263,6 +286,77 @@ mod tests {
         lcu.openapi().await.unwrap();
     }
 
+    /// Record a session with the running client, for replaying as a fixture
+    /// with [`recording::ReplayClient`].
+    #[cfg(feature = "irelia")]
+    #[tokio::test]
+    #[ignore]
+    async fn record_extended_help() {
+        let lcu = lcu().unwrap();
+        let post = |endpoint: String| async { Ok(lcu.post::<_, serde_json::Value>(endpoint, "").await?) };
+        let recorder = recording::RecordingClient::wrap(post, std::path::Path::new("recordings/extended-help"));
+        let help = collect::extended_help(|endpoint| recorder.post(endpoint), Default::default(), &collect::default_bundle_dir()).await.unwrap();
+        println!("Recorded {} types, {} endpoints, {} events", help.types.len(), help.endpoints.len(), help.events.len());
+    }
+
+    /// The conversion core must build without any networking crates in the tree.
+    /// Checks into its own target directory, so it's left out of the default run:
+    /// `cargo test -p poro_schema -- --ignored check_core_without_network`.
+    #[test]
+    #[ignore = "builds the crate again"]
+    fn check_core_without_network() {
+        let cargo = env!("CARGO");
+        let target_dir = std::env::temp_dir().join("poro_schema-core-check");
+        let args = ["-p", "poro_schema", "--offline", "--no-default-features", "--features", "core"];
+
+        let status = std::process::Command
+            ::new(cargo)
+            .args(["check", "--quiet"])
+            .args(args)
+            .env("CARGO_TARGET_DIR", &target_dir)
+            .status()
+            .unwrap();
+        assert!(status.success());
+
+        let output = std::process::Command
+            ::new(cargo)
+            .args(["tree", "-e", "normal", "--prefix", "none"])
+            .args(args)
+            .output()
+            .unwrap();
+        assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+        let tree = String::from_utf8_lossy(&output.stdout);
+        for krate in ["irelia", "hyper", "tokio"] {
+            assert!(
+                !tree.lines().any(|line| line.starts_with(&format!("{krate} "))),
+                "{krate} should not be in the core dependency tree"
+            );
+        }
+    }
+
+    #[test]
+    fn test_from_str_without_runtime() {
+        let help = miniature();
+        let spec = OpenApiSpec::from_help(&help, "1.0.0").unwrap();
+        assert_eq!(spec.info.version, "1.0.0");
+        assert!(spec.paths.contains_key("/lol-summoner/v1/current-summoner"));
+    }
+
+    #[test]
+    fn test_patch_builtin_endpoints() -> Result<(), Error> {
+        let mut jsons = [
+            serde_json::json!({ "name": "Exit", "method": null, "path": null }),
+            serde_json::json!({ "name": "GetRiotclientRegionLocale", "tags": ["Plugin riotclient"] }),
+        ];
+        patch_builtin_endpoints(&mut jsons)?;
+
+        assert_eq!(jsons[0]["method"], "post");
+        assert_eq!(jsons[0]["path"], "/Exit");
+        assert_eq!(jsons[1]["tags"], serde_json::json!(["riotclient"]));
+
+        Ok(())
+    }
+
     #[test]
     fn test_apply_patches_macro() -> Result<(), Error> {
         let mut jsons = vec![
@@ -0,0 +1,130 @@
+//! Checks for output that is valid OpenAPI but shouldn't be published.
+
+use std::collections::BTreeSet;
+
+use serde_json::Value;
+
+use crate::{ openapi::OpenApiSpec, report::SchemaReport, stability::Stability, workflow::WORKFLOW_TAG_PREFIX };
+
+/// Dot paths of the objects under `value` whose `description` is an empty
+/// string, starting from `location`.
+pub fn empty_descriptions(value: &Value, location: &str) -> Vec<String> {
+    let mut found = Vec::new();
+    collect_empty(value, location.to_string(), &mut found);
+    found
+}
+
+/// Warn about every empty `description` left in the paths and components of
+/// `spec`. They come from help data copied without [`crate::openapi::non_empty`].
+pub fn lint(spec: &OpenApiSpec, report: &mut SchemaReport) {
+    let sections = spec.paths.iter().map(|(path, item)| (format!("paths.{path}"), item));
+    let sections = sections.chain(spec.components.iter().map(|(kind, value)| (format!("components.{kind}"), value)));
+    for (location, value) in sections {
+        for location in empty_descriptions(value, &location) {
+            report.warn(location, "has an empty description");
+        }
+    }
+}
+
+/// Warn about every experimental operation a workflow view lists, which
+/// readers of the view would take for a supported step.
+pub fn lint_experimental_workflows(spec: &OpenApiSpec, report: &mut SchemaReport) {
+    let experimental = spec.paths
+        .values()
+        .filter_map(Value::as_object)
+        .flat_map(|item| item.values())
+        .filter(|operation| Stability::of(operation) == Some(Stability::Experimental))
+        .filter_map(|operation| operation["operationId"].as_str())
+        .collect::<BTreeSet<&str>>();
+    for (workflow, ids) in spec.workflows.iter() {
+        for id in ids.iter().filter(|id| experimental.contains(id.as_str())) {
+            report.warn(format!("{WORKFLOW_TAG_PREFIX}{workflow}"), format!("lists {id}, which is experimental"));
+        }
+    }
+}
+
+fn collect_empty(value: &Value, location: String, found: &mut Vec<String>) {
+    match value {
+        Value::Object(object) => {
+            if object.get("description").and_then(Value::as_str) == Some("") {
+                found.push(location.clone());
+            }
+            for (key, value) in object.iter() {
+                collect_empty(value, format!("{location}.{key}"), found);
+            }
+        }
+        Value::Array(values) => {
+            for (i, value) in values.iter().enumerate() {
+                collect_empty(value, format!("{location}.{i}"), found);
+            }
+        }
+        _ => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+    use crate::{ openapi::Operation, options::SchemaOptions, report::ReportKind, stability::StabilityRules, tests::miniature, workflow::Workflows };
+
+    #[test]
+    fn test_no_empty_descriptions() {
+        // The miniature's help has empty descriptions on most arguments and fields.
+        assert!(poroshell_test_utils::fixtures::MINIATURE_HELP.contains("\"description\": \"\""));
+
+        let mut report = SchemaReport::default();
+        let spec = OpenApiSpec::from_help_with(&miniature(), "1.0.0", &SchemaOptions::default(), &mut report).unwrap();
+        let json = serde_json::to_string_pretty(&spec).unwrap();
+        assert_eq!(json.matches("\"description\": \"\"").count(), 0);
+        assert_eq!(report.of_kind(ReportKind::Warning).count(), 0);
+
+        // Built by hand, an empty description is still skipped.
+        let operation = Operation {
+            operation_id: "A".to_string(),
+            summary: None,
+            description: Some(String::new()),
+            tags: Vec::new(),
+            parameters: Vec::new(),
+            request_body: None,
+            responses: Default::default(),
+            servers: Vec::new(),
+            additional_fields: Default::default(),
+        };
+        assert!(serde_json::to_value(&operation).unwrap().get("description").is_none());
+    }
+
+    #[test]
+    fn test_lint() {
+        let mut spec = OpenApiSpec::from_help(&miniature(), "1.0.0").unwrap();
+        spec.paths["/lol-lobby/v2/lobby"]["post"]["description"] = json!("");
+        spec.components["schemas"]["LolSummonerSummoner"]["properties"]["puuid"]["description"] = json!("");
+
+        let mut report = SchemaReport::default();
+        lint(&spec, &mut report);
+        let warned = report.of_kind(ReportKind::Warning).map(|entry| entry.target.as_str()).collect::<Vec<&str>>();
+        assert_eq!(warned, ["paths./lol-lobby/v2/lobby.post", "components.schemas.LolSummonerSummoner.properties.puuid"]);
+
+        let serialized = serde_json::to_value(&spec).unwrap();
+        assert_eq!(empty_descriptions(&serialized, "spec").len(), 2);
+    }
+
+    #[test]
+    fn test_experimental_workflows() {
+        let workflows: Workflows = serde_json::from_value(json!({ "Lobby": { "paths": ["/lol-lobby"] } })).unwrap();
+        let rules = StabilityRules { plugins: vec!["lol-lobby".to_string()], ..Default::default() };
+        let convert = |rules: StabilityRules| {
+            let options = SchemaOptions { stability: Some(rules), workflows: Some(workflows.clone()), ..Default::default() };
+            let mut report = SchemaReport::default();
+            OpenApiSpec::from_help_with(&miniature(), "1.0.0", &options, &mut report).unwrap();
+            report.of_kind(ReportKind::Warning).map(|entry| entry.detail.clone()).collect::<Vec<String>>()
+        };
+
+        assert_eq!(convert(rules.clone()), Vec::<String>::new());
+        assert_eq!(convert(StabilityRules { warn_in_workflows: true, ..rules }), [
+            "lists DeleteLolLobbyV2Lobby, which is experimental",
+            "lists PostLolLobbyV2Lobby, which is experimental",
+        ]);
+    }
+}
@@ -0,0 +1,187 @@
+//! Loading artifacts written by older versions of poroshell.
+//!
+//! Saved files carry a [`FORMAT_VERSION`]. Adding a field doesn't need a new
+//! version, but renaming one or changing what it means does: bump the
+//! artifact's [`Artifact::current`] and register a [`Migration`] from the
+//! previous version, so files already on disk keep loading. Files from before
+//! versioning have no [`FORMAT_VERSION`] and count as version 1.
+
+use std::fmt;
+
+use serde::{ Serialize, de::DeserializeOwned };
+use serde_json::{ Value, json };
+
+use crate::error::Error;
+
+/// Key holding the format version at the top of every saved artifact.
+pub const FORMAT_VERSION: &str = "format_version";
+
+/// A kind of file that poroshell writes and reads back later.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Artifact {
+    /// A saved `extended-help.json`, see [`crate::help::ExtendedHelp`].
+    ExtendedHelp,
+    /// The list of files a run wrote, see [`crate::artifact::Manifest`].
+    Manifest,
+}
+
+impl Artifact {
+    pub const ALL: [Artifact; 2] = [Self::ExtendedHelp, Self::Manifest];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::ExtendedHelp => "extended-help.json",
+            Self::Manifest => "manifest",
+        }
+    }
+
+    /// The format version this build writes.
+    pub fn current(&self) -> u32 {
+        match self {
+            Self::ExtendedHelp => 2,
+            Self::Manifest => 1,
+        }
+    }
+
+    fn migrations(&self) -> &'static [Migration] {
+        match self {
+            Self::ExtendedHelp => &EXTENDED_HELP_MIGRATIONS,
+            Self::Manifest => &[],
+        }
+    }
+}
+
+/// Turns an artifact of format version `from` into version `from + 1`.
+#[derive(Debug, Clone, Copy)]
+pub struct Migration {
+    pub from: u32,
+    pub migrate: fn(Value) -> Value,
+}
+
+const EXTENDED_HELP_MIGRATIONS: [Migration; 1] = [Migration { from: 1, migrate: backfill_path_params }];
+
+/// Version 1 files may have endpoints without `pathParams`, from before
+/// console help was merged in. Version 2 always has them, taken from the path.
+fn backfill_path_params(mut help: Value) -> Value {
+    let endpoints = help
+        .get_mut("endpoints")
+        .and_then(Value::as_array_mut)
+        .into_iter()
+        .flatten()
+        .filter_map(Value::as_object_mut);
+    for endpoint in endpoints {
+        if endpoint.contains_key("pathParams") {
+            continue;
+        }
+        let path = endpoint.get("path").and_then(Value::as_str).unwrap_or_default();
+        let params = path
+            .split('{')
+            .skip(1)
+            .filter_map(|rest| rest.split_once('}'))
+            .map(|(param, _)| param.to_string())
+            .collect::<Vec<String>>();
+        endpoint.insert("pathParams".to_string(), json!(params));
+    }
+    help
+}
+
+/// An artifact that can't be brought to the current format.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MigrationError {
+    pub artifact: &'static str,
+    pub found: u32,
+    pub current: u32,
+}
+
+impl fmt::Display for MigrationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let Self { artifact, found, current } = self;
+        if found > current {
+            write!(f, "{artifact} has format version {found}, newer than the {current} this version reads; update poroshell")
+        } else {
+            write!(f, "{artifact} has format version {found}, which can't be migrated to {current}; regenerate it")
+        }
+    }
+}
+
+impl std::error::Error for MigrationError {}
+
+/// Bring `value` to the current format of `artifact`, one migration at a
+/// time. The result has no [`FORMAT_VERSION`].
+pub fn migrate(artifact: Artifact, mut value: Value) -> Result<Value, MigrationError> {
+    let found = match value.as_object_mut().and_then(|object| object.remove(FORMAT_VERSION)) {
+        Some(version) => version.as_u64().and_then(|version| u32::try_from(version).ok()).unwrap_or(0),
+        None => 1,
+    };
+    let current = artifact.current();
+    let error = MigrationError { artifact: artifact.name(), found, current };
+    if found > current {
+        return Err(error);
+    }
+    for version in found..current {
+        let Some(migration) = artifact.migrations().iter().find(|migration| migration.from == version) else {
+            return Err(error);
+        };
+        value = (migration.migrate)(value);
+    }
+    Ok(value)
+}
+
+/// Parse an artifact of any supported format version.
+pub fn load<T: DeserializeOwned>(artifact: Artifact, json: &str) -> Result<T, Error> {
+    let value = migrate(artifact, serde_json::from_str(json)?)?;
+    Ok(serde_json::from_value(value)?)
+}
+
+/// Pretty-print an artifact, stamped with its current [`FORMAT_VERSION`].
+pub fn to_string<T: Serialize>(artifact: Artifact, value: &T) -> Result<String, Error> {
+    let mut value = serde_json::to_value(value)?;
+    if let Some(object) = value.as_object_mut() {
+        object.insert(FORMAT_VERSION.to_string(), json!(artifact.current()));
+    }
+    Ok(serde_json::to_string_pretty(&value)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{ help::ExtendedHelp, tests::miniature };
+
+    #[test]
+    fn test_old_format_migrated() {
+        let written = to_string(Artifact::ExtendedHelp, &miniature()).unwrap();
+        assert!(written.contains("\"format_version\": 2"));
+
+        // The same help as an unversioned file without path parameters.
+        let mut old = serde_json::to_value(miniature()).unwrap();
+        for endpoint in old["endpoints"].as_array_mut().unwrap() {
+            endpoint.as_object_mut().unwrap().remove("pathParams");
+        }
+        let old = serde_json::to_string(&old).unwrap();
+        assert!(!old.contains("pathParams"));
+
+        let migrated: ExtendedHelp = load(Artifact::ExtendedHelp, &old).unwrap();
+        let native: ExtendedHelp = load(Artifact::ExtendedHelp, &written).unwrap();
+        assert_eq!(serde_json::to_value(&migrated).unwrap(), serde_json::to_value(&native).unwrap());
+        let by_id = migrated.endpoints.iter().find(|endpoint| endpoint.info.name == "GetLolSummonerV1SummonersById").unwrap();
+        assert_eq!(by_id.path_params, ["id"]);
+
+        // Saved help goes through the same loader.
+        let parsed: ExtendedHelp = old.parse().unwrap();
+        assert_eq!(serde_json::to_value(&parsed).unwrap(), serde_json::to_value(&native).unwrap());
+    }
+
+    #[test]
+    fn test_unmigratable() {
+        let newer = r#"{ "format_version": 3, "types": [], "endpoints": [], "events": [] }"#;
+        let Err(Error::Migration(err)) = load::<ExtendedHelp>(Artifact::ExtendedHelp, newer) else {
+            panic!("expected a migration error");
+        };
+        assert_eq!(err, MigrationError { artifact: "extended-help.json", found: 3, current: 2 });
+        assert_eq!(err.to_string(), "extended-help.json has format version 3, newer than the 2 this version reads; update poroshell");
+
+        let unknown = r#"{ "format_version": 0, "types": [], "endpoints": [], "events": [] }"#;
+        let err = migrate(Artifact::ExtendedHelp, serde_json::from_str(unknown).unwrap()).unwrap_err();
+        assert!(err.to_string().contains("format version 0, which can't be migrated to 2"), "{err}");
+    }
+}
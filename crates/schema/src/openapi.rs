@@ -1,17 +1,393 @@
-use serde::{ Deserialize, Serialize };
+use std::collections::BTreeMap;
+
+use serde::{ Deserialize, Deserializer, Serialize };
 use serde_json::Value;
 
-#[derive(Deserialize, Serialize)]
+/// `s`, unless it's empty. The help data has empty strings where there's no
+/// description, which the spec leaves out instead.
+pub fn non_empty(s: &str) -> Option<String> {
+    if s.is_empty() { None } else { Some(s.to_string()) }
+}
+
+/// Descriptions are skipped when missing or empty, whichever way they were built.
+fn is_none_or_empty(s: &Option<String>) -> bool {
+    s.as_deref().is_none_or(str::is_empty)
+}
+
+/// The `components` section, keyed by kind, e.g. `schemas`.
+pub type Components = serde_json::Map<String, Value>;
+
+/// The OpenAPI version specs are written in.
+pub const OPENAPI_VERSION: &str = "3.0.0";
+
+/// Top-level extension holding the websocket events, by name, see [`EventSchema`].
+pub const X_EVENTS: &str = "x-events";
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
 pub struct OpenApiSpec {
     pub openapi: String,
     pub info: OpenApiInfo,
-    pub components: serde_json::Map<String, Value>,
+    pub components: Components,
     pub paths: serde_json::Map<String, Value>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<Tag>,
+    /// Workflow names to their operationIds, see [`crate::workflow`].
+    #[serde(rename = "x-workflows", default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub workflows: BTreeMap<String, Vec<String>>,
+    /// Websocket event names to their [`EventSchema`]. OpenAPI has no place
+    /// for messages that aren't answers to a request.
+    #[serde(rename = "x-events", default, skip_serializing_if = "serde_json::Map::is_empty")]
+    pub events: serde_json::Map<String, Value>,
 }
 
-#[derive(Deserialize, Serialize)]
+impl OpenApiSpec {
+    pub fn new(info: OpenApiInfo) -> Self {
+        Self {
+            openapi: OPENAPI_VERSION.to_string(),
+            info,
+            components: serde_json::Map::new(),
+            paths: serde_json::Map::new(),
+            tags: Vec::new(),
+            workflows: BTreeMap::new(),
+            events: serde_json::Map::new(),
+        }
+    }
+
+    pub fn with_components(mut self, components: serde_json::Map<String, Value>) -> Self {
+        self.components = components;
+        self
+    }
+
+    pub fn with_paths(mut self, paths: serde_json::Map<String, Value>) -> Self {
+        self.paths = paths;
+        self
+    }
+
+    pub fn with_tags(mut self, tags: Vec<Tag>) -> Self {
+        self.tags = tags;
+        self
+    }
+
+    pub fn with_events(mut self, events: serde_json::Map<String, Value>) -> Self {
+        self.events = events;
+        self
+    }
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
 pub struct OpenApiInfo {
     pub title: String,
     pub description: String,
     pub version: String,
 }
+
+impl OpenApiInfo {
+    /// Info block used for specs generated from the LCU.
+    pub fn lcu(version: impl Into<String>) -> Self {
+        Self {
+            title: "LCU PORO-SCHEMA".to_string(),
+            description: "OpenAPI v3 specification for LCU".to_string(),
+            version: version.into(),
+        }
+    }
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Tag {
+    pub name: String,
+    /// See [`crate::tags`].
+    #[serde(rename = "x-display-name", default, skip_serializing_if = "Option::is_none")]
+    pub display_name: Option<String>,
+}
+
+impl Tag {
+    /// A tag without a display name.
+    pub fn new(name: impl Into<String>) -> Self {
+        Self { name: name.into(), display_name: None }
+    }
+}
+
+/// A JSON schema as emitted into `components` and operations.
+#[derive(Deserialize, Serialize, Debug, Clone, Default, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct SchemaObject {
+    #[serde(rename = "$ref", default, skip_serializing_if = "Option::is_none")]
+    pub reference: Option<String>,
+    #[serde(rename = "type", default, skip_serializing_if = "Option::is_none")]
+    pub ty: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub format: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub minimum: Option<serde_json::Number>,
+    #[serde(default, skip_serializing_if = "is_none_or_empty")]
+    pub description: Option<String>,
+    #[serde(rename = "enum", default, skip_serializing_if = "Option::is_none")]
+    pub enum_values: Option<Vec<Value>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub properties: Option<BTreeMap<String, SchemaObject>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub required: Option<Vec<String>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub items: Option<Box<SchemaObject>>,
+    /// `None` leaves it unspecified, which OpenAPI reads as allowing any properties.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub additional_properties: Option<AdditionalProperties>,
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub deprecated: bool,
+    /// Vendor extensions (`x-*`) and any keyword not modeled above.
+    #[serde(flatten)]
+    pub additional_fields: BTreeMap<String, Value>,
+}
+
+impl SchemaObject {
+    /// A reference to a schema in `components.schemas`.
+    pub fn reference(name: &str) -> Self {
+        Self { reference: Some(format!("#/components/schemas/{name}")), ..Default::default() }
+    }
+
+    pub fn of_type(ty: &str) -> Self {
+        Self { ty: Some(ty.to_string()), ..Default::default() }
+    }
+
+    /// An object schema that does or does not allow arbitrary properties.
+    pub fn object_of(additional: bool) -> Self {
+        Self {
+            ty: Some("object".to_string()),
+            additional_properties: Some(AdditionalProperties::Bool(additional)),
+            ..Default::default()
+        }
+    }
+
+    pub fn array_of(items: SchemaObject) -> Self {
+        Self {
+            ty: Some("array".to_string()),
+            items: Some(Box::new(items)),
+            ..Default::default()
+        }
+    }
+
+    pub fn map_of(values: SchemaObject) -> Self {
+        Self {
+            ty: Some("object".to_string()),
+            additional_properties: Some(AdditionalProperties::Schema(Box::new(values))),
+            ..Default::default()
+        }
+    }
+
+    /// Whether the schema is marked deprecated. Schemas built before the flag
+    /// was modeled kept it in `additional_fields`, which still counts.
+    pub fn is_deprecated(&self) -> bool {
+        self.deprecated || self.additional_fields.get("deprecated") == Some(&Value::Bool(true))
+    }
+
+    /// Name of the referenced component, if this schema is a `$ref`.
+    pub fn reference_name(&self) -> Option<&str> {
+        self.reference.as_deref().and_then(|r| r.strip_prefix("#/components/schemas/"))
+    }
+}
+
+/// An explicit `additionalProperties`: allowed or not, or the schema of their values.
+/// An empty schema, `{}`, allows anything and is read as `true`.
+#[derive(Serialize, Debug, Clone, PartialEq)]
+#[serde(untagged)]
+pub enum AdditionalProperties {
+    Bool(bool),
+    Schema(Box<SchemaObject>),
+}
+
+impl<'de> Deserialize<'de> for AdditionalProperties {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error> where D: Deserializer<'de> {
+        #[derive(Deserialize)]
+        #[serde(untagged)]
+        enum Written {
+            Bool(bool),
+            Schema(Box<SchemaObject>),
+        }
+
+        Ok(match Written::deserialize(deserializer)? {
+            Written::Bool(allowed) => Self::Bool(allowed),
+            Written::Schema(schema) if *schema == SchemaObject::default() => Self::Bool(true),
+            Written::Schema(schema) => Self::Schema(schema),
+        })
+    }
+}
+
+impl Default for AdditionalProperties {
+    fn default() -> Self {
+        Self::Bool(false)
+    }
+}
+
+/// Every map in an operation is ordered, so its serialization depends only on
+/// its contents and not on the order it was built in.
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct Operation {
+    pub operation_id: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub summary: Option<String>,
+    #[serde(default, skip_serializing_if = "is_none_or_empty")]
+    pub description: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub parameters: Vec<Parameter>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub request_body: Option<RequestBody>,
+    pub responses: BTreeMap<String, Response>,
+    /// Where to send this operation instead of the client's own server.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub servers: Vec<ServerSpec>,
+    #[serde(flatten)]
+    pub additional_fields: BTreeMap<String, Value>,
+}
+
+/// A websocket event, e.g. `OnJsonApiEvent_lol-lobby_v2_lobby`, and the
+/// payload it carries.
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+pub struct EventSchema {
+    #[serde(default, skip_serializing_if = "is_none_or_empty")]
+    pub description: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<String>,
+    /// A generic object for events the help gives no type.
+    pub schema: SchemaObject,
+}
+
+/// A server, with `{name}` variables in its URL declared in `variables`.
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Eq)]
+pub struct ServerSpec {
+    pub url: String,
+    #[serde(default, skip_serializing_if = "is_none_or_empty")]
+    pub description: Option<String>,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub variables: BTreeMap<String, ServerVariable>,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Eq)]
+pub struct ServerVariable {
+    pub default: String,
+    #[serde(default, skip_serializing_if = "is_none_or_empty")]
+    pub description: Option<String>,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[serde(rename_all = "lowercase")]
+pub enum ParameterLocation {
+    Path,
+    Query,
+    Header,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+pub struct Parameter {
+    pub name: String,
+    #[serde(rename = "in")]
+    pub location: ParameterLocation,
+    #[serde(default)]
+    pub required: bool,
+    #[serde(default, skip_serializing_if = "is_none_or_empty")]
+    pub description: Option<String>,
+    pub schema: SchemaObject,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+pub struct RequestBody {
+    #[serde(default, skip_serializing_if = "is_none_or_empty")]
+    pub description: Option<String>,
+    #[serde(default)]
+    pub required: bool,
+    pub content: BTreeMap<String, MediaType>,
+}
+
+impl RequestBody {
+    pub fn with_content(content_type: &str, schema: SchemaObject, required: bool) -> Self {
+        Self {
+            description: None,
+            required,
+            content: BTreeMap::from([(content_type.to_string(), MediaType { schema, example: None })]),
+        }
+    }
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+pub struct MediaType {
+    pub schema: SchemaObject,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub example: Option<Value>,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+pub struct Response {
+    pub description: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub content: Option<BTreeMap<String, MediaType>>,
+}
+
+impl Response {
+    pub fn json(description: &str, schema: SchemaObject) -> Self {
+        Self {
+            description: description.to_string(),
+            content: Some(
+                BTreeMap::from([("application/json".to_string(), MediaType { schema, example: None })])
+            ),
+        }
+    }
+
+    pub fn empty(description: &str) -> Self {
+        Self { description: description.to_string(), content: None }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn media(ty: &str) -> MediaType {
+        MediaType { schema: SchemaObject::of_type(ty), example: None }
+    }
+
+    /// An operation with several responses and media types, added in `order`.
+    fn operation(order: &[usize]) -> Operation {
+        let media_types = [("application/json", "object"), ("application/octet-stream", "string"), ("text/plain", "string")];
+        let statuses = ["200", "204", "404"];
+        let mut content = BTreeMap::new();
+        let mut responses = BTreeMap::new();
+        let mut additional_fields = BTreeMap::new();
+        for &i in order {
+            let (media_type, ty) = media_types[i];
+            content.insert(media_type.to_string(), media(ty));
+            let response = Response { description: statuses[i].to_string(), content: Some(BTreeMap::from([(media_type.to_string(), media(ty))])) };
+            responses.insert(statuses[i].to_string(), response);
+            additional_fields.insert(format!("x-{i}"), Value::from(i));
+        }
+        Operation {
+            operation_id: "A".to_string(),
+            summary: None,
+            description: None,
+            tags: Vec::new(),
+            parameters: Vec::new(),
+            request_body: Some(RequestBody { description: None, required: true, content }),
+            responses,
+            servers: Vec::new(),
+            additional_fields,
+        }
+    }
+
+    #[test]
+    fn test_serialization_independent_of_construction_order() {
+        let forward = serde_json::to_vec(&operation(&[0, 1, 2])).unwrap();
+        for order in [[2, 1, 0], [1, 2, 0], [0, 2, 1]] {
+            assert_eq!(serde_json::to_vec(&operation(&order)).unwrap(), forward, "{order:?}");
+        }
+
+        let paths = |order: &[usize]| {
+            let mut spec = OpenApiSpec::new(OpenApiInfo::lcu("1.0.0"));
+            for &i in order {
+                spec.paths.insert(format!("/{i}"), serde_json::json!({ "get": operation(&[2, 0, 1]) }));
+            }
+            serde_json::to_string(&spec).unwrap()
+        };
+        assert_eq!(paths(&[0, 1, 2]), paths(&[2, 0, 1]));
+    }
+}
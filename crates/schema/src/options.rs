@@ -0,0 +1,132 @@
+use crate::{
+    caution::CautionRules,
+    component_renames::ComponentRenames,
+    drift::DriftCheck,
+    envelope::EnvelopeRules,
+    example::RequestExamples,
+    samples::CodeSamples,
+    servers::ServerRules,
+    since::RunHistory,
+    stability::StabilityRules,
+    throttle::Politeness,
+    transform::{ InlineSmallComponents, Int64AsString },
+    workflow::Workflows,
+};
+
+/// Marker the LCU uses in place of types that are not exposed through its public API.
+pub const PRIVATE_API_MARKER: &str = "PrivateApiTypeNotSupported";
+
+/// How types the LCU marks as private are emitted.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum PrivacyPolicy {
+    /// Skip private types and replace references to them with generic objects.
+    #[default]
+    Obscure,
+    /// Emit everything the help data provides.
+    Include,
+    /// Emit private types with every property replaced by a generic object
+    /// and an `x-redacted: true` marker.
+    Redact,
+}
+
+/// Whether object components claim to reject properties they don't list.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum UnknownFields {
+    /// `additionalProperties: false` on every object component.
+    #[default]
+    Closed,
+    /// Leave `additionalProperties` out, so OpenAPI's default of allowing them
+    /// applies. The LCU does send fields its help doesn't list, and models
+    /// generated with strict deserialization then fail on real payloads.
+    Unspecified,
+}
+
+/// What happens to targets that appear in the `/help` listing while help is
+/// being fetched, see [`crate::collect`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum MidRunTargets {
+    /// Fetch them too, in a second pass.
+    #[default]
+    Fetch,
+    /// Leave them out and list them in the report, for no extra requests.
+    Report,
+}
+
+/// Options for converting help data into a spec.
+#[derive(Debug, Clone)]
+pub struct SchemaOptions {
+    pub privacy: PrivacyPolicy,
+    /// Applies to components made from help types. Maps and generic objects
+    /// always say what their values are.
+    pub unknown_fields: UnknownFields,
+    /// Add `x-*` annotations that are derived from the finished spec,
+    /// such as which operations use each component.
+    pub include_extensions: bool,
+    /// Longest console help text kept on an operation, in characters.
+    /// Longer text is cut off with an ellipsis.
+    pub max_console_help: usize,
+    /// Write 64-bit integers as strings, for clients that would lose precision.
+    pub int64_as_string: Option<Int64AsString>,
+    /// Inline tiny components into the places that use them.
+    pub inline_components: Option<InlineSmallComponents>,
+    /// Mark paging wrappers and share one handle component between the
+    /// builtin async endpoints.
+    pub envelopes: Option<EnvelopeRules>,
+    /// Past runs, used to stamp operations and components with `x-since`,
+    /// and with `x-previous-slugs` when [`SchemaOptions::slugs`] is on.
+    pub history: Option<RunHistory>,
+    /// Rules for marking risky operations with `x-caution`.
+    pub caution: Option<CautionRules>,
+    /// Rules for marking unfinished operations `x-stability: experimental`.
+    /// Operations new since the [`SchemaOptions::history`] count as unfinished.
+    pub stability: Option<StabilityRules>,
+    /// Rules for giving operations the Riot Client hosts their own `servers`.
+    pub servers: Option<ServerRules>,
+    /// Workflow views, emitted as `Workflow:` tags and an `x-workflows` index.
+    pub workflows: Option<Workflows>,
+    /// Pacing for help requests to a live client.
+    pub politeness: Politeness,
+    /// Targets that appear while help is fetched from a live client.
+    pub mid_run: MidRunTargets,
+    /// Synthetic request body examples. Only [`crate::example::ExampleMode::Inline`]
+    /// changes the spec; sidecar examples are collected from the finished spec.
+    pub request_examples: Option<RequestExamples>,
+    /// Request samples as `x-codeSamples`, for documentation renderers.
+    pub code_samples: Option<CodeSamples>,
+    /// Fail when the spec lost too much of a previous run's.
+    pub drift: Option<DriftCheck>,
+    /// New names for components, applied once everything else that goes by
+    /// component name has run.
+    pub component_renames: Option<ComponentRenames>,
+    /// Stamp operations and components with `x-slug` anchors for deep links.
+    pub slugs: bool,
+    /// Fail on the first fallback instead of recording it in the report.
+    pub strict: bool,
+}
+
+impl Default for SchemaOptions {
+    fn default() -> Self {
+        Self {
+            privacy: PrivacyPolicy::default(),
+            unknown_fields: UnknownFields::default(),
+            include_extensions: false,
+            max_console_help: 1000,
+            int64_as_string: None,
+            inline_components: None,
+            envelopes: None,
+            history: None,
+            caution: None,
+            stability: None,
+            servers: None,
+            workflows: None,
+            politeness: Politeness::default(),
+            mid_run: MidRunTargets::default(),
+            request_examples: None,
+            code_samples: None,
+            drift: None,
+            component_renames: None,
+            slugs: false,
+            strict: false,
+        }
+    }
+}
@@ -147,6 +147,15 @@ impl Patch for Value {
                         );
                     }
                 }
+                DotToken::Index(index) if current.is_object() => {
+                    // Numeric keys, like response codes, are properties of objects
+                    let obj = current.as_object_mut().unwrap();
+                    if tokens.peek().is_none() {
+                        obj.insert(index.to_string(), value);
+                        return Ok(());
+                    }
+                    current = obj.entry(index.to_string()).or_insert_with(|| Value::Object(serde_json::Map::new()));
+                }
                 DotToken::Index(index) => {
                     // Check if the current value is an array
                     if let Value::Array(arr) = current {
@@ -290,6 +299,19 @@ mod test {
         );
     }
 
+    #[test]
+    fn test_patch_numeric_key() {
+        let mut json = serde_json::json!({ "responses": { "200": { "description": "" } } });
+        json.patch_mut("responses.200.description", serde_json::json!("OK")).unwrap();
+        json.patch_mut("responses.404", serde_json::json!({ "description": "Not found" })).unwrap();
+        assert_eq!(
+            json,
+            serde_json::json!({
+                "responses": { "200": { "description": "OK" }, "404": { "description": "Not found" } }
+            })
+        );
+    }
+
     #[test]
     fn test_dot_path_patch_error() {
         let mut json =
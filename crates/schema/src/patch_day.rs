@@ -0,0 +1,412 @@
+//! The patch-day run: everything the poroschema PR needs after a patch, from
+//! one fresh generation.
+//!
+//! Each [`Stage`] writes its outputs into a staging directory as soon as it
+//! finishes. Once every stage has passed, the staging directory is renamed
+//! into place, so a published set is always complete. When a stage fails the
+//! run stops there, and the staging directory keeps what the earlier stages
+//! wrote for a look at what went wrong.
+
+use std::{ collections::BTreeMap, fmt, fs, future::Future, io, path::{ Path, PathBuf } };
+
+use serde_json::{ Map, Value };
+
+use crate::{
+    artifact::{ ArtifactWriter, OutputTemplate, TemplateVars },
+    convert::PLUGINS_TAG,
+    drift::{ DriftGate, SpecCounts },
+    error::Error,
+    export::{ postman, postman_environment, PostmanOptions },
+    help::ExtendedHelp,
+    index::reachable,
+    migrate::{ self, Artifact },
+    openapi::{ OpenApiSpec, Tag },
+    options::SchemaOptions,
+    renames::{ self, ParameterRename, RenameDetection },
+    report::SchemaReport,
+    since::RunRecord,
+    style::SerializationStyle,
+};
+
+/// The steps of a patch-day run, in order.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Stage {
+    /// Fetch the help and convert it, marking parameters renamed since the baseline.
+    Generate,
+    /// Check the spec didn't shrink too much since the baseline, see [`DriftGate`].
+    Gate,
+    /// Write the changelog against the baseline.
+    Diff,
+    /// Write a spec per plugin, see [`plugin_specs`].
+    Extract,
+    /// Write the Postman collection and environment.
+    Export,
+    /// Write the report, summary, and manifest, and move the set into place.
+    Commit,
+}
+
+impl Stage {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Generate => "generate",
+            Self::Gate => "gate",
+            Self::Diff => "diff",
+            Self::Extract => "extract",
+            Self::Export => "export",
+            Self::Commit => "commit",
+        }
+    }
+}
+
+impl fmt::Display for Stage {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+/// A patch-day run that stopped at `stage`.
+#[derive(Debug)]
+pub struct PatchDayError {
+    pub stage: Stage,
+    /// Holds everything the stages before `stage` wrote.
+    pub staging: PathBuf,
+    pub source: Error,
+}
+
+impl fmt::Display for PatchDayError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "patch day stopped at {}: {}; earlier outputs are in {}", self.stage, self.source, self.staging.display())
+    }
+}
+
+impl std::error::Error for PatchDayError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        Some(&self.source)
+    }
+}
+
+/// What a finished run published, and how the spec changed since the baseline.
+#[derive(Debug, Clone)]
+pub struct PatchDaySummary {
+    pub version: String,
+    /// The directory the set was moved to.
+    pub published: PathBuf,
+    /// Counts of the baseline, when there was one.
+    pub before: Option<SpecCounts>,
+    pub after: SpecCounts,
+    /// How many plugin specs were extracted.
+    pub plugins: usize,
+    pub report: SchemaReport,
+}
+
+impl PatchDaySummary {
+    pub fn to_markdown(&self) -> String {
+        let mut markdown = format!("# Patch day {}\n\n{}\n\n", self.version, self.report.summary_line());
+        markdown.push_str("| | Before | After | Change |\n|---|---|---|---|\n");
+        for (name, after, before) in [
+            ("Operations", self.after.operations, self.before.as_ref().map(|before| before.operations)),
+            ("Components", self.after.components, self.before.as_ref().map(|before| before.components)),
+        ] {
+            let (before, change) = match before {
+                Some(before) => (before.to_string(), format!("{:+}", (after as i64) - (before as i64))),
+                None => ("-".to_string(), "-".to_string()),
+            };
+            markdown.push_str(&format!("| {name} | {before} | {after} | {change} |\n"));
+        }
+        markdown.push_str(&format!("\n{} plugin specs extracted.\n", self.plugins));
+        markdown
+    }
+}
+
+/// Runs every [`Stage`] and publishes the set at `root/<version>`.
+#[derive(Debug, Clone, Default)]
+pub struct PatchDay {
+    pub root: PathBuf,
+    /// Used for the conversion. Gating uses [`Self::gate`] against
+    /// [`Self::baseline`] instead of [`SchemaOptions::drift`].
+    pub options: SchemaOptions,
+    pub gate: DriftGate,
+    /// The last published spec, to gate and diff against. Without one both
+    /// stages pass with nothing to compare.
+    pub baseline: Option<OpenApiSpec>,
+    /// Which parameters count as renamed since [`Self::baseline`].
+    pub renames: RenameDetection,
+    pub postman: PostmanOptions,
+}
+
+impl PatchDay {
+    pub fn new(root: impl Into<PathBuf>) -> Self {
+        Self { root: root.into(), ..Default::default() }
+    }
+
+    /// Where a run for `version` stages its outputs. Cleared when the run starts.
+    pub fn staging_dir(&self, version: &str) -> PathBuf {
+        self.root.join(format!(".staging-{version}"))
+    }
+
+    /// Run every stage for `version`, with `help` generating the help data,
+    /// usually [`crate::PoroSchema::extended_help_with`] on a live client.
+    pub async fn run<Fut>(&self, help: Fut, version: &str) -> Result<PatchDaySummary, PatchDayError>
+        where Fut: Future<Output = Result<ExtendedHelp, Error>>
+    {
+        let staging = self.staging_dir(version);
+        let at = |stage: Stage| {
+            let staging = staging.clone();
+            move |source: Error| PatchDayError { stage, staging, source }
+        };
+        if let Err(err) = fs::remove_dir_all(&staging) && err.kind() != io::ErrorKind::NotFound {
+            return Err(at(Stage::Generate)(err.into()));
+        }
+
+        let vars = TemplateVars { version: version.to_string(), target: "lcu".to_string(), ..Default::default() };
+        let mut writer = ArtifactWriter::new(&staging, vars);
+        let mut report = SchemaReport::default();
+
+        let (spec, renamed) = self.generate(help.await, version, &mut writer, &mut report).map_err(at(Stage::Generate))?;
+        let after = SpecCounts::from_spec(&spec);
+        let before = self.baseline.as_ref().map(SpecCounts::from_spec);
+
+        if let Some(before) = before.as_ref() {
+            self.gate.check(before, &after, &mut report).map_err(|err| at(Stage::Gate)(err.into()))?;
+        }
+
+        let changelog = match self.baseline.as_ref() {
+            Some(baseline) => RunRecord::from_spec(baseline).changelog(&RunRecord::from_spec(&spec)) + &renames::changelog(&renamed),
+            None => format!("# {version}\n\nNo baseline to compare against.\n"),
+        };
+        write(&mut writer, "{target}/changelog.md", &changelog).map_err(at(Stage::Diff))?;
+
+        let plugins = plugin_specs(&spec);
+        for (plugin, plugin_spec) in plugins.iter() {
+            let json = serde_json::to_string_pretty(plugin_spec).map_err(|err| at(Stage::Extract)(err.into()))?;
+            write(&mut writer, &format!("{{target}}/plugins/{plugin}.json"), &json).map_err(at(Stage::Extract))?;
+        }
+
+        let mut export = || -> Result<(), Error> {
+            write(&mut writer, "{target}/postman_collection.json", &serde_json::to_string_pretty(&postman(&spec, &self.postman))?)?;
+            write(&mut writer, "{target}/postman_environment.json", &serde_json::to_string_pretty(&postman_environment(&self.postman))?)?;
+            Ok(())
+        };
+        export().map_err(at(Stage::Export))?;
+
+        let summary = PatchDaySummary {
+            version: version.to_string(),
+            published: self.root.join(version),
+            before,
+            after,
+            plugins: plugins.len(),
+            report,
+        };
+        commit(&mut writer, &summary, &staging).map_err(at(Stage::Commit))?;
+        Ok(summary)
+    }
+
+    /// Convert the help, mark renamed parameters, and write the help and the spec.
+    fn generate(
+        &self,
+        help: Result<ExtendedHelp, Error>,
+        version: &str,
+        writer: &mut ArtifactWriter,
+        report: &mut SchemaReport
+    ) -> Result<(OpenApiSpec, Vec<ParameterRename>), Error> {
+        let help = help?;
+        report.entries.extend(help.report.entries.iter().cloned());
+        write(writer, "{target}/extended-help.json", &migrate::to_string(Artifact::ExtendedHelp, &help)?)?;
+        let mut spec = OpenApiSpec::from_help_with(&help, version, &self.options, report)?;
+        let renamed = match self.baseline.as_ref() {
+            Some(baseline) => self.renames.detect(baseline, &spec),
+            None => Vec::new(),
+        };
+        renames::annotate(&renamed, &mut spec);
+        write_spec(writer, "{target}/openapi.json", &spec)?;
+        Ok((spec, renamed))
+    }
+}
+
+/// Write the report, summary, and manifest, then move `staging` to the
+/// summary's published directory. An existing directory there is left alone.
+fn commit(writer: &mut ArtifactWriter, summary: &PatchDaySummary, staging: &Path) -> Result<(), Error> {
+    write(writer, "{target}/report.md", &summary.report.to_markdown())?;
+    write(writer, "{target}/report.json", &serde_json::to_string_pretty(&summary.report)?)?;
+    write(writer, "summary.md", &summary.to_markdown())?;
+    writer.write_manifest(&OutputTemplate::parse("manifest.json")?)?;
+    if summary.published.exists() {
+        return Err(io::Error::new(io::ErrorKind::AlreadyExists, format!("{} already exists", summary.published.display())).into());
+    }
+    fs::rename(staging, &summary.published)?;
+    Ok(())
+}
+
+fn write(writer: &mut ArtifactWriter, template: &str, contents: &str) -> Result<PathBuf, Error> {
+    writer.write(&OutputTemplate::parse(template)?, contents)
+}
+
+/// Specs can run to tens of megabytes, so they're streamed instead.
+fn write_spec(writer: &mut ArtifactWriter, template: &str, spec: &OpenApiSpec) -> Result<PathBuf, Error> {
+    writer.write_streaming(&OutputTemplate::parse(template)?, |out| spec.write_streaming(out, SerializationStyle::Standard))
+}
+
+/// A spec per plugin, holding the operations and events tagged with its name,
+/// the components they use, and their tags. Operations outside a plugin, like
+/// the builtin ones, aren't in any.
+pub fn plugin_specs(spec: &OpenApiSpec) -> BTreeMap<String, OpenApiSpec> {
+    let mut paths = BTreeMap::<String, Map<String, Value>>::new();
+    for (path, item) in spec.paths.iter() {
+        for (method, operation) in item.as_object().into_iter().flatten() {
+            let tags = operation["tags"].as_array().into_iter().flatten().filter_map(Value::as_str).collect::<Vec<&str>>();
+            if !tags.contains(&PLUGINS_TAG) {
+                continue;
+            }
+            for plugin in tags.into_iter().filter(|tag| *tag != PLUGINS_TAG) {
+                let item = paths.entry(plugin.to_string()).or_default().entry(path.clone()).or_insert_with(|| Value::Object(Map::new()));
+                item[method] = operation.clone();
+            }
+        }
+    }
+
+    let schemas = spec.components.get("schemas").and_then(Value::as_object);
+    paths
+        .into_iter()
+        .map(|(plugin, paths)| {
+            let events = spec.events
+                .iter()
+                .filter(|(_, event)| event["tags"].as_array().is_some_and(|tags| tags.contains(&Value::from(plugin.as_str()))))
+                .map(|(name, event)| (name.clone(), event.clone()))
+                .collect::<Map<String, Value>>();
+            let mut used = reachable(spec, &Value::Object(paths.clone()));
+            used.extend(reachable(spec, &Value::Object(events.clone())));
+            let schemas = schemas
+                .into_iter()
+                .flatten()
+                .filter(|(name, _)| used.contains(*name))
+                .map(|(name, schema)| (name.clone(), schema.clone()))
+                .collect::<Map<String, Value>>();
+            let operations = paths.values().filter_map(Value::as_object).flat_map(|item| item.values());
+            let used_tags = operations
+                .flat_map(|operation| operation["tags"].as_array().into_iter().flatten())
+                .filter_map(Value::as_str)
+                .collect::<Vec<&str>>();
+            let tags = spec.tags.iter().filter(|tag| used_tags.contains(&tag.name.as_str())).cloned().collect::<Vec<Tag>>();
+
+            let components = Map::from_iter([("schemas".to_string(), Value::Object(schemas))]);
+            let plugin_spec = OpenApiSpec::new(spec.info.clone()).with_paths(paths).with_components(components).with_tags(tags).with_events(events);
+            (plugin, plugin_spec)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use poroshell_test_utils::{ assert_valid_openapi, HelpBuilder, MockPoroClient };
+
+    use crate::{ collect, options::MidRunTargets, tests::miniature };
+
+    fn temp_root(name: &str) -> PathBuf {
+        let root = std::env::temp_dir().join(format!("poroshell-patch-day-{name}-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&root);
+        root
+    }
+
+    /// The spec of the miniature without its lobby creation endpoint, and
+    /// with the summoner search's `name` still called `summonerName`, as the
+    /// last patch's.
+    fn baseline() -> OpenApiSpec {
+        let mut help = miniature();
+        help.endpoints.retain(|endpoint| endpoint.info.name != "PostLolLobbyV2Lobby");
+        let mut spec = OpenApiSpec::from_help(&help, "14.3.1").unwrap();
+        spec.paths["/lol-summoner/v1/summoners"]["get"]["parameters"][0]["name"] = "summonerName".into();
+        spec
+    }
+
+    fn relative_files(dir: &Path) -> Vec<String> {
+        let mut files = Vec::new();
+        for entry in fs::read_dir(dir).unwrap().map(Result::unwrap) {
+            let name = entry.file_name().to_string_lossy().to_string();
+            if entry.file_type().unwrap().is_dir() {
+                files.extend(relative_files(&entry.path()).into_iter().map(|file| format!("{name}/{file}")));
+            } else {
+                files.push(name);
+            }
+        }
+        files.sort();
+        files
+    }
+
+    #[tokio::test]
+    async fn test_patch_day() {
+        let root = temp_root("full");
+        let client = MockPoroClient::new(HelpBuilder::miniature().json());
+        let patch_day = PatchDay { baseline: Some(baseline()), ..PatchDay::new(&root) };
+        let bundles = root.join("bundles");
+        let help = collect::extended_help(|endpoint| client.post(endpoint), MidRunTargets::Fetch, &bundles);
+        let summary = patch_day.run(help, "14.4.1").await.unwrap();
+
+        assert_eq!(summary.published, root.join("14.4.1"));
+        assert!(!patch_day.staging_dir("14.4.1").exists());
+        assert_eq!(
+            relative_files(&summary.published),
+            [
+                "lcu/changelog.md",
+                "lcu/extended-help.json",
+                "lcu/openapi.json",
+                "lcu/plugins/lol-lobby.json",
+                "lcu/plugins/lol-summoner.json",
+                "lcu/postman_collection.json",
+                "lcu/postman_environment.json",
+                "lcu/report.json",
+                "lcu/report.md",
+                "manifest.json",
+                "summary.md",
+            ]
+        );
+
+        let changelog = fs::read_to_string(summary.published.join("lcu/changelog.md")).unwrap();
+        assert!(changelog.starts_with("# 14.3.1 -> 14.4.1\n## Operations: 1 added, 0 removed\n+ PostLolLobbyV2Lobby\n"), "{changelog}");
+        assert!(
+            changelog.ends_with(
+                "## Renamed parameters: 1\n~ GetLolSummonerV1Summoners: parameter `summonerName` renamed to `name` (schema unchanged)\n"
+            ),
+            "{changelog}"
+        );
+        let published: serde_json::Value = serde_json::from_str(&fs::read_to_string(summary.published.join("lcu/openapi.json")).unwrap()).unwrap();
+        assert_valid_openapi(&published);
+        assert_eq!(published["paths"]["/lol-summoner/v1/summoners"]["get"]["parameters"][0]["x-renamed-from"], "summonerName");
+        let lobby: OpenApiSpec = serde_json::from_str(&fs::read_to_string(summary.published.join("lcu/plugins/lol-lobby.json")).unwrap()).unwrap();
+        assert!(lobby.paths.keys().all(|path| path.starts_with("/lol-lobby/")));
+        assert!(lobby.components["schemas"].get("LolLobbyLobbyChangeGameDto").is_some());
+        assert!(lobby.components["schemas"].get("LolSummonerSummoner").is_none());
+        assert!(lobby.events.is_empty());
+        let summoner: OpenApiSpec = serde_json::from_str(&fs::read_to_string(summary.published.join("lcu/plugins/lol-summoner.json")).unwrap()).unwrap();
+        assert!(summoner.events.contains_key("OnJsonApiEvent_lol-summoner_v1_current-summoner"));
+
+        let before = summary.before.as_ref().unwrap();
+        assert_eq!(summary.after.operations, before.operations + 1);
+        assert_eq!(summary.plugins, 2);
+        let markdown = fs::read_to_string(summary.published.join("summary.md")).unwrap();
+        assert_eq!(markdown, summary.to_markdown());
+        assert!(markdown.contains(&format!("| Operations | {} | {} | +1 |", before.operations, summary.after.operations)), "{markdown}");
+
+        fs::remove_dir_all(&root).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_stops_at_failing_stage() {
+        let root = temp_root("gated");
+        let gutted = HelpBuilder::miniature().without(|name| name.contains("LolLobby"));
+        let client = MockPoroClient::new(gutted.json());
+        let patch_day = PatchDay { baseline: Some(baseline()), ..PatchDay::new(&root) };
+        let bundles = root.join("bundles");
+        let help = collect::extended_help(|endpoint| client.post(endpoint), MidRunTargets::Fetch, &bundles);
+        let err = patch_day.run(help, "14.4.1").await.unwrap_err();
+
+        assert_eq!(err.stage, Stage::Gate);
+        assert!(matches!(err.source, Error::Drift(_)), "{err}");
+        assert!(err.to_string().starts_with("patch day stopped at gate: spec shrank"), "{err}");
+        // What generation wrote is kept for a look, and nothing is published.
+        assert_eq!(relative_files(&err.staging), ["lcu/extended-help.json", "lcu/openapi.json"]);
+        assert!(!root.join("14.4.1").exists());
+
+        fs::remove_dir_all(&root).unwrap();
+    }
+}
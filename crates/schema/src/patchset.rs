@@ -0,0 +1,331 @@
+//! Hand edits of a generated spec, kept as patches for the next run.
+//!
+//! [`diff_to_patches`] turns a freshly generated spec and a manually edited
+//! copy into a [`PatchSet`] of set and remove operations on [`DotPathStr`]s.
+//! Applying it to the next generated spec reapplies the edits.
+
+use serde::{ Deserialize, Serialize, de::Error as DeError };
+use serde_json::{ Map, Value };
+
+use crate::{ error::Error, patch::{ DotPathStr, DotToken, Patch } };
+
+/// Ops under one subtree before [`diff_to_patches`] sets the whole subtree
+/// instead.
+pub const DEFAULT_COMPACT_THRESHOLD: usize = 4;
+
+/// One edit of a [`PatchSet`].
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(tag = "op", rename_all = "camelCase")]
+pub enum PatchOp {
+    /// Put `value` at `path`, creating objects on the way.
+    Set { path: String, value: Value },
+    /// Take the property at `path` out of its object.
+    Remove { path: String },
+}
+
+impl PatchOp {
+    pub fn path(&self) -> &str {
+        match self {
+            Self::Set { path, .. } | Self::Remove { path } => path,
+        }
+    }
+}
+
+/// Edits to apply to a spec, in order.
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+pub struct PatchSet {
+    pub ops: Vec<PatchOp>,
+}
+
+impl PatchSet {
+    /// Apply every op to a spec as JSON, e.g. `serde_json::to_value(&spec)`.
+    pub fn apply(&self, spec: &mut Value) -> Result<(), Error> {
+        for op in self.ops.iter() {
+            match op {
+                PatchOp::Set { path, value } => spec.patch_mut(DotPathStr(path), value.clone())?,
+                PatchOp::Remove { path } => remove_at(spec, path)?,
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Remove the object property a dot path ends in.
+fn remove_at(json: &mut Value, path: &str) -> Result<(), Error> {
+    let missing = || Error::from(serde_json::Error::custom(format!("Nothing to remove at path {path}")));
+    let dot_path = DotPathStr(path);
+    let tokens = dot_path.tokenize();
+    let Some((last, parents)) = tokens.split_last() else {
+        return Err(missing());
+    };
+    let mut current = json;
+    for token in parents {
+        current = match (token, current) {
+            (DotToken::Property(name), Value::Object(obj)) => obj.get_mut(*name),
+            (DotToken::Index(index), Value::Object(obj)) => obj.get_mut(&index.to_string()),
+            (DotToken::Index(index), Value::Array(arr)) => arr.get_mut(*index),
+            _ => None,
+        }.ok_or_else(missing)?;
+    }
+    let key = match last {
+        DotToken::Property(name) => name.to_string(),
+        DotToken::Index(index) => index.to_string(),
+        DotToken::Wildcard => return Err(missing()),
+    };
+    current.as_object_mut().and_then(|obj| obj.remove(&key)).map(|_| ()).ok_or_else(missing)
+}
+
+/// A [`PatchSet`] and the edits it can't express.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct PatchDiff {
+    pub patches: PatchSet,
+    /// What differs but was left out, e.g. `path added: /lol-chat/v1/me`.
+    pub unsupported: Vec<String>,
+}
+
+/// The patches that turn `generated` into `edited`, both specs as JSON.
+///
+/// Only components and operations of paths in both are patched. Added or
+/// removed paths, changes elsewhere in the spec, and keys a dot path can't
+/// name (with a `.`, or `*`) are listed as unsupported. A subtree of a
+/// component or operation that would take more than `compact_threshold`
+/// ops is set whole.
+pub fn diff_to_patches(generated: &Value, edited: &Value, compact_threshold: usize) -> PatchDiff {
+    let mut diff = PatchDiff::default();
+    let empty = Map::new();
+    let object = |json: &Value, key: &str| json.get(key).and_then(Value::as_object).cloned().unwrap_or_default();
+
+    for key in keys(generated.as_object().unwrap_or(&empty), edited.as_object().unwrap_or(&empty)) {
+        if key != "paths" && key != "components" && generated.get(&key) != edited.get(&key) {
+            diff.unsupported.push(format!("{key} changed"));
+        }
+    }
+
+    let (old, new) = (object(generated, "components"), object(edited, "components"));
+    for section in keys(&old, &new) {
+        let (old, new) = (section_of(&old, &section), section_of(&new, &section));
+        for name in keys(&old, &new) {
+            let path = format!("components.{section}.{name}");
+            diff_entry(&path, &[&section, &name], old.get(&name), new.get(&name), compact_threshold, &mut diff);
+        }
+    }
+
+    let (old, new) = (object(generated, "paths"), object(edited, "paths"));
+    for route in keys(&old, &new) {
+        let (old, new) = match (old.get(&route), new.get(&route)) {
+            (Some(old), Some(new)) => (old, new),
+            (None, _) => {
+                diff.unsupported.push(format!("path added: {route}"));
+                continue;
+            }
+            (_, None) => {
+                diff.unsupported.push(format!("path removed: {route}"));
+                continue;
+            }
+        };
+        let (old, new) = (old.as_object().unwrap_or(&empty), new.as_object().unwrap_or(&empty));
+        for method in keys(old, new) {
+            let path = format!("paths.{route}.{method}");
+            diff_entry(&path, &[&route, &method], old.get(&method), new.get(&method), compact_threshold, &mut diff);
+        }
+    }
+    diff
+}
+
+fn section_of(components: &Map<String, Value>, section: &str) -> Map<String, Value> {
+    components.get(section).and_then(Value::as_object).cloned().unwrap_or_default()
+}
+
+/// Keys of either object, in order.
+fn keys(a: &Map<String, Value>, b: &Map<String, Value>) -> Vec<String> {
+    let mut keys = a.keys().chain(b.keys()).cloned().collect::<Vec<String>>();
+    keys.sort();
+    keys.dedup();
+    keys
+}
+
+/// Whether a dot path can name this key.
+fn expressible(key: &str) -> bool {
+    !key.is_empty() && key != "*" && !key.contains('.')
+}
+
+/// Diff one component or operation, which may be added or removed whole.
+fn diff_entry(
+    path: &str,
+    names: &[&str],
+    old: Option<&Value>,
+    new: Option<&Value>,
+    compact_threshold: usize,
+    diff: &mut PatchDiff
+) {
+    if old == new {
+        return;
+    }
+    if !names.iter().all(|name| expressible(name)) {
+        diff.unsupported.push(format!("{path} changed, but its name can't be written as a dot path"));
+        return;
+    }
+    match (old, new) {
+        (Some(old), Some(new)) => diff_value(path, old, new, compact_threshold, &mut diff.patches.ops),
+        (None, Some(new)) => diff.patches.ops.push(PatchOp::Set { path: path.to_string(), value: new.clone() }),
+        (Some(_), None) => diff.patches.ops.push(PatchOp::Remove { path: path.to_string() }),
+        (None, None) => {}
+    }
+}
+
+/// Push the ops turning `old` into `new` at `path`.
+fn diff_value(path: &str, old: &Value, new: &Value, compact_threshold: usize, ops: &mut Vec<PatchOp>) {
+    let set = || PatchOp::Set { path: path.to_string(), value: new.clone() };
+    let mut children = Vec::new();
+    match (old, new) {
+        _ if old == new => return,
+        (Value::Object(old), Value::Object(new)) => {
+            if keys(old, new).iter().any(|key| !expressible(key)) {
+                ops.push(set());
+                return;
+            }
+            for key in keys(old, new) {
+                let child = format!("{path}.{key}");
+                match (old.get(&key), new.get(&key)) {
+                    (Some(old), Some(new)) => diff_value(&child, old, new, compact_threshold, &mut children),
+                    (None, Some(new)) => children.push(PatchOp::Set { path: child, value: new.clone() }),
+                    (Some(_), None) => children.push(PatchOp::Remove { path: child }),
+                    (None, None) => {}
+                }
+            }
+        }
+        // Elements can be set in place; anything else changes the indices.
+        (Value::Array(old), Value::Array(new)) if old.len() == new.len() => {
+            for (index, (old, new)) in old.iter().zip(new.iter()).enumerate() {
+                diff_value(&format!("{path}.{index}"), old, new, compact_threshold, &mut children);
+            }
+        }
+        _ => {
+            ops.push(set());
+            return;
+        }
+    }
+    if children.len() > compact_threshold {
+        ops.push(set());
+    } else {
+        ops.extend(children);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+    use crate::{ openapi::OpenApiSpec, tests::miniature };
+
+    fn generated() -> Value {
+        serde_json::to_value(OpenApiSpec::from_help(&miniature(), "1.0.0").unwrap()).unwrap()
+    }
+
+    /// Diff, then check applying the patches to the original gives the edit.
+    fn round_trip(edited: &Value, compact_threshold: usize) -> PatchDiff {
+        let generated = generated();
+        let diff = diff_to_patches(&generated, edited, compact_threshold);
+        let mut patched = generated.clone();
+        diff.patches.apply(&mut patched).unwrap();
+        if diff.unsupported.is_empty() {
+            assert_eq!(&patched, edited);
+        }
+        // Through JSON, as the patches are committed.
+        let read: PatchSet = serde_json::from_str(&serde_json::to_string(&diff.patches).unwrap()).unwrap();
+        assert_eq!(read, diff.patches);
+        diff
+    }
+
+    #[test]
+    fn test_nested_property_edit() {
+        let mut edited = generated();
+        let summoner = &mut edited["components"]["schemas"]["LolSummonerSummoner"];
+        summoner["properties"]["rerollPoints"]["description"] = json!("Points towards a reroll.");
+        summoner["properties"]["puuid"]["format"] = json!("uuid");
+        edited["paths"]["/lol-summoner/v1/summoners/{id}"]["get"]["responses"]["200"]["description"] = json!("The summoner");
+
+        let diff = round_trip(&edited, DEFAULT_COMPACT_THRESHOLD);
+        assert_eq!(diff.unsupported, Vec::<String>::new());
+        let paths = diff.patches.ops.iter().map(PatchOp::path).collect::<Vec<&str>>();
+        assert_eq!(paths, [
+            "components.schemas.LolSummonerSummoner.properties.puuid.format",
+            "components.schemas.LolSummonerSummoner.properties.rerollPoints.description",
+            "paths./lol-summoner/v1/summoners/{id}.get.responses.200.description",
+        ]);
+    }
+
+    #[test]
+    fn test_removal() {
+        let mut edited = generated();
+        let properties = edited["components"]["schemas"]["LolSummonerSummoner"]["properties"].as_object_mut().unwrap();
+        let removed = properties.keys().next().unwrap().clone();
+        properties.remove(&removed);
+        edited["paths"]["/lol-lobby/v2/lobby"].as_object_mut().unwrap().remove("delete");
+
+        let diff = round_trip(&edited, DEFAULT_COMPACT_THRESHOLD);
+        assert_eq!(diff.patches.ops, [
+            PatchOp::Remove { path: format!("components.schemas.LolSummonerSummoner.properties.{removed}") },
+            PatchOp::Remove { path: "paths./lol-lobby/v2/lobby.delete".to_string() },
+        ]);
+    }
+
+    #[test]
+    fn test_array_element_change() {
+        let mut edited = generated();
+        let tags = &mut edited["paths"]["/lol-lobby/v2/lobby"]["post"]["tags"];
+        tags[0] = json!("Plugin lol-lobby-team-builder");
+        let diff = round_trip(&edited, DEFAULT_COMPACT_THRESHOLD);
+        assert_eq!(diff.patches.ops, [
+            PatchOp::Set {
+                path: "paths./lol-lobby/v2/lobby.post.tags.0".to_string(),
+                value: json!("Plugin lol-lobby-team-builder"),
+            },
+        ]);
+
+        // A longer array is set whole.
+        edited["paths"]["/lol-lobby/v2/lobby"]["post"]["tags"].as_array_mut().unwrap().push(json!("Lobby"));
+        let diff = round_trip(&edited, DEFAULT_COMPACT_THRESHOLD);
+        assert_eq!(diff.patches.ops[0].path(), "paths./lol-lobby/v2/lobby.post.tags");
+    }
+
+    #[test]
+    fn test_compact_subtree() {
+        let mut edited = generated();
+        let properties = edited["components"]["schemas"]["LolSummonerSummoner"]["properties"].as_object_mut().unwrap();
+        for property in properties.values_mut() {
+            property["description"] = json!("Edited");
+        }
+        let count = properties.len();
+        assert!(count > 2);
+
+        let diff = round_trip(&edited, count);
+        assert_eq!(diff.patches.ops.len(), count);
+        let diff = round_trip(&edited, count - 1);
+        assert_eq!(diff.patches.ops.len(), 1);
+        assert_eq!(diff.patches.ops[0].path(), "components.schemas.LolSummonerSummoner.properties");
+    }
+
+    #[test]
+    fn test_unsupported() {
+        let mut edited = generated();
+        edited["paths"].as_object_mut().unwrap().remove("/Subscribe");
+        edited["paths"]["/lol-chat/v1/me"] = json!({ "get": {} });
+        edited["info"]["title"] = json!("Edited");
+        edited["components"]["schemas"]["Lol.Dotted"] = json!({ "type": "object" });
+        edited["paths"]["/Help"]["post"]["description"] = json!("Help.");
+
+        let diff = round_trip(&edited, DEFAULT_COMPACT_THRESHOLD);
+        assert_eq!(diff.unsupported, [
+            "info changed",
+            "components.schemas.Lol.Dotted changed, but its name can't be written as a dot path",
+            "path removed: /Subscribe",
+            "path added: /lol-chat/v1/me",
+        ]);
+        // What can be expressed still is.
+        assert_eq!(diff.patches.ops, [
+            PatchOp::Set { path: "paths./Help.post.description".to_string(), value: json!("Help.") },
+        ]);
+    }
+}
@@ -0,0 +1,119 @@
+//! Where each component and operation of a generated spec came from.
+
+use std::collections::BTreeMap;
+
+use serde::Serialize;
+use serde_json::Value;
+
+use crate::{ convert::plugin_from_tag, help::ExtendedHelp, openapi::OpenApiSpec };
+
+/// Plugin of items whose tags name no plugin.
+pub const UNKNOWN_PLUGIN: &str = "unknown";
+
+/// The LCU namespace and plugin an item was generated from.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Origin {
+    pub namespace: String,
+    pub plugin: String,
+}
+
+/// Component and operationId to their [`Origin`], written as `provenance.json`.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct Provenance {
+    pub components: BTreeMap<String, Origin>,
+    pub operations: BTreeMap<String, Origin>,
+}
+
+impl Provenance {
+    /// Record the origin of every component and operation in `spec`.
+    ///
+    /// Only items the spec kept are listed, so obscured types and path-less
+    /// endpoints are left out.
+    pub fn build(help: &ExtendedHelp, spec: &OpenApiSpec) -> Self {
+        let schemas = spec.components.get("schemas").and_then(Value::as_object);
+        let components = help.types
+            .iter()
+            .filter(|ty| schemas.is_some_and(|schemas| schemas.contains_key(&ty.info.name)))
+            .map(|ty| (ty.info.name.clone(), Origin::new(&ty.namespace, &ty.tags)))
+            .collect();
+
+        let operation_ids = spec.paths
+            .values()
+            .filter_map(Value::as_object)
+            .flat_map(|item| item.values())
+            .filter_map(|operation| operation.get("operationId").and_then(Value::as_str))
+            .collect::<Vec<&str>>();
+        let operations = help.endpoints
+            .iter()
+            .filter(|endpoint| operation_ids.contains(&endpoint.info.name.as_str()))
+            .map(|endpoint| (endpoint.info.name.clone(), Origin::new(&endpoint.namespace, &endpoint.tags)))
+            .collect();
+
+        Self { components, operations }
+    }
+
+    pub fn component(&self, name: &str) -> Option<&Origin> {
+        self.components.get(name)
+    }
+
+    pub fn operation(&self, operation_id: &str) -> Option<&Origin> {
+        self.operations.get(operation_id)
+    }
+
+    /// The `provenance.json` artifact.
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).expect("provenance serializes")
+    }
+}
+
+impl Origin {
+    fn new(namespace: &str, tags: &[String]) -> Self {
+        Self {
+            namespace: namespace.to_string(),
+            plugin: plugin_of(tags).unwrap_or(UNKNOWN_PLUGIN).to_string(),
+        }
+    }
+}
+
+/// The plugin named by the first plugin tag, as in [`crate::convert::operation_tags`].
+fn plugin_of(tags: &[String]) -> Option<&str> {
+    tags.iter().find_map(|tag| plugin_from_tag(tag))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::miniature;
+
+    #[test]
+    fn test_provenance() {
+        let help = miniature();
+        let spec = OpenApiSpec::from_help(&help, "1.0.0").unwrap();
+        let provenance = Provenance::build(&help, &spec);
+
+        assert_eq!(
+            provenance.component("LolLobbyLobbyDto"),
+            Some(&Origin { namespace: "Plugins".to_string(), plugin: "lol-lobby".to_string() })
+        );
+        assert_eq!(provenance.components.len(), 6);
+        assert_eq!(provenance.operation("GetLolSummonerV1SummonersById").unwrap().plugin, "lol-summoner");
+        // Path-less endpoints aren't operations.
+        assert_eq!(provenance.operation("LolSummonerRefresh"), None);
+
+        let json: Value = serde_json::from_str(&provenance.to_json()).unwrap();
+        assert_eq!(json["operations"]["PostLolLobbyV2Lobby"]["namespace"], "Plugins");
+    }
+
+    #[test]
+    fn test_unknown_plugin() {
+        let mut help = miniature();
+        help.types[0].tags = vec!["builtin".to_string()];
+        let spec = OpenApiSpec::from_help(&help, "1.0.0").unwrap();
+        let provenance = Provenance::build(&help, &spec);
+
+        assert_eq!(provenance.component(&help.types[0].info.name).unwrap().plugin, UNKNOWN_PLUGIN);
+        for (id, origin) in provenance.operations.iter().filter(|(_, o)| o.namespace.is_empty()) {
+            assert_eq!(origin.plugin, UNKNOWN_PLUGIN, "{id}");
+        }
+    }
+}
@@ -0,0 +1,179 @@
+//! Recording a client's answers as fixtures, and replaying them without it.
+//!
+//! [`RecordingClient`] sits in front of a request function like the one
+//! [`crate::collect::collect`] takes and writes every exchange to a directory,
+//! one file each. [`ReplayClient`] answers the same requests from that
+//! directory, so a session against a real client becomes a test fixture.
+
+use std::{ collections::BTreeMap, fmt, fs, future::Future, path::{ Path, PathBuf } };
+
+use serde::{ Deserialize, Serialize };
+use serde_json::Value;
+
+use crate::{ artifact::sha256_hex, error::Error };
+
+/// A request and the response it got, as written to a recording directory.
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+pub struct Exchange {
+    /// Uppercase, like `POST`.
+    pub method: String,
+    /// Path and query, like `/help?target=X&format=Full`.
+    pub endpoint: String,
+    /// Empty for requests without one.
+    pub body: String,
+    pub response: Value,
+}
+
+impl Exchange {
+    /// File name of the exchange, the same for every recording of the request.
+    pub fn file_name(&self) -> String {
+        let key = sha256_hex(&format!("{} {}", self.method, self.endpoint));
+        format!("{}-{}.json", self.method.to_lowercase(), &key[..16])
+    }
+}
+
+/// Passes requests through to `inner` and writes each exchange into `dir`,
+/// after the redaction hook has seen it.
+pub struct RecordingClient<F> {
+    inner: F,
+    dir: PathBuf,
+    redact: fn(&mut Exchange),
+}
+
+impl<F, Fut> RecordingClient<F> where F: Fn(String) -> Fut, Fut: Future<Output = Result<Value, Error>> {
+    /// Record what `inner` answers into `dir`, created if missing.
+    pub fn wrap(inner: F, dir: &Path) -> Self {
+        Self { inner, dir: dir.to_path_buf(), redact: |_| {} }
+    }
+
+    /// Change exchanges before they're written, such as blanking tokens. The
+    /// caller still gets the response as the client sent it.
+    pub fn with_redaction(mut self, redact: fn(&mut Exchange)) -> Self {
+        self.redact = redact;
+        self
+    }
+
+    /// Send a help request like `collect` does: a `POST` without a body.
+    pub async fn post(&self, endpoint: String) -> Result<Value, Error> {
+        let response = (self.inner)(endpoint.clone()).await?;
+        let mut exchange = Exchange { method: "POST".to_string(), endpoint, body: String::new(), response: response.clone() };
+        (self.redact)(&mut exchange);
+        fs::create_dir_all(&self.dir)?;
+        fs::write(self.dir.join(exchange.file_name()), serde_json::to_string_pretty(&exchange)?)?;
+        Ok(response)
+    }
+}
+
+/// Answers requests from a directory [`RecordingClient`] wrote.
+#[derive(Debug, Clone, Default)]
+pub struct ReplayClient {
+    /// Keyed by method and endpoint.
+    responses: BTreeMap<(String, String), Value>,
+    dir: PathBuf,
+}
+
+/// A request that isn't in the recording.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ReplayMiss {
+    pub method: String,
+    pub endpoint: String,
+    pub dir: PathBuf,
+}
+
+impl fmt::Display for ReplayMiss {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} {} wasn't recorded in {}; record the session again", self.method, self.endpoint, self.dir.display())
+    }
+}
+
+impl std::error::Error for ReplayMiss {}
+
+impl ReplayClient {
+    /// Load every exchange in `dir`. Files that aren't JSON are skipped.
+    pub fn from_dir(dir: &Path) -> Result<Self, Error> {
+        let mut responses = BTreeMap::new();
+        for entry in fs::read_dir(dir)? {
+            let path = entry?.path();
+            if path.extension().is_none_or(|extension| extension != "json") {
+                continue;
+            }
+            let exchange: Exchange = serde_json::from_str(&fs::read_to_string(&path)?)?;
+            responses.insert((exchange.method, exchange.endpoint), exchange.response);
+        }
+        Ok(Self { responses, dir: dir.to_path_buf() })
+    }
+
+    pub fn len(&self) -> usize {
+        self.responses.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.responses.is_empty()
+    }
+
+    /// The recorded response to `method` on exactly `endpoint`.
+    pub fn respond(&self, method: &str, endpoint: &str) -> Result<Value, ReplayMiss> {
+        self.responses.get(&(method.to_string(), endpoint.to_string())).cloned().ok_or_else(|| ReplayMiss {
+            method: method.to_string(),
+            endpoint: endpoint.to_string(),
+            dir: self.dir.clone(),
+        })
+    }
+
+    /// The counterpart of [`RecordingClient::post`].
+    pub async fn post(&self, endpoint: String) -> Result<Value, Error> {
+        Ok(self.respond("POST", &endpoint)?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use poroshell_test_utils::{ assert_spec_matches, HelpBuilder, MockPoroClient };
+
+    use crate::{ collect, options::MidRunTargets, tests::miniature };
+
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("poroshell-recording-{name}-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        dir
+    }
+
+    #[tokio::test]
+    async fn test_record_then_replay() {
+        let dir = temp_dir("replay");
+        let mock = MockPoroClient::new(HelpBuilder::miniature().json());
+        let recorder = RecordingClient::wrap(|endpoint| mock.post(endpoint), &dir.join("session"));
+        let recorded = collect::extended_help(|endpoint| recorder.post(endpoint), MidRunTargets::Fetch, &dir.join("bundles")).await.unwrap();
+
+        let replay = ReplayClient::from_dir(&dir.join("session")).unwrap();
+        // The listing is asked for twice but recorded once.
+        assert_eq!(replay.len(), fs::read_dir(dir.join("session")).unwrap().count());
+        let replayed = collect::extended_help(|endpoint| replay.post(endpoint), MidRunTargets::Fetch, &dir.join("bundles")).await.unwrap();
+
+        let spec = |help| serde_json::to_value(crate::openapi::OpenApiSpec::from_help(help, "1.0.0").unwrap()).unwrap();
+        assert_spec_matches(&spec(&replayed), &spec(&recorded));
+        assert_spec_matches(&spec(&replayed), &spec(&miniature()));
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_redaction_and_miss() {
+        let dir = temp_dir("redact");
+        let mock = MockPoroClient::new(HelpBuilder::miniature().json());
+        let recorder = RecordingClient::wrap(|endpoint| mock.post(endpoint), &dir).with_redaction(|exchange| {
+            exchange.response = Value::String("redacted".to_string());
+        });
+        // The caller still sees the real response.
+        assert!(recorder.post("/help".to_string()).await.unwrap().is_object());
+
+        let replay = ReplayClient::from_dir(&dir).unwrap();
+        assert_eq!(replay.post("/help".to_string()).await.unwrap(), "redacted");
+        let err = replay.respond("POST", "/help?target=X&format=Full").unwrap_err();
+        assert_eq!(err.to_string(), format!("POST /help?target=X&format=Full wasn't recorded in {}; record the session again", dir.display()));
+        assert!(matches!(replay.post("/help?target=X&format=Full".to_string()).await, Err(Error::Replay(_))));
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
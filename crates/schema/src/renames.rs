@@ -0,0 +1,303 @@
+//! Parameters renamed between two versions of a spec.
+//!
+//! An operation that keeps its operationId but swaps one parameter for
+//! another in the same place, with the same schema, most likely renamed it.
+//! Clients built against the old name only find out through a 400, so the
+//! changelog says so, and the new parameter names the old one in
+//! [`X_RENAMED_FROM`].
+//!
+//! A wrong pairing is worse than a missed one. Parameters are only paired
+//! within one location and with compatible schemas, and only reported above
+//! [`RenameDetection::threshold`].
+
+use std::{ collections::BTreeMap, fmt };
+
+use serde_json::Value;
+
+use crate::openapi::OpenApiSpec;
+
+/// Extension key on a parameter naming what it was called in the previous
+/// version, and on a component naming what the help calls it, see
+/// [`crate::component_renames`].
+pub const X_RENAMED_FROM: &str = "x-renamed-from";
+
+/// A parameter an operation lost, paired with one it gained.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ParameterRename {
+    pub operation_id: String,
+    /// `in` of both parameters, like `query` or `path`.
+    pub location: String,
+    pub from: String,
+    pub to: String,
+    /// Whether the schema is the same, and not only compatible.
+    pub schema_unchanged: bool,
+    /// Between 0 and 1, see [`RenameDetection`].
+    pub confidence: f64,
+}
+
+impl fmt::Display for ParameterRename {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let schema = if self.schema_unchanged { "schema unchanged" } else { "schema changed" };
+        write!(f, "parameter `{}` renamed to `{}` ({schema})", self.from, self.to)
+    }
+}
+
+/// Pairs up the parameters each operation lost and gained.
+///
+/// A pairing's confidence adds up:
+///
+/// - 0.6 for the same schema, or 0.3 for one of the same type
+/// - 0.25 when neither parameter has any other candidate in its location
+/// - up to 0.15 for how alike the names are
+///
+/// So at the default threshold a rename needs an unchanged schema and either
+/// no other candidates or a name differing only in case.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RenameDetection {
+    pub threshold: f64,
+}
+
+impl Default for RenameDetection {
+    fn default() -> Self {
+        Self { threshold: 0.75 }
+    }
+}
+
+impl RenameDetection {
+    /// Renames from `old` to `new`, by operationId and then location.
+    pub fn detect(&self, old: &OpenApiSpec, new: &OpenApiSpec) -> Vec<ParameterRename> {
+        let old_operations = operations(old);
+        let mut renames = Vec::new();
+        for (id, new_operation) in operations(new) {
+            let Some(old_operation) = old_operations.get(id) else {
+                continue;
+            };
+            let old_parameters = parameters(old_operation);
+            let new_parameters = parameters(new_operation);
+            let removed = old_parameters.iter().filter(|(key, _)| !new_parameters.contains_key(key)).collect::<Vec<_>>();
+            let added = new_parameters.iter().filter(|(key, _)| !old_parameters.contains_key(key)).collect::<Vec<_>>();
+
+            let pairs = |(from, from_schema): (&(&str, &str), &&Value), (to, to_schema): (&(&str, &str), &&Value)| {
+                from.0 == to.0 && compatible(from_schema, to_schema)
+            };
+            // Every compatible pair in the same location, best first.
+            let mut candidates = Vec::new();
+            for &removed_parameter in removed.iter() {
+                for &added_parameter in added.iter() {
+                    if !pairs(removed_parameter, added_parameter) {
+                        continue;
+                    }
+                    let unique = added.iter().filter(|other| pairs(removed_parameter, **other)).count() == 1 &&
+                        removed.iter().filter(|other| pairs(**other, added_parameter)).count() == 1;
+                    let ((location, from), from_schema) = removed_parameter;
+                    let ((_, to), to_schema) = added_parameter;
+                    let schema_unchanged = from_schema == to_schema;
+                    let confidence =
+                        (if schema_unchanged { 0.6 } else { 0.3 }) + (if unique { 0.25 } else { 0.0 }) + 0.15 * name_similarity(from, to);
+                    candidates.push(ParameterRename {
+                        operation_id: id.to_string(),
+                        location: location.to_string(),
+                        from: from.to_string(),
+                        to: to.to_string(),
+                        schema_unchanged,
+                        confidence,
+                    });
+                }
+            }
+            candidates.sort_by(|a, b| b.confidence.total_cmp(&a.confidence).then_with(|| (&a.from, &a.to).cmp(&(&b.from, &b.to))));
+
+            let mut paired = Vec::<ParameterRename>::new();
+            for candidate in candidates {
+                if candidate.confidence < self.threshold {
+                    break;
+                }
+                let taken = paired.iter().any(|rename| {
+                    rename.location == candidate.location && (rename.from == candidate.from || rename.to == candidate.to)
+                });
+                if !taken {
+                    paired.push(candidate);
+                }
+            }
+            paired.sort_by(|a, b| (&a.location, &a.from).cmp(&(&b.location, &b.from)));
+            renames.extend(paired);
+        }
+        renames
+    }
+}
+
+/// Write [`X_RENAMED_FROM`] on the new parameter of each rename. Returns the
+/// number of parameters annotated.
+pub fn annotate(renames: &[ParameterRename], spec: &mut OpenApiSpec) -> usize {
+    let mut count = 0;
+    let operations = spec.paths
+        .values_mut()
+        .filter_map(Value::as_object_mut)
+        .flat_map(|item| item.values_mut());
+    for operation in operations {
+        let Some(id) = operation.get("operationId").and_then(Value::as_str).map(str::to_string) else {
+            continue;
+        };
+        let Some(Value::Array(parameters)) = operation.get_mut("parameters") else {
+            continue;
+        };
+        for parameter in parameters.iter_mut().filter_map(Value::as_object_mut) {
+            let rename = renames.iter().find(|rename| {
+                rename.operation_id == id && parameter.get("in").and_then(Value::as_str) == Some(&rename.location) &&
+                    parameter.get("name").and_then(Value::as_str) == Some(&rename.to)
+            });
+            if let Some(rename) = rename {
+                parameter.insert(X_RENAMED_FROM.to_string(), Value::from(rename.from.as_str()));
+                count += 1;
+            }
+        }
+    }
+    count
+}
+
+/// A changelog section listing `renames`, or nothing without any.
+pub fn changelog(renames: &[ParameterRename]) -> String {
+    if renames.is_empty() {
+        return String::new();
+    }
+    let mut markdown = format!("## Renamed parameters: {}\n", renames.len());
+    for rename in renames {
+        markdown.push_str(&format!("~ {}: {rename}\n", rename.operation_id));
+    }
+    markdown
+}
+
+/// Operations by operationId.
+fn operations(spec: &OpenApiSpec) -> BTreeMap<&str, &Value> {
+    spec.paths
+        .values()
+        .filter_map(Value::as_object)
+        .flat_map(|item| item.values())
+        .filter_map(|operation| Some((operation.get("operationId")?.as_str()?, operation)))
+        .collect()
+}
+
+/// An operation's parameter schemas by location and name.
+fn parameters(operation: &Value) -> BTreeMap<(&str, &str), &Value> {
+    operation
+        .get("parameters")
+        .and_then(Value::as_array)
+        .into_iter()
+        .flatten()
+        .filter_map(|parameter| {
+            let key = (parameter.get("in")?.as_str()?, parameter.get("name")?.as_str()?);
+            Some((key, parameter.get("schema").unwrap_or(&Value::Null)))
+        })
+        .collect()
+}
+
+/// Whether a value for one schema would likely fit the other: the same type,
+/// reference, and item type or reference.
+fn compatible(a: &Value, b: &Value) -> bool {
+    ["type", "$ref"].iter().all(|key| a.get(key) == b.get(key)) &&
+        ["type", "$ref"].iter().all(|key| a.pointer(&format!("/items/{key}")) == b.pointer(&format!("/items/{key}")))
+}
+
+/// 1 for names equal but for case, down to 0 for nothing in common, by edit distance.
+fn name_similarity(a: &str, b: &str) -> f64 {
+    let (a, b) = (a.to_lowercase().chars().collect::<Vec<char>>(), b.to_lowercase().chars().collect::<Vec<char>>());
+    let longest = a.len().max(b.len());
+    if longest == 0 {
+        return 1.0;
+    }
+    let mut previous = (0..=b.len()).collect::<Vec<usize>>();
+    for (i, a_char) in a.iter().enumerate() {
+        let mut row = vec![i + 1];
+        for (j, b_char) in b.iter().enumerate() {
+            let substitution = previous[j] + usize::from(a_char != b_char);
+            row.push(substitution.min(previous[j + 1] + 1).min(row[j] + 1));
+        }
+        previous = row;
+    }
+    1.0 - (previous[b.len()] as f64) / (longest as f64)
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+    use crate::tests::miniature;
+
+    /// The miniature, with `GetLolSummonerV1Summoners`'s parameters replaced.
+    fn spec(parameters: Value) -> OpenApiSpec {
+        let mut spec = OpenApiSpec::from_help(&miniature(), "1.0.0").unwrap();
+        spec.paths["/lol-summoner/v1/summoners"]["get"]["parameters"] = parameters;
+        spec
+    }
+
+    fn parameter(location: &str, name: &str, schema: Value) -> Value {
+        json!({ "name": name, "in": location, "required": true, "schema": schema })
+    }
+
+    fn ids() -> Value {
+        json!({ "type": "array", "items": { "type": "string" } })
+    }
+
+    #[test]
+    fn test_clear_rename() {
+        let old = spec(json!([parameter("query", "summonerIds", ids()), parameter("query", "region", json!({ "type": "string" }))]));
+        let new = spec(json!([parameter("query", "puuids", ids()), parameter("query", "region", json!({ "type": "string" }))]));
+        let renames = RenameDetection::default().detect(&old, &new);
+        assert_eq!(renames.len(), 1);
+        let rename = &renames[0];
+        assert_eq!((rename.operation_id.as_str(), rename.location.as_str()), ("GetLolSummonerV1Summoners", "query"));
+        assert!(rename.schema_unchanged);
+        assert!(rename.confidence >= 0.85);
+        assert_eq!(rename.to_string(), "parameter `summonerIds` renamed to `puuids` (schema unchanged)");
+        assert_eq!(
+            changelog(&renames),
+            "## Renamed parameters: 1\n~ GetLolSummonerV1Summoners: parameter `summonerIds` renamed to `puuids` (schema unchanged)\n"
+        );
+
+        // Nothing changed, nothing renamed.
+        assert!(RenameDetection::default().detect(&old, &old).is_empty());
+        assert_eq!(changelog(&[]), "");
+    }
+
+    #[test]
+    fn test_unrelated_changes_not_paired() {
+        let old = spec(json!([parameter("query", "name", json!({ "type": "string" }))]));
+
+        // Removed, and something else added.
+        let new = spec(json!([parameter("query", "count", json!({ "type": "integer", "format": "uint32" }))]));
+        assert!(RenameDetection::default().detect(&old, &new).is_empty());
+
+        // The same schema, but somewhere else.
+        let new = spec(json!([parameter("header", "name-header", json!({ "type": "string" }))]));
+        assert!(RenameDetection::default().detect(&old, &new).is_empty());
+
+        // Two candidates, neither more alike than the other.
+        let two = spec(json!([parameter("query", "a", json!({ "type": "string" })), parameter("query", "b", json!({ "type": "string" }))]));
+        assert!(RenameDetection::default().detect(&old, &two).is_empty());
+
+        // A compatible but changed schema only counts at a lower threshold.
+        let changed = spec(json!([parameter("query", "displayName", json!({ "type": "string", "format": "riot-id" }))]));
+        assert!(RenameDetection::default().detect(&old, &changed).is_empty());
+        let lenient = RenameDetection { threshold: 0.5 };
+        let renames = lenient.detect(&old, &changed);
+        assert_eq!(renames[0].to_string(), "parameter `name` renamed to `displayName` (schema changed)");
+    }
+
+    #[test]
+    fn test_annotate() {
+        let old = spec(json!([parameter("query", "summonerIds", ids())]));
+        let mut new = spec(json!([parameter("query", "puuids", ids()), parameter("path", "puuids", ids())]));
+        let renames = RenameDetection::default().detect(&old, &new);
+        assert_eq!(annotate(&renames, &mut new), 1);
+        let parameters = &new.paths["/lol-summoner/v1/summoners"]["get"]["parameters"];
+        assert_eq!(parameters[0][X_RENAMED_FROM], "summonerIds");
+        assert!(parameters[1].get(X_RENAMED_FROM).is_none());
+    }
+
+    #[test]
+    fn test_name_similarity() {
+        assert_eq!(name_similarity("summonerId", "summonerID"), 1.0);
+        assert_eq!(name_similarity("abc", "xyz"), 0.0);
+        assert_eq!(name_similarity("puuid", "puuids"), 1.0 - 1.0 / 6.0);
+    }
+}
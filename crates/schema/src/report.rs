@@ -0,0 +1,268 @@
+use std::{ collections::BTreeMap, fmt::Write };
+
+use serde::{ Serialize, Serializer, ser::SerializeStruct };
+
+/// What happened to a single target while building the schema.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ReportKind {
+    /// Something looked wrong but generation carried on.
+    Warning,
+    /// Conversion fell back to a default because the help data was missing
+    /// something. [`crate::options::SchemaOptions::strict`] fails instead.
+    Fallback,
+    /// The target was left out of the output.
+    Skipped,
+    /// Identical duplicates were collapsed into one.
+    Merged,
+    /// The target was emitted under a different name.
+    Renamed,
+    /// A type the LCU marks as private, and how it was handled.
+    Private,
+    /// The component was copied into each place that used it and removed.
+    Inlined,
+    /// An operation that uses a deprecated component.
+    Deprecated,
+    /// A component recognized as a paging wrapper or async handle, see [`crate::envelope`].
+    Envelope,
+    /// How long fetching took and how fast requests went. Not an issue.
+    Timing,
+    /// Serialized size of the spec and its parts, see [`crate::size`]. Not an issue.
+    Size,
+    /// Components that refer back to themselves, see [`crate::cycles`].
+    Cycle,
+    /// Text cleaned of control characters or cut down, see [`crate::sanitize`].
+    Sanitized,
+}
+
+impl ReportKind {
+    /// Every kind, in the order reports list them.
+    pub const ALL: [ReportKind; 13] = [
+        Self::Warning,
+        Self::Fallback,
+        Self::Skipped,
+        Self::Merged,
+        Self::Renamed,
+        Self::Private,
+        Self::Inlined,
+        Self::Deprecated,
+        Self::Envelope,
+        Self::Timing,
+        Self::Size,
+        Self::Cycle,
+        Self::Sanitized,
+    ];
+
+    /// Section heading in the Markdown rendering.
+    pub fn title(&self) -> &'static str {
+        match self {
+            Self::Warning => "Warnings",
+            Self::Fallback => "Fallbacks",
+            Self::Skipped => "Skipped",
+            Self::Merged => "Merged",
+            Self::Renamed => "Renamed",
+            Self::Private => "Private",
+            Self::Inlined => "Inlined",
+            Self::Deprecated => "Deprecated",
+            Self::Envelope => "Envelopes",
+            Self::Timing => "Timing",
+            Self::Size => "Sizes",
+            Self::Cycle => "Cycles",
+            Self::Sanitized => "Sanitized",
+        }
+    }
+
+    fn summary(&self, count: usize) -> String {
+        match self {
+            Self::Warning if count == 1 => "1 warning".to_string(),
+            Self::Warning => format!("{count} warnings"),
+            Self::Fallback if count == 1 => "1 fallback".to_string(),
+            Self::Envelope if count == 1 => "1 envelope".to_string(),
+            _ => format!("{count} {}", self.title().to_lowercase()),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+pub struct ReportEntry {
+    pub kind: ReportKind,
+    /// Name of the type, endpoint, event, or path the entry is about.
+    pub target: String,
+    pub detail: String,
+}
+
+/// Accumulates everything generation did that isn't visible in the output itself.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SchemaReport {
+    pub entries: Vec<ReportEntry>,
+}
+
+impl SchemaReport {
+    pub fn push(&mut self, kind: ReportKind, target: impl Into<String>, detail: impl Into<String>) {
+        self.entries.push(ReportEntry { kind, target: target.into(), detail: detail.into() });
+    }
+
+    pub fn warn(&mut self, target: impl Into<String>, detail: impl Into<String>) {
+        self.push(ReportKind::Warning, target, detail);
+    }
+
+    /// Entries of a single kind, in the order they were recorded.
+    pub fn of_kind(&self, kind: ReportKind) -> impl Iterator<Item = &ReportEntry> {
+        self.entries.iter().filter(move |e| e.kind == kind)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Entries of a single kind sorted by target, so renderings don't depend
+    /// on the order things were converted in.
+    fn sorted(&self, kind: ReportKind) -> Vec<&ReportEntry> {
+        let mut entries = self.of_kind(kind).collect::<Vec<&ReportEntry>>();
+        entries.sort();
+        entries
+    }
+
+    /// One line for the end of a run, e.g. `schema: 2 warnings, 1 skipped`.
+    pub fn summary_line(&self) -> String {
+        let counts = ReportKind::ALL.iter()
+            .map(|kind| (kind, self.of_kind(*kind).count()))
+            .filter(|(kind, count)| *count > 0 && !matches!(kind, ReportKind::Timing | ReportKind::Size))
+            .map(|(kind, count)| kind.summary(count))
+            .collect::<Vec<String>>();
+        if counts.is_empty() { "schema: no issues".to_string() } else { format!("schema: {}", counts.join(", ")) }
+    }
+
+    /// A Markdown document with a table per kind, for PR descriptions and release notes.
+    pub fn to_markdown(&self) -> String {
+        let mut markdown = String::from("# Schema report\n\n");
+        if self.is_empty() {
+            markdown.push_str("No issues: everything was converted as-is.\n");
+            return markdown;
+        }
+
+        let _ = writeln!(markdown, "{}", self.summary_line().trim_start_matches("schema: "));
+        for kind in ReportKind::ALL {
+            let entries = self.sorted(kind);
+            if entries.is_empty() {
+                continue;
+            }
+            let _ = write!(markdown, "\n## {}\n\n| Target | Detail |\n| --- | --- |\n", kind.title());
+            for entry in entries {
+                let _ = writeln!(markdown, "| `{}` | {} |", escape_cell(&entry.target), escape_cell(&entry.detail));
+            }
+        }
+        markdown
+    }
+}
+
+/// Keeps a value inside its table cell.
+fn escape_cell(value: &str) -> String {
+    value.replace('|', "\\|").replace('\n', " ")
+}
+
+/// Serializes as per-kind counts plus every entry, sorted by kind then target.
+impl Serialize for SchemaReport {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error> where S: Serializer {
+        let counts = ReportKind::ALL.iter()
+            .map(|kind| (*kind, self.of_kind(*kind).count()))
+            .collect::<BTreeMap<ReportKind, usize>>();
+        let entries = ReportKind::ALL.iter()
+            .flat_map(|kind| self.sorted(*kind))
+            .collect::<Vec<&ReportEntry>>();
+
+        let mut report = serializer.serialize_struct("SchemaReport", 2)?;
+        report.serialize_field("counts", &counts)?;
+        report.serialize_field("entries", &entries)?;
+        report.end()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    fn canned() -> SchemaReport {
+        let mut report = SchemaReport::default();
+        report.push(ReportKind::Skipped, "/lol-lobby/v1/{", "unclosed brace");
+        report.warn("LolSummonerSummoner", "field puuid has an unknown type");
+        report.push(ReportKind::Renamed, "Lol-Chat Friend", "emitted as LolChatFriend");
+        report.warn("GetLolLobbyV2Lobby", "path parameter id has no matching argument");
+        report.push(ReportKind::Merged, "OnJsonApiEvent", "identical duplicate | kept the first");
+        report
+    }
+
+    #[test]
+    fn test_markdown() {
+        assert_eq!(
+            canned().to_markdown(),
+            "# Schema report
+
+2 warnings, 1 skipped, 1 merged, 1 renamed
+
+## Warnings
+
+| Target | Detail |
+| --- | --- |
+| `GetLolLobbyV2Lobby` | path parameter id has no matching argument |
+| `LolSummonerSummoner` | field puuid has an unknown type |
+
+## Skipped
+
+| Target | Detail |
+| --- | --- |
+| `/lol-lobby/v1/{` | unclosed brace |
+
+## Merged
+
+| Target | Detail |
+| --- | --- |
+| `OnJsonApiEvent` | identical duplicate \\| kept the first |
+
+## Renamed
+
+| Target | Detail |
+| --- | --- |
+| `Lol-Chat Friend` | emitted as LolChatFriend |
+"
+        );
+    }
+
+    #[test]
+    fn test_json() {
+        let value = serde_json::to_value(canned()).unwrap();
+        assert_eq!(
+            value["counts"],
+            json!({ "warning": 2, "fallback": 0, "skipped": 1, "merged": 1, "renamed": 1, "private": 0, "inlined": 0, "deprecated": 0, "envelope": 0, "timing": 0, "size": 0, "cycle": 0, "sanitized": 0 })
+        );
+        assert_eq!(value["entries"].as_array().unwrap().len(), 5);
+        assert_eq!(
+            value["entries"][0],
+            json!({
+                "kind": "warning",
+                "target": "GetLolLobbyV2Lobby",
+                "detail": "path parameter id has no matching argument",
+            })
+        );
+
+        // Recording order doesn't matter.
+        let mut reversed = canned();
+        reversed.entries.reverse();
+        assert_eq!(serde_json::to_string(&reversed).unwrap(), serde_json::to_string(&canned()).unwrap());
+    }
+
+    #[test]
+    fn test_empty_report() {
+        let report = SchemaReport::default();
+        assert_eq!(report.summary_line(), "schema: no issues");
+        assert_eq!(report.to_markdown(), "# Schema report\n\nNo issues: everything was converted as-is.\n");
+        assert!(!report.to_markdown().contains("##"));
+    }
+
+    #[test]
+    fn test_summary_line() {
+        assert_eq!(canned().summary_line(), "schema: 2 warnings, 1 skipped, 1 merged, 1 renamed");
+    }
+}
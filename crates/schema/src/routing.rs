@@ -0,0 +1,313 @@
+//! A compact routing table for proxies and middleware in front of the LCU.
+//!
+//! Each route has an anchored regex for its path template, so a proxy in any
+//! language can match requests without an OpenAPI library. [`RoutingTable::match_path`]
+//! does the same matching here.
+
+use regex::Regex;
+use serde::Serialize;
+use serde_json::Value;
+
+use crate::{ caution::{ Caution, CautionLevel }, openapi::OpenApiSpec };
+
+/// Operation extension listing path parameters that take the rest of the path,
+/// slashes included. The LCU writes them `{+name}`; the spec path has `{name}`.
+pub const X_GREEDY_PARAMS: &str = "x-greedy-params";
+
+/// One operation of the spec, ready for matching.
+#[derive(Serialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct Route {
+    /// Upper-case HTTP method.
+    pub method: String,
+    /// The spec's path template.
+    pub path: String,
+    /// Anchored regex for the path, with a named group per parameter. Group
+    /// names have characters regex doesn't allow replaced by `_`.
+    pub pattern: String,
+    /// Parameter names in the order they appear in the path.
+    pub params: Vec<String>,
+    pub operation_id: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub caution: Option<CautionLevel>,
+    /// Whether the last parameter takes the rest of the path.
+    #[serde(skip)]
+    greedy: bool,
+}
+
+impl Route {
+    fn new(method: &str, path: &str, operation: &Value) -> Self {
+        let greedy = operation
+            .get(X_GREEDY_PARAMS)
+            .and_then(Value::as_array)
+            .map(|names| names.iter().filter_map(Value::as_str).collect::<Vec<&str>>())
+            .unwrap_or_default();
+        let (pattern, params) = path_pattern(path, &greedy);
+        let greedy = pattern.ends_with(".+)$");
+        Self {
+            method: method.to_uppercase(),
+            path: path.to_string(),
+            pattern,
+            params,
+            operation_id: operation.get("operationId").and_then(Value::as_str).unwrap_or_default().to_string(),
+            caution: Caution::of(operation).map(|caution| caution.level),
+            greedy,
+        }
+    }
+
+    /// Lower is more specific: greedy routes after the rest, then by parameter count.
+    fn specificity(&self) -> (bool, usize) {
+        (self.greedy, self.params.len())
+    }
+}
+
+/// Regex and parameter names for a path template. Parameters match one
+/// segment, or the rest of the path when named in `greedy` or written `{+name}`.
+pub fn path_pattern(path: &str, greedy: &[&str]) -> (String, Vec<String>) {
+    let mut pattern = String::from("^");
+    let mut params = Vec::new();
+    let mut rest = path;
+    while let Some(open) = rest.find('{') {
+        let Some(close) = rest[open..].find('}').map(|close| open + close) else {
+            break;
+        };
+        pattern.push_str(&escape(&rest[..open]));
+        let raw = &rest[open + 1..close];
+        let name = raw.trim_start_matches('+');
+        let group = group_name(name, params.len());
+        if raw.starts_with('+') || greedy.contains(&name) {
+            pattern.push_str(&format!("(?P<{group}>.+)"));
+        } else {
+            pattern.push_str(&format!("(?P<{group}>[^/]+)"));
+        }
+        params.push(name.to_string());
+        rest = &rest[close + 1..];
+    }
+    pattern.push_str(&escape(rest));
+    pattern.push('$');
+    (pattern, params)
+}
+
+/// Escape regex syntax in a literal part of a path. Unlike [`regex::escape`],
+/// `-` is left alone, since JavaScript's unicode mode rejects `\-`.
+fn escape(literal: &str) -> String {
+    let mut escaped = String::with_capacity(literal.len());
+    for c in literal.chars() {
+        if "\\.+*?()|[]{}^$".contains(c) {
+            escaped.push('\\');
+        }
+        escaped.push(c);
+    }
+    escaped
+}
+
+/// A regex group name for a parameter: invalid characters become `_`, and a
+/// leading digit or a name with nothing left gets a `p{index}_` prefix.
+fn group_name(name: &str, index: usize) -> String {
+    let group = name
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() || c == '_' { c } else { '_' })
+        .collect::<String>();
+    if group.is_empty() || group.starts_with(|c: char| c.is_ascii_digit()) {
+        format!("p{index}_{group}")
+    } else {
+        group
+    }
+}
+
+/// Every route of a spec, serialized as a JSON array of [`Route`]s.
+#[derive(Serialize, Debug, Clone)]
+#[serde(transparent)]
+pub struct RoutingTable {
+    pub routes: Vec<Route>,
+    /// Index-aligned with `routes`.
+    #[serde(skip)]
+    compiled: Vec<Regex>,
+}
+
+/// Two routes that match the same concrete path with neither more specific.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RouteTie {
+    pub method: String,
+    pub first: String,
+    pub second: String,
+}
+
+impl RoutingTable {
+    pub fn new(routes: Vec<Route>) -> Self {
+        let compiled = routes
+            .iter()
+            .map(|route| Regex::new(&route.pattern).expect("route patterns should compile"))
+            .collect();
+        Self { routes, compiled }
+    }
+
+    pub fn to_json(&self) -> serde_json::Result<String> {
+        serde_json::to_string_pretty(self)
+    }
+
+    /// The most specific route for a request and its parameter values, in
+    /// path order. A greedy parameter is least specific, then more parameters;
+    /// between routes as specific, the one earlier in the table wins (see
+    /// [`RoutingTable::ties`]).
+    pub fn match_path(&self, method: &str, path: &str) -> Option<(&Route, Vec<(String, String)>)> {
+        self.routes
+            .iter()
+            .zip(self.compiled.iter())
+            .filter(|(route, _)| route.method.eq_ignore_ascii_case(method))
+            .filter_map(|(route, regex)| {
+                let captures = regex.captures(path)?;
+                let values = route.params
+                    .iter()
+                    .zip(captures.iter().skip(1))
+                    .map(|(name, value)| (name.clone(), value.map_or("", |m| m.as_str()).to_string()))
+                    .collect::<Vec<(String, String)>>();
+                Some((route, values))
+            })
+            .min_by_key(|(route, _)| route.specificity())
+    }
+
+    /// Pairs of routes that could both match some request with neither more
+    /// specific, which [`RoutingTable::match_path`] settles by table order.
+    pub fn ties(&self) -> Vec<RouteTie> {
+        let mut ties = Vec::new();
+        for (i, a) in self.routes.iter().enumerate() {
+            for b in self.routes[i + 1..].iter() {
+                if a.method == b.method && a.specificity() == b.specificity() && overlap(&segments(a), &segments(b)) {
+                    ties.push(RouteTie { method: a.method.clone(), first: a.path.clone(), second: b.path.clone() });
+                }
+            }
+        }
+        ties
+    }
+}
+
+/// A path segment as far as overlapping goes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Segment<'a> {
+    Literal(&'a str),
+    Param,
+    /// Matches one or more segments.
+    Rest,
+}
+
+fn segments(route: &Route) -> Vec<Segment<'_>> {
+    let segments = route.path.trim_start_matches('/').split('/').collect::<Vec<&str>>();
+    let last = segments.len() - 1;
+    segments
+        .into_iter()
+        .enumerate()
+        .map(|(i, segment)| {
+            if !(segment.starts_with('{') && segment.ends_with('}')) {
+                Segment::Literal(segment)
+            } else if route.greedy && i == last {
+                // Greedy parameters are last in every LCU path.
+                Segment::Rest
+            } else {
+                Segment::Param
+            }
+        })
+        .collect()
+}
+
+/// Whether some concrete path matches both segment lists.
+fn overlap(a: &[Segment<'_>], b: &[Segment<'_>]) -> bool {
+    match (a.split_first(), b.split_first()) {
+        (None, None) => true,
+        (Some((Segment::Rest, _)), Some(_)) | (Some(_), Some((Segment::Rest, _))) => true,
+        (Some((Segment::Literal(x), a)), Some((Segment::Literal(y), b))) => x == y && overlap(a, b),
+        (Some((_, a)), Some((_, b))) => overlap(a, b),
+        _ => false,
+    }
+}
+
+impl OpenApiSpec {
+    /// Every operation as a [`Route`], sorted by path then method.
+    pub fn routing_table(&self) -> RoutingTable {
+        let mut routes = Vec::new();
+        for (path, item) in self.paths.iter() {
+            let Some(item) = item.as_object() else {
+                continue;
+            };
+            for (method, operation) in item.iter() {
+                routes.push(Route::new(method, path, operation));
+            }
+        }
+        routes.sort_by(|a, b| a.path.cmp(&b.path).then_with(|| a.method.cmp(&b.method)));
+        RoutingTable::new(routes)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+    use crate::tests::miniature;
+
+    fn route(method: &str, path: &str, greedy: &[&str]) -> Route {
+        Route::new(method, path, &json!({ "operationId": path, X_GREEDY_PARAMS: greedy }))
+    }
+
+    #[test]
+    fn test_path_pattern() {
+        assert_eq!(
+            path_pattern("/lol-summoner/v1/summoners/{id}", &[]),
+            (r"^/lol-summoner/v1/summoners/(?P<id>[^/]+)$".to_string(), vec!["id".to_string()])
+        );
+        let (pattern, params) = path_pattern("/riot-client/{app-name}/v1.0/{+path}", &[]);
+        assert_eq!(pattern, r"^/riot-client/(?P<app_name>[^/]+)/v1\.0/(?P<path>.+)$");
+        assert_eq!(params, ["app-name", "path"]);
+        assert_eq!(path_pattern("/data/{path}", &["path"]).0, r"^/data/(?P<path>.+)$");
+        assert_eq!(path_pattern("/{1}", &[]).0, r"^/(?P<p0_1>[^/]+)$");
+    }
+
+    #[test]
+    fn test_match_path() {
+        let table = OpenApiSpec::from_help(&miniature(), "1.0.0").unwrap().routing_table();
+        assert!(table.ties().is_empty());
+
+        let (route, params) = table.match_path("get", "/lol-summoner/v1/summoners/42").unwrap();
+        assert_eq!(route.operation_id, "GetLolSummonerV1SummonersById");
+        assert_eq!(params, [("id".to_string(), "42".to_string())]);
+        assert!(table.match_path("GET", "/lol-summoner/v1/summoners/42/extra").is_none());
+        assert!(table.match_path("DELETE", "/lol-summoner/v1/summoners/42").is_none());
+
+        let json: Value = serde_json::from_str(&table.to_json().unwrap()).unwrap();
+        assert_eq!(json.as_array().unwrap().len(), table.routes.len());
+        assert_eq!(json[0]["method"], "POST");
+        assert_eq!(json[0]["path"], "/Help");
+    }
+
+    #[test]
+    fn test_greedy_param() {
+        let table = RoutingTable::new(vec![route("GET", "/lol-game-data/assets/{path}", &["path"])]);
+        let (_, params) = table.match_path("GET", "/lol-game-data/assets/v1/champions/1.json").unwrap();
+        assert_eq!(params, [("path".to_string(), "v1/champions/1.json".to_string())]);
+        assert!(table.match_path("GET", "/lol-game-data/assets/").is_none());
+    }
+
+    #[test]
+    fn test_specificity() {
+        let table = RoutingTable::new(vec![
+            route("GET", "/lol-lobby/v2/{a}/{b}", &[]),
+            route("GET", "/lol-lobby/v2/lobby/{b}", &[]),
+            route("GET", "/lol-lobby/v2/{a}/members", &[]),
+            route("GET", "/lol-lobby/v2/{+rest}", &[]),
+        ]);
+        // The literal segment beats a second parameter, and either beats the greedy catch-all.
+        let (route, params) = table.match_path("GET", "/lol-lobby/v2/lobby/members").unwrap();
+        assert_eq!(route.path, "/lol-lobby/v2/lobby/{b}");
+        assert_eq!(params, [("b".to_string(), "members".to_string())]);
+        assert_eq!(table.match_path("GET", "/lol-lobby/v2/a/b/c").unwrap().0.path, "/lol-lobby/v2/{+rest}");
+
+        // Both one-parameter routes match /lol-lobby/v2/lobby/members; the first wins and the tie is reported.
+        assert_eq!(table.ties(), [
+            RouteTie {
+                method: "GET".to_string(),
+                first: "/lol-lobby/v2/lobby/{b}".to_string(),
+                second: "/lol-lobby/v2/{a}/members".to_string(),
+            },
+        ]);
+    }
+}
@@ -0,0 +1,62 @@
+//! Matching operations by method, path, and tag, shared by the classifiers
+//! in [`crate::caution`], [`crate::servers`], [`crate::stability`], and [`crate::workflow`].
+
+use serde::{ Deserialize, Serialize };
+use serde_json::Value;
+
+/// Matches operations by method, path prefix, and tag. Unset fields match anything.
+#[derive(Deserialize, Serialize, Debug, Clone, Default, PartialEq, Eq)]
+pub struct OperationMatch {
+    /// Upper-case HTTP method.
+    pub method: Option<String>,
+    /// Matched whole segments at a time, so `/lol-store` doesn't match `/lol-store-front`.
+    pub path_prefix: Option<String>,
+    pub tag: Option<String>,
+}
+
+impl OperationMatch {
+    pub fn matches(&self, method: &str, path: &str, tags: &[&str]) -> bool {
+        let method_matches = self.method.as_ref().is_none_or(|m| m.eq_ignore_ascii_case(method));
+        let path_matches = self.path_prefix.as_ref().is_none_or(|prefix| prefix_match(prefix, path));
+        let tag_matches = self.tag.as_ref().is_none_or(|tag| tags.contains(&tag.as_str()));
+        method_matches && path_matches && tag_matches
+    }
+}
+
+/// Whether `path` is `prefix` or below it, whole segments at a time.
+pub fn prefix_match(prefix: &str, path: &str) -> bool {
+    let prefix = prefix.trim_end_matches('/');
+    path == prefix || path.strip_prefix(prefix).is_some_and(|rest| rest.starts_with('/'))
+}
+
+/// The tags of a generated operation.
+pub fn tags_of(operation: &Value) -> Vec<&str> {
+    operation
+        .get("tags")
+        .and_then(Value::as_array)
+        .map(|tags| tags.iter().filter_map(Value::as_str).collect())
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_prefix_match() {
+        assert!(prefix_match("/lol-store", "/lol-store"));
+        assert!(prefix_match("/lol-store/", "/lol-store/v1/catalog"));
+        assert!(!prefix_match("/lol-store", "/lol-store-front/v1"));
+    }
+
+    #[test]
+    fn test_operation_match() {
+        let all = OperationMatch::default();
+        assert!(all.matches("GET", "/anything", &[]));
+
+        let post = OperationMatch { method: Some("POST".to_string()), tag: Some("lol-lobby".to_string()), ..Default::default() };
+        assert!(post.matches("post", "/lol-lobby/v2/lobby", &["plugins", "lol-lobby"]));
+        assert!(!post.matches("get", "/lol-lobby/v2/lobby", &["lol-lobby"]));
+        assert!(!post.matches("post", "/lol-lobby/v2/lobby", &["plugins"]));
+    }
+}
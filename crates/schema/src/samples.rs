@@ -0,0 +1,368 @@
+//! Request samples for documentation renderers that read `x-codeSamples`,
+//! such as ReDoc.
+//!
+//! Every operation gets a curl sample, and optionally one in a second
+//! [`SampleLanguage`]. Samples read the port and password from the
+//! `LCU_PORT` and `LCU_PASSWORD` environment variables.
+
+use serde_json::{ Value, json };
+
+use crate::{ example::example_from_schema, openapi::{ Components, OpenApiSpec, SchemaObject } };
+
+/// Operation extension holding a list of `{ lang, source }` samples.
+pub const X_CODE_SAMPLES: &str = "x-codeSamples";
+
+/// Language of the optional second sample.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SampleLanguage {
+    /// `requests`.
+    Python,
+    /// `fetch`, in Node or a browser.
+    JavaScript,
+}
+
+impl SampleLanguage {
+    /// The `lang` ReDoc expects.
+    pub fn lang(self) -> &'static str {
+        match self {
+            Self::Python => "Python",
+            Self::JavaScript => "JavaScript",
+        }
+    }
+}
+
+/// The parts of an operation a sample needs.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SampleRequest {
+    /// Upper-case HTTP method.
+    pub method: String,
+    /// Path with parameters filled in.
+    pub path: String,
+    /// Required query parameters and their example values.
+    pub query: Vec<(String, String)>,
+    /// Content type and example body.
+    pub body: Option<(String, Value)>,
+}
+
+impl SampleRequest {
+    /// Gather a request from an operation, with placeholder values from the
+    /// example generator. A body whose JSON is longer than `max_body_len`
+    /// is generated again with shallower refs until it fits, or left `{}`.
+    pub fn new(method: &str, path: &str, operation: &Value, components: &Components, options: &CodeSamples) -> Self {
+        let mut path = path.to_string();
+        let mut query = Vec::new();
+        for parameter in operation["parameters"].as_array().into_iter().flatten() {
+            let Some(name) = parameter["name"].as_str() else {
+                continue;
+            };
+            let value = parameter_value(name, &parameter["schema"]);
+            match parameter["in"].as_str() {
+                Some("path") => path = path.replace(&format!("{{{name}}}"), &value),
+                Some("query") if parameter["required"] == true => query.push((name.to_string(), value)),
+                _ => {}
+            }
+        }
+
+        let body = operation["requestBody"]["content"].as_object().and_then(|content| {
+            let (content_type, media) = content
+                .get_key_value("application/json")
+                .or_else(|| content.iter().next())?;
+            let schema = serde_json::from_value::<SchemaObject>(media.get("schema")?.clone()).ok()?;
+            let value = (0..=options.depth_limit)
+                .rev()
+                .map(|depth| example_from_schema(&schema, components, depth))
+                .find(|value| value.to_string().len() <= options.max_body_len)
+                .unwrap_or_else(|| json!({}));
+            Some((content_type.clone(), value))
+        });
+
+        Self { method: method.to_uppercase(), path, query, body }
+    }
+
+    fn url(&self) -> String {
+        let query = self.query
+            .iter()
+            .map(|(name, value)| format!("{name}={value}"))
+            .collect::<Vec<String>>()
+            .join("&");
+        if query.is_empty() { self.path.clone() } else { format!("{}?{query}", self.path) }
+    }
+
+    /// The body as sent: compact JSON, or the text itself for other content types.
+    fn body_text(&self) -> Option<(&str, String)> {
+        self.body.as_ref().map(|(content_type, value)| {
+            match (content_type.as_str(), value) {
+                ("application/json", value) => (content_type.as_str(), value.to_string()),
+                (_, Value::String(text)) => (content_type.as_str(), text.clone()),
+                (_, value) => (content_type.as_str(), value.to_string()),
+            }
+        })
+    }
+}
+
+/// Example text for a parameter: the first enum value, zero or false for
+/// numbers and booleans, else the parameter's name.
+fn parameter_value(name: &str, schema: &Value) -> String {
+    if let Some(first) = schema["enum"].as_array().and_then(|values| values.first()) {
+        return first.as_str().map_or_else(|| first.to_string(), str::to_string);
+    }
+    match schema["type"].as_str() {
+        Some("integer" | "number") => "0".to_string(),
+        Some("boolean") => "false".to_string(),
+        _ => name.to_string(),
+    }
+}
+
+/// A curl command for the request.
+pub fn operation_to_curl(request: &SampleRequest) -> String {
+    let mut lines = vec!["curl -k -u \"riot:$LCU_PASSWORD\"".to_string()];
+    if request.method != "GET" {
+        lines[0].push_str(&format!(" -X {}", request.method));
+    }
+    lines[0].push_str(&format!(" \"https://127.0.0.1:$LCU_PORT{}\"", request.url()));
+    if let Some((content_type, body)) = request.body_text() {
+        lines.push(format!("  -H \"Content-Type: {content_type}\""));
+        lines.push(format!("  -d '{}'", body.replace('\'', r"'\''")));
+    }
+    lines.join(" \\\n")
+}
+
+/// A Python `requests` call for the request.
+pub fn operation_to_python(request: &SampleRequest) -> String {
+    let mut lines = vec![
+        "import os".to_string(),
+        String::new(),
+        "import requests".to_string(),
+        String::new(),
+        format!("response = requests.{}(", request.method.to_lowercase()),
+        format!("    f\"https://127.0.0.1:{{os.environ['LCU_PORT']}}{}\",", request.path),
+        "    auth=(\"riot\", os.environ[\"LCU_PASSWORD\"]),".to_string(),
+    ];
+    if !request.query.is_empty() {
+        let params = request.query
+            .iter()
+            .map(|(name, value)| format!("{}: {}", python_literal(&json!(name)), python_literal(&json!(value))))
+            .collect::<Vec<String>>();
+        lines.push(format!("    params={{{}}},", params.join(", ")));
+    }
+    match &request.body {
+        Some((content_type, value)) if content_type == "application/json" => {
+            lines.push(format!("    json={},", python_literal(value)));
+        }
+        Some(_) => {
+            let (content_type, text) = request.body_text().unwrap_or_default();
+            lines.push(format!("    headers={{\"Content-Type\": {}}},", python_literal(&json!(content_type))));
+            lines.push(format!("    data={},", python_literal(&json!(text))));
+        }
+        None => {}
+    }
+    lines.push("    verify=False,".to_string());
+    lines.push(")".to_string());
+    lines.join("\n")
+}
+
+/// A JavaScript `fetch` call for the request.
+pub fn operation_to_fetch(request: &SampleRequest) -> String {
+    let mut headers = vec!["    Authorization: `Basic ${btoa(`riot:${process.env.LCU_PASSWORD}`)}`,".to_string()];
+    let mut lines = vec![
+        format!("const response = await fetch(`https://127.0.0.1:${{process.env.LCU_PORT}}{}`, {{", request.url()),
+        format!("  method: \"{}\",", request.method),
+    ];
+    let body = request.body_text();
+    if let Some((content_type, _)) = body.as_ref() {
+        headers.push(format!("    \"Content-Type\": {},", json!(content_type)));
+    }
+    lines.push("  headers: {".to_string());
+    lines.extend(headers);
+    lines.push("  },".to_string());
+    match (&request.body, body) {
+        (Some((content_type, value)), _) if content_type == "application/json" => {
+            lines.push(format!("  body: JSON.stringify({value}),"));
+        }
+        (_, Some((_, text))) => lines.push(format!("  body: {},", json!(text))),
+        _ => {}
+    }
+    lines.push("});".to_string());
+    lines.join("\n")
+}
+
+/// A JSON value written as a Python literal.
+fn python_literal(value: &Value) -> String {
+    match value {
+        Value::Null => "None".to_string(),
+        Value::Bool(true) => "True".to_string(),
+        Value::Bool(false) => "False".to_string(),
+        Value::Number(_) | Value::String(_) => value.to_string(),
+        Value::Array(items) => {
+            format!("[{}]", items.iter().map(python_literal).collect::<Vec<String>>().join(", "))
+        }
+        Value::Object(fields) => {
+            let fields = fields
+                .iter()
+                .map(|(name, value)| format!("{}: {}", json!(name), python_literal(value)))
+                .collect::<Vec<String>>();
+            format!("{{{}}}", fields.join(", "))
+        }
+    }
+}
+
+/// Writes [`X_CODE_SAMPLES`] on every operation.
+#[derive(Debug, Clone)]
+pub struct CodeSamples {
+    /// Second language after curl, if any.
+    pub language: Option<SampleLanguage>,
+    /// Longest request body, as compact JSON, before the example is cut down.
+    pub max_body_len: usize,
+    /// Passed to [`example_from_schema`] for request bodies.
+    pub depth_limit: usize,
+}
+
+impl Default for CodeSamples {
+    fn default() -> Self {
+        Self { language: None, max_body_len: 2000, depth_limit: 4 }
+    }
+}
+
+impl CodeSamples {
+    /// The samples for one operation.
+    pub fn samples(&self, method: &str, path: &str, operation: &Value, components: &Components) -> Value {
+        let request = SampleRequest::new(method, path, operation, components, self);
+        let mut samples = vec![json!({ "lang": "Shell", "label": "curl", "source": operation_to_curl(&request) })];
+        match self.language {
+            Some(SampleLanguage::Python) => {
+                samples.push(json!({ "lang": SampleLanguage::Python.lang(), "source": operation_to_python(&request) }));
+            }
+            Some(SampleLanguage::JavaScript) => {
+                samples.push(json!({ "lang": SampleLanguage::JavaScript.lang(), "source": operation_to_fetch(&request) }));
+            }
+            None => {}
+        }
+        Value::Array(samples)
+    }
+
+    /// Write samples on every operation. Returns how many operations got them.
+    pub fn apply(&self, spec: &mut OpenApiSpec) -> usize {
+        let mut count = 0;
+        for (path, item) in spec.paths.iter_mut() {
+            let Some(item) = item.as_object_mut() else {
+                continue;
+            };
+            for (method, operation) in item.iter_mut() {
+                let samples = self.samples(method, path, operation, &spec.components);
+                if let Some(operation) = operation.as_object_mut() {
+                    operation.insert(X_CODE_SAMPLES.to_string(), samples);
+                    count += 1;
+                }
+            }
+        }
+        count
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{ options::SchemaOptions, report::SchemaReport, tests::miniature };
+
+    fn spec(language: Option<SampleLanguage>) -> OpenApiSpec {
+        let options = SchemaOptions {
+            code_samples: Some(CodeSamples { language, ..Default::default() }),
+            ..Default::default()
+        };
+        OpenApiSpec::from_help_with(&miniature(), "1.0.0", &options, &mut SchemaReport::default()).unwrap()
+    }
+
+    fn source(spec: &OpenApiSpec, path: &str, method: &str, index: usize) -> String {
+        spec.paths[path][method][X_CODE_SAMPLES][index]["source"].as_str().unwrap().to_string()
+    }
+
+    #[test]
+    fn test_get_with_params() {
+        let python = spec(Some(SampleLanguage::Python));
+        assert_eq!(
+            source(&python, "/lol-summoner/v1/summoners/{id}", "get", 0),
+            r#"curl -k -u "riot:$LCU_PASSWORD" "https://127.0.0.1:$LCU_PORT/lol-summoner/v1/summoners/0""#
+        );
+        assert_eq!(
+            source(&python, "/lol-summoner/v1/summoners", "get", 0),
+            r#"curl -k -u "riot:$LCU_PASSWORD" "https://127.0.0.1:$LCU_PORT/lol-summoner/v1/summoners?name=name""#
+        );
+        assert_eq!(
+            source(&python, "/lol-summoner/v1/summoners", "get", 1),
+            r#"import os
+
+import requests
+
+response = requests.get(
+    f"https://127.0.0.1:{os.environ['LCU_PORT']}/lol-summoner/v1/summoners",
+    auth=("riot", os.environ["LCU_PASSWORD"]),
+    params={"name": "name"},
+    verify=False,
+)"#
+        );
+
+        let javascript = spec(Some(SampleLanguage::JavaScript));
+        assert_eq!(
+            source(&javascript, "/lol-summoner/v1/summoners", "get", 1),
+            r#"const response = await fetch(`https://127.0.0.1:${process.env.LCU_PORT}/lol-summoner/v1/summoners?name=name`, {
+  method: "GET",
+  headers: {
+    Authorization: `Basic ${btoa(`riot:${process.env.LCU_PASSWORD}`)}`,
+  },
+});"#
+        );
+    }
+
+    #[test]
+    fn test_post_with_body() {
+        let python = spec(Some(SampleLanguage::Python));
+        assert_eq!(
+            source(&python, "/lol-lobby/v2/lobby", "post", 0),
+            r#"curl -k -u "riot:$LCU_PASSWORD" -X POST "https://127.0.0.1:$LCU_PORT/lol-lobby/v2/lobby" \
+  -H "Content-Type: application/json" \
+  -d '{"isCustom":false,"queueId":0}'"#
+        );
+        assert_eq!(
+            source(&python, "/lol-lobby/v2/lobby", "post", 1),
+            r#"import os
+
+import requests
+
+response = requests.post(
+    f"https://127.0.0.1:{os.environ['LCU_PORT']}/lol-lobby/v2/lobby",
+    auth=("riot", os.environ["LCU_PASSWORD"]),
+    json={"isCustom": False, "queueId": 0},
+    verify=False,
+)"#
+        );
+
+        let javascript = spec(Some(SampleLanguage::JavaScript));
+        assert_eq!(
+            source(&javascript, "/lol-lobby/v2/lobby", "post", 1),
+            r#"const response = await fetch(`https://127.0.0.1:${process.env.LCU_PORT}/lol-lobby/v2/lobby`, {
+  method: "POST",
+  headers: {
+    Authorization: `Basic ${btoa(`riot:${process.env.LCU_PASSWORD}`)}`,
+    "Content-Type": "application/json",
+  },
+  body: JSON.stringify({"isCustom":false,"queueId":0}),
+});"#
+        );
+        assert_eq!(javascript.paths["/lol-lobby/v2/lobby"]["post"][X_CODE_SAMPLES][1]["lang"], "JavaScript");
+    }
+
+    #[test]
+    fn test_off_by_default_and_capped() {
+        let plain = OpenApiSpec::from_help(&miniature(), "1.0.0").unwrap();
+        assert!(plain.paths["/lol-lobby/v2/lobby"]["post"].get(X_CODE_SAMPLES).is_none());
+        // curl only without a second language.
+        assert_eq!(spec(None).paths["/lol-lobby/v2/lobby"]["post"][X_CODE_SAMPLES].as_array().unwrap().len(), 1);
+
+        let operation = &plain.paths["/lol-lobby/v2/lobby"]["post"];
+        let capped = CodeSamples { max_body_len: 10, ..Default::default() };
+        let request = SampleRequest::new("post", "/lol-lobby/v2/lobby", operation, &plain.components, &capped);
+        assert_eq!(request.body, Some(("application/json".to_string(), json!({}))));
+        // Same samples every time.
+        assert_eq!(serde_json::to_string(&spec(None)).unwrap(), serde_json::to_string(&spec(None)).unwrap());
+    }
+}
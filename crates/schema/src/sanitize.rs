@@ -0,0 +1,217 @@
+//! Cleaning up help text before it's published or drawn.
+//!
+//! Some LCU descriptions carry stray control characters and broken encoding,
+//! presumably pasted from internal docs. Strict JSON parsers reject the
+//! output and terminals garble it. [`sanitize`] is the one place deciding
+//! what's kept, for the converter and the TUI alike.
+
+use std::borrow::Cow;
+
+#[cfg(feature = "core")]
+use serde_json::Value;
+
+#[cfg(feature = "core")]
+use crate::{ convert::CONSOLE_HELP_EXTENSION, enums::X_ENUM_DESCRIPTION, openapi::{ OpenApiSpec, X_EVENTS }, report::{ ReportKind, SchemaReport } };
+
+/// Characters kept of one text. Longer text is cut off with an ellipsis.
+pub const MAX_TEXT_CHARS: usize = 4096;
+/// Spaces and tabs kept of one run.
+pub const MAX_SPACE_RUN: usize = 8;
+/// Newlines kept of one run, so at most one blank line in a row.
+pub const MAX_NEWLINE_RUN: usize = 2;
+
+/// `text` without control characters other than newline and tab, with
+/// whitespace runs shortened and length capped. Clean text is returned as is.
+pub fn sanitize(text: &str) -> Cow<'_, str> {
+    if !rewrite(text, None) {
+        return Cow::Borrowed(text);
+    }
+    let mut clean = String::with_capacity(text.len().min(MAX_TEXT_CHARS + 3));
+    rewrite(text, Some(&mut clean));
+    Cow::Owned(clean)
+}
+
+/// [`sanitize`] for text that may not be UTF-8, with invalid sequences
+/// replaced by U+FFFD.
+pub fn sanitize_bytes(bytes: &[u8]) -> Cow<'_, str> {
+    match String::from_utf8_lossy(bytes) {
+        Cow::Borrowed(text) => sanitize(text),
+        Cow::Owned(text) => Cow::Owned(sanitize(&text).into_owned()),
+    }
+}
+
+/// Whether `text` needs changing. With `out`, writes the clean text into it;
+/// without, stops at the first change.
+fn rewrite(text: &str, mut out: Option<&mut String>) -> bool {
+    let mut changed = false;
+    let (mut spaces, mut newlines, mut kept) = (0, 0, 0);
+    for c in text.chars() {
+        let keep = match c {
+            '\n' => {
+                spaces = 0;
+                newlines += 1;
+                newlines <= MAX_NEWLINE_RUN
+            }
+            ' ' | '\t' => {
+                spaces += 1;
+                spaces <= MAX_SPACE_RUN
+            }
+            c if c.is_control() => false,
+            _ => {
+                (spaces, newlines) = (0, 0);
+                true
+            }
+        };
+        if keep && kept == MAX_TEXT_CHARS {
+            if let Some(out) = out {
+                out.push('…');
+            }
+            return true;
+        }
+        if !keep {
+            changed = true;
+            if out.is_none() {
+                return true;
+            }
+            continue;
+        }
+        kept += 1;
+        if let Some(out) = out.as_deref_mut() {
+            out.push(c);
+        }
+    }
+    changed
+}
+
+/// Sanitize the descriptions, summaries, console help, and enum descriptions
+/// of `spec`, recording each text that changed in `report`.
+#[cfg(feature = "core")]
+pub fn sanitize_spec(spec: &mut OpenApiSpec, report: &mut SchemaReport) {
+    if let Cow::Owned(clean) = sanitize(&spec.info.description) {
+        record(report, "info.description", &spec.info.description, &clean);
+        spec.info.description = clean;
+    }
+    for (kind, value) in spec.components.iter_mut() {
+        sanitize_value(value, format!("components.{kind}"), report);
+    }
+    for (path, value) in spec.paths.iter_mut() {
+        sanitize_value(value, format!("paths.{path}"), report);
+    }
+    for (name, value) in spec.events.iter_mut() {
+        sanitize_value(value, format!("{X_EVENTS}.{name}"), report);
+    }
+}
+
+#[cfg(feature = "core")]
+fn sanitize_value(value: &mut Value, location: String, report: &mut SchemaReport) {
+    match value {
+        Value::Object(object) => {
+            for (key, value) in object.iter_mut() {
+                let location = format!("{location}.{key}");
+                match (key.as_str(), value) {
+                    ("description" | "summary" | CONSOLE_HELP_EXTENSION, Value::String(text)) => sanitize_string(text, &location, report),
+                    (X_ENUM_DESCRIPTION, Value::Array(texts)) => {
+                        for (i, text) in texts.iter_mut().enumerate() {
+                            if let Value::String(text) = text {
+                                sanitize_string(text, &format!("{location}.{i}"), report);
+                            }
+                        }
+                    }
+                    (_, value) => sanitize_value(value, location, report),
+                }
+            }
+        }
+        Value::Array(values) => {
+            for (i, value) in values.iter_mut().enumerate() {
+                sanitize_value(value, format!("{location}.{i}"), report);
+            }
+        }
+        _ => {}
+    }
+}
+
+#[cfg(feature = "core")]
+fn sanitize_string(text: &mut String, location: &str, report: &mut SchemaReport) {
+    if let Cow::Owned(clean) = sanitize(text) {
+        record(report, location, text, &clean);
+        *text = clean;
+    }
+}
+
+#[cfg(feature = "core")]
+fn record(report: &mut SchemaReport, location: &str, before: &str, after: &str) {
+    report.push(ReportKind::Sanitized, location, format!("{} -> {} characters", before.chars().count(), after.chars().count()));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::miniature;
+
+    #[test]
+    fn test_character_classes() {
+        let cases = [
+            // C0 controls go, apart from newline and tab.
+            ("a\u{0}b\u{7}c\u{1b}[31md", "abc[31md"),
+            ("line\r\nbreak", "line\nbreak"),
+            ("tab\tkept\nnewline kept", "tab\tkept\nnewline kept"),
+            // DEL and C1 controls go.
+            ("a\u{7f}b\u{85}c\u{9f}d", "abcd"),
+            // Other non-ASCII text stays.
+            ("Gêné — ☃ 日本 \u{fffd}", "Gêné — ☃ 日本 \u{fffd}"),
+            // Whitespace runs are shortened.
+            ("a                    b", "a        b"),
+            ("a\t\t\t\t\t\t\t\t\t\t\tb", "a\t\t\t\t\t\t\t\tb"),
+            ("a\n\n\n\n\nb", "a\n\nb"),
+        ];
+        for (text, expected) in cases {
+            assert_eq!(sanitize(text), expected, "{text:?}");
+        }
+    }
+
+    #[test]
+    fn test_invalid_utf8() {
+        assert_eq!(sanitize_bytes(b"ok\xed\xa0\x80ok"), "ok\u{fffd}\u{fffd}\u{fffd}ok");
+        assert_eq!(sanitize_bytes(b"cut\xe2\x82"), "cut\u{fffd}");
+        assert_eq!(sanitize_bytes(b"bell\x07\xff"), "bell\u{fffd}");
+        assert!(matches!(sanitize_bytes(b"plain"), Cow::Borrowed("plain")));
+    }
+
+    #[test]
+    fn test_length_cap() {
+        let long = "a".repeat(MAX_TEXT_CHARS * 2);
+        let clean = sanitize(&long);
+        assert_eq!(clean.chars().count(), MAX_TEXT_CHARS + 1);
+        assert!(clean.ends_with("a…"));
+        // Exactly the cap is left alone.
+        assert!(matches!(sanitize(&long[..MAX_TEXT_CHARS]), Cow::Borrowed(_)));
+    }
+
+    #[test]
+    fn test_clean_text_borrowed() {
+        for text in ["", "Returns the current summoner.", "a\n\nb\t c", "日本語"] {
+            assert!(matches!(sanitize(text), Cow::Borrowed(borrowed) if std::ptr::eq(borrowed, text)), "{text:?}");
+        }
+    }
+
+    #[test]
+    fn test_sanitize_spec() {
+        let mut help = miniature();
+        help.types[0].info.description = "Bad\u{0}type".to_string();
+        help.endpoints[0].info.description = format!("Bad{}endpoint", " ".repeat(40));
+        let mut report = SchemaReport::default();
+        let spec = OpenApiSpec::from_help_with(&help, "1.0.0", &Default::default(), &mut report).unwrap();
+
+        let sanitized = report.of_kind(ReportKind::Sanitized).collect::<Vec<_>>();
+        assert_eq!(sanitized.len(), 2, "{sanitized:?}");
+        let json = serde_json::to_string(&spec).unwrap();
+        assert!(json.contains("Badtype"));
+        assert!(json.contains(&format!("Bad{}endpoint", " ".repeat(MAX_SPACE_RUN))));
+        assert!(!json.contains("\\u0000"));
+
+        // The miniature itself is clean.
+        let mut report = SchemaReport::default();
+        OpenApiSpec::from_help_with(&miniature(), "1.0.0", &Default::default(), &mut report).unwrap();
+        assert_eq!(report.of_kind(ReportKind::Sanitized).count(), 0);
+    }
+}
@@ -0,0 +1,379 @@
+//! Structured search over a spec's operations, like "POST operations under
+//! lol-lobby returning `LolLobbyLobbyDto`".
+//!
+//! A [`Query`] is a list of [`Clause`]s. Required clauses must all match;
+//! optional ones only rank the hits, by how many of them each matched.
+//! Queries are built in code or parsed from a line like
+//! `method:post tag:lol-lobby returns:LolLobbyLobbyDto`, see [`Query::from_str`].
+
+use std::{ cmp::Reverse, fmt, str::FromStr };
+
+use serde::Serialize;
+use serde_json::Value;
+
+use crate::{ index::SpecIndex, openapi::OpenApiSpec, workflow::glob_match };
+
+/// One condition on an operation.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Clause {
+    /// The HTTP method, in any case.
+    Method(String),
+    /// One of the operation's tags, exactly.
+    Tag(String),
+    /// A path glob, where `*` matches any run of characters and `?` one.
+    Path(String),
+    /// A component the responses contain, directly or inside another, as
+    /// [`SpecIndex::produced_by`] has it.
+    Returns(String),
+    /// A parameter named `name`, optionally only `location`s (`query`, `path`,
+    /// ...) and only required or optional ones.
+    Param { name: String, location: Option<String>, required: Option<bool> },
+    /// Text in the summary or description, in any case.
+    Text(String),
+}
+
+impl Clause {
+    fn matches(&self, operation: &Operation, index: &SpecIndex) -> bool {
+        match self {
+            Self::Method(method) => operation.method.eq_ignore_ascii_case(method),
+            Self::Tag(tag) => {
+                let tags = operation.value.get("tags").and_then(Value::as_array).into_iter().flatten();
+                tags.filter_map(Value::as_str).any(|t| t == tag)
+            }
+            Self::Path(glob) => glob_match(glob, operation.path),
+            Self::Returns(component) => {
+                index.produced_by.get(component).is_some_and(|ids| ids.contains(operation.operation_id))
+            }
+            Self::Param { name, location, required } => {
+                let mut parameters = operation.value.get("parameters").and_then(Value::as_array).into_iter().flatten();
+                parameters.any(|parameter| {
+                    parameter["name"].as_str() == Some(name) &&
+                        location.as_ref().is_none_or(|location| parameter["in"].as_str() == Some(location)) &&
+                        required.is_none_or(|required| parameter["required"].as_bool().unwrap_or(false) == required)
+                })
+            }
+            Self::Text(text) => {
+                let text = text.to_lowercase();
+                ["summary", "description"]
+                    .iter()
+                    .filter_map(|field| operation.value.get(field).and_then(Value::as_str))
+                    .any(|field| field.to_lowercase().contains(&text))
+            }
+        }
+    }
+}
+
+/// The clause in the query syntax, quoted where needed, so it parses back.
+impl fmt::Display for Clause {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let quoted = |value: &str| {
+            if value.is_empty() || value.contains(char::is_whitespace) { format!("\"{value}\"") } else { value.to_string() }
+        };
+        match self {
+            Self::Method(method) => write!(f, "method:{}", quoted(method)),
+            Self::Tag(tag) => write!(f, "tag:{}", quoted(tag)),
+            Self::Path(glob) => write!(f, "path:{}", quoted(glob)),
+            Self::Returns(component) => write!(f, "returns:{}", quoted(component)),
+            Self::Param { name, location, required } => {
+                let location = location.as_ref().map(|location| format!("{location}.")).unwrap_or_default();
+                let required = match required {
+                    Some(true) => "!",
+                    Some(false) => "?",
+                    None => "",
+                };
+                write!(f, "param:{location}{name}{required}")
+            }
+            Self::Text(text) => write!(f, "text:{}", quoted(text)),
+        }
+    }
+}
+
+/// Clauses an operation is searched by.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Query {
+    /// Every one must match.
+    pub required: Vec<Clause>,
+    /// Hits are ranked by how many of these match. With no required clauses,
+    /// at least one must.
+    pub optional: Vec<Clause>,
+}
+
+impl Query {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Only operations matching `clause` as well.
+    pub fn and(mut self, clause: Clause) -> Self {
+        self.required.push(clause);
+        self
+    }
+
+    /// Rank operations matching `clause` higher.
+    pub fn prefer(mut self, clause: Clause) -> Self {
+        self.optional.push(clause);
+        self
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.required.is_empty() && self.optional.is_empty()
+    }
+
+    fn needs_index(&self) -> bool {
+        self.required.iter().chain(self.optional.iter()).any(|clause| matches!(clause, Clause::Returns(_)))
+    }
+}
+
+/// Parses space-separated clauses of `key:value`: `method`, `tag`, `path`,
+/// `returns`, `text`, and `param:[location.]name`, ending in `!` for
+/// required parameters or `?` for optional ones. Values with spaces are
+/// quoted, like `text:"current summoner"`.
+///
+/// Clauses starting with `~` are optional, and so are bare words, which
+/// search the text.
+impl FromStr for Query {
+    type Err = QueryError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut query = Query::new();
+        for token in tokenize(s)? {
+            let (optional, key) = match token.key.as_deref().map(|key| key.strip_prefix('~').map_or((false, key), |key| (true, key))) {
+                Some((optional, key)) => (optional, Some(key)),
+                None => (true, None),
+            };
+            if token.value.is_empty() {
+                return Err(QueryError::EmptyValue(key.unwrap_or_default().to_string()));
+            }
+            let value = token.value;
+            let clause = match key {
+                None | Some("text") => Clause::Text(value),
+                Some("method") => Clause::Method(value),
+                Some("tag") => Clause::Tag(value),
+                Some("path") => Clause::Path(value),
+                Some("returns") => Clause::Returns(value),
+                Some("param") => parse_param(&value)?,
+                Some(key) => return Err(QueryError::UnknownKey(key.to_string())),
+            };
+            query = if optional { query.prefer(clause) } else { query.and(clause) };
+        }
+        Ok(query)
+    }
+}
+
+/// `[location.]name[!|?]`.
+fn parse_param(value: &str) -> Result<Clause, QueryError> {
+    let (rest, required) = match value.strip_suffix('!') {
+        Some(rest) => (rest, Some(true)),
+        None => value.strip_suffix('?').map_or((value, None), |rest| (rest, Some(false))),
+    };
+    let (location, name) = rest.split_once('.').map_or((None, rest), |(location, name)| (Some(location.to_string()), name));
+    if name.is_empty() || location.as_deref() == Some("") {
+        return Err(QueryError::InvalidParam(value.to_string()));
+    }
+    Ok(Clause::Param { name: name.to_string(), location, required })
+}
+
+/// A token of the query syntax: the key before an unquoted `:`, if any, and
+/// the value with its quotes removed.
+struct Token {
+    key: Option<String>,
+    value: String,
+}
+
+fn tokenize(s: &str) -> Result<Vec<Token>, QueryError> {
+    let mut tokens = Vec::new();
+    let mut chars = s.chars().peekable();
+    loop {
+        while chars.next_if(|c| c.is_whitespace()).is_some() {}
+        if chars.peek().is_none() {
+            return Ok(tokens);
+        }
+        let mut token = Token { key: None, value: String::new() };
+        let mut quoted = false;
+        while let Some(c) = chars.next_if(|c| quoted || !c.is_whitespace()) {
+            match c {
+                '"' => quoted = !quoted,
+                ':' if !quoted && token.key.is_none() => token.key = Some(std::mem::take(&mut token.value)),
+                c => token.value.push(c),
+            }
+        }
+        if quoted {
+            return Err(QueryError::UnclosedQuote);
+        }
+        tokens.push(token);
+    }
+}
+
+/// Why a query line doesn't parse.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum QueryError {
+    /// A `key:` that isn't a clause.
+    UnknownKey(String),
+    /// A `key:` with nothing after it.
+    EmptyValue(String),
+    /// A `param:` that isn't `[location.]name[!|?]`.
+    InvalidParam(String),
+    UnclosedQuote,
+}
+
+impl fmt::Display for QueryError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::UnknownKey(key) => {
+                write!(f, "unknown search key {key}, expected method, tag, path, returns, param or text")
+            }
+            Self::EmptyValue(key) => write!(f, "{key}: has no value"),
+            Self::InvalidParam(value) => write!(f, "param:{value} isn't [location.]name[!|?]"),
+            Self::UnclosedQuote => write!(f, "a quote isn't closed"),
+        }
+    }
+}
+
+impl std::error::Error for QueryError {}
+
+/// An operation a query found.
+#[derive(Serialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct SearchHit {
+    pub operation_id: String,
+    pub path: String,
+    /// Upper case, e.g. `POST`.
+    pub method: String,
+    /// The clauses it matched, required ones first, in the query syntax.
+    pub matched: Vec<String>,
+    /// How many optional clauses it matched.
+    pub score: usize,
+}
+
+/// An operation of the spec being searched.
+struct Operation<'a> {
+    operation_id: &'a str,
+    path: &'a str,
+    method: &'a str,
+    value: &'a Value,
+}
+
+/// Operations of `spec` matching `q`, best first, then by path and method.
+pub fn query(spec: &OpenApiSpec, q: &Query) -> Vec<SearchHit> {
+    let index = if q.needs_index() { SpecIndex::build(spec) } else { SpecIndex::default() };
+    let operations = spec.paths.iter().flat_map(|(path, item)| {
+        item.as_object().into_iter().flatten().filter_map(move |(method, value)| {
+            let operation_id = value.get("operationId")?.as_str()?;
+            Some(Operation { operation_id, path, method, value })
+        })
+    });
+
+    let mut hits = Vec::new();
+    for operation in operations {
+        if !q.required.iter().all(|clause| clause.matches(&operation, &index)) {
+            continue;
+        }
+        let optional = q.optional.iter().filter(|clause| clause.matches(&operation, &index)).collect::<Vec<&Clause>>();
+        if q.required.is_empty() && optional.is_empty() {
+            continue;
+        }
+        hits.push(SearchHit {
+            operation_id: operation.operation_id.to_string(),
+            path: operation.path.to_string(),
+            method: operation.method.to_uppercase(),
+            matched: q.required.iter().chain(optional.iter().copied()).map(Clause::to_string).collect(),
+            score: optional.len(),
+        });
+    }
+    hits.sort_by(|a, b| (Reverse(a.score), &a.path, &a.method).cmp(&(Reverse(b.score), &b.path, &b.method)));
+    hits
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::miniature_spec;
+
+    fn ids(hits: &[SearchHit]) -> Vec<&str> {
+        hits.iter().map(|hit| hit.operation_id.as_str()).collect()
+    }
+
+    fn search(line: &str) -> Vec<SearchHit> {
+        query(&miniature_spec("1.0.0"), &line.parse().unwrap())
+    }
+
+    #[test]
+    fn test_clauses() {
+        let spec = miniature_spec("1.0.0");
+        let by = |clause: Clause| ids(&query(&spec, &Query::new().and(clause))).into_iter().map(str::to_string).collect::<Vec<String>>();
+
+        assert_eq!(by(Clause::Method("post".to_string())), ["Help", "Subscribe", "PostLolLobbyV2Lobby"]);
+        assert_eq!(by(Clause::Tag("lol-lobby".to_string())), ["DeleteLolLobbyV2Lobby", "PostLolLobbyV2Lobby"]);
+        assert_eq!(by(Clause::Path("/lol-summoner/*/{id}".to_string())), ["GetLolSummonerV1SummonersById"]);
+        assert_eq!(by(Clause::Returns("LolLobbyLobbyDto".to_string())), ["PostLolLobbyV2Lobby"]);
+        // Reached through LolSummonerSummoner.
+        assert_eq!(by(Clause::Returns("LolSummonerProfilePrivacySetting".to_string())).len(), 3);
+        assert_eq!(by(Clause::Text("CREATE A".to_string())), ["PostLolLobbyV2Lobby"]);
+
+        let param = |location: Option<&str>, required| Clause::Param {
+            name: "format".to_string(),
+            location: location.map(str::to_string),
+            required,
+        };
+        assert_eq!(by(param(None, None)), ["Help", "Subscribe"]);
+        assert_eq!(by(param(Some("query"), Some(false))), ["Help", "Subscribe"]);
+        assert!(by(param(Some("path"), None)).is_empty());
+        assert!(by(param(None, Some(true))).is_empty());
+    }
+
+    #[test]
+    fn test_combined() {
+        let hits = search("method:post tag:lol-lobby returns:LolLobbyLobbyDto");
+        assert_eq!(ids(&hits), ["PostLolLobbyV2Lobby"]);
+        assert_eq!(hits[0].path, "/lol-lobby/v2/lobby");
+        assert_eq!(hits[0].method, "POST");
+        assert_eq!(hits[0].matched, ["method:post", "tag:lol-lobby", "returns:LolLobbyLobbyDto"]);
+        assert_eq!(hits[0].score, 0);
+
+        assert_eq!(ids(&search("param:query.name! method:get")), ["GetLolSummonerV1Summoners"]);
+        assert!(search("method:get tag:lol-lobby").is_empty());
+    }
+
+    #[test]
+    fn test_ranking() {
+        // Bare words are optional: the more an operation matches, the higher.
+        let hits = search("method:get ~path:/lol-summoner/v1/summoners* summoner ~returns:LolSummonerSummoner");
+        assert_eq!(
+            ids(&hits),
+            ["GetLolSummonerV1SummonersById", "GetLolSummonerV1Summoners", "GetLolSummonerV1CurrentSummoner"]
+        );
+        assert_eq!(hits.iter().map(|hit| hit.score).collect::<Vec<usize>>(), [3, 2, 1]);
+        assert_eq!(hits[1].matched, ["method:get", "path:/lol-summoner/v1/summoners*", "returns:LolSummonerSummoner"]);
+
+        // With only optional clauses, operations matching none aren't hits.
+        let hits = search("lobby subscribes");
+        assert_eq!(ids(&hits), ["Subscribe", "PostLolLobbyV2Lobby"]);
+    }
+
+    #[test]
+    fn test_parse() {
+        let query: Query = r#"tag:"Workflow: Champ select" ~method:get param:path.id! "current summoner""#.parse().unwrap();
+        assert_eq!(
+            query,
+            Query::new()
+                .and(Clause::Tag("Workflow: Champ select".to_string()))
+                .prefer(Clause::Method("get".to_string()))
+                .and(Clause::Param { name: "id".to_string(), location: Some("path".to_string()), required: Some(true) })
+                .prefer(Clause::Text("current summoner".to_string()))
+        );
+        // Clauses print back in the syntax.
+        let clauses = query.required.iter().chain(query.optional.iter()).map(Clause::to_string).collect::<Vec<String>>();
+        assert_eq!(clauses, ["tag:\"Workflow: Champ select\"", "param:path.id!", "method:get", "text:\"current summoner\""]);
+        assert_eq!(clauses.join(" ").parse::<Query>().unwrap().required.len(), 4);
+
+        assert!("".parse::<Query>().unwrap().is_empty());
+        assert_eq!("param:name?".parse::<Query>().unwrap().required[0].to_string(), "param:name?");
+        assert_eq!("text:a:b".parse::<Query>().unwrap().required, [Clause::Text("a:b".to_string())]);
+
+        assert_eq!("verb:post".parse::<Query>(), Err(QueryError::UnknownKey("verb".to_string())));
+        assert_eq!("method:".parse::<Query>(), Err(QueryError::EmptyValue("method".to_string())));
+        assert_eq!("param:query.".parse::<Query>(), Err(QueryError::InvalidParam("query.".to_string())));
+        assert_eq!("param:.id".parse::<Query>(), Err(QueryError::InvalidParam(".id".to_string())));
+        assert_eq!("text:\"lobby".parse::<Query>(), Err(QueryError::UnclosedQuote));
+    }
+}
@@ -0,0 +1,210 @@
+//! Operations served by the Riot Client instead of the League Client.
+//!
+//! Both processes answer on `127.0.0.1`, each on its own port from its own
+//! lockfile. Operations the Riot Client hosts get a `servers` entry pointing
+//! at [`riot_client_server`], so tools don't send them to the League Client.
+//!
+//! A few console URLs name their host, see [`crate::help::split_console_url`].
+//! Those on the game client's Live Client Data port get
+//! [`live_client_data_server`] instead.
+
+use std::collections::BTreeMap;
+
+use serde::{ Deserialize, Serialize };
+use serde_json::Value;
+
+use crate::{ openapi::{ OpenApiSpec, ServerSpec, ServerVariable }, rules::prefix_match };
+
+/// Name of the port variable in [`riot_client_server`]'s URL.
+pub const RIOT_CLIENT_PORT: &str = "riotClientPort";
+
+/// The Riot Client's local server, with its port left to fill in.
+pub fn riot_client_server() -> ServerSpec {
+    let port = ServerVariable {
+        default: "0".to_string(),
+        description: Some("Port of the Riot Client's local API, from its lockfile. It changes every launch.".to_string()),
+    };
+    ServerSpec {
+        url: format!("https://127.0.0.1:{{{RIOT_CLIENT_PORT}}}"),
+        description: Some("Riot Client".to_string()),
+        variables: BTreeMap::from([(RIOT_CLIENT_PORT.to_string(), port)]),
+    }
+}
+
+/// Operation extension holding the host an absolute console URL named, like
+/// `127.0.0.1:2999`. The operation's path is the URL's path alone.
+pub const X_EXTERNAL_HOST: &str = "x-external-host";
+
+/// Port of the game client's Live Client Data API. Unlike the lockfile
+/// ports, it doesn't change.
+pub const LIVE_CLIENT_DATA_PORT: u16 = 2999;
+
+/// The game client's Live Client Data API, which only answers during a game.
+pub fn live_client_data_server() -> ServerSpec {
+    ServerSpec {
+        url: format!("https://127.0.0.1:{LIVE_CLIENT_DATA_PORT}"),
+        description: Some("Live Client Data".to_string()),
+        variables: BTreeMap::new(),
+    }
+}
+
+/// Whether a console URL's host is this machine, as `localhost` or
+/// `127.0.0.1`, with or without a port.
+pub fn is_local_host(host: &str) -> bool {
+    let (name, _) = split_port(host);
+    name == "localhost" || name == "127.0.0.1"
+}
+
+/// The server for operations on `host`, if it's the Live Client Data API.
+pub fn host_server(host: &str) -> Option<ServerSpec> {
+    let (_, port) = split_port(host);
+    (is_local_host(host) && port == Some(LIVE_CLIENT_DATA_PORT)).then(live_client_data_server)
+}
+
+/// `host[:port]` without any `user@` in front.
+fn split_port(host: &str) -> (&str, Option<u16>) {
+    let host = host.rsplit_once('@').map_or(host, |(_, host)| host);
+    match host.rsplit_once(':') {
+        Some((name, port)) => (name, port.parse().ok()),
+        None => (host, None),
+    }
+}
+
+/// Matches operations by path prefix and tag. Unset fields match anything.
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Eq)]
+pub struct ServerRule {
+    /// Matched whole segments at a time, like [`crate::rules::OperationMatch::path_prefix`].
+    pub path_prefix: Option<String>,
+    pub tag: Option<String>,
+    /// `false` keeps matching operations on the League Client, for
+    /// overriding a default rule.
+    pub riot_client: bool,
+}
+
+impl ServerRule {
+    pub fn new(path_prefix: &str) -> Self {
+        Self { path_prefix: Some(path_prefix.to_string()), tag: None, riot_client: true }
+    }
+
+    pub fn matches(&self, path: &str, tags: &[&str]) -> bool {
+        let path_matches = self.path_prefix.as_ref().is_none_or(|prefix| prefix_match(prefix, path));
+        let tag_matches = self.tag.as_ref().is_none_or(|tag| tags.contains(&tag.as_str()));
+        path_matches && tag_matches
+    }
+}
+
+/// The built-in rules.
+pub fn default_rules() -> Vec<ServerRule> {
+    vec![ServerRule::new("/riotclient/region-locale")]
+}
+
+/// Finds Riot Client operations with user overrides checked before the
+/// [`default_rules`].
+#[derive(Debug, Clone, Default)]
+pub struct ServerRules {
+    pub overrides: Vec<ServerRule>,
+}
+
+impl ServerRules {
+    pub fn is_riot_client(&self, path: &str, tags: &[&str]) -> bool {
+        self.overrides
+            .iter()
+            .chain(default_rules().iter())
+            .find(|rule| rule.matches(path, tags))
+            .is_some_and(|rule| rule.riot_client)
+    }
+}
+
+/// Writes [`riot_client_server`] as the `servers` of every operation
+/// [`ServerRules`] finds. Other operations keep the document's server.
+#[derive(Debug, Clone)]
+pub struct ServerAnnotations<'a> {
+    pub rules: &'a ServerRules,
+}
+
+impl ServerAnnotations<'_> {
+    /// Returns the number of operations that were annotated.
+    pub fn apply(&self, spec: &mut OpenApiSpec) -> usize {
+        let servers = serde_json::to_value([riot_client_server()]).unwrap_or_default();
+        let mut count = 0;
+        for (path, item) in spec.paths.iter_mut() {
+            let operations = item.as_object_mut().into_iter().flat_map(|item| item.values_mut()).filter_map(Value::as_object_mut);
+            for operation in operations {
+                let tags = operation
+                    .get("tags")
+                    .and_then(Value::as_array)
+                    .map(|tags| tags.iter().filter_map(Value::as_str).collect::<Vec<&str>>())
+                    .unwrap_or_default();
+                if self.rules.is_riot_client(path, &tags) {
+                    operation.insert("servers".to_string(), servers.clone());
+                    count += 1;
+                }
+            }
+        }
+        count
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{ help::ExtendedHelp, openapi::Operation, options::SchemaOptions, report::SchemaReport, tests::miniature };
+
+    /// The miniature with the Riot Client's region and locale endpoint.
+    fn with_riot_client() -> ExtendedHelp {
+        let mut help = miniature();
+        let mut endpoint = serde_json::to_value(&help.endpoints[0]).unwrap();
+        endpoint["name"] = "GetRiotclientRegionLocale".into();
+        endpoint["method"] = "GET".into();
+        endpoint["path"] = "/riotclient/region-locale".into();
+        endpoint["pathParams"] = serde_json::json!([]);
+        endpoint["arguments"] = serde_json::json!([]);
+        endpoint["tags"] = serde_json::json!(["Plugin riotclient"]);
+        help.endpoints.push(serde_json::from_value(endpoint).unwrap());
+        help
+    }
+
+    #[test]
+    fn test_riot_client_operations() {
+        let options = SchemaOptions { servers: Some(ServerRules::default()), ..Default::default() };
+        let spec = OpenApiSpec::from_help_with(&with_riot_client(), "1.0.0", &options, &mut SchemaReport::default()).unwrap();
+
+        let locale = &spec.paths["/riotclient/region-locale"]["get"];
+        assert_eq!(locale["servers"][0]["url"], "https://127.0.0.1:{riotClientPort}");
+        assert!(spec.paths["/lol-summoner/v1/current-summoner"]["get"].get("servers").is_none());
+
+        // The variable reads back as it was declared.
+        let operation: Operation = serde_json::from_value(locale.clone()).unwrap();
+        assert_eq!(operation.servers, [riot_client_server()]);
+        assert!(operation.servers[0].variables[RIOT_CLIENT_PORT].description.is_some());
+        assert!(!operation.additional_fields.contains_key("servers"));
+
+        let plain = OpenApiSpec::from_help(&with_riot_client(), "1.0.0").unwrap();
+        assert!(plain.paths["/riotclient/region-locale"]["get"].get("servers").is_none());
+    }
+
+    #[test]
+    fn test_overrides() {
+        let rules = ServerRules {
+            overrides: vec![
+                ServerRule { riot_client: false, ..ServerRule::new("/riotclient/region-locale") },
+                ServerRule { path_prefix: None, tag: Some("Plugin rso-auth".to_string()), riot_client: true },
+            ],
+        };
+        assert!(!rules.is_riot_client("/riotclient/region-locale", &[]));
+        assert!(rules.is_riot_client("/rso-auth/v1/authorization", &["Plugin rso-auth"]));
+        assert!(!rules.is_riot_client("/riotclient/region-locale-x", &[]));
+        assert!(ServerRules::default().is_riot_client("/riotclient/region-locale", &[]));
+    }
+
+    #[test]
+    fn test_hosts() {
+        assert!(is_local_host("localhost"));
+        assert!(is_local_host("riot:hunter2@127.0.0.1:2999"));
+        assert!(!is_local_host("example.com:2999"));
+        assert_eq!(host_server("127.0.0.1:2999"), Some(live_client_data_server()));
+        assert_eq!(host_server("localhost:2999").unwrap().url, "https://127.0.0.1:2999");
+        assert_eq!(host_server("127.0.0.1:51234"), None);
+        assert_eq!(host_server("example.com:2999"), None);
+    }
+}
@@ -0,0 +1,294 @@
+//! Which LCU version each operation and component first appeared in.
+
+use std::{ collections::{ BTreeMap, BTreeSet }, fs, io, path::Path };
+
+use serde::{ Deserialize, Serialize };
+use serde_json::Value;
+
+use crate::{ error::Error, openapi::OpenApiSpec, size::SpecSizes, stability::Stability, version::LcuVersion };
+
+/// Extension key holding the release an operation or component was added in, e.g. `14.3`.
+pub const X_SINCE: &str = "x-since";
+
+/// What one generation run emitted.
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Eq)]
+pub struct RunRecord {
+    pub version: LcuVersion,
+    pub operations: BTreeSet<String>,
+    pub components: BTreeSet<String>,
+    /// [`crate::slug::X_SLUG`]s of the run, mapped to the operationId or component name.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub slugs: BTreeMap<String, String>,
+    /// Sizes of the spec the run published, see [`crate::size::SizeBudget`].
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sizes: Option<SpecSizes>,
+    /// operationIds marked experimental, see [`crate::stability`].
+    #[serde(default, skip_serializing_if = "BTreeSet::is_empty")]
+    pub experimental: BTreeSet<String>,
+}
+
+impl RunRecord {
+    pub fn from_spec(spec: &OpenApiSpec) -> Self {
+        let ids = |experimental: bool| {
+            spec.paths
+                .values()
+                .filter_map(Value::as_object)
+                .flat_map(|item| item.values())
+                .filter(move |operation| !experimental || Stability::of(operation) == Some(Stability::Experimental))
+                .filter_map(|operation| operation.get("operationId").and_then(Value::as_str))
+                .map(str::to_string)
+                .collect::<BTreeSet<String>>()
+        };
+        let components = spec.components
+            .get("schemas")
+            .and_then(Value::as_object)
+            .map(|schemas| schemas.keys().cloned().collect())
+            .unwrap_or_default();
+        let slugs = spec.slugs()
+            .into_iter()
+            .map(|(slug, target)| (slug, target.name().to_string()))
+            .collect();
+        Self {
+            version: LcuVersion::new(&spec.info.version),
+            operations: ids(false),
+            components,
+            slugs,
+            sizes: None,
+            experimental: ids(true),
+        }
+    }
+
+    /// The operations and components added and removed from `self` to `new`,
+    /// as Markdown with a section each. Added operations `new` marks
+    /// experimental get a section of their own as well.
+    pub fn changelog(&self, new: &RunRecord) -> String {
+        let mut markdown = format!("# {} -> {}\n", self.version, new.version);
+        for (title, old, new) in [("Operations", &self.operations, &new.operations), ("Components", &self.components, &new.components)] {
+            let added = new.difference(old).collect::<Vec<&String>>();
+            let removed = old.difference(new).collect::<Vec<&String>>();
+            markdown.push_str(&format!("## {title}: {} added, {} removed\n", added.len(), removed.len()));
+            for name in added {
+                markdown.push_str(&format!("+ {name}\n"));
+            }
+            for name in removed {
+                markdown.push_str(&format!("- {name}\n"));
+            }
+        }
+        let experimental = new.experimental
+            .iter()
+            .filter(|id| !self.operations.contains(*id))
+            .collect::<Vec<&String>>();
+        if !experimental.is_empty() {
+            markdown.push_str(&format!("## New experimental endpoints: {}\n", experimental.len()));
+            for id in experimental {
+                markdown.push_str(&format!("+ {id}\n"));
+            }
+        }
+        markdown
+    }
+}
+
+/// Records of past runs, one per LCU version.
+#[derive(Deserialize, Serialize, Debug, Clone, Default, PartialEq, Eq)]
+pub struct RunHistory {
+    pub records: Vec<RunRecord>,
+}
+
+impl RunHistory {
+    /// Add a run, replacing an earlier run of the same version.
+    pub fn push(&mut self, record: RunRecord) {
+        self.records.retain(|r| r.version != record.version);
+        self.records.push(record);
+        self.records.sort_by(|a, b| a.version.cmp(&b.version));
+    }
+
+    /// Load history from a file. A missing file yields an empty history.
+    pub fn load(path: &Path) -> Result<Self, Error> {
+        match fs::read_to_string(path) {
+            Ok(json) => Ok(serde_json::from_str(&json)?),
+            Err(err) if err.kind() == io::ErrorKind::NotFound => Ok(Self::default()),
+            Err(err) => Err(err.into()),
+        }
+    }
+
+    pub fn save(&self, path: &Path) -> Result<(), Error> {
+        fs::write(path, serde_json::to_string_pretty(self)?)?;
+        Ok(())
+    }
+
+    /// Rebuild history from a directory of previously generated specs (`*.json`),
+    /// taking each run's version from the spec's `info.version`.
+    pub fn backfill(dir: &Path) -> Result<Self, Error> {
+        let mut history = Self::default();
+        for entry in fs::read_dir(dir)? {
+            let path = entry?.path();
+            if path.extension().is_none_or(|ext| ext != "json") {
+                continue;
+            }
+            let spec: OpenApiSpec = serde_json::from_str(&fs::read_to_string(&path)?)?;
+            history.push(RunRecord::from_spec(&spec));
+        }
+        Ok(history)
+    }
+
+    /// Earliest version containing each operationId and component.
+    pub fn first_seen(&self) -> FirstSeen<'_> {
+        let mut first_seen = FirstSeen::default();
+        let mut records = self.records.iter().collect::<Vec<&RunRecord>>();
+        records.sort_by_key(|record| &record.version);
+        for record in records {
+            for id in record.operations.iter() {
+                first_seen.operations.entry(id).or_insert(&record.version);
+            }
+            for name in record.components.iter() {
+                first_seen.components.entry(name).or_insert(&record.version);
+            }
+        }
+        first_seen
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct FirstSeen<'a> {
+    pub operations: BTreeMap<&'a str, &'a LcuVersion>,
+    pub components: BTreeMap<&'a str, &'a LcuVersion>,
+}
+
+/// Stamps operations and components with [`X_SINCE`] from a [`RunHistory`].
+///
+/// Items the history has never seen get the spec's own version. The annotation
+/// only depends on the history, so it is the same on every run.
+#[derive(Debug, Clone)]
+pub struct SinceAnnotations<'a> {
+    pub history: &'a RunHistory,
+}
+
+impl SinceAnnotations<'_> {
+    /// Returns the number of operations and components that were annotated.
+    pub fn apply(&self, spec: &mut OpenApiSpec) -> usize {
+        let first_seen = self.history.first_seen();
+        let current = LcuVersion::new(&spec.info.version);
+        let since = |seen: Option<&&LcuVersion>| Value::from(seen.copied().unwrap_or(&current).release());
+
+        let mut count = 0;
+        let operations = spec.paths
+            .values_mut()
+            .filter_map(Value::as_object_mut)
+            .flat_map(|item| item.values_mut())
+            .filter_map(Value::as_object_mut);
+        for operation in operations {
+            let Some(id) = operation.get("operationId").and_then(Value::as_str) else {
+                continue;
+            };
+            let value = since(first_seen.operations.get(id));
+            operation.insert(X_SINCE.to_string(), value);
+            count += 1;
+        }
+
+        if let Some(Value::Object(schemas)) = spec.components.get_mut("schemas") {
+            for (name, schema) in schemas.iter_mut() {
+                if let Value::Object(schema) = schema {
+                    schema.insert(X_SINCE.to_string(), since(first_seen.components.get(name.as_str())));
+                    count += 1;
+                }
+            }
+        }
+        count
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{ options::SchemaOptions, report::SchemaReport, stability::StabilityRules, tests::miniature };
+
+    fn record(version: &str, operations: &[&str], components: &[&str]) -> RunRecord {
+        RunRecord {
+            version: LcuVersion::new(version),
+            operations: operations.iter().map(|s| s.to_string()).collect(),
+            components: components.iter().map(|s| s.to_string()).collect(),
+            slugs: BTreeMap::new(),
+            sizes: None,
+            experimental: BTreeSet::new(),
+        }
+    }
+
+    /// GetLolSummonerV1CurrentSummoner is old, GetLolSummonerV1SummonersById arrived
+    /// in 14.9 and everything else is new in the current version.
+    fn history() -> RunHistory {
+        let mut history = RunHistory::default();
+        history.push(record("14.10.600.1", &["GetLolSummonerV1CurrentSummoner", "GetLolSummonerV1SummonersById"], &["LolSummonerSummoner"]));
+        history.push(record("14.3.556.1234", &["GetLolSummonerV1CurrentSummoner"], &["LolSummonerSummoner"]));
+        history.push(record("14.9.580.2", &["GetLolSummonerV1CurrentSummoner", "GetLolSummonerV1SummonersById"], &[]));
+        history
+    }
+
+    fn operation<'a>(spec: &'a OpenApiSpec, id: &str) -> &'a Value {
+        spec.paths
+            .values()
+            .flat_map(|item| item.as_object().unwrap().values())
+            .find(|operation| operation["operationId"] == id)
+            .unwrap()
+    }
+
+    #[test]
+    fn test_since_annotations() {
+        let history = history();
+        let options = SchemaOptions { history: Some(history.clone()), ..Default::default() };
+        let spec = OpenApiSpec::from_help_with(&miniature(), "14.11.610.5", &options, &mut SchemaReport::default()).unwrap();
+
+        assert_eq!(operation(&spec, "GetLolSummonerV1CurrentSummoner")[X_SINCE], "14.3");
+        assert_eq!(operation(&spec, "GetLolSummonerV1SummonersById")[X_SINCE], "14.9");
+        assert_eq!(operation(&spec, "PostLolLobbyV2Lobby")[X_SINCE], "14.11");
+        let schemas = &spec.components["schemas"];
+        assert_eq!(schemas["LolSummonerSummoner"][X_SINCE], "14.3");
+        assert_eq!(schemas["LolLobbyLobbyDto"][X_SINCE], "14.11");
+
+        // Stable across runs, and absent without history.
+        let again = OpenApiSpec::from_help_with(&miniature(), "14.11.610.5", &options, &mut SchemaReport::default()).unwrap();
+        assert_eq!(serde_json::to_value(&spec).unwrap(), serde_json::to_value(&again).unwrap());
+        let plain = OpenApiSpec::from_help(&miniature(), "14.11.610.5").unwrap();
+        assert!(operation(&plain, "PostLolLobbyV2Lobby").get(X_SINCE).is_none());
+    }
+
+    #[test]
+    fn test_backfill() {
+        let dir = std::env::temp_dir().join(format!("poroshell-since-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let old = OpenApiSpec::from_help(&miniature(), "14.3.556.1234").unwrap();
+        let mut new = OpenApiSpec::from_help(&miniature(), "14.9.580.2").unwrap();
+        new.components.insert("schemas".to_string(), serde_json::json!({ "Added": {} }));
+        fs::write(dir.join("new.json"), serde_json::to_string(&new).unwrap()).unwrap();
+        fs::write(dir.join("old.json"), serde_json::to_string(&old).unwrap()).unwrap();
+        fs::write(dir.join("notes.txt"), "not a spec").unwrap();
+
+        let history = RunHistory::backfill(&dir).unwrap();
+        let first_seen = history.first_seen();
+        assert_eq!(history.records.len(), 2);
+        assert_eq!(first_seen.operations["PostLolLobbyV2Lobby"].as_str(), "14.3.556.1234");
+        assert_eq!(first_seen.components["LolLobbyLobbyDto"].as_str(), "14.3.556.1234");
+        assert_eq!(first_seen.components["Added"].as_str(), "14.9.580.2");
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_changelog_experimental() {
+        let old = record("14.3.556.1234", &["GetLolSummonerV1CurrentSummoner"], &[]);
+        let mut new = record("14.4.560.1", &["GetLolSummonerV1CurrentSummoner", "PostLolArenaV1Cards", "PostLolLobbyV2Lobby"], &[]);
+        // Experimental but not new, so only in the operations section.
+        new.experimental = ["GetLolSummonerV1CurrentSummoner", "PostLolArenaV1Cards"].map(str::to_string).into();
+
+        let changelog = old.changelog(&new);
+        assert!(changelog.ends_with("## New experimental endpoints: 1\n+ PostLolArenaV1Cards\n"), "{changelog}");
+        assert!(!old.changelog(&record("14.4.560.1", &[], &[])).contains("experimental"));
+
+        let options = SchemaOptions {
+            stability: Some(StabilityRules { plugins: vec!["lol-lobby".to_string()], ..Default::default() }),
+            ..Default::default()
+        };
+        let spec = OpenApiSpec::from_help_with(&miniature(), "14.4.560.1", &options, &mut SchemaReport::default()).unwrap();
+        let record = RunRecord::from_spec(&spec);
+        assert_eq!(record.experimental, ["DeleteLolLobbyV2Lobby", "PostLolLobbyV2Lobby"].map(str::to_string).into());
+    }
+}
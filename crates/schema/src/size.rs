@@ -0,0 +1,209 @@
+//! How many bytes each part of the published spec takes, and which parts grew.
+//!
+//! Consumers load the whole spec up front, so growth is worth watching. Parts
+//! are measured as compact JSON through the same serializer that writes the
+//! spec, so the numbers only change when the content does.
+
+use std::collections::BTreeMap;
+
+use serde::{ Deserialize, Serialize };
+use serde_json::Value;
+
+use crate::{ error::Error, openapi::OpenApiSpec, report::{ ReportKind, SchemaReport }, since::RunHistory };
+
+/// How many components [`SpecSizes::largest_components`] keeps.
+pub const LARGEST_COMPONENTS: usize = 10;
+
+/// Serialized size of a spec and its parts, in bytes.
+#[derive(Deserialize, Serialize, Debug, Clone, Default, PartialEq, Eq)]
+pub struct SpecSizes {
+    /// The whole spec, pretty-printed as published.
+    pub total: usize,
+    /// `paths`, `components`, and `tags`.
+    pub sections: BTreeMap<String, usize>,
+    /// The operations of each tag. An operation with several tags counts
+    /// toward each of them.
+    pub tags: BTreeMap<String, usize>,
+    /// The largest component schemas, largest first.
+    pub largest_components: Vec<(String, usize)>,
+}
+
+impl SpecSizes {
+    pub fn measure(spec: &OpenApiSpec) -> Result<Self, Error> {
+        let mut sizes = Self { total: serde_json::to_string_pretty(spec)?.len(), ..Self::default() };
+        sizes.sections.insert("paths".to_string(), serde_json::to_vec(&spec.paths)?.len());
+        sizes.sections.insert("components".to_string(), serde_json::to_vec(&spec.components)?.len());
+        sizes.sections.insert("tags".to_string(), serde_json::to_vec(&spec.tags)?.len());
+
+        let operations = spec.paths
+            .values()
+            .filter_map(Value::as_object)
+            .flat_map(|item| item.values());
+        for operation in operations {
+            let size = serde_json::to_vec(operation)?.len();
+            for tag in operation["tags"].as_array().into_iter().flatten().filter_map(Value::as_str) {
+                *sizes.tags.entry(tag.to_string()).or_default() += size;
+            }
+        }
+
+        let schemas = spec.components.get("schemas").and_then(Value::as_object).into_iter().flatten();
+        for (name, schema) in schemas {
+            sizes.largest_components.push((name.clone(), serde_json::to_vec(schema)?.len()));
+        }
+        // Ties by name so the list is stable.
+        sizes.largest_components.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+        sizes.largest_components.truncate(LARGEST_COMPONENTS);
+        Ok(sizes)
+    }
+
+    /// Record every size in `report` under [`ReportKind::Size`].
+    pub fn record(&self, report: &mut SchemaReport) {
+        report.push(ReportKind::Size, "spec", format!("{} bytes", self.total));
+        for (section, size) in self.sections.iter() {
+            report.push(ReportKind::Size, section, format!("{size} bytes"));
+        }
+        for (tag, size) in self.tags.iter() {
+            report.push(ReportKind::Size, format!("tag {tag}"), format!("{size} bytes"));
+        }
+        for (name, size) in self.largest_components.iter() {
+            report.push(ReportKind::Size, format!("component {name}"), format!("{size} bytes"));
+        }
+    }
+
+    /// The spec, each section, and each tag, named as in [`Self::record`].
+    fn budgeted(&self) -> impl Iterator<Item = (String, usize)> + '_ {
+        std::iter::once(("spec".to_string(), self.total))
+            .chain(self.sections.iter().map(|(section, size)| (section.clone(), *size)))
+            .chain(self.tags.iter().map(|(tag, size)| (format!("tag {tag}"), *size)))
+    }
+}
+
+/// Warns about parts of the spec that grew by more than `max_growth_percent`
+/// since the last run. Applied to the finished spec right before it's
+/// written, so annotations added after conversion are counted too.
+#[derive(Debug, Clone)]
+pub struct SizeBudget {
+    pub max_growth_percent: f64,
+}
+
+impl Default for SizeBudget {
+    fn default() -> Self {
+        Self { max_growth_percent: 10.0 }
+    }
+}
+
+impl SizeBudget {
+    /// Measure `spec`, record the sizes, and compare them against the latest
+    /// run in `history` that has sizes. Returns the sizes, to be kept with the
+    /// run's record.
+    pub fn apply(&self, spec: &OpenApiSpec, history: Option<&RunHistory>, report: &mut SchemaReport) -> Result<SpecSizes, Error> {
+        let sizes = SpecSizes::measure(spec)?;
+        sizes.record(report);
+        let previous = history
+            .into_iter()
+            .flat_map(|history| history.records.iter().rev())
+            .find_map(|record| record.sizes.as_ref());
+        if let Some(previous) = previous {
+            self.check(previous, &sizes, report);
+        }
+        Ok(sizes)
+    }
+
+    /// Warn about each part of `current` that grew past the budget. Parts
+    /// that are new since `previous` aren't counted as growth.
+    pub fn check(&self, previous: &SpecSizes, current: &SpecSizes, report: &mut SchemaReport) {
+        let before = previous.budgeted().collect::<BTreeMap<String, usize>>();
+        for (name, after) in current.budgeted() {
+            let Some(&before) = before.get(&name).filter(|before| **before > 0) else {
+                continue;
+            };
+            let growth = ((after as f64) - (before as f64)) / (before as f64) * 100.0;
+            if growth > self.max_growth_percent {
+                report.warn(
+                    name,
+                    format!("grew {growth:.1}% since the last run, {before} -> {after} bytes, over the {}% budget", self.max_growth_percent)
+                );
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+    use crate::{ openapi::{ OpenApiInfo, Tag }, since::RunRecord };
+
+    /// Two summoner operations and one lobby operation, with a component each.
+    fn synthetic() -> OpenApiSpec {
+        let mut spec = OpenApiSpec::new(OpenApiInfo::lcu("1.0.0"));
+        spec.tags = vec![Tag::new("Plugin lol-summoner"), Tag::new("Plugin lol-lobby")];
+        spec.paths.insert(
+            "/lol-summoner/v1/current-summoner".to_string(),
+            json!({ "get": { "operationId": "A", "tags": ["Plugin lol-summoner"] } })
+        );
+        spec.paths.insert(
+            "/lol-summoner/v1/summoners".to_string(),
+            json!({ "get": { "operationId": "B", "tags": ["Plugin lol-summoner"] } })
+        );
+        spec.paths.insert("/lol-lobby/v2/lobby".to_string(), json!({ "post": { "operationId": "C", "tags": ["Plugin lol-lobby"] } }));
+        spec.components.insert("schemas".to_string(), json!({ "Small": {}, "Large": { "type": "object" } }));
+        spec
+    }
+
+    fn bytes(value: Value) -> usize {
+        serde_json::to_vec(&value).unwrap().len()
+    }
+
+    #[test]
+    fn test_measure() {
+        let spec = synthetic();
+        let sizes = SpecSizes::measure(&spec).unwrap();
+        assert_eq!(sizes.total, serde_json::to_string_pretty(&spec).unwrap().len());
+        assert_eq!(sizes.sections["paths"], bytes(Value::Object(spec.paths.clone())));
+        assert_eq!(sizes.sections["tags"], bytes(json!([{ "name": "Plugin lol-summoner" }, { "name": "Plugin lol-lobby" }])));
+
+        let operation = |id: &str, tag: &str| bytes(json!({ "operationId": id, "tags": [tag] }));
+        assert_eq!(sizes.tags["Plugin lol-summoner"], operation("A", "Plugin lol-summoner") + operation("B", "Plugin lol-summoner"));
+        assert_eq!(sizes.tags["Plugin lol-lobby"], operation("C", "Plugin lol-lobby"));
+        assert_eq!(sizes.largest_components, [("Large".to_string(), 17), ("Small".to_string(), 2)]);
+        assert_eq!(SpecSizes::measure(&spec).unwrap(), sizes);
+
+        let mut report = SchemaReport::default();
+        sizes.record(&mut report);
+        assert_eq!(report.of_kind(ReportKind::Size).count(), 8);
+        assert_eq!(report.summary_line(), "schema: no issues");
+    }
+
+    #[test]
+    fn test_growth_warned() {
+        let spec = synthetic();
+        let mut record = RunRecord::from_spec(&spec);
+        record.sizes = Some(SpecSizes::measure(&spec).unwrap());
+        let mut history = RunHistory::default();
+        history.push(record);
+
+        // Unchanged is within budget.
+        let mut report = SchemaReport::default();
+        SizeBudget::default().apply(&spec, Some(&history), &mut report).unwrap();
+        assert_eq!(report.of_kind(ReportKind::Warning).count(), 0);
+
+        let mut grown = spec.clone();
+        grown.paths.insert(
+            "/lol-lobby/v2/lobby/members".to_string(),
+            json!({ "get": { "operationId": "D", "tags": ["Plugin lol-lobby"], "description": "A much longer operation" } })
+        );
+        let mut report = SchemaReport::default();
+        SizeBudget::default().apply(&grown, Some(&history), &mut report).unwrap();
+        let warned = report.of_kind(ReportKind::Warning).map(|e| e.target.as_str()).collect::<Vec<&str>>();
+        assert_eq!(warned, ["spec", "paths", "tag Plugin lol-lobby"]);
+        let lobby = report.of_kind(ReportKind::Warning).last().unwrap();
+        assert!(lobby.detail.contains("since the last run"), "{}", lobby.detail);
+
+        // A looser budget lets the spec through but not the lobby tag.
+        let mut report = SchemaReport::default();
+        SizeBudget { max_growth_percent: 150.0 }.apply(&grown, Some(&history), &mut report).unwrap();
+        assert_eq!(report.of_kind(ReportKind::Warning).map(|e| e.target.as_str()).collect::<Vec<&str>>(), ["tag Plugin lol-lobby"]);
+    }
+}
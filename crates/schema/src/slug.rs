@@ -0,0 +1,290 @@
+//! Stable anchors for deep links into rendered docs, like
+//! `#operation-get-lol-summoner-v1-current-summoner`.
+
+use std::collections::{ BTreeMap, BTreeSet, HashMap };
+
+use serde_json::Value;
+
+use crate::{ openapi::OpenApiSpec, since::RunHistory };
+
+/// Extension key holding the anchor of an operation or component.
+pub const X_SLUG: &str = "x-slug";
+/// Extension key listing anchors the item had in earlier runs, oldest first.
+pub const X_PREVIOUS_SLUGS: &str = "x-previous-slugs";
+
+/// What a slug links to.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum SlugTarget {
+    Operation { operation_id: String, path: String, method: String },
+    Schema(String),
+}
+
+impl SlugTarget {
+    pub fn kind(&self) -> &'static str {
+        match self {
+            Self::Operation { .. } => "operation",
+            Self::Schema(_) => "schema",
+        }
+    }
+
+    /// The operationId or component name.
+    pub fn name(&self) -> &str {
+        match self {
+            Self::Operation { operation_id, .. } => operation_id,
+            Self::Schema(name) => name,
+        }
+    }
+
+    /// The slug before collisions are resolved.
+    fn base_slug(&self) -> String {
+        let name = slugify(self.name());
+        if name.is_empty() { self.kind().to_string() } else { format!("{}-{name}", self.kind()) }
+    }
+
+    /// Whether `slug` is one of this kind's.
+    fn owns(&self, slug: &str) -> bool {
+        slug.strip_prefix(self.kind()).is_some_and(|rest| rest.is_empty() || rest.starts_with('-'))
+    }
+}
+
+/// Lowercase a name and separate its words with dashes, e.g.
+/// `GetLolSummonerV1CurrentSummoner` becomes `get-lol-summoner-v1-current-summoner`.
+///
+/// Words start at an uppercase letter after a lowercase one or a digit, and at
+/// the last capital of an acronym (`LCUStatus` is `lcu-status`). Anything that
+/// isn't a letter or digit, like the `:` of disambiguated names, separates words.
+pub fn slugify(name: &str) -> String {
+    let chars = name.chars().collect::<Vec<char>>();
+    let mut slug = String::new();
+    for (i, &c) in chars.iter().enumerate() {
+        if !c.is_alphanumeric() {
+            if !slug.is_empty() && !slug.ends_with('-') {
+                slug.push('-');
+            }
+            continue;
+        }
+        let previous = i.checked_sub(1).map(|i| chars[i]);
+        let next = chars.get(i + 1);
+        let word_start = c.is_uppercase() &&
+            previous.is_some_and(|p| {
+                p.is_lowercase() || p.is_numeric() || (p.is_uppercase() && next.is_some_and(|n| n.is_lowercase()))
+            });
+        if word_start && !slug.is_empty() && !slug.ends_with('-') {
+            slug.push('-');
+        }
+        slug.extend(c.to_lowercase());
+    }
+    slug.trim_end_matches('-').to_string()
+}
+
+/// A unique slug for every target.
+///
+/// Targets whose names slugify alike are sorted and the first keeps the plain
+/// slug; the rest get `-2`, `-3`, … skipping slugs another target has plainly.
+/// The result only depends on the set of targets, not their order.
+pub fn assign_slugs(targets: &[SlugTarget]) -> BTreeMap<SlugTarget, String> {
+    let mut groups = BTreeMap::<String, BTreeSet<&SlugTarget>>::new();
+    for target in targets {
+        groups.entry(target.base_slug()).or_default().insert(target);
+    }
+    let mut taken = groups.keys().cloned().collect::<BTreeSet<String>>();
+
+    let mut slugs = BTreeMap::new();
+    for (base, group) in groups {
+        let mut n = 2;
+        for (i, target) in group.into_iter().enumerate() {
+            let slug = if i == 0 {
+                base.clone()
+            } else {
+                loop {
+                    let candidate = format!("{base}-{n}");
+                    n += 1;
+                    if taken.insert(candidate.clone()) {
+                        break candidate;
+                    }
+                }
+            };
+            slugs.insert(target.clone(), slug);
+        }
+    }
+    slugs
+}
+
+/// Every operation with an operationId and every component schema.
+fn targets_of(spec: &OpenApiSpec) -> Vec<SlugTarget> {
+    let operations = spec.paths
+        .iter()
+        .filter_map(|(path, item)| Some((path, item.as_object()?)))
+        .flat_map(|(path, item)| item.iter().map(move |(method, operation)| (path, method, operation)))
+        .filter_map(|(path, method, operation)| {
+            let operation_id = operation.get("operationId")?.as_str()?;
+            Some(SlugTarget::Operation {
+                operation_id: operation_id.to_string(),
+                path: path.clone(),
+                method: method.clone(),
+            })
+        });
+    let schemas = spec.components
+        .get("schemas")
+        .and_then(Value::as_object)
+        .into_iter()
+        .flat_map(|schemas| schemas.keys())
+        .map(|name| SlugTarget::Schema(name.clone()));
+    operations.chain(schemas).collect()
+}
+
+/// Stamps operations and components with [`X_SLUG`], and with
+/// [`X_PREVIOUS_SLUGS`] when a [`RunHistory`] saw them under other slugs.
+#[derive(Debug, Clone, Default)]
+pub struct SlugAnnotations<'a> {
+    pub history: Option<&'a RunHistory>,
+}
+
+impl SlugAnnotations<'_> {
+    pub fn apply(&self, spec: &mut OpenApiSpec) {
+        let slugs = assign_slugs(&targets_of(spec));
+        for (target, slug) in slugs.iter() {
+            let previous = self.previous_slugs(target, slug);
+            let object = match target {
+                SlugTarget::Operation { path, method, .. } => spec.paths
+                    .get_mut(path)
+                    .and_then(|item| item.get_mut(method)),
+                SlugTarget::Schema(name) => spec.components
+                    .get_mut("schemas")
+                    .and_then(|schemas| schemas.get_mut(name)),
+            };
+            let Some(Value::Object(object)) = object else {
+                continue;
+            };
+            object.insert(X_SLUG.to_string(), Value::from(slug.as_str()));
+            if !previous.is_empty() {
+                object.insert(X_PREVIOUS_SLUGS.to_string(), Value::from(previous));
+            }
+        }
+    }
+
+    /// Slugs `target` had in earlier runs other than `current`, oldest first.
+    fn previous_slugs(&self, target: &SlugTarget, current: &str) -> Vec<String> {
+        let mut previous = Vec::<String>::new();
+        for record in self.history.iter().flat_map(|history| &history.records) {
+            for (slug, name) in record.slugs.iter() {
+                if name == target.name() && target.owns(slug) && slug != current && !previous.contains(slug) {
+                    previous.push(slug.clone());
+                }
+            }
+        }
+        previous
+    }
+}
+
+impl OpenApiSpec {
+    /// What each [`X_SLUG`] in the spec links to.
+    pub fn slugs(&self) -> HashMap<String, SlugTarget> {
+        let mut slugs = HashMap::new();
+        for target in targets_of(self) {
+            let object = match &target {
+                SlugTarget::Operation { path, method, .. } => self.paths.get(path).and_then(|item| item.get(method)),
+                SlugTarget::Schema(name) => self.components.get("schemas").and_then(|schemas| schemas.get(name)),
+            };
+            if let Some(slug) = object.and_then(|object| object.get(X_SLUG)).and_then(Value::as_str) {
+                slugs.insert(slug.to_string(), target);
+            }
+        }
+        slugs
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{ options::SchemaOptions, report::SchemaReport, since::RunRecord, tests::miniature, version::LcuVersion };
+
+    fn schema(name: &str) -> SlugTarget {
+        SlugTarget::Schema(name.to_string())
+    }
+
+    fn spec_with(options: &SchemaOptions) -> OpenApiSpec {
+        OpenApiSpec::from_help_with(&miniature(), "14.11.610.5", options, &mut SchemaReport::default()).unwrap()
+    }
+
+    #[test]
+    fn test_slugify() {
+        assert_eq!(slugify("GetLolSummonerV1CurrentSummoner"), "get-lol-summoner-v1-current-summoner");
+        assert_eq!(slugify("LCUStatus"), "lcu-status");
+        assert_eq!(slugify("OnServiceProxyEvent:lol-chat:2"), "on-service-proxy-event-lol-chat-2");
+        assert_eq!(slugify("lol_chat  Friend_"), "lol-chat-friend");
+        assert_eq!(slugify("::"), "");
+    }
+
+    #[test]
+    fn test_collisions() {
+        let targets = [schema("LolChat_Friend"), schema("lol-chat-friend-2"), schema("LolChatFriend"), schema("lol:chat:friend")];
+        let slugs = assign_slugs(&targets);
+        assert_eq!(slugs[&schema("LolChatFriend")], "schema-lol-chat-friend");
+        // Suffixes skip the slug another name has plainly.
+        assert_eq!(slugs[&schema("LolChat_Friend")], "schema-lol-chat-friend-3");
+        assert_eq!(slugs[&schema("lol:chat:friend")], "schema-lol-chat-friend-4");
+        assert_eq!(slugs[&schema("lol-chat-friend-2")], "schema-lol-chat-friend-2");
+
+        // The order targets are found in doesn't matter.
+        let mut reversed = targets.to_vec();
+        reversed.reverse();
+        assert_eq!(assign_slugs(&reversed), slugs);
+    }
+
+    #[test]
+    fn test_lookup() {
+        let mut spec = spec_with(&SchemaOptions { slugs: true, ..Default::default() });
+        let slugs = spec.slugs();
+        assert_eq!(
+            slugs["operation-get-lol-summoner-v1-current-summoner"],
+            SlugTarget::Operation {
+                operation_id: "GetLolSummonerV1CurrentSummoner".to_string(),
+                path: "/lol-summoner/v1/current-summoner".to_string(),
+                method: "get".to_string(),
+            }
+        );
+        assert_eq!(slugs["schema-lol-summoner-summoner"], schema("LolSummonerSummoner"));
+        assert_eq!(slugs.len(), targets_of(&spec).len());
+
+        // Operations and components with alike names don't share a slug.
+        let summoner = spec.components["schemas"]["LolSummonerSummoner"].clone();
+        spec.components["schemas"].as_object_mut().unwrap().insert("GetLolSummonerV1CurrentSummoner".to_string(), summoner);
+        SlugAnnotations::default().apply(&mut spec);
+        assert_eq!(spec.slugs()["schema-get-lol-summoner-v1-current-summoner"], schema("GetLolSummonerV1CurrentSummoner"));
+
+        assert!(spec_with(&SchemaOptions::default()).slugs().is_empty());
+    }
+
+    #[test]
+    fn test_previous_slugs() {
+        // Last run, LolChatFriend was alone. Now a disambiguated twin sorts first.
+        let mut record = RunRecord {
+            version: LcuVersion::new("14.10.600.1"),
+            operations: BTreeSet::new(),
+            components: BTreeSet::new(),
+            slugs: BTreeMap::new(),
+            sizes: None,
+            experimental: BTreeSet::new(),
+        };
+        record.slugs.insert("schema-lol-chat-friend".to_string(), "LolChatFriend".to_string());
+        let mut history = RunHistory::default();
+        history.push(record);
+
+        let mut spec = spec_with(&SchemaOptions::default());
+        let schemas = spec.components["schemas"].as_object_mut().unwrap();
+        schemas.insert("LolChatFriend".to_string(), Value::Object(Default::default()));
+        schemas.insert("Lol-Chat Friend".to_string(), Value::Object(Default::default()));
+        SlugAnnotations { history: Some(&history) }.apply(&mut spec);
+
+        let schemas = &spec.components["schemas"];
+        assert_eq!(schemas["Lol-Chat Friend"][X_SLUG], "schema-lol-chat-friend");
+        assert!(schemas["Lol-Chat Friend"].get(X_PREVIOUS_SLUGS).is_none());
+        assert_eq!(schemas["LolChatFriend"][X_SLUG], "schema-lol-chat-friend-2");
+        assert_eq!(schemas["LolChatFriend"][X_PREVIOUS_SLUGS], serde_json::json!(["schema-lol-chat-friend"]));
+
+        // The next run's record keeps the new slugs.
+        let record = RunRecord::from_spec(&spec);
+        assert_eq!(record.slugs["schema-lol-chat-friend-2"], "LolChatFriend");
+    }
+}
@@ -0,0 +1,554 @@
+//! What changed between two [`OpenApiSpec`]s, down to single schema keywords.
+//!
+//! Where [`crate::help_diff`] shows changes to the LCU, this shows changes to
+//! the published spec, for changelogs between client patches. Both specs are
+//! put through [`Normalize`] first, and lists whose order means nothing, like
+//! `enum`, `required`, parameters and tags, are compared as sets.
+
+use std::{ collections::{ BTreeMap, BTreeSet }, fmt::{ self, Write } };
+
+use serde::Serialize;
+use serde_json::{ Map, Value };
+
+use crate::{ openapi::OpenApiSpec, transform::Normalize };
+
+/// Keys of a path item that are operations.
+const METHODS: [&str; 8] = ["get", "put", "post", "delete", "options", "head", "patch", "trace"];
+
+/// One difference within a changed item. `at` is where in the item, like
+/// `properties.summoner.items` or `responses.200`, and empty for the item
+/// itself.
+#[derive(Serialize, Debug, Clone, PartialEq, Eq)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum SpecChange {
+    TypeChanged { at: String, old: Option<String>, new: Option<String> },
+    /// A `$ref` that points elsewhere, or a schema that became or stopped being one.
+    RefChanged { at: String, old: Option<String>, new: Option<String> },
+    PropertyAdded { at: String, name: String },
+    PropertyRemoved { at: String, name: String },
+    /// A property that became required, or optional.
+    RequiredChanged { at: String, name: String, required: bool },
+    EnumValueAdded { at: String, value: Value },
+    EnumValueRemoved { at: String, value: Value },
+    ParameterAdded { name: String },
+    ParameterRemoved { name: String },
+    ResponseAdded { status: String },
+    ResponseRemoved { status: String },
+    /// An operation a path gained.
+    MethodAdded { method: String },
+    MethodRemoved { method: String },
+    /// Any other keyword, like `format`, `description`, or an `x-` extension.
+    FieldChanged { at: String, field: String, old: Option<Value>, new: Option<Value> },
+}
+
+impl fmt::Display for SpecChange {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let at = |at: &str| if at.is_empty() { String::new() } else { format!("{at}: ") };
+        let or_none = |value: &Option<String>| value.clone().unwrap_or_else(|| "none".to_string());
+        let or_absent = |value: &Option<Value>| value.as_ref().map_or_else(|| "absent".to_string(), Value::to_string);
+        match self {
+            Self::TypeChanged { at: path, old, new } => write!(f, "{}type {} -> {}", at(path), or_none(old), or_none(new)),
+            Self::RefChanged { at: path, old, new } => write!(f, "{}$ref {} -> {}", at(path), or_none(old), or_none(new)),
+            Self::PropertyAdded { at: path, name } => write!(f, "{}property {name} added", at(path)),
+            Self::PropertyRemoved { at: path, name } => write!(f, "{}property {name} removed", at(path)),
+            Self::RequiredChanged { at: path, name, required: true } => write!(f, "{}{name} is now required", at(path)),
+            Self::RequiredChanged { at: path, name, required: false } => write!(f, "{}{name} is now optional", at(path)),
+            Self::EnumValueAdded { at: path, value } => write!(f, "{}enum value {value} added", at(path)),
+            Self::EnumValueRemoved { at: path, value } => write!(f, "{}enum value {value} removed", at(path)),
+            Self::ParameterAdded { name } => write!(f, "parameter {name} added"),
+            Self::ParameterRemoved { name } => write!(f, "parameter {name} removed"),
+            Self::ResponseAdded { status } => write!(f, "response {status} added"),
+            Self::ResponseRemoved { status } => write!(f, "response {status} removed"),
+            Self::MethodAdded { method } => write!(f, "{method} added"),
+            Self::MethodRemoved { method } => write!(f, "{method} removed"),
+            Self::FieldChanged { at: path, field, old, new } => {
+                write!(f, "{}{field} {} -> {}", at(path), or_absent(old), or_absent(new))
+            }
+        }
+    }
+}
+
+/// A changed item, by name, with what changed in it.
+#[derive(Serialize, Debug, Clone, PartialEq, Eq)]
+pub struct Changed {
+    pub name: String,
+    pub changes: Vec<SpecChange>,
+}
+
+/// Added, removed, and changed items of one kind, each sorted by name.
+#[derive(Serialize, Debug, Clone, Default, PartialEq, Eq)]
+pub struct SpecChanges {
+    pub added: Vec<String>,
+    pub removed: Vec<String>,
+    pub changed: Vec<Changed>,
+}
+
+impl SpecChanges {
+    fn of<T>(old: &BTreeMap<String, T>, new: &BTreeMap<String, T>, compare: impl Fn(&T, &T) -> Vec<SpecChange>) -> Self {
+        let mut changes = Self::default();
+        for (name, new_item) in new.iter() {
+            match old.get(name) {
+                None => changes.added.push(name.clone()),
+                Some(old_item) => {
+                    let item_changes = compare(old_item, new_item);
+                    if !item_changes.is_empty() {
+                        changes.changed.push(Changed { name: name.clone(), changes: item_changes });
+                    }
+                }
+            }
+        }
+        changes.removed = old.keys().filter(|name| !new.contains_key(*name)).cloned().collect();
+        changes
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty() && self.changed.is_empty()
+    }
+
+    fn write_markdown(&self, title: &str, markdown: &mut String) {
+        let _ = writeln!(
+            markdown,
+            "\n## {title}: {} added, {} removed, {} changed\n",
+            self.added.len(),
+            self.removed.len(),
+            self.changed.len()
+        );
+        for name in self.added.iter() {
+            let _ = writeln!(markdown, "- Added `{name}`");
+        }
+        for name in self.removed.iter() {
+            let _ = writeln!(markdown, "- Removed `{name}`");
+        }
+        for Changed { name, changes } in self.changed.iter() {
+            let _ = writeln!(markdown, "- Changed `{name}`");
+            for change in changes {
+                let _ = writeln!(markdown, "  - {change}");
+            }
+        }
+    }
+}
+
+/// Differences between two [`OpenApiSpec`]s, from [`OpenApiSpec::diff`].
+/// Serializes to JSON for publishing alongside a changelog.
+#[derive(Serialize, Debug, Clone, Default, PartialEq, Eq)]
+pub struct SpecDiff {
+    /// Component schemas, by name.
+    pub components: SpecChanges,
+    /// Paths, by path, changed when they gain or lose an operation.
+    pub paths: SpecChanges,
+    /// Operations, by method and path, like `get /lol-summoner/v1/current-summoner`.
+    pub operations: SpecChanges,
+    /// Tags, by name.
+    pub tags: SpecChanges,
+}
+
+impl SpecDiff {
+    pub fn is_empty(&self) -> bool {
+        self.components.is_empty() && self.paths.is_empty() && self.operations.is_empty() && self.tags.is_empty()
+    }
+
+    /// A `# Spec changes` section, with a list per kind that changed.
+    pub fn to_markdown(&self) -> String {
+        let mut markdown = String::from("# Spec changes\n");
+        if self.is_empty() {
+            markdown.push_str("\nThe spec is unchanged.\n");
+            return markdown;
+        }
+        let kinds = [
+            ("Components", &self.components),
+            ("Paths", &self.paths),
+            ("Operations", &self.operations),
+            ("Tags", &self.tags),
+        ];
+        for (title, changes) in kinds {
+            if !changes.is_empty() {
+                changes.write_markdown(title, &mut markdown);
+            }
+        }
+        markdown
+    }
+}
+
+impl OpenApiSpec {
+    /// What changed from `self` to `other`, after normalizing both.
+    pub fn diff(&self, other: &OpenApiSpec) -> SpecDiff {
+        let (mut old, mut new) = (self.clone(), other.clone());
+        Normalize.apply(&mut old);
+        Normalize.apply(&mut new);
+
+        let schemas = |spec: &OpenApiSpec| {
+            let schemas = spec.components.get("schemas").and_then(Value::as_object);
+            schemas.into_iter().flatten().map(|(name, schema)| (name.clone(), schema.clone())).collect::<BTreeMap<_, _>>()
+        };
+        let paths = |spec: &OpenApiSpec| {
+            spec.paths
+                .iter()
+                .map(|(path, item)| (path.clone(), methods(item).map(|(method, _)| method.to_string()).collect::<BTreeSet<_>>()))
+                .collect::<BTreeMap<_, _>>()
+        };
+        let operations = |spec: &OpenApiSpec| {
+            spec.paths
+                .iter()
+                .flat_map(|(path, item)| methods(item).map(move |(method, operation)| (format!("{method} {path}"), operation.clone())))
+                .collect::<BTreeMap<_, _>>()
+        };
+        let tags = |spec: &OpenApiSpec| {
+            spec.tags
+                .iter()
+                .map(|tag| (tag.name.clone(), serde_json::to_value(tag).unwrap_or_default()))
+                .collect::<BTreeMap<_, _>>()
+        };
+
+        SpecDiff {
+            components: SpecChanges::of(&schemas(&old), &schemas(&new), |old, new| {
+                let mut changes = Vec::new();
+                compare_schemas("", old, new, &mut changes);
+                changes
+            }),
+            paths: SpecChanges::of(&paths(&old), &paths(&new), compare_methods),
+            operations: SpecChanges::of(&operations(&old), &operations(&new), compare_operations),
+            tags: SpecChanges::of(&tags(&old), &tags(&new), |old, new| {
+                let mut changes = Vec::new();
+                compare_fields("", old, new, &["name"], &mut changes);
+                changes
+            }),
+        }
+    }
+}
+
+/// The operations of a path item, by lower-case method.
+fn methods(item: &Value) -> impl Iterator<Item = (&str, &Value)> {
+    item.as_object().into_iter().flatten().filter(|(key, _)| METHODS.contains(&key.as_str())).map(|(key, value)| (key.as_str(), value))
+}
+
+fn compare_methods(old: &BTreeSet<String>, new: &BTreeSet<String>) -> Vec<SpecChange> {
+    let added = new.difference(old).map(|method| SpecChange::MethodAdded { method: method.clone() });
+    let removed = old.difference(new).map(|method| SpecChange::MethodRemoved { method: method.clone() });
+    added.chain(removed).collect()
+}
+
+fn compare_operations(old: &Value, new: &Value) -> Vec<SpecChange> {
+    let mut changes = Vec::new();
+
+    let parameters = |operation: &Value| {
+        let parameters = operation.get("parameters").and_then(Value::as_array);
+        parameters
+            .into_iter()
+            .flatten()
+            .map(|parameter| (parameter["name"].as_str().unwrap_or_default().to_string(), parameter.clone()))
+            .collect::<BTreeMap<_, _>>()
+    };
+    let (old_parameters, new_parameters) = (parameters(old), parameters(new));
+    for (name, new_parameter) in new_parameters.iter() {
+        match old_parameters.get(name) {
+            None => changes.push(SpecChange::ParameterAdded { name: name.clone() }),
+            Some(old_parameter) => {
+                let at = format!("parameters.{name}");
+                compare_fields(&at, old_parameter, new_parameter, &["name", "schema"], &mut changes);
+                compare_schemas(&at, &old_parameter["schema"], &new_parameter["schema"], &mut changes);
+            }
+        }
+    }
+    for name in old_parameters.keys().filter(|name| !new_parameters.contains_key(*name)) {
+        changes.push(SpecChange::ParameterRemoved { name: name.clone() });
+    }
+
+    compare_content("requestBody", &old["requestBody"], &new["requestBody"], &mut changes);
+
+    let responses = |operation: &Value| operation.get("responses").and_then(Value::as_object).cloned().unwrap_or_default();
+    let (old_responses, new_responses) = (responses(old), responses(new));
+    for (status, new_response) in new_responses.iter() {
+        match old_responses.get(status) {
+            None => changes.push(SpecChange::ResponseAdded { status: status.clone() }),
+            Some(old_response) => compare_content(&format!("responses.{status}"), old_response, new_response, &mut changes),
+        }
+    }
+    for status in old_responses.keys().filter(|status| !new_responses.contains_key(*status)) {
+        changes.push(SpecChange::ResponseRemoved { status: status.clone() });
+    }
+
+    compare_fields("", old, new, &["parameters", "requestBody", "responses"], &mut changes);
+    changes
+}
+
+/// Compare a request body or response: its schemas by media type, and its
+/// other fields as they are.
+fn compare_content(at: &str, old: &Value, new: &Value, changes: &mut Vec<SpecChange>) {
+    let content = |body: &Value| body.get("content").and_then(Value::as_object).cloned().unwrap_or_default();
+    let (old_content, new_content) = (content(old), content(new));
+    let media_types = old_content.keys().chain(new_content.keys()).collect::<BTreeSet<_>>();
+    // With one media type, as the LCU has, its name is left out of locations.
+    let named = media_types.len() > 1;
+    for media_type in media_types {
+        let schema = |content: &Map<String, Value>| content.get(media_type).map_or(Value::Null, |media| media["schema"].clone());
+        let at = if named { format!("{at}.{media_type}") } else { at.to_string() };
+        compare_schemas(&at, &schema(&old_content), &schema(&new_content), changes);
+    }
+    compare_fields(at, old, new, &["content"], changes);
+}
+
+/// Compare two schemas keyword by keyword, descending into properties, items
+/// and additional properties. A missing schema is `null`.
+fn compare_schemas(at: &str, old: &Value, new: &Value, changes: &mut Vec<SpecChange>) {
+    let text = |schema: &Value, key: &str| schema.get(key).map(|value| value.as_str().map_or_else(|| value.to_string(), str::to_string));
+    let join = |name: &str| if at.is_empty() { name.to_string() } else { format!("{at}.{name}") };
+
+    let (old_ref, new_ref) = (text(old, "$ref"), text(new, "$ref"));
+    if old_ref != new_ref {
+        changes.push(SpecChange::RefChanged { at: at.to_string(), old: old_ref, new: new_ref });
+    }
+    let (old_type, new_type) = (text(old, "type"), text(new, "type"));
+    if old_type != new_type {
+        changes.push(SpecChange::TypeChanged { at: at.to_string(), old: old_type, new: new_type });
+    }
+
+    let values = |schema: &Value| schema.get("enum").and_then(Value::as_array).cloned().unwrap_or_default();
+    let (old_values, new_values) = (values(old), values(new));
+    for value in new_values.iter().filter(|value| !old_values.contains(value)) {
+        changes.push(SpecChange::EnumValueAdded { at: at.to_string(), value: value.clone() });
+    }
+    for value in old_values.iter().filter(|value| !new_values.contains(value)) {
+        changes.push(SpecChange::EnumValueRemoved { at: at.to_string(), value: value.clone() });
+    }
+
+    let properties = |schema: &Value| schema.get("properties").and_then(Value::as_object).cloned().unwrap_or_default();
+    let (old_properties, new_properties) = (properties(old), properties(new));
+    for (name, new_property) in new_properties.iter() {
+        match old_properties.get(name) {
+            None => changes.push(SpecChange::PropertyAdded { at: at.to_string(), name: name.clone() }),
+            Some(old_property) => compare_schemas(&join(&format!("properties.{name}")), old_property, new_property, changes),
+        }
+    }
+    for name in old_properties.keys().filter(|name| !new_properties.contains_key(*name)) {
+        changes.push(SpecChange::PropertyRemoved { at: at.to_string(), name: name.clone() });
+    }
+
+    let required = |schema: &Value| {
+        let required = schema.get("required").and_then(Value::as_array);
+        required.into_iter().flatten().filter_map(Value::as_str).map(str::to_string).collect::<BTreeSet<_>>()
+    };
+    let (old_required, new_required) = (required(old), required(new));
+    // Properties that came or went with the change are reported as such.
+    let kept = |name: &&String| old_properties.contains_key(*name) && new_properties.contains_key(*name);
+    for name in new_required.difference(&old_required).filter(kept) {
+        changes.push(SpecChange::RequiredChanged { at: at.to_string(), name: name.clone(), required: true });
+    }
+    for name in old_required.difference(&new_required).filter(kept) {
+        changes.push(SpecChange::RequiredChanged { at: at.to_string(), name: name.clone(), required: false });
+    }
+
+    if old.get("items").is_some() || new.get("items").is_some() {
+        compare_schemas(&join("items"), &old["items"], &new["items"], changes);
+    }
+    match (old.get("additionalProperties"), new.get("additionalProperties")) {
+        (Some(old_additional @ Value::Object(_)), Some(new_additional @ Value::Object(_))) => {
+            compare_schemas(&join("additionalProperties"), old_additional, new_additional, changes);
+        }
+        _ => compare_fields(at, &keep(old, "additionalProperties"), &keep(new, "additionalProperties"), &[], changes),
+    }
+
+    let modeled = ["$ref", "type", "enum", "properties", "required", "items", "additionalProperties"];
+    compare_fields(at, old, new, &modeled, changes);
+}
+
+/// Only the `key` of an object, for comparing one field with [`compare_fields`].
+fn keep(value: &Value, key: &str) -> Value {
+    let mut kept = Map::new();
+    if let Some(field) = value.get(key) {
+        kept.insert(key.to_string(), field.clone());
+    }
+    Value::Object(kept)
+}
+
+/// Report each field of two objects that differs, except the `skip`ped ones.
+fn compare_fields(at: &str, old: &Value, new: &Value, skip: &[&str], changes: &mut Vec<SpecChange>) {
+    let fields = |value: &Value| value.as_object().cloned().unwrap_or_default();
+    let (old, new) = (fields(old), fields(new));
+    let keys = old.keys().chain(new.keys()).filter(|key| !skip.contains(&key.as_str())).collect::<BTreeSet<_>>();
+    for key in keys {
+        let (old_field, new_field) = (old.get(key), new.get(key));
+        if !same_field(key, old_field, new_field) {
+            changes.push(SpecChange::FieldChanged {
+                at: at.to_string(),
+                field: key.clone(),
+                old: old_field.cloned(),
+                new: new_field.cloned(),
+            });
+        }
+    }
+}
+
+/// Whether a field is unchanged. Tags are a set, so their order is ignored.
+fn same_field(key: &str, old: Option<&Value>, new: Option<&Value>) -> bool {
+    match (key, old, new) {
+        ("tags", Some(Value::Array(old)), Some(Value::Array(new))) => {
+            old.iter().map(Value::to_string).collect::<BTreeSet<_>>() == new.iter().map(Value::to_string).collect::<BTreeSet<_>>()
+        }
+        _ => old == new,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+    use crate::openapi::{ OpenApiInfo, Tag };
+
+    fn spec(schemas: Value, paths: Value, tags: Vec<Tag>) -> OpenApiSpec {
+        let info = OpenApiInfo { title: "LCU".to_string(), description: String::new(), version: "1.0.0".to_string() };
+        let Value::Object(paths) = paths else { unreachable!() };
+        OpenApiSpec::new(info)
+            .with_components(Map::from_iter([("schemas".to_string(), schemas)]))
+            .with_paths(paths)
+            .with_tags(tags)
+    }
+
+    fn summoner() -> Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "accountId": { "type": "integer", "format": "uint64" },
+                "privacy": { "type": "string", "enum": ["PUBLIC", "PRIVATE"] },
+                "puuid": { "type": "string" },
+            },
+            "required": ["accountId"],
+            "additionalProperties": {},
+        })
+    }
+
+    fn changes_of(changes: &SpecChanges, name: &str) -> Vec<SpecChange> {
+        changes.changed.iter().find(|changed| changed.name == name).map(|changed| changed.changes.clone()).unwrap_or_default()
+    }
+
+    #[test]
+    fn test_schema_changes() {
+        let old = spec(json!({ "Summoner": summoner(), "Gone": { "type": "string" } }), json!({}), Vec::new());
+        let mut schema = summoner();
+        schema["properties"]["accountId"]["type"] = json!("string");
+        schema["properties"]["privacy"]["enum"] = json!(["PUBLIC", "PRIVATE", "FRIENDS"]);
+        schema["properties"].as_object_mut().unwrap().remove("puuid");
+        schema["properties"]["level"] = json!({ "type": "integer" });
+        schema["properties"]["privacy"]["format"] = json!("enum");
+        schema["required"] = json!(["privacy"]);
+        let new = spec(json!({ "Summoner": schema, "Added": { "type": "string" } }), json!({}), Vec::new());
+
+        let diff = old.diff(&new);
+        assert_eq!(diff.components.added, ["Added"]);
+        assert_eq!(diff.components.removed, ["Gone"]);
+        let at = |at: &str| at.to_string();
+        assert_eq!(
+            changes_of(&diff.components, "Summoner"),
+            [
+                SpecChange::TypeChanged { at: at("properties.accountId"), old: Some("integer".to_string()), new: Some("string".to_string()) },
+                SpecChange::PropertyAdded { at: at(""), name: "level".to_string() },
+                SpecChange::EnumValueAdded { at: at("properties.privacy"), value: json!("FRIENDS") },
+                SpecChange::FieldChanged { at: at("properties.privacy"), field: "format".to_string(), old: None, new: Some(json!("enum")) },
+                SpecChange::PropertyRemoved { at: at(""), name: "puuid".to_string() },
+                SpecChange::RequiredChanged { at: at(""), name: "privacy".to_string(), required: true },
+                SpecChange::RequiredChanged { at: at(""), name: "accountId".to_string(), required: false },
+            ]
+        );
+        assert!(diff.paths.is_empty() && diff.operations.is_empty() && diff.tags.is_empty());
+    }
+
+    #[test]
+    fn test_cosmetic_differences_ignored() {
+        let old = spec(json!({ "Summoner": summoner() }), json!({}), vec![Tag::new("a"), Tag::new("b")]);
+        let mut schema = summoner();
+        schema["additionalProperties"] = json!(true);
+        schema["properties"]["privacy"]["enum"] = json!(["PRIVATE", "PUBLIC"]);
+        let new = spec(json!({ "Summoner": schema }), json!({}), vec![Tag::new("b"), Tag::new("a")]);
+
+        let diff = old.diff(&new);
+        assert!(diff.is_empty(), "{diff:?}");
+        assert_eq!(diff.to_markdown(), "# Spec changes\n\nThe spec is unchanged.\n");
+    }
+
+    #[test]
+    fn test_operation_changes() {
+        let operation = |parameters: Value, response: &str| {
+            json!({
+                "operationId": "GetLolLobbyV2Lobby",
+                "tags": ["plugins", "lol-lobby"],
+                "parameters": parameters,
+                "responses": {
+                    "200": { "description": "Success", "content": { "application/json": { "schema": { "$ref": response } } } },
+                },
+            })
+        };
+        let old = spec(
+            json!({}),
+            json!({
+                "/lol-lobby/v2/lobby": {
+                    "get": operation(json!([{ "in": "query", "name": "id", "schema": { "type": "integer" } }]), "#/components/schemas/Lobby"),
+                    "delete": { "operationId": "DeleteLolLobbyV2Lobby", "responses": { "204": { "description": "Success" } } },
+                },
+                "/Help": { "post": { "operationId": "Help", "responses": {} } },
+            }),
+            Vec::new()
+        );
+        let mut get = operation(json!([{ "in": "query", "name": "id", "required": true, "schema": { "type": "string" } }]), "#/components/schemas/LobbyV2");
+        get["tags"] = json!(["lol-lobby", "plugins"]);
+        get["summary"] = json!("The lobby.");
+        get["responses"]["404"] = json!({ "description": "No lobby" });
+        get["parameters"].as_array_mut().unwrap().push(json!({ "in": "query", "name": "full", "schema": { "type": "boolean" } }));
+        let new = spec(
+            json!({}),
+            json!({
+                "/lol-lobby/v2/lobby": { "get": get, "put": { "operationId": "PutLolLobbyV2Lobby", "responses": {} } },
+                "/lol-lobby/v2/lobby/members": { "get": { "operationId": "GetLolLobbyV2LobbyMembers", "responses": {} } },
+            }),
+            Vec::new()
+        );
+
+        let diff = old.diff(&new);
+        assert_eq!(diff.paths.added, ["/lol-lobby/v2/lobby/members"]);
+        assert_eq!(diff.paths.removed, ["/Help"]);
+        assert_eq!(
+            changes_of(&diff.paths, "/lol-lobby/v2/lobby"),
+            [SpecChange::MethodAdded { method: "put".to_string() }, SpecChange::MethodRemoved { method: "delete".to_string() }]
+        );
+        assert_eq!(diff.operations.added, ["get /lol-lobby/v2/lobby/members", "put /lol-lobby/v2/lobby"]);
+        assert_eq!(diff.operations.removed, ["delete /lol-lobby/v2/lobby", "post /Help"]);
+
+        // Reordered tags aren't a change.
+        let changes = changes_of(&diff.operations, "get /lol-lobby/v2/lobby");
+        let lines = changes.iter().map(SpecChange::to_string).collect::<Vec<String>>();
+        assert_eq!(
+            lines,
+            [
+                "parameter full added",
+                "parameters.id: required absent -> true",
+                "parameters.id: type integer -> string",
+                "responses.200: $ref #/components/schemas/Lobby -> #/components/schemas/LobbyV2",
+                "response 404 added",
+                "summary absent -> \"The lobby.\"",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_tags_and_json() {
+        let display = |name: &str, display_name: &str| Tag { name: name.to_string(), display_name: Some(display_name.to_string()) };
+        let old = spec(json!({}), json!({}), vec![Tag::new("lol-lobby"), Tag::new("builtin")]);
+        let new = spec(json!({}), json!({}), vec![display("lol-lobby", "Lobby"), Tag::new("lol-summoner")]);
+
+        let diff = old.diff(&new);
+        assert_eq!(diff.tags.added, ["lol-summoner"]);
+        assert_eq!(diff.tags.removed, ["builtin"]);
+        assert_eq!(
+            serde_json::to_value(&diff.tags).unwrap(),
+            json!({
+                "added": ["lol-summoner"],
+                "removed": ["builtin"],
+                "changed": [{
+                    "name": "lol-lobby",
+                    "changes": [{ "kind": "field_changed", "at": "", "field": "x-display-name", "old": null, "new": "Lobby" }],
+                }],
+            })
+        );
+        assert!(diff.to_markdown().contains("## Tags: 1 added, 1 removed, 1 changed\n"));
+        assert!(diff.to_markdown().contains("  - x-display-name absent -> \"Lobby\"\n"));
+    }
+}
@@ -0,0 +1,259 @@
+//! Endpoints Riot ships unfinished, flagged apart from stable and deprecated ones.
+
+use serde::{ Deserialize, Serialize };
+use serde_json::Value;
+
+use crate::{
+    openapi::OpenApiSpec,
+    rules::{ OperationMatch, tags_of },
+    since::RunHistory,
+    version::LcuVersion,
+};
+
+/// Extension key holding an operation's [`Stability`].
+pub const X_STABILITY: &str = "x-stability";
+
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[serde(rename_all = "snake_case")]
+pub enum Stability {
+    Experimental,
+    Stable,
+}
+
+impl Stability {
+    /// The stability written on a generated operation, if it has one.
+    pub fn of(operation: &Value) -> Option<Self> {
+        serde_json::from_value(operation.get(X_STABILITY)?.clone()).ok()
+    }
+}
+
+/// A stability for the operations an [`OperationMatch`] matches.
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Eq)]
+pub struct StabilityRule {
+    #[serde(flatten)]
+    pub operations: OperationMatch,
+    pub stability: Stability,
+}
+
+/// Which rule decided an operation's stability.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum StabilitySource {
+    /// The override at this index.
+    Override(usize),
+    /// An experimental plugin, by tag.
+    Plugin(String),
+    /// A marker found in the summary or description.
+    Marker(String),
+    /// The history has no earlier version with the operation.
+    New,
+    /// Nothing matched.
+    Default,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Classification {
+    pub stability: Stability,
+    pub source: StabilitySource,
+}
+
+/// Decides which operations are experimental. Sources are checked in the
+/// order of the fields; the first that says anything decides.
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Eq)]
+#[serde(default)]
+pub struct StabilityRules {
+    /// The first matching rule decides, so an operation can be marked
+    /// stable despite the other sources.
+    pub overrides: Vec<StabilityRule>,
+    /// Plugins whose operations are all experimental, by tag, e.g. `lol-arena`.
+    pub plugins: Vec<String>,
+    /// Words in a summary or description that mark the operation unfinished,
+    /// matched whole and ignoring case.
+    pub markers: Vec<String>,
+    /// Mark operations no earlier version in the history has.
+    pub new_endpoints: bool,
+    /// Warn when a workflow view lists an experimental operation.
+    pub warn_in_workflows: bool,
+}
+
+impl Default for StabilityRules {
+    fn default() -> Self {
+        Self {
+            overrides: Vec::new(),
+            plugins: Vec::new(),
+            markers: ["WIP", "experimental", "beta"].map(str::to_string).to_vec(),
+            new_endpoints: true,
+            warn_in_workflows: false,
+        }
+    }
+}
+
+impl StabilityRules {
+    /// Classify one operation. `text` is its summary and description, and
+    /// `new` whether the history first saw it in this version.
+    pub fn classify(&self, method: &str, path: &str, tags: &[&str], text: &str, new: bool) -> Classification {
+        let experimental = |source| Classification { stability: Stability::Experimental, source };
+        if let Some((index, rule)) = self.overrides.iter().enumerate().find(|(_, rule)| rule.operations.matches(method, path, tags)) {
+            return Classification { stability: rule.stability, source: StabilitySource::Override(index) };
+        }
+        if let Some(plugin) = self.plugins.iter().find(|plugin| tags.contains(&plugin.as_str())) {
+            return experimental(StabilitySource::Plugin(plugin.clone()));
+        }
+        if let Some(marker) = self.markers.iter().find(|marker| contains_word(text, marker)) {
+            return experimental(StabilitySource::Marker(marker.clone()));
+        }
+        if self.new_endpoints && new {
+            return experimental(StabilitySource::New);
+        }
+        Classification { stability: Stability::Stable, source: StabilitySource::Default }
+    }
+}
+
+/// Whether `word` is in `text` with no letters or digits on either side, ignoring case.
+fn contains_word(text: &str, word: &str) -> bool {
+    let (text, word) = (text.to_lowercase(), word.to_lowercase());
+    if word.is_empty() {
+        return false;
+    }
+    let boundary = |c: Option<char>| c.is_none_or(|c| !c.is_alphanumeric());
+    text.match_indices(&word).any(|(start, _)| {
+        boundary(text[..start].chars().next_back()) && boundary(text[start + word.len()..].chars().next())
+    })
+}
+
+/// Writes [`X_STABILITY`] on every operation.
+///
+/// Without a history, or with only runs of this version or later, nothing
+/// counts as new: every operation would.
+#[derive(Debug, Clone)]
+pub struct StabilityAnnotations<'a> {
+    pub rules: &'a StabilityRules,
+    pub history: Option<&'a RunHistory>,
+}
+
+impl StabilityAnnotations<'_> {
+    /// Returns the number of operations marked experimental.
+    pub fn apply(&self, spec: &mut OpenApiSpec) -> usize {
+        let current = LcuVersion::new(&spec.info.version);
+        let history = self.history.filter(|history| history.records.iter().any(|record| record.version < current));
+        let first_seen = history.map(RunHistory::first_seen).unwrap_or_default();
+
+        let mut count = 0;
+        for (path, item) in spec.paths.iter_mut() {
+            let Some(item) = item.as_object_mut() else {
+                continue;
+            };
+            for (method, operation) in item.iter_mut() {
+                let text = ["summary", "description"]
+                    .iter()
+                    .filter_map(|key| operation.get(*key).and_then(Value::as_str))
+                    .collect::<Vec<&str>>()
+                    .join("\n");
+                let new = history.is_some() &&
+                    operation["operationId"]
+                        .as_str()
+                        .is_some_and(|id| first_seen.operations.get(id).is_none_or(|seen| **seen >= current));
+                let classification = self.rules.classify(method, path, &tags_of(operation), &text, new);
+                if let Value::Object(operation) = operation {
+                    operation.insert(X_STABILITY.to_string(), serde_json::to_value(classification.stability).unwrap_or_default());
+                }
+                if classification.stability == Stability::Experimental {
+                    count += 1;
+                }
+            }
+        }
+        count
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::{ BTreeMap, BTreeSet };
+
+    use super::*;
+    use crate::{ options::SchemaOptions, report::SchemaReport, since::RunRecord, tests::miniature };
+
+    fn rule(path_prefix: &str, stability: Stability) -> StabilityRule {
+        StabilityRule {
+            operations: OperationMatch { path_prefix: Some(path_prefix.to_string()), ..Default::default() },
+            stability,
+        }
+    }
+
+    fn record(version: &str, operations: &[&str]) -> RunRecord {
+        RunRecord {
+            version: LcuVersion::new(version),
+            operations: operations.iter().map(|s| s.to_string()).collect(),
+            components: BTreeSet::new(),
+            slugs: BTreeMap::new(),
+            sizes: None,
+            experimental: BTreeSet::new(),
+        }
+    }
+
+    #[test]
+    fn test_sources() {
+        let rules = StabilityRules {
+            overrides: vec![rule("/lol-arena", Stability::Experimental)],
+            plugins: vec!["lol-tft-pass".to_string()],
+            ..Default::default()
+        };
+        let classify = |path: &str, tags: &[&str], text: &str, new: bool| rules.classify("GET", path, tags, text, new).source;
+
+        assert_eq!(classify("/lol-arena/v1/cards", &[], "", false), StabilitySource::Override(0));
+        assert_eq!(classify("/lol-tft-pass/v1/pass", &["lol-tft-pass"], "", false), StabilitySource::Plugin("lol-tft-pass".to_string()));
+        assert_eq!(classify("/lol-lobby/v2/lobby", &[], "Party invites (WIP).", false), StabilitySource::Marker("WIP".to_string()));
+        assert_eq!(classify("/lol-lobby/v2/lobby", &[], "Now in Beta", false), StabilitySource::Marker("beta".to_string()));
+        assert_eq!(classify("/lol-lobby/v2/lobby", &[], "", true), StabilitySource::New);
+
+        let stable = rules.classify("GET", "/lol-lobby/v2/lobby", &[], "Alphabetical wipe of the list", false);
+        assert_eq!(stable, Classification { stability: Stability::Stable, source: StabilitySource::Default });
+        let old = StabilityRules { new_endpoints: false, ..Default::default() };
+        assert_eq!(old.classify("GET", "/lol-lobby/v2/lobby", &[], "", true).stability, Stability::Stable);
+    }
+
+    #[test]
+    fn test_precedence() {
+        let rules = StabilityRules {
+            overrides: vec![rule("/lol-tft-pass/v1/pass", Stability::Stable), rule("/lol-tft-pass", Stability::Experimental)],
+            plugins: vec!["lol-tft-pass".to_string()],
+            ..Default::default()
+        };
+        // An override beats every other source, and the first override wins.
+        let pass = rules.classify("GET", "/lol-tft-pass/v1/pass", &["lol-tft-pass"], "WIP", true);
+        assert_eq!(pass, Classification { stability: Stability::Stable, source: StabilitySource::Override(0) });
+        let other = rules.classify("GET", "/lol-tft-pass/v1/other", &["lol-tft-pass"], "WIP", true);
+        assert_eq!(other.source, StabilitySource::Override(1));
+
+        // Then plugins, then markers, then history.
+        let no_overrides = StabilityRules { overrides: Vec::new(), ..rules };
+        let plugin = no_overrides.classify("GET", "/lol-tft-pass/v1/pass", &["lol-tft-pass"], "WIP", true);
+        assert_eq!(plugin.source, StabilitySource::Plugin("lol-tft-pass".to_string()));
+        assert_eq!(no_overrides.classify("GET", "/lol-lobby", &[], "WIP", true).source, StabilitySource::Marker("WIP".to_string()));
+    }
+
+    #[test]
+    fn test_new_endpoints_from_history() {
+        let mut history = RunHistory::default();
+        history.push(record("14.3.556.1234", &["GetLolSummonerV1CurrentSummoner"]));
+        history.push(record("14.4.560.1", &["GetLolSummonerV1CurrentSummoner", "GetLolSummonerV1SummonersById"]));
+        // A run of this version already in the history doesn't make its operations old.
+        history.push(record("14.5.570.1", &["GetLolSummonerV1CurrentSummoner", "PostLolLobbyV2Lobby"]));
+
+        let rules = StabilityRules::default();
+        let options = SchemaOptions { stability: Some(rules.clone()), history: Some(history), ..Default::default() };
+        let spec = OpenApiSpec::from_help_with(&miniature(), "14.5.570.1", &options, &mut SchemaReport::default()).unwrap();
+
+        let stability = |path: &str, method: &str| Stability::of(&spec.paths[path][method]);
+        assert_eq!(stability("/lol-summoner/v1/current-summoner", "get"), Some(Stability::Stable));
+        assert_eq!(stability("/lol-summoner/v1/summoners/{id}", "get"), Some(Stability::Stable));
+        assert_eq!(stability("/lol-lobby/v2/lobby", "post"), Some(Stability::Experimental));
+        assert_eq!(stability("/lol-lobby/v2/lobby", "delete"), Some(Stability::Experimental));
+
+        // Without an older run, nothing is new.
+        let mut spec = OpenApiSpec::from_help(&miniature(), "14.3.556.1234").unwrap();
+        let history = RunHistory { records: vec![record("14.3.556.1234", &[])] };
+        assert_eq!(StabilityAnnotations { rules: &rules, history: Some(&history) }.apply(&mut spec), 0);
+        assert_eq!(StabilityAnnotations { rules: &rules, history: None }.apply(&mut spec), 0);
+        assert_eq!(Stability::of(&spec.paths["/lol-lobby/v2/lobby"]["post"]), Some(Stability::Stable));
+    }
+}
@@ -0,0 +1,264 @@
+//! Output styles for serialized specs.
+//!
+//! [`SerializationStyle::HasagiCompat`] reorders keys to follow hasagi's
+//! `openapi.json` so the two files can be diffed without ordering noise:
+//!
+//! - top-level keys are `openapi`, `info`, `tags`, `paths`, `components`,
+//!   then the `x-workflows` and `x-events` extensions, which hasagi doesn't have
+//! - paths and component schemas are ordered case-insensitively
+//! - methods within a path follow `get`, `post`, `put`, `patch`, `delete`, `head`, `options`
+//! - operation keys are `tags`, `summary`, `description`, `operationId`,
+//!   `parameters`, `requestBody`, `responses`, then any extensions
+//!
+//! The core types are left alone; the style only wraps them while serializing.
+//! Only key order changes: names are written as the spec has them.
+
+use std::{ cmp::Ordering, io::{ BufWriter, Write } };
+
+use serde::{ Serialize, Serializer, ser::SerializeMap };
+use serde_json::{ Map, Value };
+
+use crate::{ error::Error, openapi::{ OpenApiSpec, X_EVENTS }, workflow::X_WORKFLOWS };
+
+const METHOD_ORDER: [&str; 7] = ["get", "post", "put", "patch", "delete", "head", "options"];
+
+const OPERATION_KEY_ORDER: [&str; 7] = [
+    "tags",
+    "summary",
+    "description",
+    "operationId",
+    "parameters",
+    "requestBody",
+    "responses",
+];
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum SerializationStyle {
+    /// Keys in struct order, maps sorted alphabetically.
+    #[default]
+    Standard,
+    /// Keys ordered to match hasagi's output.
+    HasagiCompat,
+}
+
+impl SerializationStyle {
+    pub const ALL: [SerializationStyle; 2] = [Self::Standard, Self::HasagiCompat];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Standard => "standard",
+            Self::HasagiCompat => "hasagi-compat",
+        }
+    }
+}
+
+impl OpenApiSpec {
+    /// Pretty-print the spec in the given style.
+    pub fn to_string_styled(&self, style: SerializationStyle) -> Result<String, Error> {
+        Ok(match style {
+            SerializationStyle::Standard => serde_json::to_string_pretty(self)?,
+            SerializationStyle::HasagiCompat => serde_json::to_string_pretty(&HasagiSpec(self))?,
+        })
+    }
+
+    /// Pretty-print the spec in the given style into `writer`, byte for byte
+    /// what [`Self::to_string_styled`] returns.
+    ///
+    /// Section by section, each path item and schema is written as soon as
+    /// it's reached, so besides the spec itself only a small buffer is held,
+    /// never the whole document. Worth it for specs with examples and code
+    /// samples, which run to tens of megabytes.
+    pub fn write_streaming<W: Write>(&self, writer: W, style: SerializationStyle) -> Result<(), Error> {
+        let mut writer = BufWriter::new(writer);
+        match style {
+            SerializationStyle::Standard => serde_json::to_writer_pretty(&mut writer, self)?,
+            SerializationStyle::HasagiCompat => serde_json::to_writer_pretty(&mut writer, &HasagiSpec(self))?,
+        }
+        writer.flush()?;
+        Ok(())
+    }
+}
+
+struct HasagiSpec<'a>(&'a OpenApiSpec);
+
+impl Serialize for HasagiSpec<'_> {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+        where S: Serializer
+    {
+        let spec = self.0;
+        let mut map = serializer.serialize_map(None)?;
+        map.serialize_entry("openapi", &spec.openapi)?;
+        map.serialize_entry("info", &spec.info)?;
+        if !spec.tags.is_empty() {
+            map.serialize_entry("tags", &spec.tags)?;
+        }
+        map.serialize_entry("paths", &Node { kind: Kind::Paths, value: &spec.paths })?;
+        map.serialize_entry("components", &Node { kind: Kind::Components, value: &spec.components })?;
+        if !spec.workflows.is_empty() {
+            map.serialize_entry(X_WORKFLOWS, &spec.workflows)?;
+        }
+        if !spec.events.is_empty() {
+            map.serialize_entry(X_EVENTS, &Node { kind: Kind::Plain, value: &spec.events })?;
+        }
+        map.end()
+    }
+}
+
+/// Where a JSON object sits in the spec, which decides how its keys are ordered.
+#[derive(Clone, Copy)]
+enum Kind {
+    Plain,
+    Paths,
+    PathItem,
+    Operation,
+    Components,
+    Schemas,
+}
+
+impl Kind {
+    fn order(self, a: &str, b: &str) -> Ordering {
+        match self {
+            Kind::Plain => a.cmp(b),
+            Kind::Paths | Kind::Components | Kind::Schemas => caseless(a, b),
+            Kind::PathItem => ranked(&METHOD_ORDER, a, b),
+            Kind::Operation => ranked(&OPERATION_KEY_ORDER, a, b),
+        }
+    }
+
+    fn child(self, key: &str) -> Kind {
+        match self {
+            Kind::Paths => Kind::PathItem,
+            Kind::PathItem if METHOD_ORDER.contains(&key) => Kind::Operation,
+            Kind::Components if key == "schemas" => Kind::Schemas,
+            _ => Kind::Plain,
+        }
+    }
+}
+
+struct Node<'a, T> {
+    kind: Kind,
+    value: &'a T,
+}
+
+impl Serialize for Node<'_, Map<String, Value>> {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+        where S: Serializer
+    {
+        let mut entries = self.value.iter().collect::<Vec<_>>();
+        entries.sort_by(|(a, _), (b, _)| self.kind.order(a, b));
+
+        let mut map = serializer.serialize_map(Some(entries.len()))?;
+        for (key, value) in entries {
+            map.serialize_entry(key, &Node { kind: self.kind.child(key), value })?;
+        }
+        map.end()
+    }
+}
+
+impl Serialize for Node<'_, Value> {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+        where S: Serializer
+    {
+        match self.value {
+            Value::Object(object) => Node { kind: self.kind, value: object }.serialize(serializer),
+            other => other.serialize(serializer),
+        }
+    }
+}
+
+/// Case-insensitive, falling back to byte order so the result is total.
+fn caseless(a: &str, b: &str) -> Ordering {
+    a.to_lowercase().cmp(&b.to_lowercase()).then_with(|| a.cmp(b))
+}
+
+/// Keys listed in `order` first, in that order, then the rest by byte order.
+fn ranked(order: &[&str], a: &str, b: &str) -> Ordering {
+    let rank = |key: &str| order.iter().position(|k| *k == key).unwrap_or(order.len());
+    rank(a).cmp(&rank(b)).then_with(|| a.cmp(b))
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+    use crate::{ openapi::{ OpenApiInfo, Tag }, tests::miniature_spec };
+
+    fn spec() -> OpenApiSpec {
+        let paths =
+            json!({
+            "/lol-summoner/v1/summoners": {
+                "post": { "operationId": "PostSummoners", "responses": {}, "tags": ["lol-summoner"] },
+                "get": { "operationId": "GetSummoners", "responses": {}, "summary": "List" }
+            },
+            "/Help": { "post": { "operationId": "Help", "responses": {} } }
+        });
+        let components =
+            json!({
+            "schemas": {
+                "lolB": { "type": "string" },
+                "LolA": { "type": "object", "properties": { "z": {}, "a": {} } }
+            }
+        });
+        let mut spec = OpenApiSpec::new(OpenApiInfo::lcu("1.0.0"))
+            .with_paths(paths.as_object().unwrap().clone())
+            .with_components(components.as_object().unwrap().clone())
+            .with_tags(vec![Tag::new("lol-summoner")]);
+        spec.workflows.insert("Summoners".to_string(), vec!["GetSummoners".to_string()]);
+        let events = json!({ "OnJsonApiEvent": { "description": "Any change" } });
+        spec.with_events(events.as_object().unwrap().clone())
+    }
+
+    /// Compare `json` with `tests/snapshots/style-<name>.json`. Run with
+    /// `UPDATE_SNAPSHOTS=1` to write the files instead, then review the diff.
+    fn assert_snapshot(name: &str, expected: &str, json: &str) {
+        if std::env::var_os("UPDATE_SNAPSHOTS").is_some() {
+            let path = format!("{}/tests/snapshots/style-{name}.json", env!("CARGO_MANIFEST_DIR"));
+            std::fs::write(path, format!("{json}\n")).unwrap();
+            return;
+        }
+        assert_eq!(json, expected.trim_end(), "{name} no longer matches its snapshot");
+    }
+
+    #[test]
+    fn test_standard_style() {
+        let json = spec().to_string_styled(SerializationStyle::Standard).unwrap();
+        assert_eq!(json, serde_json::to_string_pretty(&spec()).unwrap());
+        assert_snapshot("standard", include_str!("../tests/snapshots/style-standard.json"), &json);
+    }
+
+    #[test]
+    fn test_hasagi_compat_style() {
+        let json = spec().to_string_styled(SerializationStyle::HasagiCompat).unwrap();
+        assert_snapshot("hasagi-compat", include_str!("../tests/snapshots/style-hasagi-compat.json"), &json);
+
+        // Reordering never changes the content.
+        let reparsed: Value = serde_json::from_str(&json).unwrap();
+        assert_eq!(reparsed, serde_json::to_value(spec()).unwrap());
+    }
+
+    /// The miniature, with `copies` more path items, each with a long
+    /// description, for a document of a few megabytes.
+    fn inflated(copies: usize) -> OpenApiSpec {
+        let mut spec = miniature_spec("1.0.0");
+        let template = spec.paths["/lol-summoner/v1/summoners/{id}"].clone();
+        for i in 0..copies {
+            let mut item = template.clone();
+            item["get"]["operationId"] = json!(format!("GetCopy{i}"));
+            item["get"]["description"] = json!("A long description of a copied operation. ".repeat(20));
+            spec.paths.insert(format!("/lol-copy/v1/copies/{i}/{{id}}"), item);
+        }
+        spec
+    }
+
+    #[test]
+    fn test_write_streaming_matches() {
+        for spec in [spec(), miniature_spec("1.0.0"), inflated(200)] {
+            for style in SerializationStyle::ALL {
+                let mut streamed = Vec::new();
+                spec.write_streaming(&mut streamed, style).unwrap();
+                assert_eq!(String::from_utf8(streamed).unwrap(), spec.to_string_styled(style).unwrap(), "{style:?}");
+            }
+        }
+    }
+}
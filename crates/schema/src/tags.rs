@@ -0,0 +1,108 @@
+//! Display names for tags.
+//!
+//! A tag's `name` is what operations refer to and tools match on, so it stays
+//! as the help data has it. The [`X_DISPLAY_NAME`] next to it is for people:
+//! `lol-summoner` reads as `Summoner (lol-summoner)`.
+
+use std::collections::BTreeMap;
+
+use serde::{ Deserialize, Serialize };
+
+use crate::workflow::WORKFLOW_TAG_PREFIX;
+
+/// Tag extension key with the name to show instead of the tag's own.
+pub const X_DISPLAY_NAME: &str = "x-display-name";
+
+/// Plugin name prefixes that say where a plugin runs rather than what it's for.
+const PLUGIN_PREFIXES: [&str; 5] = ["rcp-be-lol-", "rcp-fe-lol-", "rcp-be-", "rcp-fe-", "lol-"];
+
+/// Curated display names by tag, for tags the formatting can't name well.
+#[derive(Deserialize, Serialize, Debug, Clone, Default, PartialEq, Eq)]
+#[serde(transparent)]
+pub struct TagNames {
+    pub names: BTreeMap<String, String>,
+}
+
+impl TagNames {
+    /// The bundled names for builtin and workflow tags.
+    pub fn starter() -> Self {
+        serde_json::from_str(include_str!("../tags.json")).expect("starter tag names parse")
+    }
+
+    /// The display name of `tag`, which is a plugin's name when `plugin`.
+    ///
+    /// Curated names win. Otherwise workflow tags lose their prefix, plugins
+    /// get their title-cased resource with the plugin in parentheses, and
+    /// anything else is title-cased.
+    pub fn display_name(&self, tag: &str, plugin: bool) -> String {
+        if let Some(name) = self.names.get(tag) {
+            return name.clone();
+        }
+        if let Some(workflow) = tag.strip_prefix(WORKFLOW_TAG_PREFIX) {
+            return workflow.to_string();
+        }
+        if !plugin {
+            return title_case(tag);
+        }
+        let resource = PLUGIN_PREFIXES.iter().find_map(|prefix| tag.strip_prefix(prefix)).filter(|resource| !resource.is_empty());
+        format!("{} ({tag})", title_case(resource.unwrap_or(tag)))
+    }
+}
+
+/// `champ-select` as `Champ Select`.
+fn title_case(name: &str) -> String {
+    name.split(['-', '_'])
+        .filter(|word| !word.is_empty())
+        .map(|word| {
+            let mut chars = word.chars();
+            chars.next().map(|first| first.to_uppercase().chain(chars).collect::<String>()).unwrap_or_default()
+        })
+        .collect::<Vec<String>>()
+        .join(" ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::openapi::{ OpenApiSpec, Tag };
+    use crate::tests::miniature;
+
+    #[test]
+    fn test_display_name() {
+        let names = TagNames::starter();
+        let cases = [
+            ("lol-summoner", true, "Summoner (lol-summoner)"),
+            ("lol-champ-select", true, "Champ Select (lol-champ-select)"),
+            ("rcp-be-lol-game-data", true, "Game Data (rcp-be-lol-game-data)"),
+            ("rcp-fe-audio", true, "Audio (rcp-fe-audio)"),
+            ("player-notifications", true, "Player Notifications (player-notifications)"),
+            ("lol-", true, "Lol (lol-)"),
+            ("riotclient", true, "Riot Client"),
+            ("builtin", false, "Built-in"),
+            ("plugins", false, "Plugins"),
+            ("performance", false, "Performance"),
+            ("Workflow: Champ select", false, "Champ Select"),
+            ("Workflow: Ranked", false, "Ranked"),
+        ];
+        for (tag, plugin, expected) in cases {
+            assert_eq!(names.display_name(tag, plugin), expected, "{tag}");
+        }
+        assert_eq!(TagNames::default().display_name("builtin", false), "Builtin");
+    }
+
+    #[test]
+    fn test_display_names_round_trip() {
+        let spec = OpenApiSpec::from_help(&miniature(), "1.0.0").unwrap();
+        // Names are what operations refer to, unchanged.
+        let names = spec.tags.iter().map(|tag| tag.name.as_str()).collect::<Vec<&str>>();
+        assert_eq!(names, ["builtin", "lol-lobby", "lol-summoner", "plugins"]);
+        assert_eq!(spec.tags[2], Tag { name: "lol-summoner".to_string(), display_name: Some("Summoner (lol-summoner)".to_string()) });
+
+        let json = serde_json::to_value(&spec).unwrap();
+        assert_eq!(json["tags"][0], serde_json::json!({ "name": "builtin", X_DISPLAY_NAME: "Built-in" }));
+        let parsed: OpenApiSpec = serde_json::from_value(json).unwrap();
+        assert_eq!(parsed.tags, spec.tags);
+        let operation = &parsed.paths["/lol-summoner/v1/current-summoner"]["get"];
+        assert_eq!(operation["tags"], serde_json::json!(["plugins", "lol-summoner"]));
+    }
+}
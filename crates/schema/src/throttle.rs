@@ -0,0 +1,244 @@
+//! Pacing requests to a live client, so generating a spec mid-game doesn't
+//! make the client stutter.
+//!
+//! [`Throttle`] only does the arithmetic on a clock it is handed, which keeps
+//! it testable without sleeping; [`paced`] runs it on the real clock.
+
+use std::{ fmt, time::Duration };
+
+/// How politely to fetch help from a live client. The default fetches as
+/// fast as the client answers.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Politeness {
+    /// Least time between the starts of two requests.
+    pub min_delay: Duration,
+    /// Requests per second, shared by everything fetching through the same [`Throttle`].
+    pub rate: Option<f64>,
+    /// Requests allowed back to back before `rate` applies. At least 1.
+    pub burst: u32,
+    pub adaptive: Option<Adaptive>,
+    /// Help requests in flight at once. 0 and 1 both fetch one target at a
+    /// time, and the pacing above applies to all of them together.
+    pub concurrency: usize,
+}
+
+/// Back off while the client answers slowly, which means it is busy.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Adaptive {
+    /// Responses slower than this count as the client being under load.
+    pub latency_threshold: Duration,
+    /// Wait added after the first slow response, doubled on each one after.
+    pub step: Duration,
+    pub max_delay: Duration,
+}
+
+impl Default for Adaptive {
+    fn default() -> Self {
+        Self {
+            latency_threshold: Duration::from_millis(250),
+            step: Duration::from_millis(50),
+            max_delay: Duration::from_secs(2),
+        }
+    }
+}
+
+/// Decides when each request may start. Times are offsets from any fixed start.
+#[derive(Debug, Clone)]
+pub struct Throttle {
+    politeness: Politeness,
+    tokens: f64,
+    refilled_at: Duration,
+    last_start: Option<Duration>,
+    /// Added by [`Adaptive`] while the client is slow.
+    backoff: Duration,
+    stats: ThrottleStats,
+}
+
+/// What a [`Throttle`] did, for the report.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct ThrottleStats {
+    pub requests: usize,
+    /// From the first request's start to the last response.
+    pub elapsed: Duration,
+    /// Slow responses that made the throttle back off.
+    pub backoffs: usize,
+    first_start: Option<Duration>,
+}
+
+impl ThrottleStats {
+    /// Effective requests per second over the whole run.
+    pub fn average_rate(&self) -> f64 {
+        if self.elapsed.is_zero() { 0.0 } else { (self.requests as f64) / self.elapsed.as_secs_f64() }
+    }
+}
+
+impl fmt::Display for ThrottleStats {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} requests in {:.1}s, {:.1}/s on average",
+            self.requests,
+            self.elapsed.as_secs_f64(),
+            self.average_rate()
+        )?;
+        if self.backoffs > 0 {
+            write!(f, ", backed off {} times", self.backoffs)?;
+        }
+        Ok(())
+    }
+}
+
+impl Throttle {
+    pub fn new(politeness: Politeness) -> Self {
+        let burst = politeness.burst.max(1);
+        Self {
+            politeness,
+            tokens: f64::from(burst),
+            refilled_at: Duration::ZERO,
+            last_start: None,
+            backoff: Duration::ZERO,
+            stats: ThrottleStats::default(),
+        }
+    }
+
+    /// Claim the next request slot at or after `now`, and return when it starts.
+    pub fn reserve(&mut self, now: Duration) -> Duration {
+        let mut start = now + self.backoff;
+        if let Some(last) = self.last_start {
+            start = start.max(last + self.politeness.min_delay);
+        }
+        if let Some(rate) = self.politeness.rate.filter(|rate| *rate > 0.0) {
+            let burst = f64::from(self.politeness.burst.max(1));
+            let refill = start.saturating_sub(self.refilled_at).as_secs_f64() * rate;
+            self.tokens = (self.tokens + refill).min(burst);
+            if self.tokens < 1.0 {
+                start += Duration::from_secs_f64((1.0 - self.tokens) / rate);
+                self.tokens = 1.0;
+            }
+            self.tokens -= 1.0;
+            self.refilled_at = start;
+        }
+        self.last_start = Some(start);
+        self.stats.requests += 1;
+        self.stats.first_start.get_or_insert(start);
+        start
+    }
+
+    /// Record how long the request started at `start` took.
+    pub fn record(&mut self, start: Duration, latency: Duration) {
+        let first = self.stats.first_start.unwrap_or(start);
+        self.stats.elapsed = self.stats.elapsed.max(start + latency - first);
+
+        let Some(adaptive) = &self.politeness.adaptive else {
+            return;
+        };
+        if latency > adaptive.latency_threshold {
+            self.backoff = (self.backoff * 2).max(adaptive.step).min(adaptive.max_delay);
+            self.stats.backoffs += 1;
+        } else {
+            self.backoff /= 2;
+            if self.backoff < adaptive.step {
+                self.backoff = Duration::ZERO;
+            }
+        }
+    }
+
+    pub fn stats(&self) -> ThrottleStats {
+        self.stats
+    }
+}
+
+/// Run `request` through `throttle` on the real clock, measured from `epoch`.
+#[cfg(feature = "irelia")]
+pub async fn paced<F: std::future::Future>(
+    throttle: &std::sync::Mutex<Throttle>,
+    epoch: std::time::Instant,
+    request: F
+) -> F::Output {
+    let now = epoch.elapsed();
+    let start = throttle.lock().expect("throttle lock").reserve(now);
+    tokio::time::sleep(start.saturating_sub(now)).await;
+    let output = request.await;
+    let latency = epoch.elapsed().saturating_sub(start);
+    throttle.lock().expect("throttle lock").record(start, latency);
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ms(millis: u64) -> Duration {
+        Duration::from_millis(millis)
+    }
+
+    /// A mock client on a virtual clock: each request takes `latency(i)`, and
+    /// the next one is ready to go as soon as the previous answers.
+    fn run(throttle: &mut Throttle, count: usize, latency: impl Fn(usize) -> Duration) -> Vec<Duration> {
+        let mut now = Duration::ZERO;
+        (0..count)
+            .map(|i| {
+                let start = throttle.reserve(now);
+                now = start + latency(i);
+                throttle.record(start, latency(i));
+                start
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_unthrottled() {
+        let mut throttle = Throttle::new(Politeness::default());
+        let starts = run(&mut throttle, 3, |_| ms(10));
+        assert_eq!(starts, [ms(0), ms(10), ms(20)]);
+        assert_eq!(throttle.stats().elapsed, ms(30));
+        assert!((throttle.stats().average_rate() - 100.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_min_delay() {
+        let mut throttle = Throttle::new(Politeness { min_delay: ms(50), ..Default::default() });
+        let starts = run(&mut throttle, 3, |_| ms(10));
+        assert_eq!(starts, [ms(0), ms(50), ms(100)]);
+    }
+
+    #[test]
+    fn test_rate_limit() {
+        let politeness = Politeness { rate: Some(4.0), burst: 2, ..Default::default() };
+        let mut throttle = Throttle::new(politeness);
+        let starts = run(&mut throttle, 6, |_| ms(1));
+        // Two back to back, then one every 250ms.
+        assert_eq!(starts[..2], [ms(0), ms(1)]);
+        for pair in starts[1..].windows(2) {
+            assert!(pair[1] - pair[0] >= ms(249), "{pair:?}");
+        }
+        // The first second holds at most rate + burst requests.
+        assert!(starts.iter().filter(|start| **start < Duration::from_secs(1)).count() <= 6);
+    }
+
+    #[test]
+    fn test_rate_shared_across_workers() {
+        // Two workers claiming slots at the same moments still get the shared rate.
+        let mut throttle = Throttle::new(Politeness { rate: Some(10.0), ..Default::default() });
+        let starts = (0..4).map(|_| throttle.reserve(Duration::ZERO)).collect::<Vec<Duration>>();
+        assert_eq!(starts, [ms(0), ms(100), ms(200), ms(300)]);
+    }
+
+    #[test]
+    fn test_adaptive_backoff() {
+        let adaptive = Adaptive { latency_threshold: ms(100), step: ms(50), max_delay: ms(150) };
+        let mut throttle = Throttle::new(Politeness { adaptive: Some(adaptive), ..Default::default() });
+        // Requests 2..5 are slow, as if the client were under load.
+        let latency = |i: usize| if (2..5).contains(&i) { ms(200) } else { ms(10) };
+        let starts = run(&mut throttle, 8, latency);
+        let gaps = starts.windows(2).map(|pair| pair[1] - pair[0]).collect::<Vec<Duration>>();
+
+        assert_eq!(gaps[..2], [ms(10), ms(10)]);
+        // Each slow answer adds a growing, capped delay.
+        assert_eq!(gaps[2..5], [ms(250), ms(300), ms(350)]);
+        // Fast answers again wind it down.
+        assert_eq!(gaps[5..], [ms(85), ms(10)]);
+        assert_eq!(throttle.stats().backoffs, 3);
+        assert!(throttle.stats().to_string().ends_with("backed off 3 times"));
+    }
+}
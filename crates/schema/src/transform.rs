@@ -0,0 +1,632 @@
+use std::collections::{ BTreeMap, BTreeSet };
+
+use serde_json::{ Map, Value };
+
+use crate::{
+    cycles::FindCycles,
+    index::{ SpecIndex, ref_name, walk_refs },
+    openapi::OpenApiSpec,
+    report::{ ReportKind, SchemaReport },
+    visit::{ visit_schemas_mut, SchemaContext },
+};
+
+/// Extension key recording the integer format a schema had before it was rewritten.
+pub const X_ORIGINAL_FORMAT: &str = "x-original-format";
+
+/// Extension keys listing the operations that use a component.
+pub const X_USED_BY: &str = "x-used-by";
+pub const X_PRODUCED_BY: &str = "x-produced-by";
+pub const X_CONSUMED_BY: &str = "x-consumed-by";
+
+/// Opt-in transform that rewrites 64-bit integer schemas into string schemas.
+///
+/// Summoner ids, game ids, and similar values exceed JavaScript's safe integer range,
+/// so JS/TS consumers silently lose precision when the spec says `integer`. Other
+/// published Riot API specs model these ids as strings for the same reason.
+///
+/// Rewritten schemas become `type: string` and keep their old format under
+/// [`X_ORIGINAL_FORMAT`]. Names are matched against the owning property or parameter
+/// name, or against the full dot path of the schema.
+#[derive(Debug, Clone, Default)]
+pub struct Int64AsString {
+    /// When non-empty, only schemas owned by these names are rewritten.
+    pub allow: Vec<String>,
+    /// Schemas owned by these names always stay numeric (ports, counts, etc.).
+    pub deny: Vec<String>,
+}
+
+impl Int64AsString {
+    /// Apply the transform to components and parameters alike.
+    /// Returns the number of schemas that were rewritten.
+    pub fn apply(&self, spec: &mut OpenApiSpec) -> usize {
+        let mut count = 0;
+        visit_schemas_mut(spec, &mut |ctx, schema| {
+            if self.should_rewrite(ctx, schema) {
+                rewrite_as_string(schema);
+                count += 1;
+            }
+        });
+        count
+    }
+
+    fn should_rewrite(&self, ctx: &SchemaContext, schema: &Value) -> bool {
+        if !is_int64(schema) {
+            return false;
+        }
+        let matches = |names: &[String]| {
+            names.iter().any(|n| ctx.name == Some(n.as_str()) || ctx.path == *n)
+        };
+        if matches(&self.deny) {
+            return false;
+        }
+        self.allow.is_empty() || matches(&self.allow)
+    }
+}
+
+/// Annotates each component schema with the operations that use it.
+///
+/// Writes [`X_USED_BY`], plus [`X_PRODUCED_BY`] for operations returning the component
+/// and [`X_CONSUMED_BY`] for operations taking it in a body or parameter. Lists longer
+/// than `cap` are replaced by their length. Run this after every other pass so the
+/// refs it follows are final.
+#[derive(Debug, Clone)]
+pub struct UsageAnnotations {
+    pub cap: usize,
+}
+
+impl Default for UsageAnnotations {
+    fn default() -> Self {
+        Self { cap: 50 }
+    }
+}
+
+impl UsageAnnotations {
+    /// Returns the number of components that were annotated.
+    pub fn apply(&self, spec: &mut OpenApiSpec) -> usize {
+        let index = SpecIndex::build(spec);
+        let Some(Value::Object(schemas)) = spec.components.get_mut("schemas") else {
+            return 0;
+        };
+
+        let mut count = 0;
+        for (name, schema) in schemas.iter_mut() {
+            let Value::Object(schema) = schema else {
+                continue;
+            };
+            let used_by = index.used_by(name);
+            if used_by.is_empty() {
+                continue;
+            }
+            let produced_by = as_strs(index.produced_by.get(name));
+            let consumed_by = as_strs(index.consumed_by.get(name));
+
+            schema.insert(X_USED_BY.to_string(), self.capped(&used_by));
+            if !produced_by.is_empty() {
+                schema.insert(X_PRODUCED_BY.to_string(), self.capped(&produced_by));
+            }
+            if !consumed_by.is_empty() {
+                schema.insert(X_CONSUMED_BY.to_string(), self.capped(&consumed_by));
+            }
+            count += 1;
+        }
+        count
+    }
+
+    fn capped(&self, ids: &BTreeSet<&str>) -> Value {
+        if ids.len() > self.cap {
+            Value::from(ids.len())
+        } else {
+            ids.iter().map(|id| Value::from(*id)).collect()
+        }
+    }
+}
+
+/// Opt-in transform that replaces refs to tiny, rarely used components with
+/// copies of the component, then removes it.
+///
+/// A component qualifies when it has at most `max_properties` properties (enums
+/// have none) and is referenced from at most `max_refs` places outside itself.
+/// Recursive components are never inlined; small ones are reported as warnings.
+#[derive(Debug, Clone)]
+pub struct InlineSmallComponents {
+    pub max_properties: usize,
+    pub max_refs: usize,
+}
+
+impl Default for InlineSmallComponents {
+    fn default() -> Self {
+        Self { max_properties: 1, max_refs: 1 }
+    }
+}
+
+impl InlineSmallComponents {
+    /// Returns the number of components that were inlined.
+    pub fn apply(&self, spec: &mut OpenApiSpec, report: &mut SchemaReport) -> usize {
+        let Some(Value::Object(schemas)) = spec.components.get("schemas") else {
+            return 0;
+        };
+        let cyclic = spec.components.cyclic();
+
+        // Use sites of each component, not counting its references to itself.
+        let mut sites = BTreeMap::<String, usize>::new();
+        let owners = schemas
+            .iter()
+            .map(|(name, schema)| (Some(name.as_str()), schema))
+            .chain(spec.paths.values().map(|path_item| (None, path_item)))
+            .chain(spec.events.values().map(|event| (None, event)));
+        for (owner, value) in owners {
+            walk_refs(value, &mut |name| {
+                if owner != Some(name) {
+                    *sites.entry(name.to_string()).or_default() += 1;
+                }
+            });
+        }
+
+        let mut inlined = Vec::new();
+        for (name, schema) in schemas.iter() {
+            let uses = sites.get(name).copied().unwrap_or_default();
+            if uses == 0 || uses > self.max_refs || size(schema) > self.max_properties {
+                continue;
+            }
+            if cyclic.contains(name) {
+                report.warn(name, "not inlined because it refers to itself");
+                continue;
+            }
+            inlined.push(name.clone());
+        }
+        if inlined.is_empty() {
+            return 0;
+        }
+
+        let mut bodies = Map::new();
+        if let Some(Value::Object(schemas)) = spec.components.get_mut("schemas") {
+            for name in inlined.iter() {
+                if let Some(schema) = schemas.remove(name) {
+                    bodies.insert(name.clone(), schema);
+                }
+            }
+        }
+
+        // Children are visited after their parent is replaced, so refs inside an
+        // inlined body are expanded too. Bodies can't form a cycle.
+        visit_schemas_mut(spec, &mut |_, schema| {
+            let Some(body) = ref_name(schema).and_then(|name| bodies.get(name)) else {
+                return;
+            };
+            let mut inline = body.clone();
+            if let (Value::Object(inline), Value::Object(reference)) = (&mut inline, &*schema) {
+                // Keep anything written next to the ref, like a description.
+                for (key, value) in reference.iter().filter(|(key, _)| *key != "$ref") {
+                    inline.insert(key.clone(), value.clone());
+                }
+            }
+            *schema = inline;
+        });
+
+        for name in inlined.iter() {
+            report.push(ReportKind::Inlined, name, format!("inlined into {} place(s)", sites[name]));
+        }
+        inlined.len()
+    }
+}
+
+/// Rewrites schemas imported from other specs into the forms this crate emits,
+/// so comparing them against a generated spec doesn't show spurious changes.
+///
+/// `additionalProperties: {}` allows any properties, the same as `true`, which
+/// is what [`crate::openapi::SchemaObject::object_of`] writes.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Normalize;
+
+impl Normalize {
+    /// Returns the number of schemas that were rewritten.
+    pub fn apply(&self, spec: &mut OpenApiSpec) -> usize {
+        let mut count = 0;
+        visit_schemas_mut(spec, &mut |_, schema| {
+            if let Some(additional) = schema.get_mut("additionalProperties") &&
+                additional.as_object().is_some_and(Map::is_empty)
+            {
+                *additional = Value::Bool(true);
+                count += 1;
+            }
+        });
+        count
+    }
+}
+
+/// Properties of an object schema; enums and other schemas count as empty.
+fn size(schema: &Value) -> usize {
+    schema.get("properties").and_then(Value::as_object).map_or(0, Map::len)
+}
+
+fn as_strs(ids: Option<&BTreeSet<String>>) -> BTreeSet<&str> {
+    ids.into_iter().flatten().map(String::as_str).collect()
+}
+
+fn is_int64(schema: &Value) -> bool {
+    schema.get("type").and_then(|v| v.as_str()) == Some("integer") &&
+        matches!(schema.get("format").and_then(|v| v.as_str()), Some("int64" | "uint64"))
+}
+
+fn rewrite_as_string(schema: &mut Value) {
+    let Value::Object(obj) = schema else {
+        return;
+    };
+    let format = obj.remove("format").unwrap_or(Value::Null);
+    // Numeric bounds have no meaning once the value is a string.
+    obj.remove("minimum");
+    obj.remove("maximum");
+    obj.insert("type".to_string(), Value::String("string".to_string()));
+    obj.insert(X_ORIGINAL_FORMAT.to_string(), format);
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn test_usage_annotations() {
+        let mut spec = OpenApiSpec::from_help(&crate::tests::miniature(), "1.0.0").unwrap();
+        let count = UsageAnnotations::default().apply(&mut spec);
+        assert!(count > 0);
+
+        let schemas = &spec.components["schemas"];
+        let summoner = &schemas["LolSummonerSummoner"];
+        assert_eq!(
+            summoner[X_PRODUCED_BY],
+            json!([
+                "GetLolSummonerV1CurrentSummoner",
+                "GetLolSummonerV1Summoners",
+                "GetLolSummonerV1SummonersById",
+            ])
+        );
+        assert!(summoner.get(X_CONSUMED_BY).is_none());
+
+        let change = &schemas["LolLobbyLobbyChangeGameDto"];
+        assert_eq!(change[X_CONSUMED_BY], json!(["PostLolLobbyV2Lobby"]));
+        assert_eq!(change[X_USED_BY], json!(["PostLolLobbyV2Lobby"]));
+        assert!(change.get(X_PRODUCED_BY).is_none());
+    }
+
+    #[test]
+    fn test_usage_annotations_cap() {
+        let mut spec = OpenApiSpec::from_help(&crate::tests::miniature(), "1.0.0").unwrap();
+        UsageAnnotations { cap: 1 }.apply(&mut spec);
+
+        let summoner = &spec.components["schemas"]["LolSummonerSummoner"];
+        assert_eq!(summoner[X_USED_BY], 3);
+        assert_eq!(summoner[X_PRODUCED_BY], 3);
+        let change = &spec.components["schemas"]["LolLobbyLobbyChangeGameDto"];
+        assert_eq!(change[X_USED_BY], json!(["PostLolLobbyV2Lobby"]));
+    }
+
+    fn inline_fixture() -> OpenApiSpec {
+        let ref_to = |name: &str| json!({ "$ref": format!("#/components/schemas/{name}") });
+        let spec =
+            json!({
+            "openapi": "3.0.0",
+            "info": { "title": "test", "description": "test", "version": "1.0" },
+            "components": {
+                "schemas": {
+                    "LolLobbyQueueWrapper": {
+                        "type": "object",
+                        "properties": { "queue": ref_to("LolLobbyQueueType") }
+                    },
+                    "LolLobbyQueueType": {
+                        "type": "string",
+                        "enum": ["NORMAL", "RANKED"],
+                        "x-enum-description": ["Normal game", "Ranked game"]
+                    },
+                    "LolLobbyMember": {
+                        "type": "object",
+                        "properties": { "puuid": { "type": "string" } }
+                    },
+                    "LolLobbyNode": {
+                        "type": "object",
+                        "properties": { "next": ref_to("LolLobbyNode") }
+                    }
+                }
+            },
+            "paths": {
+                "/lol-lobby/v1/queue": {
+                    "get": {
+                        "responses": {
+                            "200": {
+                                "description": "ok",
+                                "content": { "application/json": { "schema": ref_to("LolLobbyQueueWrapper") } }
+                            }
+                        }
+                    }
+                },
+                "/lol-lobby/v1/members": {
+                    "get": {
+                        "parameters": [{ "in": "query", "name": "member", "schema": ref_to("LolLobbyMember") }],
+                        "responses": {
+                            "200": {
+                                "description": "ok",
+                                "content": {
+                                    "application/json": {
+                                        "schema": { "type": "array", "items": ref_to("LolLobbyMember") }
+                                    }
+                                }
+                            }
+                        }
+                    }
+                },
+                "/lol-lobby/v1/node": {
+                    "get": {
+                        "responses": {
+                            "200": {
+                                "description": "ok",
+                                "content": { "application/json": { "schema": ref_to("LolLobbyNode") } }
+                            }
+                        }
+                    }
+                }
+            }
+        });
+        serde_json::from_value(spec).unwrap()
+    }
+
+    #[test]
+    fn test_inline_single_use() {
+        let mut spec = inline_fixture();
+        let mut report = SchemaReport::default();
+        let count = InlineSmallComponents::default().apply(&mut spec, &mut report);
+        assert_eq!(count, 2);
+
+        let schemas = spec.components["schemas"].as_object().unwrap();
+        assert!(!schemas.contains_key("LolLobbyQueueWrapper"));
+        assert!(!schemas.contains_key("LolLobbyQueueType"));
+        // The enum inside the wrapper is expanded as well, description included.
+        assert_eq!(
+            spec.paths["/lol-lobby/v1/queue"]["get"]["responses"]["200"]["content"]["application/json"]["schema"],
+            json!({
+                "type": "object",
+                "properties": {
+                    "queue": {
+                        "type": "string",
+                        "enum": ["NORMAL", "RANKED"],
+                        "x-enum-description": ["Normal game", "Ranked game"]
+                    }
+                }
+            })
+        );
+        let inlined = report
+            .of_kind(ReportKind::Inlined)
+            .map(|e| e.target.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(inlined, ["LolLobbyQueueType", "LolLobbyQueueWrapper"]);
+    }
+
+    #[test]
+    fn test_inline_keeps_shared() {
+        let mut spec = inline_fixture();
+        InlineSmallComponents::default().apply(&mut spec, &mut SchemaReport::default());
+        assert!(spec.components["schemas"].get("LolLobbyMember").is_some());
+        assert_eq!(
+            spec.paths["/lol-lobby/v1/members"]["get"]["parameters"][0]["schema"],
+            json!({ "$ref": "#/components/schemas/LolLobbyMember" })
+        );
+
+        // Allowing more uses inlines it everywhere.
+        let mut spec = inline_fixture();
+        let inline = InlineSmallComponents { max_refs: 2, ..Default::default() };
+        inline.apply(&mut spec, &mut SchemaReport::default());
+        assert!(spec.components["schemas"].get("LolLobbyMember").is_none());
+    }
+
+    #[test]
+    fn test_inline_skips_recursive() {
+        let mut spec = inline_fixture();
+        let mut report = SchemaReport::default();
+        InlineSmallComponents::default().apply(&mut spec, &mut report);
+        assert!(spec.components["schemas"].get("LolLobbyNode").is_some());
+
+        let warnings = report.of_kind(ReportKind::Warning).collect::<Vec<_>>();
+        assert_eq!(warnings.len(), 1);
+        assert_eq!(warnings[0].target, "LolLobbyNode");
+    }
+
+    fn fixture() -> OpenApiSpec {
+        let spec =
+            json!({
+            "openapi": "3.0.0",
+            "info": { "title": "test", "description": "test", "version": "1.0" },
+            "components": {
+                "schemas": {
+                    "LolSummonerSummoner": {
+                        "type": "object",
+                        "properties": {
+                            "summonerId": { "type": "integer", "format": "uint64", "minimum": 0 },
+                            "gameCount": { "type": "integer", "format": "uint64" },
+                            "level": { "type": "integer", "format": "uint32" },
+                            "friendIds": {
+                                "type": "array",
+                                "items": { "type": "integer", "format": "int64" }
+                            }
+                        }
+                    }
+                }
+            },
+            "paths": {
+                "/lol-summoner/v1/summoners/{id}": {
+                    "get": {
+                        "parameters": [
+                            {
+                                "in": "path",
+                                "name": "id",
+                                "required": true,
+                                "schema": { "type": "integer", "format": "uint64" }
+                            },
+                            {
+                                "in": "query",
+                                "name": "port",
+                                "schema": { "type": "integer", "format": "uint64" }
+                            }
+                        ]
+                    }
+                }
+            }
+        });
+        serde_json::from_value(spec).unwrap()
+    }
+
+    #[test]
+    fn test_int64_as_string() {
+        let mut spec = fixture();
+        let count = (Int64AsString {
+            allow: Vec::new(),
+            deny: vec!["gameCount".to_string(), "port".to_string()],
+        }).apply(&mut spec);
+        assert_eq!(count, 3);
+
+        let props = &spec.components["schemas"]["LolSummonerSummoner"]["properties"];
+        assert_eq!(
+            props["summonerId"],
+            json!({ "type": "string", X_ORIGINAL_FORMAT: "uint64" })
+        );
+        assert_eq!(props["friendIds"]["items"], json!({ "type": "string", X_ORIGINAL_FORMAT: "int64" }));
+        // Denylisted and 32-bit fields stay numeric.
+        assert_eq!(props["gameCount"], json!({ "type": "integer", "format": "uint64" }));
+        assert_eq!(props["level"], json!({ "type": "integer", "format": "uint32" }));
+
+        let params = &spec.paths["/lol-summoner/v1/summoners/{id}"]["get"]["parameters"];
+        assert_eq!(params[0]["schema"], json!({ "type": "string", X_ORIGINAL_FORMAT: "uint64" }));
+        assert_eq!(params[1]["schema"], json!({ "type": "integer", "format": "uint64" }));
+    }
+
+    #[test]
+    fn test_int64_as_string_allowlist() {
+        let mut spec = fixture();
+        let count = (Int64AsString {
+            allow: vec!["components.schemas.LolSummonerSummoner.properties.summonerId".to_string()],
+            deny: Vec::new(),
+        }).apply(&mut spec);
+        assert_eq!(count, 1);
+
+        let props = &spec.components["schemas"]["LolSummonerSummoner"]["properties"];
+        assert_eq!(props["summonerId"]["type"], "string");
+        assert_eq!(props["friendIds"]["items"]["type"], "integer");
+    }
+
+    #[test]
+    fn test_int64_as_string_option() {
+        use crate::{ options::SchemaOptions, tests::miniature };
+
+        let options = SchemaOptions {
+            int64_as_string: Some(Int64AsString { allow: Vec::new(), deny: vec!["xpSinceLastLevel".to_string()] }),
+            ..SchemaOptions::default()
+        };
+        let spec = OpenApiSpec::from_help_with(&miniature(), "1.0.0", &options, &mut SchemaReport::default()).unwrap();
+        let props = &spec.components["schemas"]["LolSummonerSummoner"]["properties"];
+        assert_eq!(props["accountId"], json!({ "type": "string", X_ORIGINAL_FORMAT: "uint64" }));
+        assert_eq!(props["xpSinceLastLevel"]["type"], "integer");
+
+        // Off by default.
+        let spec = OpenApiSpec::from_help(&miniature(), "1.0.0").unwrap();
+        assert_eq!(spec.components["schemas"]["LolSummonerSummoner"]["properties"]["accountId"]["type"], "integer");
+    }
+
+    #[test]
+    fn test_additional_properties_forms() {
+        use crate::openapi::{ AdditionalProperties, SchemaObject };
+
+        let forms = [
+            (json!({}), json!(true)),
+            (json!(true), json!(true)),
+            (json!(false), json!(false)),
+            (json!({ "type": "string" }), json!({ "type": "string" })),
+        ];
+        for (written, canonical) in forms {
+            let read: AdditionalProperties = serde_json::from_value(written.clone()).unwrap();
+            assert_eq!(serde_json::to_value(&read).unwrap(), canonical, "{written}");
+        }
+        let open = serde_json::to_value(SchemaObject::object_of(true)).unwrap();
+        let read: SchemaObject = serde_json::from_value(json!({ "type": "object", "additionalProperties": {} })).unwrap();
+        assert_eq!(serde_json::to_value(read).unwrap(), open);
+    }
+
+    fn imported() -> OpenApiSpec {
+        serde_json::from_value(json!({
+            "openapi": "3.0.0",
+            "info": { "title": "LCU SCHEMA", "description": "", "version": "1.0.0" },
+            "components": {
+                "schemas": {
+                    "Anything": { "type": "object", "additionalProperties": {} },
+                    "Closed": { "type": "object", "additionalProperties": false },
+                    "Nested": {
+                        "type": "object",
+                        "properties": {
+                            "settings": { "type": "object", "additionalProperties": {} },
+                            "byQueue": {
+                                "type": "object",
+                                "additionalProperties": { "type": "object", "additionalProperties": {} }
+                            }
+                        }
+                    }
+                }
+            },
+            "paths": {
+                "/lol-settings/v2/account/{category}": {
+                    "get": {
+                        "operationId": "GetLolSettingsV2Account",
+                        "responses": {
+                            "200": {
+                                "description": "Successful response",
+                                "content": {
+                                    "application/json": { "schema": { "type": "object", "additionalProperties": {} } }
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+        })).unwrap()
+    }
+
+    #[test]
+    fn test_normalize() {
+        let mut spec = imported();
+        assert_eq!(Normalize.apply(&mut spec), 4);
+
+        let schemas = &spec.components["schemas"];
+        assert_eq!(schemas["Anything"], json!({ "type": "object", "additionalProperties": true }));
+        assert_eq!(schemas["Closed"]["additionalProperties"], false);
+        assert_eq!(schemas["Nested"]["properties"]["settings"]["additionalProperties"], true);
+        assert_eq!(
+            schemas["Nested"]["properties"]["byQueue"]["additionalProperties"],
+            json!({ "type": "object", "additionalProperties": true })
+        );
+        let response = &spec.paths["/lol-settings/v2/account/{category}"]["get"]["responses"]["200"];
+        assert_eq!(response["content"]["application/json"]["schema"]["additionalProperties"], true);
+
+        // Already canonical.
+        assert_eq!(Normalize.apply(&mut spec), 0);
+    }
+
+    #[test]
+    fn test_normalize_removes_spurious_diff() {
+        use crate::patchset::{ DEFAULT_COMPACT_THRESHOLD, diff_to_patches };
+
+        // What we generate for the same schemas.
+        let mut generated = imported();
+        visit_schemas_mut(&mut generated, &mut |_, schema| {
+            if schema.get("additionalProperties") == Some(&json!({})) {
+                schema["additionalProperties"] = Value::Bool(true);
+            }
+        });
+        let generated = serde_json::to_value(&generated).unwrap();
+
+        let mut spec = imported();
+        let diff = diff_to_patches(&generated, &serde_json::to_value(&spec).unwrap(), DEFAULT_COMPACT_THRESHOLD);
+        assert_eq!(diff.patches.ops.len(), 4);
+
+        Normalize.apply(&mut spec);
+        let diff = diff_to_patches(&generated, &serde_json::to_value(&spec).unwrap(), DEFAULT_COMPACT_THRESHOLD);
+        assert_eq!(diff.patches.ops, []);
+        assert_eq!(diff.unsupported, Vec::<String>::new());
+    }
+}
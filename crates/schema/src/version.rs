@@ -0,0 +1,157 @@
+//! LCU build versions, as reported by `/system/v1/builds`.
+
+use std::{ convert::Infallible, fmt, str::FromStr };
+
+use serde::{ Deserialize, Deserializer, Serialize, Serializer };
+
+/// A dotted numeric build version such as `14.3.556.1234`.
+///
+/// Versions order by their numeric segments, so `14.10` comes after `14.9`.
+/// Parsing never fails: it stops at the first segment that isn't a number and
+/// keeps the rest as a suffix, which orders after the numbers. The original
+/// string is kept and is what [`Display`](fmt::Display) and serde use.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct LcuVersion {
+    segments: Vec<u64>,
+    suffix: String,
+    raw: String,
+}
+
+impl LcuVersion {
+    pub fn new(raw: &str) -> Self {
+        let mut segments = Vec::new();
+        let mut rest = raw;
+        loop {
+            let digits = rest.find(|c: char| !c.is_ascii_digit()).unwrap_or(rest.len());
+            let Ok(segment) = rest[..digits].parse() else {
+                break;
+            };
+            segments.push(segment);
+            rest = &rest[digits..];
+            match rest.strip_prefix('.') {
+                Some(next) if next.starts_with(|c: char| c.is_ascii_digit()) => {
+                    rest = next;
+                }
+                _ => break,
+            }
+        }
+        Self { segments, suffix: rest.to_string(), raw: raw.to_string() }
+    }
+
+    /// The numeric segments, in order.
+    pub fn segments(&self) -> &[u64] {
+        &self.segments
+    }
+
+    pub fn major(&self) -> Option<u64> {
+        self.segments.first().copied()
+    }
+
+    pub fn minor(&self) -> Option<u64> {
+        self.segments.get(1).copied()
+    }
+
+    /// The major.minor release, e.g. `14.3` for `14.3.556.1234`.
+    pub fn release(&self) -> String {
+        match (self.major(), self.minor()) {
+            (Some(major), Some(minor)) => format!("{major}.{minor}"),
+            _ => self.raw.clone(),
+        }
+    }
+
+    /// Whether both versions are builds of the same major.minor release.
+    pub fn compatible_with(&self, other: &Self) -> bool {
+        self.major().is_some() &&
+            self.minor().is_some() &&
+            self.major() == other.major() &&
+            self.minor() == other.minor()
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.raw
+    }
+}
+
+impl FromStr for LcuVersion {
+    type Err = Infallible;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(Self::new(s))
+    }
+}
+
+impl From<&str> for LcuVersion {
+    fn from(s: &str) -> Self {
+        Self::new(s)
+    }
+}
+
+impl fmt::Display for LcuVersion {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.raw)
+    }
+}
+
+impl Serialize for LcuVersion {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error> where S: Serializer {
+        serializer.serialize_str(&self.raw)
+    }
+}
+
+impl<'de> Deserialize<'de> for LcuVersion {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error> where D: Deserializer<'de> {
+        Ok(Self::new(&String::deserialize(deserializer)?))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse() {
+        assert_eq!(LcuVersion::new("14.3.556.1234").segments(), [14, 3, 556, 1234]);
+        // Extra segments are kept.
+        assert_eq!(LcuVersion::new("1.2.3.4.5").segments(), [1, 2, 3, 4, 5]);
+
+        let beta = LcuVersion::new("14.3.556-beta.2");
+        assert_eq!(beta.segments(), [14, 3, 556]);
+        assert_eq!(beta.suffix, "-beta.2");
+        let odd = LcuVersion::new("14.x");
+        assert_eq!(odd.segments(), [14]);
+        assert_eq!(odd.suffix, ".x");
+        assert_eq!(LcuVersion::new("").segments(), [] as [u64; 0]);
+        assert_eq!(LcuVersion::new("main").major(), None);
+    }
+
+    #[test]
+    fn test_ordering() {
+        let v = |s: &str| LcuVersion::new(s);
+        assert!(v("14.10.1.1") > v("14.9.600.1"));
+        assert!(v("15.1.1.1") > v("14.24.9.9"));
+        assert!(v("14.3.556.1234") < v("14.3.557.1"));
+        assert!(v("14.3.556") < v("14.3.556.1"));
+        assert!(v("14.3.556-beta") > v("14.3.556"));
+
+        let mut versions = ["14.10", "14.2", "14.9", "13.24"].map(v);
+        versions.sort();
+        assert_eq!(versions.map(|v| v.to_string()), ["13.24", "14.2", "14.9", "14.10"]);
+    }
+
+    #[test]
+    fn test_compatible_with() {
+        let v = |s: &str| LcuVersion::new(s);
+        assert!(v("14.3.556.1234").compatible_with(&v("14.3.600.1")));
+        assert!(!v("14.3.556.1234").compatible_with(&v("14.4.556.1234")));
+        assert!(!v("14").compatible_with(&v("14")));
+        assert_eq!(v("14.3.556.1234").release(), "14.3");
+    }
+
+    #[test]
+    fn test_serde_keeps_original() {
+        for raw in ["14.3.556.1234", "014.03", "14.3.556-beta.2", "weird"] {
+            let version: LcuVersion = serde_json::from_str(&format!("\"{raw}\"")).unwrap();
+            assert_eq!(serde_json::to_string(&version).unwrap(), format!("\"{raw}\""));
+        }
+    }
+}
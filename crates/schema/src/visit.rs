@@ -0,0 +1,121 @@
+use serde_json::Value;
+
+use crate::openapi::{ OpenApiSpec, X_EVENTS };
+
+/// Where a visited schema lives in the spec.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SchemaContext<'a> {
+    /// Dot path of the schema from the spec root (see [`crate::patch::DotPathStr`]),
+    /// e.g. `components.schemas.LolSummonerSummoner.properties.summonerId`.
+    pub path: String,
+    /// Name of the property or parameter that owns the schema, if any.
+    /// Array items and map values inherit the name of their owner.
+    pub name: Option<&'a str>,
+}
+
+/// Visit every schema object reachable from the spec, parents before children.
+///
+/// This covers component schemas, operation parameters, request bodies, responses,
+/// and event payloads.
+/// Nested schemas are reached through `properties`, `items`, `additionalProperties`,
+/// and the `allOf`/`oneOf`/`anyOf` combinators.
+pub fn visit_schemas_mut<F>(spec: &mut OpenApiSpec, f: &mut F)
+    where F: FnMut(&SchemaContext, &mut Value)
+{
+    if let Some(Value::Object(schemas)) = spec.components.get_mut("schemas") {
+        for (name, schema) in schemas.iter_mut() {
+            let path = format!("components.schemas.{name}");
+            visit_schema_mut(schema, path, None, f);
+        }
+    }
+
+    for (route, path_item) in spec.paths.iter_mut() {
+        let Value::Object(path_item) = path_item else {
+            continue;
+        };
+        for (method, operation) in path_item.iter_mut() {
+            let base = format!("paths.{route}.{method}");
+            visit_operation_mut(operation, &base, f);
+        }
+    }
+
+    for (name, event) in spec.events.iter_mut() {
+        if let Some(schema) = event.get_mut("schema") {
+            visit_schema_mut(schema, format!("{X_EVENTS}.{name}.schema"), None, f);
+        }
+    }
+}
+
+/// Visit the schemas of one operation's parameters, request body, and responses.
+/// `base` is the operation's dot path, e.g. `paths./lol-lobby/v2/lobby.get`.
+pub fn visit_operation_mut<F>(operation: &mut Value, base: &str, f: &mut F)
+    where F: FnMut(&SchemaContext, &mut Value)
+{
+    if let Some(Value::Array(params)) = operation.get_mut("parameters") {
+        for (i, param) in params.iter_mut().enumerate() {
+            let name = param
+                .get("name")
+                .and_then(|v| v.as_str())
+                .map(str::to_string);
+            if let Some(schema) = param.get_mut("schema") {
+                let path = format!("{base}.parameters.{i}.schema");
+                visit_schema_mut(schema, path, name.as_deref(), f);
+            }
+        }
+    }
+
+    if let Some(content) = operation.get_mut("requestBody").and_then(|b| b.get_mut("content")) {
+        visit_content_mut(content, &format!("{base}.requestBody.content"), f);
+    }
+
+    if let Some(Value::Object(responses)) = operation.get_mut("responses") {
+        for (status, response) in responses.iter_mut() {
+            if let Some(content) = response.get_mut("content") {
+                visit_content_mut(content, &format!("{base}.responses.{status}.content"), f);
+            }
+        }
+    }
+}
+
+fn visit_content_mut<F>(content: &mut Value, base: &str, f: &mut F)
+    where F: FnMut(&SchemaContext, &mut Value)
+{
+    let Value::Object(content) = content else {
+        return;
+    };
+    for (media_type, media) in content.iter_mut() {
+        if let Some(schema) = media.get_mut("schema") {
+            visit_schema_mut(schema, format!("{base}.{media_type}.schema"), None, f);
+        }
+    }
+}
+
+/// Visit a single schema and everything nested inside it.
+pub fn visit_schema_mut<F>(schema: &mut Value, path: String, name: Option<&str>, f: &mut F)
+    where F: FnMut(&SchemaContext, &mut Value)
+{
+    if !schema.is_object() {
+        return;
+    }
+
+    f(&(SchemaContext { path: path.clone(), name }), schema);
+
+    if let Some(Value::Object(properties)) = schema.get_mut("properties") {
+        for (prop, sub) in properties.iter_mut() {
+            visit_schema_mut(sub, format!("{path}.properties.{prop}"), Some(prop), f);
+        }
+    }
+    if let Some(items) = schema.get_mut("items") {
+        visit_schema_mut(items, format!("{path}.items"), name, f);
+    }
+    if let Some(additional) = schema.get_mut("additionalProperties") {
+        visit_schema_mut(additional, format!("{path}.additionalProperties"), name, f);
+    }
+    for key in ["allOf", "oneOf", "anyOf"] {
+        if let Some(Value::Array(subs)) = schema.get_mut(key) {
+            for (i, sub) in subs.iter_mut().enumerate() {
+                visit_schema_mut(sub, format!("{path}.{key}.{i}"), name, f);
+            }
+        }
+    }
+}
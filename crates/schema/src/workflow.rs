@@ -0,0 +1,213 @@
+//! Workflow views: operations grouped by what users do rather than by plugin.
+
+use std::{ collections::{ BTreeMap, BTreeSet }, fs, path::Path };
+
+use serde::{ Deserialize, Serialize };
+use serde_json::Value;
+
+use crate::{ error::Error, openapi::{ OpenApiSpec, Tag }, report::SchemaReport, rules::prefix_match, tags::TagNames };
+
+/// Prefix of the operation tags naming a workflow, e.g. `Workflow: Champ select`.
+pub const WORKFLOW_TAG_PREFIX: &str = "Workflow: ";
+
+/// Top-level extension key indexing each workflow's operationIds.
+pub const X_WORKFLOWS: &str = "x-workflows";
+
+/// Which operations belong to a workflow. An operation matching any glob or
+/// prefix belongs.
+#[derive(Deserialize, Serialize, Debug, Clone, Default, PartialEq, Eq)]
+pub struct WorkflowMatch {
+    /// operationId globs, where `*` matches any run of characters and `?` one.
+    #[serde(default)]
+    pub operations: Vec<String>,
+    /// Path prefixes, matched whole segments at a time.
+    #[serde(default)]
+    pub paths: Vec<String>,
+}
+
+/// A mapping file of workflow names to their [`WorkflowMatch`].
+#[derive(Deserialize, Serialize, Debug, Clone, Default, PartialEq, Eq)]
+#[serde(transparent)]
+pub struct Workflows {
+    pub workflows: BTreeMap<String, WorkflowMatch>,
+}
+
+impl Workflows {
+    /// The bundled mapping for a few common workflows.
+    pub fn starter() -> Self {
+        serde_json::from_str(include_str!("../workflows.json")).expect("starter workflows parse")
+    }
+
+    pub fn load(path: &Path) -> Result<Self, Error> {
+        Ok(serde_json::from_str(&fs::read_to_string(path)?)?)
+    }
+}
+
+/// Whether `text` matches `pattern`, where `*` matches any run of characters
+/// and `?` exactly one.
+pub fn glob_match(pattern: &str, text: &str) -> bool {
+    let pattern = pattern.chars().collect::<Vec<char>>();
+    let text = text.chars().collect::<Vec<char>>();
+    let (mut p, mut t) = (0, 0);
+    // Where the last `*` was, and how much text it has taken so far.
+    let mut star: Option<(usize, usize)> = None;
+    while t < text.len() {
+        match pattern.get(p) {
+            Some('*') => {
+                star = Some((p, t));
+                p += 1;
+            }
+            Some(&c) if c == '?' || c == text[t] => {
+                p += 1;
+                t += 1;
+            }
+            _ => {
+                let Some((star_p, star_t)) = star else {
+                    return false;
+                };
+                p = star_p + 1;
+                t = star_t + 1;
+                star = Some((star_p, star_t + 1));
+            }
+        }
+    }
+    pattern[p..].iter().all(|&c| c == '*')
+}
+
+/// Tags operations with their workflows and writes the [`X_WORKFLOWS`] index.
+/// The workflow tags are added to the spec's tags with their display names.
+///
+/// Operations in no workflow are left alone. Globs and prefixes that match
+/// nothing are reported as warnings, since they usually mean a plugin was renamed.
+#[derive(Debug, Clone)]
+pub struct WorkflowTags<'a> {
+    pub workflows: &'a Workflows,
+}
+
+impl WorkflowTags<'_> {
+    /// Returns the number of tags added.
+    pub fn apply(&self, spec: &mut OpenApiSpec, report: &mut SchemaReport) -> usize {
+        let mut index = BTreeMap::<String, Vec<String>>::new();
+        let mut used = BTreeSet::<(&str, &str)>::new();
+        let mut count = 0;
+
+        for (path, item) in spec.paths.iter_mut() {
+            let Some(item) = item.as_object_mut() else {
+                continue;
+            };
+            for operation in item.values_mut().filter_map(Value::as_object_mut) {
+                let Some(id) = operation.get("operationId").and_then(Value::as_str).map(str::to_string) else {
+                    continue;
+                };
+                for (name, matcher) in self.workflows.workflows.iter() {
+                    let globs = matcher.operations.iter().filter(|glob| glob_match(glob, &id));
+                    let prefixes = matcher.paths.iter().filter(|prefix| prefix_match(prefix, path));
+                    let matched = globs.chain(prefixes).collect::<Vec<&String>>();
+                    if matched.is_empty() {
+                        continue;
+                    }
+                    used.extend(matched.into_iter().map(|pattern| (name.as_str(), pattern.as_str())));
+                    let tags = operation.entry("tags").or_insert_with(|| Value::Array(Vec::new()));
+                    if let Value::Array(tags) = tags {
+                        tags.push(Value::from(format!("{WORKFLOW_TAG_PREFIX}{name}")));
+                        count += 1;
+                    }
+                    index.entry(name.clone()).or_default().push(id.clone());
+                }
+            }
+        }
+
+        for (name, matcher) in self.workflows.workflows.iter() {
+            for pattern in matcher.operations.iter().chain(matcher.paths.iter()) {
+                if !used.contains(&(name.as_str(), pattern.as_str())) {
+                    report.warn(format!("{WORKFLOW_TAG_PREFIX}{name}"), format!("`{pattern}` matches no operation"));
+                }
+            }
+        }
+
+        let names = TagNames::starter();
+        for (name, ids) in index.iter_mut() {
+            ids.sort();
+            let tag = format!("{WORKFLOW_TAG_PREFIX}{name}");
+            if !spec.tags.iter().any(|existing| existing.name == tag) {
+                spec.tags.push(Tag { display_name: Some(names.display_name(&tag, false)), name: tag });
+            }
+        }
+        spec.workflows = index;
+        count
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{ options::SchemaOptions, tests::miniature };
+
+    #[test]
+    fn test_glob_match() {
+        let cases = [
+            ("*Lobby*", "PostLolLobbyV2Lobby", true),
+            ("Get*", "GetLolSummonerV1CurrentSummoner", true),
+            ("Get*", "PostLolLobbyV2Lobby", false),
+            ("*Summoner", "GetLolSummonerV1CurrentSummoner", true),
+            ("*Summoner", "GetLolSummonerV1SummonersById", false),
+            ("Get?olSummoner*", "GetLolSummonerV1SummonersById", true),
+            ("*V?*Lobby", "DeleteLolLobbyV2Lobby", true),
+            ("Help", "Help", true),
+            ("Help", "Helps", false),
+            ("*", "", true),
+        ];
+        for (pattern, text, expected) in cases {
+            assert_eq!(glob_match(pattern, text), expected, "{pattern} {text}");
+        }
+        assert!(prefix_match("/lol-lobby", "/lol-lobby/v2/lobby"));
+        assert!(!prefix_match("/lol-lobby", "/lol-lobby-team-builder/v1/lobby"));
+    }
+
+    fn workflows() -> Workflows {
+        serde_json::from_value(
+            serde_json::json!({
+                "Lobby": { "operations": ["*Lobby*"] },
+                "Profile": { "paths": ["/lol-summoner/v1/summoners"], "operations": ["*ChampSelect*"] }
+            })
+        ).unwrap()
+    }
+
+    #[test]
+    fn test_workflow_tags() {
+        let workflows = workflows();
+        let options = SchemaOptions { workflows: Some(workflows), ..Default::default() };
+        let mut report = SchemaReport::default();
+        let spec = OpenApiSpec::from_help_with(&miniature(), "1.0.0", &options, &mut report).unwrap();
+
+        let tags = &spec.paths["/lol-lobby/v2/lobby"]["post"]["tags"];
+        assert!(tags.as_array().unwrap().contains(&Value::from("Workflow: Lobby")));
+        let tags = &spec.paths["/lol-summoner/v1/current-summoner"]["get"]["tags"];
+        assert!(!tags.as_array().unwrap().iter().any(|tag| tag.as_str().unwrap().starts_with(WORKFLOW_TAG_PREFIX)));
+
+        assert_eq!(spec.workflows["Lobby"], ["DeleteLolLobbyV2Lobby", "PostLolLobbyV2Lobby"]);
+        assert_eq!(spec.workflows["Profile"], ["GetLolSummonerV1Summoners", "GetLolSummonerV1SummonersById"]);
+        let json = serde_json::to_value(&spec).unwrap();
+        assert_eq!(json[X_WORKFLOWS]["Lobby"][0], "DeleteLolLobbyV2Lobby");
+        let lobby = spec.tags.iter().find(|tag| tag.name == "Workflow: Lobby").unwrap();
+        assert_eq!(lobby.display_name.as_deref(), Some("Lobby"));
+
+        // The champ select glob matches nothing in the miniature.
+        let warnings = report.entries
+            .iter()
+            .filter(|entry| entry.target.starts_with(WORKFLOW_TAG_PREFIX))
+            .map(|entry| entry.detail.as_str())
+            .collect::<Vec<&str>>();
+        assert_eq!(warnings, ["`*ChampSelect*` matches no operation"]);
+
+        let plain = OpenApiSpec::from_help(&miniature(), "1.0.0").unwrap();
+        assert!(serde_json::to_value(&plain).unwrap().get(X_WORKFLOWS).is_none());
+    }
+
+    #[test]
+    fn test_starter() {
+        let starter = Workflows::starter();
+        assert!(starter.workflows.len() >= 3);
+        assert!(starter.workflows.values().all(|w| !w.operations.is_empty() || !w.paths.is_empty()));
+    }
+}
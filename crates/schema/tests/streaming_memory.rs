@@ -0,0 +1,79 @@
+//! Peak memory of [`OpenApiSpec::write_streaming`] against building the whole
+//! document in memory.
+//!
+//! Counting allocations needs a global allocator, so this lives in its own
+//! test binary instead of taxing every unit test in the library.
+
+use std::{ alloc::{ GlobalAlloc, Layout, System }, cell::Cell, io };
+
+use poro_schema::{ help::ExtendedHelp, openapi::OpenApiSpec, style::SerializationStyle };
+use poroshell_test_utils::fixtures::MINIATURE_HELP;
+use serde_json::json;
+
+/// Counts the bytes each thread has allocated, so tests running alongside
+/// don't skew each other's numbers.
+struct CountingAllocator;
+
+thread_local! {
+    static ALLOCATED: Cell<usize> = const { Cell::new(0) };
+    static PEAK: Cell<usize> = const { Cell::new(0) };
+}
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let _ = ALLOCATED.try_with(|allocated| {
+            allocated.set(allocated.get() + layout.size());
+            let _ = PEAK.try_with(|peak| peak.set(peak.get().max(allocated.get())));
+        });
+        unsafe { System.alloc(layout) }
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        let _ = ALLOCATED.try_with(|allocated| allocated.set(allocated.get().saturating_sub(layout.size())));
+        unsafe { System.dealloc(ptr, layout) }
+    }
+}
+
+#[global_allocator]
+static ALLOCATOR: CountingAllocator = CountingAllocator;
+
+/// Most bytes `f` held allocated at once on this thread, beyond what was
+/// allocated before it ran.
+fn peak_allocated<T>(f: impl FnOnce() -> T) -> usize {
+    let before = ALLOCATED.with(Cell::get);
+    PEAK.with(|peak| peak.set(before));
+    let result = f();
+    let peak = PEAK.with(Cell::get);
+    drop(result);
+    peak - before
+}
+
+/// The miniature, with `copies` more path items, each with a long
+/// description, for a document of a few megabytes.
+fn inflated(copies: usize) -> OpenApiSpec {
+    let help: ExtendedHelp = MINIATURE_HELP.parse().unwrap();
+    let mut spec = OpenApiSpec::from_help(&help, "1.0.0").unwrap();
+    let template = spec.paths["/lol-summoner/v1/summoners/{id}"].clone();
+    for i in 0..copies {
+        let mut item = template.clone();
+        item["get"]["operationId"] = json!(format!("GetCopy{i}"));
+        item["get"]["description"] = json!("A long description of a copied operation. ".repeat(20));
+        spec.paths.insert(format!("/lol-copy/v1/copies/{i}/{{id}}"), item);
+    }
+    spec
+}
+
+#[test]
+fn test_write_streaming_bounded() {
+    let spec = inflated(2000);
+    for style in SerializationStyle::ALL {
+        let length = spec.to_string_styled(style).unwrap().len();
+        assert!(length > 2_000_000, "{length}");
+
+        let in_memory = peak_allocated(|| spec.to_string_styled(style).unwrap());
+        let streaming = peak_allocated(|| spec.write_streaming(io::sink(), style).unwrap());
+        assert!(in_memory >= length, "{style:?}: {in_memory} bytes in memory for {length}");
+        // The buffer, and for hasagi's order the sorted keys of one map at a time.
+        assert!(streaming < length / 20, "{style:?}: {streaming} bytes streaming {length}");
+    }
+}
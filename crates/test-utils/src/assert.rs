@@ -0,0 +1,144 @@
+//! Assertions on specs as JSON, with messages that point at the problem.
+
+use serde_json::Value;
+
+/// Methods an OpenAPI path item can have operations for.
+const METHODS: [&str; 8] = [
+    "get", "put", "post", "delete", "options", "head", "patch", "trace",
+];
+
+/// Panics unless `spec` is shaped like an OpenAPI 3 document: a version, an
+/// info object, operations with responses, and every local `$ref` resolving.
+pub fn assert_valid_openapi(spec: &Value) {
+    let version = spec["openapi"].as_str().unwrap_or_default();
+    assert!(
+        version.starts_with("3."),
+        "openapi is {:?}, not 3.x",
+        spec["openapi"]
+    );
+    for field in ["title", "version"] {
+        assert!(spec["info"][field].is_string(), "info.{field} is missing");
+    }
+    let paths = spec["paths"].as_object().expect("paths is missing");
+    for (path, item) in paths {
+        assert!(path.starts_with('/'), "path {path} doesn't start with /");
+        for method in METHODS {
+            if let Some(operation) = item.get(method) {
+                assert!(
+                    operation["responses"].is_object(),
+                    "{method} {path} has no responses"
+                );
+            }
+        }
+    }
+    let mut refs = Vec::new();
+    collect_refs(spec, &mut refs);
+    for reference in refs {
+        if let Some(pointer) = reference.strip_prefix('#') {
+            assert!(
+                spec.pointer(pointer).is_some(),
+                "$ref {reference} doesn't resolve"
+            );
+        }
+    }
+}
+
+/// Like `assert_eq!`, but names the first JSON pointer where `actual` and
+/// `expected` differ instead of printing both documents.
+pub fn assert_spec_matches(actual: &Value, expected: &Value) {
+    if let Some((pointer, actual, expected)) = first_difference("", Some(actual), Some(expected)) {
+        panic!(
+            "specs differ at {}:\n  actual: {}\nexpected: {}",
+            if pointer.is_empty() {
+                "the root"
+            } else {
+                &pointer
+            },
+            describe(actual),
+            describe(expected),
+        );
+    }
+}
+
+fn collect_refs<'a>(value: &'a Value, refs: &mut Vec<&'a str>) {
+    match value {
+        Value::Object(map) => {
+            if let Some(Value::String(reference)) = map.get("$ref") {
+                refs.push(reference);
+            }
+            map.values().for_each(|value| collect_refs(value, refs));
+        }
+        Value::Array(items) => items.iter().for_each(|value| collect_refs(value, refs)),
+        _ => (),
+    }
+}
+
+fn first_difference<'a>(
+    pointer: &str,
+    actual: Option<&'a Value>,
+    expected: Option<&'a Value>,
+) -> Option<(String, Option<&'a Value>, Option<&'a Value>)> {
+    let child = |key: &str| format!("{pointer}/{}", key.replace('~', "~0").replace('/', "~1"));
+    match (actual, expected) {
+        (Some(Value::Object(a)), Some(Value::Object(e))) => {
+            let mut keys = a.keys().chain(e.keys()).collect::<Vec<&String>>();
+            keys.sort();
+            keys.dedup();
+            keys.into_iter()
+                .find_map(|key| first_difference(&child(key), a.get(key), e.get(key)))
+        }
+        (Some(Value::Array(a)), Some(Value::Array(e))) => {
+            (0..a.len().max(e.len())).find_map(|index| {
+                first_difference(&child(&index.to_string()), a.get(index), e.get(index))
+            })
+        }
+        (a, e) if a == e => None,
+        (a, e) => Some((pointer.to_string(), a, e)),
+    }
+}
+
+fn describe(value: Option<&Value>) -> String {
+    value.map_or_else(|| "(missing)".to_string(), Value::to_string)
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+    use crate::fixtures::MINIATURE_SPEC;
+
+    #[test]
+    fn test_valid_openapi() {
+        assert_valid_openapi(&serde_json::from_str(MINIATURE_SPEC).unwrap());
+    }
+
+    #[test]
+    #[should_panic(expected = "$ref #/components/schemas/Gone doesn't resolve")]
+    fn test_dangling_ref() {
+        assert_valid_openapi(&json!({
+            "openapi": "3.0.0",
+            "info": { "title": "t", "version": "1" },
+            "paths": { "/a": { "get": { "responses": {
+                "200": { "$ref": "#/components/schemas/Gone" }
+            } } } },
+        }));
+    }
+
+    #[test]
+    fn test_spec_matches() {
+        let spec = json!({ "paths": { "/a/{id}": { "get": { "tags": ["a", "b"] } } } });
+        assert_spec_matches(&spec, &spec.clone());
+
+        let mut changed = spec.clone();
+        changed["paths"]["/a/{id}"]["get"]["tags"][1] = json!("c");
+        assert_eq!(
+            first_difference("", Some(&changed), Some(&spec)),
+            Some((
+                "/paths/~1a~1{id}/get/tags/1".to_string(),
+                Some(&json!("c")),
+                Some(&json!("b"))
+            ))
+        );
+    }
+}
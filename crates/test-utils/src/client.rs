@@ -0,0 +1,154 @@
+//! A stand-in for the client's help endpoints, and the help it answers with.
+
+use std::collections::HashMap;
+
+use serde_json::{Map, Value, json};
+
+use crate::fixtures::{CONTRACT_HELP, MINIATURE_HELP};
+
+/// Sections of extended help, as in its JSON.
+const SECTIONS: [&str; 3] = ["types", "endpoints", "events"];
+
+/// Extended help as JSON, trimmed or extended from a fixture.
+#[derive(Debug, Clone, PartialEq)]
+pub struct HelpBuilder {
+    help: Value,
+}
+
+impl HelpBuilder {
+    pub fn new(json: &str) -> Self {
+        Self {
+            help: serde_json::from_str(json).unwrap(),
+        }
+    }
+
+    pub fn miniature() -> Self {
+        Self::new(MINIATURE_HELP)
+    }
+
+    pub fn contract() -> Self {
+        Self::new(CONTRACT_HELP)
+    }
+
+    /// Leave out the types, endpoints and events whose name matches.
+    #[must_use]
+    pub fn without(mut self, matches: impl Fn(&str) -> bool) -> Self {
+        for section in SECTIONS {
+            if let Some(entries) = self.help[section].as_array_mut() {
+                entries.retain(|entry| !entry["name"].as_str().is_some_and(&matches));
+            }
+        }
+        self
+    }
+
+    /// Add an entry to `section`, one of `types`, `endpoints` or `events`.
+    #[must_use]
+    pub fn with(mut self, section: &str, entry: Value) -> Self {
+        assert!(SECTIONS.contains(&section), "no {section} in extended help");
+        self.help[section].as_array_mut().unwrap().push(entry);
+        self
+    }
+
+    pub fn json(&self) -> &Value {
+        &self.help
+    }
+
+    #[cfg(feature = "schema")]
+    pub fn build(&self) -> poro_schema::help::ExtendedHelp {
+        self.help.to_string().parse().unwrap()
+    }
+
+    /// The spec converted from the help.
+    #[cfg(feature = "schema")]
+    pub fn spec(&self, version: &str) -> poro_schema::openapi::OpenApiSpec {
+        poro_schema::openapi::OpenApiSpec::from_help(&self.build(), version).unwrap()
+    }
+}
+
+/// Answers help requests like a client with `help` loaded.
+#[derive(Debug, Clone)]
+pub struct MockPoroClient {
+    listing: Value,
+    full: HashMap<String, Value>,
+}
+
+impl MockPoroClient {
+    pub fn new(help: &Value) -> Self {
+        let mut full = HashMap::new();
+        for section in SECTIONS {
+            for entry in help[section].as_array().into_iter().flatten() {
+                full.insert(name(entry).to_string(), json!([entry]));
+            }
+        }
+        let names = |section: &str| {
+            help[section]
+                .as_array()
+                .into_iter()
+                .flatten()
+                .map(|entry| (name(entry).to_string(), json!("")))
+                .collect::<Map<String, Value>>()
+        };
+        let listing = json!({
+            "types": names("types"),
+            "functions": names("endpoints"),
+            "events": names("events"),
+        });
+        Self { listing, full }
+    }
+
+    /// The answer to a POST of `endpoint`, e.g. `/help?target=GetLolLobbyV2Lobby`.
+    pub fn respond(&self, endpoint: &str) -> Value {
+        if endpoint == "/help" {
+            return self.listing.clone();
+        }
+        let target = endpoint
+            .trim_start_matches("/help?target=")
+            .split('&')
+            .next()
+            .unwrap();
+        if endpoint.ends_with("format=Console") {
+            // Console help leaves out functions without a URL.
+            let endpoint = &self.full[target][0];
+            if endpoint["path"].is_null() {
+                return json!({});
+            }
+            return json!({ target: { "url": endpoint["path"], "httpMethod": endpoint["method"] } });
+        }
+        self.full[target].clone()
+    }
+
+    /// [`MockPoroClient::respond`], in the shape a client's `post` has.
+    pub async fn post<E>(&self, endpoint: String) -> Result<Value, E> {
+        Ok(self.respond(&endpoint))
+    }
+}
+
+fn name(entry: &Value) -> &str {
+    entry["name"].as_str().unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_mock_answers_from_help() {
+        let help = HelpBuilder::miniature().without(|name| name.contains("LolLobby"));
+        let client = MockPoroClient::new(help.json());
+        let listing = client.respond("/help");
+        assert!(
+            listing["functions"]
+                .get("GetLolSummonerV1CurrentSummoner")
+                .is_some()
+        );
+        assert!(listing["functions"].get("PostLolLobbyV2Lobby").is_none());
+
+        let summoner = client.respond("/help?target=GetLolSummonerV1CurrentSummoner");
+        assert_eq!(summoner[0]["name"], "GetLolSummonerV1CurrentSummoner");
+        let console = client.respond("/help?target=GetLolSummonerV1CurrentSummoner&format=Console");
+        assert_eq!(
+            console["GetLolSummonerV1CurrentSummoner"]["url"],
+            "/lol-summoner/v1/current-summoner"
+        );
+    }
+}
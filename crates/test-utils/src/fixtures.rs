@@ -0,0 +1,34 @@
+//! The fixture files under `fixtures/`.
+
+use std::path::PathBuf;
+
+/// Extended help of a client with two plugins, small enough to read through.
+pub const MINIATURE_HELP: &str = include_str!("../fixtures/miniature-extended-help.json");
+
+/// Extended help with every key of the published format, for contract tests.
+pub const CONTRACT_HELP: &str = include_str!("../fixtures/contract-extended-help.json");
+
+/// A spec fragment that adds to the miniature's spec.
+pub const FRAGMENT: &str = include_str!("../fixtures/fragment.json");
+
+/// A hand-written spec like the miniature's, for the reader.
+pub const MINIATURE_SPEC: &str = include_str!("../fixtures/miniature-openapi.json");
+
+/// Path of a file under `fixtures/`, for code that reads from disk.
+pub fn path(name: &str) -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("fixtures")
+        .join(name)
+}
+
+/// [`MINIATURE_HELP`], parsed.
+#[cfg(feature = "schema")]
+pub fn miniature_help() -> poro_schema::help::ExtendedHelp {
+    MINIATURE_HELP.parse().unwrap()
+}
+
+/// The spec converted from [`MINIATURE_HELP`].
+#[cfg(feature = "schema")]
+pub fn miniature_spec(version: &str) -> poro_schema::openapi::OpenApiSpec {
+    poro_schema::openapi::OpenApiSpec::from_help(&miniature_help(), version).unwrap()
+}
@@ -0,0 +1,14 @@
+//! Fixtures and helpers shared by the workspace's tests, so each crate
+//! doesn't keep its own copy.
+//!
+//! Everything works on JSON by default. The `schema` feature adds typed
+//! versions for crates that depend on `poro_schema`, and `mock` a stub server.
+
+pub mod assert;
+pub mod client;
+pub mod fixtures;
+#[cfg(feature = "mock")]
+pub mod stub;
+
+pub use assert::{assert_spec_matches, assert_valid_openapi};
+pub use client::{HelpBuilder, MockPoroClient};
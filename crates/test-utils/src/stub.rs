@@ -0,0 +1,15 @@
+//! A stub HTTPS server standing in for a running client.
+
+use std::net::SocketAddr;
+
+use poro_mock::{MockOptions, MockServer};
+use poro_schema::openapi::OpenApiSpec;
+
+/// Serve `spec` in the background until the runtime shuts down. Requests need
+/// basic auth with `options.password`, and the certificate is self-signed.
+pub async fn serve(spec: &OpenApiSpec, options: &MockOptions) -> SocketAddr {
+    let server = MockServer::bind(spec, options).await.unwrap();
+    let addr = server.local_addr().unwrap();
+    tokio::spawn(server.run());
+    addr
+}
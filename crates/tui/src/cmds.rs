@@ -1,13 +1,159 @@
-use data::RiotAPILibrary;
+use std::{error::Error, fmt, io, path::PathBuf};
+
+use data::{
+    Document, RiotAPILibrary,
+    dashboard::Dashboard,
+    i18n::keys,
+    integrity::{Integrity, IntegrityError},
+    libraries::SourceUsed,
+    manifest::{ManifestError, SourceChoice},
+    settings::Settings,
+};
+
+use crate::ui::i18n::t;
 
 #[derive(Debug, PartialEq, Clone)]
 
 pub enum BackgroundCmd {
-    LibrariesLoad,
+    /// Load the library list from these mirrors, in order.
+    LibrariesLoad(Vec<String>),
     LibrariesOpenLink(String),
+    /// Fetch and parse a published spec.
+    DocumentLoad(SourceChoice),
+    /// Fetch and parse a spec to mark changes against.
+    BaselineLoad(SourceChoice),
+    /// Read the sources manifest at this URL or path.
+    ManifestLoad(String),
+    SettingsSave(Settings),
+    /// Read what the last generation run wrote to this artifact directory.
+    DashboardLoad(PathBuf),
+    /// Open a local file with the default program.
+    OpenPath(PathBuf),
 }
 
-#[derive(Debug, Clone)]
+impl BackgroundCmd {
+    /// What the status bar shows while the command runs.
+    pub fn label(&self) -> String {
+        match self {
+            Self::LibrariesLoad(_) => t!(keys::ACTIVITY_LIBRARIES).to_string(),
+            Self::LibrariesOpenLink(_) => t!(keys::ACTIVITY_OPEN_LINK).to_string(),
+            Self::DocumentLoad(source) => t!(keys::ACTIVITY_DOCUMENT, source.label()),
+            Self::BaselineLoad(source) => t!(keys::ACTIVITY_BASELINE, source.label()),
+            Self::ManifestLoad(_) => t!(keys::ACTIVITY_SOURCES).to_string(),
+            Self::SettingsSave(_) => t!(keys::ACTIVITY_SETTINGS).to_string(),
+            Self::DashboardLoad(dir) => t!(keys::ACTIVITY_DASHBOARD, dir.display()),
+            Self::OpenPath(path) => t!(keys::ACTIVITY_OPEN_FILE, path.display()),
+        }
+    }
+}
+
+#[derive(Debug)]
 pub enum BackgroundCmdResult {
-    LibrariesReady(Vec<RiotAPILibrary>),
+    LibrariesReady(Vec<RiotAPILibrary>, SourceUsed),
+    DocumentReady(SourceChoice, Document, Integrity),
+    BaselineReady(SourceChoice, Document),
+    /// The sources to offer, and why the manifest's are missing if they are.
+    ManifestReady(Vec<SourceChoice>, Option<ManifestError>),
+    DashboardReady(Dashboard),
+    /// Settings as saved, after taking in another instance's changes.
+    SettingsMerged(Settings),
+    Failed(BackgroundError),
+    /// A command that doesn't return anything finished, successfully or not,
+    /// by its [`BackgroundCmd::label`].
+    Finished(String),
+}
+
+/// Why a background command failed. [`fmt::Display`] has the details for the
+/// log, [`BackgroundError::user_message`] what the error banner shows.
+#[derive(Debug)]
+pub enum BackgroundError {
+    Network {
+        url: String,
+        source: reqwest::Error,
+    },
+    /// JSON that couldn't be read or written.
+    Parse {
+        what: String,
+        source: serde_json::Error,
+    },
+    Io {
+        path: PathBuf,
+        source: io::Error,
+    },
+    /// A download that didn't match its published hash, with no verified
+    /// copy cached.
+    Integrity(IntegrityError),
+    /// A failure in another program, like the browser opening a link.
+    External(String),
+}
+
+impl BackgroundError {
+    pub fn user_message(&self) -> String {
+        match self {
+            Self::Network { url, .. } => t!(keys::ERROR_NETWORK, url),
+            Self::Parse { what, .. } => t!(keys::ERROR_PARSE, what),
+            Self::Io { path, source } if source.kind() == io::ErrorKind::PermissionDenied => {
+                t!(keys::ERROR_PERMISSION, path.display())
+            }
+            Self::Io { path, .. } => t!(keys::ERROR_IO, path.display()),
+            Self::Integrity(err) => t!(keys::ERROR_INTEGRITY, err.url),
+            Self::External(message) => message.clone(),
+        }
+    }
+}
+
+impl fmt::Display for BackgroundError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Network { url, source } => write!(f, "request to {url} failed: {source}"),
+            Self::Parse { what, source } => write!(f, "bad JSON in {what}: {source}"),
+            Self::Io { path, source } => write!(f, "{}: {source}", path.display()),
+            Self::Integrity(err) => write!(f, "{err}"),
+            Self::External(message) => write!(f, "{message}"),
+        }
+    }
+}
+
+impl Error for BackgroundError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            Self::Network { source, .. } => Some(source),
+            Self::Parse { source, .. } => Some(source),
+            Self::Io { source, .. } => Some(source),
+            Self::Integrity(err) => Some(err),
+            Self::External(_) => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use data::integrity::IntegrityError;
+
+    use super::*;
+
+    #[test]
+    fn test_user_message() {
+        let denied = BackgroundError::Io {
+            path: PathBuf::from("settings.json"),
+            source: io::Error::from(io::ErrorKind::PermissionDenied),
+        };
+        assert_eq!(
+            denied.user_message(),
+            "Permission denied for settings.json."
+        );
+
+        let integrity = BackgroundError::Integrity(IntegrityError {
+            url: "https://example.com/openapi.json".to_string(),
+            expected: "abc".to_string(),
+            actual: "def".to_string(),
+        });
+        assert_eq!(
+            integrity.user_message(),
+            "https://example.com/openapi.json didn't match its published hash."
+        );
+
+        let external = BackgroundError::External("No browser found".to_string());
+        assert_eq!(external.user_message(), "No browser found");
+    }
 }
@@ -4,5 +4,12 @@ pub enum Id {
     Libraries,
     Navigation,
     Welcome,
+    Dashboard,
+    EndpointsGroups,
+    EndpointsTable,
     QuitDialog,
+    SetupDialog,
+    SourcesDialog,
+    ErrorBanner,
+    StatusBar,
 }
@@ -3,9 +3,17 @@
 #![deny(warnings)]
 #![forbid(unsafe_code)]
 
-use std::error::Error;
+use std::{
+    env,
+    error::Error,
+    panic::{self, PanicHookInfo},
+    path::PathBuf,
+    process::ExitCode,
+};
 
+use data::snapshot::Snapshot;
 use tracing::debug;
+use tuirealm::ratatui;
 use ui::UI;
 
 mod cmds;
@@ -15,13 +23,122 @@ mod msgs;
 mod ui;
 
 #[tokio::main]
-async fn main() -> Result<(), Box<dyn Error>> {
+async fn main() -> Result<ExitCode, Box<dyn Error>> {
     logger::setup();
+    install_panic_hook();
+
+    let args = Args::parse();
 
     debug!("Creating UI");
     let mut ui = UI::new();
+    if let Some(path) = &args.import_state {
+        ui.import_state(&Snapshot::load(path)?);
+    }
     debug!("Running UI");
     ui.run();
 
-    Ok(())
+    if args.dump_state {
+        println!("State written to {}", ui.dump_state()?.display());
+    }
+
+    // Reported here, once the terminal is back to normal.
+    let (code, message) = exit_status(ui.error().as_deref());
+    if let Some(message) = message {
+        eprintln!("{message}");
+    }
+    Ok(code)
+}
+
+/// The exit code of a session that ended with `error`, such as a failed
+/// draw, and the message to print for it.
+fn exit_status(error: Option<&str>) -> (ExitCode, Option<String>) {
+    match error {
+        Some(err) => (ExitCode::FAILURE, Some(format!("Error: {err}"))),
+        None => (ExitCode::SUCCESS, None),
+    }
+}
+
+/// Command line flags, all for reproducing reported issues.
+#[derive(Debug, Default)]
+struct Args {
+    /// `--dump-state`: write a state snapshot on exit.
+    dump_state: bool,
+    /// `--import-state <file>`: start from a state snapshot.
+    import_state: Option<PathBuf>,
+}
+
+impl Args {
+    fn parse() -> Self {
+        let mut args = Self::default();
+        let mut raw_args = env::args().skip(1);
+        while let Some(arg) = raw_args.next() {
+            match arg.as_str() {
+                "--dump-state" => args.dump_state = true,
+                "--import-state" => args.import_state = raw_args.next().map(PathBuf::from),
+                _ => debug!(arg, "Ignoring argument"),
+            }
+        }
+        args
+    }
+}
+
+type PanicHook = Box<dyn Fn(&PanicHookInfo<'_>) + Sync + Send + 'static>;
+
+/// Restore the terminal before the panic message is printed, so a panic
+/// doesn't leave the shell in raw mode on the alternate screen.
+fn install_panic_hook() {
+    panic::set_hook(restore_then(ratatui::restore, panic::take_hook()));
+}
+
+/// A panic hook that runs `restore`, then the hook `previous` that was set
+/// before it.
+fn restore_then(restore: impl Fn() + Sync + Send + 'static, previous: PanicHook) -> PanicHook {
+    Box::new(move |info| {
+        restore();
+        previous(info);
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{Arc, Mutex};
+
+    use super::*;
+
+    #[test]
+    fn test_panic_hook_chains() {
+        let calls = Arc::new(Mutex::new(Vec::new()));
+        let restored = Arc::clone(&calls);
+        let chained = Arc::clone(&calls);
+        let previous: PanicHook = Box::new(move |info| {
+            let payload = info.payload().downcast_ref::<&str>().copied();
+            chained
+                .lock()
+                .unwrap()
+                .push(payload.unwrap_or_default().to_string());
+        });
+        panic::set_hook(restore_then(
+            move || restored.lock().unwrap().push("restore".to_string()),
+            previous,
+        ));
+
+        let result = panic::catch_unwind(|| panic!("boom"));
+        // Back to the default hook.
+        drop(panic::take_hook());
+
+        assert!(result.is_err());
+        assert_eq!(*calls.lock().unwrap(), ["restore", "boom"]);
+    }
+
+    #[test]
+    fn test_exit_status() {
+        let (code, message) = exit_status(Some("Failed to draw: broken pipe"));
+        assert_ne!(code, ExitCode::SUCCESS);
+        assert_eq!(
+            message.as_deref(),
+            Some("Error: Failed to draw: broken pipe")
+        );
+
+        assert_eq!(exit_status(None), (ExitCode::SUCCESS, None));
+    }
 }
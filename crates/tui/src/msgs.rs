@@ -1,12 +1,53 @@
 #[derive(Debug, PartialEq, Clone, Copy)]
 pub enum Msg {
     AppClose,
+    /// Left click at a column and row, for moving focus between panes.
+    Click(u16, u16),
+    /// Write a debug snapshot of the state.
+    StateDump,
+    /// Grow the navigation pane by this many percentage points, or shrink it.
+    LayoutResize(i16),
+    /// The terminal is now this many columns and rows.
+    WindowResize(u16, u16),
+    /// Advance the status bar's spinner and expire its messages.
+    StatusTick,
     LibrariesInit,
+    DocumentsInit,
     LibrariesSubmit(usize),
+    /// Copy the library's link.
+    LibrariesCopy(usize),
     LibrariesBlur,
     NavigationBlur,
+    NavigationSubmit(usize),
+    /// The navigation selection moved to this row.
+    NavigationSelect(usize),
+    /// Switch the navigation pane between plugin and workflow groups.
+    NavigationToggleGrouping,
+    /// Show only the groups with endpoints changed since the baseline, or all.
+    NavigationToggleChanged,
+    /// Show the endpoints of the group on this row.
+    EndpointsSelect(usize),
+    EndpointsFocusTable,
+    /// The endpoints selection moved by this many rows.
+    EndpointsMove(isize),
+    EndpointsFocusGroups,
     QuitDialogShow,
     QuitDialogCancel,
     QuitDialogOk,
+    QuitDialogDefer,
+    /// Pick another document to browse.
+    SourcesDialogShow,
+    SourcesDialogSubmit(usize),
+    /// Compare the browsed documents against this source.
+    SourcesDialogBaseline(usize),
+    /// Show the last run's dashboard, or leave it.
+    DashboardToggle,
+    DashboardReload,
+    DashboardOpenChangelog,
+    /// Browse the spec the last run wrote.
+    DashboardLoadSpec,
+    SetupNext,
+    SetupBack,
+    SetupSkip,
     None,
 }
@@ -1,60 +1,338 @@
-use std::sync::Arc;
+use std::{
+    path::Path,
+    sync::{self, Arc, PoisonError},
+    time::Instant,
+};
 
-use color_eyre::eyre::Result;
-use data::RiotAPILibrary;
-use tokio::sync::{
-    Mutex,
-    mpsc::{UnboundedReceiver, UnboundedSender},
+use data::{
+    Document, RiotAPILibrary,
+    coalesce::{Begin, Coalescer},
+    dashboard::{DEFAULT_TARGET, Dashboard},
+    i18n::keys,
+    integrity::{CACHE_DIR, CachedLoader, DocumentError, Integrity, Loaded},
+    libraries::{self, EMBEDDED, SourceUsed},
+    manifest::{self, SourceChoice},
+    settings::{Settings, SettingsFile},
+};
+use tokio::{
+    sync::{
+        Mutex,
+        mpsc::{UnboundedReceiver, UnboundedSender},
+    },
+    task::JoinHandle,
 };
-use tracing::{debug, error};
+use tracing::{debug, error, warn};
 
-use crate::cmds::{BackgroundCmd, BackgroundCmdResult};
+use crate::{
+    cmds::{BackgroundCmd, BackgroundCmdResult, BackgroundError},
+    ui::i18n::t,
+};
 
 use super::UI;
 
+/// Library loads keyed by their mirror list.
+pub type LibrariesLoads = Coalescer<Vec<String>, (Vec<RiotAPILibrary>, SourceUsed)>;
+
 impl UI {
-    pub fn run_background(&self) {
+    pub fn run_background(&self) -> JoinHandle<()> {
         let rx: Arc<Mutex<UnboundedReceiver<BackgroundCmd>>> = self.bg_rx.clone();
         let tx: Arc<Mutex<UnboundedSender<BackgroundCmdResult>>> = self.result_tx.clone();
+        let libraries_loads = self.libraries_loads.clone();
+        let settings_file = self.model.settings_file.clone();
         tokio::spawn(async move {
             let mut lock = rx.lock().await;
             // Tick background
             while let Some(msg) = lock.recv().await {
                 let printed_msg = format!("{msg:?}");
                 debug!(msg = printed_msg, "Received background message");
+                let label = msg.label();
+                // Library loads report through `LibrariesReady` instead.
+                let finishes = !matches!(msg, BackgroundCmd::LibrariesLoad(_));
                 let result = match msg {
-                    BackgroundCmd::LibrariesLoad => Self::load_libraries(tx.clone()).await,
-                    BackgroundCmd::LibrariesOpenLink(link) => Self::open_library_link(link),
+                    BackgroundCmd::LibrariesLoad(mirrors) => {
+                        // Run alongside the loop, so repeats arriving meanwhile
+                        // can join it instead of queueing another fetch.
+                        tokio::spawn(Self::load_libraries(
+                            tx.clone(),
+                            libraries_loads.clone(),
+                            mirrors,
+                        ));
+                        Ok(())
+                    }
+                    BackgroundCmd::LibrariesOpenLink(link) => Self::open_library_link(&link),
+                    BackgroundCmd::DocumentLoad(source) => {
+                        Self::load_document(&tx, source, false).await
+                    }
+                    BackgroundCmd::BaselineLoad(source) => {
+                        Self::load_document(&tx, source, true).await
+                    }
+                    BackgroundCmd::ManifestLoad(location) => {
+                        Self::load_manifest(&tx, &location).await;
+                        Ok(())
+                    }
+                    BackgroundCmd::SettingsSave(settings) => {
+                        Self::save_settings(&tx, &settings_file, settings).await
+                    }
+                    BackgroundCmd::DashboardLoad(dir) => {
+                        let dashboard = Dashboard::load(&dir, DEFAULT_TARGET);
+                        let lock = tx.lock().await;
+                        lock.send(BackgroundCmdResult::DashboardReady(dashboard))
+                            .ok();
+                        Ok(())
+                    }
+                    BackgroundCmd::OpenPath(path) => Self::open_path(&path),
                 };
                 if let Err(err) = result {
                     error!(
-                        error = err.root_cause(),
+                        error = err.to_string(),
                         "Failed to execute background command"
                     );
+                    let lock = tx.lock().await;
+                    lock.send(BackgroundCmdResult::Failed(err)).ok();
+                }
+                if finishes {
+                    let lock = tx.lock().await;
+                    lock.send(BackgroundCmdResult::Finished(label)).ok();
                 }
             }
-        });
+        })
     }
 
     async fn load_libraries(
         result_tx: Arc<Mutex<UnboundedSender<BackgroundCmdResult>>>,
-    ) -> Result<()> {
-        let libraries = reqwest::get("https://raw.githubusercontent.com/BlossomiShymae/poroschema/refs/heads/main/other/libraries.json")
-            .await?
-            .error_for_status()?
-            .json::<Vec<RiotAPILibrary>>()
-            .await?;
+        loads: Arc<Mutex<LibrariesLoads>>,
+        mirrors: Vec<String>,
+    ) {
+        let begin = loads.lock().await.begin(&mirrors, Instant::now());
+        let (libraries, source) = match begin {
+            Begin::Run => {
+                let loaded =
+                    libraries::load(
+                        &mirrors,
+                        EMBEDDED,
+                        |url| async move { fetch_text(&url).await },
+                    )
+                    .await;
+                for failure in loaded.1.failures() {
+                    warn!(error = failure.to_string(), "Skipped libraries mirror");
+                }
+                // A stale snapshot isn't worth reusing; the next request retries the mirrors.
+                let cached = (!loaded.1.is_stale()).then(|| loaded.clone());
+                let waiters = loads.lock().await.finish(&mirrors, cached, Instant::now());
+                debug!(waiters, "Loaded libraries");
+                loaded
+            }
+            // The running load sends the result. The UI has a single consumer,
+            // so one delivery answers every waiter.
+            Begin::Joined => return,
+            Begin::Cached(loaded) => loaded,
+        };
 
         let lock = result_tx.lock().await;
-        lock.send(BackgroundCmdResult::LibrariesReady(libraries))
+        lock.send(BackgroundCmdResult::LibrariesReady(libraries, source))
             .ok();
+    }
 
+    /// Load a document to browse, or with `baseline` one to compare against.
+    async fn load_document(
+        result_tx: &Arc<Mutex<UnboundedSender<BackgroundCmdResult>>>,
+        source: SourceChoice,
+        baseline: bool,
+    ) -> Result<(), BackgroundError> {
+        let loaded = fetch_document(&source).await?;
+        if let Integrity::Cached(err) = &loaded.integrity {
+            warn!(error = err.to_string(), "Using the cached copy");
+        }
+        let document =
+            Document::from_json(&loaded.text).map_err(|source_err| BackgroundError::Parse {
+                what: t!(keys::ERROR_SCHEMA, source.label()),
+                source: source_err,
+            })?;
+        let result = if baseline {
+            BackgroundCmdResult::BaselineReady(source, document)
+        } else {
+            BackgroundCmdResult::DocumentReady(source, document, loaded.integrity)
+        };
+        let lock = result_tx.lock().await;
+        lock.send(result).ok();
         Ok(())
     }
 
-    fn open_library_link(link: String) -> Result<()> {
-        open::that(link)?;
+    async fn load_manifest(
+        result_tx: &Arc<Mutex<UnboundedSender<BackgroundCmdResult>>>,
+        location: &str,
+    ) {
+        let (choices, error) =
+            manifest::load(location, |url| async move { fetch_text(&url).await }).await;
+        if let Some(error) = error.as_ref() {
+            warn!(error = error.to_string(), "Using the built-in sources only");
+        }
+        let lock = result_tx.lock().await;
+        lock.send(BackgroundCmdResult::ManifestReady(choices, error))
+            .ok();
+    }
+
+    fn open_library_link(link: &str) -> Result<(), BackgroundError> {
+        open::that(link)
+            .map_err(|err| BackgroundError::External(t!(keys::ERROR_OPEN_LINK, link, err)))
+    }
 
+    fn open_path(path: &Path) -> Result<(), BackgroundError> {
+        open::that(path).map_err(|err| {
+            BackgroundError::External(t!(keys::ERROR_OPEN_LINK, path.display(), err))
+        })
+    }
+
+    /// Save through the file's lock, and pass on the settings as written if
+    /// they took in changes from another instance.
+    async fn save_settings(
+        result_tx: &Arc<Mutex<UnboundedSender<BackgroundCmdResult>>>,
+        settings_file: &Arc<sync::Mutex<SettingsFile>>,
+        settings: Settings,
+    ) -> Result<(), BackgroundError> {
+        let settings_file = settings_file.clone();
+        let sent = settings.clone();
+        // Waiting on another instance's lock blocks.
+        let (path, saved) = tokio::task::spawn_blocking(move || {
+            let mut file = settings_file.lock().unwrap_or_else(PoisonError::into_inner);
+            (file.path().to_path_buf(), file.save(&settings))
+        })
+        .await
+        .map_err(|err| BackgroundError::External(err.to_string()))?;
+        let saved = saved.map_err(|source| BackgroundError::Io { path, source })?;
+        if saved != sent {
+            let lock = result_tx.lock().await;
+            lock.send(BackgroundCmdResult::SettingsMerged(saved)).ok();
+        }
         Ok(())
     }
 }
+
+/// The text of `source`, checked against its published hash where there is one.
+async fn fetch_document(source: &SourceChoice) -> Result<Loaded, BackgroundError> {
+    // Built in, so there's nothing to download or check.
+    #[cfg(feature = "demo")]
+    if let SourceChoice::Demo(_) = source {
+        return Ok(Loaded {
+            text: data::demo::SPEC.to_string(),
+            integrity: Integrity::Verified,
+        });
+    }
+    // A file written locally, like the spec of the last generation run. There's
+    // no published hash to check it against, and no point in caching it.
+    let location = source.url();
+    if !location.starts_with("http://") && !location.starts_with("https://") {
+        let text = tokio::fs::read_to_string(location)
+            .await
+            .map_err(|source| BackgroundError::Io {
+                path: location.into(),
+                source,
+            })?;
+        return Ok(Loaded {
+            text,
+            integrity: Integrity::Unverified,
+        });
+    }
+    CachedLoader::new(CACHE_DIR)
+        .load(source.url(), |url| async move { fetch_text(&url).await })
+        .await
+        .map_err(|err| match err {
+            DocumentError::Fetch(err) => err,
+            DocumentError::Integrity(err) => BackgroundError::Integrity(err),
+        })
+}
+
+async fn fetch_text(url: &str) -> Result<String, BackgroundError> {
+    let network = |source| BackgroundError::Network {
+        url: url.to_string(),
+        source,
+    };
+    reqwest::get(url)
+        .await
+        .and_then(reqwest::Response::error_for_status)
+        .map_err(network)?
+        .text()
+        .await
+        .map_err(network)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{fs, path::PathBuf};
+
+    use data::manifest::ManifestSource;
+    use tokio::sync::mpsc;
+
+    use super::*;
+
+    fn temp_path(name: &str) -> PathBuf {
+        std::env::temp_dir().join(format!(
+            "poroshell-background-{name}-{}",
+            std::process::id()
+        ))
+    }
+
+    #[tokio::test]
+    async fn test_failed_request_is_network() {
+        // Nothing listens on the discard port.
+        let url = "http://127.0.0.1:9/openapi.json";
+        let err = fetch_text(url).await.unwrap_err();
+        assert!(
+            matches!(&err, BackgroundError::Network { url: failed, .. } if failed == url),
+            "{err:?}"
+        );
+        assert_eq!(
+            err.user_message(),
+            "Couldn't reach http://127.0.0.1:9/openapi.json. Check your internet connection."
+        );
+    }
+
+    #[tokio::test]
+    async fn test_malformed_json_is_parse() {
+        let path = temp_path("malformed.json");
+        fs::write(&path, r#"{ "openapi": "#).unwrap();
+        let source = SourceChoice::Manifest(ManifestSource {
+            name: "Local".to_string(),
+            url: path.display().to_string(),
+            format: None,
+            description: String::new(),
+        });
+        let (tx, _rx) = mpsc::unbounded_channel();
+        let result = UI::load_document(&Arc::new(Mutex::new(tx)), source, false).await;
+        fs::remove_file(&path).unwrap();
+
+        let err = result.unwrap_err();
+        assert!(matches!(err, BackgroundError::Parse { .. }), "{err:?}");
+        assert_eq!(
+            err.user_message(),
+            "The Local schema isn't in the expected format."
+        );
+    }
+
+    #[tokio::test]
+    async fn test_unwritable_path_is_io() {
+        // A file where the settings' directory should be.
+        let file = temp_path("not-a-directory");
+        fs::write(&file, "").unwrap();
+        let path = file.join("settings.json");
+        let settings_file = Arc::new(sync::Mutex::new(SettingsFile::new(&path)));
+        let (tx, _rx) = mpsc::unbounded_channel();
+        let result = UI::save_settings(
+            &Arc::new(Mutex::new(tx)),
+            &settings_file,
+            Settings::default(),
+        )
+        .await;
+        fs::remove_file(&file).unwrap();
+
+        let err = result.unwrap_err();
+        assert!(
+            matches!(&err, BackgroundError::Io { path: failed, .. } if *failed == path),
+            "{err:?}"
+        );
+        assert_eq!(
+            err.user_message(),
+            format!("Couldn't access {}.", path.display())
+        );
+    }
+}
@@ -0,0 +1,39 @@
+//! The app's [`Clipboard`]: the system clipboard when built with the
+//! `system-clipboard` feature, then OSC 52 on the terminal, then a file.
+
+use std::io::{self, IsTerminal, Write};
+
+use data::clipboard::{Clipboard, SystemClipboard};
+
+pub fn clipboard() -> Clipboard {
+    let terminal = io::stdout()
+        .is_terminal()
+        .then(|| Box::new(io::stdout()) as Box<dyn Write>);
+    Clipboard::new(system(), terminal)
+}
+
+#[cfg(feature = "system-clipboard")]
+struct Arboard(arboard::Clipboard);
+
+#[cfg(feature = "system-clipboard")]
+impl SystemClipboard for Arboard {
+    fn set_text(&mut self, text: &str) -> Result<(), String> {
+        self.0.set_text(text).map_err(|err| err.to_string())
+    }
+}
+
+#[cfg(feature = "system-clipboard")]
+fn system() -> Option<Box<dyn SystemClipboard>> {
+    match arboard::Clipboard::new() {
+        Ok(clipboard) => Some(Box::new(Arboard(clipboard))),
+        Err(err) => {
+            tracing::debug!(error = err.to_string(), "No system clipboard");
+            None
+        }
+    }
+}
+
+#[cfg(not(feature = "system-clipboard"))]
+fn system() -> Option<Box<dyn SystemClipboard>> {
+    None
+}
@@ -1,4 +1,6 @@
 pub mod quit;
+pub mod setup;
+pub mod sources;
 use tui_realm_stdlib::Radio;
 use tuirealm::{
     Event, MockComponent, NoUserEvent, State, StateValue,
@@ -18,18 +20,51 @@ pub struct DialogStyle {
 #[derive(Debug, Clone, PartialEq, Copy)]
 pub enum DialogType {
     Warning,
+    /// Confirms something that can't be undone.
+    Danger,
+}
+
+/// A button and the message sent when it is submitted.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DialogButton {
+    pub label: String,
+    pub msg: Msg,
+}
+
+impl DialogButton {
+    pub fn new<T: Into<String>>(label: T, msg: Msg) -> Self {
+        Self {
+            label: label.into(),
+            msg,
+        }
+    }
 }
 
 #[derive(MockComponent)]
 pub struct Dialog {
     component: Radio,
+    buttons: Vec<DialogButton>,
+    /// Sent on Esc, whichever button is selected.
+    cancel: Msg,
 }
 
 impl Dialog {
-    pub fn new<T: Into<String>>(title: T, style: &DialogStyle) -> Self {
+    /// A dialog with `buttons` in order, `selected` focused at first.
+    pub fn new<T: Into<String>>(
+        title: T,
+        style: &DialogStyle,
+        buttons: Vec<DialogButton>,
+        selected: usize,
+        cancel: Msg,
+    ) -> Self {
         let border_color = match style.dialog_type {
             DialogType::Warning => Color::LightYellow,
+            DialogType::Danger => Color::LightRed,
         };
+        let labels = buttons
+            .iter()
+            .map(|button| button.label.as_str())
+            .collect::<Vec<&str>>();
 
         Self {
             component: Radio::default()
@@ -40,14 +75,16 @@ impl Dialog {
                 )
                 .title(title, style.title_alignment)
                 .rewind(true)
-                .choices(&["Ok", "Cancel"])
-                .value(0),
+                .choices(&labels)
+                .value(selected),
+            buttons,
+            cancel,
         }
     }
 
-    pub fn on(&mut self, ev: &Event<NoUserEvent>, on_ok: Msg, on_cancel: Msg) -> Option<Msg> {
+    pub fn on(&mut self, ev: &Event<NoUserEvent>) -> Option<Msg> {
         let cmd_result = match ev {
-            Event::Keyboard(KeyEvent { code: Key::Esc, .. }) => return Some(on_cancel),
+            Event::Keyboard(KeyEvent { code: Key::Esc, .. }) => return Some(self.cancel),
             Event::Keyboard(KeyEvent {
                 code: Key::Left, ..
             }) => self.perform(Cmd::Move(Direction::Left)),
@@ -61,10 +98,65 @@ impl Dialog {
         };
 
         match cmd_result {
-            CmdResult::Submit(State::One(StateValue::Usize(0))) => Some(on_ok),
-            CmdResult::Submit(State::One(StateValue::Usize(1))) => Some(on_cancel),
+            CmdResult::Submit(State::One(StateValue::Usize(index))) => self.button_msg(index),
             CmdResult::None => None,
             _ => Some(Msg::None),
         }
     }
+
+    fn button_msg(&self, index: usize) -> Option<Msg> {
+        self.buttons.get(index).map(|button| button.msg)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use tuirealm::event::KeyModifiers;
+
+    use super::*;
+
+    const MSGS: [Msg; 3] = [
+        Msg::QuitDialogOk,
+        Msg::QuitDialogDefer,
+        Msg::QuitDialogCancel,
+    ];
+
+    fn dialog(buttons: usize) -> Dialog {
+        let style = DialogStyle {
+            dialog_type: DialogType::Warning,
+            title_alignment: Alignment::Center,
+        };
+        let buttons = MSGS[..buttons]
+            .iter()
+            .map(|msg| DialogButton::new(format!("{msg:?}"), *msg))
+            .collect();
+        Dialog::new("Title", &style, buttons, 0, Msg::AppClose)
+    }
+
+    fn key(code: Key) -> Event<NoUserEvent> {
+        Event::Keyboard(KeyEvent::new(code, KeyModifiers::NONE))
+    }
+
+    #[test]
+    fn test_submit_each_button() {
+        for buttons in 1..=3 {
+            for (index, msg) in MSGS[..buttons].iter().enumerate() {
+                let mut dialog = dialog(buttons);
+                for _ in 0..index {
+                    dialog.on(&key(Key::Right));
+                }
+                assert_eq!(dialog.on(&key(Key::Enter)), Some(*msg), "{buttons}");
+                assert_eq!(dialog.button_msg(index), Some(*msg), "{buttons}");
+            }
+            assert_eq!(dialog(buttons).button_msg(buttons), None, "{buttons}");
+        }
+    }
+
+    #[test]
+    fn test_esc_cancels() {
+        let mut dialog = dialog(3);
+        dialog.on(&key(Key::Right));
+        assert_eq!(dialog.on(&key(Key::Esc)), Some(Msg::AppClose));
+        assert_eq!(dialog.on(&key(Key::Char('x'))), None);
+    }
 }
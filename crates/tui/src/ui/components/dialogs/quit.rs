@@ -1,3 +1,4 @@
+use data::i18n::keys;
 use tuirealm::{
     Application, Component, Frame, MockComponent, NoUserEvent, props::Alignment,
     ratatui::widgets::Clear,
@@ -6,10 +7,10 @@ use tuirealm::{
 use crate::{
     ids::Id,
     msgs::Msg,
-    ui::{model::Model, utils::draw_area_in_absolute},
+    ui::{i18n::t, model::Model, utils::draw_area_in_absolute},
 };
 
-use super::{Dialog, DialogStyle, DialogType};
+use super::{Dialog, DialogButton, DialogStyle, DialogType};
 
 #[derive(MockComponent)]
 pub struct QuitDialog {
@@ -19,11 +20,37 @@ pub struct QuitDialog {
 impl QuitDialog {
     pub fn new() -> Self {
         let component = Dialog::new(
-            " Are you sure you want to quit? ",
+            format!(" {} ", t!(keys::QUIT_CONFIRM)),
             &DialogStyle {
                 dialog_type: DialogType::Warning,
                 title_alignment: Alignment::Center,
             },
+            vec![
+                DialogButton::new(t!(keys::QUIT_OK), Msg::QuitDialogOk),
+                DialogButton::new(t!(keys::QUIT_CANCEL), Msg::QuitDialogCancel),
+            ],
+            0,
+            Msg::QuitDialogCancel,
+        );
+
+        Self { component }
+    }
+
+    /// Asked while background work is running, which quitting now would drop.
+    pub fn working() -> Self {
+        let component = Dialog::new(
+            format!(" {} ", t!(keys::QUIT_WORKING)),
+            &DialogStyle {
+                dialog_type: DialogType::Danger,
+                title_alignment: Alignment::Center,
+            },
+            vec![
+                DialogButton::new(t!(keys::QUIT_NOW), Msg::QuitDialogOk),
+                DialogButton::new(t!(keys::QUIT_WHEN_DONE), Msg::QuitDialogDefer),
+                DialogButton::new(t!(keys::QUIT_CANCEL), Msg::QuitDialogCancel),
+            ],
+            2,
+            Msg::QuitDialogCancel,
         );
 
         Self { component }
@@ -32,15 +59,19 @@ impl QuitDialog {
 
 impl Component<Msg, NoUserEvent> for QuitDialog {
     fn on(&mut self, ev: tuirealm::Event<NoUserEvent>) -> Option<Msg> {
-        self.component
-            .on(&ev, Msg::QuitDialogOk, Msg::QuitDialogCancel)
+        self.component.on(&ev)
     }
 }
 
 impl Model {
     pub fn mount_quit_dialog(&mut self) {
+        let dialog = if self.loading_libraries {
+            QuitDialog::working()
+        } else {
+            QuitDialog::new()
+        };
         self.app
-            .mount(Id::QuitDialog, Box::new(QuitDialog::new()), Vec::new())
+            .mount(Id::QuitDialog, Box::new(dialog), Vec::new())
             .ok();
         self.app.active(&Id::QuitDialog).ok();
     }
@@ -51,7 +82,7 @@ impl Model {
 
     pub fn view_quit_dialog(app: &mut Application<Id, Msg, NoUserEvent>, f: &mut Frame<'_>) {
         if app.mounted(&Id::QuitDialog) {
-            let dialog = draw_area_in_absolute(f.area(), 35, 3);
+            let dialog = draw_area_in_absolute(f.area(), 45, 3);
             f.render_widget(Clear, dialog);
             app.view(&Id::QuitDialog, f, dialog);
         }
@@ -0,0 +1,301 @@
+use std::path::Path;
+
+use data::{
+    i18n::keys,
+    settings::{SchemaSource, Settings},
+    setup::{SetupStep, SetupWizard},
+};
+use tui_realm_stdlib::{Checkbox, Input, Radio};
+use tuirealm::{
+    Application, Component, Event, Frame, MockComponent, NoUserEvent, State, StateValue,
+    command::{Cmd, CmdResult, Direction, Position},
+    event::{Key, KeyEvent, KeyModifiers},
+    props::{Alignment, BorderType, Borders, Color, InputType},
+    ratatui::widgets::Clear,
+};
+
+use crate::{
+    cmds::BackgroundCmd,
+    ids::Id,
+    msgs::Msg,
+    ui::{i18n::t, model::Model, utils::draw_area_in_absolute},
+};
+
+const STEP_COUNT: usize = 3;
+
+/// Keys shared by every step: Enter continues, Shift+Tab goes back, and
+/// Ctrl+S skips setup with the defaults.
+fn step_msg(ev: &Event<NoUserEvent>) -> Option<Msg> {
+    match ev {
+        Event::Keyboard(KeyEvent {
+            code: Key::Enter, ..
+        }) => Some(Msg::SetupNext),
+        Event::Keyboard(KeyEvent {
+            code: Key::BackTab, ..
+        }) => Some(Msg::SetupBack),
+        Event::Keyboard(KeyEvent {
+            code: Key::Char('s'),
+            modifiers,
+        }) if *modifiers == KeyModifiers::CONTROL => Some(Msg::SetupSkip),
+        _ => None,
+    }
+}
+
+fn step_borders(error: bool) -> Borders {
+    let color = if error {
+        Color::LightYellow
+    } else {
+        Color::LightCyan
+    };
+    Borders::default()
+        .color(color)
+        .modifiers(BorderType::Rounded)
+}
+
+#[derive(MockComponent)]
+pub struct SetupSources {
+    component: Checkbox,
+}
+
+impl SetupSources {
+    pub fn new(title: &str, error: bool, selected: &[SchemaSource]) -> Self {
+        let values = SchemaSource::ALL
+            .iter()
+            .enumerate()
+            .filter(|(_, source)| selected.contains(source))
+            .map(|(index, _)| index)
+            .collect::<Vec<usize>>();
+
+        Self {
+            component: Checkbox::default()
+                .borders(step_borders(error))
+                .title(title, Alignment::Center)
+                .rewind(true)
+                .choices(&SchemaSource::ALL.map(|source| source.label()))
+                .values(&values),
+        }
+    }
+}
+
+impl Component<Msg, NoUserEvent> for SetupSources {
+    fn on(&mut self, ev: Event<NoUserEvent>) -> Option<Msg> {
+        if let Some(msg) = step_msg(&ev) {
+            return Some(msg);
+        }
+        let _cmd_result = match ev {
+            Event::Keyboard(KeyEvent {
+                code: Key::Left, ..
+            }) => self.perform(Cmd::Move(Direction::Left)),
+            Event::Keyboard(KeyEvent {
+                code: Key::Right, ..
+            }) => self.perform(Cmd::Move(Direction::Right)),
+            Event::Keyboard(KeyEvent {
+                code: Key::Char(' '),
+                ..
+            }) => self.perform(Cmd::Toggle),
+            _ => CmdResult::None,
+        };
+
+        Some(Msg::None)
+    }
+}
+
+#[derive(MockComponent)]
+pub struct SetupLiveLcu {
+    component: Radio,
+}
+
+impl SetupLiveLcu {
+    pub fn new(title: &str, error: bool, live_lcu: bool) -> Self {
+        Self {
+            component: Radio::default()
+                .borders(step_borders(error))
+                .title(title, Alignment::Center)
+                .rewind(true)
+                .choices(&[t!(keys::SETUP_YES), t!(keys::SETUP_NO)])
+                .value(usize::from(!live_lcu)),
+        }
+    }
+}
+
+impl Component<Msg, NoUserEvent> for SetupLiveLcu {
+    fn on(&mut self, ev: Event<NoUserEvent>) -> Option<Msg> {
+        if let Some(msg) = step_msg(&ev) {
+            return Some(msg);
+        }
+        let _cmd_result = match ev {
+            Event::Keyboard(KeyEvent {
+                code: Key::Left, ..
+            }) => self.perform(Cmd::Move(Direction::Left)),
+            Event::Keyboard(KeyEvent {
+                code: Key::Right, ..
+            }) => self.perform(Cmd::Move(Direction::Right)),
+            _ => CmdResult::None,
+        };
+
+        Some(Msg::None)
+    }
+}
+
+#[derive(MockComponent)]
+pub struct SetupArtifactDir {
+    component: Input,
+}
+
+impl SetupArtifactDir {
+    pub fn new(title: &str, error: bool, artifact_dir: &Path) -> Self {
+        Self {
+            component: Input::default()
+                .borders(step_borders(error))
+                .title(title, Alignment::Center)
+                .input_type(InputType::Text)
+                .value(artifact_dir.to_string_lossy()),
+        }
+    }
+}
+
+impl Component<Msg, NoUserEvent> for SetupArtifactDir {
+    fn on(&mut self, ev: Event<NoUserEvent>) -> Option<Msg> {
+        if let Some(msg) = step_msg(&ev) {
+            return Some(msg);
+        }
+        let _cmd_result = match ev {
+            Event::Keyboard(KeyEvent {
+                code: Key::Left, ..
+            }) => self.perform(Cmd::Move(Direction::Left)),
+            Event::Keyboard(KeyEvent {
+                code: Key::Right, ..
+            }) => self.perform(Cmd::Move(Direction::Right)),
+            Event::Keyboard(KeyEvent {
+                code: Key::Home, ..
+            }) => self.perform(Cmd::GoTo(Position::Begin)),
+            Event::Keyboard(KeyEvent { code: Key::End, .. }) => {
+                self.perform(Cmd::GoTo(Position::End))
+            }
+            Event::Keyboard(KeyEvent {
+                code: Key::Backspace,
+                ..
+            }) => self.perform(Cmd::Delete),
+            Event::Keyboard(KeyEvent {
+                code: Key::Delete, ..
+            }) => self.perform(Cmd::Cancel),
+            Event::Keyboard(KeyEvent {
+                code: Key::Char(c),
+                modifiers,
+            }) if modifiers == KeyModifiers::NONE || modifiers == KeyModifiers::SHIFT => {
+                self.perform(Cmd::Type(c))
+            }
+            _ => CmdResult::None,
+        };
+
+        Some(Msg::None)
+    }
+}
+
+impl Model {
+    /// Mount the dialog for the wizard's current step, replacing the previous one.
+    pub fn mount_setup_dialog(&mut self, error: Option<&str>) {
+        let Some(wizard) = self.setup.as_ref() else {
+            return;
+        };
+        let settings = wizard.settings();
+        let (number, question) = match wizard.step() {
+            SetupStep::Sources => (1, t!(keys::SETUP_SOURCES)),
+            SetupStep::LiveLcu => (2, t!(keys::SETUP_LIVE_LCU)),
+            SetupStep::ArtifactDir => (3, t!(keys::SETUP_ARTIFACT_DIR)),
+            SetupStep::Done => return,
+        };
+        let title = match error {
+            Some(error) => format!(" {error} "),
+            None => format!(" {} ", t!(keys::SETUP_TITLE, number, STEP_COUNT, question)),
+        };
+        let has_error = error.is_some();
+
+        let component: Box<dyn Component<Msg, NoUserEvent>> = match wizard.step() {
+            SetupStep::Sources => Box::new(SetupSources::new(&title, has_error, &settings.sources)),
+            SetupStep::LiveLcu => Box::new(SetupLiveLcu::new(&title, has_error, settings.live_lcu)),
+            _ => Box::new(SetupArtifactDir::new(
+                &title,
+                has_error,
+                &settings.artifact_dir,
+            )),
+        };
+        self.app
+            .remount(Id::SetupDialog, component, Vec::new())
+            .ok();
+        self.app.active(&Id::SetupDialog).ok();
+    }
+
+    pub fn setup_next(&mut self) {
+        self.store_setup_answer();
+        let Some(wizard) = self.setup.as_mut() else {
+            return;
+        };
+        match wizard.advance() {
+            Ok(SetupStep::Done) => {
+                if let Some(settings) = self.setup.take().and_then(SetupWizard::finish) {
+                    self.finish_setup(settings);
+                }
+            }
+            Ok(_) => self.mount_setup_dialog(None),
+            Err(err) => self.mount_setup_dialog(Some(&err.to_string())),
+        }
+    }
+
+    pub fn setup_back(&mut self) {
+        self.store_setup_answer();
+        if let Some(wizard) = self.setup.as_mut() {
+            wizard.back();
+            self.mount_setup_dialog(None);
+        }
+    }
+
+    pub fn setup_skip(&mut self) {
+        self.setup = None;
+        self.finish_setup(Settings::default());
+    }
+
+    /// Copy the mounted step's answer into the wizard.
+    fn store_setup_answer(&mut self) {
+        let state = self.app.state(&Id::SetupDialog).unwrap_or(State::None);
+        let Some(wizard) = self.setup.as_mut() else {
+            return;
+        };
+        match (wizard.step(), state) {
+            (SetupStep::Sources, State::Vec(values)) => {
+                let sources = values
+                    .into_iter()
+                    .filter_map(|value| match value {
+                        StateValue::Usize(index) => SchemaSource::ALL.get(index).copied(),
+                        _ => None,
+                    })
+                    .collect();
+                wizard.set_sources(sources);
+            }
+            (SetupStep::Sources, _) => wizard.set_sources(Vec::new()),
+            (SetupStep::LiveLcu, State::One(StateValue::Usize(index))) => {
+                wizard.set_live_lcu(index == 0);
+            }
+            (SetupStep::ArtifactDir, State::One(StateValue::String(dir))) => {
+                wizard.set_artifact_dir(dir);
+            }
+            (SetupStep::ArtifactDir, _) => wizard.set_artifact_dir(""),
+            _ => (),
+        }
+    }
+
+    fn finish_setup(&mut self, settings: Settings) {
+        self.send(BackgroundCmd::SettingsSave(settings.clone()));
+        self.settings = Some(settings);
+        self.app.umount(&Id::SetupDialog).ok();
+        self.app.active(&Id::Libraries).ok();
+    }
+
+    pub fn view_setup_dialog(app: &mut Application<Id, Msg, NoUserEvent>, f: &mut Frame<'_>) {
+        if app.mounted(&Id::SetupDialog) {
+            let dialog = draw_area_in_absolute(f.area(), 70, 3);
+            f.render_widget(Clear, dialog);
+            app.view(&Id::SetupDialog, f, dialog);
+        }
+    }
+}
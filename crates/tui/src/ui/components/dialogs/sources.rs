@@ -0,0 +1,144 @@
+use std::fmt::Write;
+
+use data::{i18n::keys, manifest::SourceChoice};
+use tui_realm_stdlib::List;
+use tuirealm::{
+    Application, Component, Event, Frame, MockComponent, NoUserEvent, State, StateValue,
+    command::{Cmd, CmdResult, Direction},
+    event::{Key, KeyEvent},
+    props::{Alignment, BorderType, Borders, Color, TableBuilder},
+    ratatui::widgets::Clear,
+};
+
+use crate::{
+    cmds::BackgroundCmd,
+    ids::Id,
+    msgs::Msg,
+    ui::{
+        i18n::t,
+        model::Model,
+        utils::{draw_area_in_absolute, text_span},
+    },
+};
+
+/// Picks the document to browse, from the built-in sources and the manifest's.
+#[derive(MockComponent)]
+pub struct SourcesDialog {
+    component: List,
+}
+
+impl SourcesDialog {
+    pub fn new(choices: &[SourceChoice]) -> Self {
+        let mut rows = TableBuilder::default();
+        for choice in choices {
+            let mut text = choice.label().to_string();
+            if let SourceChoice::Manifest(source) = choice {
+                let details = [source.format.as_deref(), Some(source.description.as_str())]
+                    .into_iter()
+                    .flatten()
+                    .filter(|detail| !detail.is_empty())
+                    .collect::<Vec<&str>>();
+                if !details.is_empty() {
+                    write!(text, " ({})", details.join(", ")).ok();
+                }
+            }
+            rows.add_col(text_span(&text)).add_row();
+        }
+
+        Self {
+            component: List::default()
+                .borders(
+                    Borders::default()
+                        .color(Color::LightCyan)
+                        .modifiers(BorderType::Rounded),
+                )
+                .title(format!(" {} ", t!(keys::SOURCES_TITLE)), Alignment::Center)
+                .scroll(true)
+                .highlighted_color(Color::LightCyan)
+                .rows(rows.build())
+                .selected_line(0),
+        }
+    }
+}
+
+impl Component<Msg, NoUserEvent> for SourcesDialog {
+    fn on(&mut self, ev: Event<NoUserEvent>) -> Option<Msg> {
+        // Esc reaches the global listener, which closes the dialog.
+        let _cmd_result = match ev {
+            Event::Keyboard(KeyEvent {
+                code: Key::Down, ..
+            }) => self.perform(Cmd::Move(Direction::Down)),
+            Event::Keyboard(KeyEvent { code: Key::Up, .. }) => {
+                self.perform(Cmd::Move(Direction::Up))
+            }
+            Event::Keyboard(KeyEvent {
+                code: Key::Enter, ..
+            }) => {
+                if let State::One(StateValue::Usize(index)) = self.state() {
+                    return Some(Msg::SourcesDialogSubmit(index));
+                }
+                CmdResult::None
+            }
+            Event::Keyboard(KeyEvent {
+                code: Key::Char('b'),
+                ..
+            }) => {
+                if let State::One(StateValue::Usize(index)) = self.state() {
+                    return Some(Msg::SourcesDialogBaseline(index));
+                }
+                CmdResult::None
+            }
+            _ => CmdResult::None,
+        };
+
+        Some(Msg::None)
+    }
+}
+
+impl Model {
+    pub fn mount_sources_dialog(&mut self) {
+        self.app
+            .remount(
+                Id::SourcesDialog,
+                Box::new(SourcesDialog::new(&self.source_choices)),
+                Vec::new(),
+            )
+            .ok();
+        self.app.active(&Id::SourcesDialog).ok();
+    }
+
+    pub fn umount_sources_dialog(&mut self) {
+        self.app.umount(&Id::SourcesDialog).ok();
+        self.app.active(&Id::Navigation).ok();
+    }
+
+    /// Load the chosen source and close the dialog.
+    pub fn submit_sources_dialog(&mut self, index: usize) {
+        if let Some(choice) = self.source_choices.get(index).cloned() {
+            self.send(BackgroundCmd::DocumentLoad(choice));
+        }
+        self.umount_sources_dialog();
+    }
+
+    /// Compare against the chosen source from now on, remember it in the
+    /// settings, and close the dialog.
+    pub fn choose_baseline(&mut self, index: usize) {
+        if let Some(choice) = self.source_choices.get(index).cloned() {
+            if let Some(settings) = self.settings.as_mut() {
+                settings.baseline = Some(choice.url().to_string());
+                let settings = settings.clone();
+                self.send(BackgroundCmd::SettingsSave(settings));
+            }
+            self.send(BackgroundCmd::BaselineLoad(choice));
+        }
+        self.umount_sources_dialog();
+    }
+
+    pub fn view_sources_dialog(app: &mut Application<Id, Msg, NoUserEvent>, f: &mut Frame<'_>) {
+        if app.mounted(&Id::SourcesDialog) {
+            let dialog = draw_area_in_absolute(f.area(), 60, 12);
+            f.render_widget(Clear, dialog);
+            app.view(&Id::SourcesDialog, f, dialog);
+        }
+    }
+}
@@ -0,0 +1,61 @@
+use tui_realm_stdlib::Label;
+use tuirealm::{
+    Application, Component, Frame, MockComponent, NoUserEvent,
+    props::{Alignment, Color},
+    ratatui::{layout::Rect, widgets::Clear},
+};
+
+use crate::{ids::Id, msgs::Msg, ui::model::Model};
+
+/// One line along the bottom of the screen describing the last background failure.
+#[derive(MockComponent)]
+pub struct ErrorBanner {
+    component: Label,
+}
+
+impl ErrorBanner {
+    pub fn new(message: &str) -> Self {
+        Self {
+            component: Label::default()
+                .text(message)
+                .alignment(Alignment::Center)
+                .foreground(Color::Black)
+                .background(Color::LightRed),
+        }
+    }
+}
+
+impl Component<Msg, NoUserEvent> for ErrorBanner {
+    fn on(&mut self, _ev: tuirealm::Event<NoUserEvent>) -> Option<Msg> {
+        Some(Msg::None)
+    }
+}
+
+impl Model {
+    /// Show `message` in the banner, replacing any earlier one. The banner never
+    /// takes focus.
+    pub fn mount_error_banner(&mut self, message: &str) {
+        self.app
+            .remount(
+                Id::ErrorBanner,
+                Box::new(ErrorBanner::new(message)),
+                Vec::new(),
+            )
+            .ok();
+    }
+
+    pub fn umount_error_banner(&mut self) {
+        if self.app.mounted(&Id::ErrorBanner) {
+            self.app.umount(&Id::ErrorBanner).ok();
+        }
+    }
+
+    pub fn view_error_banner(app: &mut Application<Id, Msg, NoUserEvent>, f: &mut Frame<'_>) {
+        if app.mounted(&Id::ErrorBanner) {
+            let area = f.area();
+            let banner = Rect::new(area.x, area.bottom().saturating_sub(1), area.width, 1);
+            f.render_widget(Clear, banner);
+            app.view(&Id::ErrorBanner, f, banner);
+        }
+    }
+}
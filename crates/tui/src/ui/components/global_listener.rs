@@ -2,10 +2,11 @@ use tracing::debug;
 use tui_realm_stdlib::Phantom;
 use tuirealm::{
     Component, MockComponent, NoUserEvent,
-    command::CmdResult,
-    event::{Key, KeyModifiers},
+    event::{Key, KeyModifiers, MouseButton, MouseEvent, MouseEventKind},
 };
 
+use data::layout::RESIZE_STEP;
+
 use crate::msgs::Msg;
 
 #[derive(MockComponent)]
@@ -23,8 +24,17 @@ impl GlobalListener {
 
 impl Component<Msg, NoUserEvent> for GlobalListener {
     fn on(&mut self, ev: tuirealm::Event<NoUserEvent>) -> Option<Msg> {
-        let _cmd_result = match ev {
+        match ev {
             tuirealm::Event::Keyboard(key_event) => {
+                let msg = match (key_event.code, key_event.modifiers) {
+                    (Key::Esc, KeyModifiers::NONE) => Msg::QuitDialogShow,
+                    (Key::Char('c'), KeyModifiers::CONTROL) => Msg::AppClose,
+                    (Key::Char('d'), KeyModifiers::CONTROL) => Msg::StateDump,
+                    (Key::Char('r'), KeyModifiers::CONTROL) => Msg::DashboardToggle,
+                    (Key::Left, KeyModifiers::CONTROL) => Msg::LayoutResize(-RESIZE_STEP),
+                    (Key::Right, KeyModifiers::CONTROL) => Msg::LayoutResize(RESIZE_STEP),
+                    _ => return None,
+                };
                 let printed_modifier = format!("{:?}", key_event.modifiers);
                 let printed_code = format!("{:?}", key_event.code);
                 debug!(
@@ -32,17 +42,16 @@ impl Component<Msg, NoUserEvent> for GlobalListener {
                     modifier = printed_modifier,
                     "Key pressed"
                 );
-                match key_event.code {
-                    Key::Esc => return Some(Msg::QuitDialogShow),
-                    Key::Char('c') if key_event.modifiers == KeyModifiers::CONTROL => {
-                        return Some(Msg::AppClose);
-                    }
-                    _ => CmdResult::None,
-                }
+                Some(msg)
             }
-            _ => CmdResult::None,
-        };
-
-        Some(Msg::None)
+            tuirealm::Event::Mouse(MouseEvent {
+                kind: MouseEventKind::Down(MouseButton::Left),
+                column,
+                row,
+                ..
+            }) => Some(Msg::Click(column, row)),
+            tuirealm::Event::WindowResize(width, height) => Some(Msg::WindowResize(width, height)),
+            _ => None,
+        }
     }
 }
@@ -1,3 +1,5 @@
 pub mod dialogs;
+pub mod error_banner;
 pub mod global_listener;
 pub mod pages;
+pub mod status_bar;
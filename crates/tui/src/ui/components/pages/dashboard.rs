@@ -0,0 +1,273 @@
+use std::time::Instant;
+
+use data::{
+    dashboard::{self, Category, Count, Panel, ReportLine, TOP_ENTRIES},
+    i18n::keys,
+    manifest::{ManifestSource, SourceChoice},
+};
+use tui_realm_stdlib::List;
+use tuirealm::{
+    Application, AttrValue, Attribute, Component, Event, Frame, MockComponent, NoUserEvent,
+    command::{Cmd, CmdResult, Direction, Position},
+    event::{Key, KeyEvent, KeyModifiers},
+    props::{Alignment, BorderType, Borders, TableBuilder, TextSpan},
+    ratatui::layout::Rect,
+};
+
+use crate::{
+    cmds::BackgroundCmd,
+    ids::Id,
+    msgs::Msg,
+    ui::{components::pages::Page, i18n::t, model::Model, utils::text_span},
+};
+
+#[derive(MockComponent)]
+pub struct Dashboard {
+    component: List,
+}
+
+impl Dashboard {
+    pub fn new() -> Self {
+        Self {
+            component: List::default()
+                .borders(Borders::default().modifiers(BorderType::Rounded))
+                .scroll(true)
+                .step(4)
+                .rows(
+                    TableBuilder::default()
+                        .add_col(TextSpan::from(t!(keys::DASHBOARD_EMPTY)))
+                        .add_row()
+                        .build(),
+                )
+                .selected_line(0),
+        }
+    }
+}
+
+impl Component<Msg, NoUserEvent> for Dashboard {
+    fn on(&mut self, ev: tuirealm::Event<NoUserEvent>) -> Option<Msg> {
+        let _cmd_result = match ev {
+            Event::Keyboard(KeyEvent {
+                code: Key::Char('c'),
+                modifiers: KeyModifiers::NONE,
+            }) => return Some(Msg::DashboardOpenChangelog),
+            Event::Keyboard(KeyEvent {
+                code: Key::Char('l'),
+                modifiers: KeyModifiers::NONE,
+            }) => return Some(Msg::DashboardLoadSpec),
+            Event::Keyboard(KeyEvent {
+                code: Key::Char('r'),
+                modifiers: KeyModifiers::NONE,
+            }) => return Some(Msg::DashboardReload),
+            Event::Keyboard(KeyEvent {
+                code: Key::Down, ..
+            }) => self.perform(Cmd::Move(Direction::Down)),
+            Event::Keyboard(KeyEvent { code: Key::Up, .. }) => {
+                self.perform(Cmd::Move(Direction::Up))
+            }
+            Event::Keyboard(KeyEvent {
+                code: Key::PageDown,
+                ..
+            }) => self.perform(Cmd::Scroll(Direction::Down)),
+            Event::Keyboard(KeyEvent {
+                code: Key::PageUp, ..
+            }) => self.perform(Cmd::Scroll(Direction::Up)),
+            Event::Keyboard(KeyEvent {
+                code: Key::Home, ..
+            }) => self.perform(Cmd::GoTo(Position::Begin)),
+            Event::Keyboard(KeyEvent { code: Key::End, .. }) => {
+                self.perform(Cmd::GoTo(Position::End))
+            }
+            _ => CmdResult::None,
+        };
+        Some(Msg::None)
+    }
+}
+
+impl Model {
+    /// Show the dashboard of the configured artifact directory, or go back home.
+    pub fn toggle_dashboard(&mut self) {
+        // Dialogs keep the focus until they're closed.
+        if self.app.mounted(&Id::QuitDialog)
+            || self.app.mounted(&Id::SetupDialog)
+            || self.app.mounted(&Id::SourcesDialog)
+        {
+            return;
+        }
+        if self.page == Page::Dashboard {
+            self.app.umount(&Id::Dashboard).ok();
+            self.app.active(&Id::Navigation).ok();
+            self.page = Page::Home;
+            return;
+        }
+        if self.page == Page::Endpoints {
+            self.close_endpoints();
+        }
+        self.app
+            .remount(Id::Dashboard, Box::new(Dashboard::new()), Vec::new())
+            .ok();
+        self.app.active(&Id::Dashboard).ok();
+        self.page = Page::Dashboard;
+        if let Some(dashboard) = self.dashboard.take() {
+            self.update_dashboard(dashboard);
+        }
+        self.reload_dashboard();
+    }
+
+    pub fn reload_dashboard(&mut self) {
+        let dir = self.settings.clone().unwrap_or_default().artifact_dir;
+        self.send(BackgroundCmd::DashboardLoad(dir));
+    }
+
+    /// Keep the last run's artifacts and list them, a section per panel.
+    pub fn update_dashboard(&mut self, dashboard: dashboard::Dashboard) {
+        let mut rows = TableBuilder::default();
+        add_panel(
+            &mut rows,
+            keys::DASHBOARD_HEADLINE,
+            &dashboard.headline,
+            |count| vec![count_line(count)],
+        );
+        add_panel(
+            &mut rows,
+            keys::DASHBOARD_REPORT,
+            &dashboard.categories,
+            category_lines,
+        );
+        add_panel(
+            &mut rows,
+            keys::DASHBOARD_SKIPPED,
+            &dashboard.skipped,
+            |entry| vec![entry_line(entry)],
+        );
+        add_panel(
+            &mut rows,
+            keys::DASHBOARD_COLLIDED,
+            &dashboard.collided,
+            |entry| vec![entry_line(entry)],
+        );
+        add_panel(
+            &mut rows,
+            keys::DASHBOARD_ARTIFACTS,
+            &dashboard.artifacts,
+            |path| vec![text_span(&format!("  {}", path.display()))],
+        );
+        self.app
+            .attr(
+                &Id::Dashboard,
+                Attribute::Content,
+                AttrValue::Table(rows.build()),
+            )
+            .ok();
+
+        let mut title = t!(keys::DASHBOARD_TITLE, dashboard.dir.display());
+        if let Some(version) = &dashboard.version {
+            title = format!("{title} (v{version})");
+        }
+        self.app
+            .attr(
+                &Id::Dashboard,
+                Attribute::Title,
+                AttrValue::Title((title, Alignment::Left)),
+            )
+            .ok();
+        self.dashboard = Some(dashboard);
+    }
+
+    pub fn open_changelog(&mut self) {
+        match self.dashboard.as_ref().and_then(|d| d.changelog.clone()) {
+            Some(path) => self.send(BackgroundCmd::OpenPath(path)),
+            None => self
+                .status
+                .messages
+                .push(t!(keys::STATUS_NO_CHANGELOG), Instant::now()),
+        }
+    }
+
+    /// Browse the generated spec like any other source.
+    pub fn load_generated_spec(&mut self) {
+        let Some(path) = self.dashboard.as_ref().and_then(|d| d.spec.clone()) else {
+            self.status
+                .messages
+                .push(t!(keys::STATUS_NO_SPEC), Instant::now());
+            return;
+        };
+        let source = ManifestSource {
+            name: path.display().to_string(),
+            url: path.display().to_string(),
+            format: None,
+            description: String::new(),
+        };
+        self.send(BackgroundCmd::DocumentLoad(SourceChoice::Manifest(source)));
+        self.toggle_dashboard();
+    }
+
+    pub fn view_page_dashboard(
+        app: &mut Application<Id, Msg, NoUserEvent>,
+        f: &mut Frame<'_>,
+        area: Rect,
+    ) {
+        app.view(&Id::Dashboard, f, area);
+    }
+}
+
+/// A heading, then the panel's lines or a placeholder saying why it has none.
+fn add_panel<T>(
+    rows: &mut TableBuilder,
+    heading: &str,
+    panel: &Panel<Vec<T>>,
+    lines: impl Fn(&T) -> Vec<TextSpan>,
+) {
+    rows.add_col(TextSpan::from(t!(heading)).bold()).add_row();
+    let placeholder = match panel {
+        Panel::Ready(items) if items.is_empty() => t!(keys::DASHBOARD_NONE).to_string(),
+        Panel::Ready(items) => {
+            for line in items.iter().flat_map(lines) {
+                rows.add_col(line).add_row();
+            }
+            return;
+        }
+        Panel::Missing(path) => t!(keys::DASHBOARD_MISSING, path.display()),
+        Panel::Unreadable { path, error } => t!(keys::DASHBOARD_UNREADABLE, path.display(), error),
+    };
+    rows.add_col(text_span(&format!("  {placeholder}")))
+        .add_row();
+}
+
+fn count_line(count: &Count) -> TextSpan {
+    let name = t!(count.name);
+    let line = match count.delta {
+        Some(delta) => t!(
+            keys::DASHBOARD_COUNT_DELTA,
+            name,
+            count.value,
+            format!("{delta:+}")
+        ),
+        None => t!(keys::DASHBOARD_COUNT, name, count.value),
+    };
+    TextSpan::from(format!("  {line}"))
+}
+
+fn category_lines(category: &Category) -> Vec<TextSpan> {
+    let mut lines = vec![TextSpan::from(format!(
+        "  {}",
+        t!(keys::DASHBOARD_CATEGORY, category.kind, category.count)
+    ))];
+    lines.extend(
+        category
+            .entries
+            .iter()
+            .map(|entry| text_span(&format!("    {}: {}", entry.target, entry.detail))),
+    );
+    if category.count > TOP_ENTRIES {
+        lines.push(TextSpan::from(format!(
+            "    {}",
+            t!(keys::DASHBOARD_MORE, category.count - TOP_ENTRIES)
+        )));
+    }
+    lines
+}
+
+fn entry_line(entry: &ReportLine) -> TextSpan {
+    text_span(&format!("  {}: {}", entry.target, entry.detail))
+}
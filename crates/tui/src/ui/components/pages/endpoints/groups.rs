@@ -0,0 +1,64 @@
+use data::i18n::keys;
+use tui_realm_stdlib::List;
+use tuirealm::{
+    Component, Event, MockComponent, NoUserEvent, State, StateValue,
+    command::{Cmd, CmdResult, Direction},
+    event::{Key, KeyEvent},
+    props::{Alignment, BorderType, Borders, TableBuilder, TextSpan},
+};
+
+use crate::{msgs::Msg, ui::i18n::t};
+
+/// The left pane: the document's groups, opening each in the table as it's
+/// moved to.
+#[derive(MockComponent)]
+pub struct EndpointsGroups {
+    component: List,
+}
+
+impl EndpointsGroups {
+    pub fn new() -> Self {
+        Self {
+            component: List::default()
+                .borders(Borders::default().modifiers(BorderType::Rounded))
+                .scroll(true)
+                .title(t!(keys::ENDPOINTS_GROUPS), Alignment::Left)
+                .rows(
+                    TableBuilder::default()
+                        .add_col(TextSpan::from(t!(keys::ENDPOINTS_EMPTY)))
+                        .add_row()
+                        .build(),
+                )
+                .selected_line(0),
+        }
+    }
+
+    fn selected(&self) -> Msg {
+        match self.state() {
+            State::One(StateValue::Usize(index)) => Msg::EndpointsSelect(index),
+            _ => Msg::None,
+        }
+    }
+}
+
+impl Component<Msg, NoUserEvent> for EndpointsGroups {
+    fn on(&mut self, ev: tuirealm::Event<NoUserEvent>) -> Option<Msg> {
+        let _cmd_result = match ev {
+            Event::Keyboard(KeyEvent { code: Key::Tab, .. }) => {
+                return Some(Msg::EndpointsFocusTable);
+            }
+            Event::Keyboard(KeyEvent {
+                code: Key::Down, ..
+            }) => {
+                self.perform(Cmd::Move(Direction::Down));
+                return Some(self.selected());
+            }
+            Event::Keyboard(KeyEvent { code: Key::Up, .. }) => {
+                self.perform(Cmd::Move(Direction::Up));
+                return Some(self.selected());
+            }
+            _ => CmdResult::None,
+        };
+        Some(Msg::None)
+    }
+}
@@ -0,0 +1,327 @@
+pub mod groups;
+pub mod table;
+
+use data::{
+    ALL_GROUP,
+    diff::{self, Change, DiffRow},
+    i18n::keys,
+    layout::{Arrangement, SplitLayout},
+    window::WindowedRows,
+};
+use groups::EndpointsGroups;
+use table::EndpointsTable;
+use tuirealm::{
+    Application, AttrValue, Attribute, Frame, NoUserEvent,
+    props::{Alignment, PropPayload, PropValue, Table, TableBuilder, TextSpan},
+    ratatui::layout::{Constraint, Direction, Layout, Rect},
+};
+
+use crate::{
+    ids::Id,
+    msgs::Msg,
+    ui::{
+        components::pages::Page,
+        i18n::t,
+        model::{Areas, Model},
+        utils::text_span,
+    },
+};
+
+impl Model {
+    /// Show the endpoints of the latest document, starting at `group`.
+    pub fn open_endpoints(&mut self, group: &str) {
+        self.app
+            .remount(
+                Id::EndpointsGroups,
+                Box::new(EndpointsGroups::new()),
+                Vec::new(),
+            )
+            .ok();
+        self.app
+            .remount(
+                Id::EndpointsTable,
+                Box::new(EndpointsTable::new()),
+                Vec::new(),
+            )
+            .ok();
+        self.app.active(&Id::EndpointsGroups).ok();
+        self.page = Page::Endpoints;
+        self.endpoints_group = group.to_string();
+        self.view_endpoints();
+    }
+
+    /// Go back home, to the navigation pane.
+    pub fn close_endpoints(&mut self) {
+        self.app.umount(&Id::EndpointsGroups).ok();
+        self.app.umount(&Id::EndpointsTable).ok();
+        self.app.active(&Id::Navigation).ok();
+        self.page = Page::Home;
+    }
+
+    /// Show the endpoints of the group on a row of the left pane.
+    pub fn select_endpoints_group(&mut self, index: usize) {
+        if let Some(group) = self.endpoint_groups().get(index) {
+            self.endpoints_group.clone_from(group);
+            self.view_endpoints();
+        }
+    }
+
+    pub fn focus_endpoints_table(&mut self) {
+        self.app
+            .attr(
+                &Id::EndpointsGroups,
+                Attribute::Focus,
+                AttrValue::Flag(false),
+            )
+            .ok();
+        self.app.active(&Id::EndpointsTable).ok();
+    }
+
+    pub fn focus_endpoints_groups(&mut self) {
+        self.app
+            .attr(
+                &Id::EndpointsTable,
+                Attribute::Focus,
+                AttrValue::Flag(false),
+            )
+            .ok();
+        self.app.active(&Id::EndpointsGroups).ok();
+    }
+
+    /// [`ALL_GROUP`], then the latest document's groups in the current
+    /// grouping that [`Model::filter`] keeps any endpoints of.
+    fn endpoint_groups(&self) -> Vec<String> {
+        let diff = self.diff.current();
+        let groups = self.document.iter().flat_map(|(_, document)| {
+            document
+                .groups(self.grouping)
+                .keys()
+                .filter(move |group| {
+                    self.filter
+                        .group(document, diff, self.grouping, group)
+                        .is_some()
+                })
+                .cloned()
+        });
+        std::iter::once(ALL_GROUP.to_string())
+            .chain(groups)
+            .collect()
+    }
+
+    /// List the groups and the selected group's endpoints, marked with what
+    /// changed since the baseline if there is one. Does nothing off the page,
+    /// so it can follow every document load.
+    pub fn view_endpoints(&mut self) {
+        if self.page != Page::Endpoints {
+            return;
+        }
+        let Some((_, document)) = &self.document else {
+            return;
+        };
+        let stats = self.documents.last().map(|summary| &summary.stats);
+        let label = |group: &str| {
+            stats.map_or(group.to_string(), |stats| {
+                stats.label(self.grouping, group).to_string()
+            })
+        };
+
+        let groups = self.endpoint_groups();
+        let selected = groups
+            .iter()
+            .position(|group| *group == self.endpoints_group)
+            .unwrap_or(0);
+        let group = groups[selected].clone();
+        let mut rows = TableBuilder::default();
+        for group in &groups {
+            rows.add_col(text_span(&label(group))).add_row();
+        }
+        self.app
+            .attr(
+                &Id::EndpointsGroups,
+                Attribute::Content,
+                AttrValue::Table(rows.build()),
+            )
+            .ok();
+        self.app
+            .attr(
+                &Id::EndpointsGroups,
+                Attribute::Value,
+                AttrValue::Payload(PropPayload::One(PropValue::Usize(selected))),
+            )
+            .ok();
+
+        // Removed endpoints come last, from the baseline.
+        let endpoints = self.filter.apply(diff::rows(
+            document,
+            self.diff.current(),
+            self.grouping,
+            &group,
+        ));
+        let title = t!(keys::ENDPOINTS_TITLE, label(&group), endpoints.len());
+        self.app
+            .attr(
+                &Id::EndpointsTable,
+                Attribute::Title,
+                AttrValue::Title((title, Alignment::Left)),
+            )
+            .ok();
+        // Like the table did on its own, keep the selection where it was.
+        let selected = isize::try_from(self.endpoint_rows.selected()).unwrap_or(isize::MAX);
+        self.endpoint_rows = WindowedRows::new(endpoints, self.endpoints_height());
+        self.endpoint_rows.move_by(selected);
+        self.show_endpoint_rows();
+    }
+
+    /// Follow a move of the table's selection, handing it new rows once it
+    /// nears the edge of those it has.
+    pub fn move_endpoints(&mut self, delta: isize) {
+        if self.endpoint_rows.move_by(delta) {
+            self.show_endpoint_rows();
+        }
+    }
+
+    /// The terminal is now `height` rows, which the table can't outgrow.
+    pub fn resize_endpoints(&mut self, height: u16) {
+        self.endpoint_rows.resize(usize::from(height));
+        if self.page == Page::Endpoints {
+            self.show_endpoint_rows();
+        }
+    }
+
+    /// Rows the endpoints table shows at once: its last drawn height less
+    /// the borders and header, or the terminal's before it has been drawn.
+    fn endpoints_height(&self) -> usize {
+        let height = match self.areas.get(&Id::EndpointsTable) {
+            Some(area) => area.height.saturating_sub(3),
+            None => self
+                .terminal
+                .raw()
+                .size()
+                .map_or(u16::MAX, |size| size.height),
+        };
+        usize::from(height)
+    }
+
+    fn show_endpoint_rows(&mut self) {
+        let (rows, selected) = endpoint_table(&mut self.endpoint_rows);
+        self.app
+            .attr(
+                &Id::EndpointsTable,
+                Attribute::Content,
+                AttrValue::Table(rows),
+            )
+            .ok();
+        self.app
+            .attr(
+                &Id::EndpointsTable,
+                Attribute::Value,
+                AttrValue::Payload(PropPayload::One(PropValue::Usize(selected))),
+            )
+            .ok();
+    }
+
+    /// Groups beside the endpoints, or above them on narrow terminals, sized
+    /// like the home page's panes.
+    pub fn view_page_endpoints(
+        app: &mut Application<Id, Msg, NoUserEvent>,
+        f: &mut Frame<'_>,
+        area: Rect,
+        areas: &mut Areas,
+        layout: SplitLayout,
+    ) {
+        let chunks = match Arrangement::for_width(area.width) {
+            Arrangement::Split => {
+                let (groups, _) = layout.widths(area.width);
+                Layout::default()
+                    .direction(Direction::Horizontal)
+                    .constraints([Constraint::Length(groups), Constraint::Fill(1)].as_ref())
+                    .split(area)
+            }
+            Arrangement::Stacked => Layout::default()
+                .direction(Direction::Vertical)
+                .constraints([Constraint::Fill(1), Constraint::Fill(2)].as_ref())
+                .split(area),
+        };
+
+        app.view(&Id::EndpointsGroups, f, chunks[0]);
+        app.view(&Id::EndpointsTable, f, chunks[1]);
+
+        areas.clear();
+        areas.insert(Id::EndpointsGroups, chunks[0]);
+        areas.insert(Id::EndpointsTable, chunks[1]);
+    }
+}
+
+/// The table rows for the window of `rows`, and the selection among them.
+fn endpoint_table(rows: &mut WindowedRows<DiffRow>) -> (Table, usize) {
+    let (_, window, selected) = rows.window();
+    let mut table = TableBuilder::default();
+    for row in window {
+        let marker = row.change.map_or(' ', Change::marker);
+        let operation = row.plugin.operation();
+        let description = operation
+            .summary
+            .or(operation.description)
+            .unwrap_or_default();
+        table
+            .add_col(TextSpan::from(format!(
+                "{marker} {}",
+                row.plugin.method().to_uppercase()
+            )))
+            .add_col(text_span(&row.plugin.path()))
+            .add_col(text_span(description.lines().next().unwrap_or_default()))
+            .add_row();
+    }
+    (table.build(), selected)
+}
+
+#[cfg(test)]
+mod tests {
+    use data::{Document, Grouping, window::WINDOW_MARGIN};
+    use serde_json::{Map, Value, json};
+
+    use super::*;
+
+    /// A document with `len` endpoints in one plugin.
+    fn document(len: usize) -> Document {
+        let paths = (0..len)
+            .map(|i| {
+                let operation = json!({
+                    "operationId": format!("GetResource{i}"),
+                    "tags": ["plugins", "lol-plugin"],
+                    "parameters": [],
+                    "responses": {},
+                });
+                (
+                    format!("/lol-plugin/v1/resource/{i}"),
+                    json!({ "get": operation }),
+                )
+            })
+            .collect::<Map<String, Value>>();
+        let spec = json!({
+            "openapi": "3.0.0",
+            "info": { "title": "LCU", "description": "", "version": "1.0.0" },
+            "paths": paths,
+            "components": { "schemas": {} },
+        });
+        Document::new(serde_json::from_value(spec).unwrap())
+    }
+
+    #[test]
+    fn test_table_bounded_by_viewport() {
+        let document = document(5000);
+        let endpoints = diff::rows(&document, None, Grouping::Plugin, ALL_GROUP);
+        assert_eq!(endpoints.len(), 5000);
+        let mut rows = WindowedRows::new(endpoints, 30);
+
+        for delta in [0, 1, 2500, -40, isize::MAX, isize::MIN] {
+            rows.move_by(delta);
+            let expected = rows.selected_row().unwrap().plugin.path();
+            let (table, selected) = endpoint_table(&mut rows);
+            // The builder always leaves an empty row at the end.
+            let built = table.len() - 1;
+            assert!(built <= 30 + 2 * WINDOW_MARGIN, "{delta}: {built}");
+            assert_eq!(table[selected][1].content, expected, "{delta}");
+        }
+    }
+}
@@ -0,0 +1,72 @@
+use data::i18n::keys;
+use tui_realm_stdlib::Table;
+use tuirealm::{
+    Component, Event, MockComponent, NoUserEvent,
+    command::{Cmd, Direction, Position},
+    event::{Key, KeyEvent},
+    props::{Alignment, BorderType, Borders, Color},
+};
+
+use crate::{msgs::Msg, ui::i18n::t};
+
+/// Rows Page Up and Page Down move by.
+const PAGE_STEP: isize = 4;
+
+/// The right pane: method, path and description of the selected group's
+/// endpoints. It holds only the rows around the visible ones, so each move is
+/// reported for the model to hand it new rows when needed.
+#[derive(MockComponent)]
+pub struct EndpointsTable {
+    component: Table,
+}
+
+impl EndpointsTable {
+    pub fn new() -> Self {
+        Self {
+            component: Table::default()
+                .title(t!(keys::ENDPOINTS_EMPTY), Alignment::Left)
+                .borders(Borders::default().modifiers(BorderType::Rounded))
+                .scroll(true)
+                .highlighted_color(Color::White)
+                .step(PAGE_STEP.unsigned_abs())
+                .row_height(1)
+                .headers(&[
+                    t!(keys::ENDPOINTS_METHOD),
+                    t!(keys::ENDPOINTS_PATH),
+                    t!(keys::ENDPOINTS_DESCRIPTION),
+                ])
+                .column_spacing(2)
+                .widths(&[10, 45, 45]),
+        }
+    }
+}
+
+impl Component<Msg, NoUserEvent> for EndpointsTable {
+    fn on(&mut self, ev: tuirealm::Event<NoUserEvent>) -> Option<Msg> {
+        let (cmd, delta) = match ev {
+            Event::Keyboard(KeyEvent { code: Key::Tab, .. }) => {
+                return Some(Msg::EndpointsFocusGroups);
+            }
+            Event::Keyboard(KeyEvent {
+                code: Key::Down, ..
+            }) => (Cmd::Move(Direction::Down), 1),
+            Event::Keyboard(KeyEvent { code: Key::Up, .. }) => (Cmd::Move(Direction::Up), -1),
+            Event::Keyboard(KeyEvent {
+                code: Key::PageDown,
+                ..
+            }) => (Cmd::Scroll(Direction::Down), PAGE_STEP),
+            Event::Keyboard(KeyEvent {
+                code: Key::PageUp, ..
+            }) => (Cmd::Scroll(Direction::Up), -PAGE_STEP),
+            Event::Keyboard(KeyEvent {
+                code: Key::Home, ..
+            }) => (Cmd::GoTo(Position::Begin), isize::MIN),
+            Event::Keyboard(KeyEvent { code: Key::End, .. }) => {
+                (Cmd::GoTo(Position::End), isize::MAX)
+            }
+            _ => return Some(Msg::None),
+        };
+        self.perform(cmd);
+        Some(Msg::EndpointsMove(delta))
+    }
+}
@@ -1,37 +1,96 @@
-use data::RiotAPILibrary;
+use std::time::{Duration, Instant};
+
+use data::{RiotAPILibrary, i18n::keys, libraries::SourceUsed};
 use tui_realm_stdlib::Table;
 use tuirealm::{
-    AttrValue, Attribute, Component, Event, MockComponent, NoUserEvent,
+    AttrValue, Attribute, Component, Event, Frame, MockComponent, NoUserEvent, State,
     command::{Cmd, CmdResult, Direction, Position},
-    event::{Key, KeyEvent},
+    event::{Key, KeyEvent, MouseButton, MouseEvent, MouseEventKind},
     props::{Alignment, BorderType, Borders, Color, TableBuilder, TextSpan},
+    ratatui::layout::Rect,
+};
+
+use crate::{
+    ids::Id,
+    msgs::Msg,
+    ui::{i18n::t, model::Model, utils::table_row_at},
 };
 
-use crate::{ids::Id, msgs::Msg, ui::model::Model};
+/// Longest gap between two clicks on a row that still counts as a double-click.
+const DOUBLE_CLICK: Duration = Duration::from_millis(400);
 
-#[derive(MockComponent)]
 pub struct Libraries {
     component: Table,
     init: bool,
+    /// Where the table was last drawn, for mapping clicks to rows.
+    area: Rect,
+    last_click: Option<(Instant, usize)>,
 }
 
 impl Libraries {
     pub fn new() -> Self {
         Self {
             component: Table::default()
-                .title("Libraries", Alignment::Center)
+                .title(t!(keys::LIBRARIES_TITLE), Alignment::Center)
                 .borders(Borders::default().modifiers(BorderType::Rounded))
                 .scroll(true)
                 .rewind(true)
                 .highlighted_color(Color::White)
                 .step(4)
                 .row_height(1)
-                .headers(&["Owner", "Repo", "Language"])
+                .headers(&[
+                    t!(keys::LIBRARIES_OWNER),
+                    t!(keys::LIBRARIES_REPO),
+                    t!(keys::LIBRARIES_LANGUAGE),
+                ])
                 .column_spacing(3)
                 .widths(&[40, 40, 20]),
             init: false,
+            area: Rect::default(),
+            last_click: None,
         }
     }
+
+    /// Select the clicked row. A second click on the same row soon after opens it.
+    fn click(&mut self, row: u16) -> Option<Msg> {
+        let states = &mut self.component.states;
+        let index = table_row_at(self.area, states.list_index, states.list_len, row)?;
+        states.list_index = index;
+
+        let now = Instant::now();
+        let double = self
+            .last_click
+            .is_some_and(|(at, last)| last == index && now.duration_since(at) <= DOUBLE_CLICK);
+        if double {
+            self.last_click = None;
+            return Some(Msg::LibrariesSubmit(index));
+        }
+        self.last_click = Some((now, index));
+        Some(Msg::None)
+    }
+}
+
+impl MockComponent for Libraries {
+    fn view(&mut self, frame: &mut Frame<'_>, area: Rect) {
+        self.area = area;
+        self.component.view(frame, area);
+    }
+
+    fn query(&self, attr: Attribute) -> Option<AttrValue> {
+        self.component.query(attr)
+    }
+
+    fn attr(&mut self, attr: Attribute, value: AttrValue) {
+        self.component.attr(attr, value);
+    }
+
+    fn state(&self) -> State {
+        self.component.state()
+    }
+
+    fn perform(&mut self, cmd: Cmd) -> CmdResult {
+        self.component.perform(cmd)
+    }
 }
 
 impl Component<Msg, NoUserEvent> for Libraries {
@@ -44,10 +103,16 @@ impl Component<Msg, NoUserEvent> for Libraries {
             Event::Keyboard(KeyEvent { code: Key::Tab, .. }) => return Some(Msg::LibrariesBlur),
             Event::Keyboard(KeyEvent {
                 code: Key::Down, ..
+            })
+            | Event::Mouse(MouseEvent {
+                kind: MouseEventKind::ScrollDown,
+                ..
             }) => self.perform(Cmd::Move(Direction::Down)),
-            Event::Keyboard(KeyEvent { code: Key::Up, .. }) => {
-                self.perform(Cmd::Move(Direction::Up))
-            }
+            Event::Keyboard(KeyEvent { code: Key::Up, .. })
+            | Event::Mouse(MouseEvent {
+                kind: MouseEventKind::ScrollUp,
+                ..
+            }) => self.perform(Cmd::Move(Direction::Up)),
             Event::Keyboard(KeyEvent {
                 code: Key::PageDown,
                 ..
@@ -64,6 +129,17 @@ impl Component<Msg, NoUserEvent> for Libraries {
             Event::Keyboard(KeyEvent {
                 code: Key::Enter, ..
             }) => return Some(Msg::LibrariesSubmit(self.component.states.list_index)),
+            Event::Keyboard(KeyEvent {
+                code: Key::Char('y'),
+                ..
+            }) => return Some(Msg::LibrariesCopy(self.component.states.list_index)),
+            Event::Mouse(MouseEvent {
+                kind: MouseEventKind::Down(MouseButton::Left),
+                row,
+                ..
+            }) => return self.click(row),
+            // Don't redraw for every mouse move.
+            Event::Mouse(_) => return None,
             _ => CmdResult::None,
         };
 
@@ -79,7 +155,7 @@ impl Model {
         self.app.active(&Id::Navigation).ok();
     }
 
-    pub fn update_libraries(&mut self, libraries: Vec<RiotAPILibrary>) {
+    pub fn update_libraries(&mut self, libraries: Vec<RiotAPILibrary>, source: &SourceUsed) {
         let current_libraries = libraries
             .into_iter()
             .filter(is_lcu_or_ingame_library)
@@ -99,14 +175,28 @@ impl Model {
                 AttrValue::Table(table.build()),
             )
             .ok();
+
+        // Every mirror failed, so say the list may be out of date.
+        let title = if source.is_stale() {
+            t!(keys::LIBRARIES_TITLE_OFFLINE)
+        } else {
+            t!(keys::LIBRARIES_TITLE)
+        };
+        self.app
+            .attr(
+                &Id::Libraries,
+                Attribute::Title,
+                AttrValue::Title((title.to_string(), Alignment::Center)),
+            )
+            .ok();
     }
 }
 
 fn is_lcu_or_ingame_library(x: &RiotAPILibrary) -> bool {
-    if let Some(tags) = &x.tags {
-        if tags.contains(&String::from("lcu")) || tags.contains(&String::from("ingame")) {
-            return true;
-        }
+    if let Some(tags) = &x.tags
+        && (tags.contains(&String::from("lcu")) || tags.contains(&String::from("ingame")))
+    {
+        return true;
     }
     false
 }
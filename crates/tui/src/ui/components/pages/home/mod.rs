@@ -3,16 +3,22 @@ pub mod navigation;
 pub mod welcome;
 
 use color_eyre::eyre::Result;
+use data::layout::{Arrangement, SplitLayout};
 use libraries::Libraries;
 use navigation::Navigation;
 use tuirealm::{
     Application, Frame, NoUserEvent,
-    ratatui::layout::{Constraint, Direction, Layout},
+    ratatui::layout::{Constraint, Direction, Layout, Rect},
 };
 
 use welcome::Welcome;
 
-use crate::{ids::Id, msgs::Msg, ui::model::Model};
+use crate::{
+    cmds::BackgroundCmd,
+    ids::Id,
+    msgs::Msg,
+    ui::model::{Areas, Model},
+};
 
 impl Model {
     pub fn mount_home(app: &mut Application<Id, Msg, NoUserEvent>) -> Result<()> {
@@ -23,11 +29,37 @@ impl Model {
         Ok(())
     }
 
-    pub fn view_page_home(app: &mut Application<Id, Msg, NoUserEvent>, f: &mut Frame<'_>) {
-        let chunks = Layout::default()
-            .direction(Direction::Horizontal)
-            .constraints([Constraint::Fill(1), Constraint::Fill(2)].as_ref())
-            .split(f.area());
+    /// Resize the home page's navigation pane and save the new size.
+    pub fn resize_layout(&mut self, delta: i16) {
+        self.layouts.home = self.layouts.home.resized(delta);
+        if let Some(settings) = self.settings.as_mut() {
+            settings.layouts = self.layouts;
+            let settings = settings.clone();
+            self.send(BackgroundCmd::SettingsSave(settings));
+        }
+    }
+
+    /// Navigation beside the details, or above them on narrow terminals.
+    pub fn view_page_home(
+        app: &mut Application<Id, Msg, NoUserEvent>,
+        f: &mut Frame<'_>,
+        area: Rect,
+        areas: &mut Areas,
+        layout: SplitLayout,
+    ) {
+        let chunks = match Arrangement::for_width(area.width) {
+            Arrangement::Split => {
+                let (navigation, _) = layout.widths(area.width);
+                Layout::default()
+                    .direction(Direction::Horizontal)
+                    .constraints([Constraint::Length(navigation), Constraint::Fill(1)].as_ref())
+                    .split(area)
+            }
+            Arrangement::Stacked => Layout::default()
+                .direction(Direction::Vertical)
+                .constraints([Constraint::Fill(1), Constraint::Fill(2)].as_ref())
+                .split(area),
+        };
 
         let sub_chunks = Layout::default()
             .direction(Direction::Vertical)
@@ -37,5 +69,10 @@ impl Model {
         app.view(&Id::Libraries, f, sub_chunks[1]);
         app.view(&Id::Navigation, f, chunks[0]);
         app.view(&Id::Welcome, f, sub_chunks[0]);
+
+        areas.clear();
+        areas.insert(Id::Libraries, sub_chunks[1]);
+        areas.insert(Id::Navigation, chunks[0]);
+        areas.insert(Id::Welcome, sub_chunks[0]);
     }
 }
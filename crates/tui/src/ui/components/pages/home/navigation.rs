@@ -1,16 +1,30 @@
+use data::{
+    ALL_GROUP, Document, Grouping,
+    diff::ChangeCounts,
+    i18n::keys,
+    manifest::{ManifestError, ManifestSource, SourceChoice},
+    snapshot::DocumentSummary,
+};
+use tracing::debug;
 use tui_realm_stdlib::List;
 use tuirealm::{
-    AttrValue, Attribute, Component, Event, MockComponent, NoUserEvent,
-    command::CmdResult,
+    AttrValue, Attribute, Component, Event, MockComponent, NoUserEvent, State, StateValue,
+    command::{Cmd, CmdResult, Direction},
     event::{Key, KeyEvent},
-    props::{Alignment, BorderType, Borders, TableBuilder, TextSpan},
+    props::{Alignment, BorderType, Borders, PropPayload, PropValue, TableBuilder, TextSpan},
 };
 
-use crate::{ids::Id, msgs::Msg, ui::model::Model};
+use crate::{
+    cmds::BackgroundCmd,
+    ids::Id,
+    msgs::Msg,
+    ui::{i18n::t, model::Model, utils::text_span},
+};
 
 #[derive(MockComponent)]
 pub struct Navigation {
     component: List,
+    init: bool,
 }
 
 impl Navigation {
@@ -19,14 +33,23 @@ impl Navigation {
             component: List::default()
                 .borders(Borders::default().modifiers(BorderType::Rounded))
                 .scroll(true)
-                .title("Nav", Alignment::Left)
+                .title(t!(keys::NAVIGATION_TITLE), Alignment::Left)
                 .rows(
                     TableBuilder::default()
-                        .add_col(TextSpan::from("Documents"))
+                        .add_col(TextSpan::from(t!(keys::NAVIGATION_EMPTY)))
                         .add_row()
                         .build(),
                 )
                 .selected_line(0),
+            init: false,
+        }
+    }
+
+    /// Report the row moved to, so the model can keep it across rebuilds.
+    fn selected(&self) -> Msg {
+        match self.state() {
+            State::One(StateValue::Usize(index)) => Msg::NavigationSelect(index),
+            _ => Msg::None,
         }
     }
 }
@@ -34,7 +57,41 @@ impl Navigation {
 impl Component<Msg, NoUserEvent> for Navigation {
     fn on(&mut self, ev: tuirealm::Event<NoUserEvent>) -> Option<Msg> {
         let _cmd_result = match ev {
+            Event::Tick if !self.init => {
+                self.init = true;
+                return Some(Msg::DocumentsInit);
+            }
             Event::Keyboard(KeyEvent { code: Key::Tab, .. }) => return Some(Msg::NavigationBlur),
+            Event::Keyboard(KeyEvent {
+                code: Key::Char('w'),
+                ..
+            }) => return Some(Msg::NavigationToggleGrouping),
+            Event::Keyboard(KeyEvent {
+                code: Key::Char('c'),
+                ..
+            }) => return Some(Msg::NavigationToggleChanged),
+            Event::Keyboard(KeyEvent {
+                code: Key::Char('o'),
+                ..
+            }) => return Some(Msg::SourcesDialogShow),
+            Event::Keyboard(KeyEvent {
+                code: Key::Down, ..
+            }) => {
+                self.perform(Cmd::Move(Direction::Down));
+                return Some(self.selected());
+            }
+            Event::Keyboard(KeyEvent { code: Key::Up, .. }) => {
+                self.perform(Cmd::Move(Direction::Up));
+                return Some(self.selected());
+            }
+            Event::Keyboard(KeyEvent {
+                code: Key::Enter, ..
+            }) => {
+                if let State::One(StateValue::Usize(index)) = self.state() {
+                    return Some(Msg::NavigationSubmit(index));
+                }
+                CmdResult::None
+            }
             _ => CmdResult::None,
         };
         Some(Msg::None)
@@ -48,4 +105,225 @@ impl Model {
             .ok();
         self.app.active(&Id::Libraries).ok();
     }
+
+    /// Remember the document's sizes and show its groups.
+    pub fn update_document(&mut self, source: &SourceChoice, document: &Document) {
+        let summary = DocumentSummary {
+            url: source.url().to_string(),
+            stats: document.stats(),
+        };
+        match self.documents.iter_mut().find(|d| d.url == summary.url) {
+            Some(document) => *document = summary,
+            None => self.documents.push(summary),
+        }
+        self.document = Some((source.url().to_string(), document.clone()));
+        self.refresh_diff();
+        self.view_navigation_groups();
+        self.view_endpoints();
+    }
+
+    /// Mark changes against this document from now on.
+    pub fn update_baseline(&mut self, source: &SourceChoice, document: Document) {
+        self.baseline = Some((source.url().to_string(), document));
+        self.refresh_diff();
+        self.view_navigation_groups();
+        self.view_endpoints();
+    }
+
+    /// Bring the diff up to date with the latest document and the baseline.
+    /// It is only computed again when one of them is another document.
+    fn refresh_diff(&mut self) {
+        match (&self.baseline, &self.document) {
+            (Some((baseline_url, baseline)), Some((url, document))) => {
+                self.diff.get((baseline_url, baseline), (url, document));
+            }
+            _ => self.diff.clear(),
+        }
+    }
+
+    /// Switch between all groups and those with changes since the baseline.
+    pub fn toggle_only_changed(&mut self) {
+        self.filter.only_changed = !self.filter.only_changed;
+        self.view_navigation_groups();
+        self.view_endpoints();
+    }
+
+    /// Switch between plugin and workflow groups.
+    pub fn toggle_grouping(&mut self) {
+        self.grouping = self.grouping.toggled();
+        self.view_navigation_groups();
+    }
+
+    /// List the latest document's groups with their endpoint counts, under an
+    /// [`ALL_GROUP`] row, and sum them up in the title. With a baseline, groups
+    /// say what changed. Groups [`Model::filter`] keeps none of are left out.
+    fn view_navigation_groups(&mut self) {
+        let (Some(DocumentSummary { url, stats }), Some((_, document))) =
+            (self.documents.last(), &self.document)
+        else {
+            return;
+        };
+        let diff = self.diff.current();
+        let marked = |counts: ChangeCounts| {
+            if counts.is_empty() {
+                String::new()
+            } else {
+                format!(" {counts}")
+            }
+        };
+
+        // Groups with unfinished endpoints say how many, after their count.
+        let experimental = |group: &str| match stats.experimental_in(self.grouping, group) {
+            0 => String::new(),
+            count => format!(" [{}]", t!(keys::NAVIGATION_EXPERIMENTAL, count)),
+        };
+        let mut rows = TableBuilder::default();
+        let mut keys = vec![ALL_GROUP.to_string()];
+        rows.add_col(TextSpan::from(format!(
+            "{ALL_GROUP} ({}){}{}",
+            stats.endpoints,
+            experimental(ALL_GROUP),
+            marked(
+                diff.map(|diff| diff.counts(self.grouping, ALL_GROUP))
+                    .unwrap_or_default()
+            )
+        )))
+        .add_row();
+        for (group, count) in stats.grouped(self.grouping) {
+            let Some(counts) = self.filter.group(document, diff, self.grouping, group) else {
+                continue;
+            };
+            keys.push(group.clone());
+            // Aliased groups name the tags folded into them, e.g. a legacy plugin.
+            let includes = match stats.members.get(group) {
+                Some(tags) if self.grouping == Grouping::Plugin => {
+                    format!(", includes {}", tags.join(", "))
+                }
+                _ => String::new(),
+            };
+            // Display names where the spec has them; selection still keys on the tag.
+            let label = stats.label(self.grouping, group);
+            rows.add_col(text_span(&format!(
+                "{label} ({count}{includes}){}{}",
+                experimental(group),
+                marked(counts)
+            )))
+            .add_row();
+        }
+        self.app
+            .attr(
+                &Id::Navigation,
+                Attribute::Content,
+                AttrValue::Table(rows.build()),
+            )
+            .ok();
+        // Stay on the same group through reloads, and per grouping.
+        let selected = self.navigation_selection.view(self.grouping).resolve(keys);
+        self.app
+            .attr(
+                &Id::Navigation,
+                Attribute::Value,
+                AttrValue::Payload(PropPayload::One(PropValue::Usize(selected))),
+            )
+            .ok();
+
+        let label = self
+            .source_choices
+            .iter()
+            .find(|choice| choice.url() == url)
+            .map_or(url.as_str(), SourceChoice::label);
+        let key = match self.grouping {
+            Grouping::Plugin => keys::NAVIGATION_GROUPS,
+            Grouping::Workflow => keys::NAVIGATION_WORKFLOWS,
+        };
+        let title = t!(
+            key,
+            label,
+            stats.version,
+            stats.grouped(self.grouping).len(),
+            stats.endpoints
+        );
+        self.app
+            .attr(
+                &Id::Navigation,
+                Attribute::Title,
+                AttrValue::Title((title, Alignment::Left)),
+            )
+            .ok();
+    }
+
+    /// Load the documents of an imported snapshot, or else the first
+    /// configured source. Snapshot documents that can't be fetched again are
+    /// reported as missing.
+    pub fn init_documents(&mut self) {
+        let sources = match self.restore.take() {
+            Some(restore) => {
+                if !restore.missing.is_empty() {
+                    self.mount_error_banner(&t!(
+                        keys::ERROR_SNAPSHOT_MISSING,
+                        restore.missing.join(", ")
+                    ));
+                }
+                restore.sources
+            }
+            None => self
+                .settings
+                .clone()
+                .unwrap_or_default()
+                .sources
+                .into_iter()
+                .take(1)
+                .collect(),
+        };
+        for source in sources {
+            self.send(BackgroundCmd::DocumentLoad(SourceChoice::Builtin(source)));
+        }
+        if let Some(url) = self.settings.clone().unwrap_or_default().baseline {
+            let choice = self
+                .source_choices
+                .iter()
+                .find(|choice| choice.url() == url)
+                .cloned()
+                .unwrap_or_else(|| {
+                    SourceChoice::Manifest(ManifestSource {
+                        name: url.clone(),
+                        url,
+                        format: None,
+                        description: String::new(),
+                    })
+                });
+            self.send(BackgroundCmd::BaselineLoad(choice));
+        }
+        let manifest = self.settings.clone().unwrap_or_default().sources_manifest;
+        self.send(BackgroundCmd::ManifestLoad(manifest));
+    }
+
+    /// Offer these sources from now on. Without the manifest's, say so.
+    pub fn update_source_choices(
+        &mut self,
+        choices: Vec<SourceChoice>,
+        error: Option<&ManifestError>,
+    ) {
+        self.source_choices = choices;
+        if let Some(error) = error {
+            self.errors.push(error.to_string());
+            self.mount_error_banner(t!(keys::ERROR_MANIFEST));
+        }
+    }
+
+    /// Open the group on a navigation row in the endpoints page. Row 0 is the
+    /// flat list of every endpoint. Rows are looked up by the keys last
+    /// listed, as the only-changed filter leaves groups out.
+    pub fn select_group(&mut self, index: usize) {
+        if self.document.is_none() {
+            return;
+        }
+        let selection = self.navigation_selection.view(self.grouping);
+        selection.select(index);
+        let Some(group) = selection.key().cloned() else {
+            return;
+        };
+        debug!(group = group.as_str(), "Selected group");
+        self.open_endpoints(&group);
+    }
 }
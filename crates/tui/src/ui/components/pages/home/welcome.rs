@@ -1,3 +1,4 @@
+use data::i18n::keys;
 use tui_realm_stdlib::{Container, Label};
 use tuirealm::{
     Component, MockComponent, NoUserEvent,
@@ -5,7 +6,7 @@ use tuirealm::{
     ratatui::layout::{Constraint, Direction},
 };
 
-use crate::msgs::Msg;
+use crate::{msgs::Msg, ui::i18n::t};
 
 #[derive(MockComponent)]
 pub struct Welcome {
@@ -24,7 +25,7 @@ impl Welcome {
                         .constraints([Constraint::Percentage(100)].as_ref()),
                 )
                 .children(vec![Box::new(
-                    Label::default().text("Welcome to Poroshell"),
+                    Label::default().text(t!(keys::HOME_WELCOME)),
                 )]),
         }
     }
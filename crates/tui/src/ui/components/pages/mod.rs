@@ -1,6 +1,12 @@
+pub mod dashboard;
+pub mod endpoints;
 pub mod home;
 
 #[derive(Debug, Eq, PartialEq, Clone, Hash)]
 pub enum Page {
     Home,
+    /// What the last generation run wrote, see [`data::dashboard`].
+    Dashboard,
+    /// A group's endpoints beside the list of groups, from the navigation pane.
+    Endpoints,
 }
@@ -0,0 +1,62 @@
+use tui_realm_stdlib::Label;
+use tuirealm::{
+    Application, AttrValue, Attribute, Component, Event, Frame, MockComponent, NoUserEvent,
+    props::{Alignment, Color},
+    ratatui::layout::Rect,
+};
+
+use crate::{
+    ids::Id,
+    msgs::Msg,
+    ui::{i18n, model::Model},
+};
+
+/// The bottom line of the screen: hints for the focused pane, a spinner while
+/// background work runs, and short confirmations.
+#[derive(MockComponent)]
+pub struct StatusBar {
+    component: Label,
+}
+
+impl StatusBar {
+    pub fn new() -> Self {
+        Self {
+            component: Label::default()
+                .alignment(Alignment::Left)
+                .foreground(Color::Gray),
+        }
+    }
+}
+
+impl Component<Msg, NoUserEvent> for StatusBar {
+    fn on(&mut self, ev: Event<NoUserEvent>) -> Option<Msg> {
+        match ev {
+            Event::Tick => Some(Msg::StatusTick),
+            _ => None,
+        }
+    }
+}
+
+impl Model {
+    /// Show the current status for the page and focused pane.
+    pub fn update_status_bar(&mut self) {
+        let page = format!("{:?}", self.page);
+        let focus = self
+            .app
+            .focus()
+            .map(|id| format!("{id:?}"))
+            .unwrap_or_default();
+        let line = self.status.line(&page, &focus, i18n::strings());
+        self.app
+            .attr(&Id::StatusBar, Attribute::Text, AttrValue::String(line))
+            .ok();
+    }
+
+    pub fn view_status_bar(
+        app: &mut Application<Id, Msg, NoUserEvent>,
+        f: &mut Frame<'_>,
+        area: Rect,
+    ) {
+        app.view(&Id::StatusBar, f, area);
+    }
+}
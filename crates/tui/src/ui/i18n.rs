@@ -0,0 +1,34 @@
+//! The UI strings of the configured locale, see [`data::i18n`].
+
+use std::sync::OnceLock;
+
+use data::i18n::{DEFAULT_LOCALE, Strings};
+use tracing::warn;
+
+static STRINGS: OnceLock<Strings> = OnceLock::new();
+
+/// Use `locale` for the rest of the session. Only the first call counts.
+pub fn init(locale: &str) {
+    STRINGS.set(load(locale)).ok();
+}
+
+pub fn strings() -> &'static Strings {
+    STRINGS.get_or_init(|| load(DEFAULT_LOCALE))
+}
+
+fn load(locale: &str) -> Strings {
+    Strings::new(locale).with_missing_hook(|locale, key| warn!(locale, key, "Missing translation"))
+}
+
+/// The text of a [`data::i18n::keys`] key, with any further arguments filling
+/// its `{0}`, `{1}`, ... placeholders.
+macro_rules! t {
+    ($key:expr) => {
+        $crate::ui::i18n::strings().get($key)
+    };
+    ($key:expr, $($arg:expr),+ $(,)?) => {
+        $crate::ui::i18n::strings().format($key, &[$(&$arg),+])
+    };
+}
+
+pub(crate) use t;
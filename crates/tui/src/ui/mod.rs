@@ -1,8 +1,11 @@
 pub mod background;
+pub mod clipboard;
+pub mod i18n;
 pub mod utils;
-use std::sync::Arc;
+use std::{io, path::PathBuf, sync::Arc, time::Duration};
 
-use model::Model;
+use data::{coalesce::Coalescer, snapshot::Snapshot};
+use model::{Model, Quit};
 use tokio::sync::{
     Mutex,
     mpsc::{UnboundedReceiver, UnboundedSender, unbounded_channel},
@@ -23,6 +26,7 @@ pub struct UI {
     bg_rx: Arc<Mutex<UnboundedReceiver<BackgroundCmd>>>,
     result_tx: Arc<Mutex<UnboundedSender<BackgroundCmdResult>>>,
     result_rx: UnboundedReceiver<BackgroundCmdResult>,
+    libraries_loads: Arc<Mutex<background::LibrariesLoads>>,
 }
 
 impl UI {
@@ -30,32 +34,81 @@ impl UI {
         let (bg_tx, bg_rx) = unbounded_channel::<BackgroundCmd>();
         let (result_tx, result_rx) = unbounded_channel::<BackgroundCmdResult>();
         let model = Model::new(bg_tx);
+        let ttl = Duration::from_secs(model.settings.clone().unwrap_or_default().result_ttl_secs);
         Self {
             model,
             bg_rx: Arc::new(Mutex::new(bg_rx)),
             result_tx: Arc::new(Mutex::new(result_tx)),
             result_rx,
+            libraries_loads: Arc::new(Mutex::new(Coalescer::new(ttl))),
         }
     }
 
     pub fn run(&mut self) {
         self.model.init_terminal();
+        debug!("Spinning background");
+        let background = self.run_background();
         self.run_inner();
+        // Don't let a pending request keep the process alive.
+        background.abort();
         self.model.finalize_terminal();
     }
 
-    fn run_inner(&mut self) {
-        debug!("Spinning background");
-        self.run_background();
+    pub fn error(&self) -> Option<String> {
+        self.model.error.clone()
+    }
 
+    /// Start from a debug snapshot instead of the configured documents.
+    pub fn import_state(&mut self, snapshot: &Snapshot) {
+        self.model.import_state(snapshot);
+    }
+
+    pub fn dump_state(&self) -> io::Result<PathBuf> {
+        self.model.dump_state()
+    }
+
+    fn run_inner(&mut self) {
         debug!("Spinning UI");
-        while !self.model.quit {
+        while self.model.quit != Quit::Now {
             // Tick background results
             while let Ok(result) = self.result_rx.try_recv() {
                 self.model.redraw = true;
+                self.model.counters.results_received += 1;
                 match result {
-                    BackgroundCmdResult::LibrariesReady(libraries) => {
-                        self.model.update_libraries(libraries);
+                    BackgroundCmdResult::LibrariesReady(libraries, source) => {
+                        // One result answers every load that joined the first.
+                        let label = BackgroundCmd::LibrariesLoad(Vec::new()).label();
+                        self.model.status.activity.finish_all(&label);
+                        self.model.umount_error_banner();
+                        self.model.update_libraries(libraries, &source);
+                        // Library loads can't fail, they fall back to the snapshot.
+                        self.model.loading_libraries = false;
+                        if self.model.quit == Quit::WhenIdle {
+                            self.model.quit = Quit::Now;
+                        }
+                    }
+                    BackgroundCmdResult::DocumentReady(source, document, integrity) => {
+                        self.model.update_document(&source, &document);
+                        self.model.status.integrity = Some(integrity);
+                    }
+                    BackgroundCmdResult::BaselineReady(source, document) => {
+                        self.model.update_baseline(&source, document);
+                    }
+                    BackgroundCmdResult::ManifestReady(choices, error) => {
+                        self.model.update_source_choices(choices, error.as_ref());
+                    }
+                    BackgroundCmdResult::DashboardReady(dashboard) => {
+                        self.model.update_dashboard(dashboard);
+                    }
+                    BackgroundCmdResult::SettingsMerged(settings) => {
+                        self.model.settings = Some(settings);
+                    }
+                    BackgroundCmdResult::Failed(err) => {
+                        self.model.record_error(&err);
+                        self.model.mount_error_banner(&err.user_message());
+                    }
+                    BackgroundCmdResult::Finished(label) => {
+                        self.model.status.activity.finish(&label);
                     }
                 }
             }
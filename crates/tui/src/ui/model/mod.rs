@@ -1,45 +1,168 @@
+pub mod snapshot;
 pub mod update;
 pub mod view;
-use std::time::Duration;
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
 
 use color_eyre::eyre::Result;
-use data::RiotAPILibrary;
+use data::{
+    ALL_GROUP, Document, Grouping, RiotAPILibrary,
+    clipboard::Clipboard,
+    dashboard::Dashboard,
+    diff::{DiffCache, DiffRow, RowFilter},
+    layout::{Layouts, MinSize},
+    manifest::{self, SourceChoice},
+    selection::Selections,
+    settings::{SETTINGS_FILE, Settings, SettingsFile},
+    setup::SetupWizard,
+    snapshot::{Counters, DocumentSummary, Restore},
+    status::Status,
+    window::WindowedRows,
+};
 use tokio::sync::mpsc::UnboundedSender;
+use tracing::{error, warn};
 use tuirealm::{
     Application, EventListenerCfg, NoUserEvent, Sub, SubClause, SubEventClause,
-    event::{Key, KeyEvent, KeyModifiers},
+    ratatui::layout::{Position, Rect},
     terminal::{CrosstermTerminalAdapter, TerminalBridge},
 };
 
 use crate::{cmds::BackgroundCmd, ids::Id, msgs::Msg};
 
-use super::components::{global_listener::GlobalListener, pages::Page};
+use super::{
+    clipboard,
+    components::{global_listener::GlobalListener, pages::Page, status_bar::StatusBar},
+    i18n,
+};
+
+/// Where each pane was last drawn.
+pub type Areas = HashMap<Id, Rect>;
+
+/// When the session ends.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Quit {
+    No,
+    /// Once the running background work finishes.
+    WhenIdle,
+    Now,
+}
 
 pub struct Model {
     pub app: Application<Id, Msg, NoUserEvent>,
     pub terminal: TerminalBridge<CrosstermTerminalAdapter>,
-    pub quit: bool,
+    pub quit: Quit,
     pub redraw: bool,
+    /// Fatal error that ended the session, shown after the terminal is restored.
+    pub error: Option<String>,
     pub page: Page,
+    pub areas: Areas,
+    /// Pane sizes of each page, saved in the settings when changed.
+    pub layouts: Layouts,
+    /// Below this size a placeholder is drawn instead of the pages.
+    pub min_size: MinSize,
     pub bg_tx: UnboundedSender<BackgroundCmd>,
     pub libraries: Option<Vec<RiotAPILibrary>>,
+    /// Sources the user can pick: the built-ins, then the manifest's once loaded.
+    pub source_choices: Vec<SourceChoice>,
+    /// Sizes of the loaded documents, computed once when each arrives.
+    pub documents: Vec<DocumentSummary>,
+    /// The latest document, by URL, to compare against the baseline.
+    pub document: Option<(String, Document)>,
+    /// The document changes are marked against, by URL, once loaded.
+    pub baseline: Option<(String, Document)>,
+    /// Changes of [`Model::document`] since [`Model::baseline`].
+    pub diff: DiffCache,
+    /// Narrows the groups and endpoints listed, e.g. to those with changes
+    /// since the baseline.
+    pub filter: RowFilter,
+    /// The group the endpoints page shows.
+    pub endpoints_group: String,
+    /// Its endpoints, of which only the window on screen is in the table.
+    pub endpoint_rows: WindowedRows<DiffRow>,
+    /// How the navigation pane groups the documents' endpoints.
+    pub grouping: Grouping,
+    /// Selected navigation row by group name, for each grouping.
+    pub navigation_selection: Selections<Grouping, String>,
+    pub counters: Counters,
+    /// Latest background errors, for state dumps.
+    pub errors: Vec<String>,
+    /// Documents of an imported snapshot, loaded in place of the configured ones.
+    pub restore: Option<Restore>,
+    /// Whether a libraries load is waiting on the background task.
+    pub loading_libraries: bool,
+    pub settings: Option<Settings>,
+    /// Where the settings are saved, shared with the background task.
+    pub settings_file: Arc<Mutex<SettingsFile>>,
+    /// First-run wizard, present until it is finished or skipped.
+    pub setup: Option<SetupWizard>,
+    /// Key hints, running work and messages for the status bar.
+    pub status: Status,
+    pub clipboard: Clipboard,
+    /// The last run's artifacts, once the dashboard has read them.
+    pub dashboard: Option<Dashboard>,
 }
 
 impl Model {
     pub fn new(bg_tx: UnboundedSender<BackgroundCmd>) -> Self {
         let terminal = TerminalBridge::init_crossterm().expect("Cannot create terminal bridge");
 
+        let mut settings_file = SettingsFile::new(SETTINGS_FILE);
+        let settings = settings_file.load().unwrap_or_else(|err| {
+            error!(error = err.to_string(), "Failed to load settings");
+            None
+        });
+        if settings_file.recovered() {
+            warn!(
+                backup = settings_file.backup_path().display().to_string(),
+                "Settings were unreadable, loaded the backup"
+            );
+        }
+        // Before mounting, which looks up the components' strings.
+        i18n::init(&settings.clone().unwrap_or_default().locale);
+
         let app = Self::init_app();
+        let setup = settings.is_none().then(SetupWizard::new);
+        let layouts = settings.clone().unwrap_or_default().layouts;
+        let min_size = settings.clone().unwrap_or_default().min_terminal_size;
 
-        Self {
+        let mut model = Self {
             app,
             terminal,
-            quit: false,
+            quit: Quit::No,
             redraw: true,
+            error: None,
             page: Page::Home,
+            areas: Areas::new(),
+            layouts,
+            min_size,
             bg_tx,
             libraries: None,
-        }
+            source_choices: manifest::merge(None),
+            documents: Vec::new(),
+            document: None,
+            baseline: None,
+            diff: DiffCache::default(),
+            filter: RowFilter::default(),
+            endpoints_group: ALL_GROUP.to_string(),
+            endpoint_rows: WindowedRows::new(Vec::new(), 1),
+            grouping: Grouping::default(),
+            navigation_selection: Selections::default(),
+            counters: Counters::default(),
+            errors: Vec::new(),
+            restore: None,
+            loading_libraries: false,
+            settings,
+            settings_file: Arc::new(Mutex::new(settings_file)),
+            setup,
+            status: Status::default(),
+            clipboard: clipboard::clipboard(),
+            dashboard: None,
+        };
+        model.mount_setup_dialog(None);
+        model
     }
 
     pub fn init_app() -> Application<Id, Msg, NoUserEvent> {
@@ -61,22 +184,16 @@ impl Model {
         app.mount(
             Id::GlobalListener,
             Box::new(GlobalListener::new()),
-            vec![
-                Sub::new(
-                    SubEventClause::Keyboard(KeyEvent {
-                        code: Key::Esc,
-                        modifiers: KeyModifiers::NONE,
-                    }),
-                    SubClause::Always,
-                ),
-                Sub::new(
-                    SubEventClause::Keyboard(KeyEvent {
-                        code: Key::Char('c'),
-                        modifiers: KeyModifiers::CONTROL,
-                    }),
-                    SubClause::Always,
-                ),
-            ],
+            // Every event, since tuirealm doesn't export the clause for mouse
+            // events. The listener keeps the global keys, clicks on panes
+            // that aren't focused, and resizes, and ignores the rest.
+            vec![Sub::new(SubEventClause::Any, SubClause::Always)],
+        )?;
+
+        app.mount(
+            Id::StatusBar,
+            Box::new(StatusBar::new()),
+            vec![Sub::new(SubEventClause::Tick, SubClause::Always)],
         )?;
 
         Self::mount_home(app)?;
@@ -84,14 +201,66 @@ impl Model {
         Ok(())
     }
 
+    /// Hand a command to the background task.
+    pub fn send(&mut self, cmd: BackgroundCmd) {
+        let label = cmd.label();
+        if self.bg_tx.send(cmd).is_ok() {
+            self.counters.commands_sent += 1;
+            self.status.activity.start(&label);
+        }
+    }
+
+    /// Copy `text` and say how in the status bar.
+    pub fn copy(&mut self, text: &str) {
+        let outcome = self.clipboard.copy(text);
+        self.status
+            .messages
+            .push(&outcome.to_string(), Instant::now());
+    }
+
     pub fn init_terminal(&mut self) {
         let _ = self.terminal.enable_raw_mode();
         let _ = self.terminal.enter_alternate_screen();
         let _ = self.terminal.clear_screen();
+        if self.settings.as_ref().is_none_or(|settings| settings.mouse) {
+            let _ = self.terminal.enable_mouse_capture();
+        }
     }
 
     pub fn finalize_terminal(&mut self) {
+        // Harmless when capture was never enabled.
+        let _ = self.terminal.disable_mouse_capture();
         let _ = self.terminal.disable_raw_mode();
         let _ = self.terminal.leave_alternate_screen();
     }
+
+    /// Focus the pane under a click. Clicks go to the dialog while one is open.
+    pub fn focus_at(&mut self, column: u16, row: u16) {
+        if self.app.mounted(&Id::QuitDialog)
+            || self.app.mounted(&Id::SetupDialog)
+            || self.app.mounted(&Id::SourcesDialog)
+        {
+            return;
+        }
+        let clicked = self
+            .areas
+            .iter()
+            .find(|(_, area)| area.contains(Position::new(column, row)))
+            .map(|(id, _)| id.clone());
+        match clicked {
+            Some(Id::Libraries) if self.app.focus() != Some(&Id::Libraries) => {
+                self.blur_navigation();
+            }
+            Some(Id::Navigation) if self.app.focus() != Some(&Id::Navigation) => {
+                self.blur_libraries();
+            }
+            Some(Id::EndpointsGroups) if self.app.focus() != Some(&Id::EndpointsGroups) => {
+                self.focus_endpoints_groups();
+            }
+            Some(Id::EndpointsTable) if self.app.focus() != Some(&Id::EndpointsTable) => {
+                self.focus_endpoints_table();
+            }
+            _ => (),
+        }
+    }
 }
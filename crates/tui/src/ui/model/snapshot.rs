@@ -0,0 +1,79 @@
+use std::{env, io, path::PathBuf, time::Instant};
+
+use data::{
+    i18n::keys,
+    snapshot::{MAX_ERRORS, Snapshot},
+};
+use time::OffsetDateTime;
+use tracing::{debug, error, warn};
+
+use crate::{cmds::BackgroundError, ids::Id, ui::i18n::t};
+
+use super::Model;
+
+/// Where state dumps are written, relative to the working directory like `logs/`.
+pub const SNAPSHOT_DIR: &str = "snapshots";
+
+impl Model {
+    /// The current state, unsanitized.
+    pub fn snapshot(&self) -> Snapshot {
+        let mut snapshot = Snapshot::new(&format!("{:?}", self.page));
+        snapshot.documents.clone_from(&self.documents);
+        snapshot.settings.clone_from(&self.settings);
+        snapshot.counters = self.counters;
+        snapshot.errors.clone_from(&self.errors);
+        snapshot
+    }
+
+    /// Write a sanitized snapshot under [`SNAPSHOT_DIR`] and return its path.
+    pub fn dump_state(&self) -> io::Result<PathBuf> {
+        let config_dir = env::current_dir()?;
+        let timestamp = OffsetDateTime::now_utc().unix_timestamp();
+        let path = PathBuf::from(SNAPSHOT_DIR).join(format!("state-{timestamp}.json"));
+        self.snapshot().sanitized(&config_dir).save(&path)?;
+        Ok(path)
+    }
+
+    /// [`Model::dump_state`] from the keybinding, which can only report back through the UI.
+    pub fn dump_state_now(&mut self) {
+        match self.dump_state() {
+            Ok(path) => {
+                debug!(path = path.display().to_string(), "Dumped state");
+                let message = t!(keys::STATUS_STATE_WRITTEN, path.display());
+                self.status.messages.push(&message, Instant::now());
+            }
+            Err(err) => {
+                error!(error = err.to_string(), "Failed to dump state");
+                self.mount_error_banner(t!(keys::ERROR_STATE_DUMP));
+            }
+        }
+    }
+
+    /// Take the settings and documents of a snapshot. Its documents load on
+    /// the first tick in place of the configured ones.
+    pub fn import_state(&mut self, snapshot: &Snapshot) {
+        if snapshot.page != format!("{:?}", self.page) {
+            warn!(page = snapshot.page, "Snapshot page not found");
+        }
+        if let Some(settings) = &snapshot.settings {
+            // Imported settings are only for this session, so they aren't saved.
+            self.settings = Some(settings.clone());
+            self.setup = None;
+            self.app.umount(&Id::SetupDialog).ok();
+            self.app.active(&Id::Libraries).ok();
+        }
+        for err in &snapshot.errors {
+            debug!(error = err, "Snapshot error");
+        }
+        self.restore = Some(snapshot.restore());
+    }
+
+    /// Remember a background failure for the next state dump.
+    pub fn record_error(&mut self, err: &BackgroundError) {
+        self.counters.failures += 1;
+        self.errors.push(err.to_string());
+        if self.errors.len() > MAX_ERRORS {
+            self.errors.remove(0);
+        }
+    }
+}
@@ -1,39 +1,89 @@
+use std::time::Instant;
+
 use tuirealm::Update;
 
-use crate::{cmds::BackgroundCmd, msgs::Msg};
+use crate::{cmds::BackgroundCmd, ids::Id, msgs::Msg, ui::components::pages::Page};
 
-use super::Model;
+use super::{Model, Quit};
 
 impl Update<Msg> for Model {
     fn update(&mut self, msg: Option<Msg>) -> Option<Msg> {
         self.redraw = true;
         match msg.unwrap_or(Msg::None) {
-            Msg::QuitDialogShow => {
-                self.mount_quit_dialog();
+            Msg::Click(column, row) => self.focus_at(column, row),
+            Msg::StateDump => self.dump_state_now(),
+            Msg::LayoutResize(delta) => self.resize_layout(delta),
+            // The size guard checks the height on every draw; the endpoints
+            // table needs rows for it.
+            Msg::WindowResize(width, height) => {
+                self.resize_terminal(width);
+                self.resize_endpoints(height);
+            }
+            Msg::StatusTick => {
+                self.status.activity.tick();
+                self.status.messages.expire(Instant::now());
             }
-            Msg::QuitDialogOk | Msg::AppClose => {
-                self.quit = true;
+            // Esc closes the sources dialog before it asks to quit.
+            Msg::QuitDialogShow if self.app.mounted(&Id::SourcesDialog) => {
+                self.umount_sources_dialog();
             }
-            Msg::QuitDialogCancel => {
+            // And leaves the endpoints for home.
+            Msg::QuitDialogShow if self.page == Page::Endpoints => self.close_endpoints(),
+            Msg::QuitDialogShow => self.mount_quit_dialog(),
+            Msg::QuitDialogOk | Msg::AppClose => self.quit = Quit::Now,
+            Msg::QuitDialogDefer => {
+                self.quit = Quit::WhenIdle;
                 self.umount_quit_dialog();
             }
+            Msg::QuitDialogCancel => self.umount_quit_dialog(),
+            Msg::SourcesDialogShow => self.mount_sources_dialog(),
+            Msg::SourcesDialogSubmit(index) => self.submit_sources_dialog(index),
+            Msg::SourcesDialogBaseline(index) => self.choose_baseline(index),
+            Msg::DashboardToggle => self.toggle_dashboard(),
+            Msg::DashboardReload => self.reload_dashboard(),
+            Msg::DashboardOpenChangelog => self.open_changelog(),
+            Msg::DashboardLoadSpec => self.load_generated_spec(),
+            Msg::SetupNext => self.setup_next(),
+            Msg::SetupBack => self.setup_back(),
+            Msg::SetupSkip => self.setup_skip(),
             Msg::LibrariesInit => {
-                self.bg_tx.send(BackgroundCmd::LibrariesLoad).ok();
+                let mirrors = self.settings.clone().unwrap_or_default().library_mirrors;
+                self.send(BackgroundCmd::LibrariesLoad(mirrors));
+                self.loading_libraries = true;
             }
+            Msg::DocumentsInit => self.init_documents(),
             Msg::LibrariesSubmit(index) => {
-                if let Some(libraries) = self.libraries.take() {
-                    if let Some(library) = libraries.get(index) {
-                        let link = format!("https://github.com/{}/{}", library.owner, library.repo);
-                        self.bg_tx.send(BackgroundCmd::LibrariesOpenLink(link)).ok();
-                    }
+                if let Some(libraries) = self.libraries.take()
+                    && let Some(library) = libraries.get(index)
+                {
+                    let link = format!("https://github.com/{}/{}", library.owner, library.repo);
+                    self.send(BackgroundCmd::LibrariesOpenLink(link));
                 }
             }
-            Msg::LibrariesBlur => {
-                self.blur_libraries();
+            Msg::LibrariesCopy(index) => {
+                let link = self
+                    .libraries
+                    .as_ref()
+                    .and_then(|libraries| libraries.get(index))
+                    .map(|library| {
+                        format!("https://github.com/{}/{}", library.owner, library.repo)
+                    });
+                if let Some(link) = link {
+                    self.copy(&link);
+                }
             }
-            Msg::NavigationBlur => {
-                self.blur_navigation();
+            Msg::LibrariesBlur => self.blur_libraries(),
+            Msg::NavigationBlur => self.blur_navigation(),
+            Msg::NavigationSubmit(index) => self.select_group(index),
+            Msg::NavigationSelect(index) => {
+                self.navigation_selection.view(self.grouping).select(index);
             }
+            Msg::NavigationToggleGrouping => self.toggle_grouping(),
+            Msg::NavigationToggleChanged => self.toggle_only_changed(),
+            Msg::EndpointsSelect(index) => self.select_endpoints_group(index),
+            Msg::EndpointsFocusTable => self.focus_endpoints_table(),
+            Msg::EndpointsMove(delta) => self.move_endpoints(delta),
+            Msg::EndpointsFocusGroups => self.focus_endpoints_groups(),
             Msg::None => (),
         }
 
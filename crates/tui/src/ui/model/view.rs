@@ -1,21 +1,103 @@
+use data::{
+    i18n::keys,
+    layout::{MinSize, SizeGuard},
+};
 use tracing::error;
+use tuirealm::{
+    Frame,
+    ratatui::{
+        layout::{Alignment, Constraint, Layout, Rect},
+        widgets::{Paragraph, Wrap},
+    },
+};
 
-use crate::ui::components::pages::Page;
+use crate::ui::{components::pages::Page, i18n::t};
 
-use super::Model;
+use super::{Model, Quit};
 
 impl Model {
     pub fn view(&mut self) {
         if self.redraw {
+            self.update_status_bar();
             if let Err(err) = self.terminal.raw_mut().draw(|f| {
+                let area = f.area();
+                if let SizeGuard::TooSmall { need, have } =
+                    self.min_size.check(area.width, area.height)
+                {
+                    // Nothing is clickable until the pages are back.
+                    self.areas.clear();
+                    Self::view_too_small(f, area, need, have);
+                    return;
+                }
+                let [page, status] =
+                    Layout::vertical([Constraint::Fill(1), Constraint::Length(1)]).areas(area);
                 match self.page {
-                    Page::Home => Self::view_page_home(&mut self.app, f),
+                    Page::Home => {
+                        Self::view_page_home(
+                            &mut self.app,
+                            f,
+                            page,
+                            &mut self.areas,
+                            self.layouts.home,
+                        );
+                    }
+                    Page::Endpoints => {
+                        Self::view_page_endpoints(
+                            &mut self.app,
+                            f,
+                            page,
+                            &mut self.areas,
+                            self.layouts.home,
+                        );
+                    }
+                    Page::Dashboard => {
+                        // Only the dashboard is drawn, so nothing else takes clicks.
+                        self.areas.clear();
+                        Self::view_page_dashboard(&mut self.app, f, page);
+                    }
                 }
+                Self::view_status_bar(&mut self.app, f, status);
+                Self::view_error_banner(&mut self.app, f);
+                Self::view_sources_dialog(&mut self.app, f);
+                Self::view_setup_dialog(&mut self.app, f);
                 Self::view_quit_dialog(&mut self.app, f);
             }) {
                 error!(error = err.get_ref(), "Failed to draw");
-                panic!();
+                self.error = Some(format!("Failed to draw: {err}"));
+                self.quit = Quit::Now;
             }
         }
     }
+
+    /// The terminal was resized to `width` columns: fit the pane shares to it
+    /// and repaint everything on the next draw.
+    pub fn resize_terminal(&mut self, width: u16) {
+        self.layouts.home = self.layouts.home.clamped(width);
+        if let Err(err) = self.terminal.raw_mut().clear() {
+            error!(error = err.to_string(), "Failed to clear after resize");
+        }
+    }
+
+    /// Says how big the terminal needs to be, centered in place of the pages.
+    fn view_too_small(f: &mut Frame<'_>, area: Rect, need: MinSize, have: MinSize) {
+        let text = t!(
+            keys::TERMINAL_TOO_SMALL,
+            need.width,
+            need.height,
+            have.width,
+            have.height
+        );
+        let [_, line, _] = Layout::vertical([
+            Constraint::Fill(1),
+            Constraint::Length(2),
+            Constraint::Fill(1),
+        ])
+        .areas(area);
+        f.render_widget(
+            Paragraph::new(text)
+                .alignment(Alignment::Center)
+                .wrap(Wrap { trim: true }),
+            line,
+        );
+    }
 }
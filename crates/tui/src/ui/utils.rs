@@ -1,4 +1,11 @@
-use tuirealm::ratatui::layout::{Constraint, Direction, Layout, Rect};
+use poro_schema::sanitize::sanitize;
+use tuirealm::{
+    props::TextSpan,
+    ratatui::layout::{Constraint, Direction, Layout, Rect},
+};
+
+/// Rows taken above a bordered table's first row: the border and the header.
+const TABLE_TOP: u16 = 2;
 
 pub fn draw_area_in_absolute(parent: Rect, width: u16, height: u16) -> Rect {
     let area = Layout::default()
@@ -24,3 +31,65 @@ pub fn draw_area_in_absolute(parent: Rect, width: u16, height: u16) -> Rect {
         )
         .split(area[1])[1]
 }
+
+/// Index of the table row drawn at screen row `row`, for a bordered table with
+/// a one-line header.
+///
+/// The stdlib table scrolls only as far as it needs to keep the selected row
+/// in view, so the first visible row follows from the selection.
+pub fn table_row_at(area: Rect, selected: usize, len: usize, row: u16) -> Option<usize> {
+    let top = area.y + TABLE_TOP;
+    let bottom = area.bottom().saturating_sub(1);
+    if row < top || row >= bottom {
+        return None;
+    }
+    let visible = usize::from(bottom - top);
+    let offset = (selected + 1).saturating_sub(visible);
+    let index = offset + usize::from(row - top);
+    (index < len).then_some(index)
+}
+
+/// A span of text from a spec or manifest, without the control characters
+/// that would garble the terminal.
+pub fn text_span(text: &str) -> TextSpan {
+    TextSpan::from(sanitize(text).into_owned())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Rows 5 and 14 are the borders, row 6 the header, rows 7 to 13 the body.
+    const AREA: Rect = Rect {
+        x: 0,
+        y: 5,
+        width: 40,
+        height: 10,
+    };
+
+    #[test]
+    fn test_table_row_at_header_and_borders() {
+        assert_eq!(table_row_at(AREA, 0, 20, 4), None);
+        assert_eq!(table_row_at(AREA, 0, 20, 5), None);
+        assert_eq!(table_row_at(AREA, 0, 20, 6), None);
+        assert_eq!(table_row_at(AREA, 0, 20, 14), None);
+        assert_eq!(table_row_at(AREA, 0, 20, 7), Some(0));
+        assert_eq!(table_row_at(AREA, 0, 20, 13), Some(6));
+    }
+
+    #[test]
+    fn test_table_row_at_scrolled() {
+        // Row 10 selected, so the table scrolled to keep it on the last line.
+        assert_eq!(table_row_at(AREA, 10, 20, 13), Some(10));
+        assert_eq!(table_row_at(AREA, 10, 20, 7), Some(4));
+        // Row 6 still fits without scrolling.
+        assert_eq!(table_row_at(AREA, 6, 20, 7), Some(0));
+    }
+
+    #[test]
+    fn test_table_row_at_past_the_end() {
+        assert_eq!(table_row_at(AREA, 0, 3, 9), Some(2));
+        assert_eq!(table_row_at(AREA, 0, 3, 10), None);
+        assert_eq!(table_row_at(AREA, 0, 0, 7), None);
+    }
+}